@@ -1,10 +1,10 @@
 use clap::{App, Arg, ArgMatches};
 use environment::Environment;
 use slashing_protection::{
-    interchange::Interchange, InterchangeError, InterchangeImportOutcome, SlashingDatabase,
-    SLASHING_PROTECTION_FILENAME,
+    interchange::Interchange, InterchangeError, InterchangeImportOutcome, NotSafe,
+    SlashingDatabase, SLASHING_PROTECTION_FILENAME, SLASHING_PROTECTION_HISTORY_EPOCHS,
 };
-use std::fs::File;
+use std::fs::{self, File};
 use std::path::PathBuf;
 use std::str::FromStr;
 use types::{Epoch, EthSpec, PublicKeyBytes, Slot};
@@ -12,16 +12,18 @@ use types::{Epoch, EthSpec, PublicKeyBytes, Slot};
 pub const CMD: &str = "slashing-protection";
 pub const IMPORT_CMD: &str = "import";
 pub const EXPORT_CMD: &str = "export";
+pub const PRUNE_CMD: &str = "prune";
 
 pub const IMPORT_FILE_ARG: &str = "IMPORT-FILE";
 pub const EXPORT_FILE_ARG: &str = "EXPORT-FILE";
 
 pub const MINIFY_FLAG: &str = "minify";
 pub const PUBKEYS_FLAG: &str = "pubkeys";
+pub const HISTORY_EPOCHS_FLAG: &str = "history-epochs";
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
-        .about("Import or export slashing protection data to or from another client")
+        .about("Import, export or prune slashing protection data")
         .subcommand(
             App::new(IMPORT_CMD)
                 .about("Import an interchange file")
@@ -31,6 +33,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         .value_name("FILE")
                         .help("The slashing protection interchange file to import (.json)"),
                 )
+                .arg(
+                    Arg::with_name(PUBKEYS_FLAG)
+                        .long(PUBKEYS_FLAG)
+                        .takes_value(true)
+                        .value_name("PUBKEYS")
+                        .help(
+                            "List of public keys to import history for. Keys should be 0x-prefixed, \
+                             comma-separated, or the path to a file containing such a list. All \
+                             keys in the file will be imported if omitted",
+                        ),
+                )
                 .arg(
                     Arg::with_name(MINIFY_FLAG)
                         .long(MINIFY_FLAG)
@@ -58,7 +71,8 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         .value_name("PUBKEYS")
                         .help(
                             "List of public keys to export history for. Keys should be 0x-prefixed, \
-                             comma-separated. All known keys will be exported if omitted",
+                             comma-separated, or the path to a file containing such a list. All \
+                             known keys will be exported if omitted",
                         ),
                 )
                 .arg(
@@ -73,6 +87,29 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         ),
                 ),
         )
+        .subcommand(
+            App::new(PRUNE_CMD)
+                .about(
+                    "Remove old entries from the slashing protection database. The validator \
+                     client must not be running.",
+                )
+                .arg(
+                    Arg::with_name(HISTORY_EPOCHS_FLAG)
+                        .long(HISTORY_EPOCHS_FLAG)
+                        .takes_value(true)
+                        .value_name("EPOCHS")
+                        .default_value(Box::leak(
+                            SLASHING_PROTECTION_HISTORY_EPOCHS
+                                .to_string()
+                                .into_boxed_str(),
+                        ))
+                        .help(
+                            "The number of epochs of history to retain for each validator, counted \
+                             back from its latest signed block or attestation. The latest block \
+                             and attestation of each validator are always kept.",
+                        ),
+                ),
+        )
 }
 
 pub fn cli_run<T: EthSpec>(
@@ -93,6 +130,9 @@ pub fn cli_run<T: EthSpec>(
         (IMPORT_CMD, Some(matches)) => {
             let import_filename: PathBuf = clap_utils::parse_required(matches, IMPORT_FILE_ARG)?;
             let minify: Option<bool> = clap_utils::parse_optional(matches, MINIFY_FLAG)?;
+            let selected_pubkeys = clap_utils::parse_optional::<String>(matches, PUBKEYS_FLAG)?
+                .map(|pubkeys| parse_pubkeys(&pubkeys))
+                .transpose()?;
             let import_file = File::open(&import_filename).map_err(|e| {
                 format!(
                     "Unable to open import file at {}: {:?}",
@@ -106,6 +146,15 @@ pub fn cli_run<T: EthSpec>(
                 .map_err(|e| format!("Error parsing file for import: {:?}", e))?;
             eprintln!(" [done].");
 
+            if let Some(pubkeys) = selected_pubkeys {
+                interchange = interchange.select_pubkeys(&pubkeys).map_err(|e| match e {
+                    InterchangeError::MissingPubkey(pubkey) => {
+                        format!("Validator {:?} is not present in the import file", pubkey)
+                    }
+                    e => format!("Unable to select public keys: {:?}", e),
+                })?;
+            }
+
             if let Some(minify) = minify {
                 eprintln!(
                     "WARNING: --minify flag is deprecated and will be removed in a future release"
@@ -208,18 +257,9 @@ pub fn cli_run<T: EthSpec>(
             let export_filename: PathBuf = clap_utils::parse_required(matches, EXPORT_FILE_ARG)?;
             let minify: bool = clap_utils::parse_required(matches, MINIFY_FLAG)?;
 
-            let selected_pubkeys = if let Some(pubkeys) =
-                clap_utils::parse_optional::<String>(matches, PUBKEYS_FLAG)?
-            {
-                let pubkeys = pubkeys
-                    .split(',')
-                    .map(PublicKeyBytes::from_str)
-                    .collect::<Result<Vec<_>, _>>()
-                    .map_err(|e| format!("Invalid --{} value: {:?}", PUBKEYS_FLAG, e))?;
-                Some(pubkeys)
-            } else {
-                None
-            };
+            let selected_pubkeys = clap_utils::parse_optional::<String>(matches, PUBKEYS_FLAG)?
+                .map(|pubkeys| parse_pubkeys(&pubkeys))
+                .transpose()?;
 
             if !slashing_protection_db_path.exists() {
                 return Err(format!(
@@ -239,7 +279,13 @@ pub fn cli_run<T: EthSpec>(
 
             let mut interchange = slashing_protection_database
                 .export_interchange_info(genesis_validators_root, selected_pubkeys.as_deref())
-                .map_err(|e| format!("Error during export: {:?}", e))?;
+                .map_err(|e| match e {
+                    InterchangeError::NotSafe(NotSafe::UnregisteredValidator(pubkey)) => format!(
+                        "Validator {:?} is not present in the slashing protection database",
+                        pubkey
+                    ),
+                    e => format!("Error during export: {:?}", e),
+                })?;
 
             if minify {
                 eprintln!("Minifying output file");
@@ -259,7 +305,51 @@ pub fn cli_run<T: EthSpec>(
 
             Ok(())
         }
+        (PRUNE_CMD, Some(matches)) => {
+            let history_epochs: u64 = clap_utils::parse_required(matches, HISTORY_EPOCHS_FLAG)?;
+
+            if !slashing_protection_db_path.exists() {
+                return Err(format!(
+                    "No slashing protection database exists at: {}",
+                    slashing_protection_db_path.display()
+                ));
+            }
+
+            let slashing_protection_database = SlashingDatabase::open(&slashing_protection_db_path)
+                .map_err(|e| {
+                    format!(
+                        "Unable to open database at {}: {:?}",
+                        slashing_protection_db_path.display(),
+                        e
+                    )
+                })?;
+
+            slashing_protection_database
+                .prune_all_relative_to_latest(history_epochs, T::slots_per_epoch())
+                .map_err(|e| format!("Error during pruning: {:?}", e))?;
+
+            eprintln!("Pruning completed successfully");
+
+            Ok(())
+        }
         ("", _) => Err("No subcommand provided, see --help for options".to_string()),
         (command, _) => Err(format!("No such subcommand `{}`", command)),
     }
 }
+
+/// Parse a list of public keys, given either as a comma-separated list or as the path to a file
+/// containing comma or newline separated public keys.
+fn parse_pubkeys(value: &str) -> Result<Vec<PublicKeyBytes>, String> {
+    let path = PathBuf::from(value);
+    let list = if path.is_file() {
+        fs::read_to_string(&path)
+            .map_err(|e| format!("Unable to read --{} file {}: {:?}", PUBKEYS_FLAG, value, e))?
+    } else {
+        value.to_string()
+    };
+    list.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|pubkey| !pubkey.is_empty())
+        .map(PublicKeyBytes::from_str)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Invalid --{} value: {:?}", PUBKEYS_FLAG, e))
+}
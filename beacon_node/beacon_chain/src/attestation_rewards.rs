@@ -40,6 +40,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Get state
         let state_slot = (epoch + 1).end_slot(T::EthSpec::slots_per_epoch());
 
+        // Rewards for `epoch` are only known once the following epoch has been processed.
+        let current_slot = self.slot()?;
+        if current_slot < state_slot {
+            return Err(BeaconChainError::AttestationRewardsEpochIncomplete {
+                epoch,
+                current_slot,
+            });
+        }
+
         let state_root = self
             .state_root_at_slot(state_slot)?
             .ok_or(BeaconChainError::NoStateForSlot(state_slot))?;
@@ -112,11 +112,17 @@ where
 
         let _signature_verification_timer =
             metrics::start_timer(&metrics::ATTESTATION_PROCESSING_BATCH_AGG_SIGNATURE_TIMES);
+        metrics::observe(
+            &metrics::ATTESTATION_PROCESSING_BATCH_AGG_SIZE,
+            num_indexed as f64,
+        );
 
         if verify_signature_sets(signature_sets.iter()) {
             // Since all the signatures verified in a batch, there's no reason for them to be
             // checked again later.
             check_signatures = CheckAttestationSignature::No
+        } else {
+            metrics::inc_counter(&metrics::ATTESTATION_PROCESSING_BATCH_AGG_FALLBACKS);
         }
     }
 
@@ -201,11 +207,17 @@ where
 
         let _signature_verification_timer =
             metrics::start_timer(&metrics::ATTESTATION_PROCESSING_BATCH_UNAGG_SIGNATURE_TIMES);
+        metrics::observe(
+            &metrics::ATTESTATION_PROCESSING_BATCH_UNAGG_SIZE,
+            num_partially_verified as f64,
+        );
 
         if verify_signature_sets(signature_sets.iter()) {
             // Since all the signatures verified in a batch, there's no reason for them to be
             // checked again later.
             check_signatures = CheckAttestationSignature::No
+        } else {
+            metrics::inc_counter(&metrics::ATTESTATION_PROCESSING_BATCH_UNAGG_FALLBACKS);
         }
     }
 
@@ -1,8 +1,9 @@
+use crate::events::EventKind;
 use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
-use eth2::lighthouse::StandardBlockReward;
+use eth2::lighthouse::{PublishedBlockReward, StandardBlockReward};
 use operation_pool::RewardCache;
 use safe_arith::SafeArith;
-use slog::error;
+use slog::{debug, error, info};
 use state_processing::{
     common::{
         altair, get_attestation_participation_flag_indices, get_attesting_indices_from_state,
@@ -10,12 +11,16 @@ use state_processing::{
     per_block_processing::{
         altair::sync_committee::compute_sync_aggregate_rewards, get_slashable_indices,
     },
+    state_advance::complete_state_advance,
 };
+use std::sync::Arc;
 use store::{
     consts::altair::{PARTICIPATION_FLAG_WEIGHTS, PROPOSER_WEIGHT, WEIGHT_DENOMINATOR},
     RelativeEpoch,
 };
-use types::{AbstractExecPayload, BeaconBlockRef, BeaconState, BeaconStateError, Hash256};
+use types::{
+    AbstractExecPayload, BeaconBlockRef, BeaconState, BeaconStateError, Hash256, SignedBeaconBlock,
+};
 
 type BeaconBlockSubRewardValue = u64;
 
@@ -33,6 +38,107 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         state.build_committee_cache(RelativeEpoch::Previous, &self.spec)?;
         state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
 
+        self.compute_beacon_block_reward_with_committee_caches(block, block_root, state)
+    }
+
+    /// Compute the proposer reward for an imported `block` that was published by this node, then
+    /// log it and emit a `published_block_reward` event.
+    ///
+    /// Loading the pre-state is expensive, so the reward is computed on a blocking task after
+    /// import rather than on the import path.
+    pub fn report_published_block_reward(
+        self: &Arc<Self>,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        block_root: Hash256,
+    ) {
+        let chain = self.clone();
+        self.task_executor.clone().spawn_blocking(
+            move || {
+                let reward = chain
+                    .state_before_applying_block(&block)
+                    .and_then(|mut state| {
+                        chain.compute_beacon_block_reward(block.message(), block_root, &mut state)
+                    });
+
+                let reward = match reward {
+                    Ok(reward) => reward,
+                    Err(e) => {
+                        debug!(
+                            chain.log,
+                            "Unable to compute block reward for published block";
+                            "root" => ?block_root,
+                            "error" => ?e,
+                        );
+                        return;
+                    }
+                };
+
+                info!(
+                    chain.log,
+                    "Block reward for published block";
+                    "root" => ?block_root,
+                    "slot" => block.slot(),
+                    "proposer_index" => reward.proposer_index,
+                    "total_gwei" => reward.total,
+                    "attestations_gwei" => reward.attestations,
+                    "sync_aggregate_gwei" => reward.sync_aggregate,
+                    "proposer_slashings_gwei" => reward.proposer_slashings,
+                    "attester_slashings_gwei" => reward.attester_slashings,
+                );
+                if let Some(event_handler) = chain.event_handler.as_ref() {
+                    if event_handler.has_published_block_reward_subscribers() {
+                        event_handler.register(EventKind::PublishedBlockReward(
+                            PublishedBlockReward {
+                                block_root,
+                                slot: block.slot(),
+                                reward,
+                            },
+                        ));
+                    }
+                }
+            },
+            "published_block_reward",
+        );
+    }
+
+    /// Load the parent state of `block` and advance it to the block's slot.
+    fn state_before_applying_block(
+        &self,
+        block: &SignedBeaconBlock<T::EthSpec>,
+    ) -> Result<BeaconState<T::EthSpec>, BeaconChainError> {
+        let parent_root = block.parent_root();
+        let parent_block = self
+            .get_blinded_block(&parent_root)?
+            .ok_or(BeaconChainError::MissingBeaconBlock(parent_root))?;
+        let parent_state_root = parent_block.state_root();
+        let mut state = self
+            .get_state(&parent_state_root, Some(parent_block.slot()))?
+            .ok_or(BeaconChainError::MissingBeaconState(parent_state_root))?;
+        complete_state_advance(
+            &mut state,
+            Some(parent_state_root),
+            block.slot(),
+            &self.spec,
+        )?;
+        Ok(state)
+    }
+
+    /// Compute the proposer reward for `block` from the `state` it is applied to, which must
+    /// already have its previous and current epoch committee caches built.
+    ///
+    /// Unlike `compute_beacon_block_reward` this leaves `state` untouched.
+    pub fn compute_beacon_block_reward_with_committee_caches<
+        Payload: AbstractExecPayload<T::EthSpec>,
+    >(
+        &self,
+        block: BeaconBlockRef<'_, T::EthSpec, Payload>,
+        block_root: Hash256,
+        state: &BeaconState<T::EthSpec>,
+    ) -> Result<StandardBlockReward, BeaconChainError> {
+        if block.slot() != state.slot() {
+            return Err(BeaconChainError::BlockRewardSlotError);
+        }
+
         let proposer_index = block.proposer_index();
 
         let sync_aggregate_reward =
@@ -176,8 +282,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     fn compute_beacon_block_attestation_reward_altair<Payload: AbstractExecPayload<T::EthSpec>>(
         &self,
         block: BeaconBlockRef<'_, T::EthSpec, Payload>,
-        state: &mut BeaconState<T::EthSpec>,
+        state: &BeaconState<T::EthSpec>,
     ) -> Result<BeaconBlockSubRewardValue, BeaconChainError> {
+        // Track the flags set by earlier attestations in the block on copies of the
+        // participation lists, rather than on the state itself.
+        let mut previous_epoch_participation = state.previous_epoch_participation()?.clone();
+        let mut current_epoch_participation = state.current_epoch_participation()?.clone();
+
         let total_active_balance = state.get_total_active_balance()?;
         let base_reward_per_increment =
             altair::BaseRewardPerIncrement::new(total_active_balance, &self.spec)?;
@@ -205,8 +316,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             for index in attesting_indices {
                 let index = index as usize;
                 for (flag_index, &weight) in PARTICIPATION_FLAG_WEIGHTS.iter().enumerate() {
-                    let epoch_participation =
-                        state.get_epoch_participation_mut(data.target.epoch)?;
+                    let epoch_participation = if data.target.epoch == state.current_epoch() {
+                        &mut current_epoch_participation
+                    } else if data.target.epoch == state.previous_epoch() {
+                        &mut previous_epoch_participation
+                    } else {
+                        return Err(BeaconStateError::EpochOutOfBounds.into());
+                    };
                     let validator_participation = epoch_participation
                         .get_mut(index)
                         .ok_or(BeaconStateError::ParticipationOutOfBounds(index))?;
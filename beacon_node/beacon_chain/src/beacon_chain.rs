@@ -7,6 +7,7 @@ use crate::attester_cache::{AttesterCache, AttesterCacheKey};
 use crate::beacon_block_streamer::{BeaconBlockStreamer, CheckEarlyAttesterCache};
 use crate::beacon_proposer_cache::compute_proposer_duties_from_head;
 use crate::beacon_proposer_cache::BeaconProposerCache;
+use crate::block_production_timings::BlockProductionTimings;
 use crate::block_times_cache::BlockTimesCache;
 use crate::block_verification::{
     check_block_is_finalized_checkpoint_or_descendant, check_block_relevancy, get_block_root,
@@ -24,12 +25,14 @@ use crate::execution_payload::{get_execution_payload, NotifyExecutionLayer, Prep
 use crate::fork_choice_signal::{ForkChoiceSignalRx, ForkChoiceSignalTx, ForkChoiceWaitResult};
 use crate::head_tracker::HeadTracker;
 use crate::historical_blocks::HistoricalBlockError;
+use crate::invalid_payload_cache::InvalidPayloadCache;
 use crate::light_client_finality_update_verification::{
     Error as LightClientFinalityUpdateError, VerifiedLightClientFinalityUpdate,
 };
 use crate::light_client_optimistic_update_verification::{
     Error as LightClientOptimisticUpdateError, VerifiedLightClientOptimisticUpdate,
 };
+use crate::light_client_server_cache::LightClientServerCache;
 use crate::migrate::BackgroundMigrator;
 use crate::naive_aggregation_pool::{
     AggregatedAttestationMap, Error as NaiveAggregationError, NaiveAggregationPool,
@@ -43,10 +46,14 @@ use crate::observed_attesters::{
 };
 use crate::observed_block_producers::ObservedBlockProducers;
 use crate::observed_operations::{ObservationOutcome, ObservedOperations};
-use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
+use crate::persisted_beacon_chain::PersistedBeaconChain;
 use crate::persisted_fork_choice::PersistedForkChoice;
+use crate::persisted_proposer_preparation::{
+    PersistedProposerPreparation, PersistedProposerPreparationEntry,
+};
 use crate::pre_finalization_cache::PreFinalizationBlockCache;
-use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
+use crate::shuffling_cache::{promise_wait_timeout, BlockShufflingIds, ShufflingCache};
+use crate::slasher_slashings::PendingSlasherSlashings;
 use crate::snapshot_cache::{BlockProductionPreState, SnapshotCache};
 use crate::sync_committee_verification::{
     Error as SyncCommitteeError, VerifiedSyncCommitteeMessage, VerifiedSyncContribution,
@@ -132,6 +139,7 @@ pub const BEACON_CHAIN_DB_KEY: Hash256 = Hash256::zero();
 pub const OP_POOL_DB_KEY: Hash256 = Hash256::zero();
 pub const ETH1_CACHE_DB_KEY: Hash256 = Hash256::zero();
 pub const FORK_CHOICE_DB_KEY: Hash256 = Hash256::zero();
+pub const PROPOSER_PREPARATION_DB_KEY: Hash256 = Hash256::zero();
 
 /// Defines how old a block can be before it's no longer a candidate for the early attester cache.
 const EARLY_ATTESTER_CACHE_HISTORIC_SLOTS: u64 = 4;
@@ -151,6 +159,12 @@ const PREPARE_PROPOSER_HISTORIC_EPOCHS: u64 = 4;
 /// impact whilst having 8 epochs without a block is a comfortable grace period.
 const MAX_PER_SLOT_FORK_CHOICE_DISTANCE: u64 = 256;
 
+/// The maximum number of orphaned blocks from which operations will be re-inserted into the op
+/// pool after a re-org.
+///
+/// Deeper re-orgs are rare, and replaying their blocks would be expensive.
+pub const MAX_REORG_OPERATION_REINSERT_DEPTH: u64 = 16;
+
 /// Reported to the user when the justified block has an invalid execution payload.
 pub const INVALID_JUSTIFIED_PAYLOAD_SHUTDOWN_REASON: &str =
     "Justified block has an invalid execution payload.";
@@ -290,6 +304,7 @@ struct PartialBeaconBlock<E: EthSpec, Payload: AbstractExecPayload<E>> {
     sync_aggregate: Option<SyncAggregate<E>>,
     prepare_payload_handle: Option<PreparePayloadHandle<E, Payload>>,
     bls_to_execution_changes: Vec<SignedBlsToExecutionChange>,
+    timings: BlockProductionTimings,
 }
 
 pub type BeaconForkChoice<T> = ForkChoice<
@@ -415,6 +430,11 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub block_times_cache: Arc<RwLock<BlockTimesCache>>,
     /// A cache used to track pre-finalization block roots for quick rejection.
     pub pre_finalization_block_cache: PreFinalizationBlockCache,
+    /// A cache of execution block hashes known to be invalid, for quick rejection of blocks which
+    /// build upon them.
+    pub invalid_payload_cache: InvalidPayloadCache,
+    /// A cache of light client objects served via the HTTP API.
+    pub light_client_server_cache: LightClientServerCache<T::EthSpec>,
     /// Sender given to tasks, so that if they encounter a state in which execution cannot
     /// continue they can request that everything shuts down.
     pub shutdown_sender: Sender<ShutdownReason>,
@@ -424,13 +444,19 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) graffiti: Graffiti,
     /// Optional slasher.
     pub slasher: Option<Arc<Slasher<T::EthSpec>>>,
+    /// Slashings found by the slasher which have not been included in a block yet.
+    pub pending_slasher_slashings: Mutex<PendingSlasherSlashings<T::EthSpec>>,
     /// Provides monitoring of a set of explicitly defined validators.
     pub validator_monitor: RwLock<ValidatorMonitor<T::EthSpec>>,
     /// The slot at which blocks are downloaded back to.
     pub genesis_backfill_slot: Slot,
 }
 
-type BeaconBlockAndState<T, Payload> = (BeaconBlock<T, Payload>, BeaconState<T>);
+type BeaconBlockAndState<T, Payload> = (
+    BeaconBlock<T, Payload>,
+    BeaconState<T>,
+    BlockProductionTimings,
+);
 
 impl FinalizationAndCanonicity {
     pub fn is_finalized(self) -> bool {
@@ -498,6 +524,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// We do it atomically even though no guarantees need to be made about blocks from
     /// the head tracker also being present in fork choice.
     pub fn persist_head_and_fork_choice(&self) -> Result<(), Error> {
+        let _persist_lock = self.canonical_head.persist_lock.lock();
         let mut batch = vec![];
 
         let _head_timer = metrics::start_timer(&metrics::PERSIST_HEAD);
@@ -507,6 +534,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         batch.push(self.persist_fork_choice_in_batch());
 
         self.store.hot_db.do_atomically(batch)?;
+        *self.canonical_head.last_persisted.lock() = Instant::now();
 
         Ok(())
     }
@@ -514,10 +542,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     /// Return a `PersistedBeaconChain` without reference to a `BeaconChain`.
     pub fn make_persisted_head(
         genesis_block_root: Hash256,
+        canonical_head_block_root: Hash256,
         head_tracker: &HeadTracker,
     ) -> PersistedBeaconChain {
         PersistedBeaconChain {
-            _canonical_head_block_root: DUMMY_CANONICAL_HEAD_BLOCK_ROOT,
+            canonical_head_block_root,
             genesis_block_root,
             ssz_head_tracker: head_tracker.to_ssz_container(),
         }
@@ -525,14 +554,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Return a database operation for writing the beacon chain head to disk.
     pub fn persist_head_in_batch(&self) -> KeyValueStoreOp {
-        Self::persist_head_in_batch_standalone(self.genesis_block_root, &self.head_tracker)
+        Self::persist_head_in_batch_standalone(
+            self.genesis_block_root,
+            self.canonical_head.cached_head().head_block_root(),
+            &self.head_tracker,
+        )
     }
 
     pub fn persist_head_in_batch_standalone(
         genesis_block_root: Hash256,
+        canonical_head_block_root: Hash256,
         head_tracker: &HeadTracker,
     ) -> KeyValueStoreOp {
-        Self::make_persisted_head(genesis_block_root, head_tracker)
+        Self::make_persisted_head(genesis_block_root, canonical_head_block_root, head_tracker)
             .as_kv_store_op(BEACON_CHAIN_DB_KEY)
     }
 
@@ -563,6 +597,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Persists `self.op_pool` to disk.
     ///
+    /// This is called at the start of every epoch by `per_slot_task`, and on shutdown.
+    ///
     /// ## Notes
     ///
     /// This operation is typically slow and causes a lot of allocations. It should be used
@@ -590,6 +626,90 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Persists the proposer preparation data and builder registrations held by the execution
+    /// layer to disk.
+    ///
+    /// This is called by `per_slot_task` when validator clients have provided new preparation data
+    /// or registrations, so that bursts of changes result in a single write.
+    pub async fn persist_proposer_preparation(&self) -> Result<(), Error> {
+        let execution_layer = match self.execution_layer.as_ref() {
+            Some(execution_layer) => execution_layer,
+            None => return Ok(()),
+        };
+
+        let preparation_data = execution_layer
+            .proposer_preparation_entries()
+            .await
+            .into_iter()
+            .map(
+                |(update_epoch, preparation_data)| PersistedProposerPreparationEntry {
+                    update_epoch,
+                    preparation_data,
+                },
+            )
+            .collect();
+        let (builder_url, builder_registrations) = execution_layer.builder_registrations();
+        let persisted = PersistedProposerPreparation {
+            preparation_data,
+            builder_url: builder_url.map(String::into_bytes).unwrap_or_default(),
+            builder_registrations,
+        };
+
+        let store = self.store.clone();
+        self.spawn_blocking_handle(
+            move || store.put_item(&PROPOSER_PREPARATION_DB_KEY, &persisted),
+            "persist_proposer_preparation",
+        )
+        .await??;
+
+        Ok(())
+    }
+
+    /// Loads proposer preparation data and builder registrations persisted by a previous run of
+    /// the beacon node into the execution layer.
+    ///
+    /// Preparation data which has expired since it was persisted is ignored.
+    pub fn load_proposer_preparation(&self) -> Result<(), Error> {
+        let execution_layer = match self.execution_layer.as_ref() {
+            Some(execution_layer) => execution_layer,
+            None => return Ok(()),
+        };
+        let persisted = match self
+            .store
+            .get_item::<PersistedProposerPreparation>(&PROPOSER_PREPARATION_DB_KEY)?
+        {
+            Some(persisted) => persisted,
+            None => return Ok(()),
+        };
+
+        let current_epoch = self
+            .slot_clock
+            .now_or_genesis()
+            .ok_or(Error::UnableToReadSlot)?
+            .epoch(T::EthSpec::slots_per_epoch());
+        let entries = persisted
+            .preparation_data
+            .into_iter()
+            .map(|entry| (entry.update_epoch, entry.preparation_data))
+            .collect();
+        let restored = execution_layer
+            .restore_proposer_preparation(current_epoch, entries)
+            .map_err(Error::RestoreProposerPreparationFailed)?;
+        let num_registrations = String::from_utf8(persisted.builder_url).map_or(0, |builder_url| {
+            execution_layer
+                .restore_builder_registrations(&builder_url, persisted.builder_registrations)
+        });
+
+        debug!(
+            self.log,
+            "Loaded proposer preparation data from disk";
+            "preparation_entries" => restored,
+            "builder_registrations" => num_registrations,
+        );
+
+        Ok(())
+    }
+
     /// Returns the slot _right now_ according to `self.slot_clock`. Returns `Err` if the slot is
     /// unavailable.
     ///
@@ -1998,6 +2118,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     "slot" => slot.as_u64(),
                 );
             }
+            Err(e @ NaiveAggregationError::ReachedMaxItemsPerSlot(_)) => {
+                // The pool is bounded per slot to limit memory usage, which is expected to be
+                // reached when importing all attestations from all subnets.
+                metrics::inc_counter(&metrics::ATTESTATION_PROCESSING_AGG_POOL_FULL);
+                debug!(
+                    self.log,
+                    "Agg pool full, refused to store unaggregated attestation";
+                    "index" => attestation.data.index,
+                    "slot" => attestation.data.slot.as_u64(),
+                );
+                return Err(Error::from(e).into());
+            }
             Err(e) => {
                 error!(
                         self.log,
@@ -2262,6 +2394,105 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Re-insert the operations from blocks orphaned by a re-org into the op pool, so that they
+    /// can be included in a block on the new canonical chain.
+    ///
+    /// Orphaned blocks are found by walking back from `old_head_block_root` until a block on the
+    /// chain of `new_head_state` is reached, visiting at most `MAX_REORG_OPERATION_REINSERT_DEPTH`
+    /// blocks. Operations which are invalid with respect to `new_head_state` are skipped.
+    pub fn reinsert_orphaned_operations(
+        &self,
+        old_head_block_root: Hash256,
+        new_head_state: &BeaconState<T::EthSpec>,
+    ) -> Result<(), Error> {
+        // Operations are only added to the op pool if we have the ability to propose blocks.
+        if self.eth1_chain.is_none() {
+            return Ok(());
+        }
+
+        let mut block_root = old_head_block_root;
+        let mut num_orphaned_blocks = 0;
+        let mut num_reinserted = 0;
+
+        for _ in 0..MAX_REORG_OPERATION_REINSERT_DEPTH {
+            let block = match self.store.get_blinded_block(&block_root)? {
+                Some(block) => block,
+                None => break,
+            };
+            let is_canonical = new_head_state
+                .get_block_root(block.slot())
+                .map_or(false, |root| *root == block_root);
+            if is_canonical {
+                break;
+            }
+            num_orphaned_blocks += 1;
+
+            let body = block.message().body();
+            for exit in body.voluntary_exits() {
+                if let Ok(exit) = exit.clone().validate(new_head_state, &self.spec) {
+                    self.op_pool.insert_voluntary_exit(exit);
+                    num_reinserted += 1;
+                }
+            }
+            for proposer_slashing in body.proposer_slashings() {
+                if let Ok(proposer_slashing) = proposer_slashing
+                    .clone()
+                    .validate(new_head_state, &self.spec)
+                {
+                    self.op_pool.insert_proposer_slashing(proposer_slashing);
+                    num_reinserted += 1;
+                }
+            }
+            for attester_slashing in body.attester_slashings() {
+                if let Ok(attester_slashing) = attester_slashing
+                    .clone()
+                    .validate(new_head_state, &self.spec)
+                {
+                    self.op_pool.insert_attester_slashing(attester_slashing);
+                    num_reinserted += 1;
+                }
+            }
+            for attestation in body.attestations() {
+                // The attesting indices are computed from the committees of `new_head_state`, so
+                // they're only correct if the attestation's shuffling matches.
+                if !self.shuffling_is_compatible(
+                    &attestation.data.beacon_block_root,
+                    attestation.data.target.epoch,
+                    new_head_state,
+                ) {
+                    continue;
+                }
+                let import = |attestation: &Attestation<T::EthSpec>| {
+                    let attesting_indices =
+                        get_attesting_indices_from_state(new_head_state, attestation)?;
+                    self.op_pool
+                        .insert_attestation(attestation.clone(), attesting_indices)
+                };
+                match import(attestation) {
+                    Ok(()) => num_reinserted += 1,
+                    Err(e) => debug!(
+                        self.log,
+                        "Failed to re-insert orphaned attestation";
+                        "reason" => ?e,
+                        "block_root" => ?block_root,
+                    ),
+                }
+            }
+
+            block_root = block.parent_root();
+        }
+
+        debug!(
+            self.log,
+            "Re-inserted operations from orphaned blocks";
+            "orphaned_blocks" => num_orphaned_blocks,
+            "operations" => num_reinserted,
+            "old_head_block_root" => ?old_head_block_root,
+        );
+
+        Ok(())
+    }
+
     /// Verify a proposer slashing before allowing it to propagate on the gossip network.
     pub fn verify_proposer_slashing_for_gossip(
         &self,
@@ -2782,6 +3013,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             payload_verification_handle,
             parent_eth1_finalization_data,
             consensus_context,
+            report_proposer_reward,
         } = execution_pending_block;
 
         let PayloadVerificationOutcome {
@@ -2822,6 +3054,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             );
         }
 
+        // Retain the block so that its proposer reward can be computed after import.
+        let reward_block = report_proposer_reward.then(|| block.clone());
+
         let chain = self.clone();
         let block_hash = self
             .spawn_blocking_handle(
@@ -2841,6 +3076,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             )
             .await??;
 
+        if let Some(block) = reward_block {
+            self.report_published_block_reward(block, block_root);
+        }
+
         Ok(block_hash)
     }
 
@@ -2925,6 +3164,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     &state,
                     payload_verification_status,
                     self.config.progressive_balances_mode,
+                    self.config.count_unrealized.into(),
                     &self.spec,
                     &self.log,
                 )
@@ -3638,11 +3878,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         validator_graffiti: Option<Graffiti>,
         verification: ProduceBlockVerification,
     ) -> Result<BeaconBlockAndState<T::EthSpec, Payload>, BlockProductionError> {
+        let production_start = Instant::now();
+
         // Part 1/2 (blocking)
         //
         // Load the parent state from disk.
         let chain = self.clone();
-        let (state, state_root_opt) = self
+        let (state, state_root_opt, load_timings) = self
             .task_executor
             .spawn_blocking_handle(
                 move || chain.load_state_for_block_production(slot),
@@ -3655,28 +3897,60 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Part 2/2 (async, with some blocking components)
         //
         // Produce the block upon the state
-        self.produce_block_on_state::<Payload>(
-            state,
-            state_root_opt,
-            slot,
-            randao_reveal,
-            validator_graffiti,
-            verification,
-        )
-        .await
+        let (block, state, mut timings) = self
+            .produce_block_on_state::<Payload>(
+                state,
+                state_root_opt,
+                slot,
+                randao_reveal,
+                validator_graffiti,
+                verification,
+            )
+            .await?;
+        timings.fork_choice = load_timings.fork_choice;
+        timings.state_load = load_timings.state_load;
+        timings.total = production_start.elapsed();
+
+        debug!(
+            self.log,
+            "Block production timings";
+            "slot" => slot,
+            "total_ms" => timings.total.as_millis(),
+            "fork_choice_ms" => timings.fork_choice.as_millis(),
+            "state_load_ms" => timings.state_load.as_millis(),
+            "state_advance_ms" => timings.state_advance.as_millis(),
+            "op_pool_packing_ms" => timings.op_pool_packing.as_millis(),
+            "payload_fetch_ms" => timings.payload_fetch.as_millis(),
+            "block_processing_ms" => timings.block_processing.as_millis(),
+            "state_root_ms" => timings.state_root.as_millis(),
+        );
+
+        Ok((block, state, timings))
     }
 
     /// Load a beacon state from the database for block production. This is a long-running process
     /// that should not be performed in an `async` context.
+    ///
+    /// The returned timings only include the fork choice and state load stages.
     fn load_state_for_block_production(
         self: &Arc<Self>,
         slot: Slot,
-    ) -> Result<(BeaconState<T::EthSpec>, Option<Hash256>), BlockProductionError> {
+    ) -> Result<
+        (
+            BeaconState<T::EthSpec>,
+            Option<Hash256>,
+            BlockProductionTimings,
+        ),
+        BlockProductionError,
+    > {
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_REQUESTS);
         let _complete_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_TIMES);
+        let mut timings = BlockProductionTimings::default();
 
         let fork_choice_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_FORK_CHOICE_TIMES);
+        let fork_choice_start = Instant::now();
         self.wait_for_fork_choice_before_block_production(slot)?;
+        timings.fork_choice = fork_choice_start.elapsed();
         drop(fork_choice_timer);
 
         // Producing a block requires the tree hash cache, so clone a full state corresponding to
@@ -3685,6 +3959,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // signed. If we miss the cache or we're producing a block that conflicts with the head,
         // fall back to getting the head from `slot - 1`.
         let state_load_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_STATE_LOAD_TIMES);
+        let state_load_start = Instant::now();
 
         // Atomically read some values from the head whilst avoiding holding cached head `Arc` any
         // longer than necessary.
@@ -3712,8 +3987,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     snapshot_cache.get_state_for_block_production(head_block_root)
                 })
             {
+                // The state advance timer leaves an advanced pre-state in the snapshot cache.
+                if pre_state.pre_state.slot() == slot {
+                    metrics::inc_counter(&metrics::BLOCK_PRODUCTION_ADVANCED_STATE_HITS);
+                } else {
+                    metrics::inc_counter(&metrics::BLOCK_PRODUCTION_ADVANCED_STATE_MISSES);
+                }
                 (pre_state.pre_state, pre_state.state_root)
             } else {
+                metrics::inc_counter(&metrics::BLOCK_PRODUCTION_ADVANCED_STATE_MISSES);
                 warn!(
                     self.log,
                     "Block production cache miss";
@@ -3740,9 +4022,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             (state, None)
         };
 
+        timings.state_load = state_load_start.elapsed();
         drop(state_load_timer);
 
-        Ok((state, state_root_opt))
+        Ok((state, state_root_opt, timings))
     }
 
     /// Fetch the beacon state to use for producing a block if a 1-slot proposer re-org is viable.
@@ -3803,6 +4086,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
 
         // Is the current head weak and appropriate for re-orging?
+        metrics::inc_counter(&metrics::BLOCK_PRODUCTION_RE_ORG_ATTEMPTS);
         let proposer_head_timer =
             metrics::start_timer(&metrics::BLOCK_PRODUCTION_GET_PROPOSER_HEAD_TIMES);
         let proposer_head = self
@@ -3815,20 +4099,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 &self.config.re_org_disallowed_offsets,
                 self.config.re_org_max_epochs_since_finalization,
             )
-            .map_err(|e| match e {
-                ProposerHeadError::DoNotReOrg(reason) => {
-                    debug!(
-                        self.log,
-                        "Not attempting re-org";
-                        "reason" => %reason,
-                    );
-                }
-                ProposerHeadError::Error(e) => {
-                    warn!(
-                        self.log,
-                        "Not attempting re-org";
-                        "error" => ?e,
-                    );
+            .map_err(|e| {
+                metrics::inc_counter(&metrics::BLOCK_PRODUCTION_RE_ORG_ABORTS);
+                match e {
+                    ProposerHeadError::DoNotReOrg(reason) => {
+                        debug!(
+                            self.log,
+                            "Not attempting re-org";
+                            "reason" => %reason,
+                        );
+                    }
+                    ProposerHeadError::Error(e) => {
+                        warn!(
+                            self.log,
+                            "Not attempting re-org";
+                            "error" => ?e,
+                        );
+                    }
                 }
             })
             .ok()?;
@@ -3843,6 +4130,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 snapshot_cache.get_state_for_block_production(re_org_parent_block)
             })
             .or_else(|| {
+                metrics::inc_counter(&metrics::BLOCK_PRODUCTION_RE_ORG_ABORTS);
                 debug!(
                     self.log,
                     "Not attempting re-org";
@@ -3860,6 +4148,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             "head_weight" => proposer_head.head_node.weight,
             "threshold_weight" => proposer_head.re_org_weight_threshold
         );
+        metrics::inc_counter(&metrics::BLOCK_PRODUCTION_RE_ORG_SUCCESSES);
 
         Some(pre_state)
     }
@@ -4263,12 +4552,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Wait for the execution layer to return an execution payload (if one is required).
         let prepare_payload_handle = partial_beacon_block.prepare_payload_handle.take();
         let block_contents = if let Some(prepare_payload_handle) = prepare_payload_handle {
-            Some(
-                prepare_payload_handle
-                    .await
-                    .map_err(BlockProductionError::TokioJoin)?
-                    .ok_or(BlockProductionError::ShuttingDown)??,
-            )
+            let payload_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_PAYLOAD_TIMES);
+            let payload_start = Instant::now();
+            let block_contents = prepare_payload_handle
+                .await
+                .map_err(BlockProductionError::TokioJoin)?
+                .ok_or(BlockProductionError::ShuttingDown)??;
+            partial_beacon_block.timings.payload_fetch = payload_start.elapsed();
+            drop(payload_timer);
+            Some(block_contents)
         } else {
             None
         };
@@ -4314,12 +4606,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             });
         }
 
+        let mut timings = BlockProductionTimings::default();
         let slot_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_SLOT_PROCESS_TIMES);
+        let state_advance_start = Instant::now();
 
         // Ensure the state has performed a complete transition into the required slot.
         complete_state_advance(&mut state, state_root_opt, produce_at_slot, &self.spec)?;
 
+        timings.state_advance = state_advance_start.elapsed();
         drop(slot_timer);
+        let packing_start = Instant::now();
 
         state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
 
@@ -4513,6 +4809,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             Some(sync_aggregate)
         };
 
+        timings.op_pool_packing = packing_start.elapsed();
+
         Ok(PartialBeaconBlock {
             state,
             slot,
@@ -4529,6 +4827,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             sync_aggregate,
             prepare_payload_handle,
             bls_to_execution_changes,
+            timings,
         })
     }
 
@@ -4557,6 +4856,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             // produce said `execution_payload`.
             prepare_payload_handle: _,
             bls_to_execution_changes,
+            mut timings,
         } = partial_beacon_block;
 
         let inner_block = match &state {
@@ -4666,6 +4966,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
 
         let process_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_PROCESS_TIMES);
+        let process_start = Instant::now();
         let signature_strategy = match verification {
             ProduceBlockVerification::VerifyRandao => BlockSignatureStrategy::VerifyRandao,
             ProduceBlockVerification::NoVerification => BlockSignatureStrategy::NoVerification,
@@ -4681,10 +4982,13 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             &mut ctxt,
             &self.spec,
         )?;
+        timings.block_processing = process_start.elapsed();
         drop(process_timer);
 
         let state_root_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_STATE_ROOT_TIMES);
+        let state_root_start = Instant::now();
         let state_root = state.update_tree_hash_cache()?;
+        timings.state_root = state_root_start.elapsed();
         drop(state_root_timer);
 
         let (mut block, _) = block.deconstruct();
@@ -4700,7 +5004,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             "slot" => block.slot()
         );
 
-        Ok((block, state))
+        Ok((block, state, timings))
     }
 
     /// This method must be called whenever an execution engine indicates that a payload is
@@ -4726,18 +5030,44 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // on the core executor is bad.
         let chain = self.clone();
         let inner_op = op.clone();
-        let fork_choice_result = self
+        let (fork_choice_result, invalid_block_roots, latest_valid_root) = self
             .spawn_blocking_handle(
                 move || {
-                    chain
-                        .canonical_head
-                        .fork_choice_write_lock()
-                        .on_invalid_execution_payload(&inner_op)
+                    let mut fork_choice = chain.canonical_head.fork_choice_write_lock();
+                    let result = fork_choice.on_invalid_execution_payload(&inner_op);
+                    // Collect the blocks invalidated by `inner_op`, which are the block it targets
+                    // and its invalid ancestors.
+                    let invalid_block_roots = fork_choice
+                        .proto_array()
+                        .iter_nodes(&inner_op.block_root())
+                        .map_while(|node| match node.execution_status {
+                            ExecutionStatus::Invalid(_) => Some(node.root),
+                            _ => None,
+                        })
+                        .collect::<Vec<_>>();
+                    let latest_valid_root = inner_op.latest_valid_ancestor().and_then(|hash| {
+                        fork_choice
+                            .proto_array()
+                            .core_proto_array()
+                            .execution_block_hash_to_beacon_block_root(&hash)
+                    });
+                    (result, invalid_block_roots, latest_valid_root)
                 },
                 "invalid_payload_fork_choice_update",
             )
             .await?;
 
+        // Remember the invalidated blocks so that blocks built upon them can be rejected without
+        // consulting the execution engine. Only blocks that fork choice has marked invalid are
+        // recorded. The latest valid ancestor is known to be valid, even if it was previously
+        // thought to be invalid.
+        for block_root in invalid_block_roots {
+            self.invalid_payload_cache.insert(block_root);
+        }
+        if let Some(latest_valid_root) = latest_valid_root {
+            self.invalid_payload_cache.remove(&latest_valid_root);
+        }
+
         // Update fork choice.
         if let Err(e) = fork_choice_result {
             crit!(
@@ -4806,6 +5136,37 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .contains_block(root)
     }
 
+    /// Returns the root of a previously seen block which was proposed by the proposer of `block`
+    /// at the same slot but differs from `block`, if any.
+    ///
+    /// Both the blocks observed on gossip or via the HTTP API and the block in the early attester
+    /// cache are considered.
+    pub fn find_equivocating_proposal(
+        &self,
+        block: BeaconBlockRef<T::EthSpec>,
+        block_root: Hash256,
+    ) -> Result<Option<Hash256>, Error> {
+        let observed_root = self
+            .observed_block_producers
+            .read()
+            .conflicting_block_roots(block, block_root)?
+            .into_iter()
+            .next();
+        if observed_root.is_some() {
+            return Ok(observed_root);
+        }
+
+        Ok(self
+            .early_attester_cache
+            .get_head_block()
+            .filter(|(cached_root, cached_block)| {
+                *cached_root != block_root
+                    && cached_block.slot() == block.slot()
+                    && cached_block.message().proposer_index() == block.proposer_index()
+            })
+            .map(|(cached_root, _)| cached_root))
+    }
+
     /// Determines the beacon proposer for the next slot. If that proposer is registered in the
     /// `execution_layer`, provide the `execution_layer` with the necessary information to produce
     /// `PayloadAttributes` for future calls to fork choice.
@@ -4890,25 +5251,37 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             };
 
         // If the execution layer doesn't have any proposer data for this validator then we assume
-        // it's not connected to this BN and no action is required.
+        // it's not connected to this BN and no action is required, unless we're always preparing
+        // payloads, in which case the fee recipient falls back to `--suggested-fee-recipient`.
         let proposer = pre_payload_attributes.proposer_index;
-        if !self.config.always_prepare_payload
-            && !execution_layer
-                .has_proposer_preparation_data(proposer)
-                .await
+        if !execution_layer
+            .has_proposer_preparation_data(proposer)
+            .await
         {
-            return Ok(());
+            if !self.config.always_prepare_payload {
+                return Ok(());
+            }
+            warn!(
+                self.log,
+                "Preparing payload for unknown proposer";
+                "prepare_slot" => prepare_slot,
+                "validator" => proposer,
+                "info" => "using the default fee recipient"
+            );
         }
 
         // Fetch payoad attributes from the execution layer's cache, or compute them from scratch
         // if no matching entry is found. This saves recomputing the withdrawals which can take
         // considerable time to compute if a state load is required.
+        //
+        // The `payload_attributes` event is only emitted when the attributes are first inserted,
+        // so that it fires at most once per proposal slot and head block root.
         let head_root = forkchoice_update_params.head_root;
-        let payload_attributes = if let Some(payload_attributes) = execution_layer
+        let (payload_attributes, is_new) = if let Some(payload_attributes) = execution_layer
             .payload_attributes(prepare_slot, head_root)
             .await
         {
-            payload_attributes
+            (payload_attributes, false)
         } else {
             let withdrawals = match self.spec.fork_name_at_slot::<T::EthSpec>(prepare_slot) {
                 ForkName::Base | ForkName::Altair | ForkName::Merge => None,
@@ -4935,7 +5308,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 withdrawals.map(Into::into),
             );
 
-            execution_layer
+            // Another task may have inserted the same attributes concurrently.
+            let already_known = execution_layer
                 .insert_proposer(
                     prepare_slot,
                     head_root,
@@ -4953,11 +5327,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 "validator" => proposer,
                 "parent_root" => ?head_root,
             );
-            payload_attributes
+            (payload_attributes, !already_known)
         };
 
         // Push a server-sent event (probably to a block builder or relay).
-        if let Some(event_handler) = &self.event_handler {
+        if let Some(event_handler) = self.event_handler.as_ref().filter(|_| is_new) {
             if event_handler.has_payload_attributes_subscribers() {
                 event_handler.register(EventKind::PayloadAttributes(ForkVersionedResponse {
                     data: SseExtendedPayloadAttributes {
@@ -5422,6 +5796,29 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             self.naive_aggregation_pool.write().prune(slot);
             self.block_times_cache.write().prune(slot);
 
+            // Persist proposer preparation data and builder registrations if they have changed
+            // since the last slot.
+            if self
+                .execution_layer
+                .as_ref()
+                .map_or(false, |el| el.take_proposer_preparation_changed())
+            {
+                let chain = self.clone();
+                self.task_executor.spawn(
+                    async move {
+                        if let Err(e) = chain.persist_proposer_preparation().await {
+                            error!(
+                                chain.log,
+                                "Failed to persist proposer preparation data";
+                                "error" => ?e,
+                                "slot" => slot,
+                            );
+                        }
+                    },
+                    "per_slot_task_persist_proposer_preparation",
+                );
+            }
+
             // Don't run heavy-weight tasks during sync.
             if self.best_slot() + MAX_PER_SLOT_FORK_CHOICE_DISTANCE < slot {
                 return;
@@ -5451,9 +5848,65 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 },
                 "per_slot_task_fc_signal_tx",
             );
+
+            // Persist the op pool once per epoch so that a crash loses at most an epoch of
+            // operations. The op pool is also persisted on shutdown, when the chain is dropped.
+            if slot % T::EthSpec::slots_per_epoch() == 0 {
+                let chain = self.clone();
+                self.task_executor.clone().spawn_blocking(
+                    move || {
+                        if let Err(e) = chain.persist_op_pool() {
+                            error!(
+                                chain.log,
+                                "Failed to persist op pool";
+                                "error" => ?e,
+                                "slot" => slot,
+                            );
+                        }
+                    },
+                    "per_slot_task_persist_op_pool",
+                );
+
+                // Refresh the database statistics (and their metrics) if they're stale so that
+                // they're ready for the `/lighthouse/database/info` endpoint, which only ever
+                // reads the cached statistics.
+                let chain = self.clone();
+                self.task_executor.clone().spawn_blocking(
+                    move || {
+                        if let Err(e) = chain
+                            .store
+                            .database_stats(slot.epoch(T::EthSpec::slots_per_epoch()))
+                        {
+                            error!(
+                                chain.log,
+                                "Failed to gather database statistics";
+                                "error" => ?e,
+                                "slot" => slot,
+                            );
+                        }
+                    },
+                    "per_slot_task_database_stats",
+                );
+            }
         }
     }
 
+    /// Returns the number of snapshots currently held in the snapshot cache.
+    pub fn snapshot_cache_len(&self) -> Result<usize, Error> {
+        self.snapshot_cache
+            .try_read_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+            .map(|cache| cache.len())
+            .ok_or(Error::SnapshotCacheLockTimeout)
+    }
+
+    /// Returns the number of committee caches (and promises) currently held in the shuffling cache.
+    pub fn shuffling_cache_len(&self) -> Result<usize, Error> {
+        self.shuffling_cache
+            .try_read_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+            .map(|cache| cache.len())
+            .ok_or(Error::AttestationCacheLockTimeout)
+    }
+
     /// Runs the `map_fn` with the committee cache for `shuffling_epoch` from the chain with head
     /// `head_block_root`. The `map_fn` will be supplied two values:
     ///
@@ -5509,37 +5962,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             head_block_epoch: head_block.slot.epoch(T::EthSpec::slots_per_epoch()),
         })?;
 
-        // Obtain the shuffling cache, timing how long we wait.
-        let cache_wait_timer =
-            metrics::start_timer(&metrics::ATTESTATION_PROCESSING_SHUFFLING_CACHE_WAIT_TIMES);
-
-        let mut shuffling_cache = self
-            .shuffling_cache
-            .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
-            .ok_or(Error::AttestationCacheLockTimeout)?;
-
-        metrics::stop_timer(cache_wait_timer);
-
-        if let Some(cache_item) = shuffling_cache.get(&shuffling_id) {
-            // The shuffling cache is no longer required, drop the write-lock to allow concurrent
-            // access.
-            drop(shuffling_cache);
-
-            let committee_cache = cache_item.wait()?;
-            map_fn(&committee_cache, shuffling_id.shuffling_decision_block)
-        } else {
-            // Create an entry in the cache that "promises" this value will eventually be computed.
-            // This avoids the case where multiple threads attempt to produce the same value at the
-            // same time.
-            //
-            // Creating the promise whilst we hold the `shuffling_cache` lock will prevent the same
-            // promise from being created twice.
-            let sender = shuffling_cache.create_promise(shuffling_id.clone())?;
-
-            // Drop the shuffling cache to avoid holding the lock for any longer than
-            // required.
-            drop(shuffling_cache);
-
+        let committee_cache = self.get_or_build_committee_cache(&shuffling_id, || {
             debug!(
                 self.log,
                 "Committee cache miss";
@@ -5611,8 +6034,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     .saturating_sub(1_u64)
                     .start_slot(T::EthSpec::slots_per_epoch());
 
-                // Advance the state into the required slot, using the "partial" method since the state
-                // roots are not relevant for the shuffling.
+                // Advance the state into the required slot, using the "partial" method since the
+                // state roots are not relevant for the shuffling.
                 partial_state_advance(&mut state, Some(state_root), target_slot, &self.spec)?;
             } else if state.current_epoch() > shuffling_epoch {
                 return Err(Error::InvalidStateForShuffling {
@@ -5631,20 +6054,92 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             state.build_committee_cache(relative_epoch, &self.spec)?;
 
             let committee_cache = state.take_committee_cache(relative_epoch)?;
-            let committee_cache = Arc::new(committee_cache);
-            let shuffling_decision_block = shuffling_id.shuffling_decision_block;
 
-            self.shuffling_cache
+            metrics::stop_timer(committee_building_timer);
+
+            Ok(Arc::new(committee_cache))
+        })?;
+
+        map_fn(&committee_cache, shuffling_id.shuffling_decision_block)
+    }
+
+    /// Returns the committee cache for `shuffling_id` from the `shuffling_cache`, calling
+    /// `build_fn` to compute it if it is neither cached nor promised by another thread.
+    ///
+    /// Whilst `build_fn` runs, the `shuffling_cache` holds a promise for its result, so that
+    /// concurrent callers for the same `shuffling_id` wait on this computation instead of each
+    /// computing the committee cache themselves.
+    pub fn get_or_build_committee_cache<F>(
+        &self,
+        shuffling_id: &AttestationShufflingId,
+        build_fn: F,
+    ) -> Result<Arc<CommitteeCache>, Error>
+    where
+        F: FnOnce() -> Result<Arc<CommitteeCache>, Error>,
+    {
+        // Whether we have already given up on another thread's promise for this committee.
+        let mut promise_abandoned = false;
+
+        let sender = loop {
+            // Obtain the shuffling cache, timing how long we wait.
+            let cache_wait_timer =
+                metrics::start_timer(&metrics::ATTESTATION_PROCESSING_SHUFFLING_CACHE_WAIT_TIMES);
+
+            let mut shuffling_cache = self
+                .shuffling_cache
                 .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
-                .ok_or(Error::AttestationCacheLockTimeout)?
-                .insert_committee_cache(shuffling_id, &committee_cache);
+                .ok_or(Error::AttestationCacheLockTimeout)?;
+
+            metrics::stop_timer(cache_wait_timer);
+
+            if let Some(cache_item) = shuffling_cache.get(shuffling_id) {
+                // The shuffling cache is no longer required, drop the write-lock to allow
+                // concurrent access.
+                drop(shuffling_cache);
+
+                let timeout = promise_wait_timeout(self.slot_clock.slot_duration());
+                match cache_item.clone().wait(timeout) {
+                    Ok(committee_cache) => return Ok(committee_cache),
+                    // The thread computing the committee failed (dropping its sender) or is stuck.
+                    // Poison its promise and retry, which will compute the committee on this
+                    // thread. Only do this once, so a repeatedly failing computation can't keep
+                    // this thread looping.
+                    Err(Error::CommitteePromiseFailed(_))
+                    | Err(Error::CommitteePromiseTimeout(_))
+                        if !promise_abandoned =>
+                    {
+                        promise_abandoned = true;
+                        self.shuffling_cache
+                            .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+                            .ok_or(Error::AttestationCacheLockTimeout)?
+                            .poison_promise(shuffling_id, &cache_item);
+                    }
+                    Err(e) => return Err(e),
+                }
+            } else {
+                // Create an entry in the cache that "promises" this value will eventually be
+                // computed. This avoids the case where multiple threads attempt to produce the
+                // same value at the same time.
+                //
+                // Creating the promise whilst we hold the `shuffling_cache` lock will prevent the
+                // same promise from being created twice.
+                //
+                // If we return early with an error (or panic) before the committee is sent, the
+                // sender is dropped, which poisons the promise and causes waiters to retry.
+                break shuffling_cache.create_promise(shuffling_id.clone())?;
+            }
+        };
 
-            metrics::stop_timer(committee_building_timer);
+        let committee_cache = build_fn()?;
+
+        self.shuffling_cache
+            .try_write_for(ATTESTATION_CACHE_LOCK_TIMEOUT)
+            .ok_or(Error::AttestationCacheLockTimeout)?
+            .insert_committee_cache(shuffling_id.clone(), &committee_cache);
 
-            sender.send(committee_cache.clone());
+        sender.send(committee_cache.clone());
 
-            map_fn(&committee_cache, shuffling_decision_block)
-        }
+        Ok(committee_cache)
     }
 
     /// Dumps the entire canonical chain, from the head to genesis to a vector for analysis.
@@ -5785,6 +6280,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
         let epoch_skips_check = epoch_skips <= self.config.builder_fallback_skips_per_epoch;
 
+        // Check that the builder revealed the payload for our previous blinded proposal.
+        let builder_reveal_check = !self
+            .execution_layer
+            .as_ref()
+            .map_or(false, |el| el.last_builder_reveal_failed());
+
         if !head_skips_check {
             Ok(ChainHealth::Unhealthy(FailedCondition::Skips))
         } else if !finalization_check {
@@ -5793,6 +6294,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             ))
         } else if !epoch_skips_check {
             Ok(ChainHealth::Unhealthy(FailedCondition::SkipsPerEpoch))
+        } else if !builder_reveal_check {
+            Ok(ChainHealth::Unhealthy(
+                FailedCondition::PreviousBuilderProposalMissed,
+            ))
         } else {
             Ok(ChainHealth::Healthy)
         }
@@ -7,6 +7,9 @@
 //! The cache is a fairly unintelligent LRU cache that is not pruned after finality. This makes it
 //! very simple to reason about, but it might store values that are useless due to finalization. The
 //! values it stores are very small, so this should not be an issue.
+//!
+//! Values whose decision block has been re-orged out of the canonical chain may be dropped with
+//! `BeaconProposerCache::retain_decision_block`.
 
 use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
 use fork_choice::ExecutionStatus;
@@ -131,6 +134,24 @@ impl BeaconProposerCache {
 
         Ok(())
     }
+
+    /// Drop any proposers for `epoch` which were not decided by `shuffling_decision_block`.
+    ///
+    /// This should be used when `shuffling_decision_block` is known to be canonical, so that
+    /// proposers from a re-orged chain don't linger in the cache.
+    pub fn retain_decision_block(&mut self, epoch: Epoch, shuffling_decision_block: Hash256) {
+        let stale_keys = self
+            .cache
+            .iter()
+            .map(|(key, _)| *key)
+            .filter(|&(key_epoch, key_root)| {
+                key_epoch == epoch && key_root != shuffling_decision_block
+            })
+            .collect::<Vec<_>>();
+        for key in stale_keys {
+            self.cache.pop(&key);
+        }
+    }
 }
 
 /// Compute the proposer duties using the head state without cache.
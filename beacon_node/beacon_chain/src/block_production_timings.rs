@@ -0,0 +1,45 @@
+//! Provides `BlockProductionTimings`, a breakdown of the time spent producing a block.
+use std::time::Duration;
+
+/// The time spent in each stage of producing a block.
+///
+/// Stages which weren't performed (e.g. loading the parent state when producing a block upon a
+/// given state) are left as zero.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BlockProductionTimings {
+    /// The wall-clock time taken to produce the block, which includes the time spent between the
+    /// stages below (e.g. assembling the block and its signature sets).
+    pub total: Duration,
+    /// Waiting for fork choice to run prior to the proposal.
+    pub fork_choice: Duration,
+    /// Loading the parent state from the snapshot cache or database.
+    pub state_load: Duration,
+    /// Advancing the parent state to the proposal slot.
+    pub state_advance: Duration,
+    /// Packing attestations and other operations from the op pool.
+    pub op_pool_packing: Duration,
+    /// Waiting for the execution payload once packing had completed.
+    ///
+    /// The payload is requested concurrently with op pool packing, so this is only the additional
+    /// delay caused by the execution layer or builder.
+    pub payload_fetch: Duration,
+    /// Processing the block upon the advanced state.
+    pub block_processing: Duration,
+    /// Computing the post-state root.
+    pub state_root: Duration,
+}
+
+impl BlockProductionTimings {
+    /// Returns the name and duration of each stage, in the order that they are performed.
+    pub fn stages(&self) -> [(&'static str, Duration); 7] {
+        [
+            ("fork_choice", self.fork_choice),
+            ("state_load", self.state_load),
+            ("state_advance", self.state_advance),
+            ("op_pool_packing", self.op_pool_packing),
+            ("payload_fetch", self.payload_fetch),
+            ("block_processing", self.block_processing),
+            ("state_root", self.state_root),
+        ]
+    }
+}
@@ -348,6 +348,13 @@ pub enum ExecutionPayloadError {
     ///
     /// The peer is not necessarily invalid.
     UnverifiedNonOptimisticCandidate,
+    /// The execution payload of the block with `block_root` was previously declared invalid by the
+    /// execution engine.
+    ///
+    /// ## Peer scoring
+    ///
+    /// As for `Self::RejectedByExecutionEngine`, the peer is not necessarily invalid.
+    KnownInvalidPayload { block_root: Hash256 },
 }
 
 impl ExecutionPayloadError {
@@ -378,6 +385,8 @@ impl ExecutionPayloadError {
             ExecutionPayloadError::InvalidTerminalBlockHash { .. } => false,
             // Do not penalize the peer since it's not their fault that *we're* optimistic.
             ExecutionPayloadError::UnverifiedNonOptimisticCandidate => false,
+            // As per `Self::RejectedByExecutionEngine`.
+            ExecutionPayloadError::KnownInvalidPayload { .. } => false,
         }
     }
 }
@@ -570,6 +579,7 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
             block_root: *block_root,
             parent: None,
             consensus_context,
+            report_proposer_reward: false,
         });
     }
 
@@ -595,6 +605,10 @@ pub struct GossipVerifiedBlock<T: BeaconChainTypes> {
     pub block_root: Hash256,
     parent: Option<PreProcessingSnapshot<T::EthSpec>>,
     consensus_context: ConsensusContext<T::EthSpec>,
+    /// Log the proposer reward and emit it as a `published_block_reward` event on import.
+    report_proposer_reward: bool,
+    /// Verify the proposer signature again during signature verification.
+    reverify_proposal_signature: bool,
 }
 
 /// A wrapper around a `SignedBeaconBlock` that indicates that all signatures (except the deposit
@@ -604,6 +618,7 @@ pub struct SignatureVerifiedBlock<T: BeaconChainTypes> {
     block_root: Hash256,
     parent: Option<PreProcessingSnapshot<T::EthSpec>>,
     consensus_context: ConsensusContext<T::EthSpec>,
+    report_proposer_reward: bool,
 }
 
 /// Used to await the result of executing payload with a remote EE.
@@ -630,6 +645,8 @@ pub struct ExecutionPendingBlock<T: BeaconChainTypes> {
     pub confirmed_state_roots: Vec<Hash256>,
     pub consensus_context: ConsensusContext<T::EthSpec>,
     pub payload_verification_handle: PayloadVerificationHandle<T::EthSpec>,
+    /// Whether the proposer reward should be reported once the block has been imported.
+    pub report_proposer_reward: bool,
 }
 
 pub trait IntoGossipVerifiedBlock<T: BeaconChainTypes>: Sized {
@@ -747,6 +764,9 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
         // Do not gossip a block from a finalized slot.
         check_block_against_finalized_slot(block.message(), block_root, chain)?;
 
+        // Do not gossip a block built upon a payload known to be invalid.
+        check_block_against_invalid_payloads(block.message(), block_root, chain)?;
+
         // Check if the block is already known. We know it is post-finalization, so it is
         // sufficient to check the fork choice.
         //
@@ -902,12 +922,28 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
             block_root,
             parent,
             consensus_context,
+            report_proposer_reward: false,
+            reverify_proposal_signature: false,
         })
     }
 
     pub fn block_root(&self) -> Hash256 {
         self.block_root
     }
+
+    /// Compute the proposer reward for this block while it is imported, then log it and emit a
+    /// `published_block_reward` event. Intended for blocks published via the HTTP API.
+    pub fn report_proposer_reward(mut self) -> Self {
+        self.report_proposer_reward = true;
+        self
+    }
+
+    /// Check the proposer signature again when the block is signature verified, instead of
+    /// relying on the check made during gossip verification. Used by `--paranoid-block-import`.
+    pub fn reverify_proposal_signature(mut self) -> Self {
+        self.reverify_proposal_signature = true;
+        self
+    }
 }
 
 impl<T: BeaconChainTypes> IntoExecutionPendingBlock<T> for GossipVerifiedBlock<T> {
@@ -974,6 +1010,7 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
                 block,
                 block_root,
                 parent: Some(parent),
+                report_proposer_reward: false,
             })
         } else {
             Err(BlockError::InvalidSignature)
@@ -991,7 +1028,7 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
     }
 
     /// Finishes signature verification on the provided `GossipVerifedBlock`. Does not re-verify
-    /// the proposer signature.
+    /// the proposer signature unless `reverify_proposal_signature` was requested.
     pub fn from_gossip_verified_block(
         from: GossipVerifiedBlock<T>,
         chain: &BeaconChain<T>,
@@ -1016,8 +1053,12 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
         // Gossip verification has already checked the proposer index. Use it to check the RANDAO
         // signature.
         let mut consensus_context = from.consensus_context;
-        signature_verifier
-            .include_all_signatures_except_proposal(&block, &mut consensus_context)?;
+        if from.reverify_proposal_signature {
+            signature_verifier.include_all_signatures(&block, &mut consensus_context)?;
+        } else {
+            signature_verifier
+                .include_all_signatures_except_proposal(&block, &mut consensus_context)?;
+        }
 
         if signature_verifier.verify().is_ok() {
             Ok(Self {
@@ -1025,6 +1066,7 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
                 block_root: from.block_root,
                 parent: Some(parent),
                 consensus_context,
+                report_proposer_reward: from.report_proposer_reward,
             })
         } else {
             Err(BlockError::InvalidSignature)
@@ -1069,6 +1111,7 @@ impl<T: BeaconChainTypes> IntoExecutionPendingBlock<T> for SignatureVerifiedBloc
             self.consensus_context,
             chain,
             notify_execution_layer,
+            self.report_proposer_reward,
         )
         .map_err(|e| BlockSlashInfo::SignatureValid(header, e))
     }
@@ -1115,6 +1158,7 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
         mut consensus_context: ConsensusContext<T::EthSpec>,
         chain: &Arc<BeaconChain<T>>,
         notify_execution_layer: NotifyExecutionLayer,
+        report_proposer_reward: bool,
     ) -> Result<Self, BlockError<T::EthSpec>> {
         chain
             .observed_block_producers
@@ -1498,6 +1542,7 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
             confirmed_state_roots,
             consensus_context,
             payload_verification_handle,
+            report_proposer_reward,
         })
     }
 }
@@ -1515,6 +1560,27 @@ fn check_block_against_anchor_slot<T: BeaconChainTypes>(
     Ok(())
 }
 
+/// Returns `Ok(())` if neither the execution payload of the block nor that of its parent is known
+/// to be invalid.
+///
+/// This check allows descendants of an invalid payload to be rejected without looking up their
+/// parents or consulting the execution engine again.
+fn check_block_against_invalid_payloads<T: BeaconChainTypes>(
+    block: BeaconBlockRef<'_, T::EthSpec>,
+    block_root: Hash256,
+    chain: &BeaconChain<T>,
+) -> Result<(), BlockError<T::EthSpec>> {
+    if chain.invalid_payload_cache.contains(&block.parent_root()) {
+        return Err(BlockError::ParentExecutionPayloadInvalid {
+            parent_root: block.parent_root(),
+        });
+    }
+    if chain.invalid_payload_cache.contains(&block_root) {
+        return Err(ExecutionPayloadError::KnownInvalidPayload { block_root }.into());
+    }
+    Ok(())
+}
+
 /// Returns `Ok(())` if the block is later than the finalized slot on `chain`.
 ///
 /// Returns an error if the block is earlier or equal to the finalized slot, or there was an error
@@ -1617,6 +1683,9 @@ pub fn check_block_relevancy<T: BeaconChainTypes>(
     // Do not process a block from a finalized slot.
     check_block_against_finalized_slot(block, block_root, chain)?;
 
+    // Do not process a block built upon a payload known to be invalid.
+    check_block_against_invalid_payloads(block, block_root, chain)?;
+
     // Check if the block is already known. We know it is post-finalization, so it is
     // sufficient to check the fork choice.
     if chain
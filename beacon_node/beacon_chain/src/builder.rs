@@ -5,9 +5,9 @@ use crate::fork_choice_signal::ForkChoiceSignalTx;
 use crate::fork_revert::{reset_fork_choice_to_finalization, revert_to_fork_boundary};
 use crate::head_tracker::HeadTracker;
 use crate::migrate::{BackgroundMigrator, MigratorConfig};
-use crate::persisted_beacon_chain::PersistedBeaconChain;
+use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
-use crate::snapshot_cache::{SnapshotCache, DEFAULT_SNAPSHOT_CACHE_SIZE};
+use crate::snapshot_cache::SnapshotCache;
 use crate::timeout_rw_lock::TimeoutRwLock;
 use crate::validator_monitor::ValidatorMonitor;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
@@ -18,13 +18,13 @@ use crate::{
 };
 use eth1::Config as Eth1Config;
 use execution_layer::ExecutionLayer;
-use fork_choice::{ForkChoice, ResetPayloadStatuses};
+use fork_choice::{CountUnrealized, ForkChoice, ResetPayloadStatuses};
 use futures::channel::mpsc::Sender;
 use operation_pool::{OperationPool, PersistedOperationPool};
 use parking_lot::RwLock;
 use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use slasher::Slasher;
-use slog::{crit, debug, error, info, Logger};
+use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
 use state_processing::per_slot_processing;
 use std::marker::PhantomData;
@@ -34,7 +34,7 @@ use store::{Error as StoreError, HotColdDB, ItemStore, KeyValueStoreOp};
 use task_executor::{ShutdownReason, TaskExecutor};
 use types::{
     BeaconBlock, BeaconState, ChainSpec, Checkpoint, Epoch, EthSpec, Graffiti, Hash256,
-    PublicKeyBytes, Signature, SignedBeaconBlock, Slot,
+    ProgressiveBalancesMode, PublicKeyBytes, Signature, SignedBeaconBlock, Slot,
 };
 
 /// An empty struct used to "witness" all the `BeaconChainTypes` traits. It has no user-facing
@@ -269,7 +269,12 @@ where
                     .to_string()
             })?;
 
-        let fork_choice =
+        let head_tracker = HeadTracker::from_ssz_container(&chain.ssz_head_tracker)
+            .map_err(|e| format!("Failed to decode head tracker for database: {:?}", e))?;
+
+        // Restore fork choice verbatim, only rebuilding it from the finalized checkpoint if the
+        // persisted fork choice is missing, incompatible or inconsistent with the database.
+        let persisted_fork_choice =
             BeaconChain::<Witness<TSlotClock, TEth1Backend, _, _, _>>::load_fork_choice(
                 store.clone(),
                 ResetPayloadStatuses::always_reset_conditionally(
@@ -278,8 +283,30 @@ where
                 &self.spec,
                 log,
             )
-            .map_err(|e| format!("Unable to load fork choice from disk: {:?}", e))?
-            .ok_or("Fork choice not found in store")?;
+            .map_err(|e| format!("Unable to load fork choice from disk: {:?}", e))
+            .and_then(|fork_choice| fork_choice.ok_or_else(|| "Fork choice not found".into()))
+            .and_then(|fork_choice| {
+                check_restored_fork_choice(&fork_choice, &store).map(|()| fork_choice)
+            });
+        let fork_choice = match persisted_fork_choice {
+            Ok(fork_choice) => fork_choice,
+            Err(e) => {
+                warn!(
+                    log,
+                    "Rebuilding fork choice";
+                    "info" => "this may take some time",
+                    "reason" => e,
+                );
+                rebuild_fork_choice(
+                    chain.canonical_head_block_root,
+                    store.clone(),
+                    &self.spec,
+                    self.chain_config.progressive_balances_mode,
+                    self.chain_config.count_unrealized.into(),
+                    log,
+                )?
+            }
+        };
 
         let genesis_block = store
             .get_blinded_block(&chain.genesis_block_root)
@@ -312,10 +339,7 @@ where
 
         self.genesis_block_root = Some(chain.genesis_block_root);
         self.genesis_state_root = Some(genesis_block.state_root());
-        self.head_tracker = Some(
-            HeadTracker::from_ssz_container(&chain.ssz_head_tracker)
-                .map_err(|e| format!("Failed to decode head tracker for database: {:?}", e))?,
-        );
+        self.head_tracker = Some(head_tracker);
         self.validator_pubkey_cache = Some(pubkey_cache);
         self.fork_choice = Some(fork_choice);
 
@@ -715,6 +739,7 @@ where
                 Some(current_slot),
                 &self.spec,
                 self.chain_config.progressive_balances_mode,
+                self.chain_config.count_unrealized.into(),
                 &log,
             )?;
         }
@@ -784,7 +809,9 @@ where
         self.pending_io_batch.push(BeaconChain::<
             Witness<TSlotClock, TEth1Backend, TEthSpec, THotStore, TColdStore>,
         >::persist_head_in_batch_standalone(
-            genesis_block_root, &head_tracker
+            genesis_block_root,
+            head_snapshot.beacon_block_root,
+            &head_tracker,
         ));
         self.pending_io_batch.push(BeaconChain::<
             Witness<TSlotClock, TEth1Backend, TEthSpec, THotStore, TColdStore>,
@@ -873,7 +900,7 @@ where
             event_handler: self.event_handler,
             head_tracker,
             snapshot_cache: TimeoutRwLock::new(SnapshotCache::new(
-                DEFAULT_SNAPSHOT_CACHE_SIZE,
+                self.chain_config.snapshot_cache_size,
                 head_for_snapshot_cache,
             )),
             shuffling_cache: TimeoutRwLock::new(ShufflingCache::new(
@@ -885,6 +912,8 @@ where
             beacon_proposer_cache: <_>::default(),
             block_times_cache: <_>::default(),
             pre_finalization_block_cache: <_>::default(),
+            invalid_payload_cache: <_>::default(),
+            light_client_server_cache: <_>::default(),
             validator_pubkey_cache: TimeoutRwLock::new(validator_pubkey_cache),
             attester_cache: <_>::default(),
             early_attester_cache: <_>::default(),
@@ -894,6 +923,7 @@ where
             log: log.clone(),
             graffiti: self.graffiti,
             slasher: self.slasher.clone(),
+            pending_slasher_slashings: <_>::default(),
             validator_monitor: RwLock::new(validator_monitor),
             genesis_backfill_slot,
         };
@@ -939,8 +969,54 @@ where
             "head_slot" => format!("{}", head.beacon_block.slot()),
         );
 
+        // Restore the fee recipients provided by validator clients prior to a restart, so that
+        // they're used for proposals before the validator clients next provide them.
+        if let Err(e) = beacon_chain.load_proposer_preparation() {
+            warn!(
+                log,
+                "Failed to load proposer preparation data";
+                "error" => ?e,
+            );
+        }
+
+        // Delete hot states which are no longer reachable from fork choice, e.g. those left behind
+        // by an unclean shutdown. States prior to the finalized slot are retained as they may be
+        // awaiting migration to the freezer, which must happen after this.
+        let num_deleted = {
+            let fork_choice = beacon_chain.canonical_head.fork_choice_read_lock();
+            let finalized_slot = fork_choice
+                .finalized_checkpoint()
+                .epoch
+                .start_slot(TEthSpec::slots_per_epoch());
+            beacon_chain
+                .store
+                .delete_abandoned_hot_states(|block_root, slot| {
+                    slot <= finalized_slot || fork_choice.contains_block(block_root)
+                })
+                .map_err(|e| format!("Unable to garbage collect hot states: {:?}", e))?
+        };
+        if num_deleted > 0 {
+            beacon_chain
+                .store_migrator
+                .process_garbage_collection_compaction(num_deleted);
+        }
+
+        // Continue migrating the freezer database to a new restore point spacing (in the
+        // background).
+        if beacon_chain.store.restore_point_migration_in_progress() {
+            beacon_chain
+                .store_migrator
+                .process_restore_point_migration();
+        }
+
         // Check for states to reconstruct (in the background).
         if beacon_chain.config.reconstruct_historic_states {
+            // Historic states may have been pruned by `lighthouse db prune-states`, in which case
+            // they must be retained again for reconstruction to fill them in.
+            beacon_chain
+                .store
+                .resume_historic_state_retention()
+                .map_err(|e| format!("Unable to resume historic state retention: {:?}", e))?;
             beacon_chain.store_migrator.process_reconstruction();
         }
 
@@ -1036,6 +1112,89 @@ fn genesis_block<T: EthSpec>(
     ))
 }
 
+/// Check that fork choice restored from disk is consistent with the rest of the database.
+fn check_restored_fork_choice<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    fork_choice: &ForkChoice<BeaconForkChoiceStore<E, Hot, Cold>, E>,
+    store: &HotColdDB<E, Hot, Cold>,
+) -> Result<(), String> {
+    let finalized_checkpoint = fork_choice.finalized_checkpoint();
+    if !fork_choice.contains_block(&finalized_checkpoint.root) {
+        return Err(format!(
+            "finalized block {:?} is missing from fork choice",
+            finalized_checkpoint.root
+        ));
+    }
+
+    let finalized_slot = finalized_checkpoint.epoch.start_slot(E::slots_per_epoch());
+    let split_slot = store.get_split_slot();
+    if finalized_slot < split_slot {
+        return Err(format!(
+            "fork choice is finalized at slot {} prior to the database split at slot {}",
+            finalized_slot, split_slot
+        ));
+    }
+
+    let head_block_root = fork_choice.get_forkchoice_update_parameters().head_root;
+    if !store
+        .block_exists(&head_block_root)
+        .map_err(|e| format!("unable to check head block: {:?}", e))?
+    {
+        return Err(format!(
+            "head block {:?} is missing from the database",
+            head_block_root
+        ));
+    }
+
+    Ok(())
+}
+
+/// Build fork choice afresh from the finalized checkpoint of the canonical head that was persisted
+/// alongside the head tracker, replaying the blocks from there to that head.
+///
+/// The head must be consistent with the database's split, otherwise fork choice would be rebuilt
+/// on a chain that may not be canonical.
+fn rebuild_fork_choice<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    canonical_head_block_root: Hash256,
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    spec: &ChainSpec,
+    progressive_balances_mode: ProgressiveBalancesMode,
+    count_unrealized: CountUnrealized,
+    log: &Logger,
+) -> Result<ForkChoice<BeaconForkChoiceStore<E, Hot, Cold>, E>, String> {
+    if canonical_head_block_root == DUMMY_CANONICAL_HEAD_BLOCK_ROOT {
+        return Err("Unable to rebuild fork choice: canonical head is unknown".into());
+    }
+    let head_block = store
+        .get_blinded_block(&canonical_head_block_root)
+        .map_err(|e| descriptive_db_error("head block", &e))?
+        .ok_or("Unable to rebuild fork choice: head block not found in store")?;
+    let head_state = store
+        .get_state(&head_block.state_root(), Some(head_block.slot()))
+        .map_err(|e| descriptive_db_error("head state", &e))?
+        .ok_or("Unable to rebuild fork choice: head state not found in store")?;
+
+    let split = store.get_split_info();
+    let finalized_checkpoint = head_state.finalized_checkpoint();
+    if finalized_checkpoint.epoch.start_slot(E::slots_per_epoch()) < split.slot {
+        return Err(format!(
+            "Unable to rebuild fork choice: head {:?} is finalized at {:?}, prior to the database \
+             split at slot {}",
+            canonical_head_block_root, finalized_checkpoint, split.slot
+        ));
+    }
+
+    reset_fork_choice_to_finalization(
+        canonical_head_block_root,
+        &head_state,
+        store,
+        None,
+        spec,
+        progressive_balances_mode,
+        count_unrealized,
+        log,
+    )
+}
+
 // Helper function to return more useful errors when reading from the database.
 fn descriptive_db_error(item: &str, error: &StoreError) -> String {
     let additional_info = if let StoreError::SszDecodeError(_) = error {
@@ -14,6 +14,9 @@
 //! 2. `RwLock<CachedHead>`: Contains a cached block/state from the last run of `proto_array`.
 //! 3. `Mutex<()>`: Is used to prevent concurrent execution of `BeaconChain::recompute_head`.
 //!
+//! A fourth `Mutex<()>` serialises writes of fork choice to disk. It is only ever taken by
+//! `BeaconChain::persist_head_and_fork_choice`, before locks (1) and (2) are briefly taken.
+//!
 //! This module has to take great efforts to avoid causing a deadlock with these three methods. Any
 //! developers working in this module should tread carefully and seek a detailed review.
 //!
@@ -38,6 +41,7 @@ use crate::{
     beacon_chain::{
         BeaconForkChoice, BeaconStore, OverrideForkchoiceUpdate,
         BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT, FORK_CHOICE_DB_KEY,
+        MAX_REORG_OPERATION_REINSERT_DEPTH,
     },
     block_times_cache::BlockTimesCache,
     events::ServerSentEventHandler,
@@ -54,12 +58,16 @@ use itertools::process_results;
 use parking_lot::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use slog::{crit, debug, error, warn, Logger};
 use slot_clock::SlotClock;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use store::{iter::StateRootsIterator, KeyValueStoreOp, StoreItem};
 use task_executor::{JoinHandle, ShutdownReason};
 use types::*;
 
+/// Minimum time between writes of fork choice to disk triggered by head changes.
+pub const FORK_CHOICE_PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Simple wrapper around `RwLock` that uses private visibility to prevent any other modules from
 /// accessing the contained lock without it being explicitly noted in this module.
 pub struct CanonicalHeadRwLock<T>(RwLock<T>);
@@ -249,6 +257,14 @@ pub struct CanonicalHead<T: BeaconChainTypes> {
     ///
     /// This lock **should not be made public**, it should only be used inside this module.
     recompute_head_lock: Mutex<()>,
+    /// A lock used to ensure that persisted copies of fork choice are written to disk in the
+    /// order in which they were read from `self.fork_choice`.
+    pub(crate) persist_lock: Mutex<()>,
+    /// Set whilst a background task to persist fork choice is queued, so that bursts of head
+    /// changes are coalesced into a single database write.
+    persist_pending: AtomicBool,
+    /// The time at which fork choice was last written to disk.
+    pub(crate) last_persisted: Mutex<Instant>,
 }
 
 impl<T: BeaconChainTypes> CanonicalHead<T> {
@@ -272,6 +288,9 @@ impl<T: BeaconChainTypes> CanonicalHead<T> {
             fork_choice: CanonicalHeadRwLock::new(fork_choice),
             cached_head: CanonicalHeadRwLock::new(cached_head),
             recompute_head_lock: Mutex::new(()),
+            persist_lock: Mutex::new(()),
+            persist_pending: AtomicBool::new(false),
+            last_persisted: Mutex::new(Instant::now()),
         }
     }
 
@@ -747,7 +766,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let old_snapshot = &old_cached_head.snapshot;
 
         // If the head changed, perform some updates.
-        if new_snapshot.beacon_block_root != old_snapshot.beacon_block_root {
+        let head_changed = new_snapshot.beacon_block_root != old_snapshot.beacon_block_root;
+        if head_changed {
             if let Err(e) =
                 self.after_new_head(&old_cached_head, &new_cached_head, new_head_proto_block)
             {
@@ -760,13 +780,41 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
 
         // Drop the old cache head nice and early to try and free the memory as soon as possible.
+        // Its snapshot is retained only if needed to compute light client updates.
+        let old_head_snapshot = self
+            .config
+            .enable_light_client_server
+            .then(|| old_cached_head.snapshot.clone());
         drop(old_cached_head);
 
+        // Persist fork choice so that a restart can restore it verbatim. It is also persisted when
+        // the chain is dropped at shutdown.
+        //
+        // A change of finalization is persisted immediately, before finalization triggers a
+        // migration, so that the persisted fork choice is never finalized prior to the database's
+        // split. Head changes are far more frequent, so they are persisted at most once every
+        // `FORK_CHOICE_PERSIST_INTERVAL` on a background task, to keep the database write off the
+        // path to updating the execution layer.
+        let finalization_changed = new_view.finalized_checkpoint != old_view.finalized_checkpoint;
+        let persist_due =
+            self.canonical_head.last_persisted.lock().elapsed() >= FORK_CHOICE_PERSIST_INTERVAL;
+        if finalization_changed {
+            if let Err(e) = self.persist_head_and_fork_choice() {
+                crit!(
+                    self.log,
+                    "Error persisting fork choice";
+                    "error" => ?e
+                );
+            }
+        } else if head_changed && persist_due {
+            self.spawn_persist_head_and_fork_choice();
+        }
+
         // If the finalized checkpoint changed, perform some updates.
         //
         // The `after_finalization` function will take a write-lock on `fork_choice`, therefore it
         // is a dead-lock risk to hold any other lock on fork choice at this point.
-        if new_view.finalized_checkpoint != old_view.finalized_checkpoint {
+        if finalization_changed {
             if let Err(e) =
                 self.after_finalization(&new_cached_head, new_view, finalized_proto_block)
             {
@@ -778,6 +826,30 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             }
         }
 
+        // Refresh the light client updates served via the HTTP API. This is done on a blocking
+        // task since it may need to load a state from disk, which shouldn't delay the update of
+        // the execution layer.
+        if let Some(old_head_snapshot) =
+            old_head_snapshot.filter(|_| head_changed || finalization_changed)
+        {
+            let chain = self.clone();
+            let new_cached_head = new_cached_head.clone();
+            self.task_executor.spawn_blocking(
+                move || {
+                    if let Err(e) =
+                        chain.recompute_light_client_updates(&new_cached_head, &old_head_snapshot)
+                    {
+                        debug!(
+                            chain.log,
+                            "Unable to compute light client updates";
+                            "error" => ?e
+                        );
+                    }
+                },
+                "recompute_light_client_updates",
+            );
+        }
+
         // The execution layer updates might attempt to take a write-lock on fork choice, so it's
         // important to ensure the fork-choice lock isn't being held.
         let el_update_handle =
@@ -898,10 +970,34 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         );
 
         if is_epoch_transition || reorg_distance.is_some() {
-            self.persist_head_and_fork_choice()?;
             self.op_pool.prune_attestations(self.epoch()?);
         }
 
+        // Recover operations which were only included in the blocks orphaned by a re-org. This
+        // loads blocks and re-verifies signatures, so it is done on a blocking task to avoid
+        // delaying the update of the execution layer.
+        if reorg_distance.map_or(false, |depth| depth <= MAX_REORG_OPERATION_REINSERT_DEPTH) {
+            let chain = self.clone();
+            let old_head_block_root = old_snapshot.beacon_block_root;
+            let new_snapshot = new_snapshot.clone();
+            self.task_executor.spawn_blocking(
+                move || {
+                    if let Err(e) = chain.reinsert_orphaned_operations(
+                        old_head_block_root,
+                        &new_snapshot.beacon_state,
+                    ) {
+                        warn!(
+                            chain.log,
+                            "Failed to re-insert orphaned operations";
+                            "error" => ?e,
+                            "old_head_block_root" => ?old_head_block_root,
+                        );
+                    }
+                },
+                "reinsert_orphaned_operations",
+            );
+        }
+
         // Register server-sent-events for a new head.
         if let Some(event_handler) = self
             .event_handler
@@ -1057,6 +1153,38 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Persist the head tracker and fork choice on a blocking task.
+    ///
+    /// If a task is already queued it will persist the latest fork choice when it runs, so no
+    /// further task is spawned.
+    fn spawn_persist_head_and_fork_choice(self: &Arc<Self>) {
+        if self
+            .canonical_head
+            .persist_pending
+            .swap(true, Ordering::AcqRel)
+        {
+            return;
+        }
+
+        let chain = self.clone();
+        self.task_executor.spawn_blocking(
+            move || {
+                chain
+                    .canonical_head
+                    .persist_pending
+                    .store(false, Ordering::Release);
+                if let Err(e) = chain.persist_head_and_fork_choice() {
+                    crit!(
+                        chain.log,
+                        "Error persisting fork choice";
+                        "error" => ?e
+                    );
+                }
+            },
+            "persist_head_and_fork_choice",
+        );
+    }
+
     /// Return a database operation for writing fork choice to disk.
     pub fn persist_fork_choice_in_batch(&self) -> KeyValueStoreOp {
         Self::persist_fork_choice_in_batch_standalone(&self.canonical_head.fork_choice_read_lock())
@@ -1264,6 +1392,10 @@ fn detect_reorg<E: EthSpec>(
             &metrics::FORK_CHOICE_REORG_DISTANCE,
             reorg_distance.as_u64() as i64,
         );
+        metrics::observe(
+            &metrics::FORK_CHOICE_REORG_DEPTH,
+            reorg_distance.as_u64() as f64,
+        );
         warn!(
             log,
             "Beacon chain re-org";
@@ -12,6 +12,9 @@ pub const DEFAULT_FORK_CHOICE_BEFORE_PROPOSAL_TIMEOUT: u64 = 250;
 /// Default fraction of a slot lookahead for payload preparation (12/3 = 4 seconds on mainnet).
 pub const DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR: u32 = 3;
 
+/// Default fraction of a slot lookahead for the state advance timer (12/4 = 3 seconds on mainnet).
+pub const DEFAULT_STATE_ADVANCE_LOOKAHEAD_FACTOR: u32 = 4;
+
 /// Fraction of a slot lookahead for fork choice in the state advance timer (500ms on mainnet).
 pub const FORK_CHOICE_LOOKAHEAD_FACTOR: u32 = 24;
 
@@ -61,6 +64,12 @@ pub struct ChainConfig {
     pub always_reset_payload_statuses: bool,
     /// Whether to apply paranoid checks to blocks proposed by this beacon node.
     pub paranoid_block_proposal: bool,
+    /// Whether to re-verify the proposer signature of blocks submitted via the HTTP API before
+    /// importing them, rather than trusting the check performed during gossip verification.
+    pub paranoid_block_import: bool,
+    /// Whether gossip blocks with an unknown parent should be handed to sync so that the missing
+    /// ancestors can be looked up. When `false` such blocks are ignored.
+    pub unknown_parent_lookup: bool,
     /// Optionally set timeout for calls to checkpoint sync endpoint.
     pub checkpoint_sync_url_timeout: u64,
     /// The offset before the start of a proposal slot at which payload attributes should be sent.
@@ -68,10 +77,17 @@ pub struct ChainConfig {
     /// Low values are useful for execution engines which don't improve their payload after the
     /// first call, and high values are useful for ensuring the EL is given ample notice.
     pub prepare_payload_lookahead: Duration,
+    /// The offset before the start of the next slot at which the head state is advanced.
+    ///
+    /// The state advance always runs before the fork choice lookahead, so values shorter than
+    /// `1 / FORK_CHOICE_LOOKAHEAD_FACTOR` of a slot are ignored.
+    pub state_advance_lookahead: Duration,
     /// Use EL-free optimistic sync for the finalized part of the chain.
     pub optimistic_finalized_sync: bool,
     /// The size of the shuffling cache,
     pub shuffling_cache_size: usize,
+    /// The maximum number of states held in the snapshot cache.
+    pub snapshot_cache_size: usize,
     /// If using a weak-subjectivity sync, whether we should download blocks all the way back to
     /// genesis.
     pub genesis_backfill: bool,
@@ -79,10 +95,17 @@ pub struct ChainConfig {
     ///
     /// This is useful for block builders and testing.
     pub always_prepare_payload: bool,
+    /// Whether fork choice should count the unrealized justification and finalization of blocks.
+    ///
+    /// Disabling this deviates from the fork choice specification and is only intended for
+    /// debugging and testing.
+    pub count_unrealized: bool,
     /// Whether to use `ProgressiveBalancesCache` in unrealized FFG progression calculation.
     pub progressive_balances_mode: ProgressiveBalancesMode,
     /// Number of epochs between each migration of data from the hot database to the freezer.
     pub epochs_per_migration: u64,
+    /// Whether to compute and cache light client updates after each change of head.
+    pub enable_light_client_server: bool,
 }
 
 impl Default for ChainConfig {
@@ -105,15 +128,21 @@ impl Default for ChainConfig {
             builder_fallback_disable_checks: false,
             always_reset_payload_statuses: false,
             paranoid_block_proposal: false,
+            paranoid_block_import: false,
+            unknown_parent_lookup: true,
             checkpoint_sync_url_timeout: 60,
             prepare_payload_lookahead: Duration::from_secs(4),
+            state_advance_lookahead: Duration::from_secs(3),
             // This value isn't actually read except in tests.
             optimistic_finalized_sync: true,
             shuffling_cache_size: crate::shuffling_cache::DEFAULT_CACHE_SIZE,
+            snapshot_cache_size: crate::snapshot_cache::DEFAULT_SNAPSHOT_CACHE_SIZE,
             genesis_backfill: false,
             always_prepare_payload: false,
+            count_unrealized: true,
             progressive_balances_mode: ProgressiveBalancesMode::Checked,
             epochs_per_migration: crate::migrate::DEFAULT_EPOCHS_PER_MIGRATION,
+            enable_light_client_server: false,
         }
     }
 }
@@ -155,6 +155,14 @@ impl<E: EthSpec> EarlyAttesterCache<E> {
             .map(|item| item.block.clone())
     }
 
+    /// Returns the root and block of the cached item, if any.
+    pub fn get_head_block(&self) -> Option<(Hash256, Arc<SignedBeaconBlock<E>>)> {
+        self.item
+            .read()
+            .as_ref()
+            .map(|item| (item.beacon_block_root, item.block.clone()))
+    }
+
     /// Returns the proto-array block, if `block_root` matches the cached item.
     pub fn get_proto_block(&self, block_root: Hash256) -> Option<ProtoBlock> {
         self.item
@@ -29,6 +29,7 @@ use state_processing::{
 use std::time::Duration;
 use task_executor::ShutdownReason;
 use tokio::task::JoinError;
+use types::light_client_update::Error as LightClientError;
 use types::*;
 
 macro_rules! easy_from_to {
@@ -148,6 +149,7 @@ pub enum BeaconChainError {
     EngineGetCapabilititesFailed(Box<execution_layer::Error>),
     ExecutionLayerGetBlockByNumberFailed(Box<execution_layer::Error>),
     ExecutionLayerGetBlockByHashFailed(Box<execution_layer::Error>),
+    RestoreProposerPreparationFailed(execution_layer::Error),
     BlockHashMissingFromExecutionLayer(ExecutionBlockHash),
     InconsistentPayloadReconstructed {
         slot: Slot,
@@ -168,6 +170,12 @@ pub enum BeaconChainError {
     BlockRewardSyncError,
     SyncCommitteeRewardsSyncError,
     AttestationRewardsError,
+    AttestationRewardsEpochIncomplete {
+        epoch: Epoch,
+        current_slot: Slot,
+    },
+    LightClientBootstrapPreAltair(Hash256),
+    LightClientError(LightClientError),
     HeadMissingFromForkChoice(Hash256),
     FinalizedBlockMissingFromForkChoice(Hash256),
     HeadBlockMissingFromForkChoice(Hash256),
@@ -211,6 +219,7 @@ pub enum BeaconChainError {
     AttestationHeadNotInForkChoice(Hash256),
     MissingPersistedForkChoice,
     CommitteePromiseFailed(oneshot_broadcast::Error),
+    CommitteePromiseTimeout(Duration),
     MaxCommitteePromises(usize),
     BlsToExecutionPriorToCapella,
     BlsToExecutionConflictsWithPool,
@@ -17,6 +17,7 @@ pub struct ServerSentEventHandler<T: EthSpec> {
     payload_attributes_tx: Sender<EventKind<T>>,
     late_head: Sender<EventKind<T>>,
     block_reward_tx: Sender<EventKind<T>>,
+    published_block_reward_tx: Sender<EventKind<T>>,
     log: Logger,
 }
 
@@ -39,6 +40,7 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         let (payload_attributes_tx, _) = broadcast::channel(capacity);
         let (late_head, _) = broadcast::channel(capacity);
         let (block_reward_tx, _) = broadcast::channel(capacity);
+        let (published_block_reward_tx, _) = broadcast::channel(capacity);
 
         Self {
             attestation_tx,
@@ -51,6 +53,7 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
             payload_attributes_tx,
             late_head,
             block_reward_tx,
+            published_block_reward_tx,
             log,
         }
     }
@@ -105,6 +108,10 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
                 .block_reward_tx
                 .send(kind)
                 .map(|count| log_count("block reward", count)),
+            EventKind::PublishedBlockReward(_) => self
+                .published_block_reward_tx
+                .send(kind)
+                .map(|count| log_count("published block reward", count)),
         };
         if let Err(SendError(event)) = result {
             trace!(self.log, "No receivers registered to listen for event"; "event" => ?event);
@@ -151,6 +158,10 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
         self.block_reward_tx.subscribe()
     }
 
+    pub fn subscribe_published_block_reward(&self) -> Receiver<EventKind<T>> {
+        self.published_block_reward_tx.subscribe()
+    }
+
     pub fn has_attestation_subscribers(&self) -> bool {
         self.attestation_tx.receiver_count() > 0
     }
@@ -190,4 +201,8 @@ impl<T: EthSpec> ServerSentEventHandler<T> {
     pub fn has_block_reward_subscribers(&self) -> bool {
         self.block_reward_tx.receiver_count() > 0
     }
+
+    pub fn has_published_block_reward_subscribers(&self) -> bool {
+        self.published_block_reward_tx.receiver_count() > 0
+    }
 }
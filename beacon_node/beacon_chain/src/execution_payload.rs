@@ -151,7 +151,12 @@ async fn notify_new_payload<'a, T: BeaconChainTypes>(
 
     match new_payload_response {
         Ok(status) => match status {
-            PayloadStatus::Valid => Ok(PayloadVerificationStatus::Verified),
+            PayloadStatus::Valid => {
+                // A valid payload implies that its ancestors are valid.
+                chain.invalid_payload_cache.remove(&block.tree_hash_root());
+                chain.invalid_payload_cache.remove(&block.parent_root());
+                Ok(PayloadVerificationStatus::Verified)
+            }
             PayloadStatus::Syncing | PayloadStatus::Accepted => {
                 Ok(PayloadVerificationStatus::Optimistic)
             }
@@ -172,6 +177,11 @@ async fn notify_new_payload<'a, T: BeaconChainTypes>(
                     "method" => "new_payload",
                 );
 
+                // Remember the block with the invalid payload so that its descendants are
+                // rejected without consulting the execution engine. Any ancestors invalidated by
+                // the `latest_valid_hash` are recorded by `process_invalid_execution_payload`.
+                chain.invalid_payload_cache.insert(block.tree_hash_root());
+
                 // Only trigger payload invalidation in fork choice if the
                 // `latest_valid_hash` is `Some` and non-zero.
                 //
@@ -221,6 +231,7 @@ async fn notify_new_payload<'a, T: BeaconChainTypes>(
                 // Returning an error here should be sufficient to invalidate the block. We have no
                 // information to indicate its parent is invalid, so no need to run
                 // `BeaconChain::process_invalid_execution_payload`.
+                chain.invalid_payload_cache.insert(block.tree_hash_root());
                 Err(ExecutionPayloadError::RejectedByExecutionEngine { status }.into())
             }
         },
@@ -0,0 +1,85 @@
+use lru::LruCache;
+use parking_lot::Mutex;
+use types::Hash256;
+
+const INVALID_PAYLOAD_CACHE_LIMIT: usize = 1_024;
+
+/// Cache for rejecting blocks which are, or descend from, blocks whose execution payloads an
+/// execution engine has declared invalid.
+///
+/// Blocks with an invalid payload are never imported to fork choice, so without this cache their
+/// descendants would be looked up and verified against the execution engine again and again.
+///
+/// Entries are keyed by *beacon block root* rather than execution block hash. The block hash in a
+/// payload is unverified until the execution engine has checked it, so keying by it would allow a
+/// peer to poison the cache for an honest payload by gossiping a block which claims its hash.
+#[derive(Default)]
+pub struct InvalidPayloadCache {
+    block_roots: Mutex<Cache>,
+}
+
+struct Cache(LruCache<Hash256, ()>);
+
+impl Default for Cache {
+    fn default() -> Self {
+        Cache(LruCache::new(INVALID_PAYLOAD_CACHE_LIMIT))
+    }
+}
+
+impl InvalidPayloadCache {
+    /// Record that the payload of the block with `block_root` is invalid.
+    pub fn insert(&self, block_root: Hash256) {
+        self.block_roots.lock().0.put(block_root, ());
+    }
+
+    /// Record that the payload of the block with `block_root` is valid, e.g. because its payload
+    /// was returned as the `latest_valid_hash` by an execution engine.
+    pub fn remove(&self, block_root: &Hash256) {
+        self.block_roots.lock().0.pop(block_root);
+    }
+
+    /// Returns `true` if the payload of the block with `block_root` is known to be invalid.
+    pub fn contains(&self, block_root: &Hash256) -> bool {
+        self.block_roots.lock().0.contains(block_root)
+    }
+
+    pub fn len(&self) -> usize {
+        self.block_roots.lock().0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(i: u64) -> Hash256 {
+        Hash256::from_low_u64_be(i)
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let cache = InvalidPayloadCache::default();
+        cache.insert(root(1));
+        assert!(cache.contains(&root(1)));
+        assert!(!cache.contains(&root(2)));
+
+        cache.remove(&root(1));
+        assert!(!cache.contains(&root(1)));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn bounded() {
+        let cache = InvalidPayloadCache::default();
+        for i in 0..INVALID_PAYLOAD_CACHE_LIMIT as u64 + 1 {
+            cache.insert(root(i + 1));
+        }
+        assert_eq!(cache.len(), INVALID_PAYLOAD_CACHE_LIMIT);
+        assert!(!cache.contains(&root(1)));
+        assert!(cache.contains(&root(INVALID_PAYLOAD_CACHE_LIMIT as u64 + 1)));
+    }
+}
@@ -7,6 +7,7 @@ mod beacon_chain;
 mod beacon_fork_choice_store;
 pub mod beacon_proposer_cache;
 mod beacon_snapshot;
+pub mod block_production_timings;
 pub mod block_reward;
 mod block_times_cache;
 mod block_verification;
@@ -24,8 +25,10 @@ pub mod fork_choice_signal;
 pub mod fork_revert;
 mod head_tracker;
 pub mod historical_blocks;
+pub mod invalid_payload_cache;
 pub mod light_client_finality_update_verification;
 pub mod light_client_optimistic_update_verification;
+mod light_client_server_cache;
 pub mod merge_readiness;
 pub mod metrics;
 pub mod migrate;
@@ -37,10 +40,12 @@ pub mod observed_operations;
 pub mod otb_verification_service;
 mod persisted_beacon_chain;
 mod persisted_fork_choice;
+mod persisted_proposer_preparation;
 mod pre_finalization_cache;
 pub mod proposer_prep_service;
 pub mod schema_change;
 pub mod shuffling_cache;
+pub mod slasher_slashings;
 mod snapshot_cache;
 pub mod state_advance_timer;
 pub mod sync_committee_rewards;
@@ -62,6 +67,7 @@ pub use self::errors::{BeaconChainError, BlockProductionError};
 pub use self::historical_blocks::HistoricalBlockError;
 pub use attestation_verification::Error as AttestationError;
 pub use beacon_fork_choice_store::{BeaconForkChoiceStore, Error as ForkChoiceStoreError};
+pub use block_production_timings::BlockProductionTimings;
 pub use block_verification::{
     get_block_root, BlockError, ExecutionPayloadError, GossipVerifiedBlock,
     IntoExecutionPendingBlock, IntoGossipVerifiedBlock,
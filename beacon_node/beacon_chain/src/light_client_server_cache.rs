@@ -0,0 +1,294 @@
+use crate::canonical_head::CachedHead;
+use crate::BeaconSnapshot;
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use lru::LruCache;
+use parking_lot::{Mutex, RwLock};
+use types::light_client_update::Error as LightClientError;
+use types::{
+    BeaconBlockHeader, BeaconState, EthSpec, ForkName, Hash256, LightClientBootstrap,
+    LightClientFinalityUpdate, LightClientOptimisticUpdate, LightClientUpdate, SignedBeaconBlock,
+    SignedBlindedBeaconBlock, SyncAggregate,
+};
+
+/// The maximum number of `LightClientBootstrap` objects to retain.
+///
+/// Light clients bootstrap from recent finalized checkpoints, so only a handful of distinct block
+/// roots are requested at any one time.
+const BOOTSTRAP_CACHE_SIZE: usize = 32;
+
+/// Caches light client objects served by the HTTP API.
+///
+/// Computing these objects may require loading (and replaying) historic states from disk, so
+/// repeated requests for the same data should be served from memory.
+pub struct LightClientServerCache<E: EthSpec> {
+    /// Bootstraps keyed by the root of the block they were computed for, alongside the fork at
+    /// that block's slot.
+    bootstraps: Mutex<LruCache<Hash256, (LightClientBootstrap<E>, ForkName)>>,
+    /// The best finality update computed from the canonical head.
+    latest_finality_update: RwLock<Option<LightClientFinalityUpdate<E>>>,
+    /// The best optimistic update computed from the canonical head.
+    latest_optimistic_update: RwLock<Option<LightClientOptimisticUpdate<E>>>,
+}
+
+impl<E: EthSpec> Default for LightClientServerCache<E> {
+    fn default() -> Self {
+        Self {
+            bootstraps: Mutex::new(LruCache::new(BOOTSTRAP_CACHE_SIZE)),
+            latest_finality_update: RwLock::new(None),
+            latest_optimistic_update: RwLock::new(None),
+        }
+    }
+}
+
+/// Returns `true` if an update attesting to `new_header` with `new_aggregate` should replace one
+/// attesting to `old_header` with `old_aggregate`.
+///
+/// Updates which attest to a later slot are always preferred. Amongst updates attesting to the
+/// same slot, the one with the greater sync committee participation is preferred.
+fn is_better_latest_update<E: EthSpec>(
+    new_header: &BeaconBlockHeader,
+    new_aggregate: &SyncAggregate<E>,
+    old_header: &BeaconBlockHeader,
+    old_aggregate: &SyncAggregate<E>,
+) -> bool {
+    new_header.slot > old_header.slot
+        || (new_header.slot == old_header.slot
+            && new_aggregate.num_set_bits() > old_aggregate.num_set_bits())
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Returns the `LightClientBootstrap` for the block with `block_root`, alongside the fork at
+    /// the block's slot.
+    ///
+    /// Returns `Ok(None)` if either the block or its post-state are not known to this node. An
+    /// error is returned if the block is from prior to the Altair fork, since those states do
+    /// not contain a sync committee.
+    pub fn get_light_client_bootstrap(
+        &self,
+        block_root: &Hash256,
+    ) -> Result<Option<(LightClientBootstrap<T::EthSpec>, ForkName)>, BeaconChainError> {
+        if let Some(cached) = self
+            .light_client_server_cache
+            .bootstraps
+            .lock()
+            .get(block_root)
+        {
+            return Ok(Some(cached.clone()));
+        }
+
+        let block = match self.get_blinded_block(block_root)? {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+
+        let fork_name = self.spec.fork_name_at_slot::<T::EthSpec>(block.slot());
+        if fork_name == ForkName::Base {
+            return Err(BeaconChainError::LightClientBootstrapPreAltair(*block_root));
+        }
+
+        let mut state = match self.get_state(&block.state_root(), Some(block.slot()))? {
+            Some(state) => state,
+            None => return Ok(None),
+        };
+
+        let bootstrap = LightClientBootstrap::from_beacon_state(&mut state)
+            .map_err(BeaconChainError::LightClientError)?;
+
+        self.light_client_server_cache
+            .bootstraps
+            .lock()
+            .put(*block_root, (bootstrap.clone(), fork_name));
+
+        Ok(Some((bootstrap, fork_name)))
+    }
+
+    /// Returns the latest `LightClientFinalityUpdate` computed from the canonical head.
+    pub fn get_latest_light_client_finality_update(
+        &self,
+    ) -> Option<LightClientFinalityUpdate<T::EthSpec>> {
+        self.light_client_server_cache
+            .latest_finality_update
+            .read()
+            .clone()
+    }
+
+    /// Returns the latest `LightClientOptimisticUpdate` computed from the canonical head.
+    pub fn get_latest_light_client_optimistic_update(
+        &self,
+    ) -> Option<LightClientOptimisticUpdate<T::EthSpec>> {
+        self.light_client_server_cache
+            .latest_optimistic_update
+            .read()
+            .clone()
+    }
+
+    /// Recompute the cached light client updates using the sync aggregate of the block at the
+    /// head of `cached_head`.
+    ///
+    /// The head block's sync aggregate signs over its parent, so the parent's post-state is used
+    /// as the attested state. The parent is usually the previous head, in which case its state is
+    /// taken from `old_head_snapshot` rather than loaded from disk. No updates are produced if the
+    /// sync aggregate does not meet the minimum participation required by the spec, or if the head
+    /// is prior to Altair.
+    pub(crate) fn recompute_light_client_updates(
+        &self,
+        cached_head: &CachedHead<T::EthSpec>,
+        old_head_snapshot: &BeaconSnapshot<T::EthSpec>,
+    ) -> Result<(), BeaconChainError> {
+        let head_block = &cached_head.snapshot.beacon_block;
+        let sync_aggregate = match head_block.message().body().sync_aggregate() {
+            Ok(sync_aggregate) => sync_aggregate,
+            // Blocks prior to Altair do not contain a sync aggregate.
+            Err(_) => return Ok(()),
+        };
+        if sync_aggregate.num_set_bits() < self.spec.min_sync_committee_participants as usize {
+            return Ok(());
+        }
+
+        let attested_block_root = head_block.parent_root();
+        let mut attested_state = if old_head_snapshot.beacon_block_root == attested_block_root {
+            old_head_snapshot.beacon_state.clone()
+        } else {
+            let attested_block = self
+                .get_blinded_block(&attested_block_root)?
+                .ok_or(BeaconChainError::MissingBeaconBlock(attested_block_root))?;
+            self.get_state(&attested_block.state_root(), Some(attested_block.slot()))?
+                .ok_or_else(|| BeaconChainError::MissingBeaconState(attested_block.state_root()))?
+        };
+
+        let optimistic_update =
+            match LightClientOptimisticUpdate::new(&self.spec, head_block, &attested_state) {
+                Ok(update) => update,
+                // The attested block is prior to Altair.
+                Err(LightClientError::AltairForkNotActive) => return Ok(()),
+                Err(e) => return Err(BeaconChainError::LightClientError(e)),
+            };
+        {
+            let mut latest = self
+                .light_client_server_cache
+                .latest_optimistic_update
+                .write();
+            let replace = match latest.as_ref() {
+                Some(current) => is_better_latest_update(
+                    &optimistic_update.attested_header,
+                    &optimistic_update.sync_aggregate,
+                    &current.attested_header,
+                    &current.sync_aggregate,
+                ),
+                None => true,
+            };
+            if replace {
+                *latest = Some(optimistic_update);
+            }
+        }
+
+        // A finality update can only be produced once the attested state has a finalized block
+        // other than genesis, which has a zero root in its checkpoint.
+        let finalized_block_root = attested_state.finalized_checkpoint().root;
+        let finalized_block = self.get_blinded_block(&finalized_block_root)?;
+
+        // The best update for the period is tracked regardless of whether a finality update can
+        // be produced, since updates without finality still carry the next sync committee.
+        self.persist_best_light_client_update(
+            head_block,
+            &mut attested_state,
+            finalized_block.as_ref(),
+        )?;
+
+        let finalized_block = match finalized_block {
+            Some(block) => block,
+            None => return Ok(()),
+        };
+
+        let finality_update = match LightClientFinalityUpdate::new(
+            &self.spec,
+            &cached_head.snapshot.beacon_state,
+            head_block,
+            &mut attested_state,
+            &finalized_block,
+        ) {
+            Ok(update) => update,
+            // The head and attested states disagree about finality (i.e. the head block
+            // finalized a new checkpoint). The next head will yield a consistent update.
+            Err(LightClientError::InvalidFinalizedBlock) => return Ok(()),
+            Err(e) => return Err(BeaconChainError::LightClientError(e)),
+        };
+        {
+            let mut latest = self
+                .light_client_server_cache
+                .latest_finality_update
+                .write();
+            let replace = match latest.as_ref() {
+                Some(current) => {
+                    finality_update.finalized_header.slot > current.finalized_header.slot
+                        || (finality_update.finalized_header.slot == current.finalized_header.slot
+                            && is_better_latest_update(
+                                &finality_update.attested_header,
+                                &finality_update.sync_aggregate,
+                                &current.attested_header,
+                                &current.sync_aggregate,
+                            ))
+                }
+                None => true,
+            };
+            if replace {
+                *latest = Some(finality_update);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Store the `LightClientUpdate` derived from `block` if it is better than the best update
+    /// already stored for its sync committee period.
+    fn persist_best_light_client_update(
+        &self,
+        block: &SignedBeaconBlock<T::EthSpec>,
+        attested_state: &mut BeaconState<T::EthSpec>,
+        finalized_block: Option<&SignedBlindedBeaconBlock<T::EthSpec>>,
+    ) -> Result<(), BeaconChainError> {
+        let update =
+            match LightClientUpdate::new(&self.spec, block, attested_state, finalized_block) {
+                Ok(update) => update,
+                // The sync aggregate was signed in the period after the attested block, so it
+                // cannot attest to the attested block's next sync committee.
+                Err(LightClientError::MismatchingPeriods) => return Ok(()),
+                Err(e) => return Err(BeaconChainError::LightClientError(e)),
+            };
+        let period = update
+            .attested_header
+            .slot
+            .epoch(T::EthSpec::slots_per_epoch())
+            .sync_committee_period(&self.spec)?;
+
+        let is_best = match self.store.get_light_client_update(period)? {
+            Some(best) => update
+                .is_better_update(&best, &self.spec)
+                .map_err(BeaconChainError::LightClientError)?,
+            None => true,
+        };
+        if is_best {
+            self.store.put_light_client_update(period, &update)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the best stored `LightClientUpdate` for each of the `count` sync committee periods
+    /// starting at `start_period`.
+    ///
+    /// Periods for which no update is stored are omitted.
+    pub fn get_light_client_updates(
+        &self,
+        start_period: u64,
+        count: u64,
+    ) -> Result<Vec<LightClientUpdate<T::EthSpec>>, BeaconChainError> {
+        let mut updates = vec![];
+        for period in start_period..start_period.saturating_add(count) {
+            if let Some(update) = self.store.get_light_client_update(period)? {
+                updates.push(update);
+            }
+        }
+        Ok(updates)
+    }
+}
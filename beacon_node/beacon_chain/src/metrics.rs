@@ -109,6 +109,26 @@ lazy_static! {
         "Time taken for fork choice to compute the proposer head before block production",
         exponential_buckets(1e-3, 2.0, 8)
     );
+    pub static ref BLOCK_PRODUCTION_RE_ORG_ATTEMPTS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_production_re_org_attempts_total",
+        "Count of block proposals which considered re-orging a late head"
+    );
+    pub static ref BLOCK_PRODUCTION_RE_ORG_SUCCESSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_production_re_org_successes_total",
+        "Count of block proposals built upon the parent of a weak head"
+    );
+    pub static ref BLOCK_PRODUCTION_RE_ORG_ABORTS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_production_re_org_aborts_total",
+        "Count of block proposals which considered and then declined to re-org a late head"
+    );
+    pub static ref BLOCK_PRODUCTION_ADVANCED_STATE_HITS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_production_advanced_state_hits_total",
+        "Count of block proposals which used a head state advanced by the state advance timer"
+    );
+    pub static ref BLOCK_PRODUCTION_ADVANCED_STATE_MISSES: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_production_advanced_state_misses_total",
+        "Count of block proposals atop the head which had to advance the head state themselves"
+    );
     pub static ref BLOCK_PRODUCTION_STATE_LOAD_TIMES: Result<Histogram> = try_create_histogram(
         "beacon_block_production_state_load_seconds",
         "Time taken to load the base state for block production"
@@ -125,6 +145,10 @@ lazy_static! {
         "beacon_block_production_attestation_seconds",
         "Time taken to pack attestations into a block"
     );
+    pub static ref BLOCK_PRODUCTION_PAYLOAD_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_block_production_payload_seconds",
+        "Time spent waiting for the execution payload after packing the block"
+    );
     pub static ref BLOCK_PRODUCTION_PROCESS_TIMES: Result<Histogram> = try_create_histogram(
         "beacon_block_production_process_seconds",
         "Time taken to process the block produced"
@@ -189,6 +213,10 @@ lazy_static! {
         "beacon_attestation_processing_apply_to_agg_pool",
         "Time spent applying an attestation to the naive aggregation pool"
     );
+    pub static ref ATTESTATION_PROCESSING_AGG_POOL_FULL: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_processing_agg_pool_full_total",
+        "Count of attestations not stored because the agg pool reached its limit for their slot"
+    );
     pub static ref ATTESTATION_PROCESSING_AGG_POOL_PRUNE: Result<Histogram> = try_create_histogram(
         "beacon_attestation_processing_agg_pool_prune",
         "Time spent for the agg pool to prune"
@@ -261,6 +289,26 @@ lazy_static! {
         "beacon_attestation_processing_batch_unagg_signature_times",
         "Time spent on the signature verification of batch unaggregate attestation processing"
     );
+    pub static ref ATTESTATION_PROCESSING_BATCH_AGG_SIZE: Result<Histogram> = try_create_histogram_with_buckets(
+        "beacon_attestation_processing_batch_agg_size",
+        "Number of aggregates which had their signatures verified in a single batch",
+        exponential_buckets(1.0, 2.0, 8)
+    );
+    pub static ref ATTESTATION_PROCESSING_BATCH_UNAGG_SIZE: Result<Histogram> = try_create_histogram_with_buckets(
+        "beacon_attestation_processing_batch_unagg_size",
+        "Number of unaggregated attestations which had their signatures verified in a single batch",
+        exponential_buckets(1.0, 2.0, 8)
+    );
+    pub static ref ATTESTATION_PROCESSING_BATCH_AGG_FALLBACKS: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_processing_batch_agg_fallbacks_total",
+        "Count of aggregate batches which failed batch signature verification and fell back to \
+        individual verification"
+    );
+    pub static ref ATTESTATION_PROCESSING_BATCH_UNAGG_FALLBACKS: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_processing_batch_unagg_fallbacks_total",
+        "Count of unaggregated attestation batches which failed batch signature verification and \
+        fell back to individual verification"
+    );
 
     /*
      * Shuffling cache
@@ -273,6 +321,10 @@ lazy_static! {
         try_create_int_counter("beacon_shuffling_cache_promise_hits_total", "Count of times shuffling cache returns a promise to future shuffling");
     pub static ref SHUFFLING_CACHE_PROMISE_FAILS: Result<IntCounter> =
         try_create_int_counter("beacon_shuffling_cache_promise_fails_total", "Count of times shuffling cache detects a failed promise");
+    pub static ref SHUFFLING_CACHE_PROMISE_TIMEOUTS: Result<IntCounter> =
+        try_create_int_counter("beacon_shuffling_cache_promise_timeouts_total", "Count of times a thread gave up waiting for a shuffling cache promise");
+    pub static ref SHUFFLING_CACHE_PROMISE_WAIT_TIMES: Result<Histogram> =
+        try_create_histogram("beacon_shuffling_cache_promise_wait_seconds", "Time spent waiting for a shuffling cache promise to be fulfilled");
 
     /*
      * Early attester cache
@@ -282,6 +334,14 @@ lazy_static! {
         "Count of times the early attester cache returns an attestation"
     );
 
+    /*
+     * State advance timer
+     */
+    pub static ref STATE_ADVANCE_SLOT_PROCESSING_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_state_advance_slot_processing_seconds",
+        "Time spent advancing the head state into the next slot ahead of time"
+    );
+
     /*
      * Attestation Production
      */
@@ -328,6 +388,11 @@ lazy_static! {
         "beacon_fork_choice_reorg_distance",
         "The distance of each re-org of the fork choice algorithm"
     );
+    pub static ref FORK_CHOICE_REORG_DEPTH: Result<Histogram> = try_create_histogram_with_buckets(
+        "beacon_fork_choice_reorg_depth",
+        "Histogram of the depths of re-orgs of the fork choice algorithm",
+        Ok(vec![1.0, 2.0, 3.0, 4.0, 8.0, 16.0, 32.0, 64.0])
+    );
     pub static ref FORK_CHOICE_REORG_COUNT_INTEROP: Result<IntCounter> = try_create_int_counter(
         "beacon_reorgs_total",
         "Count of occasions fork choice has switched to a different chain"
@@ -381,6 +446,22 @@ lazy_static! {
     pub static ref PERSIST_FORK_CHOICE: Result<Histogram> =
         try_create_histogram("beacon_persist_fork_choice", "Time taken to persist the fork choice struct");
 
+    /*
+     * Database migration
+     */
+    pub static ref STORE_MIGRATION_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_store_migration_seconds",
+        "Time taken to prune abandoned forks and migrate finalized states to the freezer database"
+    );
+    pub static ref STORE_MIGRATION_LAG: Result<IntGauge> = try_create_int_gauge(
+        "beacon_store_migration_lag_epochs",
+        "Number of finalized epochs which have not yet been migrated to the freezer database"
+    );
+    pub static ref STORE_MIGRATION_COALESCED_NOTIFICATIONS: Result<IntCounter> = try_create_int_counter(
+        "beacon_store_migration_coalesced_notifications_total",
+        "Count of finalization notifications superseded before the migrator could process them"
+    );
+
     /*
      * Eth1
      */
@@ -596,6 +677,13 @@ lazy_static! {
             "The attestation inclusion distance calculated during per epoch processing",
             &["validator"]
         );
+    pub static ref VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_INCLUSION_DISTANCE_DISTRIBUTION: Result<Histogram> =
+        try_create_histogram_with_buckets(
+            "validator_monitor_prev_epoch_on_chain_inclusion_distance_distribution",
+            "The distribution of attestation inclusion distances across all monitored validators, \
+            calculated during per epoch processing",
+            Ok(linear_buckets(1.0, 1.0, 32))
+        );
     pub static ref VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATIONS_TOTAL: Result<IntGaugeVec> =
         try_create_int_gauge_vec(
             "validator_monitor_prev_epoch_attestations_total",
@@ -803,6 +891,11 @@ lazy_static! {
         "Number of beacon exits seen",
         &["src", "validator"]
     );
+    pub static ref VALIDATOR_MONITOR_FEE_RECIPIENT_MISMATCH_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "validator_monitor_fee_recipient_mismatch_total",
+        "Number of blocks published for the validator whose payload pays a fee recipient other than the expected one",
+        &["src", "validator"]
+    );
     pub static ref VALIDATOR_MONITOR_PROPOSER_SLASHING_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
         "validator_monitor_proposer_slashing_total",
         "Number of proposer slashings seen",
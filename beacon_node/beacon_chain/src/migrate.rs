@@ -1,14 +1,16 @@
 use crate::beacon_chain::BEACON_CHAIN_DB_KEY;
 use crate::errors::BeaconChainError;
 use crate::head_tracker::{HeadTracker, SszHeadTracker};
+use crate::metrics;
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use parking_lot::Mutex;
 use slog::{debug, error, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::mem;
-use std::sync::{mpsc, Arc};
+use std::sync::mpsc::{self, RecvError, TryRecvError};
+use std::sync::Arc;
 use std::thread;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use store::hot_cold_store::{migrate_database, HotColdDBError};
 use store::iter::RootsIterator;
 use store::{Error, ItemStore, StoreItem, StoreOp};
@@ -28,12 +30,31 @@ const COMPACTION_FINALITY_DISTANCE: u64 = 1024;
 /// Default number of epochs to wait between finalization migrations.
 pub const DEFAULT_EPOCHS_PER_MIGRATION: u64 = 1;
 
+/// Number of slots to replay in each batch of historic state reconstruction.
+///
+/// Between batches the migrator thread services any pending finalization notifications, so that
+/// a long reconstruction does not hold up the hot database migration.
+const SLOTS_PER_RECONSTRUCTION_BATCH: usize = 8192;
+
+/// Number of restore points to create in each batch of a restore point migration.
+const RESTORE_POINTS_PER_MIGRATION_BATCH: usize = 4;
+
+/// Maximum time to wait for the migrator thread to finish its current batch of work on drop.
+///
+/// If the thread is still busy after this long it is left to finish in the background.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// The background migrator runs a thread to perform pruning and migrate state from the hot
 /// to the cold database.
 pub struct BackgroundMigrator<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     db: Arc<HotColdDB<E, Hot, Cold>>,
     /// Record of when the last migration ran, for enforcing `epochs_per_migration`.
     prev_migration: Arc<Mutex<PrevMigration>>,
+    /// The latest finalization notification which the background thread has yet to pick up.
+    ///
+    /// Finalization notifications received while a migration is running are coalesced here rather
+    /// than queued, so that at most one migration is ever outstanding.
+    pending_finalization: Arc<Mutex<Option<FinalizationNotification>>>,
     #[allow(clippy::type_complexity)]
     tx_thread: Option<Mutex<(mpsc::Sender<Notification>, thread::JoinHandle<()>)>>,
     /// Genesis block root, for persisting the `PersistedBeaconChain`.
@@ -115,8 +136,12 @@ pub enum PruningError {
 
 /// Message sent to the migration thread containing the information it needs to run.
 pub enum Notification {
-    Finalization(FinalizationNotification),
+    /// A finalization notification is waiting in the migrator's `pending_finalization`.
+    Finalization,
     Reconstruction,
+    RestorePointMigration,
+    /// The given number of states were garbage collected, and the database may need compacting.
+    GarbageCollectionCompaction(usize),
 }
 
 pub struct FinalizationNotification {
@@ -140,15 +165,21 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             epoch: db.get_split_slot().epoch(E::slots_per_epoch()),
             epochs_per_migration: config.epochs_per_migration,
         }));
+        let pending_finalization = Arc::new(Mutex::new(None));
         let tx_thread = if config.blocking {
             None
         } else {
-            Some(Mutex::new(Self::spawn_thread(db.clone(), log.clone())))
+            Some(Mutex::new(Self::spawn_thread(
+                db.clone(),
+                pending_finalization.clone(),
+                log.clone(),
+            )))
         };
         Self {
             db,
             tx_thread,
             prev_migration,
+            pending_finalization,
             genesis_block_root,
             log,
         }
@@ -173,11 +204,30 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             genesis_block_root: self.genesis_block_root,
         };
 
-        // Send to background thread if configured, otherwise run in foreground.
-        if let Some(Notification::Finalization(notif)) =
-            self.send_background_notification(Notification::Finalization(notif))
-        {
+        // Run in the foreground if there's no background thread.
+        if self.tx_thread.is_none() {
             Self::run_migration(self.db.clone(), notif, &self.log);
+            return Ok(());
+        }
+
+        // Otherwise replace any notification which the background thread has yet to pick up,
+        // only waking the thread if there was no such notification.
+        let mut pending_finalization = self.pending_finalization.lock();
+        let wake_thread = match pending_finalization.as_ref() {
+            None => true,
+            Some(pending) => {
+                metrics::inc_counter(&metrics::STORE_MIGRATION_COALESCED_NOTIFICATIONS);
+                if pending.finalized_checkpoint.epoch > notif.finalized_checkpoint.epoch {
+                    return Ok(());
+                }
+                false
+            }
+        };
+        *pending_finalization = Some(notif);
+        drop(pending_finalization);
+
+        if wake_thread {
+            let _ = self.send_background_notification(Notification::Finalization);
         }
 
         Ok(())
@@ -187,18 +237,90 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         if let Some(Notification::Reconstruction) =
             self.send_background_notification(Notification::Reconstruction)
         {
-            Self::run_reconstruction(self.db.clone(), &self.log);
+            Self::run_reconstruction(self.db.clone(), false, &self.log);
+        }
+    }
+
+    pub fn process_restore_point_migration(&self) {
+        if let Some(Notification::RestorePointMigration) =
+            self.send_background_notification(Notification::RestorePointMigration)
+        {
+            Self::run_restore_point_migration(self.db.clone(), false, &self.log);
+        }
+    }
+
+    pub fn process_garbage_collection_compaction(&self, num_deleted: usize) {
+        if let Some(Notification::GarbageCollectionCompaction(num_deleted)) = self
+            .send_background_notification(Notification::GarbageCollectionCompaction(num_deleted))
+        {
+            Self::run_garbage_collection_compaction(&self.db, num_deleted, &self.log);
+        }
+    }
+
+    /// Compact the database after `num_deleted` states were garbage collected, if worthwhile.
+    fn run_garbage_collection_compaction(
+        db: &HotColdDB<E, Hot, Cold>,
+        num_deleted: usize,
+        log: &Logger,
+    ) {
+        if let Err(e) = db.compact_after_garbage_collection(num_deleted) {
+            error!(
+                log,
+                "Database compaction failed";
+                "error" => ?e,
+            );
+        }
+    }
+
+    /// Run a migration of the freezer database to a new restore point spacing.
+    ///
+    /// If `batched` is true then only a single batch of restore points is created. Otherwise the
+    /// migration runs to completion on the current thread.
+    ///
+    /// Return `true` if another batch should be run to continue the migration.
+    pub fn run_restore_point_migration(
+        db: Arc<HotColdDB<E, Hot, Cold>>,
+        batched: bool,
+        log: &Logger,
+    ) -> bool {
+        let num_restore_points = batched.then_some(RESTORE_POINTS_PER_MIGRATION_BATCH);
+        if let Err(e) = db.migrate_restore_points(num_restore_points) {
+            error!(
+                log,
+                "Restore point migration failed";
+                "error" => ?e,
+            );
+            return false;
         }
+
+        batched && db.restore_point_migration_in_progress()
     }
 
-    pub fn run_reconstruction(db: Arc<HotColdDB<E, Hot, Cold>>, log: &Logger) {
-        if let Err(e) = db.reconstruct_historic_states() {
+    /// Run state reconstruction.
+    ///
+    /// If `batched` is true then only a single batch of reconstruction is performed. Otherwise
+    /// reconstruction runs to completion on the current thread.
+    ///
+    /// Return `true` if another batch should be run to continue reconstruction.
+    pub fn run_reconstruction(
+        db: Arc<HotColdDB<E, Hot, Cold>>,
+        batched: bool,
+        log: &Logger,
+    ) -> bool {
+        let num_slots = batched.then_some(SLOTS_PER_RECONSTRUCTION_BATCH);
+        if let Err(e) = db.reconstruct_historic_states(num_slots) {
             error!(
                 log,
                 "State reconstruction failed";
                 "error" => ?e,
             );
+            return false;
         }
+
+        batched
+            && db
+                .get_anchor_info()
+                .map_or(false, |anchor| !anchor.all_historic_states_stored())
     }
 
     /// If configured to run in the background, send `notif` to the background thread.
@@ -212,7 +334,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
 
             // Restart the background thread if it has crashed.
             if let Err(tx_err) = tx.send(notif) {
-                let (new_tx, new_thread) = Self::spawn_thread(self.db.clone(), self.log.clone());
+                let (new_tx, new_thread) = Self::spawn_thread(
+                    self.db.clone(),
+                    self.pending_finalization.clone(),
+                    self.log.clone(),
+                );
 
                 *tx = new_tx;
                 let old_thread = mem::replace(thread, new_thread);
@@ -245,6 +371,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
     ) {
         // Do not run too frequently.
         let epoch = notif.finalized_checkpoint.epoch;
+        Self::update_migration_lag(&db, epoch);
         let mut prev_migration = notif.prev_migration.lock();
         if epoch < prev_migration.epoch + prev_migration.epochs_per_migration {
             debug!(
@@ -264,6 +391,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         drop(prev_migration);
 
         debug!(log, "Database consolidation started");
+        let _timer = metrics::start_timer(&metrics::STORE_MIGRATION_TIMES);
 
         let finalized_state_root = notif.finalized_state_root;
         let finalized_block_root = notif.finalized_checkpoint.root;
@@ -343,6 +471,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                 return;
             }
         };
+        Self::update_migration_lag(&db, epoch);
 
         // Finally, compact the database so that new free space is properly reclaimed.
         if let Err(e) = Self::run_compaction(
@@ -357,39 +486,83 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         debug!(log, "Database consolidation complete");
     }
 
+    /// Record the number of epochs between the split and the `finalized_epoch`.
+    fn update_migration_lag(db: &HotColdDB<E, Hot, Cold>, finalized_epoch: Epoch) {
+        let split_epoch = db.get_split_slot().epoch(E::slots_per_epoch());
+        metrics::set_gauge(
+            &metrics::STORE_MIGRATION_LAG,
+            finalized_epoch.saturating_sub(split_epoch).as_u64() as i64,
+        );
+    }
+
     /// Spawn a new child thread to run the migration process.
     ///
     /// Return a channel handle for sending requests to the thread.
     fn spawn_thread(
         db: Arc<HotColdDB<E, Hot, Cold>>,
+        pending_finalization: Arc<Mutex<Option<FinalizationNotification>>>,
         log: Logger,
     ) -> (mpsc::Sender<Notification>, thread::JoinHandle<()>) {
         let (tx, rx) = mpsc::channel();
         let thread = thread::spawn(move || {
-            while let Ok(notif) = rx.recv() {
-                // Read the rest of the messages in the channel, preferring any reconstruction
-                // notification, or the finalization notification with the greatest finalized epoch.
-                let notif =
-                    rx.try_iter()
-                        .fold(notif, |best, other: Notification| match (&best, &other) {
-                            (Notification::Reconstruction, _)
-                            | (_, Notification::Reconstruction) => Notification::Reconstruction,
-                            (
-                                Notification::Finalization(fin1),
-                                Notification::Finalization(fin2),
-                            ) => {
-                                if fin2.finalized_checkpoint.epoch > fin1.finalized_checkpoint.epoch
-                                {
-                                    other
-                                } else {
-                                    best
-                                }
-                            }
-                        });
+            // Reconstruction and restore point migration run in batches. Their progress is tracked
+            // here rather than by re-queuing notifications, so that the thread only holds the
+            // receiver and exits as soon as every sender is dropped.
+            let mut reconstruction_pending = false;
+            let mut restore_point_migration_pending = false;
+            let mut garbage_collected = 0;
+
+            loop {
+                // Reconstruction is deferred until any restore point migration completes, as the
+                // migration only creates restore points for states which are already available.
+                let batch_ready = restore_point_migration_pending
+                    || (reconstruction_pending && !db.restore_point_migration_in_progress());
+
+                // Only block waiting for a notification if there is no batch to run.
+                let notif = if batch_ready {
+                    match rx.try_recv() {
+                        Ok(notif) => Some(notif),
+                        Err(TryRecvError::Empty) => None,
+                        Err(TryRecvError::Disconnected) => break,
+                    }
+                } else {
+                    match rx.recv() {
+                        Ok(notif) => Some(notif),
+                        Err(RecvError) => break,
+                    }
+                };
+
+                // Read the rest of the messages in the channel. The finalization notification
+                // itself is taken from `pending_finalization`, which holds only the latest one.
+                for notif in notif.into_iter().chain(rx.try_iter()) {
+                    match notif {
+                        Notification::Reconstruction => reconstruction_pending = true,
+                        Notification::RestorePointMigration => {
+                            restore_point_migration_pending = true
+                        }
+                        Notification::GarbageCollectionCompaction(num_deleted) => {
+                            garbage_collected += num_deleted
+                        }
+                        Notification::Finalization => (),
+                    }
+                }
 
-                match notif {
-                    Notification::Reconstruction => Self::run_reconstruction(db.clone(), &log),
-                    Notification::Finalization(fin) => Self::run_migration(db.clone(), fin, &log),
+                // Run finalization first so that a long-running reconstruction cannot delay the
+                // migration of the hot database.
+                let finalization_notif = pending_finalization.lock().take();
+                if let Some(fin) = finalization_notif {
+                    Self::run_migration(db.clone(), fin, &log);
+                }
+                if garbage_collected > 0 {
+                    Self::run_garbage_collection_compaction(&db, garbage_collected, &log);
+                    garbage_collected = 0;
+                }
+                if restore_point_migration_pending {
+                    restore_point_migration_pending =
+                        Self::run_restore_point_migration(db.clone(), true, &log);
+                }
+                if reconstruction_pending && !db.restore_point_migration_in_progress() {
+                    reconstruction_pending = Self::run_reconstruction(db.clone(), true, &log);
                 }
             }
         });
@@ -611,6 +784,14 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             }
         }
 
+        // The canonical head isn't known here, so retain the one last persisted by the chain. It is
+        // read before locking the head tracker to avoid holding the lock during a database read.
+        let canonical_head_block_root = store
+            .get_item::<PersistedBeaconChain>(&BEACON_CHAIN_DB_KEY)?
+            .map_or(DUMMY_CANONICAL_HEAD_BLOCK_ROOT, |persisted| {
+                persisted.canonical_head_block_root
+            });
+
         // Update the head tracker before the database, so that we maintain the invariant
         // that a block present in the head tracker is present in the database.
         // See https://github.com/sigp/lighthouse/issues/1557
@@ -651,7 +832,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         // Persist the head in case the process is killed or crashes here. This prevents
         // the head tracker reverting after our mutation above.
         let persisted_head = PersistedBeaconChain {
-            _canonical_head_block_root: DUMMY_CANONICAL_HEAD_BLOCK_ROOT,
+            canonical_head_block_root,
             genesis_block_root,
             ssz_head_tracker: SszHeadTracker::from_map(&head_tracker_lock),
         };
@@ -714,3 +895,151 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         Ok(())
     }
 }
+
+impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> Drop for BackgroundMigrator<E, Hot, Cold> {
+    /// Signal the background thread to stop by dropping its sender, and wait up to
+    /// `SHUTDOWN_TIMEOUT` for it to finish its current batch of work.
+    fn drop(&mut self) {
+        if let Some(tx_thread) = self.tx_thread.take() {
+            let (tx, thread) = tx_thread.into_inner();
+            drop(tx);
+            join_with_timeout(thread, SHUTDOWN_TIMEOUT, &self.log);
+        }
+    }
+}
+
+/// Join `thread` if it finishes within `timeout`, otherwise leave it detached.
+///
+/// Return `true` if the thread was joined.
+fn join_with_timeout(thread: thread::JoinHandle<()>, timeout: Duration, log: &Logger) -> bool {
+    let start = Instant::now();
+    while !thread.is_finished() {
+        if start.elapsed() >= timeout {
+            warn!(
+                log,
+                "Migration thread did not stop in time";
+                "info" => "the current batch of work will continue in the background",
+                "timeout_secs" => timeout.as_secs()
+            );
+            return false;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    if let Err(e) = thread.join() {
+        warn!(
+            log,
+            "Migration thread panicked";
+            "reason" => format!("{:?}", e)
+        );
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging::test_logger;
+    use types::{ChainSpec, MinimalEthSpec};
+
+    type Migrator = BackgroundMigrator<
+        MinimalEthSpec,
+        MemoryStore<MinimalEthSpec>,
+        MemoryStore<MinimalEthSpec>,
+    >;
+
+    fn wait_for(condition: impl Fn() -> bool) {
+        let start = Instant::now();
+        while !condition() {
+            assert!(start.elapsed() < Duration::from_secs(10), "timed out");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn drop_joins_idle_thread() {
+        let db = Arc::new(
+            HotColdDB::open_ephemeral(<_>::default(), ChainSpec::minimal(), test_logger()).unwrap(),
+        );
+        let migrator = Migrator::new(
+            db.clone(),
+            MigratorConfig::default(),
+            Hash256::zero(),
+            test_logger(),
+        );
+        drop(migrator);
+
+        // The thread has exited and released its handle on the database.
+        assert_eq!(Arc::strong_count(&db), 1);
+    }
+
+    #[test]
+    fn join_with_timeout_detaches_busy_thread() {
+        let (tx, rx) = mpsc::channel::<()>();
+        let thread = thread::spawn(move || {
+            let _ = rx.recv();
+        });
+
+        let start = Instant::now();
+        assert!(!join_with_timeout(
+            thread,
+            Duration::from_millis(50),
+            &test_logger()
+        ));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // Let the detached thread finish.
+        drop(tx);
+    }
+
+    #[test]
+    fn finalization_notifications_coalesce_while_migrating() {
+        let db = Arc::new(
+            HotColdDB::open_ephemeral(<_>::default(), ChainSpec::minimal(), test_logger()).unwrap(),
+        );
+        let migrator = Migrator::new(
+            db,
+            MigratorConfig::default(),
+            Hash256::zero(),
+            test_logger(),
+        );
+        let finalize = |epoch: u64| {
+            let checkpoint = Checkpoint {
+                epoch: Epoch::new(epoch),
+                root: Hash256::zero(),
+            };
+            migrator
+                .process_finalization(Hash256::zero().into(), checkpoint, <_>::default())
+                .unwrap();
+        };
+        let pending_epoch = || {
+            migrator
+                .pending_finalization
+                .lock()
+                .as_ref()
+                .map(|notif| notif.finalized_checkpoint.epoch.as_u64())
+        };
+        let coalesced = || {
+            metrics::STORE_MIGRATION_COALESCED_NOTIFICATIONS
+                .as_ref()
+                .map_or(0, |counter| counter.get())
+        };
+
+        // Stall the migration thread once it has picked up the first notification.
+        let prev_migration = migrator.prev_migration.lock();
+        finalize(1);
+        wait_for(|| pending_epoch().is_none());
+
+        // Notifications sent meanwhile replace each other, but are never replaced by older ones.
+        let coalesced_before = coalesced();
+        finalize(3);
+        finalize(4);
+        finalize(2);
+        assert_eq!(pending_epoch(), Some(4));
+        assert_eq!(coalesced(), coalesced_before + 2);
+
+        // Only the latest notification is processed once the thread resumes.
+        drop(prev_migration);
+        wait_for(|| migrator.prev_migration.lock().epoch == Epoch::new(4));
+        assert_eq!(pending_epoch(), None);
+    }
+}
@@ -154,6 +154,38 @@ impl<E: EthSpec> ObservedBlockProducers<E> {
         }
     }
 
+    /// Returns the roots of all blocks other than `block_root` which have been observed from
+    /// `block.proposer_index` at `block.slot`. Does not update the cache.
+    ///
+    /// ## Errors
+    ///
+    /// - `block.proposer_index` is greater than `VALIDATOR_REGISTRY_LIMIT`.
+    /// - `block.slot` is equal to or less than the latest pruned `finalized_slot`.
+    pub fn conflicting_block_roots(
+        &self,
+        block: BeaconBlockRef<'_, E>,
+        block_root: Hash256,
+    ) -> Result<Vec<Hash256>, Error> {
+        self.sanitize_block(block)?;
+
+        let key = ProposalKey {
+            slot: block.slot(),
+            proposer: block.proposer_index(),
+        };
+
+        Ok(self
+            .items
+            .get(&key)
+            .map(|block_roots| {
+                block_roots
+                    .iter()
+                    .filter(|root| **root != block_root)
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
     /// Returns `Ok(())` if the given `block` is sane.
     fn sanitize_block(&self, block: BeaconBlockRef<'_, E>) -> Result<(), Error> {
         if block.proposer_index() >= E::ValidatorRegistryLimit::to_u64() {
@@ -210,6 +242,59 @@ mod tests {
         block
     }
 
+    #[test]
+    fn conflicting_block_roots() {
+        let mut cache = ObservedBlockProducers::default();
+
+        let block_a = get_block(1, 0);
+        let root_a = block_a.canonical_root();
+        let mut block_b = get_block(1, 0);
+        *block_b.state_root_mut() = Hash256::repeat_byte(1);
+        let root_b = block_b.canonical_root();
+
+        assert_eq!(
+            cache.conflicting_block_roots(block_a.to_ref(), root_a),
+            Ok(vec![]),
+            "no conflicts before any observations"
+        );
+
+        cache
+            .observe_proposal(root_a, block_a.to_ref())
+            .expect("can observe block a");
+
+        assert_eq!(
+            cache.conflicting_block_roots(block_a.to_ref(), root_a),
+            Ok(vec![]),
+            "a block does not conflict with itself"
+        );
+        assert_eq!(
+            cache.conflicting_block_roots(block_b.to_ref(), root_b),
+            Ok(vec![root_a]),
+            "block b conflicts with block a"
+        );
+        assert_eq!(
+            cache.conflicting_block_roots(get_block(1, 1).to_ref(), root_b),
+            Ok(vec![]),
+            "a different proposer does not conflict"
+        );
+        assert_eq!(
+            cache.conflicting_block_roots(get_block(2, 0).to_ref(), root_b),
+            Ok(vec![]),
+            "a different slot does not conflict"
+        );
+
+        cache.prune(Slot::new(1));
+
+        assert_eq!(
+            cache.conflicting_block_roots(block_b.to_ref(), root_b),
+            Err(Error::FinalizedBlock {
+                slot: Slot::new(1),
+                finalized_slot: Slot::new(1),
+            }),
+            "cannot check finalized blocks"
+        );
+    }
+
     #[test]
     fn pruning() {
         let mut cache = ObservedBlockProducers::default();
@@ -9,14 +9,15 @@ pub const DUMMY_CANONICAL_HEAD_BLOCK_ROOT: Hash256 = Hash256::repeat_byte(0xff);
 
 #[derive(Clone, Encode, Decode)]
 pub struct PersistedBeaconChain {
-    /// This value is ignored to resolve the issue described here:
+    /// The canonical head at the time fork choice was last persisted alongside this value.
     ///
-    /// https://github.com/sigp/lighthouse/pull/1639
+    /// It is never used to set the head, see:
     ///
-    /// Its removal is tracked here:
+    /// https://github.com/sigp/lighthouse/pull/1639
     ///
-    /// https://github.com/sigp/lighthouse/issues/1784
-    pub _canonical_head_block_root: Hash256,
+    /// It is only used as the head to rebuild fork choice towards if the persisted fork choice is
+    /// unusable. It may be `DUMMY_CANONICAL_HEAD_BLOCK_ROOT` if no head is known.
+    pub canonical_head_block_root: Hash256,
     pub genesis_block_root: Hash256,
     pub ssz_head_tracker: SszHeadTracker,
 }
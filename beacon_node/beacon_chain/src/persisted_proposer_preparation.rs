@@ -0,0 +1,35 @@
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use store::{DBColumn, Error as StoreError, StoreItem};
+use types::{Epoch, ProposerPreparationData, ValidatorRegistrationData};
+
+/// A proposer preparation received from a validator client, and the epoch at which it was received.
+#[derive(Clone, Encode, Decode)]
+pub struct PersistedProposerPreparationEntry {
+    pub update_epoch: Epoch,
+    pub preparation_data: ProposerPreparationData,
+}
+
+/// The proposer preparation data and builder registrations held by the execution layer, persisted
+/// so that a restarted beacon node does not need to wait for validator clients to resubmit them.
+#[derive(Clone, Encode, Decode)]
+pub struct PersistedProposerPreparation {
+    pub preparation_data: Vec<PersistedProposerPreparationEntry>,
+    /// The URL of the builder which accepted `builder_registrations`, or empty if there is none.
+    pub builder_url: Vec<u8>,
+    pub builder_registrations: Vec<ValidatorRegistrationData>,
+}
+
+impl StoreItem for PersistedProposerPreparation {
+    fn db_column() -> DBColumn {
+        DBColumn::ProposerPreparation
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Self::from_ssz_bytes(bytes).map_err(Into::into)
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 use itertools::Itertools;
 use slog::{debug, Logger};
@@ -30,6 +31,20 @@ pub const DEFAULT_CACHE_SIZE: usize = 16;
 /// better than low-resource nodes going OOM.
 const MAX_CONCURRENT_PROMISES: usize = 2;
 
+/// The maximum time to wait for another thread to fulfil a committee cache promise, as a fraction
+/// of the slot duration.
+///
+/// Computing a committee may require loading a state from disk, which can take a few seconds. The
+/// timeout is kept well above that so that a slow but successful computation is not abandoned and
+/// repeated by each of its waiters. If a promise is unresolved after this time we assume the
+/// computation is stuck: the waiter poisons the promise and computes the committee itself.
+const PROMISE_WAIT_TIMEOUT_SLOT_FRACTION: u32 = 2;
+
+/// Returns the maximum time to wait for a committee cache promise with the given `slot_duration`.
+pub fn promise_wait_timeout(slot_duration: Duration) -> Duration {
+    slot_duration / PROMISE_WAIT_TIMEOUT_SLOT_FRACTION
+}
+
 #[derive(Clone)]
 pub enum CacheItem {
     /// A committee.
@@ -43,12 +58,31 @@ impl CacheItem {
         matches!(self, CacheItem::Promise(_))
     }
 
-    pub fn wait(self) -> Result<Arc<CommitteeCache>, BeaconChainError> {
+    /// Return the committee cache if it has already been computed, without waiting on a promise.
+    pub fn committee(self) -> Option<Arc<CommitteeCache>> {
+        match self {
+            CacheItem::Committee(cache) => Some(cache),
+            CacheItem::Promise(receiver) => receiver.try_recv().ok().flatten(),
+        }
+    }
+
+    /// Return the committee cache, waiting up to `timeout` for it to be computed if `self` is a
+    /// promise.
+    ///
+    /// See `promise_wait_timeout` for a suitable `timeout`.
+    pub fn wait(self, timeout: Duration) -> Result<Arc<CommitteeCache>, BeaconChainError> {
         match self {
             CacheItem::Committee(cache) => Ok(cache),
-            CacheItem::Promise(receiver) => receiver
-                .recv()
-                .map_err(BeaconChainError::CommitteePromiseFailed),
+            CacheItem::Promise(receiver) => {
+                let _timer = metrics::start_timer(&metrics::SHUFFLING_CACHE_PROMISE_WAIT_TIMES);
+                receiver
+                    .recv_timeout(timeout)
+                    .map_err(BeaconChainError::CommitteePromiseFailed)?
+                    .ok_or_else(|| {
+                        metrics::inc_counter(&metrics::SHUFFLING_CACHE_PROMISE_TIMEOUTS);
+                        BeaconChainError::CommitteePromiseTimeout(timeout)
+                    })
+            }
         }
     }
 }
@@ -128,6 +162,15 @@ impl ShufflingCache {
         self.cache.contains_key(key)
     }
 
+    /// The number of committees and promises contained in `self`.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
     pub fn insert_committee_cache<C: ToArcCommitteeCache>(
         &mut self,
         key: AttestationShufflingId,
@@ -189,6 +232,26 @@ impl ShufflingCache {
         }
     }
 
+    /// Remove the promise for `key` from the cache, if the entry is still the `promise` which the
+    /// caller gave up on.
+    ///
+    /// Used when a promise has not been fulfilled within `promise_wait_timeout`, so that later
+    /// lookups compute the committee instead of waiting on a computation which may never finish.
+    /// A newer promise for `key`, created by another thread which has already poisoned `promise`,
+    /// is left untouched. Promises whose sender has been dropped are removed by `Self::get`.
+    pub fn poison_promise(&mut self, key: &AttestationShufflingId, promise: &CacheItem) {
+        let is_same_promise = match (self.cache.get(key), promise) {
+            (Some(CacheItem::Promise(current)), CacheItem::Promise(poisoned)) => {
+                current.is_same_channel(poisoned)
+            }
+            _ => false,
+        };
+        if is_same_promise {
+            metrics::inc_counter(&metrics::SHUFFLING_CACHE_PROMISE_FAILS);
+            self.cache.remove(key);
+        }
+    }
+
     pub fn create_promise(
         &mut self,
         key: AttestationShufflingId,
@@ -402,6 +465,77 @@ mod test {
         assert!(cache.cache.is_empty(), "the cache should be empty");
     }
 
+    #[test]
+    fn poisoned_promise() {
+        let (committee_a, _) = committee_caches();
+        let id_a = shuffling_id(1);
+        let mut cache = new_shuffling_cache();
+
+        // Create a promise which is never resolved, simulating a stuck computation.
+        let stuck_sender = cache.create_promise(id_a.clone()).unwrap();
+        let stuck_item = cache.get(&id_a).unwrap();
+        assert!(
+            matches!(
+                stuck_item.clone().wait(Duration::from_millis(10)),
+                Err(BeaconChainError::CommitteePromiseTimeout(_))
+            ),
+            "waiting on the promise should time out"
+        );
+
+        // Poison the promise, allowing a new one to be created for the same key.
+        cache.poison_promise(&id_a, &stuck_item);
+        assert!(cache.get(&id_a).is_none(), "the slot should be empty");
+        let sender = cache.create_promise(id_a.clone()).unwrap();
+
+        // Another waiter giving up on the stuck promise must not poison the new one.
+        cache.poison_promise(&id_a, &stuck_item);
+        assert!(
+            cache.get(&id_a).map_or(false, |item| item.is_promise()),
+            "the new promise should remain"
+        );
+        sender.send(committee_a.clone());
+
+        // A late result from the stuck computation does not affect the new promise.
+        drop(stuck_sender);
+        let item = cache.get(&id_a).unwrap();
+        assert!(
+            matches!(item, CacheItem::Committee(committee) if committee == committee_a),
+            "the new promise should be resolved"
+        );
+
+        // Poisoning a resolved committee is a no-op.
+        cache.poison_promise(&id_a, &stuck_item);
+        assert!(cache.contains(&id_a), "the committee should remain");
+    }
+
+    #[test]
+    fn slow_promise_is_not_abandoned() {
+        let (committee_a, _) = committee_caches();
+        let id_a = shuffling_id(1);
+        let mut cache = new_shuffling_cache();
+        let spec = MinimalEthSpec::default_spec();
+        let timeout = promise_wait_timeout(Duration::from_secs(spec.seconds_per_slot));
+
+        // Resolve the promise on another thread, taking a few seconds as when a state must be
+        // loaded from disk.
+        let build_time = Duration::from_millis(2_500);
+        assert!(build_time < timeout);
+        let sender = cache.create_promise(id_a.clone()).unwrap();
+        let item = cache.get(&id_a).unwrap();
+        let committee = committee_a.clone();
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(build_time);
+            sender.send(committee);
+        });
+
+        assert_eq!(item.wait(timeout).unwrap(), committee_a);
+        handle.join().unwrap();
+        assert!(
+            matches!(cache.get(&id_a).unwrap(), CacheItem::Committee(committee) if committee == committee_a),
+            "the promise should be resolved rather than poisoned"
+        );
+    }
+
     #[test]
     fn two_promises() {
         let (committee_a, committee_b) = committee_caches();
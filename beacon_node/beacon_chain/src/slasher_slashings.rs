@@ -0,0 +1,281 @@
+//! Tracks slashings found by the slasher from detection until their inclusion in a block.
+//!
+//! Slashings are harvested from the slasher after each batch, verified against the head state and
+//! added to the op pool. Slashings which fail verification (e.g. because the head state hasn't
+//! caught up with the slashed validators yet) are retried after later batches. Once verified, a
+//! slashing's signatures are not checked again: later batches only check whether its validators
+//! are still slashable.
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use serde::{Deserialize, Serialize};
+use slasher::metrics::{
+    self, SLASHER_SLASHINGS_DETECTED, SLASHER_SLASHINGS_DROPPED, SLASHER_SLASHINGS_INCLUDED,
+};
+use slog::{debug, warn};
+use state_processing::per_block_processing::errors::{
+    AttesterSlashingInvalid, BlockOperationError, ProposerSlashingInvalid,
+};
+use state_processing::per_block_processing::get_slashable_indices;
+use state_processing::VerifyOperation;
+use types::{AttesterSlashing, Epoch, EthSpec, ProposerSlashing};
+
+/// The number of times verification of a slashing against the head state may fail before it is
+/// dropped.
+pub const MAX_SLASHING_VERIFICATION_ATTEMPTS: u64 = 8;
+
+/// The maximum number of pending slashings of each type. Beyond this the oldest are dropped.
+pub const MAX_PENDING_SLASHER_SLASHINGS: usize = 1_024;
+
+const ATTESTER: &str = "attester";
+const PROPOSER: &str = "proposer";
+
+/// A slashing found by the slasher which has not been included in a block yet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingSlashing<T> {
+    pub slashing: T,
+    /// The epoch in which the slasher found the slashing.
+    pub detected_epoch: Epoch,
+    /// Whether the slashing has been verified against the head state and added to the op pool.
+    pub in_op_pool: bool,
+    /// The number of times verification against the head state has failed.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub failed_attempts: u64,
+}
+
+impl<T> PendingSlashing<T> {
+    fn new(slashing: T, detected_epoch: Epoch) -> Self {
+        Self {
+            slashing,
+            detected_epoch,
+            in_op_pool: false,
+            failed_attempts: 0,
+        }
+    }
+}
+
+/// The slashings found by the slasher which have not been included in a block yet.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct PendingSlasherSlashings<E: EthSpec> {
+    pub attester_slashings: Vec<PendingSlashing<AttesterSlashing<E>>>,
+    pub proposer_slashings: Vec<PendingSlashing<ProposerSlashing>>,
+}
+
+impl<E: EthSpec> Default for PendingSlasherSlashings<E> {
+    fn default() -> Self {
+        Self {
+            attester_slashings: vec![],
+            proposer_slashings: vec![],
+        }
+    }
+}
+
+/// Slashings which were newly added to the op pool, and should be published on gossip.
+#[derive(Debug)]
+pub struct NewSlasherSlashings<E: EthSpec> {
+    pub attester_slashings: Vec<AttesterSlashing<E>>,
+    pub proposer_slashings: Vec<ProposerSlashing>,
+}
+
+impl<E: EthSpec> Default for NewSlasherSlashings<E> {
+    fn default() -> Self {
+        Self {
+            attester_slashings: vec![],
+            proposer_slashings: vec![],
+        }
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Harvest the slashings found by the slasher and add them to the op pool, retrying those
+    /// which previously failed verification against the head state.
+    ///
+    /// Slashings remain pending until none of their validators are slashable in the head state,
+    /// i.e. until they (or an equivalent slashing) have been included in a block.
+    pub fn import_slasher_slashings(
+        &self,
+    ) -> Result<NewSlasherSlashings<T::EthSpec>, BeaconChainError> {
+        let mut new_slashings = NewSlasherSlashings::default();
+        let slasher = match self.slasher.as_ref() {
+            Some(slasher) => slasher,
+            None => return Ok(new_slashings),
+        };
+        let current_epoch = self.epoch()?;
+        let head = self.canonical_head.cached_head();
+        let state = &head.snapshot.beacon_state;
+
+        let mut pending = self.pending_slasher_slashings.lock();
+
+        for slashing in slasher.get_attester_slashings() {
+            metrics::inc_counter_vec(&SLASHER_SLASHINGS_DETECTED, &[ATTESTER]);
+            pending
+                .attester_slashings
+                .push(PendingSlashing::new(slashing, current_epoch));
+        }
+        for slashing in slasher.get_proposer_slashings() {
+            metrics::inc_counter_vec(&SLASHER_SLASHINGS_DETECTED, &[PROPOSER]);
+            pending
+                .proposer_slashings
+                .push(PendingSlashing::new(slashing, current_epoch));
+        }
+
+        self.evict_slasher_slashings(&mut pending.attester_slashings, ATTESTER);
+        self.evict_slasher_slashings(&mut pending.proposer_slashings, PROPOSER);
+
+        pending.attester_slashings.retain_mut(|pending| {
+            if pending.in_op_pool {
+                return match get_slashable_indices(state, &pending.slashing) {
+                    Ok(_) => true,
+                    Err(BlockOperationError::Invalid(
+                        AttesterSlashingInvalid::NoSlashableIndices,
+                    )) => {
+                        self.resolve_slasher_slashing(pending, ATTESTER);
+                        false
+                    }
+                    Err(e) => self.retry_slasher_slashing(pending, ATTESTER, format!("{:?}", e)),
+                };
+            }
+
+            match pending.slashing.clone().validate(state, &self.spec) {
+                Ok(verified) => {
+                    self.import_attester_slashing(verified);
+                    pending.in_op_pool = true;
+                    new_slashings
+                        .attester_slashings
+                        .push(pending.slashing.clone());
+                    true
+                }
+                Err(BlockOperationError::Invalid(AttesterSlashingInvalid::NoSlashableIndices)) => {
+                    self.resolve_slasher_slashing(pending, ATTESTER);
+                    false
+                }
+                Err(e) => self.retry_slasher_slashing(pending, ATTESTER, format!("{:?}", e)),
+            }
+        });
+
+        let head_epoch = state.current_epoch();
+        pending.proposer_slashings.retain_mut(|pending| {
+            if pending.in_op_pool {
+                let index = pending.slashing.signed_header_1.message.proposer_index;
+                let slashable = state
+                    .validators()
+                    .get(index as usize)
+                    .map_or(false, |validator| validator.is_slashable_at(head_epoch));
+                if !slashable {
+                    debug!(self.log, "Proposer no longer slashable"; "validator_index" => index);
+                    self.resolve_slasher_slashing(pending, PROPOSER);
+                }
+                return slashable;
+            }
+
+            match pending.slashing.clone().validate(state, &self.spec) {
+                Ok(verified) => {
+                    self.import_proposer_slashing(verified);
+                    pending.in_op_pool = true;
+                    new_slashings
+                        .proposer_slashings
+                        .push(pending.slashing.clone());
+                    true
+                }
+                Err(BlockOperationError::Invalid(
+                    ProposerSlashingInvalid::ProposerNotSlashable(index),
+                )) => {
+                    debug!(self.log, "Proposer no longer slashable"; "validator_index" => index);
+                    self.resolve_slasher_slashing(pending, PROPOSER);
+                    false
+                }
+                Err(e) => self.retry_slasher_slashing(pending, PROPOSER, format!("{:?}", e)),
+            }
+        });
+
+        Ok(new_slashings)
+    }
+
+    /// Drop the oldest of `pending` so that at most `MAX_PENDING_SLASHER_SLASHINGS` remain.
+    fn evict_slasher_slashings<S>(
+        &self,
+        pending: &mut Vec<PendingSlashing<S>>,
+        kind: &'static str,
+    ) {
+        let evicted = evict_oldest(pending, MAX_PENDING_SLASHER_SLASHINGS);
+        if evicted > 0 {
+            warn!(
+                self.log,
+                "Too many pending slasher slashings, dropping oldest";
+                "type" => kind,
+                "count" => evicted,
+            );
+            metrics::inc_counter_vec_by(&SLASHER_SLASHINGS_DROPPED, &[kind], evicted as u64);
+        }
+    }
+
+    /// Record that none of the validators of `pending` are slashable any more.
+    fn resolve_slasher_slashing<S>(&self, pending: &PendingSlashing<S>, kind: &'static str) {
+        if pending.in_op_pool {
+            debug!(
+                self.log,
+                "Slasher slashing included";
+                "type" => kind,
+                "detected_epoch" => pending.detected_epoch,
+            );
+            metrics::inc_counter_vec(&SLASHER_SLASHINGS_INCLUDED, &[kind]);
+        } else {
+            debug!(
+                self.log,
+                "Skipping slasher slashing for slashed validators";
+                "type" => kind,
+            );
+        }
+    }
+
+    /// Record a failed verification of `pending`, returning `false` if it should be dropped.
+    fn retry_slasher_slashing<S>(
+        &self,
+        pending: &mut PendingSlashing<S>,
+        kind: &'static str,
+        error: String,
+    ) -> bool {
+        pending.failed_attempts += 1;
+        if pending.failed_attempts < MAX_SLASHING_VERIFICATION_ATTEMPTS {
+            debug!(
+                self.log,
+                "Slasher slashing invalid against head, will retry";
+                "type" => kind,
+                "error" => error,
+                "failed_attempts" => pending.failed_attempts,
+            );
+            true
+        } else {
+            warn!(
+                self.log,
+                "Dropping invalid slasher slashing";
+                "type" => kind,
+                "error" => error,
+                "detected_epoch" => pending.detected_epoch,
+            );
+            metrics::inc_counter_vec(&SLASHER_SLASHINGS_DROPPED, &[kind]);
+            false
+        }
+    }
+}
+
+/// Remove the oldest items of `pending` so that at most `max_len` remain, returning the number
+/// removed.
+fn evict_oldest<T>(pending: &mut Vec<T>, max_len: usize) -> usize {
+    let excess = pending.len().saturating_sub(max_len);
+    pending.drain(..excess);
+    excess
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_oldest_beyond_max_len() {
+        let mut pending = (0..5).collect::<Vec<u64>>();
+        assert_eq!(evict_oldest(&mut pending, 8), 0);
+        assert_eq!(pending, vec![0, 1, 2, 3, 4]);
+        assert_eq!(evict_oldest(&mut pending, 3), 2);
+        assert_eq!(pending, vec![2, 3, 4]);
+    }
+}
@@ -17,6 +17,7 @@ use crate::validator_monitor::HISTORIC_EPOCHS as VALIDATOR_MONITOR_HISTORIC_EPOC
 use crate::{
     beacon_chain::{ATTESTATION_CACHE_LOCK_TIMEOUT, BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT},
     chain_config::FORK_CHOICE_LOOKAHEAD_FACTOR,
+    metrics,
     snapshot_cache::StateAdvance,
     BeaconChain, BeaconChainError, BeaconChainTypes,
 };
@@ -123,8 +124,13 @@ async fn state_advance_timer<T: BeaconChainTypes>(
             }
         };
 
-        // Run the state advance 3/4 of the way through the slot (9s on mainnet).
-        let state_advance_offset = slot_duration / 4;
+        // Run the state advance 3/4 of the way through the slot by default (9s on mainnet). It
+        // must run before the fork choice lookahead below.
+        let fork_choice_offset = slot_duration / FORK_CHOICE_LOOKAHEAD_FACTOR;
+        let state_advance_offset = std::cmp::max(
+            beacon_chain.config.state_advance_lookahead,
+            fork_choice_offset,
+        );
         let state_advance_instant = if duration_to_next_slot > state_advance_offset {
             Instant::now() + duration_to_next_slot - state_advance_offset
         } else {
@@ -134,7 +140,6 @@ async fn state_advance_timer<T: BeaconChainTypes>(
 
         // Run fork choice 23/24s of the way through the slot (11.5s on mainnet).
         // We need to run after the state advance, so use the same condition as above.
-        let fork_choice_offset = slot_duration / FORK_CHOICE_LOOKAHEAD_FACTOR;
         let fork_choice_instant = if duration_to_next_slot > state_advance_offset {
             Instant::now() + duration_to_next_slot - fork_choice_offset
         } else {
@@ -328,9 +333,12 @@ fn advance_head<T: BeaconChainTypes>(
     };
 
     // Advance the state a single slot.
-    if let Some(summary) = per_slot_processing(&mut state, state_root, &beacon_chain.spec)
-        .map_err(BeaconChainError::from)?
-    {
+    let slot_processing_timer = metrics::start_timer(&metrics::STATE_ADVANCE_SLOT_PROCESSING_TIMES);
+    let summary = per_slot_processing(&mut state, state_root, &beacon_chain.spec)
+        .map_err(BeaconChainError::from)?;
+    drop(slot_processing_timer);
+
+    if let Some(summary) = summary {
         // Expose Prometheus metrics.
         if let Err(e) = summary.observe_metrics() {
             error!(
@@ -432,10 +432,14 @@ where
     }
 
     pub fn mock_execution_layer(self) -> Self {
-        self.mock_execution_layer_with_config(None)
+        self.mock_execution_layer_with_config(None, None)
     }
 
-    pub fn mock_execution_layer_with_config(mut self, builder_threshold: Option<u128>) -> Self {
+    pub fn mock_execution_layer_with_config(
+        mut self,
+        builder_threshold: Option<u128>,
+        builder_boost_factor: Option<u64>,
+    ) -> Self {
         let spec = self.spec.clone().expect("cannot build without spec");
         let shanghai_time = spec.capella_fork_epoch.map(|epoch| {
             HARNESS_GENESIS_TIME + spec.seconds_per_slot * E::slots_per_epoch() * epoch.as_u64()
@@ -445,6 +449,7 @@ where
             DEFAULT_TERMINAL_BLOCK,
             shanghai_time,
             builder_threshold,
+            builder_boost_factor,
             Some(JwtKey::from_slice(&DEFAULT_JWT_SECRET).unwrap()),
             spec,
         );
@@ -775,7 +780,7 @@ where
 
         let randao_reveal = self.sign_randao_reveal(&state, proposer_index, slot);
 
-        let (block, state) = self
+        let (block, state, _) = self
             .chain
             .produce_block_on_state(
                 state,
@@ -824,7 +829,7 @@ where
 
         let pre_state = state.clone();
 
-        let (block, state) = self
+        let (block, state, _) = self
             .chain
             .produce_block_on_state(
                 state,
@@ -4,7 +4,7 @@
 
 use crate::metrics;
 use parking_lot::RwLock;
-use slog::{crit, debug, info, Logger};
+use slog::{crit, debug, info, warn, Logger};
 use slot_clock::SlotClock;
 use state_processing::per_epoch_processing::{
     errors::EpochProcessingError, EpochProcessingSummary,
@@ -17,7 +17,7 @@ use std::str::Utf8Error;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use store::AbstractExecPayload;
 use types::{
-    AttesterSlashing, BeaconBlockRef, BeaconState, ChainSpec, Epoch, EthSpec, Hash256,
+    Address, AttesterSlashing, BeaconBlockRef, BeaconState, ChainSpec, Epoch, EthSpec, Hash256,
     IndexedAttestation, ProposerSlashing, PublicKeyBytes, SignedAggregateAndProof,
     SignedContributionAndProof, Slot, SyncCommitteeMessage, VoluntaryExit,
 };
@@ -37,6 +37,10 @@ pub const HISTORIC_EPOCHS: usize = 10;
 /// Prometheus cardinality and log volume.
 pub const DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD: usize = 64;
 
+/// Automatic registration stops once the validator monitor holds this many validators, so that
+/// pointing a large number of validators at a single node doesn't grow the monitor without bound.
+pub const MAX_AUTO_REGISTERED_VALIDATORS: usize = 4_096;
+
 #[derive(Debug)]
 pub enum Error {
     InvalidPubkey(String),
@@ -79,11 +83,11 @@ pub struct EpochSummary {
      * SyncCommitteeMessages in the current epoch
      */
     /// The number of sync committee messages seen.
-    sync_committee_messages: usize,
+    pub sync_committee_messages: usize,
     /// The delay between when the sync committee message should have been produced and when it was observed.
     sync_committee_message_min_delay: Option<Duration>,
     /// The number of times a validator's sync signature was included in the sync aggregate.
-    sync_signature_block_inclusions: usize,
+    pub sync_signature_block_inclusions: usize,
     /// The number of times a validator's sync signature was aggregated into a sync contribution.
     sync_signature_contribution_inclusions: usize,
 
@@ -191,6 +195,16 @@ impl EpochSummary {
 
 type SummaryMap = HashMap<Epoch, EpochSummary>;
 
+/// A block proposed by a monitored validator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProposalInfo {
+    pub slot: Slot,
+    /// The earliest delay at which the block was observed, relative to the start of its slot.
+    pub delay: Duration,
+    /// `true` if the block was observed before attestations for its slot were due.
+    pub on_time: bool,
+}
+
 #[derive(Default)]
 pub struct ValidatorMetrics {
     pub attestation_hits: u64,
@@ -200,6 +214,8 @@ pub struct ValidatorMetrics {
     pub attestation_target_hits: u64,
     pub attestation_target_misses: u64,
     pub latest_attestation_inclusion_distance: u64,
+    /// The most recent proposals, ordered by slot and capped at `HISTORIC_EPOCHS` entries.
+    pub proposals: Vec<ProposalInfo>,
 }
 
 impl ValidatorMetrics {
@@ -230,6 +246,29 @@ impl ValidatorMetrics {
     pub fn set_latest_inclusion_distance(&mut self, distance: u64) {
         self.latest_attestation_inclusion_distance = distance;
     }
+
+    /// Record a proposal, keeping the smallest delay if the block was already observed from
+    /// another source.
+    pub fn register_proposal(&mut self, slot: Slot, delay: Duration, on_time_threshold: Duration) {
+        match self.proposals.iter_mut().find(|p| p.slot == slot) {
+            Some(proposal) if delay < proposal.delay => {
+                proposal.delay = delay;
+                proposal.on_time = delay < on_time_threshold;
+            }
+            Some(_) => {}
+            None => {
+                self.proposals.push(ProposalInfo {
+                    slot,
+                    delay,
+                    on_time: delay < on_time_threshold,
+                });
+                self.proposals.sort_by_key(|p| p.slot);
+                if self.proposals.len() > HISTORIC_EPOCHS {
+                    self.proposals.remove(0);
+                }
+            }
+        }
+    }
 }
 
 /// A validator that is being monitored by the `ValidatorMonitor`.
@@ -338,6 +377,9 @@ pub struct ValidatorMonitor<T> {
     indices: HashMap<u64, PublicKeyBytes>,
     /// If true, allow the automatic registration of validators.
     auto_register: bool,
+    /// Set once automatic registration has been refused due to `MAX_AUTO_REGISTERED_VALIDATORS`,
+    /// to avoid repeating the warning for every request.
+    auto_register_limit_reached: bool,
     /// Once the number of monitored validators goes above this threshold, we
     /// will stop tracking metrics/logs on a per-validator basis. This prevents
     /// large validator counts causing infeasibly high cardinailty for
@@ -358,6 +400,7 @@ impl<T: EthSpec> ValidatorMonitor<T> {
             validators: <_>::default(),
             indices: <_>::default(),
             auto_register,
+            auto_register_limit_reached: false,
             individual_tracking_threshold,
             log,
             _phantom: PhantomData,
@@ -371,10 +414,33 @@ impl<T: EthSpec> ValidatorMonitor<T> {
     /// Returns `true` when the validator count is sufficiently low enough to
     /// emit metrics and logs on a per-validator basis (rather than just an
     /// aggregated basis).
-    fn individual_tracking(&self) -> bool {
+    pub fn individual_tracking(&self) -> bool {
         self.validators.len() <= self.individual_tracking_threshold
     }
 
+    /// Returns the threshold above which per-validator metrics and logs are disabled.
+    pub fn individual_tracking_threshold(&self) -> usize {
+        self.individual_tracking_threshold
+    }
+
+    /// Update the threshold above which per-validator metrics and logs are disabled.
+    ///
+    /// Already-monitored validators are retained, only the granularity of their metrics changes.
+    pub fn set_individual_tracking_threshold(&mut self, individual_tracking_threshold: usize) {
+        let was_individual_tracking = self.individual_tracking();
+        self.individual_tracking_threshold = individual_tracking_threshold;
+
+        if was_individual_tracking != self.individual_tracking() {
+            info!(
+                self.log,
+                "Validator monitor individual tracking updated";
+                "individual_tracking" => self.individual_tracking(),
+                "monitored_validators" => self.validators.len(),
+                "threshold" => individual_tracking_threshold,
+            );
+        }
+    }
+
     /// Add some validators to `self` for additional monitoring.
     fn add_validator_pubkey(&mut self, pubkey: PublicKeyBytes) {
         let index_opt = self
@@ -691,14 +757,20 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                         }
                     }
 
+                    metrics::observe(
+                        &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_INCLUSION_DISTANCE_DISTRIBUTION,
+                        inclusion_delay as f64,
+                    );
                     if self.individual_tracking() {
                         metrics::set_int_gauge(
                             &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_INCLUSION_DISTANCE,
                             &[id],
                             inclusion_delay as i64,
                         );
-                        validator_metrics.set_latest_inclusion_distance(inclusion_delay);
                     }
+                    // Always stored so that the HTTP API can report on individual validators,
+                    // regardless of whether the Prometheus metrics are aggregated.
+                    validator_metrics.set_latest_inclusion_distance(inclusion_delay);
                 }
                 drop(validator_metrics);
 
@@ -760,7 +832,7 @@ impl<T: EthSpec> ValidatorMonitor<T> {
             );
         }
         if !attestation_miss.is_empty() {
-            info!(
+            warn!(
                 self.log,
                 "Previous epoch attestation(s) missing";
                 "epoch" => prev_epoch,
@@ -824,6 +896,12 @@ impl<T: EthSpec> ValidatorMonitor<T> {
 
     /// If `self.auto_register == true`, add the `validator_index` to `self.monitored_validators`.
     /// Otherwise, do nothing.
+    ///
+    /// No validators are added once `MAX_AUTO_REGISTERED_VALIDATORS` are monitored.
+    ///
+    /// Local validators are learned from their subnet subscriptions, proposer preparations and
+    /// builder registrations. Since validator clients re-send these at least once per epoch,
+    /// auto-registered validators are re-learned within an epoch of a restart.
     pub fn auto_register_local_validator(&mut self, validator_index: u64) {
         if !self.auto_register {
             return;
@@ -831,6 +909,21 @@ impl<T: EthSpec> ValidatorMonitor<T> {
 
         if let Some(pubkey) = self.indices.get(&validator_index) {
             if !self.validators.contains_key(pubkey) {
+                if self.validators.len() >= MAX_AUTO_REGISTERED_VALIDATORS {
+                    if !self.auto_register_limit_reached {
+                        warn!(
+                            self.log,
+                            "Validator monitor auto-registration limit reached";
+                            "msg" => "further validators will not be monitored automatically",
+                            "monitored_validators" => self.validators.len(),
+                            "limit" => MAX_AUTO_REGISTERED_VALIDATORS,
+                            "hint" => "use --validator-monitor-pubkeys to monitor specific validators",
+                        );
+                        self.auto_register_limit_reached = true;
+                    }
+                    return;
+                }
+
                 info!(
                     self.log,
                     "Started monitoring validator";
@@ -842,6 +935,18 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                     *pubkey,
                     MonitoredValidator::new(*pubkey, Some(validator_index)),
                 );
+
+                if self.validators.len() == self.individual_tracking_threshold + 1 {
+                    warn!(
+                        self.log,
+                        "Validator monitor individual tracking disabled";
+                        "msg" => "too many validators are monitored for per-validator metrics, \
+                                  only aggregate metrics will be recorded",
+                        "monitored_validators" => self.validators.len(),
+                        "threshold" => self.individual_tracking_threshold,
+                        "hint" => "raise --validator-monitor-individual-tracking-threshold",
+                    );
+                }
             }
         }
     }
@@ -904,6 +1009,12 @@ impl<T: EthSpec> ValidatorMonitor<T> {
             );
 
             validator.with_epoch_summary(epoch, |summary| summary.register_block(delay));
+            // Use the same threshold as late block logging when publishing blocks.
+            validator.metrics.write().register_proposal(
+                block.slot(),
+                delay,
+                slot_clock.unagg_attestation_production_delay(),
+            );
         }
     }
 
@@ -1493,6 +1604,43 @@ impl<T: EthSpec> ValidatorMonitor<T> {
         }
     }
 
+    /// Register a block published for a monitored proposer at `slot` whose payload from `src`
+    /// pays `fee_recipient` rather than the `expected_fee_recipient`.
+    ///
+    /// Callers should only register mismatches deemed significant by the execution layer, so that
+    /// builders which legitimately pay the proposer via a transaction are not counted.
+    pub fn register_fee_recipient_mismatch(
+        &self,
+        src: &str,
+        proposer_index: u64,
+        slot: Slot,
+        expected_fee_recipient: Address,
+        fee_recipient: Address,
+    ) {
+        if let Some(validator) = self.get_validator(proposer_index) {
+            let id = &validator.id;
+
+            self.aggregatable_metric(id, |label| {
+                metrics::inc_counter_vec(
+                    &metrics::VALIDATOR_MONITOR_FEE_RECIPIENT_MISMATCH_TOTAL,
+                    &[src, label],
+                );
+            });
+
+            if self.individual_tracking() {
+                warn!(
+                    self.log,
+                    "Payload fee recipient differs from expected";
+                    "fee_recipient" => ?fee_recipient,
+                    "expected_fee_recipient" => ?expected_fee_recipient,
+                    "slot" => %slot,
+                    "validator" => %id,
+                    "src" => src,
+                );
+            }
+        }
+    }
+
     /// Register an attester slashing from the gossip network.
     pub fn register_gossip_attester_slashing(&self, slashing: &AttesterSlashing<T>) {
         self.register_attester_slashing("gossip", slashing)
@@ -1660,11 +1808,13 @@ impl<T: EthSpec> ValidatorMonitor<T> {
                         );
                     }
                     if let Some(delay) = summary.sync_contribution_min_delay {
-                        metrics::observe_timer_vec(
-                            &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_CONTRIBUTION_MIN_DELAY_SECONDS,
-                            &[id],
-                            delay,
-                        );
+                        self.aggregatable_metric(id, |tag| {
+                            metrics::observe_timer_vec(
+                                &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_CONTRIBUTION_MIN_DELAY_SECONDS,
+                                &[tag],
+                                delay,
+                            );
+                        });
                     }
 
                     /*
@@ -1793,3 +1943,93 @@ fn min_opt<T: Ord>(a: Option<T>, b: Option<T>) -> Option<T> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{Keypair, MainnetEthSpec};
+
+    #[test]
+    fn changing_threshold_retains_validators() {
+        let pubkeys = (0..4).map(|_| Keypair::random().pk.compress()).collect();
+        let log = Logger::root(slog::Discard, slog::o!());
+        let mut monitor = ValidatorMonitor::<MainnetEthSpec>::new(pubkeys, false, 2, log);
+        assert!(!monitor.individual_tracking());
+
+        monitor.set_individual_tracking_threshold(4);
+        assert!(monitor.individual_tracking());
+        assert_eq!(monitor.individual_tracking_threshold(), 4);
+        assert_eq!(monitor.num_validators(), 4);
+
+        monitor.set_individual_tracking_threshold(0);
+        assert!(!monitor.individual_tracking());
+        assert_eq!(monitor.num_validators(), 4);
+    }
+
+    #[test]
+    fn auto_registration_is_capped() {
+        let log = Logger::root(slog::Discard, slog::o!());
+        let mut monitor = ValidatorMonitor::<MainnetEthSpec>::new(vec![], true, 1, log);
+        let num_validators = MAX_AUTO_REGISTERED_VALIDATORS as u64 + 2;
+        for i in 0..num_validators {
+            monitor.indices.insert(i, Keypair::random().pk.compress());
+            monitor.auto_register_local_validator(i);
+        }
+
+        assert_eq!(monitor.num_validators(), MAX_AUTO_REGISTERED_VALIDATORS);
+        assert!(monitor.get_validator(0).is_some());
+        assert!(monitor.get_validator(num_validators - 1).is_none());
+    }
+
+    #[test]
+    fn fee_recipient_mismatches_counted_for_monitored_proposers() {
+        let log = Logger::root(slog::Discard, slog::o!());
+        let mut monitor = ValidatorMonitor::<MainnetEthSpec>::new(vec![], true, 1, log);
+        monitor.indices.insert(7, Keypair::random().pk.compress());
+        monitor.auto_register_local_validator(7);
+        let id = monitor.get_validator(7).unwrap().id.clone();
+
+        let count = |src: &str| {
+            metrics::get_int_counter(
+                &metrics::VALIDATOR_MONITOR_FEE_RECIPIENT_MISMATCH_TOTAL,
+                &[src, &id],
+            )
+            .map_or(0, |counter| counter.get())
+        };
+        let (local_before, builder_before) = (count("local"), count("builder"));
+
+        let expected = Address::repeat_byte(1);
+        let paid = Address::repeat_byte(2);
+        monitor.register_fee_recipient_mismatch("builder", 7, Slot::new(1), expected, paid);
+        // Unknown proposers are ignored.
+        monitor.register_fee_recipient_mismatch("local", 8, Slot::new(2), expected, paid);
+
+        assert_eq!(count("builder"), builder_before + 1);
+        assert_eq!(count("local"), local_before);
+    }
+
+    #[test]
+    fn register_proposal_keeps_earliest_observation() {
+        let threshold = Duration::from_secs(4);
+        let mut metrics = ValidatorMetrics::default();
+
+        metrics.register_proposal(Slot::new(1), Duration::from_secs(5), threshold);
+        metrics.register_proposal(Slot::new(1), Duration::from_secs(2), threshold);
+        metrics.register_proposal(Slot::new(1), Duration::from_secs(3), threshold);
+        assert_eq!(
+            metrics.proposals,
+            vec![ProposalInfo {
+                slot: Slot::new(1),
+                delay: Duration::from_secs(2),
+                on_time: true,
+            }]
+        );
+
+        for slot in 2..=HISTORIC_EPOCHS as u64 + 1 {
+            metrics.register_proposal(Slot::new(slot), threshold, threshold);
+        }
+        assert_eq!(metrics.proposals.len(), HISTORIC_EPOCHS);
+        assert_eq!(metrics.proposals[0].slot, Slot::new(2));
+        assert!(!metrics.proposals[0].on_time);
+    }
+}
@@ -218,3 +218,86 @@ async fn early_attester_cache_old_request() {
         .unwrap();
     assert_eq!(attested_block.slot(), attest_slot);
 }
+
+/// Ensures that the early attester cache does not retain a block once the head has moved
+/// elsewhere, even if the new head is not itself added to the cache.
+#[tokio::test]
+async fn early_attester_cache_cleared_on_head_change() {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS[..].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    harness.advance_slot();
+
+    harness
+        .extend_chain(
+            2,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Insert the parent of the head, simulating a block which was cached but did not remain the
+    // head.
+    let head = harness.chain.head_snapshot();
+    let stale_root = head.beacon_block.parent_root();
+    let stale_block = harness.chain.get_block(&stale_root).await.unwrap().unwrap();
+    let stale_state = harness
+        .chain
+        .get_state(&stale_block.state_root(), Some(stale_block.slot()))
+        .unwrap()
+        .unwrap();
+    let stale_proto_block = harness
+        .chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .get_block(&stale_root)
+        .unwrap();
+    harness
+        .chain
+        .early_attester_cache
+        .add_head_block(
+            stale_root,
+            Arc::new(stale_block),
+            stale_proto_block,
+            &stale_state,
+            &harness.chain.spec,
+        )
+        .unwrap();
+    assert!(harness
+        .chain
+        .early_attester_cache
+        .contains_block(stale_root));
+
+    // Import a new head block which is too old to be added to the early attester cache itself, so
+    // that only the head change can evict the stale block.
+    let block_slot = head.beacon_block.slot() + 1;
+    let (block, _) = harness
+        .make_block(harness.get_current_state(), block_slot)
+        .await;
+    let new_head_root = block.canonical_root();
+    harness.set_current_slot(block_slot + MainnetEthSpec::slots_per_epoch() / 2);
+    harness.process_block_result(block).await.unwrap();
+
+    assert_eq!(
+        harness.chain.head_snapshot().beacon_block_root,
+        new_head_root
+    );
+    assert!(!harness
+        .chain
+        .early_attester_cache
+        .contains_block(new_head_root));
+    assert!(!harness
+        .chain
+        .early_attester_cache
+        .contains_block(stale_root));
+
+    let attestation = harness
+        .chain
+        .produce_unaggregated_attestation(harness.chain.slot().unwrap(), 0)
+        .unwrap();
+    assert_eq!(attestation.data.beacon_block_root, new_head_root);
+}
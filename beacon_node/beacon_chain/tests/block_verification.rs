@@ -1019,6 +1019,66 @@ async fn verify_block_for_gossip_slashing_detection() {
     slasher_dir.close().unwrap();
 }
 
+#[tokio::test]
+async fn slasher_attester_slashing_included_in_next_block() {
+    let slasher_dir = tempdir().unwrap();
+    let slasher = Arc::new(
+        Slasher::open(SlasherConfig::new(slasher_dir.path().into()), test_logger()).unwrap(),
+    );
+
+    let inner_slasher = slasher.clone();
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS.to_vec())
+        .fresh_ephemeral_store()
+        .initial_mutator(Box::new(move |builder| builder.slasher(inner_slasher)))
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+
+    // Hand the slasher a double vote by validator 0 and run a batch.
+    let double_vote = harness.make_attester_slashing(vec![0]);
+    slasher.accept_attestation(double_vote.attestation_1.clone());
+    slasher.accept_attestation(double_vote.attestation_2.clone());
+    slasher.process_queued(Epoch::new(0)).unwrap();
+
+    let new_slashings = harness.chain.import_slasher_slashings().unwrap();
+    assert_eq!(new_slashings.attester_slashings.len(), 1);
+    let slashing = new_slashings.attester_slashings[0].clone();
+    {
+        let pending = harness.chain.pending_slasher_slashings.lock();
+        assert_eq!(pending.attester_slashings.len(), 1);
+        assert!(pending.attester_slashings[0].in_op_pool);
+    }
+
+    // The slashing should be included in the next block.
+    let state = harness.get_current_state();
+    let (block, _) = harness.make_block(state, Slot::new(1)).await;
+    assert_eq!(
+        block.message().body().attester_slashings().to_vec(),
+        vec![slashing]
+    );
+
+    // Once the block is imported the slashing is no longer pending, and it isn't published again.
+    harness
+        .process_block(Slot::new(1), block.canonical_root(), block)
+        .await
+        .unwrap();
+    let new_slashings = harness.chain.import_slasher_slashings().unwrap();
+    assert!(new_slashings.attester_slashings.is_empty());
+    assert!(harness
+        .chain
+        .pending_slasher_slashings
+        .lock()
+        .attester_slashings
+        .is_empty());
+
+    // windows won't delete the temporary directory if you don't do this..
+    drop(harness);
+    drop(slasher);
+    slasher_dir.close().unwrap();
+}
+
 #[tokio::test]
 async fn verify_block_for_gossip_doppelganger_detection() {
     let harness = get_harness(VALIDATOR_COUNT);
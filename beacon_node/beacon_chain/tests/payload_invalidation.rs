@@ -21,6 +21,10 @@ use fork_choice::{Error as ForkChoiceError, InvalidationOperation, PayloadVerifi
 use logging::test_logger;
 use proto_array::{Error as ProtoArrayError, ExecutionStatus};
 use slot_clock::SlotClock;
+use state_processing::{
+    per_block_processing, BlockSignatureStrategy, ConsensusContext, StateProcessingStrategy,
+    VerifyBlockRoot,
+};
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
@@ -734,6 +738,7 @@ async fn invalidates_all_descendants() {
     // triggered the INVALID response from the EL.
     assert!(rig.execution_status(fork_block_root).is_invalid());
 
+    let invalid_payload_cache = &rig.harness.chain.invalid_payload_cache;
     for root in blocks {
         let slot = rig
             .harness
@@ -758,9 +763,11 @@ async fn invalidates_all_descendants() {
         } else if slot <= latest_valid_slot {
             // Blocks prior to and included the latest valid hash are not marked as valid.
             assert!(execution_status.is_strictly_optimistic());
+            assert!(!invalid_payload_cache.contains(&root));
         } else {
-            // Blocks after the latest valid hash are invalid.
+            // Blocks after the latest valid hash are invalid, and are remembered.
             assert!(execution_status.is_invalid());
+            assert!(invalid_payload_cache.contains(&root));
         }
     }
 }
@@ -1069,6 +1076,7 @@ async fn invalid_parent() {
             &state,
             PayloadVerificationStatus::Optimistic,
             rig.harness.chain.config.progressive_balances_mode,
+            rig.harness.chain.config.count_unrealized.into(),
             &rig.harness.chain.spec,
             rig.harness.logger()
         ),
@@ -1083,6 +1091,158 @@ async fn invalid_parent() {
     ));
 }
 
+/// Ensure that descendants of a payload declared invalid by the EE are rejected without consulting
+/// the EE again.
+#[tokio::test]
+async fn descendant_of_cached_invalid_payload() {
+    let mut rig = InvalidPayloadRig::new();
+    rig.move_to_terminal_block();
+    rig.import_block(Payload::Valid).await; // Import a valid transition block.
+    let valid_root = rig.import_block(Payload::Syncing).await;
+    let valid_hash = rig.block_hash(valid_root);
+
+    // Produce a block and a child atop it, but don't import either yet.
+    let head = rig.harness.chain.head_snapshot();
+    let state = head.beacon_state.clone_with_only_committee_caches();
+    let slot = state.slot() + 1;
+    let (invalid_block, invalid_state) = rig.harness.make_block(state, slot).await;
+    let invalid_block = Arc::new(invalid_block);
+    let invalid_root = invalid_block.canonical_root();
+    let (child_block, _) = rig.harness.make_block(invalid_state, slot + 1).await;
+    let child_block = Arc::new(child_block);
+    assert_eq!(child_block.parent_root(), invalid_root);
+
+    // Have the EE declare the first block invalid.
+    let mock_execution_layer = rig.harness.mock_execution_layer.as_ref().unwrap();
+    mock_execution_layer
+        .server
+        .all_payloads_invalid_on_new_payload(valid_hash);
+    assert!(matches!(
+        rig.harness
+            .chain
+            .process_block(
+                invalid_root,
+                invalid_block.clone(),
+                NotifyExecutionLayer::Yes,
+                || Ok(()),
+            )
+            .await,
+        Err(BlockError::ExecutionPayloadError(
+            ExecutionPayloadError::RejectedByExecutionEngine { .. }
+        ))
+    ));
+    let invalid_payload_cache = &rig.harness.chain.invalid_payload_cache;
+    assert!(invalid_payload_cache.contains(&invalid_root));
+    assert!(!invalid_payload_cache.contains(&valid_root));
+
+    // Flip the EE to declare all payloads valid. The cache should still be consulted first.
+    mock_execution_layer
+        .server
+        .all_payloads_valid_on_new_payload();
+    rig.harness.set_current_slot(slot + 1);
+
+    assert!(matches!(
+        rig.harness.chain.clone().verify_block_for_gossip(child_block.clone()).await,
+        Err(BlockError::ParentExecutionPayloadInvalid { parent_root })
+        if parent_root == invalid_root
+    ));
+    assert!(matches!(
+        rig.harness.chain.process_block(child_block.canonical_root(), child_block.clone(), NotifyExecutionLayer::Yes,
+            || Ok(()),
+        ).await,
+        Err(BlockError::ParentExecutionPayloadInvalid { parent_root })
+        if parent_root == invalid_root
+    ));
+    assert!(matches!(
+        rig.harness.chain.process_block(invalid_root, invalid_block.clone(), NotifyExecutionLayer::Yes,
+            || Ok(()),
+        ).await,
+        Err(BlockError::ExecutionPayloadError(
+            ExecutionPayloadError::KnownInvalidPayload { block_root }
+        ))
+        if block_root == invalid_root
+    ));
+}
+
+/// Ensure that a block which is rejected by the EE does not cause a different block carrying a
+/// payload with the same block hash to be rejected.
+#[tokio::test]
+async fn invalid_block_does_not_poison_payload_block_hash() {
+    let mut rig = InvalidPayloadRig::new();
+    rig.move_to_terminal_block();
+    rig.import_block(Payload::Valid).await; // Import a valid transition block.
+    rig.import_block(Payload::Syncing).await;
+
+    // Produce an honest block, then a forgery with a different graffiti that carries the exact
+    // same payload.
+    let head = rig.harness.chain.head_snapshot();
+    let state = head.beacon_state.clone_with_only_committee_caches();
+    let slot = state.slot() + 1;
+    let (honest_block, pre_state) = rig.harness.make_block_return_pre_state(state, slot).await;
+    let honest_root = honest_block.canonical_root();
+
+    let (mut forged_block, _) = honest_block.clone().deconstruct();
+    *forged_block.body_mut().graffiti_mut() = Graffiti::from([42; 32]);
+    let mut forged_state = pre_state.clone();
+    per_block_processing(
+        &mut forged_state,
+        &SignedBeaconBlock::from_block(forged_block.clone(), Signature::empty()),
+        BlockSignatureStrategy::NoVerification,
+        StateProcessingStrategy::Accurate,
+        VerifyBlockRoot::True,
+        &mut ConsensusContext::new(slot),
+        &rig.harness.chain.spec,
+    )
+    .unwrap();
+    *forged_block.state_root_mut() = forged_state.update_tree_hash_cache().unwrap();
+    let forged_block = Arc::new(rig.harness.sign_beacon_block(forged_block, &pre_state));
+    let forged_root = forged_block.canonical_root();
+    assert_ne!(forged_root, honest_root);
+    assert_eq!(
+        forged_block
+            .message()
+            .execution_payload()
+            .unwrap()
+            .block_hash(),
+        honest_block
+            .message()
+            .execution_payload()
+            .unwrap()
+            .block_hash()
+    );
+
+    // Have the EE reject the forgery.
+    let mock_execution_layer = rig.harness.mock_execution_layer.as_ref().unwrap();
+    mock_execution_layer
+        .server
+        .all_payloads_invalid_block_hash_on_new_payload();
+    rig.harness.set_current_slot(slot);
+    assert!(matches!(
+        rig.harness
+            .chain
+            .process_block(forged_root, forged_block, NotifyExecutionLayer::Yes, || Ok(
+                ()
+            ),)
+            .await,
+        Err(BlockError::ExecutionPayloadError(
+            ExecutionPayloadError::RejectedByExecutionEngine { .. }
+        ))
+    ));
+    let invalid_payload_cache = &rig.harness.chain.invalid_payload_cache;
+    assert!(invalid_payload_cache.contains(&forged_root));
+    assert!(!invalid_payload_cache.contains(&honest_root));
+
+    // The honest block is still imported once the EE accepts its payload.
+    mock_execution_layer
+        .server
+        .all_payloads_syncing_on_new_payload(true);
+    rig.harness
+        .process_block(slot, honest_root, honest_block)
+        .await
+        .unwrap();
+    assert!(rig.execution_status(honest_root).is_strictly_optimistic());
+}
+
 /// Tests to ensure that we will still send a proposer preparation
 #[tokio::test]
 async fn payload_preparation_before_transition_block() {
@@ -8,6 +8,7 @@ use beacon_chain::test_utils::{
 use beacon_chain::{
     test_utils::{AttestationStrategy, BlockStrategy, RelativeSyncCommittee},
     types::{Epoch, EthSpec, Keypair, MinimalEthSpec},
+    BeaconChainError,
 };
 use eth2::lighthouse::attestation_rewards::TotalAttestationRewards;
 use eth2::lighthouse::StandardAttestationRewards;
@@ -167,6 +168,29 @@ async fn test_verify_attestation_rewards_base() {
     assert_eq!(expected_balances, balances);
 }
 
+#[tokio::test]
+async fn test_attestation_rewards_incomplete_epoch() {
+    let harness = get_harness(E::default_spec());
+
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // The rewards for the current and previous epochs are not known until the end of the
+    // following epoch.
+    let current_epoch = harness.get_current_slot().epoch(E::slots_per_epoch());
+    for epoch in [current_epoch, current_epoch - 1] {
+        assert!(matches!(
+            harness.chain.compute_attestation_rewards(epoch, vec![]),
+            Err(BeaconChainError::AttestationRewardsEpochIncomplete { .. })
+        ));
+    }
+}
+
 #[tokio::test]
 async fn test_verify_attestation_rewards_base_inactivity_leak() {
     let spec = E::default_spec();
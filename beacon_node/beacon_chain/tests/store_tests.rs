@@ -1,6 +1,7 @@
 #![cfg(not(debug_assertions))]
 
 use beacon_chain::attestation_verification::Error as AttnError;
+use beacon_chain::beacon_chain::FORK_CHOICE_DB_KEY;
 use beacon_chain::builder::BeaconChainBuilder;
 use beacon_chain::schema_change::migrate_schema;
 use beacon_chain::test_utils::{
@@ -22,11 +23,12 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::Arc;
-use std::time::Duration;
-use store::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION};
+use std::time::{Duration, Instant};
+use store::database_stats::DATABASE_STATS_REFRESH_EPOCHS;
+use store::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION, STATE_UPPER_LIMIT_NO_RETAIN};
 use store::{
     iter::{BlockRootsIterator, StateRootsIterator},
-    HotColdDB, LevelDB, StoreConfig,
+    DBColumn, HotColdDB, KeyValueStore, LevelDB, StoreConfig, StoreOp,
 };
 use tempfile::{tempdir, TempDir};
 use tokio::time::sleep;
@@ -63,6 +65,24 @@ fn get_store_with_spec(
         .expect("disk store should initialize")
 }
 
+fn open_store_with_config(
+    db_path: &TempDir,
+    config: StoreConfig,
+) -> Result<Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>, store::Error> {
+    let hot_path = db_path.path().join("hot_db");
+    let cold_path = db_path.path().join("cold_db");
+    let log = test_logger();
+
+    HotColdDB::open(
+        &hot_path,
+        &cold_path,
+        |_, _, _| Ok(()),
+        config,
+        test_spec::<E>(),
+        log,
+    )
+}
+
 fn get_harness(
     store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
     validator_count: usize,
@@ -2038,6 +2058,134 @@ async fn garbage_collect_temp_states_from_failed_block() {
     assert_eq!(store.iter_temporary_state_roots().count(), 0);
 }
 
+#[tokio::test]
+async fn garbage_collect_abandoned_states_after_crash() {
+    let db_path = tempdir().unwrap();
+
+    let (mut store, slot_clock, head_state_root, orphan_roots) = {
+        let store = get_store(&db_path);
+        let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+        harness
+            .extend_chain(
+                E::slots_per_epoch() as usize * 2,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+        let head_state_root = harness.chain.head_snapshot().beacon_state_root();
+
+        // Simulate a crash mid-import: the post-state of a block is written with its temporary
+        // flag, but the block never makes it into fork choice.
+        let next_slot = harness.chain.slot().unwrap() + 1;
+        let (temp_block, temp_state) = harness
+            .make_block(harness.get_current_state(), next_slot)
+            .await;
+        let temp_state_root = temp_block.state_root();
+        store
+            .do_atomically(vec![
+                StoreOp::PutState(temp_state_root, &temp_state),
+                StoreOp::PutStateTemporaryFlag(temp_state_root),
+            ])
+            .unwrap();
+
+        // A state for a block which was never imported, without a temporary flag.
+        let (orphan_block, orphan_state) = harness
+            .make_block(harness.get_current_state(), next_slot)
+            .await;
+        let orphan_state_root = orphan_block.state_root();
+        store.put_state(&orphan_state_root, &orphan_state).unwrap();
+
+        assert_eq!(store.iter_temporary_state_roots().count(), 1);
+
+        harness
+            .chain
+            .persist_head_and_fork_choice()
+            .expect("should persist the head and fork choice");
+
+        (
+            store,
+            harness.chain.slot_clock.clone(),
+            head_state_root,
+            vec![temp_state_root, orphan_state_root],
+        )
+    };
+
+    // Wait until all the references to the store have been dropped, so that it can be re-opened.
+    loop {
+        store = if let Err(store_arc) = Arc::try_unwrap(store) {
+            sleep(Duration::from_millis(500)).await;
+            store_arc
+        } else {
+            break;
+        }
+    }
+
+    let store = get_store(&db_path);
+    assert_eq!(store.iter_temporary_state_roots().count(), 0);
+
+    let resumed_harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS[0..LOW_VALIDATOR_COUNT].to_vec())
+        .resumed_disk_store(store.clone())
+        .testing_slot_clock(slot_clock)
+        .mock_execution_layer()
+        .build();
+
+    // Abandoned states are deleted on startup, while the head state is retained.
+    for state_root in &orphan_roots {
+        assert!(store.load_hot_state_summary(state_root).unwrap().is_none());
+    }
+    assert_eq!(
+        resumed_harness.chain.head_snapshot().beacon_state_root(),
+        head_state_root
+    );
+    assert!(store.get_state(&head_state_root, None).unwrap().is_some());
+}
+
+#[tokio::test]
+async fn database_stats() {
+    let num_blocks_produced = E::slots_per_epoch() * 2;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let epoch = harness.chain.epoch().unwrap();
+    assert!(store.cached_database_stats().is_none());
+    let stats = store.database_stats(epoch).unwrap();
+    assert!(Arc::ptr_eq(&stats, &store.cached_database_stats().unwrap()));
+    assert_eq!(stats.epoch, epoch);
+
+    // Every block (including genesis) is in the hot database.
+    let blocks = stats
+        .hot
+        .iter()
+        .find(|column| column.column == "BeaconBlock")
+        .expect("block column should be non-empty");
+    assert_eq!(blocks.key_count, num_blocks_produced + 1);
+    assert!(blocks.approximate_size > 0);
+    assert!(stats.hot.iter().all(|column| column.key_count > 0));
+
+    // The statistics are cached until the refresh interval has elapsed.
+    let next_refresh = epoch + DATABASE_STATS_REFRESH_EPOCHS;
+    assert!(Arc::ptr_eq(
+        &stats,
+        &store.database_stats(next_refresh - 1).unwrap()
+    ));
+    assert!(!Arc::ptr_eq(
+        &stats,
+        &store.database_stats(next_refresh).unwrap()
+    ));
+}
+
 #[tokio::test]
 async fn weak_subjectivity_sync_easy() {
     let num_initial_slots = E::slots_per_epoch() * 11;
@@ -2282,11 +2430,180 @@ async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
     // Anchor slot is still set to the slot of the checkpoint block.
     assert_eq!(store.get_anchor_slot(), Some(wss_block.slot()));
 
-    // Reconstruct states.
-    store.clone().reconstruct_historic_states().unwrap();
+    // Reconstruct states in batches, checking that progress is persisted between batches.
+    let mut prev_lower_limit = store.get_anchor_info().unwrap().state_lower_limit;
+    loop {
+        store
+            .clone()
+            .reconstruct_historic_states(Some(E::slots_per_epoch() as usize))
+            .unwrap();
+        match store.get_anchor_info() {
+            Some(anchor) => {
+                assert!(anchor.state_lower_limit > prev_lower_limit);
+                prev_lower_limit = anchor.state_lower_limit;
+            }
+            None => break,
+        }
+    }
     assert_eq!(store.get_anchor_slot(), None);
 }
 
+#[tokio::test]
+async fn prune_historic_states_and_reconstruct() {
+    let num_blocks_produced = E::slots_per_epoch() * 5;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let split_slot = store.get_split_slot();
+    assert!(split_slot > 0);
+    let frozen_states = harness
+        .chain
+        .forwards_iter_state_roots(Slot::new(1))
+        .unwrap()
+        .map(Result::unwrap)
+        .take_while(|(_, slot)| *slot < split_slot)
+        .collect::<Vec<_>>();
+    assert!(!frozen_states.is_empty());
+
+    let mut genesis_state = store
+        .load_cold_state_by_slot(Slot::new(0))
+        .unwrap()
+        .unwrap();
+    let genesis_state_root = genesis_state.update_tree_hash_cache().unwrap();
+
+    store
+        .prune_historic_states(genesis_state_root, &genesis_state)
+        .unwrap();
+
+    // Frozen states are gone, like on a checkpoint-synced node, while the genesis state and all
+    // blocks are retained.
+    let anchor = store.get_anchor_info().unwrap();
+    assert_eq!(anchor.state_upper_limit, STATE_UPPER_LIMIT_NO_RETAIN);
+    assert_eq!(anchor.oldest_block_slot, 0);
+    for (state_root, slot) in &frozen_states {
+        assert_eq!(store.get_state(state_root, Some(*slot)).unwrap(), None);
+        assert_eq!(store.load_cold_state_by_slot(*slot).unwrap(), None);
+    }
+    assert_eq!(
+        store
+            .get_state(&genesis_state_root, None)
+            .unwrap()
+            .unwrap()
+            .canonical_root(),
+        genesis_state_root
+    );
+
+    // Pruning again is a no-op.
+    let genesis_state = store
+        .load_cold_state_by_slot(Slot::new(0))
+        .unwrap()
+        .unwrap();
+    store
+        .prune_historic_states(genesis_state_root, &genesis_state)
+        .unwrap();
+    assert_eq!(store.get_anchor_info(), Some(anchor));
+
+    // States can be regenerated by reconstruction.
+    store.resume_historic_state_retention().unwrap();
+    store.clone().reconstruct_historic_states(None).unwrap();
+    assert_eq!(store.get_anchor_info(), None);
+    for (state_root, slot) in &frozen_states {
+        let state = store.get_state(state_root, Some(*slot)).unwrap().unwrap();
+        assert_eq!(state.canonical_root(), *state_root);
+    }
+    check_chain_dump(&harness, num_blocks_produced + 1);
+    check_iterators(&harness);
+}
+
+#[tokio::test]
+async fn restore_point_migration() {
+    let num_blocks_produced = E::slots_per_epoch() * 10;
+    let db_path = tempdir().unwrap();
+    let store_config = |slots_per_restore_point, migrate| StoreConfig {
+        slots_per_restore_point,
+        slots_per_restore_point_set_explicitly: true,
+        slots_per_restore_point_migrate: migrate,
+        ..StoreConfig::default()
+    };
+    let store = open_store_with_config(&db_path, store_config(16, false)).unwrap();
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let split_slot = store.get_split_slot();
+    assert!(split_slot > 32);
+    let frozen_states = harness
+        .chain
+        .forwards_iter_state_roots(Slot::new(0))
+        .unwrap()
+        .map(Result::unwrap)
+        .take_while(|(_, slot)| *slot < split_slot)
+        .collect::<Vec<_>>();
+
+    drop(store);
+    drop(harness);
+
+    let check_frozen_states = |store: &HotColdDB<E, LevelDB<E>, LevelDB<E>>| {
+        for (state_root, slot) in &frozen_states {
+            let state = store.get_state(state_root, Some(*slot)).unwrap().unwrap();
+            assert_eq!(state.canonical_root(), *state_root);
+        }
+    };
+
+    // A different spacing is refused without the migrate flag.
+    assert!(matches!(
+        open_store_with_config(&db_path, store_config(8, false)),
+        Err(store::Error::ConfigError(_))
+    ));
+
+    // Densify, interrupting the migration part way through.
+    let store = open_store_with_config(&db_path, store_config(8, true)).unwrap();
+    assert_eq!(store.slots_per_restore_point(), 16);
+    store.migrate_restore_points(Some(2)).unwrap();
+    let migration = store.get_restore_point_migration().unwrap().unwrap();
+    assert_eq!((migration.from, migration.to), (16, 8));
+    assert_eq!(migration.next_slot, 16);
+    check_frozen_states(&store);
+    drop(store);
+
+    let store = open_store_with_config(&db_path, store_config(8, true)).unwrap();
+    assert_eq!(
+        store.get_restore_point_migration().unwrap(),
+        Some(migration)
+    );
+    store.migrate_restore_points(None).unwrap();
+    assert_eq!(store.get_restore_point_migration().unwrap(), None);
+    assert_eq!(store.slots_per_restore_point(), 8);
+    check_frozen_states(&store);
+    drop(store);
+
+    // The new spacing is persisted.
+    let store = open_store_with_config(&db_path, store_config(8, false)).unwrap();
+    check_frozen_states(&store);
+    drop(store);
+
+    // Sparsify.
+    let store = open_store_with_config(&db_path, store_config(32, true)).unwrap();
+    store.migrate_restore_points(None).unwrap();
+    assert_eq!(store.slots_per_restore_point(), 32);
+    check_frozen_states(&store);
+}
+
 /// Test that blocks and attestations that refer to states around an unaligned split state are
 /// processed correctly.
 #[tokio::test]
@@ -2414,6 +2731,298 @@ async fn process_blocks_and_attestations_for_unaligned_checkpoint() {
     }
 }
 
+#[tokio::test]
+async fn fork_choice_restored_verbatim_on_restart() {
+    let num_blocks_produced = E::slots_per_epoch() * 384;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let original_chain = harness.chain.clone();
+    let slot_clock = original_chain.slot_clock.clone();
+    let (head_root, justified, finalized, unrealized_justified, proposer_boost_root, num_nodes) = {
+        let fork_choice = original_chain.canonical_head.fork_choice_read_lock();
+        (
+            fork_choice.get_forkchoice_update_parameters().head_root,
+            fork_choice.justified_checkpoint(),
+            fork_choice.finalized_checkpoint(),
+            fork_choice.unrealized_justified_checkpoint(),
+            fork_choice.proposer_boost_root(),
+            fork_choice.proto_array().len(),
+        )
+    };
+
+    // Head changes are only persisted periodically, the latest fork choice is persisted when the
+    // chain is dropped at shutdown.
+    drop(harness);
+    wait_for_drop(original_chain).await;
+
+    let resume = |store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>| {
+        BeaconChainHarness::builder(MinimalEthSpec)
+            .default_spec()
+            .keypairs(KEYPAIRS[0..LOW_VALIDATOR_COUNT].to_vec())
+            .resumed_disk_store(store)
+            .testing_slot_clock(slot_clock.clone())
+            .mock_execution_layer()
+            .build()
+    };
+
+    let timer = Instant::now();
+    let restored = resume(store.clone());
+    let restore_time = timer.elapsed();
+    {
+        let fork_choice = restored.chain.canonical_head.fork_choice_read_lock();
+        assert_eq!(
+            fork_choice.get_forkchoice_update_parameters().head_root,
+            head_root
+        );
+        assert_eq!(fork_choice.justified_checkpoint(), justified);
+        assert_eq!(fork_choice.finalized_checkpoint(), finalized);
+        assert_eq!(
+            fork_choice.unrealized_justified_checkpoint(),
+            unrealized_justified
+        );
+        assert_eq!(fork_choice.proposer_boost_root(), proposer_boost_root);
+        assert_eq!(fork_choice.proto_array().len(), num_nodes);
+    }
+
+    // Wait until the restored chain has been dropped, so that it doesn't overwrite the
+    // persisted fork choice below.
+    let restored_chain = restored.chain.clone();
+    drop(restored);
+    wait_for_drop(restored_chain).await;
+
+    // An incompatible persisted fork choice is rebuilt from the finalized checkpoint towards the
+    // persisted canonical head.
+    store
+        .hot_db
+        .put_bytes(
+            DBColumn::ForkChoice.into(),
+            FORK_CHOICE_DB_KEY.as_bytes(),
+            &[0xff; 4],
+        )
+        .unwrap();
+
+    let timer = Instant::now();
+    let rebuilt = resume(store);
+    let rebuild_time = timer.elapsed();
+    {
+        let fork_choice = rebuilt.chain.canonical_head.fork_choice_read_lock();
+        assert_eq!(
+            fork_choice.get_forkchoice_update_parameters().head_root,
+            head_root
+        );
+        assert_eq!(fork_choice.finalized_checkpoint(), finalized);
+    }
+
+    println!(
+        "restart with {} blocks: fork choice restored in {:?}, rebuilt in {:?}",
+        num_blocks_produced, restore_time, rebuild_time
+    );
+}
+
+/// Wait until all other references to `chain` have been dropped, then drop it.
+async fn wait_for_drop<T: BeaconChainTypes>(mut chain: Arc<BeaconChain<T>>) {
+    loop {
+        chain = match Arc::try_unwrap(chain) {
+            Ok(chain) => {
+                drop(chain);
+                return;
+            }
+            Err(chain) => {
+                sleep(Duration::from_millis(500)).await;
+                chain
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn op_pool_survives_restart() {
+    let validator_count = 16;
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 2;
+
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    let harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS[0..validator_count].to_vec())
+        .fresh_disk_store(store.clone())
+        .mock_execution_layer()
+        .build();
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    harness.add_proposer_slashing(1).unwrap();
+    harness.add_attester_slashing(vec![2, 3]).unwrap();
+    assert!(harness.chain.op_pool.num_attestations() > 0);
+
+    // Produce a block at the next slot from the pre-restart op pool, without importing it.
+    let next_slot = harness.chain.slot().unwrap() + 1;
+    let (original_block, _) = harness
+        .make_block(harness.get_current_state(), next_slot)
+        .await;
+
+    harness
+        .chain
+        .persist_head_and_fork_choice()
+        .expect("should persist the head and fork choice");
+    harness
+        .chain
+        .persist_op_pool()
+        .expect("should persist the op pool");
+    harness
+        .chain
+        .persist_eth1_cache()
+        .expect("should persist the eth1 cache");
+
+    let original_chain = harness.chain;
+
+    let resumed_harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS[0..validator_count].to_vec())
+        .resumed_disk_store(store)
+        .testing_slot_clock(original_chain.slot_clock.clone())
+        .mock_execution_layer()
+        .build();
+
+    assert_chains_pretty_much_the_same(&original_chain, &resumed_harness.chain);
+
+    let (resumed_block, _) = resumed_harness
+        .make_block(resumed_harness.get_current_state(), next_slot)
+        .await;
+
+    // Operations are packed by max-cover, which may break ties differently, so compare the
+    // operations included in each block irrespective of order.
+    let operation_roots = |block: &SignedBeaconBlock<E>| {
+        let body = block.message().body();
+        let mut roots = body
+            .attestations()
+            .iter()
+            .map(|op| op.tree_hash_root())
+            .chain(
+                body.attester_slashings()
+                    .iter()
+                    .map(|op| op.tree_hash_root()),
+            )
+            .chain(
+                body.proposer_slashings()
+                    .iter()
+                    .map(|op| op.tree_hash_root()),
+            )
+            .chain(body.voluntary_exits().iter().map(|op| op.tree_hash_root()))
+            .collect::<Vec<_>>();
+        roots.sort();
+        roots
+    };
+    let original_body = original_block.message().body();
+    assert_eq!(original_body.proposer_slashings().len(), 1);
+    assert_eq!(original_body.attester_slashings().len(), 1);
+    assert!(!original_body.attestations().is_empty());
+    assert_eq!(
+        operation_roots(&original_block),
+        operation_roots(&resumed_block)
+    );
+    assert_eq!(
+        original_body.sync_aggregate().ok(),
+        resumed_block.message().body().sync_aggregate().ok()
+    );
+}
+
+#[tokio::test]
+async fn proposer_preparation_survives_restart() {
+    let validator_count = 16;
+    let mut spec = test_spec::<E>();
+    spec.altair_fork_epoch = Some(Epoch::new(0));
+    spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+
+    let db_path = tempdir().unwrap();
+    let store = get_store_with_spec(&db_path, spec.clone());
+
+    let harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .spec(spec.clone())
+        .keypairs(KEYPAIRS[0..validator_count].to_vec())
+        .fresh_disk_store(store.clone())
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Prepare every validator with a fee recipient distinct from the harness default.
+    let fee_recipient = Address::repeat_byte(0x77);
+    let preparation_data = (0..validator_count as u64)
+        .map(|validator_index| ProposerPreparationData {
+            validator_index,
+            fee_recipient,
+        })
+        .collect::<Vec<_>>();
+    harness
+        .chain
+        .execution_layer
+        .as_ref()
+        .unwrap()
+        .update_proposer_preparation(harness.chain.epoch().unwrap(), &preparation_data)
+        .await;
+
+    // The change is flagged so that it is persisted by the next `per_slot_task`, and is only
+    // flagged once.
+    let execution_layer = harness.chain.execution_layer.as_ref().unwrap();
+    assert!(execution_layer.take_proposer_preparation_changed());
+    assert!(!execution_layer.take_proposer_preparation_changed());
+    harness
+        .chain
+        .persist_proposer_preparation()
+        .await
+        .expect("should persist proposer preparation data");
+    harness
+        .chain
+        .persist_head_and_fork_choice()
+        .expect("should persist the head and fork choice");
+
+    let original_chain = harness.chain;
+
+    let resumed_harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .spec(spec)
+        .keypairs(KEYPAIRS[0..validator_count].to_vec())
+        .resumed_disk_store(store)
+        .testing_slot_clock(original_chain.slot_clock.clone())
+        .mock_execution_layer()
+        .build();
+
+    let next_slot = resumed_harness.chain.slot().unwrap() + 1;
+    let (block, _) = resumed_harness
+        .make_block(resumed_harness.get_current_state(), next_slot)
+        .await;
+    let payload = block
+        .message()
+        .body()
+        .execution_payload()
+        .expect("block should have a payload");
+    assert_eq!(payload.fee_recipient(), fee_recipient);
+}
+
 #[tokio::test]
 async fn finalizes_after_resuming_from_db() {
     let validator_count = 16;
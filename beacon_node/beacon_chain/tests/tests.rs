@@ -3,18 +3,25 @@
 use beacon_chain::{
     attestation_verification::Error as AttnError,
     test_utils::{
-        AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
+        test_spec, AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
         OP_POOL_DB_KEY,
     },
-    BeaconChain, ChainConfig, NotifyExecutionLayer, StateSkipConfig, WhenSlotSkipped,
+    BeaconChain, BeaconChainError, BlockError, ChainConfig, NotifyExecutionLayer, StateSkipConfig,
+    WhenSlotSkipped,
 };
 use lazy_static::lazy_static;
 use operation_pool::PersistedOperationPool;
+use parking_lot::RwLock;
 use state_processing::{
     per_slot_processing, per_slot_processing::Error as SlotProcessingError, EpochProcessingError,
+    VerifyOperation,
 };
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use types::{
-    BeaconState, BeaconStateError, EthSpec, Hash256, Keypair, MinimalEthSpec, RelativeEpoch, Slot,
+    AttestationShufflingId, BeaconState, BeaconStateError, Checkpoint, Epoch, EthSpec, Hash256,
+    Keypair, MinimalEthSpec, RelativeEpoch, Slot,
 };
 
 // Should ideally be divisible by 3.
@@ -482,6 +489,79 @@ async fn roundtrip_operation_pool() {
     assert_eq!(harness.chain.op_pool, restored_op_pool);
 }
 
+#[tokio::test]
+async fn reorged_exit_is_reinserted_into_op_pool() {
+    let mut spec = test_spec::<MinimalEthSpec>();
+    // Allow validators to exit immediately.
+    spec.shard_committee_period = 0;
+    let get_harness = || {
+        let harness = BeaconChainHarness::builder(MinimalEthSpec)
+            .spec(spec.clone())
+            .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+            .fresh_ephemeral_store()
+            .mock_execution_layer()
+            .build();
+        harness.advance_slot();
+        harness
+    };
+    let harness = get_harness();
+    let other_harness = get_harness();
+
+    // Have another node produce a block at slot 1 containing an exit which was never in our op
+    // pool.
+    let exit = harness.make_voluntary_exit(VALIDATOR_COUNT as u64 - 1, Epoch::new(0));
+    other_harness.chain.import_voluntary_exit(
+        exit.clone()
+            .validate(&other_harness.get_current_state(), &spec)
+            .unwrap(),
+    );
+    let (genesis_state, genesis_state_root) = harness.get_current_state_and_root();
+    let (orphan_block, _) = other_harness
+        .make_block(genesis_state.clone(), Slot::new(1))
+        .await;
+    assert_eq!(orphan_block.message().body().voluntary_exits().len(), 1);
+
+    let orphan_root = orphan_block.canonical_root();
+    harness
+        .process_block(Slot::new(1), orphan_root, orphan_block)
+        .await
+        .unwrap();
+    assert_eq!(harness.head_block_root(), orphan_root);
+    assert_eq!(harness.chain.op_pool.num_voluntary_exits(), 0);
+
+    // Build a competing block at slot 2 which skips the block at slot 1 and is attested to by
+    // all validators, re-orging out the block containing the exit.
+    harness
+        .add_attested_blocks_at_slots(
+            genesis_state,
+            genesis_state_root,
+            &[Slot::new(2)],
+            &harness.get_all_validators(),
+        )
+        .await;
+    harness.advance_slot();
+    harness.chain.recompute_head_at_current_slot().await;
+    assert_ne!(harness.head_block_root(), orphan_root);
+
+    // The exit is re-inserted on a background task after the head changes.
+    for _ in 0..100 {
+        if harness.chain.op_pool.num_voluntary_exits() > 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(harness.chain.op_pool.num_voluntary_exits(), 1);
+
+    // The exit should be included in the next block.
+    let (block, _) = harness
+        .make_block(harness.get_current_state(), harness.chain.slot().unwrap())
+        .await;
+    assert_eq!(
+        block.message().body().voluntary_exits().to_vec(),
+        vec![exit]
+    );
+}
+
 #[tokio::test]
 async fn unaggregated_attestations_added_to_fork_choice_some_none() {
     let num_blocks_produced = MinimalEthSpec::slots_per_epoch() / 2;
@@ -872,3 +952,210 @@ async fn block_roots_skip_slot_behaviour() {
         "WhenSlotSkipped::Prev should return None on a future slot"
     );
 }
+
+#[tokio::test]
+async fn verify_weak_subjectivity_checkpoint() {
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
+
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let head = harness.chain.head_snapshot();
+    let finalized_checkpoint = head.beacon_state.finalized_checkpoint();
+    assert!(finalized_checkpoint.epoch > 1, "test precondition");
+
+    let verify = |wss_checkpoint| {
+        harness.chain.verify_weak_subjectivity_checkpoint(
+            wss_checkpoint,
+            head.beacon_block_root,
+            &head.beacon_state,
+        )
+    };
+
+    // The finalized checkpoint itself passes.
+    assert!(verify(finalized_checkpoint).is_ok());
+
+    // A checkpoint which is an ancestor of the finalized checkpoint passes.
+    let ancestor_epoch = finalized_checkpoint.epoch - 1;
+    let ancestor_root = harness
+        .chain
+        .block_root_at_slot(
+            ancestor_epoch.start_slot(MinimalEthSpec::slots_per_epoch()),
+            WhenSlotSkipped::Prev,
+        )
+        .unwrap()
+        .unwrap();
+    assert!(verify(Checkpoint {
+        epoch: ancestor_epoch,
+        root: ancestor_root,
+    })
+    .is_ok());
+
+    // A conflicting root at either epoch fails.
+    for epoch in [finalized_checkpoint.epoch, ancestor_epoch] {
+        assert!(matches!(
+            verify(Checkpoint {
+                epoch,
+                root: Hash256::repeat_byte(0xff),
+            }),
+            Err(BeaconChainError::WeakSubjectivtyVerificationFailure)
+        ));
+    }
+
+    // A checkpoint which is not yet finalized cannot be checked, so it passes.
+    assert!(verify(Checkpoint {
+        epoch: finalized_checkpoint.epoch + 1,
+        root: Hash256::repeat_byte(0xff),
+    })
+    .is_ok());
+}
+
+#[tokio::test]
+async fn shuts_down_on_weak_subjectivity_conflict() {
+    let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 5;
+
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // A node configured with a weak subjectivity checkpoint which conflicts with the chain above.
+    let wss_checkpoint = Checkpoint {
+        epoch: Epoch::new(2),
+        root: Hash256::repeat_byte(0xff),
+    };
+    let wss_harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .default_spec()
+        .chain_config(ChainConfig {
+            weak_subjectivity_checkpoint: Some(wss_checkpoint),
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    // Import blocks until the weak subjectivity epoch is finalized, which should fail.
+    let chain_dump = harness.chain.chain_dump().unwrap();
+    let mut import_error = None;
+    for snapshot in chain_dump.iter().skip(1) {
+        let block = harness
+            .chain
+            .get_block(&snapshot.beacon_block_root)
+            .await
+            .unwrap()
+            .unwrap();
+        wss_harness.chain.slot_clock.set_slot(block.slot().as_u64());
+        match wss_harness
+            .chain
+            .process_block(
+                snapshot.beacon_block_root,
+                Arc::new(block),
+                NotifyExecutionLayer::Yes,
+                || Ok(()),
+            )
+            .await
+        {
+            Ok(_) => wss_harness.chain.recompute_head_at_current_slot().await,
+            Err(e) => {
+                import_error = Some(e);
+                break;
+            }
+        }
+    }
+
+    assert!(matches!(
+        import_error,
+        Some(BlockError::WeakSubjectivityConflict)
+    ));
+    assert!(
+        wss_harness
+            .chain
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint()
+            .epoch
+            < wss_checkpoint.epoch
+    );
+    assert_eq!(wss_harness.shutdown_reasons().len(), 1);
+}
+
+/// Concurrent requests for a committee cache that is not in the shuffling cache should wait on a
+/// single computation, rather than each computing it.
+#[test]
+fn concurrent_committee_cache_requests_build_once() {
+    let num_requests = 8;
+    let harness = get_harness(VALIDATOR_COUNT);
+    let chain = harness.chain.clone();
+
+    let mut state = harness.get_current_state();
+    state
+        .build_committee_cache(RelativeEpoch::Current, &chain.spec)
+        .unwrap();
+    let committee_cache = Arc::new(
+        state
+            .committee_cache(RelativeEpoch::Current)
+            .unwrap()
+            .clone(),
+    );
+    let shuffling_id = AttestationShufflingId {
+        shuffling_epoch: state.current_epoch(),
+        shuffling_decision_block: Hash256::repeat_byte(42),
+    };
+
+    // Every build blocks until the gate is opened, so the first request is still computing the
+    // committee cache when the others arrive.
+    let gate = Arc::new(RwLock::new(()));
+    let gate_guard = gate.write();
+    let builds = Arc::new(AtomicUsize::new(0));
+
+    let handles = (0..num_requests)
+        .map(|_| {
+            let chain = chain.clone();
+            let shuffling_id = shuffling_id.clone();
+            let committee_cache = committee_cache.clone();
+            let gate = gate.clone();
+            let builds = builds.clone();
+            std::thread::spawn(move || {
+                chain
+                    .get_or_build_committee_cache(&shuffling_id, || {
+                        builds.fetch_add(1, Ordering::SeqCst);
+                        drop(gate.read());
+                        Ok(committee_cache)
+                    })
+                    .unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // Wait for a request to start building, then give the others time to find its promise.
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while builds.load(Ordering::SeqCst) == 0 {
+        assert!(Instant::now() < deadline, "no request built the committee");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    std::thread::sleep(Duration::from_millis(500));
+    assert_eq!(builds.load(Ordering::SeqCst), 1);
+
+    drop(gate_guard);
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), committee_cache);
+    }
+    assert_eq!(builds.load(Ordering::SeqCst), 1);
+    assert!(chain
+        .shuffling_cache
+        .try_read_for(Duration::from_secs(1))
+        .unwrap()
+        .contains(&shuffling_id));
+}
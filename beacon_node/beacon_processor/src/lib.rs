@@ -235,6 +235,8 @@ pub struct BeaconProcessorConfig {
     pub max_gossip_attestation_batch_size: usize,
     pub max_gossip_aggregate_batch_size: usize,
     pub enable_backfill_rate_limiting: bool,
+    /// If set, at most one backfill batch is processed every this many slots.
+    pub backfill_rate_limit_slots: Option<u64>,
 }
 
 impl Default for BeaconProcessorConfig {
@@ -246,6 +248,7 @@ impl Default for BeaconProcessorConfig {
             max_gossip_attestation_batch_size: DEFAULT_MAX_GOSSIP_ATTESTATION_BATCH_SIZE,
             max_gossip_aggregate_batch_size: DEFAULT_MAX_GOSSIP_AGGREGATE_BATCH_SIZE,
             enable_backfill_rate_limiting: true,
+            backfill_rate_limit_slots: None,
         }
     }
 }
@@ -799,6 +802,7 @@ impl<E: EthSpec> BeaconProcessor<E> {
             slot_clock,
             self.log.clone(),
             maximum_gossip_clock_disparity,
+            self.config.backfill_rate_limit_slots,
         )?;
 
         let executor = self.executor.clone();
@@ -240,6 +240,10 @@ struct ReprocessQueue<S> {
     attestation_delay_debounce: TimeLatch,
     lc_update_delay_debounce: TimeLatch,
     next_backfill_batch_event: Option<Pin<Box<tokio::time::Sleep>>>,
+    /// If set, at most one backfill batch is processed every this many slots.
+    backfill_rate_limit_slots: Option<u64>,
+    /// The slot in which the last backfill batch was sent for processing.
+    last_backfill_batch_slot: Option<Slot>,
     slot_clock: Pin<Box<S>>,
 }
 
@@ -332,6 +336,9 @@ impl<S: SlotClock> Stream for ReprocessQueue<S> {
             match next_backfill_batch_event.as_mut().poll(cx) {
                 Poll::Ready(_) => {
                     let maybe_batch = self.queued_backfill_batches.pop();
+                    if maybe_batch.is_some() {
+                        self.last_backfill_batch_slot = self.slot_clock.now();
+                    }
                     self.recompute_next_backfill_batch_event();
 
                     if let Some(batch) = maybe_batch {
@@ -362,6 +369,7 @@ pub fn spawn_reprocess_scheduler<S: SlotClock + 'static>(
     slot_clock: S,
     log: Logger,
     maximum_gossip_clock_disparity: Duration,
+    backfill_rate_limit_slots: Option<u64>,
 ) -> Result<(), String> {
     // Sanity check
     if ADDITIONAL_QUEUED_BLOCK_DELAY >= maximum_gossip_clock_disparity {
@@ -388,6 +396,8 @@ pub fn spawn_reprocess_scheduler<S: SlotClock + 'static>(
         attestation_delay_debounce: TimeLatch::default(),
         lc_update_delay_debounce: TimeLatch::default(),
         next_backfill_batch_event: None,
+        backfill_rate_limit_slots,
+        last_backfill_batch_slot: None,
         slot_clock: Box::pin(slot_clock.clone()),
     };
 
@@ -918,9 +928,20 @@ impl<S: SlotClock> ReprocessQueue<S> {
     fn recompute_next_backfill_batch_event(&mut self) {
         // only recompute the `next_backfill_batch_event` if there are backfill batches in the queue
         if !self.queued_backfill_batches.is_empty() {
-            self.next_backfill_batch_event = Some(Box::pin(tokio::time::sleep(
-                ReprocessQueue::<S>::duration_until_next_backfill_batch_event(&self.slot_clock),
-            )));
+            let duration = self
+                .backfill_rate_limit_slots
+                .zip(self.last_backfill_batch_slot)
+                .and_then(|(slots_per_batch, last_batch_slot)| {
+                    ReprocessQueue::<S>::duration_until_rate_limited_backfill_batch_event(
+                        &self.slot_clock,
+                        slots_per_batch,
+                        last_batch_slot,
+                    )
+                })
+                .unwrap_or_else(|| {
+                    ReprocessQueue::<S>::duration_until_next_backfill_batch_event(&self.slot_clock)
+                });
+            self.next_backfill_batch_event = Some(Box::pin(tokio::time::sleep(duration)));
         } else {
             self.next_backfill_batch_event = None
         }
@@ -953,6 +974,21 @@ impl<S: SlotClock> ReprocessQueue<S> {
             // If we can't read the slot clock, just wait another slot.
             .unwrap_or(slot_duration)
     }
+
+    /// Returns the duration until the first scheduled processing time of the slot which is
+    /// `slots_per_batch` slots after `last_batch_slot`, or `None` if that slot has already started
+    /// and the regular schedule applies.
+    fn duration_until_rate_limited_backfill_batch_event(
+        slot_clock: &S,
+        slots_per_batch: u64,
+        last_batch_slot: Slot,
+    ) -> Option<Duration> {
+        let (multiplier, divisor) = BACKFILL_SCHEDULE_IN_SLOT[0];
+        let first_event_time = (slot_clock.slot_duration() / divisor) * multiplier;
+        slot_clock
+            .duration_to_slot(last_batch_slot + slots_per_batch)
+            .map(|duration_to_slot| duration_to_slot + first_event_time)
+    }
 }
 
 #[cfg(test)]
@@ -998,4 +1034,39 @@ mod tests {
             duration_to_next_slot + event_times[0]
         );
     }
+
+    #[test]
+    fn rate_limited_backfill_processing_schedule_calculation() {
+        let slot_duration = Duration::from_secs(12);
+        let slot_clock = TestingSlotClock::new(Slot::new(0), Duration::from_secs(0), slot_duration);
+        let (multiplier, divisor) = BACKFILL_SCHEDULE_IN_SLOT[0];
+        let first_event_time = (slot_duration / divisor) * multiplier;
+
+        // A batch was processed in slot 100, the next may only be processed in slot 104.
+        let last_batch_slot = Slot::new(100);
+        slot_clock.set_current_time(slot_clock.start_of(Slot::new(101)).unwrap());
+        let duration_to_next_event =
+            ReprocessQueue::<TestingSlotClock>::duration_until_rate_limited_backfill_batch_event(
+                &slot_clock,
+                4,
+                last_batch_slot,
+            );
+        assert_eq!(
+            duration_to_next_event,
+            Some(slot_duration * 3 + first_event_time)
+        );
+
+        // Once the slot has started the regular schedule applies.
+        slot_clock.set_current_time(
+            slot_clock.start_of(Slot::new(104)).unwrap() + Duration::from_secs(1),
+        );
+        assert_eq!(
+            ReprocessQueue::<TestingSlotClock>::duration_until_rate_limited_backfill_batch_event(
+                &slot_clock,
+                4,
+                last_batch_slot,
+            ),
+            None
+        );
+    }
 }
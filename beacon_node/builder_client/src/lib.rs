@@ -20,12 +20,13 @@ pub const DEFAULT_GET_HEADER_TIMEOUT_MILLIS: u64 = 1000;
 /// Default user agent for HTTP requests.
 pub const DEFAULT_USER_AGENT: &str = lighthouse_version::VERSION;
 
-#[derive(Clone)]
+/// Timeouts for each of the builder API methods.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Timeouts {
-    get_header: Duration,
-    post_validators: Duration,
-    post_blinded_blocks: Duration,
-    get_builder_status: Duration,
+    pub get_header: Duration,
+    pub post_validators: Duration,
+    pub post_blinded_blocks: Duration,
+    pub get_builder_status: Duration,
 }
 
 impl Default for Timeouts {
@@ -48,13 +49,17 @@ pub struct BuilderHttpClient {
 }
 
 impl BuilderHttpClient {
-    pub fn new(server: SensitiveUrl, user_agent: Option<String>) -> Result<Self, Error> {
+    pub fn new(
+        server: SensitiveUrl,
+        user_agent: Option<String>,
+        timeouts: Timeouts,
+    ) -> Result<Self, Error> {
         let user_agent = user_agent.unwrap_or(DEFAULT_USER_AGENT.to_string());
         let client = reqwest::Client::builder().user_agent(&user_agent).build()?;
         Ok(Self {
             client,
             server,
-            timeouts: Timeouts::default(),
+            timeouts,
             user_agent,
         })
     }
@@ -63,6 +68,10 @@ impl BuilderHttpClient {
         &self.user_agent
     }
 
+    pub fn get_timeouts(&self) -> &Timeouts {
+        &self.timeouts
+    }
+
     async fn get_with_timeout<T: DeserializeOwned, U: IntoUrl>(
         &self,
         url: U,
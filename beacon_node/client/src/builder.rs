@@ -10,7 +10,7 @@ use beacon_chain::{
     eth1_chain::{CachingEth1Backend, Eth1Chain},
     slot_clock::{SlotClock, SystemTimeSlotClock},
     state_advance_timer::spawn_state_advance_timer,
-    store::{HotColdDB, ItemStore, LevelDB, StoreConfig},
+    store::{config::StoreConfigError, HotColdDB, ItemStore, LevelDB, StoreConfig},
     BeaconChain, BeaconChainTypes, Eth1ChainBackend, MigratorConfig, ServerSentEventHandler,
 };
 use beacon_processor::BeaconProcessorConfig;
@@ -18,7 +18,7 @@ use beacon_processor::{BeaconProcessor, BeaconProcessorChannels};
 use environment::RuntimeContext;
 use eth1::{Config as Eth1Config, Service as Eth1Service};
 use eth2::{
-    types::{BlockId, StateId},
+    types::{BlockId, GenesisData, StateId},
     BeaconNodeHttpClient, Error as ApiError, Timeouts,
 };
 use execution_layer::ExecutionLayer;
@@ -37,12 +37,21 @@ use timer::spawn_timer;
 use tokio::sync::oneshot;
 use types::{
     test_utils::generate_deterministic_keypairs, BeaconState, ChainSpec, EthSpec,
-    ExecutionBlockHash, Hash256, SignedBeaconBlock,
+    ExecutionBlockHash, Fork, Hash256, SignedBeaconBlock,
 };
 
 /// Interval between polling the eth1 node for genesis information.
 pub const ETH1_GENESIS_UPDATE_INTERVAL_MILLIS: u64 = 7_000;
 
+/// Number of attempts to download the finalized state when syncing from a checkpoint sync URL.
+///
+/// Finalized states are large, so a download may be interrupted by a transient network error.
+pub const CHECKPOINT_SYNC_STATE_DOWNLOAD_ATTEMPTS: usize = 3;
+
+/// Delay before the first retry of a failed finalized state download, doubled for each subsequent
+/// retry so that an overloaded remote is given time to recover.
+pub const CHECKPOINT_SYNC_STATE_DOWNLOAD_RETRY_DELAY: Duration = Duration::from_secs(5);
+
 /// Builds a `Client` instance.
 ///
 /// ## Notes
@@ -207,6 +216,7 @@ where
         };
 
         let chain_exists = builder.store_contains_beacon_chain().unwrap_or(false);
+        verify_checkpoint_sync_without_database(&client_genesis, chain_exists)?;
 
         // If the client is expect to resume but there's no beacon chain in the database,
         // use the `DepositContract` method. This scenario is quite common when the client
@@ -220,16 +230,6 @@ where
 
             ClientGenesis::DepositContract
         } else if chain_exists {
-            if matches!(client_genesis, ClientGenesis::WeakSubjSszBytes { .. })
-                || matches!(client_genesis, ClientGenesis::CheckpointSyncUrl { .. })
-            {
-                info!(
-                    context.log(),
-                    "Refusing to checkpoint sync";
-                    "msg" => "database already exists, use --purge-db to force checkpoint sync"
-                );
-            }
-
             ClientGenesis::FromStore
         } else {
             client_genesis
@@ -302,6 +302,27 @@ where
                     )),
                 );
 
+                let genesis_state = genesis_state(&runtime_context, &config, log).await?;
+
+                // Check that the remote node is on the same network before downloading the
+                // (potentially very large) finalized state.
+                let remote_genesis = remote
+                    .get_beacon_genesis()
+                    .await
+                    .map_err(|e| format!("Error fetching genesis from remote: {:?}", e))?
+                    .data;
+                let remote_fork_schedule = remote
+                    .get_config_fork_schedule()
+                    .await
+                    .map_err(|e| format!("Error fetching fork schedule from remote: {:?}", e))?
+                    .data;
+                verify_checkpoint_sync_network(
+                    &spec,
+                    genesis_state.genesis_validators_root(),
+                    &remote_genesis,
+                    &remote_fork_schedule,
+                )?;
+
                 let deposit_snapshot = if config.sync_eth1_chain {
                     // We want to fetch deposit snapshot before fetching the finalized beacon state to
                     // ensure that the snapshot is not newer than the beacon state that satisfies the
@@ -351,14 +372,66 @@ where
                     context.log(),
                     "Downloading finalized state";
                 );
-                let state = remote
-                    .get_debug_beacon_states_ssz::<TEthSpec>(StateId::Finalized, &spec)
-                    .await
-                    .map_err(|e| format!("Error loading checkpoint state from remote: {:?}", e))?
-                    .ok_or_else(|| "Checkpoint state missing from remote".to_string())?;
+                let mut attempt = 1;
+                let mut retry_delay = CHECKPOINT_SYNC_STATE_DOWNLOAD_RETRY_DELAY;
+                let state = loop {
+                    match remote
+                        .get_debug_beacon_states_ssz::<TEthSpec>(StateId::Finalized, &spec)
+                        .await
+                    {
+                        Ok(state) => break state,
+                        Err(e) if attempt < CHECKPOINT_SYNC_STATE_DOWNLOAD_ATTEMPTS => {
+                            warn!(
+                                context.log(),
+                                "Failed to download finalized state, retrying";
+                                "error" => ?e,
+                                "attempt" => attempt,
+                                "retry_delay" => ?retry_delay,
+                            );
+                            tokio::time::sleep(retry_delay).await;
+                            retry_delay *= 2;
+                            attempt += 1;
+                        }
+                        Err(e) => {
+                            return Err(format!(
+                                "Error loading checkpoint state from remote: {:?}",
+                                e
+                            ))
+                        }
+                    }
+                }
+                .ok_or_else(|| "Checkpoint state missing from remote".to_string())?;
 
                 debug!(context.log(), "Downloaded finalized state"; "slot" => ?state.slot());
 
+                // The snapshot was fetched before the state, so it must not contain deposits which
+                // the state has not yet processed. If it covers exactly the deposits in the state's
+                // eth1 data then the deposit roots must also agree.
+                let deposit_snapshot = deposit_snapshot.filter(|snapshot| {
+                    let eth1_data = state.eth1_data();
+                    if snapshot.deposit_count > state.eth1_deposit_index() {
+                        warn!(
+                            context.log(),
+                            "Ignoring deposit snapshot newer than checkpoint state";
+                            "snapshot_deposit_count" => snapshot.deposit_count,
+                            "state_deposit_index" => state.eth1_deposit_index(),
+                        );
+                        false
+                    } else if snapshot.deposit_count == eth1_data.deposit_count
+                        && snapshot.deposit_root != eth1_data.deposit_root
+                    {
+                        warn!(
+                            context.log(),
+                            "Ignoring deposit snapshot inconsistent with checkpoint state";
+                            "snapshot_deposit_root" => ?snapshot.deposit_root,
+                            "state_deposit_root" => ?eth1_data.deposit_root,
+                        );
+                        false
+                    } else {
+                        true
+                    }
+                });
+
                 let finalized_block_slot = state.latest_block_header().slot;
 
                 debug!(context.log(), "Downloading finalized block"; "block_slot" => ?finalized_block_slot);
@@ -377,8 +450,6 @@ where
 
                 debug!(context.log(), "Downloaded finalized block");
 
-                let genesis_state = genesis_state(&runtime_context, &config, log).await?;
-
                 info!(
                     context.log(),
                     "Loaded checkpoint block and state";
@@ -940,7 +1011,18 @@ where
             spec,
             context.log().clone(),
         )
-        .map_err(|e| format!("Unable to open database: {:?}", e))?;
+        .map_err(|e| match e {
+            store::Error::ConfigError(StoreConfigError::MismatchedSlotsPerRestorePoint {
+                config,
+                on_disk,
+            }) => format!(
+                "Unable to open database: it was created with --slots-per-restore-point {} but \
+                 {} was provided. Use --slots-per-restore-point-migrate to migrate the database, \
+                 or set --slots-per-restore-point {}",
+                on_disk, config, on_disk
+            ),
+            e => format!("Unable to open database: {:?}", e),
+        })?;
         self.store = Some(store);
         Ok(self)
     }
@@ -1083,6 +1165,72 @@ where
 }
 
 /// Obtain the genesis state from the `eth2_network_config` in `context`.
+/// Refuses to checkpoint sync on top of an existing database, which must be removed with
+/// `--purge-db` first.
+fn verify_checkpoint_sync_without_database(
+    client_genesis: &ClientGenesis,
+    chain_exists: bool,
+) -> Result<(), String> {
+    let checkpoint_sync = matches!(
+        client_genesis,
+        ClientGenesis::WeakSubjSszBytes { .. } | ClientGenesis::CheckpointSyncUrl { .. }
+    );
+    if checkpoint_sync && chain_exists {
+        return Err(
+            "Refusing to checkpoint sync: a database already exists. Use --purge-db to delete \
+             it and checkpoint sync, or remove the checkpoint sync flags to resume from it"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Checks that a remote beacon node used for checkpoint sync is on the same network as this node,
+/// by comparing genesis information and the fork digest of every scheduled fork.
+fn verify_checkpoint_sync_network(
+    spec: &ChainSpec,
+    genesis_validators_root: Hash256,
+    remote_genesis: &GenesisData,
+    remote_fork_schedule: &[Fork],
+) -> Result<(), String> {
+    if remote_genesis.genesis_fork_version != spec.genesis_fork_version {
+        return Err(format!(
+            "Checkpoint sync URL appears to be for the wrong network. Remote genesis fork \
+             version is {:?} but should be {:?}",
+            remote_genesis.genesis_fork_version, spec.genesis_fork_version
+        ));
+    }
+
+    if remote_genesis.genesis_validators_root != genesis_validators_root {
+        return Err(format!(
+            "Checkpoint sync URL appears to be for the wrong network. Remote genesis validators \
+             root is {:?} but should be {:?}",
+            remote_genesis.genesis_validators_root, genesis_validators_root
+        ));
+    }
+
+    for fork in remote_fork_schedule
+        .iter()
+        .filter(|fork| fork.epoch != spec.far_future_epoch)
+    {
+        let remote_fork_digest =
+            ChainSpec::compute_fork_digest(fork.current_version, genesis_validators_root);
+        let local_fork_digest = ChainSpec::compute_fork_digest(
+            spec.fork_version_for_name(spec.fork_name_at_epoch(fork.epoch)),
+            genesis_validators_root,
+        );
+        if remote_fork_digest != local_fork_digest {
+            return Err(format!(
+                "Checkpoint sync URL appears to be for the wrong network. Remote fork digest at \
+                 epoch {} is {:?} but should be {:?}",
+                fork.epoch, remote_fork_digest, local_fork_digest
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 async fn genesis_state<T: EthSpec>(
     context: &RuntimeContext<T>,
     config: &ClientConfig,
@@ -1101,3 +1249,116 @@ async fn genesis_state<T: EthSpec>(
         .await?
         .ok_or_else(|| "Genesis state is unknown".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sensitive_url::SensitiveUrl;
+    use types::{Epoch, MainnetEthSpec};
+
+    fn remote_genesis(spec: &ChainSpec, genesis_validators_root: Hash256) -> GenesisData {
+        GenesisData {
+            genesis_time: 0,
+            genesis_validators_root,
+            genesis_fork_version: spec.genesis_fork_version,
+        }
+    }
+
+    fn fork_schedule(spec: &ChainSpec) -> Vec<Fork> {
+        vec![
+            Fork {
+                previous_version: spec.genesis_fork_version,
+                current_version: spec.genesis_fork_version,
+                epoch: Epoch::new(0),
+            },
+            Fork {
+                previous_version: spec.genesis_fork_version,
+                current_version: spec.altair_fork_version,
+                epoch: spec.altair_fork_epoch.unwrap(),
+            },
+        ]
+    }
+
+    #[test]
+    fn checkpoint_sync_refuses_existing_database() {
+        let checkpoint_sync = ClientGenesis::CheckpointSyncUrl {
+            url: SensitiveUrl::parse("http://localhost:5052").unwrap(),
+        };
+        assert!(verify_checkpoint_sync_without_database(&checkpoint_sync, true).is_err());
+        assert!(verify_checkpoint_sync_without_database(&checkpoint_sync, false).is_ok());
+
+        let checkpoint_files = ClientGenesis::WeakSubjSszBytes {
+            anchor_state_bytes: vec![],
+            anchor_block_bytes: vec![],
+        };
+        assert!(verify_checkpoint_sync_without_database(&checkpoint_files, true).is_err());
+
+        // Resuming from an existing database is unaffected.
+        assert!(verify_checkpoint_sync_without_database(&ClientGenesis::FromStore, true).is_ok());
+    }
+
+    #[test]
+    fn checkpoint_sync_network_matches() {
+        let spec = MainnetEthSpec::default_spec();
+        let root = Hash256::repeat_byte(1);
+        assert_eq!(
+            verify_checkpoint_sync_network(
+                &spec,
+                root,
+                &remote_genesis(&spec, root),
+                &fork_schedule(&spec)
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn checkpoint_sync_genesis_validators_root_mismatch() {
+        let spec = MainnetEthSpec::default_spec();
+        let err = verify_checkpoint_sync_network(
+            &spec,
+            Hash256::repeat_byte(1),
+            &remote_genesis(&spec, Hash256::repeat_byte(2)),
+            &fork_schedule(&spec),
+        )
+        .unwrap_err();
+        assert!(err.contains("genesis validators root"), "{}", err);
+    }
+
+    #[test]
+    fn checkpoint_sync_fork_digest_mismatch() {
+        let spec = MainnetEthSpec::default_spec();
+        let root = Hash256::repeat_byte(1);
+        let mut remote_fork_schedule = fork_schedule(&spec);
+        remote_fork_schedule[1].current_version = [0xff; 4];
+        let err = verify_checkpoint_sync_network(
+            &spec,
+            root,
+            &remote_genesis(&spec, root),
+            &remote_fork_schedule,
+        )
+        .unwrap_err();
+        assert!(err.contains("fork digest"), "{}", err);
+    }
+
+    #[test]
+    fn checkpoint_sync_ignores_unscheduled_forks() {
+        let spec = MainnetEthSpec::default_spec();
+        let root = Hash256::repeat_byte(1);
+        let mut remote_fork_schedule = fork_schedule(&spec);
+        remote_fork_schedule.push(Fork {
+            previous_version: spec.altair_fork_version,
+            current_version: [0xff; 4],
+            epoch: spec.far_future_epoch,
+        });
+        assert_eq!(
+            verify_checkpoint_sync_network(
+                &spec,
+                root,
+                &remote_genesis(&spec, root),
+                &remote_fork_schedule
+            ),
+            Ok(())
+        );
+    }
+}
@@ -1090,4 +1090,56 @@ pub mod tests {
 
         verify_equality(&deposit_cache, &third_recovery);
     }
+
+    #[test]
+    fn deposit_snapshot_round_trip() {
+        use ssz::{Decode, Encode};
+
+        let n = 512;
+        let mut deposit_cache = get_cache_with_deposits(n);
+        assert!(
+            deposit_cache.get_deposit_snapshot().is_none(),
+            "should not have a snapshot prior to finalization"
+        );
+
+        let block255 = fake_eth1_block(&deposit_cache, 255).expect("should create fake eth1 block");
+        let later_logs = (256..n as usize)
+            .map(|i| deposit_cache.get_log(i).cloned().expect("log should exist"))
+            .collect::<Vec<_>>();
+        deposit_cache.finalize(block255).expect("should finalize");
+
+        // Serve the snapshot as SSZ and initialize a fresh cache from it.
+        let snapshot = deposit_cache
+            .get_deposit_snapshot()
+            .expect("should have a snapshot after finalization");
+        let snapshot = DepositTreeSnapshot::from_ssz_bytes(&snapshot.as_ssz_bytes())
+            .expect("should decode snapshot");
+        assert!(snapshot.is_valid(), "snapshot should be valid");
+        let mut snapshot_cache =
+            DepositCache::from_deposit_snapshot(0, &snapshot).expect("should create cache");
+        for log in later_logs {
+            snapshot_cache
+                .insert_log(log)
+                .expect("should add consecutive logs");
+        }
+
+        assert_eq!(
+            deposit_cache.deposit_tree.root(),
+            snapshot_cache.deposit_tree.root(),
+            "deposit tree root should match a cache that synced all logs"
+        );
+        for i in 256..=n as usize {
+            assert_eq!(
+                deposit_cache.get_root(i),
+                snapshot_cache.get_root(i),
+                "deposit root for count {} should match",
+                i
+            );
+        }
+        assert_eq!(
+            deposit_cache.get_deposits(300, 400, n),
+            snapshot_cache.get_deposits(300, 400, n),
+            "deposits and their proofs should match"
+        );
+    }
 }
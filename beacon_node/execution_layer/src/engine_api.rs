@@ -3,6 +3,7 @@ use crate::http::{
     ENGINE_FORKCHOICE_UPDATED_V1, ENGINE_FORKCHOICE_UPDATED_V2,
     ENGINE_GET_PAYLOAD_BODIES_BY_HASH_V1, ENGINE_GET_PAYLOAD_BODIES_BY_RANGE_V1,
     ENGINE_GET_PAYLOAD_V1, ENGINE_GET_PAYLOAD_V2, ENGINE_NEW_PAYLOAD_V1, ENGINE_NEW_PAYLOAD_V2,
+    LIGHTHOUSE_CAPABILITIES,
 };
 use eth2::types::{SsePayloadAttributes, SsePayloadAttributesV1, SsePayloadAttributesV2};
 pub use ethers_core::types::Transaction;
@@ -481,4 +482,15 @@ impl EngineCapabilities {
 
         response
     }
+
+    /// Returns the engine methods supported by Lighthouse which the execution engine does not
+    /// support.
+    pub fn missing(&self) -> Vec<&'static str> {
+        let supported = self.to_response();
+        LIGHTHOUSE_CAPABILITIES
+            .iter()
+            .copied()
+            .filter(|method| !supported.contains(method))
+            .collect()
+    }
 }
@@ -125,6 +125,9 @@ pub struct Engine {
     payload_id_cache: Mutex<LruCache<PayloadIdCacheKey, PayloadId>>,
     state: RwLock<State>,
     latest_forkchoice_state: RwLock<Option<ForkchoiceState>>,
+    /// The engine methods that were missing when capabilities were last exchanged, used to avoid
+    /// repeatedly warning about the same missing methods.
+    missing_capabilities: Mutex<Option<Vec<&'static str>>>,
     executor: TaskExecutor,
     log: Logger,
 }
@@ -137,6 +140,7 @@ impl Engine {
             payload_id_cache: Mutex::new(LruCache::new(PAYLOAD_ID_LRU_CACHE_SIZE)),
             state: Default::default(),
             latest_forkchoice_state: Default::default(),
+            missing_capabilities: Default::default(),
             executor,
             log: log.clone(),
         }
@@ -302,14 +306,15 @@ impl Engine {
         match cache_action {
             CapabilitiesCacheAction::None => {}
             CapabilitiesCacheAction::Update => {
-                if let Err(e) = self
+                match self
                     .get_engine_capabilities(Some(CACHED_ENGINE_CAPABILITIES_AGE_LIMIT))
                     .await
                 {
-                    warn!(self.log,
+                    Ok(capabilities) => self.warn_missing_capabilities(capabilities).await,
+                    Err(e) => warn!(self.log,
                         "Error during exchange capabilities";
                         "error" => ?e,
-                    )
+                    ),
                 }
             }
             CapabilitiesCacheAction::Clear => self.api.clear_exchange_capabilties_cache().await,
@@ -338,6 +343,25 @@ impl Engine {
         self.api.get_engine_capabilities(age_limit).await
     }
 
+    /// Log a warning listing the engine methods not supported by the execution engine, if they
+    /// differ from those listed at the last exchange.
+    async fn warn_missing_capabilities(&self, capabilities: EngineCapabilities) {
+        let missing = capabilities.missing();
+        let mut last_missing = self.missing_capabilities.lock().await;
+        if last_missing.as_ref() == Some(&missing) {
+            return;
+        }
+        if !missing.is_empty() {
+            warn!(
+                self.log,
+                "Execution engine is missing capabilities";
+                "info" => "some features will be unavailable or use slower fallbacks",
+                "missing" => ?missing,
+            );
+        }
+        *last_missing = Some(missing);
+    }
+
     /// Run `func` on the node regardless of the node's current state.
     ///
     /// ## Note
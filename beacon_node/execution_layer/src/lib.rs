@@ -5,9 +5,10 @@
 //! deposit-contract functionality that the `beacon_node/eth1` crate already provides.
 
 use crate::payload_cache::PayloadCache;
+use crate::registration_cache::RegistrationCache;
 use arc_swap::ArcSwapOption;
 use auth::{strip_prefix, Auth, JwtKey};
-use builder_client::BuilderHttpClient;
+use builder_client::{BuilderHttpClient, Timeouts as BuilderTimeouts};
 pub use engine_api::EngineCapabilities;
 use engine_api::Error as ApiError;
 pub use engine_api::*;
@@ -29,6 +30,7 @@ use std::future::Future;
 use std::io::Write;
 use std::marker::PhantomData;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use strum::AsRefStr;
@@ -43,7 +45,7 @@ use types::{AbstractExecPayload, BeaconStateError, ExecPayload};
 use types::{
     BlindedPayload, BlockType, ChainSpec, Epoch, ExecutionPayloadCapella, ExecutionPayloadMerge,
     ForkVersionedResponse, ProposerPreparationData, PublicKeyBytes, Signature, SignedBeaconBlock,
-    Slot,
+    SignedValidatorRegistrationData, Slot, ValidatorRegistrationData,
 };
 
 mod block_hash;
@@ -53,6 +55,7 @@ mod keccak;
 mod metrics;
 pub mod payload_cache;
 mod payload_status;
+mod registration_cache;
 pub mod test_utils;
 
 /// Indicates the default jwt authenticated execution endpoint.
@@ -61,10 +64,25 @@ pub const DEFAULT_EXECUTION_ENDPOINT: &str = "http://localhost:8551/";
 /// Name for the default file used for the jwt secret.
 pub const DEFAULT_JWT_FILE: &str = "jwt.hex";
 
+/// The default percentage by which builder payload values are multiplied before being compared
+/// to the value of the local payload.
+pub const DEFAULT_BUILDER_BOOST_FACTOR: u64 = 100;
+
+/// The default number of consecutive failed engine API calls after which the execution layer is
+/// reported as offline.
+pub const DEFAULT_EXECUTION_OFFLINE_THRESHOLD: usize = 1;
+
+/// The default number of validator registrations to send to the builder in each request.
+pub const DEFAULT_BUILDER_REGISTRATION_BATCH_SIZE: usize = 500;
+
 /// Each time the `ExecutionLayer` retrieves a block from an execution node, it stores that block
 /// in an LRU cache to avoid redundant lookups. This is the size of that cache.
 const EXECUTION_BLOCKS_LRU_CACHE_SIZE: usize = 128;
 
+/// Proposer preparation data which has not been updated by a validator for this many epochs is
+/// considered expired.
+const PROPOSER_PREPARATION_RETAIN_EPOCHS: u64 = 2;
+
 /// A fee recipient address for use during block production. Only used as a very last resort if
 /// there is no address provided by the user.
 ///
@@ -75,6 +93,19 @@ const EXECUTION_BLOCKS_LRU_CACHE_SIZE: usize = 128;
 const DEFAULT_SUGGESTED_FEE_RECIPIENT: [u8; 20] =
     [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
 
+/// The outcome of forwarding a set of validator registrations to the builder.
+#[derive(Debug)]
+pub struct RegistrationSummary {
+    /// Registrations which were new or modified, and were sent to the builder.
+    pub new: usize,
+    /// Registrations which were unchanged since last accepted by the builder, and were skipped.
+    pub unchanged: usize,
+    /// New registrations in batches that the builder failed to accept.
+    pub failed: usize,
+    /// The error returned by the builder for the last batch it failed to accept, if any.
+    pub last_error: Option<builder_client::Error>,
+}
+
 /// A payload alongside some information about where it came from.
 pub enum ProvenancedPayload<P> {
     /// A good old fashioned farm-to-table payload from your local EE.
@@ -105,6 +136,12 @@ pub enum Error {
     InvalidForkForPayload,
     InvalidPayloadBody(String),
     BeaconStateError(BeaconStateError),
+    RevealedPayloadMismatch {
+        field: &'static str,
+        expected: String,
+        revealed: String,
+    },
+    ProposerPreparationDataLocked,
 }
 
 impl From<BeaconStateError> for Error {
@@ -201,11 +238,14 @@ pub enum ChainHealth {
     PreMerge,
 }
 
-#[derive(Debug)]
+#[derive(Debug, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum FailedCondition {
     Skips,
     SkipsPerEpoch,
     EpochsSinceFinalization,
+    /// The builder failed to reveal the payload for our previous blinded block proposal.
+    PreviousBuilderProposalMissed,
 }
 
 struct Inner<E: EthSpec> {
@@ -219,13 +259,27 @@ struct Inner<E: EthSpec> {
     executor: TaskExecutor,
     payload_cache: PayloadCache<E>,
     builder_profit_threshold: Uint256,
+    builder_boost_factor: u64,
+    builder_timeouts: BuilderTimeouts,
+    builder_registration_batch_size: usize,
+    builder_registrations: RegistrationCache,
+    /// Set when the proposer preparation data or builder registrations change, until they are
+    /// next persisted.
+    proposer_preparation_changed: AtomicBool,
     log: Logger,
     always_prefer_builder_payload: bool,
-    /// Track whether the last `newPayload` call errored.
+    warn_builder_fee_recipient_mismatch: bool,
+    /// The number of consecutive `newPayload` and `forkchoiceUpdated` calls which have errored.
     ///
     /// This is used *only* in the informational sync status endpoint, so that a VC using this
     /// node can prefer another node with a healthier EL.
-    last_new_payload_errored: RwLock<bool>,
+    consecutive_engine_failures: AtomicUsize,
+    /// The number of consecutive engine failures after which the EL is reported as offline.
+    execution_offline_threshold: usize,
+    /// Track whether the builder failed to reveal the payload for our last blinded proposal.
+    ///
+    /// This trips the builder circuit breaker so that the next proposal uses a local payload.
+    last_builder_reveal_failed: AtomicBool,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -236,6 +290,14 @@ pub struct Config {
     pub builder_url: Option<SensitiveUrl>,
     /// User agent to send with requests to the builder API.
     pub builder_user_agent: Option<String>,
+    /// Timeout for `getHeader` requests to the builder API.
+    pub builder_header_timeout: Option<Duration>,
+    /// Timeout for `submitBlindedBlock` requests to the builder API.
+    pub builder_blinded_blocks_timeout: Option<Duration>,
+    /// Timeout for `registerValidator` requests to the builder API.
+    pub builder_register_validator_timeout: Option<Duration>,
+    /// The number of validator registrations to send to the builder in each request.
+    pub builder_registration_batch_size: Option<usize>,
     /// JWT secrets for the above endpoints running the engine api.
     pub secret_files: Vec<PathBuf>,
     /// The default fee recipient to use on the beacon node if none if provided from
@@ -249,8 +311,20 @@ pub struct Config {
     pub default_datadir: PathBuf,
     /// The minimum value of an external payload for it to be considered in a proposal.
     pub builder_profit_threshold: u128,
+    /// The percentage to multiply builder payload values by when comparing them to the value of
+    /// the local payload. Defaults to `DEFAULT_BUILDER_BOOST_FACTOR`.
+    pub builder_boost_factor: Option<u64>,
     pub execution_timeout_multiplier: Option<u32>,
+    /// The number of consecutive failed engine API calls after which the execution layer is
+    /// reported as offline. Defaults to `DEFAULT_EXECUTION_OFFLINE_THRESHOLD`.
+    pub execution_offline_threshold: Option<usize>,
     pub always_prefer_builder_payload: bool,
+    /// Log builder payloads which pay a fee recipient other than the suggested fee recipient at
+    /// `WARN` rather than `DEBUG`.
+    ///
+    /// Builders commonly set themselves as the fee recipient and pay the proposer in the last
+    /// transaction of the payload, so such mismatches are not necessarily a problem.
+    pub warn_builder_fee_recipient_mismatch: bool,
 }
 
 /// Provides access to one execution engine and provides a neat interface for consumption by the
@@ -267,14 +341,21 @@ impl<T: EthSpec> ExecutionLayer<T> {
             execution_endpoints: urls,
             builder_url,
             builder_user_agent,
+            builder_header_timeout,
+            builder_blinded_blocks_timeout,
+            builder_register_validator_timeout,
+            builder_registration_batch_size,
             secret_files,
             suggested_fee_recipient,
             jwt_id,
             jwt_version,
             default_datadir,
             builder_profit_threshold,
+            builder_boost_factor,
             execution_timeout_multiplier,
+            execution_offline_threshold,
             always_prefer_builder_payload,
+            warn_builder_fee_recipient_mismatch,
         } = config;
 
         if urls.len() > 1 {
@@ -325,6 +406,16 @@ impl<T: EthSpec> ExecutionLayer<T> {
             Engine::new(api, executor.clone(), &log)
         };
 
+        let default_builder_timeouts = BuilderTimeouts::default();
+        let builder_timeouts = BuilderTimeouts {
+            get_header: builder_header_timeout.unwrap_or(default_builder_timeouts.get_header),
+            post_blinded_blocks: builder_blinded_blocks_timeout
+                .unwrap_or(default_builder_timeouts.post_blinded_blocks),
+            post_validators: builder_register_validator_timeout
+                .unwrap_or(default_builder_timeouts.post_validators),
+            ..default_builder_timeouts
+        };
+
         let inner = Inner {
             engine: Arc::new(engine),
             builder: ArcSwapOption::empty(),
@@ -336,9 +427,19 @@ impl<T: EthSpec> ExecutionLayer<T> {
             executor,
             payload_cache: PayloadCache::default(),
             builder_profit_threshold: Uint256::from(builder_profit_threshold),
+            builder_boost_factor: builder_boost_factor.unwrap_or(DEFAULT_BUILDER_BOOST_FACTOR),
+            builder_timeouts,
+            builder_registration_batch_size: builder_registration_batch_size
+                .unwrap_or(DEFAULT_BUILDER_REGISTRATION_BATCH_SIZE),
+            builder_registrations: RegistrationCache::default(),
+            proposer_preparation_changed: AtomicBool::new(false),
             log,
             always_prefer_builder_payload,
-            last_new_payload_errored: RwLock::new(false),
+            warn_builder_fee_recipient_mismatch,
+            consecutive_engine_failures: AtomicUsize::new(0),
+            execution_offline_threshold: execution_offline_threshold
+                .unwrap_or(DEFAULT_EXECUTION_OFFLINE_THRESHOLD),
+            last_builder_reveal_failed: AtomicBool::new(false),
         };
 
         let el = Self {
@@ -369,19 +470,123 @@ impl<T: EthSpec> ExecutionLayer<T> {
         builder_url: SensitiveUrl,
         builder_user_agent: Option<String>,
     ) -> Result<(), Error> {
-        let builder_client = BuilderHttpClient::new(builder_url.clone(), builder_user_agent)
-            .map_err(Error::Builder)?;
+        let builder_client = BuilderHttpClient::new(
+            builder_url.clone(),
+            builder_user_agent,
+            self.inner.builder_timeouts.clone(),
+        )
+        .map_err(Error::Builder)?;
+        let timeouts = builder_client.get_timeouts();
         info!(
             self.log(),
             "Using external block builder";
             "builder_url" => ?builder_url,
             "builder_profit_threshold" => self.inner.builder_profit_threshold.as_u128(),
             "local_user_agent" => builder_client.get_user_agent(),
+            "header_timeout_ms" => timeouts.get_header.as_millis(),
+            "blinded_blocks_timeout_ms" => timeouts.post_blinded_blocks.as_millis(),
+            "register_validator_timeout_ms" => timeouts.post_validators.as_millis(),
         );
         self.inner.builder.swap(Some(Arc::new(builder_client)));
+        // The new builder has not seen any of our registrations.
+        self.inner
+            .builder_registrations
+            .reset(builder_url.full.to_string());
         Ok(())
     }
 
+    /// Forward `registrations` to the builder, skipping those which are unchanged since they were
+    /// last accepted by the builder.
+    ///
+    /// The remaining registrations are sent in batches, which are spread over `spread` with some
+    /// random jitter to avoid overwhelming the builder. A failed batch does not prevent the
+    /// remaining batches from being sent.
+    pub async fn register_validators_with_builder(
+        &self,
+        registrations: Vec<SignedValidatorRegistrationData>,
+        spread: Duration,
+    ) -> Result<RegistrationSummary, Error> {
+        let builder = self.builder().ok_or(Error::NoPayloadBuilder)?;
+
+        let (new_registrations, unchanged) = self
+            .inner
+            .builder_registrations
+            .filter_unchanged(registrations);
+        let mut summary = RegistrationSummary {
+            new: new_registrations.len(),
+            unchanged,
+            failed: 0,
+            last_error: None,
+        };
+
+        let batch_size = std::cmp::max(self.inner.builder_registration_batch_size, 1);
+        let num_batches = (new_registrations.len() + batch_size - 1) / batch_size;
+        let start = Instant::now();
+
+        for (i, batch) in new_registrations.chunks(batch_size).enumerate() {
+            // Send the first batch immediately, and each subsequent batch at a random time within
+            // its share of `spread`.
+            if i > 0 {
+                let offset =
+                    spread.mul_f64((i as f64 + rand::random::<f64>()) / num_batches as f64);
+                tokio::time::sleep_until((start + offset).into()).await;
+            }
+
+            let (result, duration) = timed_future(metrics::POST_BUILDER_VALIDATORS, async {
+                builder.post_builder_validators(batch).await
+            })
+            .await;
+
+            match result {
+                Ok(_) => {
+                    if self.inner.builder_registrations.insert(batch) > 0 {
+                        self.inner
+                            .proposer_preparation_changed
+                            .store(true, Ordering::Relaxed);
+                    }
+                }
+                Err(e) => {
+                    summary.failed += batch.len();
+                    warn!(
+                        self.log(),
+                        "Relay error when registering validator(s)";
+                        "num_registrations" => batch.len(),
+                        "batch" => i,
+                        "relay_response_ms" => duration.as_millis(),
+                        "error" => ?e,
+                    );
+                    summary.last_error = Some(e);
+                }
+            }
+        }
+
+        metrics::inc_counter_vec_by(
+            &metrics::EXECUTION_LAYER_BUILDER_REGISTRATIONS,
+            &["new"],
+            summary.new as u64,
+        );
+        metrics::inc_counter_vec_by(
+            &metrics::EXECUTION_LAYER_BUILDER_REGISTRATIONS,
+            &["unchanged"],
+            summary.unchanged as u64,
+        );
+        metrics::inc_counter_vec_by(
+            &metrics::EXECUTION_LAYER_BUILDER_REGISTRATIONS,
+            &["failed"],
+            summary.failed as u64,
+        );
+        info!(
+            self.log(),
+            "Forwarded validator registrations to builder";
+            "new" => summary.new,
+            "unchanged" => summary.unchanged,
+            "failed" => summary.failed,
+            "batches" => num_batches,
+        );
+
+        Ok(summary)
+    }
+
     /// Cache a full payload, keyed on the `tree_hash_root` of the payload
     fn cache_payload(&self, payload: ExecutionPayloadRef<T>) -> Option<ExecutionPayload<T>> {
         self.inner.payload_cache.put(payload.clone_from_ref())
@@ -392,6 +597,14 @@ impl<T: EthSpec> ExecutionLayer<T> {
         self.inner.payload_cache.get(root)
     }
 
+    /// Attempt to retrieve a full payload from the payload cache by its execution block hash
+    pub fn get_payload_by_block_hash(
+        &self,
+        block_hash: &ExecutionBlockHash,
+    ) -> Option<ExecutionPayload<T>> {
+        self.inner.payload_cache.get_by_block_hash(block_hash)
+    }
+
     pub fn executor(&self) -> &TaskExecutor {
         &self.inner.executor
     }
@@ -539,13 +752,41 @@ impl<T: EthSpec> ExecutionLayer<T> {
         synced
     }
 
-    /// Return `true` if the execution layer is offline or returning errors on `newPayload`.
+    /// Return `true` if the execution layer is offline, or if the most recent `newPayload` and
+    /// `forkchoiceUpdated` calls have errored at least `execution_offline_threshold` times in a row.
     ///
     /// This function should never be used to prevent any operation in the beacon node, but can
     /// be used to give an indication on the HTTP API that the node's execution layer is struggling,
     /// which can in turn be used by the VC.
     pub async fn is_offline_or_erroring(&self) -> bool {
-        self.engine().is_offline().await || *self.inner.last_new_payload_errored.read().await
+        self.engine().is_offline().await
+            || self
+                .inner
+                .consecutive_engine_failures
+                .load(Ordering::Relaxed)
+                >= self.inner.execution_offline_threshold
+    }
+
+    /// Record the outcome of an engine API call, resetting the failure count on success.
+    fn record_engine_result<O, Err>(&self, result: &Result<O, Err>) {
+        if result.is_ok() {
+            self.inner
+                .consecutive_engine_failures
+                .store(0, Ordering::Relaxed);
+        } else {
+            self.inner
+                .consecutive_engine_failures
+                .fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns `true` if the builder failed to reveal the payload for our most recent blinded block
+    /// proposal, and no proposal has since fallen back to a local payload or had its payload
+    /// revealed by the builder.
+    pub fn last_builder_reveal_failed(&self) -> bool {
+        self.inner
+            .last_builder_reveal_failed
+            .load(Ordering::Relaxed)
     }
 
     /// Updates the proposer preparation data provided by validators
@@ -566,8 +807,86 @@ impl<T: EthSpec> ExecutionLayer<T> {
 
             if existing != Some(new) {
                 metrics::inc_counter(&metrics::EXECUTION_LAYER_PROPOSER_DATA_UPDATED);
+                self.inner
+                    .proposer_preparation_changed
+                    .store(true, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Returns the proposer preparation data provided by validators, along with the epoch at
+    /// which each entry was last updated.
+    pub async fn proposer_preparation_entries(&self) -> Vec<(Epoch, ProposerPreparationData)> {
+        self.proposer_preparation_data()
+            .await
+            .values()
+            .map(|entry| (entry.update_epoch, entry.preparation_data.clone()))
+            .collect()
+    }
+
+    /// Restores proposer preparation data persisted prior to a restart, ignoring entries which
+    /// would already have expired at `current_epoch`.
+    ///
+    /// This method is intended to be called at start-up, before any validators have provided
+    /// preparation data. Entries are never restored over newer data provided by validators.
+    ///
+    /// Returns the number of entries restored.
+    pub fn restore_proposer_preparation(
+        &self,
+        current_epoch: Epoch,
+        entries: Vec<(Epoch, ProposerPreparationData)>,
+    ) -> Result<usize, Error> {
+        let retain_epoch = current_epoch.saturating_sub(PROPOSER_PREPARATION_RETAIN_EPOCHS);
+        let mut proposer_preparation_data = self
+            .inner
+            .proposer_preparation_data
+            .try_lock()
+            .map_err(|_| Error::ProposerPreparationDataLocked)?;
+        let mut restored = 0;
+        for (update_epoch, preparation_data) in entries {
+            if update_epoch < retain_epoch {
+                continue;
             }
+            proposer_preparation_data
+                .entry(preparation_data.validator_index)
+                .or_insert_with(|| {
+                    restored += 1;
+                    ProposerPreparationDataEntry {
+                        update_epoch,
+                        preparation_data,
+                    }
+                });
         }
+        Ok(restored)
+    }
+
+    /// Returns the URL of the builder, along with the validator registrations it most recently
+    /// accepted.
+    pub fn builder_registrations(&self) -> (Option<String>, Vec<ValidatorRegistrationData>) {
+        self.inner.builder_registrations.registrations()
+    }
+
+    /// Restores validator registrations accepted by the builder at `builder_url` prior to a
+    /// restart, so that unchanged registrations are not forwarded to the builder again.
+    ///
+    /// Registrations are ignored if a different builder is now in use. Returns the number of
+    /// registrations restored.
+    pub fn restore_builder_registrations(
+        &self,
+        builder_url: &str,
+        registrations: Vec<ValidatorRegistrationData>,
+    ) -> usize {
+        self.inner
+            .builder_registrations
+            .restore(builder_url, registrations)
+    }
+
+    /// Returns `true` if the proposer preparation data or builder registrations have changed
+    /// since this function last returned `true`, indicating that they should be persisted.
+    pub fn take_proposer_preparation_changed(&self) -> bool {
+        self.inner
+            .proposer_preparation_changed
+            .swap(false, Ordering::Relaxed)
     }
 
     /// Removes expired entries from proposer_preparation_data and proposers caches
@@ -575,7 +894,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
         let mut proposer_preparation_data = self.proposer_preparation_data().await;
 
         // Keep all entries that have been updated in the last 2 epochs
-        let retain_epoch = current_epoch.saturating_sub(Epoch::new(2));
+        let retain_epoch = current_epoch.saturating_sub(PROPOSER_PREPARATION_RETAIN_EPOCHS);
         proposer_preparation_data.retain(|_validator_index, preparation_entry| {
             preparation_entry.update_epoch >= retain_epoch
         });
@@ -616,14 +935,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
 
     /// Returns the fee-recipient address that should be used to build a block
     pub async fn get_suggested_fee_recipient(&self, proposer_index: u64) -> Address {
-        if let Some(preparation_data_entry) =
-            self.proposer_preparation_data().await.get(&proposer_index)
-        {
-            // The values provided via the API have first priority.
-            preparation_data_entry.preparation_data.fee_recipient
-        } else if let Some(address) = self.inner.suggested_fee_recipient {
-            // If there has been no fee recipient provided via the API, but the BN has been provided
-            // with a global default address, use that.
+        if let Some(address) = self.expected_fee_recipient(proposer_index).await {
             address
         } else {
             // If there is no user-provided fee recipient, use a junk value and complain loudly.
@@ -640,6 +952,89 @@ impl<T: EthSpec> ExecutionLayer<T> {
         }
     }
 
+    /// Returns the fee recipient which payloads for `proposer_index` are expected to pay, if the
+    /// user has provided one.
+    ///
+    /// Unlike `Self::get_suggested_fee_recipient`, this does not fall back to a junk address.
+    pub async fn expected_fee_recipient(&self, proposer_index: u64) -> Option<Address> {
+        if let Some(preparation_data_entry) =
+            self.proposer_preparation_data().await.get(&proposer_index)
+        {
+            // The values provided via the API have first priority.
+            Some(preparation_data_entry.preparation_data.fee_recipient)
+        } else {
+            // If there has been no fee recipient provided via the API, but the BN has been provided
+            // with a global default address, use that.
+            self.inner.suggested_fee_recipient
+        }
+    }
+
+    /// Log and count a revealed builder payload whose `fee_recipient` differs from the
+    /// `suggested_fee_recipient` we expect.
+    ///
+    /// Returns `true` if the mismatch is significant, i.e. `warn_builder_fee_recipient_mismatch`
+    /// is set. Otherwise the mismatch is assumed to be legitimate and is only logged at `DEBUG`.
+    pub fn check_builder_fee_recipient(
+        &self,
+        fee_recipient: Address,
+        suggested_fee_recipient: Address,
+    ) -> bool {
+        self.check_fee_recipient(metrics::BUILDER, fee_recipient, suggested_fee_recipient)
+    }
+
+    /// Log and count a payload from `source` whose `fee_recipient` differs from the
+    /// `suggested_fee_recipient` we requested.
+    ///
+    /// Local payloads should always honour the suggested fee recipient, so a mismatch is logged as
+    /// an error. Builders legitimately pay the proposer via a transaction instead, so mismatches
+    /// are only logged at `WARN` if `warn_builder_fee_recipient_mismatch` is set.
+    ///
+    /// Returns `true` if the mismatch was logged at `WARN` or above.
+    fn check_fee_recipient(
+        &self,
+        source: &str,
+        fee_recipient: Address,
+        suggested_fee_recipient: Address,
+    ) -> bool {
+        if fee_recipient == suggested_fee_recipient {
+            return false;
+        }
+
+        metrics::inc_counter_vec(&metrics::EXECUTION_LAYER_FEE_RECIPIENT_MISMATCH, &[source]);
+
+        if source == metrics::LOCAL {
+            error!(
+                self.log(),
+                "Inconsistent fee recipient";
+                "msg" => "The fee recipient returned from the Execution Engine differs \
+                from the suggested_fee_recipient set on the beacon node. This could \
+                indicate that fees are being diverted to another address. Please \
+                ensure that the value of suggested_fee_recipient is set correctly and \
+                that the Execution Engine is trusted.",
+                "fee_recipient" => ?fee_recipient,
+                "suggested_fee_recipient" => ?suggested_fee_recipient,
+            );
+            true
+        } else if self.inner.warn_builder_fee_recipient_mismatch {
+            warn!(
+                self.log(),
+                "Builder payload fee recipient mismatch";
+                "msg" => "the builder may pay the proposer via a transaction instead",
+                "fee_recipient" => ?fee_recipient,
+                "suggested_fee_recipient" => ?suggested_fee_recipient,
+            );
+            true
+        } else {
+            debug!(
+                self.log(),
+                "Builder payload fee recipient mismatch";
+                "fee_recipient" => ?fee_recipient,
+                "suggested_fee_recipient" => ?suggested_fee_recipient,
+            );
+            false
+        }
+    }
+
     /// Maps to the `engine_getPayload` JSON-RPC call.
     ///
     /// However, it will attempt to call `self.prepare_payload` if it cannot find an existing
@@ -701,6 +1096,11 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     &metrics::EXECUTION_LAYER_GET_PAYLOAD_SOURCE,
                     &[metrics::LOCAL],
                 );
+                self.check_fee_recipient(
+                    metrics::LOCAL,
+                    block_proposal_contents.payload().fee_recipient(),
+                    payload_attributes.suggested_fee_recipient(),
+                );
                 Ok(block_proposal_contents)
             }
             Ok(ProvenancedPayload::Builder(block_proposal_contents)) => {
@@ -712,6 +1112,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     &metrics::EXECUTION_LAYER_GET_PAYLOAD_SOURCE,
                     &[metrics::BUILDER],
                 );
+                // Builder payloads are checked once revealed, via `check_builder_fee_recipient`.
                 Ok(block_proposal_contents)
             }
             Err(e) => {
@@ -817,13 +1218,19 @@ impl<T: EthSpec> ExecutionLayer<T> {
 
                             let relay_value = relay.data.message.value;
                             let local_value = *local.block_value();
+                            observe_payload_value_delta(relay_value, local_value);
+
                             if !self.inner.always_prefer_builder_payload {
-                                if local_value >= relay_value {
+                                let boosted_relay_value = relay_value
+                                    .saturating_mul(Uint256::from(self.inner.builder_boost_factor))
+                                    / 100;
+                                if local_value >= boosted_relay_value {
                                     info!(
                                         self.log(),
                                         "Local block is more profitable than relay block";
                                         "local_block_value" => %local_value,
-                                        "relay_value" => %relay_value
+                                        "relay_value" => %relay_value,
+                                        "boosted_relay_value" => %boosted_relay_value,
                                     );
                                     return Ok(ProvenancedPayload::Local(local));
                                 } else {
@@ -831,7 +1238,8 @@ impl<T: EthSpec> ExecutionLayer<T> {
                                         self.log(),
                                         "Relay block is more profitable than local block";
                                         "local_block_value" => %local_value,
-                                        "relay_value" => %relay_value
+                                        "relay_value" => %relay_value,
+                                        "boosted_relay_value" => %boosted_relay_value,
                                     );
                                 }
                             }
@@ -959,13 +1367,26 @@ impl<T: EthSpec> ExecutionLayer<T> {
                         }
                     };
                 }
-                ChainHealth::Unhealthy(condition) => info!(
-                    self.log(),
-                    "Chain is unhealthy, using local payload";
-                    "info" => "this helps protect the network. the --builder-fallback flags \
-                        can adjust the expected health conditions.",
-                    "failed_condition" => ?condition
-                ),
+                ChainHealth::Unhealthy(condition) => {
+                    metrics::inc_counter_vec(
+                        &metrics::EXECUTION_LAYER_BUILDER_FALLBACK_CONDITIONS,
+                        &[condition.as_ref()],
+                    );
+                    info!(
+                        self.log(),
+                        "Chain is unhealthy, using local payload";
+                        "info" => "this helps protect the network. the --builder-fallback flags \
+                            can adjust the expected health conditions.",
+                        "failed_condition" => ?condition
+                    );
+                    // The circuit breaker has tripped once for the missed builder proposal, so
+                    // allow the builder to be used again for the next proposal.
+                    if let FailedCondition::PreviousBuilderProposalMissed = condition {
+                        self.inner
+                            .last_builder_reveal_failed
+                            .store(false, Ordering::Relaxed);
+                    }
+                }
                 // Intentional no-op, so we never attempt builder API proposals pre-merge.
                 ChainHealth::PreMerge => (),
                 ChainHealth::Optimistic => info!(
@@ -1094,29 +1515,17 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     engine.api.get_payload::<T>(current_fork, payload_id).await
                 };
                 let payload_response = payload_fut.await;
-                let (execution_payload, block_value) = payload_response.map(|payload_response| {
-                    if payload_response.execution_payload_ref().fee_recipient() != payload_attributes.suggested_fee_recipient() {
-                        error!(
-                            self.log(),
-                            "Inconsistent fee recipient";
-                            "msg" => "The fee recipient returned from the Execution Engine differs \
-                            from the suggested_fee_recipient set on the beacon node. This could \
-                            indicate that fees are being diverted to another address. Please \
-                            ensure that the value of suggested_fee_recipient is set correctly and \
-                            that the Execution Engine is trusted.",
-                            "fee_recipient" => ?payload_response.execution_payload_ref().fee_recipient(),
-                            "suggested_fee_recipient" => ?payload_attributes.suggested_fee_recipient(),
-                        );
-                    }
-                    if f(self, payload_response.execution_payload_ref()).is_some() {
-                        warn!(
-                            self.log(),
-                            "Duplicate payload cached, this might indicate redundant proposal \
+                let (execution_payload, block_value) =
+                    payload_response.map(|payload_response| {
+                        if f(self, payload_response.execution_payload_ref()).is_some() {
+                            warn!(
+                                self.log(),
+                                "Duplicate payload cached, this might indicate redundant proposal \
                                  attempts."
-                        );
-                    }
-                    payload_response.into()
-                })?;
+                            );
+                        }
+                        payload_response.into()
+                    })?;
                 Ok(BlockProposalContents::Payload {
                     payload: execution_payload.into(),
                     block_value,
@@ -1157,7 +1566,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
                 &["new_payload", status.status.into()],
             );
         }
-        *self.inner.last_new_payload_errored.write().await = result.is_err();
+        self.record_engine_result(&result);
 
         process_payload_status(execution_payload.block_hash(), result, self.log())
             .map_err(Box::new)
@@ -1299,6 +1708,7 @@ impl<T: EthSpec> ExecutionLayer<T> {
                 &["forkchoice_updated", status.payload_status.status.into()],
             );
         }
+        self.record_engine_result(&result);
 
         process_payload_status(
             head_block_hash,
@@ -1533,13 +1943,18 @@ impl<T: EthSpec> ExecutionLayer<T> {
         &self,
         hashes: Vec<ExecutionBlockHash>,
     ) -> Result<Vec<Option<ExecutionPayloadBodyV1<T>>>, Error> {
-        self.engine()
+        let _timer = metrics::start_timer(&metrics::EXECUTION_LAYER_GET_PAYLOAD_BODIES_BY_HASH);
+        let num_requested = hashes.len();
+        let bodies = self
+            .engine()
             .request(|engine: &Engine| async move {
                 engine.api.get_payload_bodies_by_hash_v1(hashes).await
             })
             .await
             .map_err(Box::new)
-            .map_err(Error::EngineError)
+            .map_err(Error::EngineError)?;
+        observe_payload_bodies(metrics::GET_PAYLOAD_BODIES_BY_HASH, num_requested, &bodies);
+        Ok(bodies)
     }
 
     pub async fn get_payload_bodies_by_range(
@@ -1548,7 +1963,8 @@ impl<T: EthSpec> ExecutionLayer<T> {
         count: u64,
     ) -> Result<Vec<Option<ExecutionPayloadBodyV1<T>>>, Error> {
         let _timer = metrics::start_timer(&metrics::EXECUTION_LAYER_GET_PAYLOAD_BODIES_BY_RANGE);
-        self.engine()
+        let bodies = self
+            .engine()
             .request(|engine: &Engine| async move {
                 engine
                     .api
@@ -1557,7 +1973,13 @@ impl<T: EthSpec> ExecutionLayer<T> {
             })
             .await
             .map_err(Box::new)
-            .map_err(Error::EngineError)
+            .map_err(Error::EngineError)?;
+        observe_payload_bodies(
+            metrics::GET_PAYLOAD_BODIES_BY_RANGE,
+            count as usize,
+            &bodies,
+        );
+        Ok(bodies)
     }
 
     /// Fetch a full payload from the execution node.
@@ -1605,6 +2027,59 @@ impl<T: EthSpec> ExecutionLayer<T> {
         }
     }
 
+    /// Rebuild the full payload for `header` using the transactions and withdrawals known to the
+    /// local execution node, via `engine_getPayloadBodiesByHashV1`.
+    ///
+    /// Returns `Ok(None)` if the execution node doesn't support the method, doesn't know the block
+    /// or if the rebuilt payload doesn't hash to the same root as `header`.
+    pub async fn get_payload_for_header_by_hash(
+        &self,
+        header: &ExecutionPayloadHeader<T>,
+        fork: ForkName,
+    ) -> Result<Option<ExecutionPayload<T>>, Error> {
+        let hash = header.block_hash();
+
+        // Handle default payload body.
+        if hash == ExecutionBlockHash::zero() {
+            let payload = match fork {
+                ForkName::Merge => ExecutionPayloadMerge::default().into(),
+                ForkName::Capella => ExecutionPayloadCapella::default().into(),
+                ForkName::Base | ForkName::Altair => {
+                    return Err(Error::InvalidForkForPayload);
+                }
+            };
+            return Ok(Some(payload));
+        }
+
+        let capabilities = self.get_engine_capabilities(None).await?;
+        if !capabilities.get_payload_bodies_by_hash_v1 {
+            return Ok(None);
+        }
+
+        let mut payload_bodies = self.get_payload_bodies_by_hash(vec![hash]).await?;
+        if payload_bodies.len() != 1 {
+            return Ok(None);
+        }
+
+        let payload = match payload_bodies.pop().flatten() {
+            Some(body) => body
+                .to_payload(header.clone())
+                .map_err(Error::InvalidPayloadBody)?,
+            None => return Ok(None),
+        };
+
+        if payload.tree_hash_root() != header.tree_hash_root() {
+            warn!(
+                self.log(),
+                "Payload body from execution node does not match header";
+                "block_hash" => ?hash,
+            );
+            return Ok(None);
+        }
+
+        Ok(Some(payload))
+    }
+
     pub async fn get_block_by_number(
         &self,
         query: BlockByNumberQuery<'_>,
@@ -1738,12 +2213,23 @@ impl<T: EthSpec> ExecutionLayer<T> {
                         .post_builder_blinded_blocks(block)
                         .await
                         .map_err(Error::Builder)
-                        .map(|d| d.data)
+                        .and_then(|d| {
+                            let header = block
+                                .message()
+                                .execution_payload()?
+                                .to_execution_payload_header();
+                            verify_revealed_payload(&header, &d.data)?;
+                            Ok(d.data)
+                        })
                 })
                 .await;
 
             match &payload_result {
                 Ok(payload) => {
+                    // The builder has recovered, so stop bypassing it for future proposals.
+                    self.inner
+                        .last_builder_reveal_failed
+                        .store(false, Ordering::Relaxed);
                     metrics::inc_counter_vec(
                         &metrics::EXECUTION_LAYER_BUILDER_REVEAL_PAYLOAD_OUTCOME,
                         &[metrics::SUCCESS],
@@ -1759,6 +2245,9 @@ impl<T: EthSpec> ExecutionLayer<T> {
                     )
                 }
                 Err(e) => {
+                    self.inner
+                        .last_builder_reveal_failed
+                        .store(true, Ordering::Relaxed);
                     metrics::inc_counter_vec(
                         &metrics::EXECUTION_LAYER_BUILDER_REVEAL_PAYLOAD_OUTCOME,
                         &[metrics::FAILURE],
@@ -1786,6 +2275,60 @@ impl<T: EthSpec> ExecutionLayer<T> {
     }
 }
 
+/// Check that a payload revealed by a builder is the one committed to by the `header` in the
+/// blinded block signed by the proposer.
+///
+/// The cheaper field comparisons are performed first so that the error names the mismatched field
+/// where possible.
+fn verify_revealed_payload<T: EthSpec>(
+    header: &ExecutionPayloadHeader<T>,
+    payload: &ExecutionPayload<T>,
+) -> Result<(), Error> {
+    fn mismatch(
+        field: &'static str,
+        expected: impl fmt::Debug,
+        revealed: impl fmt::Debug,
+    ) -> Error {
+        Error::RevealedPayloadMismatch {
+            field,
+            expected: format!("{:?}", expected),
+            revealed: format!("{:?}", revealed),
+        }
+    }
+
+    if payload.block_hash() != header.block_hash() {
+        return Err(mismatch(
+            "block_hash",
+            header.block_hash(),
+            payload.block_hash(),
+        ));
+    }
+    if payload.fee_recipient() != header.fee_recipient() {
+        return Err(mismatch(
+            "fee_recipient",
+            header.fee_recipient(),
+            payload.fee_recipient(),
+        ));
+    }
+    let transactions_root = payload.transactions().tree_hash_root();
+    if transactions_root != header.transactions_root() {
+        return Err(mismatch(
+            "transactions_root",
+            header.transactions_root(),
+            transactions_root,
+        ));
+    }
+    let payload_root = payload.tree_hash_root();
+    if payload_root != header.tree_hash_root() {
+        return Err(mismatch(
+            "tree_hash_root",
+            header.tree_hash_root(),
+            payload_root,
+        ));
+    }
+    Ok(())
+}
+
 #[derive(AsRefStr)]
 #[strum(serialize_all = "snake_case")]
 enum InvalidBuilderPayload {
@@ -1973,6 +2516,47 @@ async fn timed_future<F: Future<Output = T>, T>(metric: &str, future: F) -> (T,
     (result, duration)
 }
 
+/// Record the difference in gwei between the value of the builder payload and the local payload.
+///
+/// Differences which can't be represented by our Prometheus library are not recorded.
+fn observe_payload_value_delta(relay_value: Uint256, local_value: Uint256) {
+    let relay_value_gwei = relay_value / 1_000_000_000;
+    let local_value_gwei = local_value / 1_000_000_000;
+    let max = Uint256::from(i64::max_value());
+    let delta = if relay_value_gwei >= local_value_gwei {
+        let delta = relay_value_gwei - local_value_gwei;
+        (delta <= max).then(|| delta.low_u64() as i64)
+    } else {
+        let delta = local_value_gwei - relay_value_gwei;
+        (delta <= max).then(|| -(delta.low_u64() as i64))
+    };
+    if let Some(delta) = delta {
+        metrics::set_gauge(&metrics::EXECUTION_LAYER_BUILDER_PAYLOAD_VALUE_DELTA, delta);
+    }
+}
+
+/// Record how many of the `num_requested` payload bodies were supplied by the EE.
+///
+/// Bodies which are `None`, or which were omitted from the end of the response, count as misses.
+fn observe_payload_bodies<T: EthSpec>(
+    method: &str,
+    num_requested: usize,
+    bodies: &[Option<ExecutionPayloadBodyV1<T>>],
+) {
+    let hits = bodies.iter().filter(|body| body.is_some()).count();
+    let misses = num_requested.saturating_sub(hits);
+    metrics::inc_counter_vec_by(
+        &metrics::EXECUTION_LAYER_PAYLOAD_BODIES,
+        &[method, metrics::HIT],
+        hits as u64,
+    );
+    metrics::inc_counter_vec_by(
+        &metrics::EXECUTION_LAYER_PAYLOAD_BODIES,
+        &[method, metrics::MISS],
+        misses as u64,
+    );
+}
+
 fn noop<T: EthSpec>(
     _: &ExecutionLayer<T>,
     _: ExecutionPayloadRef<T>,
@@ -1992,7 +2576,7 @@ fn timestamp_now() -> u64 {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::test_utils::MockExecutionLayer as GenericMockExecutionLayer;
+    use crate::test_utils::{Block, MockExecutionLayer as GenericMockExecutionLayer};
     use task_executor::test_utils::TestRuntime;
     use types::MainnetEthSpec;
 
@@ -2011,6 +2595,91 @@ mod test {
             .await;
     }
 
+    #[tokio::test]
+    async fn payload_for_zero_hash_header_is_default() {
+        let runtime = TestRuntime::default();
+        let mock = MockExecutionLayer::default_params(runtime.task_executor.clone());
+        let header = ExecutionPayloadHeader::Merge(<_>::default());
+
+        assert_eq!(
+            mock.el
+                .get_payload_for_header_by_hash(&header, ForkName::Merge)
+                .await
+                .unwrap(),
+            Some(ExecutionPayloadMerge::default().into())
+        );
+    }
+
+    #[tokio::test]
+    async fn payload_for_header_by_hash_checks_root() {
+        let runtime = TestRuntime::default();
+        let mock = MockExecutionLayer::default_params(runtime.task_executor.clone())
+            .move_to_terminal_block()
+            .produce_valid_execution_payload_on_head()
+            .await;
+
+        let payload = match mock.server.execution_block_generator().latest_block() {
+            Some(Block::PoS(payload)) => payload,
+            other => panic!("expected a post-merge head block, got {:?}", other),
+        };
+        let header = ExecutionPayloadHeader::from(payload.to_ref());
+
+        assert_eq!(
+            mock.el
+                .get_payload_for_header_by_hash(&header, ForkName::Merge)
+                .await
+                .unwrap(),
+            Some(payload)
+        );
+
+        // A header which commits to different transactions must not be matched.
+        let mut mismatched_header = header;
+        *mismatched_header.transactions_root_mut() = Hash256::repeat_byte(0xff);
+        assert_eq!(
+            mock.el
+                .get_payload_for_header_by_hash(&mismatched_header, ForkName::Merge)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn revealed_payload_must_match_header() {
+        let payload: ExecutionPayload<MainnetEthSpec> = ExecutionPayloadMerge {
+            fee_recipient: Address::repeat_byte(1),
+            block_hash: ExecutionBlockHash::repeat_byte(2),
+            transactions: VariableList::new(vec![VariableList::new(vec![3]).unwrap()]).unwrap(),
+            ..<_>::default()
+        }
+        .into();
+        let header = ExecutionPayloadHeader::from(payload.to_ref());
+        verify_revealed_payload(&header, &payload).unwrap();
+
+        let assert_mismatch = |modifier: fn(&mut ExecutionPayload<MainnetEthSpec>), name| {
+            let mut revealed = payload.clone();
+            modifier(&mut revealed);
+            match verify_revealed_payload(&header, &revealed) {
+                Err(Error::RevealedPayloadMismatch { field, .. }) => assert_eq!(field, name),
+                other => panic!("expected {} mismatch, got {:?}", name, other),
+            }
+        };
+
+        assert_mismatch(
+            |p| *p.block_hash_mut() = ExecutionBlockHash::repeat_byte(4),
+            "block_hash",
+        );
+        assert_mismatch(
+            |p| *p.fee_recipient_mut() = Address::repeat_byte(5),
+            "fee_recipient",
+        );
+        assert_mismatch(
+            |p| *p.transactions_mut() = VariableList::empty(),
+            "transactions_root",
+        );
+        assert_mismatch(|p| *p.gas_used_mut() = 6, "tree_hash_root");
+    }
+
     #[tokio::test]
     async fn test_forked_terminal_block() {
         let runtime = TestRuntime::default();
@@ -7,8 +7,11 @@ pub const GET_BLINDED_PAYLOAD: &str = "get_blinded_payload";
 pub const GET_BLINDED_PAYLOAD_LOCAL: &str = "get_blinded_payload_local";
 pub const GET_BLINDED_PAYLOAD_BUILDER: &str = "get_blinded_payload_builder";
 pub const POST_BLINDED_PAYLOAD_BUILDER: &str = "post_blinded_payload_builder";
+pub const POST_BUILDER_VALIDATORS: &str = "post_builder_validators";
 pub const NEW_PAYLOAD: &str = "new_payload";
 pub const FORKCHOICE_UPDATED: &str = "forkchoice_updated";
+pub const GET_PAYLOAD_BODIES_BY_HASH: &str = "get_payload_bodies_by_hash";
+pub const GET_PAYLOAD_BODIES_BY_RANGE: &str = "get_payload_bodies_by_range";
 pub const GET_TERMINAL_POW_BLOCK_HASH: &str = "get_terminal_pow_block_hash";
 pub const IS_VALID_TERMINAL_POW_BLOCK_HASH: &str = "is_valid_terminal_pow_block_hash";
 pub const LOCAL: &str = "local";
@@ -49,6 +52,15 @@ lazy_static::lazy_static! {
         "execution_layer_get_payload_bodies_by_range_time",
         "Time to fetch a range of payload bodies from the EE"
     );
+    pub static ref EXECUTION_LAYER_GET_PAYLOAD_BODIES_BY_HASH: Result<Histogram> = try_create_histogram(
+        "execution_layer_get_payload_bodies_by_hash_time",
+        "Time to fetch a list of payload bodies by block hash from the EE"
+    );
+    pub static ref EXECUTION_LAYER_PAYLOAD_BODIES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "execution_layer_payload_bodies",
+        "Count of payload bodies requested from the EE, by whether the EE was able to supply them",
+        &["method", "event"]
+    );
     pub static ref EXECUTION_LAYER_VERIFY_BLOCK_HASH: Result<Histogram> = try_create_histogram_with_buckets(
         "execution_layer_verify_block_hash_time",
         "Time to verify the execution block hash in Lighthouse, without the EL",
@@ -74,6 +86,25 @@ lazy_static::lazy_static! {
         "The source of each payload returned from get_payload",
         &["source"]
     );
+    pub static ref EXECUTION_LAYER_FEE_RECIPIENT_MISMATCH: Result<IntCounterVec> = try_create_int_counter_vec(
+        "execution_layer_fee_recipient_mismatch_total",
+        "Count of payloads with a fee recipient other than the suggested fee recipient",
+        &["source"]
+    );
+    pub static ref EXECUTION_LAYER_BUILDER_PAYLOAD_VALUE_DELTA: Result<IntGauge> = try_create_int_gauge(
+        "execution_layer_builder_payload_value_delta",
+        "The gwei value of the most recent builder payload minus the value of the local payload",
+    );
+    pub static ref EXECUTION_LAYER_BUILDER_FALLBACK_CONDITIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "execution_layer_builder_fallback_conditions",
+        "The failed chain health conditions which caused the builder to be bypassed",
+        &["condition"]
+    );
+    pub static ref EXECUTION_LAYER_BUILDER_REGISTRATIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "execution_layer_builder_registrations",
+        "Count of validator registrations forwarded to the builder, by outcome",
+        &["outcome"]
+    );
     pub static ref EXECUTION_LAYER_GET_PAYLOAD_BUILDER_REJECTIONS: Result<IntCounterVec> = try_create_int_counter_vec(
         "execution_layer_get_payload_builder_rejections",
         "The reasons why a payload from a builder was rejected",
@@ -1,13 +1,21 @@
 use lru::LruCache;
 use parking_lot::Mutex;
 use tree_hash::TreeHash;
-use types::{EthSpec, ExecutionPayload, Hash256};
+use types::{EthSpec, ExecutionBlockHash, ExecutionPayload, Hash256};
 
 pub const DEFAULT_PAYLOAD_CACHE_SIZE: usize = 10;
 
-/// A cache mapping execution payloads by tree hash roots.
+/// A cache mapping execution payloads by tree hash roots, with a secondary index on block hash.
 pub struct PayloadCache<T: EthSpec> {
-    payloads: Mutex<LruCache<PayloadCacheId, ExecutionPayload<T>>>,
+    inner: Mutex<PayloadCacheInner<T>>,
+}
+
+struct PayloadCacheInner<T: EthSpec> {
+    payloads: LruCache<PayloadCacheId, ExecutionPayload<T>>,
+    /// Maps a payload's `block_hash` to its tree hash root.
+    ///
+    /// Entries may outlive the payload they refer to, in which case lookups simply miss.
+    roots_by_block_hash: LruCache<ExecutionBlockHash, Hash256>,
 }
 
 #[derive(Hash, PartialEq, Eq)]
@@ -16,7 +24,10 @@ struct PayloadCacheId(Hash256);
 impl<T: EthSpec> Default for PayloadCache<T> {
     fn default() -> Self {
         PayloadCache {
-            payloads: Mutex::new(LruCache::new(DEFAULT_PAYLOAD_CACHE_SIZE)),
+            inner: Mutex::new(PayloadCacheInner {
+                payloads: LruCache::new(DEFAULT_PAYLOAD_CACHE_SIZE),
+                roots_by_block_hash: LruCache::new(DEFAULT_PAYLOAD_CACHE_SIZE),
+            }),
         }
     }
 }
@@ -24,14 +35,77 @@ impl<T: EthSpec> Default for PayloadCache<T> {
 impl<T: EthSpec> PayloadCache<T> {
     pub fn put(&self, payload: ExecutionPayload<T>) -> Option<ExecutionPayload<T>> {
         let root = payload.tree_hash_root();
-        self.payloads.lock().put(PayloadCacheId(root), payload)
+        let mut inner = self.inner.lock();
+        inner.roots_by_block_hash.put(payload.block_hash(), root);
+        inner.payloads.put(PayloadCacheId(root), payload)
     }
 
     pub fn pop(&self, root: &Hash256) -> Option<ExecutionPayload<T>> {
-        self.payloads.lock().pop(&PayloadCacheId(*root))
+        let mut inner = self.inner.lock();
+        let payload = inner.payloads.pop(&PayloadCacheId(*root))?;
+        inner.roots_by_block_hash.pop(&payload.block_hash());
+        Some(payload)
     }
 
     pub fn get(&self, hash: &Hash256) -> Option<ExecutionPayload<T>> {
-        self.payloads.lock().get(&PayloadCacheId(*hash)).cloned()
+        self.inner
+            .lock()
+            .payloads
+            .get(&PayloadCacheId(*hash))
+            .cloned()
+    }
+
+    pub fn get_by_block_hash(
+        &self,
+        block_hash: &ExecutionBlockHash,
+    ) -> Option<ExecutionPayload<T>> {
+        let mut inner = self.inner.lock();
+        let root = *inner.roots_by_block_hash.get(block_hash)?;
+        inner.payloads.get(&PayloadCacheId(root)).cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use types::{ExecutionPayloadMerge, MainnetEthSpec};
+
+    type E = MainnetEthSpec;
+
+    fn payload(byte: u8) -> ExecutionPayload<E> {
+        ExecutionPayload::Merge(ExecutionPayloadMerge {
+            block_hash: ExecutionBlockHash::repeat_byte(byte),
+            ..<_>::default()
+        })
+    }
+
+    #[test]
+    fn get_by_root_and_block_hash() {
+        let cache = PayloadCache::<E>::default();
+        let payload = payload(1);
+        let root = payload.tree_hash_root();
+        let block_hash = payload.block_hash();
+
+        assert!(cache.put(payload.clone()).is_none());
+        assert_eq!(cache.get(&root), Some(payload.clone()));
+        assert_eq!(cache.get_by_block_hash(&block_hash), Some(payload.clone()));
+
+        assert_eq!(cache.pop(&root), Some(payload));
+        assert_eq!(cache.get(&root), None);
+        assert_eq!(cache.get_by_block_hash(&block_hash), None);
+    }
+
+    #[test]
+    fn block_hash_index_follows_evictions() {
+        let cache = PayloadCache::<E>::default();
+        let first = payload(0);
+
+        cache.put(first.clone());
+        for i in 1..=DEFAULT_PAYLOAD_CACHE_SIZE as u8 {
+            cache.put(payload(i));
+        }
+
+        assert_eq!(cache.get(&first.tree_hash_root()), None);
+        assert_eq!(cache.get_by_block_hash(&first.block_hash()), None);
     }
 }
@@ -0,0 +1,160 @@
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use types::{PublicKeyBytes, SignedValidatorRegistrationData, ValidatorRegistrationData};
+
+/// A cache of the validator registrations most recently accepted by the builder, keyed by
+/// validator public key.
+///
+/// Validator clients re-submit their registrations every epoch, usually without modification, so
+/// this cache is used to avoid forwarding unchanged registrations to the builder. Registrations are
+/// only meaningful for the builder which accepted them, so the cache also records its URL.
+#[derive(Default)]
+pub struct RegistrationCache {
+    inner: Mutex<Registrations>,
+}
+
+#[derive(Default)]
+struct Registrations {
+    builder_url: Option<String>,
+    registrations: HashMap<PublicKeyBytes, ValidatorRegistrationData>,
+}
+
+impl RegistrationCache {
+    /// Split `registrations` into those which differ from the registration last accepted by the
+    /// builder for the same validator, and the number which are unchanged.
+    pub fn filter_unchanged(
+        &self,
+        registrations: Vec<SignedValidatorRegistrationData>,
+    ) -> (Vec<SignedValidatorRegistrationData>, usize) {
+        let inner = self.inner.lock();
+        let num_registrations = registrations.len();
+        let new_registrations = registrations
+            .into_iter()
+            .filter(|registration| {
+                inner.registrations.get(&registration.message.pubkey) != Some(&registration.message)
+            })
+            .collect::<Vec<_>>();
+        let num_unchanged = num_registrations - new_registrations.len();
+        (new_registrations, num_unchanged)
+    }
+
+    /// Record that `registrations` have been accepted by the builder.
+    ///
+    /// Returns the number of registrations which changed the contents of the cache.
+    pub fn insert(&self, registrations: &[SignedValidatorRegistrationData]) -> usize {
+        let mut inner = self.inner.lock();
+        registrations
+            .iter()
+            .filter(|registration| {
+                inner
+                    .registrations
+                    .insert(registration.message.pubkey, registration.message.clone())
+                    .as_ref()
+                    != Some(&registration.message)
+            })
+            .count()
+    }
+
+    /// Returns the URL of the builder, along with all registrations it has accepted.
+    pub fn registrations(&self) -> (Option<String>, Vec<ValidatorRegistrationData>) {
+        let inner = self.inner.lock();
+        (
+            inner.builder_url.clone(),
+            inner.registrations.values().cloned().collect(),
+        )
+    }
+
+    /// Record that `registrations` were accepted by the builder at `builder_url` prior to a
+    /// restart. They are ignored if that is no longer the builder in use.
+    ///
+    /// Returns the number of registrations restored.
+    pub fn restore(
+        &self,
+        builder_url: &str,
+        registrations: Vec<ValidatorRegistrationData>,
+    ) -> usize {
+        let mut inner = self.inner.lock();
+        if inner.builder_url.as_deref() != Some(builder_url) {
+            return 0;
+        }
+        let num_registrations = registrations.len();
+        for registration in registrations {
+            inner
+                .registrations
+                .entry(registration.pubkey)
+                .or_insert(registration);
+        }
+        num_registrations
+    }
+
+    /// Forget all registrations and record that the builder at `builder_url` is now in use, so
+    /// that all registrations are forwarded to it.
+    pub fn reset(&self, builder_url: String) {
+        let mut inner = self.inner.lock();
+        inner.builder_url = Some(builder_url);
+        inner.registrations.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use types::{Address, Signature};
+
+    fn registration(byte: u8, gas_limit: u64) -> SignedValidatorRegistrationData {
+        SignedValidatorRegistrationData {
+            message: ValidatorRegistrationData {
+                fee_recipient: Address::repeat_byte(byte),
+                gas_limit,
+                timestamp: 0,
+                pubkey: PublicKeyBytes::empty(),
+            },
+            signature: Signature::empty(),
+        }
+    }
+
+    #[test]
+    fn filters_unchanged_registrations() {
+        let cache = RegistrationCache::default();
+        let first = registration(1, 30_000_000);
+
+        let (new, unchanged) = cache.filter_unchanged(vec![first.clone()]);
+        assert_eq!(new, vec![first.clone()]);
+        assert_eq!(unchanged, 0);
+
+        assert_eq!(cache.insert(&new), 1);
+        assert_eq!(cache.insert(&new), 0);
+        let (new, unchanged) = cache.filter_unchanged(vec![first]);
+        assert!(new.is_empty());
+        assert_eq!(unchanged, 1);
+
+        // A change to the registration contents is forwarded again.
+        let updated = registration(1, 36_000_000);
+        let (new, unchanged) = cache.filter_unchanged(vec![updated.clone()]);
+        assert_eq!(new, vec![updated]);
+        assert_eq!(unchanged, 0);
+    }
+
+    #[test]
+    fn restores_registrations_for_same_builder_only() {
+        let cache = RegistrationCache::default();
+        let first = registration(1, 30_000_000);
+
+        cache.reset("http://builder-a".to_string());
+        assert_eq!(
+            cache.restore("http://builder-b", vec![first.message.clone()]),
+            0
+        );
+        assert_eq!(cache.filter_unchanged(vec![first.clone()]).1, 0);
+
+        assert_eq!(
+            cache.restore("http://builder-a", vec![first.message.clone()]),
+            1
+        );
+        assert_eq!(cache.filter_unchanged(vec![first.clone()]).1, 1);
+        assert_eq!(
+            cache.registrations(),
+            (Some("http://builder-a".to_string()), vec![first.message])
+        );
+    }
+}
@@ -36,8 +36,8 @@ use task_executor::TaskExecutor;
 use tempfile::NamedTempFile;
 use tree_hash::TreeHash;
 use types::{
-    Address, BeaconState, BlindedPayload, ChainSpec, EthSpec, ExecPayload, ForkName, Hash256, Slot,
-    Uint256,
+    Address, BeaconState, BlindedPayload, ChainSpec, EthSpec, ExecPayload, ExecutionPayload,
+    ForkName, Hash256, Slot, Uint256,
 };
 
 pub type MockBuilderServer = axum::Server<
@@ -185,6 +185,7 @@ pub struct MockBuilder<E: EthSpec> {
     builder_sk: SecretKey,
     operations: Arc<RwLock<Vec<Operation>>>,
     invalidate_signatures: Arc<RwLock<bool>>,
+    revealed_payload_modifier: Arc<RwLock<Option<fn(&mut ExecutionPayload<E>)>>>,
 }
 
 impl<E: EthSpec> MockBuilder<E> {
@@ -246,6 +247,7 @@ impl<E: EthSpec> MockBuilder<E> {
             builder_sk: sk,
             operations: Arc::new(RwLock::new(vec![])),
             invalidate_signatures: Arc::new(RwLock::new(false)),
+            revealed_payload_modifier: Arc::new(RwLock::new(None)),
         }
     }
 
@@ -263,6 +265,12 @@ impl<E: EthSpec> MockBuilder<E> {
         *self.invalidate_signatures.write() = false;
     }
 
+    /// Apply `modifier` to every payload revealed by the builder, so that it no longer matches the
+    /// header the proposer signed.
+    pub fn modify_revealed_payloads(&self, modifier: fn(&mut ExecutionPayload<E>)) {
+        *self.revealed_payload_modifier.write() = Some(modifier);
+    }
+
     fn apply_operations<B: BidStuff>(&self, bid: &mut B) -> Result<(), MevError> {
         let mut guard = self.operations.write();
         while let Some(op) = guard.pop() {
@@ -464,11 +472,15 @@ impl<E: EthSpec> mev_rs::BlindedBlockProvider for MockBuilder<E> {
         }
         .map_err(convert_err)?;
 
-        let payload = self
+        let mut payload = self
             .el
             .get_payload_by_root(&from_ssz_rs(&node)?)
             .ok_or_else(|| convert_err("missing payload for tx root"))?;
 
+        if let Some(modifier) = *self.revealed_payload_modifier.read() {
+            modifier(&mut payload);
+        }
+
         let json_payload = serde_json::to_string(&payload).map_err(convert_err)?;
         serde_json::from_str(json_payload.as_str()).map_err(convert_err)
     }
@@ -29,6 +29,7 @@ impl<T: EthSpec> MockExecutionLayer<T> {
             DEFAULT_TERMINAL_BLOCK,
             None,
             None,
+            None,
             Some(JwtKey::from_slice(&DEFAULT_JWT_SECRET).unwrap()),
             spec,
         )
@@ -40,6 +41,7 @@ impl<T: EthSpec> MockExecutionLayer<T> {
         terminal_block: u64,
         shanghai_time: Option<u64>,
         builder_threshold: Option<u128>,
+        builder_boost_factor: Option<u64>,
         jwt_key: Option<JwtKey>,
         spec: ChainSpec,
     ) -> Self {
@@ -66,6 +68,7 @@ impl<T: EthSpec> MockExecutionLayer<T> {
             secret_files: vec![path],
             suggested_fee_recipient: Some(Address::repeat_byte(42)),
             builder_profit_threshold: builder_threshold.unwrap_or(DEFAULT_BUILDER_THRESHOLD_WEI),
+            builder_boost_factor,
             ..Default::default()
         };
         let el =
@@ -1,24 +1,36 @@
+use crate::task_spawner::{Priority, TaskSpawner};
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use bytes::Bytes;
 use eth2::lighthouse::{
     AttestationPerformance, AttestationPerformanceQuery, AttestationPerformanceStatistics,
+    EpochAttestationPerformance,
 };
+use slog::debug;
 use state_processing::{
     per_epoch_processing::altair::participation_cache::Error as ParticipationCacheError,
     per_epoch_processing::EpochProcessingSummary, BlockReplayError, BlockReplayer,
 };
 use std::sync::Arc;
-use types::{BeaconState, BeaconStateError, EthSpec, Hash256};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use types::{BeaconState, BeaconStateError, EthSpec, Hash256, Slot};
+use warp::hyper::Body;
+use warp::reply::Response;
 use warp_utils::reject::{beacon_chain_error, custom_bad_request, custom_server_error};
 
-const MAX_REQUEST_RANGE_EPOCHS: usize = 100;
 const BLOCK_ROOT_CHUNK_SIZE: usize = 100;
 
+/// The number of serialized epochs which may be buffered whilst streaming a response.
+const STREAM_BUFFERED_EPOCHS: usize = 2;
+
 #[derive(Debug)]
 enum AttestationPerformanceError {
     BlockReplay(BlockReplayError),
     BeaconState(BeaconStateError),
     ParticipationCache(ParticipationCacheError),
     UnableToFindValidator(usize),
+    Serialize(String),
+    ResponseDropped,
 }
 
 impl From<BlockReplayError> for AttestationPerformanceError {
@@ -39,12 +51,19 @@ impl From<ParticipationCacheError> for AttestationPerformanceError {
     }
 }
 
-pub fn get_attestation_performance<T: BeaconChainTypes>(
-    target: String,
-    query: AttestationPerformanceQuery,
-    chain: Arc<BeaconChain<T>>,
-) -> Result<Vec<AttestationPerformance>, warp::Rejection> {
-    let spec = &chain.spec;
+/// The slots to replay in order to analyse the epochs of an `AttestationPerformanceQuery`.
+struct ReplayRange {
+    prior_slot: Slot,
+    start_slot: Slot,
+    end_slot: Slot,
+}
+
+/// Check that `query` is valid and return the slots to replay in order to analyse it.
+fn replay_range<T: BeaconChainTypes>(
+    query: &AttestationPerformanceQuery,
+    max_request_range_epochs: usize,
+    chain: &BeaconChain<T>,
+) -> Result<ReplayRange, warp::Rejection> {
     // We increment by 2 here so that when we build the state from the `prior_slot` it is
     // still 1 epoch ahead of the first epoch we want to analyse.
     // This ensures the `.is_previous_epoch_X` functions on `EpochProcessingSummary` return results
@@ -56,15 +75,6 @@ pub fn get_attestation_performance<T: BeaconChainTypes>(
     let end_epoch = query.end_epoch + 2;
     let end_slot = end_epoch.end_slot(T::EthSpec::slots_per_epoch());
 
-    // Ensure end_epoch is smaller than the current epoch - 1.
-    let current_epoch = chain.epoch().map_err(beacon_chain_error)?;
-    if query.end_epoch >= current_epoch - 1 {
-        return Err(custom_bad_request(format!(
-            "end_epoch must be less than the current epoch - 1. current: {}, end: {}",
-            current_epoch, query.end_epoch
-        )));
-    }
-
     // Check query is valid.
     if start_epoch > end_epoch {
         return Err(custom_bad_request(format!(
@@ -75,34 +85,63 @@ pub fn get_attestation_performance<T: BeaconChainTypes>(
 
     // The response size can grow exceptionally large therefore we should check that the
     // query is within permitted bounds to prevent potential OOM errors.
-    if (end_epoch - start_epoch).as_usize() > MAX_REQUEST_RANGE_EPOCHS {
+    if (end_epoch - start_epoch).as_usize() > max_request_range_epochs {
         return Err(custom_bad_request(format!(
             "end_epoch must not exceed start_epoch by more than {} epochs. start: {}, end: {}",
-            MAX_REQUEST_RANGE_EPOCHS, query.start_epoch, query.end_epoch
+            max_request_range_epochs, query.start_epoch, query.end_epoch
         )));
     }
 
-    // Either use the global validator set, or the specified index.
-    //
-    // Does no further validation of the indices, so in the event an index has not yet been
-    // activated or does not yet exist (according to the head state), it will return all fields as
-    // `false`.
-    let index_range = if target.to_lowercase() == "global" {
-        chain
-            .with_head(|head| Ok((0..head.beacon_state.validators().len() as u64).collect()))
-            .map_err(beacon_chain_error)?
-    } else {
-        vec![target.parse::<u64>().map_err(|_| {
-            custom_bad_request(format!(
-                "Invalid validator index: {:?}",
-                target.to_lowercase()
-            ))
-        })?]
-    };
+    // Ensure end_epoch is smaller than the current epoch - 1.
+    let current_epoch = chain.epoch().map_err(beacon_chain_error)?;
+    if query.end_epoch >= current_epoch - 1 {
+        return Err(custom_bad_request(format!(
+            "end_epoch must be less than the current epoch - 1. current: {}, end: {}",
+            current_epoch, query.end_epoch
+        )));
+    }
+
+    Ok(ReplayRange {
+        prior_slot,
+        start_slot,
+        end_slot,
+    })
+}
+
+/// Returns the attestation performance of the validator at `index`, according to the `summary`
+/// of the epoch after the one being analysed.
+fn attestation_performance_statistics<E: EthSpec>(
+    summary: &EpochProcessingSummary<E>,
+    index: usize,
+) -> Result<AttestationPerformanceStatistics, AttestationPerformanceError> {
+    Ok(AttestationPerformanceStatistics {
+        active: summary.is_active_unslashed_in_previous_epoch(index),
+        head: summary.is_previous_epoch_head_attester(index)?,
+        target: summary.is_previous_epoch_target_attester(index)?,
+        source: summary.is_previous_epoch_source_attester(index)?,
+        delay: summary
+            .previous_epoch_inclusion_info(index)
+            .map(|info| info.delay),
+    })
+}
+
+/// Replay the blocks in `range`, calling `on_epoch` with each analysed epoch, the number of
+/// validators in the replayed state and the summary from which its attestation performance can be
+/// read.
+fn replay_epochs<T: BeaconChainTypes>(
+    range: ReplayRange,
+    chain: &BeaconChain<T>,
+    mut on_epoch: impl FnMut(
+        u64,
+        usize,
+        &EpochProcessingSummary<T::EthSpec>,
+    ) -> Result<(), AttestationPerformanceError>,
+) -> Result<(), warp::Rejection> {
+    let spec = &chain.spec;
 
     // Load block roots.
     let mut block_roots: Vec<Hash256> = chain
-        .forwards_iter_block_roots_until(start_slot, end_slot)
+        .forwards_iter_block_roots_until(range.start_slot, range.end_slot)
         .map_err(beacon_chain_error)?
         .map(|res| res.map(|(root, _)| root))
         .collect::<Result<Vec<Hash256>, _>>()
@@ -134,14 +173,10 @@ pub fn get_attestation_performance<T: BeaconChainTypes>(
     // Load state for block replay.
     let state_root = prior_block.state_root();
     let state = chain
-        .get_state(&state_root, Some(prior_slot))
+        .get_state(&state_root, Some(range.prior_slot))
         .and_then(|maybe_state| maybe_state.ok_or(BeaconChainError::MissingBeaconState(state_root)))
         .map_err(beacon_chain_error)?;
 
-    // Allocate an AttestationPerformance vector for each validator in the range.
-    let mut perfs: Vec<AttestationPerformance> =
-        AttestationPerformance::initialize(index_range.clone());
-
     let post_slot_hook = |state: &mut BeaconState<T::EthSpec>,
                           summary: Option<EpochProcessingSummary<T::EthSpec>>,
                           _is_skip_slot: bool|
@@ -149,40 +184,11 @@ pub fn get_attestation_performance<T: BeaconChainTypes>(
         // If a `summary` was not output then an epoch boundary was not crossed
         // so we move onto the next slot.
         if let Some(summary) = summary {
-            for (position, i) in index_range.iter().enumerate() {
-                let index = *i as usize;
-
-                let val = perfs
-                    .get_mut(position)
-                    .ok_or(AttestationPerformanceError::UnableToFindValidator(index))?;
-
-                // We are two epochs ahead since the summary is generated for
-                // `state.previous_epoch()` then `summary.is_previous_epoch_X` functions return
-                // data for the epoch before that.
-                let epoch = state.previous_epoch().as_u64() - 1;
-
-                let is_active = summary.is_active_unslashed_in_previous_epoch(index);
-
-                let received_source_reward = summary.is_previous_epoch_source_attester(index)?;
-
-                let received_head_reward = summary.is_previous_epoch_head_attester(index)?;
-
-                let received_target_reward = summary.is_previous_epoch_target_attester(index)?;
-
-                let inclusion_delay = summary
-                    .previous_epoch_inclusion_info(index)
-                    .map(|info| info.delay);
-
-                let perf = AttestationPerformanceStatistics {
-                    active: is_active,
-                    head: received_head_reward,
-                    target: received_target_reward,
-                    source: received_source_reward,
-                    delay: inclusion_delay,
-                };
-
-                val.epochs.insert(epoch, perf);
-            }
+            // We are two epochs ahead since the summary is generated for
+            // `state.previous_epoch()` then `summary.is_previous_epoch_X` functions return
+            // data for the epoch before that.
+            let epoch = state.previous_epoch().as_u64() - 1;
+            on_epoch(epoch, state.validators().len(), &summary)?;
         }
         Ok(())
     };
@@ -214,7 +220,132 @@ pub fn get_attestation_performance<T: BeaconChainTypes>(
             .map_err(|e| custom_server_error(format!("{:?}", e)))?;
     }
 
-    drop(replayer);
+    Ok(())
+}
+
+pub fn get_attestation_performance<T: BeaconChainTypes>(
+    target: String,
+    query: AttestationPerformanceQuery,
+    max_request_range_epochs: usize,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Vec<AttestationPerformance>, warp::Rejection> {
+    let range = replay_range(&query, max_request_range_epochs, &chain)?;
+
+    // Either use the global validator set, or the specified index.
+    //
+    // Does no further validation of the indices, so in the event an index has not yet been
+    // activated or does not yet exist (according to the head state), it will return all fields as
+    // `false`.
+    let target = target.to_lowercase();
+    let index_range = if target == "global" {
+        chain
+            .with_head(|head| Ok((0..head.beacon_state.validators().len() as u64).collect()))
+            .map_err(beacon_chain_error)?
+    } else {
+        vec![target
+            .parse::<u64>()
+            .map_err(|_| custom_bad_request(format!("Invalid validator index: {:?}", target)))?]
+    };
+
+    // Allocate an AttestationPerformance vector for each validator in the range.
+    let mut perfs: Vec<AttestationPerformance> =
+        AttestationPerformance::initialize(index_range.clone());
+
+    replay_epochs(range, &chain, |epoch, _, summary| {
+        for (position, i) in index_range.iter().enumerate() {
+            let index = *i as usize;
+
+            let val = perfs
+                .get_mut(position)
+                .ok_or(AttestationPerformanceError::UnableToFindValidator(index))?;
+
+            val.epochs
+                .insert(epoch, attestation_performance_statistics(summary, index)?);
+        }
+        Ok(())
+    })?;
 
     Ok(perfs)
 }
+
+/// Stream the attestation performance of the entire validator set as a JSON array of
+/// `EpochAttestationPerformance`, one epoch at a time.
+///
+/// Only a bounded number of epochs are held in memory: the blocks are replayed as a `P1` task on
+/// the `task_spawner`, which waits for each epoch to be sent to the client before analysing the
+/// next.
+pub async fn stream_attestation_performance<T: BeaconChainTypes>(
+    query: AttestationPerformanceQuery,
+    max_request_range_epochs: usize,
+    task_spawner: TaskSpawner<T::EthSpec>,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Response, warp::Rejection> {
+    let range = replay_range(&query, max_request_range_epochs, &chain)?;
+    let (tx, rx) = mpsc::channel::<Result<Bytes, std::io::Error>>(STREAM_BUFFERED_EPOCHS);
+    let abort_tx = tx.clone();
+
+    let replay_chain = chain.clone();
+    let replay = move || -> Result<(), warp::Rejection> {
+        let chain = replay_chain;
+        let mut separator = "[";
+        let result = replay_epochs(range, &chain, |epoch, num_validators, summary| {
+            let validators = (0..num_validators)
+                .map(|index| attestation_performance_statistics(summary, index))
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut chunk = separator.as_bytes().to_vec();
+            serde_json::to_writer(
+                &mut chunk,
+                &EpochAttestationPerformance { epoch, validators },
+            )
+            .map_err(|e| AttestationPerformanceError::Serialize(e.to_string()))?;
+            separator = ",";
+            tx.blocking_send(Ok(chunk.into()))
+                .map_err(|_| AttestationPerformanceError::ResponseDropped)
+        });
+
+        let last_chunk = match result {
+            // An empty range still produces a valid (empty) array.
+            Ok(()) if separator == "[" => Ok(Bytes::from_static(b"[]")),
+            Ok(()) => Ok(Bytes::from_static(b"]")),
+            // The status has already been sent, so abort the response with an error.
+            Err(e) => {
+                debug!(
+                    chain.log,
+                    "Failed to stream attestation performance";
+                    "error" => ?e
+                );
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    "failed to compute attestation performance",
+                ))
+            }
+        };
+        let _ = tx.blocking_send(last_chunk);
+        // Errors are reported to the client through the stream, since the status has been sent.
+        Ok(())
+    };
+
+    // The replay feeds the response body as it goes, so it is not awaited before responding.
+    chain.task_executor.spawn(
+        async move {
+            let response = task_spawner.blocking_json_task(Priority::P1, replay).await;
+            // The replay is dropped if the server is overloaded or shutting down, in which case
+            // the response must be aborted rather than left truncated.
+            if !response.status().is_success() {
+                let _ = abort_tx
+                    .send(Err(std::io::Error::new(
+                        std::io::ErrorKind::Other,
+                        "attestation performance replay did not run",
+                    )))
+                    .await;
+            }
+        },
+        "stream_attestation_performance",
+    );
+
+    Response::builder()
+        .status(200)
+        .header("Content-Type", "application/json")
+        .body(Body::wrap_stream(ReceiverStream::new(rx)))
+        .map_err(|e| custom_server_error(format!("failed to create response: {}", e)))
+}
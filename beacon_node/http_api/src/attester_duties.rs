@@ -50,6 +50,11 @@ pub fn attester_duties<T: BeaconChainTypes>(
     }
 }
 
+/// Compute attester duties from the committee cache in the beacon chain's shuffling cache,
+/// without cloning the head state unless the shuffling is not yet known.
+///
+/// Concurrent requests for an unknown shuffling wait on a single computation of the committee
+/// cache, rather than each building it.
 fn cached_attestation_duties<T: BeaconChainTypes>(
     request_epoch: Epoch,
     request_indices: &[u64],
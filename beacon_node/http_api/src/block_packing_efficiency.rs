@@ -15,6 +15,8 @@ use types::{
 };
 use warp_utils::reject::{beacon_chain_error, custom_bad_request, custom_server_error};
 
+/// The maximum number of epochs which may be analysed in a single request.
+const MAX_REQUEST_RANGE_EPOCHS: usize = 100;
 /// Load blocks from block roots in chunks to reduce load on memory.
 const BLOCK_ROOT_CHUNK_SIZE: usize = 100;
 
@@ -232,6 +234,15 @@ pub fn get_block_packing_efficiency<T: BeaconChainTypes>(
         )));
     }
 
+    // Each epoch in the range requires replaying its blocks, so bound the range to prevent
+    // expensive queries from exhausting the node's resources.
+    if (end_epoch - start_epoch).as_usize() > MAX_REQUEST_RANGE_EPOCHS {
+        return Err(custom_bad_request(format!(
+            "end_epoch must not exceed start_epoch by more than {} epochs. start: {}, end: {}",
+            MAX_REQUEST_RANGE_EPOCHS, start_epoch, end_epoch
+        )));
+    }
+
     let prior_epoch = start_epoch - 1;
     let start_slot_of_prior_epoch = prior_epoch.start_slot(T::EthSpec::slots_per_epoch());
 
@@ -1,6 +1,6 @@
 use beacon_chain::store::{metadata::CURRENT_SCHEMA_VERSION, AnchorInfo};
-use beacon_chain::{BeaconChain, BeaconChainTypes};
-use eth2::lighthouse::DatabaseInfo;
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::lighthouse::{CacheInfo, CachesInfo, DatabaseInfo};
 use std::sync::Arc;
 use types::SignedBlindedBeaconBlock;
 
@@ -11,12 +11,45 @@ pub fn info<T: BeaconChainTypes>(
     let split = store.get_split_info();
     let config = store.get_config().clone();
     let anchor = store.get_anchor_info();
+    let restore_point_migration = store
+        .get_restore_point_migration()
+        .map_err(|e| warp_utils::reject::beacon_chain_error(BeaconChainError::DBError(e)))?;
+    // Only report the statistics gathered by the background task, gathering them here would
+    // scan the entire database on the request path.
+    let stats = store.cached_database_stats();
+
+    let caches = CachesInfo {
+        snapshot: CacheInfo {
+            capacity: chain.config.snapshot_cache_size,
+            len: chain
+                .snapshot_cache_len()
+                .map_err(warp_utils::reject::beacon_chain_error)?,
+        },
+        shuffling: CacheInfo {
+            capacity: chain.config.shuffling_cache_size,
+            len: chain
+                .shuffling_cache_len()
+                .map_err(warp_utils::reject::beacon_chain_error)?,
+        },
+        block: CacheInfo {
+            capacity: config.block_cache_size,
+            len: store.block_cache_len(),
+        },
+        historic_state: CacheInfo {
+            capacity: config.historic_state_cache_size,
+            len: store.historic_state_cache_len(),
+        },
+    };
 
     Ok(DatabaseInfo {
         schema_version: CURRENT_SCHEMA_VERSION.as_u64(),
         config,
         split,
         anchor,
+        caches,
+        restore_point_migration,
+        slots_per_restore_point: store.slots_per_restore_point(),
+        stats: stats.map(|stats| stats.as_ref().clone()),
     })
 }
 
@@ -12,6 +12,7 @@ mod block_packing_efficiency;
 mod block_rewards;
 mod builder_states;
 mod database;
+mod light_client;
 mod metrics;
 mod proposer_duties;
 mod publish_blocks;
@@ -29,7 +30,7 @@ mod version;
 use beacon_chain::{
     attestation_verification::VerifiedAttestation, observed_operations::ObservationOutcome,
     validator_monitor::timestamp_now, AttestationError as AttnError, BeaconChain, BeaconChainError,
-    BeaconChainTypes, ProduceBlockVerification, WhenSlotSkipped,
+    BeaconChainTypes, BlockProductionTimings, ProduceBlockVerification, WhenSlotSkipped,
 };
 use beacon_processor::BeaconProcessorSend;
 pub use block_id::BlockId;
@@ -37,10 +38,18 @@ use builder_states::get_next_withdrawals;
 use bytes::Bytes;
 use directory::DEFAULT_ROOT_DIR;
 use eth2::types::{
-    self as api_types, BroadcastValidation, EndpointVersion, ForkChoice, ForkChoiceNode,
-    SkipRandaoVerification, ValidatorId, ValidatorStatus,
+    self as api_types, EndpointVersion, ForkChoice, ForkChoiceNode, SkipRandaoVerification,
+    ValidatorId, ValidatorStatus,
+};
+use eth2::{block_production_stage_header, BLOCK_PRODUCTION_TIME_HEADER, CONSENSUS_VERSION_HEADER};
+use light_client::{
+    get_light_client_bootstrap, get_light_client_finality_update,
+    get_light_client_optimistic_update, get_light_client_updates,
+};
+use lighthouse_network::{
+    types::{NatState, SyncState},
+    EnrExt, NetworkGlobals, PeerId, PubsubMessage, TARGET_SUBNET_PEERS,
 };
-use lighthouse_network::{types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage};
 use lighthouse_version::version_with_platform;
 use logging::SSELoggingComponents;
 use network::{NetworkMessage, NetworkSenders, ValidatorSubscriptionMessage};
@@ -62,11 +71,9 @@ use std::pin::Pin;
 use std::sync::Arc;
 use sysinfo::{System, SystemExt};
 use system_health::observe_system_health_bn;
-use task_spawner::{Priority, TaskSpawner};
-use tokio::sync::{
-    mpsc::{Sender, UnboundedSender},
-    oneshot,
-};
+use task_spawner::{convert_rejection, Priority, TaskSpawner};
+use tokio::sync::mpsc::{Sender, UnboundedSender};
+use tokio::sync::oneshot;
 use tokio_stream::{
     wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
     StreamExt,
@@ -74,7 +81,7 @@ use tokio_stream::{
 use types::{
     Attestation, AttestationData, AttestationShufflingId, AttesterSlashing, BeaconStateError,
     BlindedPayload, CommitteeCache, ConfigAndPreset, Epoch, EthSpec, ForkName, FullPayload,
-    ProposerPreparationData, ProposerSlashing, RelativeEpoch, SignedAggregateAndProof,
+    Hash256, ProposerPreparationData, ProposerSlashing, RelativeEpoch, SignedAggregateAndProof,
     SignedBeaconBlock, SignedBlindedBeaconBlock, SignedBlsToExecutionChange,
     SignedContributionAndProof, SignedValidatorRegistrationData, SignedVoluntaryExit, Slot,
     SyncCommitteeMessage, SyncContributionData,
@@ -84,6 +91,7 @@ use version::{
     add_consensus_version_header, execution_optimistic_finalized_fork_versioned_response,
     fork_versioned_response, inconsistent_fork_rejection, unsupported_version_rejection, V1, V2,
 };
+use warp::http::header::{HeaderName, HeaderValue};
 use warp::http::StatusCode;
 use warp::sse::Event;
 use warp::Reply;
@@ -141,6 +149,11 @@ pub struct Config {
     pub enable_beacon_processor: bool,
     #[serde(with = "eth2::types::serde_status_code")]
     pub duplicate_block_status_code: StatusCode,
+    /// The maximum number of epochs which may be requested from the attestation performance
+    /// analysis endpoint.
+    pub attestation_performance_max_epochs: usize,
+    /// Whether backfill sync is rate-limited, as reported by the node syncing endpoint.
+    pub backfill_rate_limited: bool,
 }
 
 impl Default for Config {
@@ -157,6 +170,8 @@ impl Default for Config {
             sse_capacity_multiplier: 1,
             enable_beacon_processor: true,
             duplicate_block_status_code: StatusCode::ACCEPTED,
+            attestation_performance_max_epochs: 100,
+            backfill_rate_limited: true,
         }
     }
 }
@@ -514,6 +529,8 @@ pub fn serve<T: BeaconChainTypes>(
         warp::any().map(move || TaskSpawner::new(beacon_processor_send.clone()));
 
     let duplicate_block_status_code = ctx.config.duplicate_block_status_code;
+    let attestation_performance_max_epochs = ctx.config.attestation_performance_max_epochs;
+    let backfill_rate_limited = ctx.config.backfill_rate_limited;
 
     /*
      *
@@ -879,7 +896,9 @@ pub fn serve<T: BeaconChainTypes>(
                                 };
 
                                 // Attempt to read from the chain cache if there exists a
-                                // shuffling_id
+                                // shuffling_id. Don't wait on a pending promise, computing the
+                                // committees from the state we already have is cheaper than
+                                // tying up this thread.
                                 let maybe_cached_shuffling = if let Some(shuffling_id) =
                                     shuffling_id.as_ref()
                                 {
@@ -887,7 +906,7 @@ pub fn serve<T: BeaconChainTypes>(
                                         .shuffling_cache
                                         .try_write_for(std::time::Duration::from_secs(1))
                                         .and_then(|mut cache_write| cache_write.get(shuffling_id))
-                                        .and_then(|cache_item| cache_item.wait().ok())
+                                        .and_then(|cache_item| cache_item.committee())
                                 } else {
                                     None
                                 };
@@ -1282,6 +1301,7 @@ pub fn serve<T: BeaconChainTypes>(
     let post_beacon_blocks = eth_v1
         .and(warp::path("beacon"))
         .and(warp::path("blocks"))
+        .and(warp::query::<api_types::BroadcastValidationQuery>())
         .and(warp::path::end())
         .and(warp::body::json())
         .and(task_spawner_filter.clone())
@@ -1289,7 +1309,8 @@ pub fn serve<T: BeaconChainTypes>(
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
         .then(
-            move |block: Arc<SignedBeaconBlock<T::EthSpec>>,
+            move |validation_level: api_types::BroadcastValidationQuery,
+                  block: Arc<SignedBeaconBlock<T::EthSpec>>,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
@@ -1301,7 +1322,7 @@ pub fn serve<T: BeaconChainTypes>(
                         chain,
                         &network_tx,
                         log,
-                        BroadcastValidation::default(),
+                        validation_level.broadcast_validation,
                         duplicate_block_status_code,
                     )
                     .await
@@ -1312,33 +1333,34 @@ pub fn serve<T: BeaconChainTypes>(
     let post_beacon_blocks_ssz = eth_v1
         .and(warp::path("beacon"))
         .and(warp::path("blocks"))
+        .and(warp::query::<api_types::BroadcastValidationQuery>())
         .and(warp::path::end())
+        .and(warp::header::optional::<String>(CONSENSUS_VERSION_HEADER))
         .and(warp::body::bytes())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
         .then(
-            move |block_bytes: Bytes,
+            move |validation_level: api_types::BroadcastValidationQuery,
+                  consensus_version: Option<String>,
+                  block_bytes: Bytes,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
                   log: Logger| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
-                    let block =
-                        SignedBeaconBlock::<T::EthSpec>::from_ssz_bytes(&block_bytes, &chain.spec)
-                            .map_err(|e| {
-                                warp_utils::reject::custom_bad_request(format!(
-                                    "invalid SSZ: {e:?}"
-                                ))
-                            })?;
+                    let block = publish_blocks::decode_ssz_block::<
+                        T::EthSpec,
+                        FullPayload<T::EthSpec>,
+                    >(&block_bytes, consensus_version, &chain.spec)?;
                     publish_blocks::publish_block(
                         None,
                         ProvenancedBlock::local(Arc::new(block)),
                         chain,
                         &network_tx,
                         log,
-                        BroadcastValidation::default(),
+                        validation_level.broadcast_validation,
                         duplicate_block_status_code,
                     )
                     .await
@@ -1383,6 +1405,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("blocks"))
         .and(warp::query::<api_types::BroadcastValidationQuery>())
         .and(warp::path::end())
+        .and(warp::header::optional::<String>(CONSENSUS_VERSION_HEADER))
         .and(warp::body::bytes())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
@@ -1390,19 +1413,17 @@ pub fn serve<T: BeaconChainTypes>(
         .and(log_filter.clone())
         .then(
             move |validation_level: api_types::BroadcastValidationQuery,
+                  consensus_version: Option<String>,
                   block_bytes: Bytes,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
                   log: Logger| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
-                    let block =
-                        SignedBeaconBlock::<T::EthSpec>::from_ssz_bytes(&block_bytes, &chain.spec)
-                            .map_err(|e| {
-                                warp_utils::reject::custom_bad_request(format!(
-                                    "invalid SSZ: {e:?}"
-                                ))
-                            })?;
+                    let block = publish_blocks::decode_ssz_block::<
+                        T::EthSpec,
+                        FullPayload<T::EthSpec>,
+                    >(&block_bytes, consensus_version, &chain.spec)?;
                     publish_blocks::publish_block(
                         None,
                         ProvenancedBlock::local(Arc::new(block)),
@@ -1425,6 +1446,7 @@ pub fn serve<T: BeaconChainTypes>(
     let post_beacon_blinded_blocks = eth_v1
         .and(warp::path("beacon"))
         .and(warp::path("blinded_blocks"))
+        .and(warp::query::<api_types::BroadcastValidationQuery>())
         .and(warp::path::end())
         .and(warp::body::json())
         .and(task_spawner_filter.clone())
@@ -1432,7 +1454,8 @@ pub fn serve<T: BeaconChainTypes>(
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
         .then(
-            move |block: SignedBlindedBeaconBlock<T::EthSpec>,
+            move |validation_level: api_types::BroadcastValidationQuery,
+                  block: SignedBlindedBeaconBlock<T::EthSpec>,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
@@ -1443,7 +1466,7 @@ pub fn serve<T: BeaconChainTypes>(
                         chain,
                         &network_tx,
                         log,
-                        BroadcastValidation::default(),
+                        validation_level.broadcast_validation,
                         duplicate_block_status_code,
                     )
                     .await
@@ -1455,32 +1478,34 @@ pub fn serve<T: BeaconChainTypes>(
     let post_beacon_blinded_blocks_ssz = eth_v1
         .and(warp::path("beacon"))
         .and(warp::path("blinded_blocks"))
+        .and(warp::query::<api_types::BroadcastValidationQuery>())
         .and(warp::path::end())
+        .and(warp::header::optional::<String>(CONSENSUS_VERSION_HEADER))
         .and(warp::body::bytes())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
         .then(
-            move |block_bytes: Bytes,
+            move |validation_level: api_types::BroadcastValidationQuery,
+                  consensus_version: Option<String>,
+                  block_bytes: Bytes,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
                   log: Logger| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
-                    let block = SignedBlindedBeaconBlock::<T::EthSpec>::from_ssz_bytes(
+                    let block = publish_blocks::decode_ssz_block::<T::EthSpec, _>(
                         &block_bytes,
+                        consensus_version,
                         &chain.spec,
-                    )
-                    .map_err(|e| {
-                        warp_utils::reject::custom_bad_request(format!("invalid SSZ: {e:?}"))
-                    })?;
+                    )?;
                     publish_blocks::publish_blinded_block(
                         block,
                         chain,
                         &network_tx,
                         log,
-                        BroadcastValidation::default(),
+                        validation_level.broadcast_validation,
                         duplicate_block_status_code,
                     )
                     .await
@@ -1524,6 +1549,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("blinded_blocks"))
         .and(warp::query::<api_types::BroadcastValidationQuery>())
         .and(warp::path::end())
+        .and(warp::header::optional::<String>(CONSENSUS_VERSION_HEADER))
         .and(warp::body::bytes())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
@@ -1531,19 +1557,18 @@ pub fn serve<T: BeaconChainTypes>(
         .and(log_filter.clone())
         .then(
             move |validation_level: api_types::BroadcastValidationQuery,
+                  consensus_version: Option<String>,
                   block_bytes: Bytes,
                   task_spawner: TaskSpawner<T::EthSpec>,
                   chain: Arc<BeaconChain<T>>,
                   network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
                   log: Logger| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
-                    let block = SignedBlindedBeaconBlock::<T::EthSpec>::from_ssz_bytes(
+                    let block = publish_blocks::decode_ssz_block::<T::EthSpec, _>(
                         &block_bytes,
+                        consensus_version,
                         &chain.spec,
-                    )
-                    .map_err(|e| {
-                        warp_utils::reject::custom_bad_request(format!("invalid SSZ: {e:?}"))
-                    })?;
+                    )?;
                     publish_blocks::publish_blinded_block(
                         block,
                         chain,
@@ -2208,40 +2233,109 @@ pub fn serve<T: BeaconChainTypes>(
             |accept_header: Option<api_types::Accept>,
              task_spawner: TaskSpawner<T::EthSpec>,
              eth1_service: eth1::Service| {
-                task_spawner.blocking_response_task(Priority::P1, move || match accept_header {
-                    Some(api_types::Accept::Json) | None => {
-                        let snapshot = eth1_service.get_deposit_snapshot();
-                        Ok(
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let snapshot = eth1_service.get_deposit_snapshot().ok_or_else(|| {
+                        warp_utils::reject::custom_not_found(
+                            "no finalized deposit snapshot is available".to_string(),
+                        )
+                    })?;
+                    match accept_header {
+                        Some(api_types::Accept::Ssz) => Response::builder()
+                            .status(200)
+                            .header("Content-Type", "application/octet-stream")
+                            .body(snapshot.as_ssz_bytes().into())
+                            .map_err(|e| {
+                                warp_utils::reject::custom_server_error(format!(
+                                    "failed to create response: {}",
+                                    e
+                                ))
+                            }),
+                        _ => Ok(
                             warp::reply::json(&api_types::GenericResponse::from(snapshot))
                                 .into_response(),
-                        )
+                        ),
                     }
-                    _ => eth1_service
-                        .get_deposit_snapshot()
-                        .map(|snapshot| {
-                            Response::builder()
-                                .status(200)
-                                .header("Content-Type", "application/octet-stream")
-                                .body(snapshot.as_ssz_bytes().into())
-                                .map_err(|e| {
-                                    warp_utils::reject::custom_server_error(format!(
-                                        "failed to create response: {}",
-                                        e
-                                    ))
-                                })
-                        })
-                        .unwrap_or_else(|| {
-                            Response::builder()
-                                .status(503)
-                                .header("Content-Type", "application/octet-stream")
-                                .body(Vec::new().into())
-                                .map_err(|e| {
-                                    warp_utils::reject::custom_server_error(format!(
-                                        "failed to create response: {}",
-                                        e
-                                    ))
-                                })
-                        }),
+                })
+            },
+        );
+
+    let beacon_light_client_path = eth_v1
+        .and(warp::path("beacon"))
+        .and(warp::path("light_client"))
+        .and(chain_filter.clone());
+
+    // GET beacon/light_client/bootstrap/{block_root}
+    let get_beacon_light_client_bootstrap = beacon_light_client_path
+        .clone()
+        .and(task_spawner_filter.clone())
+        .and(warp::path("bootstrap"))
+        .and(warp::path::param::<Hash256>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid block root value".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .then(
+            |chain: Arc<BeaconChain<T>>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             block_root: Hash256,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    get_light_client_bootstrap(chain, &block_root, accept_header)
+                })
+            },
+        );
+
+    // GET beacon/light_client/finality_update
+    let get_beacon_light_client_finality_update = beacon_light_client_path
+        .clone()
+        .and(task_spawner_filter.clone())
+        .and(warp::path("finality_update"))
+        .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .then(
+            |chain: Arc<BeaconChain<T>>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    get_light_client_finality_update(chain, accept_header)
+                })
+            },
+        );
+
+    // GET beacon/light_client/optimistic_update
+    let get_beacon_light_client_optimistic_update = beacon_light_client_path
+        .clone()
+        .and(task_spawner_filter.clone())
+        .and(warp::path("optimistic_update"))
+        .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .then(
+            |chain: Arc<BeaconChain<T>>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    get_light_client_optimistic_update(chain, accept_header)
+                })
+            },
+        );
+
+    // GET beacon/light_client/updates?start_period,count
+    let get_beacon_light_client_updates = beacon_light_client_path
+        .clone()
+        .and(task_spawner_filter.clone())
+        .and(warp::path("updates"))
+        .and(warp::path::end())
+        .and(warp::query::<api_types::LightClientUpdatesQuery>())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .then(
+            |chain: Arc<BeaconChain<T>>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             query: api_types::LightClientUpdatesQuery,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    get_light_client_updates(chain, query, accept_header)
                 })
             },
         );
@@ -2364,6 +2458,14 @@ pub fn serve<T: BeaconChainTypes>(
                                     "missing state at slot {slot}"
                                 ))
                             }
+                            BeaconChainError::AttestationRewardsEpochIncomplete {
+                                epoch,
+                                current_slot,
+                            } => warp_utils::reject::custom_bad_request(format!(
+                                "rewards for epoch {epoch} are not available until the end of \
+                                 epoch {}, current slot is {current_slot}",
+                                epoch + 1
+                            )),
                             BeaconChainError::BeaconStateError(
                                 BeaconStateError::UnknownValidator(validator_index),
                             ) => warp_utils::reject::custom_bad_request(format!(
@@ -2655,7 +2757,7 @@ pub fn serve<T: BeaconChainTypes>(
              network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
                     let enr = network_globals.local_enr();
-                    let p2p_addresses = enr.multiaddr_p2p_tcp();
+                    let p2p_addresses = network_globals.p2p_multiaddrs();
                     let discovery_addresses = enr.multiaddr_p2p_udp();
                     let meta_data = network_globals.local_metadata.read();
                     Ok(api_types::GenericResponse::from(api_types::IdentityData {
@@ -2707,9 +2809,9 @@ pub fn serve<T: BeaconChainTypes>(
         .and(network_globals.clone())
         .and(chain_filter.clone())
         .then(
-            |task_spawner: TaskSpawner<T::EthSpec>,
-             network_globals: Arc<NetworkGlobals<T::EthSpec>>,
-             chain: Arc<BeaconChain<T>>| {
+            move |task_spawner: TaskSpawner<T::EthSpec>,
+                  network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+                  chain: Arc<BeaconChain<T>>| {
                 async move {
                     let el_offline = if let Some(el) = &chain.execution_layer {
                         el.is_offline_or_erroring().await
@@ -2734,12 +2836,19 @@ pub fn serve<T: BeaconChainTypes>(
                                 .is_optimistic_or_invalid_head()
                                 .map_err(warp_utils::reject::beacon_chain_error)?;
 
+                            let sync_state = network_globals.sync_state();
+                            let is_backfilling =
+                                matches!(sync_state, SyncState::BackFillSyncing { .. });
+
                             let syncing_data = api_types::SyncingData {
-                                is_syncing: network_globals.sync_state.read().is_syncing(),
+                                is_syncing: sync_state.is_syncing(),
                                 is_optimistic: Some(is_optimistic),
                                 el_offline: Some(el_offline),
                                 head_slot,
                                 sync_distance,
+                                is_backfilling: Some(is_backfilling),
+                                backfill_rate_limited: is_backfilling
+                                    .then_some(backfill_rate_limited),
                             };
 
                             Ok(api_types::GenericResponse::from(syncing_data))
@@ -2843,6 +2952,7 @@ pub fn serve<T: BeaconChainTypes>(
                                 state: api_types::PeerState::from_peer_connection_status(
                                     peer_info.connection_status(),
                                 ),
+                                client: api_types::PeerClient::from_client(peer_info.client()),
                             }));
                         }
                     }
@@ -2905,6 +3015,9 @@ pub fn serve<T: BeaconChainTypes>(
                                         last_seen_p2p_address: address,
                                         direction,
                                         state,
+                                        client: api_types::PeerClient::from_client(
+                                            peer_info.client(),
+                                        ),
                                     });
                                 }
                             }
@@ -3041,7 +3154,7 @@ pub fn serve<T: BeaconChainTypes>(
                             ProduceBlockVerification::VerifyRandao
                         };
 
-                    let (block, _) = chain
+                    let (block, _, timings) = chain
                         .produce_block_with_verification::<FullPayload<T::EthSpec>>(
                             randao_reveal,
                             slot,
@@ -3055,7 +3168,7 @@ pub fn serve<T: BeaconChainTypes>(
                         .fork_name(&chain.spec)
                         .map_err(inconsistent_fork_rejection)?;
 
-                    match accept_header {
+                    let response = match accept_header {
                         Some(api_types::Accept::Ssz) => Response::builder()
                             .status(200)
                             .header("Content-Type", "application/octet-stream")
@@ -3072,7 +3185,8 @@ pub fn serve<T: BeaconChainTypes>(
                         _ => fork_versioned_response(endpoint_version, fork_name, block)
                             .map(|response| warp::reply::json(&response).into_response())
                             .map(|res| add_consensus_version_header(res, fork_name)),
-                    }
+                    };
+                    response.map(|res| add_block_production_timings_headers(res, &timings))
                 })
             },
         );
@@ -3119,7 +3233,7 @@ pub fn serve<T: BeaconChainTypes>(
                             ProduceBlockVerification::VerifyRandao
                         };
 
-                    let (block, _) = chain
+                    let (block, _, timings) = chain
                         .produce_block_with_verification::<BlindedPayload<T::EthSpec>>(
                             randao_reveal,
                             slot,
@@ -3133,7 +3247,7 @@ pub fn serve<T: BeaconChainTypes>(
                         .fork_name(&chain.spec)
                         .map_err(inconsistent_fork_rejection)?;
 
-                    match accept_header {
+                    let response = match accept_header {
                         Some(api_types::Accept::Ssz) => Response::builder()
                             .status(200)
                             .header("Content-Type", "application/octet-stream")
@@ -3151,7 +3265,8 @@ pub fn serve<T: BeaconChainTypes>(
                         _ => fork_versioned_response(V2, fork_name, block)
                             .map(|response| warp::reply::json(&response).into_response())
                             .map(|res| add_consensus_version_header(res, fork_name)),
-                    }
+                    };
+                    response.map(|res| add_block_production_timings_headers(res, &timings))
                 })
             },
         );
@@ -3537,6 +3652,14 @@ pub fn serve<T: BeaconChainTypes>(
                         "count" => preparation_data.len(),
                     );
 
+                    {
+                        let mut validator_monitor = chain.validator_monitor.write();
+                        for preparation in &preparation_data {
+                            validator_monitor
+                                .auto_register_local_validator(preparation.validator_index);
+                        }
+                    }
+
                     execution_layer
                         .update_proposer_preparation(current_epoch, &preparation_data)
                         .await;
@@ -3569,7 +3692,7 @@ pub fn serve<T: BeaconChainTypes>(
             |task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
              log: Logger,
-             register_val_data: Vec<SignedValidatorRegistrationData>| async {
+             register_val_data: Vec<SignedValidatorRegistrationData>| async move {
                 let (tx, rx) = oneshot::channel();
 
                 let initial_result = task_spawner
@@ -3639,14 +3762,22 @@ pub fn serve<T: BeaconChainTypes>(
                             })
                             .unzip();
 
+                        {
+                            let mut validator_monitor = chain.validator_monitor.write();
+                            for preparation in &preparation_data {
+                                validator_monitor
+                                    .auto_register_local_validator(preparation.validator_index);
+                            }
+                        }
+
                         // Update the prepare beacon proposer cache based on this request.
                         execution_layer
                             .update_proposer_preparation(current_epoch, &preparation_data)
                             .await;
 
-                        // Call prepare beacon proposer blocking with the latest update in order to make
-                        // sure we have a local payload to fall back to in the event of the blinded block
-                        // flow failing.
+                        // Call prepare beacon proposer blocking with the latest update in order to
+                        // make sure we have a local payload to fall back to in the event of the
+                        // blinded block flow failing.
                         chain
                             .prepare_beacon_proposer(current_slot)
                             .await
@@ -3657,81 +3788,72 @@ pub fn serve<T: BeaconChainTypes>(
                                 ))
                             })?;
 
-                        info!(
-                            log,
-                            "Forwarding register validator request to connected builder";
-                            "count" => filtered_registration_data.len(),
-                        );
-
-                        // It's a waste of a `BeaconProcessor` worker to just
-                        // wait on a response from the builder (especially since
-                        // they have frequent timeouts). Spawn a new task and
-                        // send the response back to our original HTTP request
-                        // task via a channel.
-                        let builder_future = async move {
-                            let arc_builder = chain
-                                .execution_layer
-                                .as_ref()
-                                .ok_or(BeaconChainError::ExecutionLayerMissing)
-                                .map_err(warp_utils::reject::beacon_chain_error)?
-                                .builder();
-                            let builder = arc_builder
-                                .as_ref()
-                                .ok_or(BeaconChainError::BuilderMissing)
-                                .map_err(warp_utils::reject::beacon_chain_error)?;
+                        if execution_layer.builder().is_none() {
+                            return Err(warp_utils::reject::beacon_chain_error(
+                                BeaconChainError::BuilderMissing,
+                            ));
+                        }
 
-                            builder
-                                .post_builder_validators(&filtered_registration_data)
-                                .await
-                                .map(|resp| warp::reply::json(&resp).into_response())
-                                .map_err(|e| {
-                                    warn!(
-                                        log,
-                                        "Relay error when registering validator(s)";
-                                        "num_registrations" => filtered_registration_data.len(),
-                                        "error" => ?e
-                                    );
-                                    // Forward the HTTP status code if we are able to, otherwise fall back
-                                    // to a server error.
-                                    if let eth2::Error::ServerMessage(message) = e {
-                                        if message.code == StatusCode::BAD_REQUEST.as_u16() {
-                                            return warp_utils::reject::custom_bad_request(
-                                                message.message,
-                                            );
-                                        } else {
-                                            // According to the spec this response should only be a 400 or 500,
-                                            // so we fall back to a 500 here.
-                                            return warp_utils::reject::custom_server_error(
-                                                message.message,
-                                            );
-                                        }
-                                    }
-                                    warp_utils::reject::custom_server_error(format!("{e:?}"))
-                                })
-                        };
-                        tokio::task::spawn(async move { tx.send(builder_future.await) });
+                        // It's a waste of a `BeaconProcessor` worker to wait on the builder, which
+                        // may be slow to respond. Forward the registrations from a separate task
+                        // and send the outcome back to the HTTP request's task. The batches are
+                        // spread over half a slot to keep the response well within the validator
+                        // client's timeout.
+                        let spread = chain.slot_clock.slot_duration() / 2;
+                        let execution_layer = execution_layer.clone();
+                        chain.task_executor.spawn(
+                            async move {
+                                let result = execution_layer
+                                    .register_validators_with_builder(
+                                        filtered_registration_data,
+                                        spread,
+                                    )
+                                    .await;
+                                let _ = tx.send(result);
+                            },
+                            "register_validators_with_builder",
+                        );
 
-                        // Just send a generic 200 OK from this closure. We'll
-                        // ignore the `Ok` variant and form a proper response
-                        // from what is sent back down the channel.
-                        Ok(warp::reply::reply().into_response())
+                        Ok::<_, warp::reject::Rejection>(warp::reply::reply().into_response())
                     })
                     .await;
 
                 if initial_result.is_err() {
-                    return task_spawner::convert_rejection(initial_result).await;
+                    return convert_rejection(initial_result).await;
                 }
 
-                // Await a response from the builder without blocking a
-                // `BeaconProcessor` worker.
-                task_spawner::convert_rejection(rx.await.unwrap_or_else(|_| {
-                    Ok(warp::reply::with_status(
-                        warp::reply::json(&"No response from channel"),
-                        eth2::StatusCode::INTERNAL_SERVER_ERROR,
-                    )
-                    .into_response())
-                }))
-                .await
+                // Await the builder without occupying a `BeaconProcessor` worker, so that failures
+                // are still reported to the caller.
+                let result = match rx.await {
+                    Ok(Ok(summary)) if summary.failed > 0 => {
+                        let message = format!(
+                            "{} of {} validator registrations were not accepted by the builder: \
+                             {:?}",
+                            summary.failed, summary.new, summary.last_error
+                        );
+                        // Forward client errors from the builder, such as invalid signatures, as
+                        // they are likely to be the fault of the caller.
+                        if summary
+                            .last_error
+                            .as_ref()
+                            .and_then(|e| e.status())
+                            .map_or(false, |status| status.is_client_error())
+                        {
+                            Err(warp_utils::reject::custom_bad_request(message))
+                        } else {
+                            Err(warp_utils::reject::custom_server_error(message))
+                        }
+                    }
+                    Ok(Ok(_)) => Ok(warp::reply::reply().into_response()),
+                    Ok(Err(e)) => Err(warp_utils::reject::custom_server_error(format!(
+                        "error registering validators with the builder: {:?}",
+                        e
+                    ))),
+                    Err(_) => Err(warp_utils::reject::custom_server_error(
+                        "no response from the builder registration task".to_string(),
+                    )),
+                };
+                convert_rejection(result).await
             },
         );
     // POST validator/sync_committee_subscriptions
@@ -3779,7 +3901,7 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
-    // POST vaidator/liveness/{epoch}
+    // POST validator/liveness/{epoch}
     let post_validator_liveness_epoch = eth_v1
         .and(warp::path("validator"))
         .and(warp::path("liveness"))
@@ -3794,16 +3916,16 @@ pub fn serve<T: BeaconChainTypes>(
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>| {
                 task_spawner.blocking_json_task(Priority::P0, move || {
-                    // Ensure the request is for either the current, previous or next epoch.
+                    // Ensure the request is for either the current or previous epoch, since
+                    // validators cannot have been observed in a future epoch.
                     let current_epoch = chain
                         .epoch()
                         .map_err(warp_utils::reject::beacon_chain_error)?;
                     let prev_epoch = current_epoch.saturating_sub(Epoch::new(1));
-                    let next_epoch = current_epoch.saturating_add(Epoch::new(1));
 
-                    if epoch < prev_epoch || epoch > next_epoch {
+                    if epoch < prev_epoch || epoch > current_epoch {
                         return Err(warp_utils::reject::custom_bad_request(format!(
-                            "request epoch {} is more than one epoch from the current epoch {}",
+                            "request epoch {} is not the current epoch {} or the previous epoch",
                             epoch, current_epoch
                         )));
                     }
@@ -3943,6 +4065,32 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // POST lighthouse/validator_monitor/individual_tracking_threshold
+    let post_lighthouse_validator_monitor_individual_tracking_threshold = warp::path("lighthouse")
+        .and(warp::path("validator_monitor"))
+        .and(warp::path("individual_tracking_threshold"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |request: eth2::lighthouse::IndividualTrackingThreshold,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let mut validator_monitor = chain.validator_monitor.write();
+                    validator_monitor.set_individual_tracking_threshold(request.threshold as usize);
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::IndividualTrackingStatus {
+                            threshold: request.threshold,
+                            individual_tracking: validator_monitor.individual_tracking(),
+                            monitored_validators: validator_monitor.num_validators(),
+                        },
+                    ))
+                })
+            },
+        );
+
     // POST lighthouse/ui/validator_info
     let post_lighthouse_ui_validator_info = warp::path("lighthouse")
         .and(warp::path("ui"))
@@ -3969,12 +4117,39 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(network_globals.clone())
         .then(
-            |task_spawner: TaskSpawner<T::EthSpec>,
-             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+            move |task_spawner: TaskSpawner<T::EthSpec>,
+                  network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
                 task_spawner.blocking_json_task(Priority::P0, move || {
-                    Ok(api_types::GenericResponse::from(
-                        network_globals.sync_state(),
-                    ))
+                    let sync_state = network_globals.sync_state();
+                    let is_backfilling = matches!(sync_state, SyncState::BackFillSyncing { .. });
+                    Ok(eth2::lighthouse::SyncingResponse {
+                        data: sync_state,
+                        is_backfilling,
+                        backfill_rate_limited: is_backfilling.then_some(backfill_rate_limited),
+                    })
+                })
+            },
+        );
+
+    // GET lighthouse/slasher/slashings
+    let get_lighthouse_slasher_slashings = warp::path("lighthouse")
+        .and(warp::path("slasher"))
+        .and(warp::path("slashings"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    if chain.slasher.is_some() {
+                        Ok(api_types::GenericResponse::from(
+                            chain.pending_slasher_slashings.lock().clone(),
+                        ))
+                    } else {
+                        Err(warp_utils::reject::custom_not_found(
+                            "slasher is not enabled, see the --slasher CLI flag".to_string(),
+                        ))
+                    }
                 })
             },
         );
@@ -3984,17 +4159,22 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("nat"))
         .and(task_spawner_filter.clone())
         .and(warp::path::end())
-        .then(|task_spawner: TaskSpawner<T::EthSpec>| {
-            task_spawner.blocking_json_task(Priority::P1, move || {
-                Ok(api_types::GenericResponse::from(
-                    lighthouse_network::metrics::NAT_OPEN
-                        .as_ref()
-                        .map(|v| v.get())
-                        .unwrap_or(0)
-                        != 0,
-                ))
-            })
-        });
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(NatState {
+                        open: lighthouse_network::metrics::NAT_OPEN
+                            .as_ref()
+                            .map(|v| v.get())
+                            .unwrap_or(0)
+                            != 0,
+                        mappings: network_globals.nat_mappings.read().clone(),
+                    }))
+                })
+            },
+        );
 
     // GET lighthouse/peers
     let get_lighthouse_peers = warp::path("lighthouse")
@@ -4006,7 +4186,7 @@ pub fn serve<T: BeaconChainTypes>(
             |task_spawner: TaskSpawner<T::EthSpec>,
              network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
-                    Ok(network_globals
+                    let peers = network_globals
                         .peers
                         .read()
                         .peers()
@@ -4014,7 +4194,19 @@ pub fn serve<T: BeaconChainTypes>(
                             peer_id: peer_id.to_string(),
                             peer_info: peer_info.clone(),
                         })
-                        .collect::<Vec<_>>())
+                        .collect::<Vec<_>>();
+                    let mut subnets = network_globals
+                        .subscribed_subnet_peer_counts()
+                        .into_iter()
+                        .map(|(subnet, peers)| eth2::lighthouse::SubnetPeers {
+                            kind: subnet.kind().to_string(),
+                            subnet_id: subnet.id(),
+                            peers,
+                            target_peers: TARGET_SUBNET_PEERS,
+                        })
+                        .collect::<Vec<_>>();
+                    subnets.sort_by(|a, b| (&a.kind, a.subnet_id).cmp(&(&b.kind, b.subnet_id)));
+                    Ok(eth2::lighthouse::PeersResponse { peers, subnets })
                 })
             },
         );
@@ -4180,6 +4372,53 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/execution/capabilities
+    let get_lighthouse_execution_capabilities = warp::path("lighthouse")
+        .and(warp::path("execution"))
+        .and(warp::path("capabilities"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.spawn_async_with_rejection(Priority::P1, async move {
+                    let execution_layer = chain
+                        .execution_layer
+                        .as_ref()
+                        .ok_or(BeaconChainError::ExecutionLayerMissing)
+                        .map_err(warp_utils::reject::beacon_chain_error)?;
+
+                    let capabilities = execution_layer
+                        .get_engine_capabilities(None)
+                        .await
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "unable to fetch engine capabilities: {:?}",
+                                e
+                            ))
+                        })?;
+
+                    let response = eth2::lighthouse::ExecutionEngineCapabilities {
+                        supported: capabilities
+                            .to_response()
+                            .into_iter()
+                            .map(String::from)
+                            .collect(),
+                        missing: capabilities
+                            .missing()
+                            .into_iter()
+                            .map(String::from)
+                            .collect(),
+                    };
+
+                    Ok::<_, warp::reject::Rejection>(
+                        warp::reply::json(&api_types::GenericResponse::from(response))
+                            .into_response(),
+                    )
+                })
+            },
+        );
+
     // GET lighthouse/beacon/states/{state_id}/ssz
     let get_lighthouse_beacon_states_ssz = warp::path("lighthouse")
         .and(warp::path("beacon"))
@@ -4329,10 +4568,34 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .then(
-            |target, query, task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
-                    attestation_performance::get_attestation_performance(target, query, chain)
-                })
+            move |target: String,
+                  query,
+                  task_spawner: TaskSpawner<T::EthSpec>,
+                  chain: Arc<BeaconChain<T>>| async move {
+                // The entire validator set is streamed one epoch at a time to bound memory usage.
+                if target.eq_ignore_ascii_case("all") {
+                    convert_rejection(
+                        attestation_performance::stream_attestation_performance(
+                            query,
+                            attestation_performance_max_epochs,
+                            task_spawner,
+                            chain,
+                        )
+                        .await,
+                    )
+                    .await
+                } else {
+                    task_spawner
+                        .blocking_json_task(Priority::P1, move || {
+                            attestation_performance::get_attestation_performance(
+                                target,
+                                query,
+                                attestation_performance_max_epochs,
+                                chain,
+                            )
+                        })
+                        .await
+                }
             },
         );
 
@@ -4415,6 +4678,9 @@ pub fn serve<T: BeaconChainTypes>(
                                 api_types::EventTopic::BlockReward => {
                                     event_handler.subscribe_block_reward()
                                 }
+                                api_types::EventTopic::PublishedBlockReward => {
+                                    event_handler.subscribe_published_block_reward()
+                                }
                             };
 
                             receivers.push(
@@ -4526,6 +4792,10 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_beacon_pool_voluntary_exits)
                 .uor(get_beacon_pool_bls_to_execution_changes)
                 .uor(get_beacon_deposit_snapshot)
+                .uor(get_beacon_light_client_bootstrap)
+                .uor(get_beacon_light_client_finality_update)
+                .uor(get_beacon_light_client_optimistic_update)
+                .uor(get_beacon_light_client_updates)
                 .uor(get_beacon_rewards_blocks)
                 .uor(get_config_fork_schedule)
                 .uor(get_config_spec)
@@ -4551,6 +4821,7 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_ui_validator_count)
                 .uor(get_lighthouse_syncing)
                 .uor(get_lighthouse_nat)
+                .uor(get_lighthouse_slasher_slashings)
                 .uor(get_lighthouse_peers)
                 .uor(get_lighthouse_peers_connected)
                 .uor(get_lighthouse_proto_array)
@@ -4559,6 +4830,7 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_eth1_syncing)
                 .uor(get_lighthouse_eth1_block_cache)
                 .uor(get_lighthouse_eth1_deposit_cache)
+                .uor(get_lighthouse_execution_capabilities)
                 .uor(get_lighthouse_beacon_states_ssz)
                 .uor(get_lighthouse_staking)
                 .uor(get_lighthouse_database_info)
@@ -4609,6 +4881,7 @@ pub fn serve<T: BeaconChainTypes>(
                     .uor(post_lighthouse_block_rewards)
                     .uor(post_lighthouse_ui_validator_metrics)
                     .uor(post_lighthouse_ui_validator_info)
+                    .uor(post_lighthouse_validator_monitor_individual_tracking_threshold)
                     .recover(warp_utils::reject::handle_rejection),
             ),
         )
@@ -4653,6 +4926,27 @@ pub fn serve<T: BeaconChainTypes>(
     Ok(http_server)
 }
 
+/// Attach the total and per-stage block production times to `response`, in milliseconds.
+fn add_block_production_timings_headers(
+    mut response: warp::reply::Response,
+    timings: &BlockProductionTimings,
+) -> warp::reply::Response {
+    let headers = response.headers_mut();
+    let stage_headers = timings
+        .stages()
+        .into_iter()
+        .map(|(stage, duration)| (block_production_stage_header(stage), duration));
+    for (name, duration) in
+        std::iter::once((BLOCK_PRODUCTION_TIME_HEADER.to_string(), timings.total))
+            .chain(stage_headers)
+    {
+        // The header names are built from fixed stage names, so they are always valid.
+        let name = HeaderName::try_from(name).unwrap();
+        headers.insert(name, HeaderValue::from(duration.as_millis() as u64));
+    }
+    response
+}
+
 /// Publish a message to the libp2p pubsub network.
 fn publish_pubsub_message<T: EthSpec>(
     network_tx: &UnboundedSender<NetworkMessage<T>>,
@@ -0,0 +1,168 @@
+use crate::version::add_consensus_version_header;
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::types::{self as api_types};
+use serde::Serialize;
+use ssz::Encode;
+use std::sync::Arc;
+use types::{ChainSpec, ForkName, ForkVersionedResponse, Hash256};
+use warp::{http::Response, Reply};
+
+/// The maximum number of `LightClientUpdate`s that may be requested at once.
+///
+/// This mirrors `MAX_REQUEST_LIGHT_CLIENT_UPDATES` from the light client networking spec.
+const MAX_REQUEST_LIGHT_CLIENT_UPDATES: u64 = 128;
+
+/// Returns the `LightClientBootstrap` for the block with `block_root`, as either JSON or SSZ
+/// depending on the `accept_header`.
+pub fn get_light_client_bootstrap<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    block_root: &Hash256,
+    accept_header: Option<api_types::Accept>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    let (bootstrap, fork_name) = chain
+        .get_light_client_bootstrap(block_root)
+        .map_err(|e| match e {
+            BeaconChainError::LightClientBootstrapPreAltair(_) => {
+                warp_utils::reject::custom_bad_request(format!(
+                    "block {:?} is prior to the Altair fork",
+                    block_root
+                ))
+            }
+            e => warp_utils::reject::beacon_chain_error(e),
+        })?
+        .ok_or_else(|| {
+            warp_utils::reject::custom_not_found(format!(
+                "no light client bootstrap available for block {:?}",
+                block_root
+            ))
+        })?;
+
+    light_client_response(bootstrap, fork_name, accept_header)
+}
+
+/// Returns the latest `LightClientFinalityUpdate`, as either JSON or SSZ depending on the
+/// `accept_header`.
+pub fn get_light_client_finality_update<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    accept_header: Option<api_types::Accept>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    let update = chain
+        .get_latest_light_client_finality_update()
+        .ok_or_else(|| {
+            warp_utils::reject::custom_not_found(
+                "no light client finality update is available".to_string(),
+            )
+        })?;
+    let fork_name = chain
+        .spec
+        .fork_name_at_slot::<T::EthSpec>(update.signature_slot);
+
+    light_client_response(update, fork_name, accept_header)
+}
+
+/// Returns the latest `LightClientOptimisticUpdate`, as either JSON or SSZ depending on the
+/// `accept_header`.
+pub fn get_light_client_optimistic_update<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    accept_header: Option<api_types::Accept>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    let update = chain
+        .get_latest_light_client_optimistic_update()
+        .ok_or_else(|| {
+            warp_utils::reject::custom_not_found(
+                "no light client optimistic update is available".to_string(),
+            )
+        })?;
+    let fork_name = chain
+        .spec
+        .fork_name_at_slot::<T::EthSpec>(update.signature_slot);
+
+    light_client_response(update, fork_name, accept_header)
+}
+
+/// Returns the best `LightClientUpdate` for each sync committee period in the range described by
+/// `query`, as either JSON or SSZ depending on the `accept_header`.
+///
+/// The SSZ response follows the `LightClientUpdatesByRange` response format: each update is
+/// prefixed by its 8-byte little-endian length and the 4-byte fork digest of its attested slot.
+pub fn get_light_client_updates<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    query: api_types::LightClientUpdatesQuery,
+    accept_header: Option<api_types::Accept>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    if query.count > MAX_REQUEST_LIGHT_CLIENT_UPDATES {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "count {} exceeds the maximum of {}",
+            query.count, MAX_REQUEST_LIGHT_CLIENT_UPDATES
+        )));
+    }
+    let updates = chain
+        .get_light_client_updates(query.start_period, query.count)
+        .map_err(warp_utils::reject::beacon_chain_error)?;
+    let fork_name_of = |update: &types::LightClientUpdate<T::EthSpec>| {
+        chain
+            .spec
+            .fork_name_at_slot::<T::EthSpec>(update.attested_header.slot)
+    };
+
+    match accept_header {
+        Some(api_types::Accept::Ssz) => {
+            let mut bytes = vec![];
+            for update in &updates {
+                let fork_digest = ChainSpec::compute_fork_digest(
+                    chain.spec.fork_version_for_name(fork_name_of(update)),
+                    chain.genesis_validators_root,
+                );
+                let payload = update.as_ssz_bytes();
+                let length = (fork_digest.len() + payload.len()) as u64;
+                bytes.extend_from_slice(&length.to_le_bytes());
+                bytes.extend_from_slice(&fork_digest);
+                bytes.extend_from_slice(&payload);
+            }
+            Response::builder()
+                .status(200)
+                .header("Content-Type", "application/octet-stream")
+                .body(bytes.into())
+                .map_err(|e| {
+                    warp_utils::reject::custom_server_error(format!(
+                        "failed to create response: {}",
+                        e
+                    ))
+                })
+        }
+        _ => {
+            let updates = updates
+                .into_iter()
+                .map(|update| ForkVersionedResponse {
+                    version: Some(fork_name_of(&update)),
+                    data: update,
+                })
+                .collect::<Vec<_>>();
+            Ok(warp::reply::json(&updates).into_response())
+        }
+    }
+}
+
+/// Encode `data` as SSZ or as a fork-versioned JSON response, adding the consensus version header
+/// in both cases.
+fn light_client_response<U: Encode + Serialize>(
+    data: U,
+    fork_name: ForkName,
+    accept_header: Option<api_types::Accept>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    match accept_header {
+        Some(api_types::Accept::Ssz) => Response::builder()
+            .status(200)
+            .header("Content-Type", "application/octet-stream")
+            .body(data.as_ssz_bytes().into())
+            .map_err(|e| {
+                warp_utils::reject::custom_server_error(format!("failed to create response: {}", e))
+            }),
+        _ => Ok(warp::reply::json(&ForkVersionedResponse {
+            version: Some(fork_name),
+            data,
+        })
+        .into_response()),
+    }
+    .map(|resp| add_consensus_version_header(resp, fork_name))
+}
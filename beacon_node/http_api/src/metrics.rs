@@ -34,6 +34,11 @@ lazy_static::lazy_static! {
         "Time between start of the slot and when the block was broadcast",
         &["provenance"]
     );
+    pub static ref HTTP_API_BLOCK_PUBLISH_STAGE_TIMES: Result<HistogramVec> = try_create_histogram_vec(
+        "http_api_block_publish_stage_seconds",
+        "Time taken by each stage of publishing a block received via the HTTP API",
+        &["stage"]
+    );
     pub static ref HTTP_API_BLOCK_PUBLISHED_LATE_TOTAL: Result<IntCounter> = try_create_int_counter(
         "http_api_block_published_late_total",
         "The count of times a block was published beyond more than half way to the attestation deadline"
@@ -42,4 +47,8 @@ lazy_static::lazy_static! {
         "http_api_block_published_very_late_total",
         "The count of times a block was published beyond the attestation deadline"
     );
+    pub static ref HTTP_API_BLOCK_DUPLICATE_PUBLISHES_TOTAL: Result<IntCounter> = try_create_int_counter(
+        "http_api_block_duplicate_publishes_total",
+        "The count of times a block was published that had already been imported"
+    );
 }
@@ -55,16 +55,19 @@ pub fn proposer_duties<T: BeaconChainTypes>(
             .safe_add(1)
             .map_err(warp_utils::reject::arith_error)?
     {
-        let (proposers, dependent_root, execution_status, _fork) =
-            compute_proposer_duties_from_head(request_epoch, chain)
-                .map_err(warp_utils::reject::beacon_chain_error)?;
-        convert_to_api_response(
-            chain,
-            request_epoch,
-            dependent_root,
-            execution_status.is_optimistic_or_invalid(),
-            proposers,
-        )
+        // Computing the duties for the next epoch requires advancing a copy of the head state,
+        // so cache them against their decision root. The state advance timer also primes this
+        // cache towards the end of each epoch.
+        if let Some(duties) = try_proposer_duties_from_cache(request_epoch, chain)? {
+            Ok(duties)
+        } else {
+            debug!(
+                log,
+                "Proposer cache miss for next epoch";
+                "request_epoch" =>  request_epoch,
+            );
+            compute_and_cache_proposer_duties(request_epoch, chain)
+        }
     } else if request_epoch
         > current_epoch
             .safe_add(1)
@@ -88,7 +91,7 @@ pub fn proposer_duties<T: BeaconChainTypes>(
 ///
 /// ## Notes
 ///
-/// The `current_epoch` value should equal the current epoch on the slot clock (with some
+/// The `request_epoch` value should equal the current or next epoch on the slot clock (with some
 /// tolerance), otherwise we risk washing out the proposer cache at the expense of block processing.
 fn try_proposer_duties_from_cache<T: BeaconChainTypes>(
     request_epoch: Epoch,
@@ -146,27 +149,32 @@ fn try_proposer_duties_from_cache<T: BeaconChainTypes>(
 ///
 /// ## Notes
 ///
-/// The `current_epoch` value should equal the current epoch on the slot clock, otherwise we risk
-/// washing out the proposer cache at the expense of block processing.
+/// The `request_epoch` value should equal the current or next epoch on the slot clock, otherwise we
+/// risk washing out the proposer cache at the expense of block processing.
+///
+/// The duties are keyed by their decision root, so a re-org which changes the decision root will
+/// result in a cache miss rather than stale duties. Since the duties are computed from the head,
+/// duties for the same epoch under any other decision root are stale and are dropped.
 fn compute_and_cache_proposer_duties<T: BeaconChainTypes>(
-    current_epoch: Epoch,
+    request_epoch: Epoch,
     chain: &BeaconChain<T>,
 ) -> Result<ApiDuties, warp::reject::Rejection> {
     let (indices, dependent_root, execution_status, fork) =
-        compute_proposer_duties_from_head(current_epoch, chain)
+        compute_proposer_duties_from_head(request_epoch, chain)
             .map_err(warp_utils::reject::beacon_chain_error)?;
 
     // Prime the proposer shuffling cache with the newly-learned value.
-    chain
-        .beacon_proposer_cache
-        .lock()
-        .insert(current_epoch, dependent_root, indices.clone(), fork)
+    let mut beacon_proposer_cache = chain.beacon_proposer_cache.lock();
+    beacon_proposer_cache.retain_decision_block(request_epoch, dependent_root);
+    beacon_proposer_cache
+        .insert(request_epoch, dependent_root, indices.clone(), fork)
         .map_err(BeaconChainError::from)
         .map_err(warp_utils::reject::beacon_chain_error)?;
+    drop(beacon_proposer_cache);
 
     convert_to_api_response(
         chain,
-        current_epoch,
+        request_epoch,
         dependent_root,
         execution_status.is_optimistic_or_invalid(),
         indices,
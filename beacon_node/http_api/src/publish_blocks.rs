@@ -5,19 +5,22 @@ use beacon_chain::{
     NotifyExecutionLayer,
 };
 use eth2::types::{BroadcastValidation, ErrorMessage};
+use eth2::CONSENSUS_VERSION_HEADER;
 use execution_layer::ProvenancedPayload;
 use lighthouse_network::PubsubMessage;
 use network::NetworkMessage;
-use slog::{debug, error, info, warn, Logger};
+use parking_lot::Mutex;
+use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::marker::PhantomData;
+use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::UnboundedSender;
 use tree_hash::TreeHash;
 use types::{
-    AbstractExecPayload, BeaconBlockRef, BlindedPayload, EthSpec, ExecPayload, ExecutionBlockHash,
-    FullPayload, Hash256, SignedBeaconBlock,
+    AbstractExecPayload, BeaconBlockRef, BlindedPayload, ChainSpec, EthSpec, ExecPayload,
+    ExecutionBlockHash, ForkName, FullPayload, Hash256, SignedBeaconBlock, Slot,
 };
 use warp::http::StatusCode;
 use warp::{reply::Response, Rejection, Reply};
@@ -64,6 +67,8 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
                               sender,
                               log,
                               seen_timestamp| {
+        let _timer =
+            metrics::start_timer_vec(&metrics::HTTP_API_BLOCK_PUBLISH_STAGE_TIMES, &["broadcast"]);
         let publish_timestamp = timestamp_now();
         let publish_delay = publish_timestamp
             .checked_sub(seen_timestamp)
@@ -76,20 +81,56 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
             .map_err(|_| BeaconChainError::UnableToPublish.into())
     };
 
+    /* refuse to publish a block which conflicts with a block we've already seen from the same
+     * proposer, unless the caller has opted into gossip-only validation */
+    if !matches!(validation_level, BroadcastValidation::Gossip) {
+        let root = block_root.unwrap_or_else(|| beacon_block.canonical_root());
+        match chain.find_equivocating_proposal(beacon_block.message(), root) {
+            Ok(Some(previous_root)) => {
+                crit!(
+                    log,
+                    "Not publishing equivocating block";
+                    "slot" => beacon_block.slot(),
+                    "proposer_index" => beacon_block.message().proposer_index(),
+                    "block_root" => ?root,
+                    "previous_block_root" => ?previous_root,
+                );
+                return Err(warp_utils::reject::custom_bad_request(format!(
+                    "block equivocates with previously seen block {:?}",
+                    previous_root
+                )));
+            }
+            Ok(None) => (),
+            // Errors (e.g. for blocks prior to finalization) are reported by gossip verification.
+            Err(e) => debug!(
+                log,
+                "Unable to check block for equivocation";
+                "slot" => beacon_block.slot(),
+                "error" => ?e
+            ),
+        }
+    }
+
     /* if we can form a `GossipVerifiedBlock`, we've passed our basic gossip checks */
-    let gossip_verified_block = match block.into_gossip_verified_block(&chain) {
-        Ok(b) => b,
+    let gossip_timer = metrics::start_timer_vec(
+        &metrics::HTTP_API_BLOCK_PUBLISH_STAGE_TIMES,
+        &["gossip_verification"],
+    );
+    let gossip_verification_result = block.into_gossip_verified_block(&chain);
+    drop(gossip_timer);
+    let gossip_verified_block = match gossip_verification_result {
+        // Have the proposer reward computed from the pre-state during import, so that it can be
+        // logged and emitted as an event without loading the parent state again.
+        Ok(b) if chain.config.paranoid_block_import => {
+            b.report_proposer_reward().reverify_proposal_signature()
+        }
+        Ok(b) => b.report_proposer_reward(),
         Err(BlockError::BlockIsAlreadyKnown) => {
-            // Allow the status code for duplicate blocks to be overridden based on config.
-            return Ok(warp::reply::with_status(
-                warp::reply::json(&ErrorMessage {
-                    code: duplicate_status_code.as_u16(),
-                    message: "duplicate block".to_string(),
-                    stacktraces: vec![],
-                }),
+            return Ok(duplicate_block_response(
+                beacon_block.slot(),
                 duplicate_status_code,
-            )
-            .into_response());
+                &log,
+            ));
         }
         Err(e) => {
             warn!(
@@ -120,7 +161,7 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
     let log_clone = log.clone();
     let sender_clone = network_tx.clone();
 
-    let publish_fn = move || match validation_level {
+    let publish_block_for_validation_level = move || match validation_level {
         BroadcastValidation::Gossip => Ok(()),
         BroadcastValidation::Consensus => {
             publish_block(block_clone, sender_clone, log_clone, seen_timestamp)
@@ -145,15 +186,41 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
         }
     };
 
-    match chain
+    // `process_block` calls `publish_fn` between consensus verification and import, so record
+    // when it ran in order to time those stages separately from the broadcast.
+    let publish_fn_times = Arc::new(Mutex::new(None));
+    let publish_fn_times_clone = publish_fn_times.clone();
+
+    let publish_fn = move || {
+        let publish_fn_start = Instant::now();
+        let result = publish_block_for_validation_level();
+        *publish_fn_times_clone.lock() = Some((publish_fn_start, Instant::now()));
+        result
+    };
+
+    let process_start = Instant::now();
+    let import_result = chain
         .process_block(
             block_root,
             gossip_verified_block,
             NotifyExecutionLayer::Yes,
             publish_fn,
         )
-        .await
-    {
+        .await;
+    if let Some((publish_fn_start, publish_fn_end)) = *publish_fn_times.lock() {
+        metrics::observe_timer_vec(
+            &metrics::HTTP_API_BLOCK_PUBLISH_STAGE_TIMES,
+            &["consensus_verification"],
+            publish_fn_start.saturating_duration_since(process_start),
+        );
+        metrics::observe_timer_vec(
+            &metrics::HTTP_API_BLOCK_PUBLISH_STAGE_TIMES,
+            &["import"],
+            publish_fn_end.elapsed(),
+        );
+    }
+
+    match import_result {
         Ok(root) => {
             info!(
                 log,
@@ -174,7 +241,12 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
 
             // Update the head since it's likely this block will become the new
             // head.
+            let recompute_head_timer = metrics::start_timer_vec(
+                &metrics::HTTP_API_BLOCK_PUBLISH_STAGE_TIMES,
+                &["recompute_head"],
+            );
             chain.recompute_head_at_current_slot().await;
+            drop(recompute_head_timer);
 
             // Only perform late-block logging here if the block is local. For
             // blocks built with builders we consider the broadcast time to be
@@ -189,6 +261,9 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
                     &log,
                 )
             }
+
+            check_fee_recipient(&chain, &beacon_block, is_locally_built_block).await;
+
             Ok(warp::reply().into_response())
         }
         Err(BlockError::BeaconChainError(BeaconChainError::UnableToPublish)) => {
@@ -196,6 +271,13 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
                 "unable to publish to network channel".to_string(),
             ))
         }
+        // The block may have been imported via gossip whilst we were verifying it. It is already
+        // known to the network so there is nothing more for us to do.
+        Err(BlockError::BlockIsAlreadyKnown) => Ok(duplicate_block_response(
+            beacon_block.slot(),
+            duplicate_status_code,
+            &log,
+        )),
         Err(BlockError::Slashable) => Err(warp_utils::reject::custom_bad_request(
             "proposal for this slot and proposer has already been seen".to_string(),
         )),
@@ -217,6 +299,33 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
     }
 }
 
+/// Build the response for a block which has already been imported, e.g. because it was published
+/// to multiple beacon nodes by the same validator client.
+fn duplicate_block_response(
+    slot: Slot,
+    duplicate_status_code: StatusCode,
+    log: &Logger,
+) -> Response {
+    metrics::inc_counter(&metrics::HTTP_API_BLOCK_DUPLICATE_PUBLISHES_TOTAL);
+    debug!(
+        log,
+        "Block from HTTP API already known";
+        "slot" => slot,
+        "status_code" => duplicate_status_code.as_u16(),
+    );
+
+    // Allow the status code for duplicate blocks to be overridden based on config.
+    warp::reply::with_status(
+        warp::reply::json(&ErrorMessage {
+            code: duplicate_status_code.as_u16(),
+            message: "duplicate block".to_string(),
+            stacktraces: vec![],
+        }),
+        duplicate_status_code,
+    )
+    .into_response()
+}
+
 /// Handles a request from the HTTP API for blinded blocks. This converts blinded blocks into full
 /// blocks before publishing.
 pub async fn publish_blinded_block<T: BeaconChainTypes>(
@@ -243,8 +352,8 @@ pub async fn publish_blinded_block<T: BeaconChainTypes>(
 }
 
 /// Deconstruct the given blinded block, and construct a full block. This attempts to use the
-/// execution layer's payload cache, and if that misses, attempts a blind block proposal to retrieve
-/// the full payload.
+/// execution layer's payload cache, then the payload bodies known to the execution node, and if
+/// both miss, attempts a blind block proposal to retrieve the full payload.
 pub async fn reconstruct_block<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
     block_root: Hash256,
@@ -256,26 +365,60 @@ pub async fn reconstruct_block<T: BeaconChainTypes>(
             warp_utils::reject::custom_server_error("Missing execution layer".to_string())
         })?;
 
+        let fork_name = chain
+            .spec
+            .fork_name_at_epoch(block.slot().epoch(T::EthSpec::slots_per_epoch()));
+
         // If the execution block hash is zero, use an empty payload.
         let full_payload = if payload_header.block_hash() == ExecutionBlockHash::zero() {
-            let payload = FullPayload::default_at_fork(
-                chain
-                    .spec
-                    .fork_name_at_epoch(block.slot().epoch(T::EthSpec::slots_per_epoch())),
-            )
-            .map_err(|e| {
-                warp_utils::reject::custom_server_error(format!(
-                    "Default payload construction error: {e:?}"
-                ))
-            })?
-            .into();
+            let payload = FullPayload::default_at_fork(fork_name)
+                .map_err(|e| {
+                    warp_utils::reject::custom_server_error(format!(
+                        "Default payload construction error: {e:?}"
+                    ))
+                })?
+                .into();
             ProvenancedPayload::Local(payload)
-        // If we already have an execution payload with this transactions root cached, use it.
-        } else if let Some(cached_payload) =
-            el.get_payload_by_root(&payload_header.tree_hash_root())
+        // If we already have an execution payload with this block hash or root cached, use it.
+        } else if let Some(cached_payload) = el
+            .get_payload_by_block_hash(&payload_header.block_hash())
+            .or_else(|| el.get_payload_by_root(&payload_header.tree_hash_root()))
         {
+            // Never publish a block whose body differs from the header signed by the proposer.
+            if cached_payload.tree_hash_root() != payload_header.tree_hash_root() {
+                warn!(
+                    log,
+                    "Cached payload does not match blinded block";
+                    "block_hash" => ?payload_header.block_hash(),
+                    "slot" => block.slot(),
+                );
+                return Err(warp_utils::reject::custom_bad_request(format!(
+                    "execution payload header does not match cached payload with block hash {:?}",
+                    payload_header.block_hash()
+                )));
+            }
             info!(log, "Reconstructing a full block using a local payload"; "block_hash" => ?cached_payload.block_hash());
             ProvenancedPayload::Local(cached_payload)
+        // If the payload was built locally but has since been evicted from the cache, our
+        // execution node may still be able to provide its body.
+        } else if let Some(local_payload) = el
+            .get_payload_for_header_by_hash(
+                &payload_header.to_execution_payload_header(),
+                fork_name,
+            )
+            .await
+            .unwrap_or_else(|e| {
+                warn!(
+                    log,
+                    "Unable to fetch payload body from execution node";
+                    "error" => ?e,
+                    "block_hash" => ?payload_header.block_hash(),
+                );
+                None
+            })
+        {
+            info!(log, "Reconstructing a full block using a payload body from the EL"; "block_hash" => ?local_payload.block_hash());
+            ProvenancedPayload::Local(local_payload)
         // Otherwise, this means we are attempting a blind block proposal.
         } else {
             // Perform the logging for late blocks when we publish to the
@@ -331,6 +474,99 @@ pub async fn reconstruct_block<T: BeaconChainTypes>(
     })
 }
 
+/// Decode an SSZ-encoded block received via the HTTP API.
+///
+/// If the client supplied an `Eth-Consensus-Version` header then the block is decoded as that
+/// fork's variant and checked against the fork at the block's slot. Otherwise the variant is
+/// determined from the slot alone.
+pub fn decode_ssz_block<E: EthSpec, Payload: AbstractExecPayload<E>>(
+    block_bytes: &[u8],
+    consensus_version: Option<String>,
+    spec: &ChainSpec,
+) -> Result<SignedBeaconBlock<E, Payload>, Rejection> {
+    let consensus_version = match consensus_version {
+        Some(version) => version,
+        None => {
+            return SignedBeaconBlock::from_ssz_bytes(block_bytes, spec)
+                .map_err(|e| warp_utils::reject::custom_bad_request(format!("invalid SSZ: {e:?}")))
+        }
+    };
+
+    let fork_name = ForkName::from_str(&consensus_version).map_err(|e| {
+        warp_utils::reject::custom_bad_request(format!(
+            "invalid {CONSENSUS_VERSION_HEADER} header: {e}"
+        ))
+    })?;
+    let block =
+        SignedBeaconBlock::from_ssz_bytes_for_fork(block_bytes, fork_name).map_err(|e| {
+            warp_utils::reject::custom_bad_request(format!(
+                "invalid SSZ for {fork_name} block: {e:?}"
+            ))
+        })?;
+    block.fork_name(spec).map_err(|e| {
+        warp_utils::reject::custom_bad_request(format!(
+            "{CONSENSUS_VERSION_HEADER} ({}) does not match the fork at the block slot ({})",
+            e.object_fork, e.fork_at_slot
+        ))
+    })?;
+
+    Ok(block)
+}
+
+/// Compare the fee recipient of an imported block's payload against the one expected for its
+/// proposer, recording significant mismatches against the proposer in the validator monitor.
+///
+/// Builder payloads are logged and counted by the execution layer here, once revealed, and are only
+/// significant if the user has opted in. Local payloads were logged by the execution layer when
+/// they were produced, and a mismatch is always significant.
+async fn check_fee_recipient<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block: &SignedBeaconBlock<T::EthSpec>,
+    is_locally_built_block: bool,
+) {
+    let (el, payload) = match (
+        chain.execution_layer.as_ref(),
+        block.message().execution_payload(),
+    ) {
+        (Some(el), Ok(payload)) if payload.block_hash() != ExecutionBlockHash::zero() => {
+            (el, payload)
+        }
+        // Pre-merge blocks have no fee recipient.
+        _ => return,
+    };
+
+    let proposer_index = block.message().proposer_index();
+    let expected_fee_recipient = match el.expected_fee_recipient(proposer_index).await {
+        Some(fee_recipient) => fee_recipient,
+        None => return,
+    };
+    let fee_recipient = payload.fee_recipient();
+    if fee_recipient == expected_fee_recipient {
+        return;
+    }
+
+    let (src, significant) = if is_locally_built_block {
+        ("local", true)
+    } else {
+        (
+            "builder",
+            el.check_builder_fee_recipient(fee_recipient, expected_fee_recipient),
+        )
+    };
+    if significant {
+        chain
+            .validator_monitor
+            .read()
+            .register_fee_recipient_mismatch(
+                src,
+                proposer_index,
+                block.slot(),
+                expected_fee_recipient,
+                fee_recipient,
+            );
+    }
+}
+
 /// If the `seen_timestamp` is some time after the start of the slot for
 /// `block`, create some logs to indicate that the block was published late.
 fn late_block_logging<T: BeaconChainTypes, P: AbstractExecPayload<T::EthSpec>>(
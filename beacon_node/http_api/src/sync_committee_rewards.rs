@@ -5,8 +5,8 @@ use eth2::types::ValidatorId;
 use slog::{debug, Logger};
 use state_processing::BlockReplayer;
 use std::sync::Arc;
-use types::{BeaconState, SignedBlindedBeaconBlock};
-use warp_utils::reject::{beacon_chain_error, custom_not_found};
+use types::{BeaconState, ForkName, SignedBlindedBeaconBlock};
+use warp_utils::reject::{beacon_chain_error, custom_bad_request, custom_not_found};
 
 pub fn compute_sync_committee_rewards<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
@@ -16,6 +16,13 @@ pub fn compute_sync_committee_rewards<T: BeaconChainTypes>(
 ) -> Result<(Option<Vec<SyncCommitteeReward>>, ExecutionOptimistic, bool), warp::Rejection> {
     let (block, execution_optimistic, finalized) = block_id.blinded_block(&chain)?;
 
+    if chain.spec.fork_name_at_slot::<T::EthSpec>(block.slot()) == ForkName::Base {
+        return Err(custom_bad_request(format!(
+            "block at slot {} is prior to the Altair fork and has no sync aggregate",
+            block.slot()
+        )));
+    }
+
     let mut state = get_state_before_applying_block(chain.clone(), &block)?;
 
     let reward_payload = chain
@@ -1,7 +1,7 @@
 use beacon_chain::{
     validator_monitor::HISTORIC_EPOCHS, BeaconChain, BeaconChainError, BeaconChainTypes,
 };
-use eth2::types::{Epoch, ValidatorStatus};
+use eth2::types::{Epoch, Slot, ValidatorStatus};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
@@ -155,6 +155,15 @@ pub struct ValidatorMetricsRequestData {
 }
 
 #[derive(PartialEq, Serialize, Deserialize)]
+pub struct ValidatorProposal {
+    slot: Slot,
+    delay_ms: u64,
+    on_time: bool,
+}
+
+/// Metrics for a single validator. Validators which are not monitored are reported with the
+/// default (empty) value.
+#[derive(Default, PartialEq, Serialize, Deserialize)]
 pub struct ValidatorMetrics {
     attestation_hits: u64,
     attestation_misses: u64,
@@ -166,6 +175,9 @@ pub struct ValidatorMetrics {
     attestation_target_misses: u64,
     attestation_target_hit_percentage: f64,
     latest_attestation_inclusion_distance: u64,
+    sync_committee_messages: u64,
+    sync_signature_block_inclusions: u64,
+    proposals: Vec<ValidatorProposal>,
 }
 
 #[derive(PartialEq, Serialize, Deserialize)]
@@ -173,84 +185,79 @@ pub struct ValidatorMetricsResponse {
     validators: HashMap<String, ValidatorMetrics>,
 }
 
+fn hit_percentage(hits: u64, misses: u64) -> f64 {
+    let total = hits + misses;
+    if total == 0 {
+        0.0
+    } else {
+        (100 * hits / total) as f64
+    }
+}
+
 pub fn post_validator_monitor_metrics<T: BeaconChainTypes>(
     request_data: ValidatorMetricsRequestData,
     chain: Arc<BeaconChain<T>>,
 ) -> Result<ValidatorMetricsResponse, warp::Rejection> {
-    let validator_ids = chain
-        .validator_monitor
-        .read()
-        .get_all_monitored_validators()
-        .iter()
-        .cloned()
-        .collect::<HashSet<String>>();
-
-    let indices = request_data
-        .indices
-        .iter()
-        .map(|index| index.to_string())
-        .collect::<HashSet<String>>();
-
-    let ids = validator_ids
-        .intersection(&indices)
-        .collect::<HashSet<&String>>();
+    let validator_monitor = chain.validator_monitor.read();
 
     let mut validators = HashMap::new();
 
-    for id in ids {
-        if let Ok(index) = id.parse::<u64>() {
-            if let Some(validator) = chain
-                .validator_monitor
-                .read()
-                .get_monitored_validator(index)
-            {
-                let val_metrics = validator.metrics.read();
-                let attestation_hits = val_metrics.attestation_hits;
-                let attestation_misses = val_metrics.attestation_misses;
-                let attestation_head_hits = val_metrics.attestation_head_hits;
-                let attestation_head_misses = val_metrics.attestation_head_misses;
-                let attestation_target_hits = val_metrics.attestation_target_hits;
-                let attestation_target_misses = val_metrics.attestation_target_misses;
-                let latest_attestation_inclusion_distance =
-                    val_metrics.latest_attestation_inclusion_distance;
-                drop(val_metrics);
-
-                let attestations = attestation_hits + attestation_misses;
-                let attestation_hit_percentage: f64 = if attestations == 0 {
-                    0.0
-                } else {
-                    (100 * attestation_hits / attestations) as f64
-                };
-                let head_attestations = attestation_head_hits + attestation_head_misses;
-                let attestation_head_hit_percentage: f64 = if head_attestations == 0 {
-                    0.0
-                } else {
-                    (100 * attestation_head_hits / head_attestations) as f64
-                };
+    for index in request_data.indices {
+        let validator = match validator_monitor.get_monitored_validator(index) {
+            Some(validator) => validator,
+            None => {
+                validators.insert(index.to_string(), ValidatorMetrics::default());
+                continue;
+            }
+        };
 
-                let target_attestations = attestation_target_hits + attestation_target_misses;
-                let attestation_target_hit_percentage: f64 = if target_attestations == 0 {
-                    0.0
-                } else {
-                    (100 * attestation_target_hits / target_attestations) as f64
-                };
+        let (sync_committee_messages, sync_signature_block_inclusions) = validator
+            .summaries
+            .read()
+            .values()
+            .fold((0, 0), |(messages, inclusions), summary| {
+                (
+                    messages + summary.sync_committee_messages as u64,
+                    inclusions + summary.sync_signature_block_inclusions as u64,
+                )
+            });
 
-                let metrics = ValidatorMetrics {
-                    attestation_hits,
-                    attestation_misses,
-                    attestation_hit_percentage,
-                    attestation_head_hits,
-                    attestation_head_misses,
-                    attestation_head_hit_percentage,
-                    attestation_target_hits,
-                    attestation_target_misses,
-                    attestation_target_hit_percentage,
-                    latest_attestation_inclusion_distance,
-                };
+        let val_metrics = validator.metrics.read();
+        let metrics = ValidatorMetrics {
+            attestation_hits: val_metrics.attestation_hits,
+            attestation_misses: val_metrics.attestation_misses,
+            attestation_hit_percentage: hit_percentage(
+                val_metrics.attestation_hits,
+                val_metrics.attestation_misses,
+            ),
+            attestation_head_hits: val_metrics.attestation_head_hits,
+            attestation_head_misses: val_metrics.attestation_head_misses,
+            attestation_head_hit_percentage: hit_percentage(
+                val_metrics.attestation_head_hits,
+                val_metrics.attestation_head_misses,
+            ),
+            attestation_target_hits: val_metrics.attestation_target_hits,
+            attestation_target_misses: val_metrics.attestation_target_misses,
+            attestation_target_hit_percentage: hit_percentage(
+                val_metrics.attestation_target_hits,
+                val_metrics.attestation_target_misses,
+            ),
+            latest_attestation_inclusion_distance: val_metrics
+                .latest_attestation_inclusion_distance,
+            sync_committee_messages,
+            sync_signature_block_inclusions,
+            proposals: val_metrics
+                .proposals
+                .iter()
+                .map(|proposal| ValidatorProposal {
+                    slot: proposal.slot,
+                    delay_ms: proposal.delay.as_millis() as u64,
+                    on_time: proposal.on_time,
+                })
+                .collect(),
+        };
 
-                validators.insert(id.clone(), metrics);
-            }
-        }
+        validators.insert(index.to_string(), metrics);
     }
 
     Ok(ValidatorMetricsResponse { validators })
@@ -1,12 +1,13 @@
 use beacon_chain::{
     test_utils::{AttestationStrategy, BlockStrategy},
-    GossipVerifiedBlock,
+    ChainConfig, GossipVerifiedBlock,
 };
 use eth2::types::{BroadcastValidation, SignedBeaconBlock, SignedBlindedBeaconBlock};
 use http_api::test_utils::InteractiveTester;
 use http_api::{publish_blinded_block, publish_block, reconstruct_block, ProvenancedBlock};
+use std::sync::Arc;
 use tree_hash::TreeHash;
-use types::{Hash256, MainnetEthSpec, Slot};
+use types::{Hash256, MainnetEthSpec, Signature, Slot};
 use warp::Rejection;
 use warp_utils::reject::CustomBadRequest;
 
@@ -21,11 +22,13 @@ type E = MainnetEthSpec;
  *   -  Invalid (400)
  *   -  Full Pass (200)
  *   -  Partial Pass (202)
+ *   -  Equivocates with a block seen on gossip (200)
  *  -  `broadcast_validation=consensus`
  *    -  Invalid (400)
  *    -  Only gossip (400)
- *    -  Only consensus pass (i.e., equivocates) (200)
+ *    -  Only consensus pass (i.e., equivocates with a block seen on gossip) (400)
  *    -  Full pass (200)
+ *    -  Forged proposer signature, with and without `paranoid_block_import` (400/200)
  *  -  `broadcast_validation=consensus_and_equivocation`
  *    -  Invalid (400)
  *    -  Invalid due to early equivocation (400)
@@ -217,6 +220,75 @@ pub async fn gossip_full_pass_ssz() {
         .block_is_known_to_fork_choice(&block.canonical_root()));
 }
 
+/// This test checks that a block that is valid from both a gossip and consensus perspective, but equivocates with a block only seen on gossip, is accepted when using `broadcast_validation=gossip`.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn gossip_equivocation() {
+    /* this test targets gossip-level validation */
+    let validation_level: Option<BroadcastValidation> = Some(BroadcastValidation::Gossip);
+
+    // Validator count needs to be at least 32 or proposer boost gets set to 0 when computing
+    // `validator_count // 32`.
+    let validator_count = 64;
+    let num_initial: u64 = 31;
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let test_logger = tester.harness.logger().clone();
+
+    // Create some chain depth.
+    tester.harness.advance_slot();
+    tester
+        .harness
+        .extend_chain(
+            num_initial as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    tester.harness.advance_slot();
+
+    let slot_a = Slot::new(num_initial);
+    let slot_b = slot_a + 1;
+
+    let state_a = tester.harness.get_current_state();
+    let (block_a, _): (SignedBeaconBlock<E>, _) =
+        tester.harness.make_block(state_a.clone(), slot_b).await;
+    let (block_b, _): (SignedBeaconBlock<E>, _) = tester.harness.make_block(state_a, slot_b).await;
+    assert_ne!(block_a.canonical_root(), block_b.canonical_root());
+
+    let gossip_block_b = GossipVerifiedBlock::new(block_b.clone().into(), &tester.harness.chain);
+    assert!(gossip_block_b.is_ok());
+    let gossip_block_a = GossipVerifiedBlock::new(block_a.clone().into(), &tester.harness.chain);
+    assert!(gossip_block_a.is_err());
+    assert_eq!(
+        tester
+            .harness
+            .chain
+            .find_equivocating_proposal(block_b.message(), block_b.canonical_root())
+            .unwrap(),
+        Some(block_a.canonical_root())
+    );
+
+    /* submit `block_b`, which equivocates but should be published since the equivocation check
+     * is bypassed for gossip validation */
+    let channel = tokio::sync::mpsc::unbounded_channel();
+
+    let publication_result = publish_block(
+        None,
+        ProvenancedBlock::local(gossip_block_b.unwrap()),
+        tester.harness.chain.clone(),
+        &channel.0,
+        test_logger,
+        validation_level.unwrap(),
+        StatusCode::ACCEPTED,
+    )
+    .await;
+
+    assert!(publication_result.is_ok());
+    assert!(tester
+        .harness
+        .chain
+        .block_is_known_to_fork_choice(&block_b.canonical_root()));
+}
+
 /// This test checks that a block that is **invalid** from a gossip perspective gets rejected when using `broadcast_validation=consensus`.
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 pub async fn consensus_invalid() {
@@ -317,7 +389,7 @@ pub async fn consensus_gossip() {
     );
 }
 
-/// This test checks that a block that is valid from both a gossip and consensus perspective, but nonetheless equivocates, is accepted when using `broadcast_validation=consensus`.
+/// This test checks that a block that is valid from both a gossip and consensus perspective, but equivocates with a block only seen on gossip, is rejected when using `broadcast_validation=consensus`.
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 pub async fn consensus_partial_pass_only_consensus() {
     /* this test targets gossip-level validation */
@@ -375,8 +447,18 @@ pub async fn consensus_partial_pass_only_consensus() {
     )
     .await;
 
-    assert!(publication_result.is_ok());
-    assert!(tester
+    assert!(publication_result.is_err());
+
+    let publication_error = publication_result.unwrap_err();
+
+    assert_eq!(
+        *publication_error.find::<CustomBadRequest>().unwrap().0,
+        format!(
+            "block equivocates with previously seen block {:?}",
+            block_a.canonical_root()
+        )
+    );
+    assert!(!tester
         .harness
         .chain
         .block_is_known_to_fork_choice(&block_b.canonical_root()));
@@ -424,6 +506,107 @@ pub async fn consensus_full_pass() {
         .block_is_known_to_fork_choice(&block.canonical_root()));
 }
 
+/// This test checks that a gossip verified block carrying an invalid proposer signature is imported
+/// when `paranoid_block_import` is disabled, since gossip verification is trusted.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn consensus_forged_signature_non_paranoid() {
+    let (publication_result, imported) = publish_block_with_forged_signature(false).await;
+
+    assert!(publication_result.is_ok());
+    assert!(imported);
+}
+
+/// This test checks that a gossip verified block carrying an invalid proposer signature is rejected
+/// when `paranoid_block_import` is enabled.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn consensus_forged_signature_paranoid() {
+    let (publication_result, imported) = publish_block_with_forged_signature(true).await;
+
+    let error_response: Rejection = publication_result.err().unwrap();
+    assert!(error_response.find::<CustomBadRequest>().is_some());
+    assert_eq!(
+        *error_response.find::<CustomBadRequest>().unwrap().0,
+        "Invalid block: InvalidSignature".to_string()
+    );
+    assert!(!imported);
+}
+
+/// Gossip verify a valid block, strip its proposer signature and publish it with
+/// `broadcast_validation=consensus`. Returns the publication result and whether the block was
+/// imported.
+async fn publish_block_with_forged_signature(
+    paranoid_block_import: bool,
+) -> (Result<warp::reply::Response, Rejection>, bool) {
+    let validation_level: Option<BroadcastValidation> = Some(BroadcastValidation::Consensus);
+
+    // Validator count needs to be at least 32 or proposer boost gets set to 0 when computing
+    // `validator_count // 32`.
+    let validator_count = 64;
+    let num_initial: u64 = 31;
+    let tester = InteractiveTester::<E>::new_with_initializer_and_mutator(
+        None,
+        validator_count,
+        Some(Box::new(move |builder| {
+            builder
+                .deterministic_keypairs(validator_count)
+                .fresh_ephemeral_store()
+                .chain_config(ChainConfig {
+                    paranoid_block_import,
+                    ..ChainConfig::default()
+                })
+        })),
+        None,
+    )
+    .await;
+    let test_logger = tester.harness.logger().clone();
+
+    // Create some chain depth.
+    tester.harness.advance_slot();
+    tester
+        .harness
+        .extend_chain(
+            num_initial as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    tester.harness.advance_slot();
+
+    let state_a = tester.harness.get_current_state();
+    let (block, _): (SignedBeaconBlock<E>, _) = tester
+        .harness
+        .make_block(state_a, Slot::new(num_initial) + 1)
+        .await;
+    let block_root = block.canonical_root();
+
+    let mut gossip_block =
+        GossipVerifiedBlock::new(Arc::new(block.clone()), &tester.harness.chain).unwrap();
+    gossip_block.block = Arc::new(SignedBeaconBlock::from_block(
+        block.message().clone(),
+        Signature::empty(),
+    ));
+
+    let channel = tokio::sync::mpsc::unbounded_channel();
+
+    let publication_result = publish_block(
+        None,
+        ProvenancedBlock::local(gossip_block),
+        tester.harness.chain.clone(),
+        &channel.0,
+        test_logger,
+        validation_level.unwrap(),
+        StatusCode::ACCEPTED,
+    )
+    .await;
+
+    let imported = tester
+        .harness
+        .chain
+        .block_is_known_to_fork_choice(&block_root);
+
+    (publication_result, imported)
+}
+
 /// This test checks that a block that is **invalid** from a gossip perspective gets rejected when using `broadcast_validation=consensus_and_equivocation`.
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 pub async fn equivocation_invalid() {
@@ -539,7 +722,7 @@ pub async fn equivocation_consensus_early_equivocation() {
     assert_eq!(error_response.status(), Some(StatusCode::BAD_REQUEST));
 
     assert!(
-        matches!(error_response, eth2::Error::ServerMessage(err) if err.message ==  "BAD_REQUEST: Slashable".to_string())
+        matches!(error_response, eth2::Error::ServerMessage(err) if err.message == format!("BAD_REQUEST: block equivocates with previously seen block {:?}", block_a.canonical_root()))
     );
 }
 
@@ -661,7 +844,10 @@ pub async fn equivocation_consensus_late_equivocation() {
 
     assert_eq!(
         *publication_error.find::<CustomBadRequest>().unwrap().0,
-        "proposal for this slot and proposer has already been seen".to_string()
+        format!(
+            "block equivocates with previously seen block {:?}",
+            block_a.canonical_root()
+        )
     );
 }
 
@@ -1163,7 +1349,7 @@ pub async fn blinded_equivocation_consensus_early_equivocation() {
     assert_eq!(error_response.status(), Some(StatusCode::BAD_REQUEST));
 
     assert!(
-        matches!(error_response, eth2::Error::ServerMessage(err) if err.message ==  "BAD_REQUEST: Slashable".to_string())
+        matches!(error_response, eth2::Error::ServerMessage(err) if err.message == format!("BAD_REQUEST: block equivocates with previously seen block {:?}", block_a.canonical_root()))
     );
 }
 
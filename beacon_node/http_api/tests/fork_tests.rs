@@ -1,15 +1,21 @@
 //! Tests for API behaviour across fork boundaries.
 use beacon_chain::{
-    test_utils::{RelativeSyncCommittee, DEFAULT_ETH1_BLOCK_HASH, HARNESS_GENESIS_TIME},
-    StateSkipConfig,
+    test_utils::{
+        AttestationStrategy, BlockStrategy, RelativeSyncCommittee, SyncCommitteeStrategy,
+        DEFAULT_ETH1_BLOCK_HASH, HARNESS_GENESIS_TIME,
+    },
+    ChainConfig, StateSkipConfig, WhenSlotSkipped,
 };
+use eth2::reqwest::StatusCode;
 use eth2::types::{IndexedErrorMessage, StateId, SyncSubcommittee};
 use genesis::{bls_withdrawal_credentials, interop_genesis_state_with_withdrawal_credentials};
 use http_api::test_utils::*;
 use std::collections::HashSet;
+use std::time::Duration;
 use types::{
     test_utils::{generate_deterministic_keypair, generate_deterministic_keypairs},
-    Address, ChainSpec, Epoch, EthSpec, Hash256, MinimalEthSpec, Slot,
+    Address, ChainSpec, Epoch, EthSpec, ForkName, Hash256, LightClientBootstrap, MinimalEthSpec,
+    Slot,
 };
 
 type E = MinimalEthSpec;
@@ -536,3 +542,198 @@ async fn bls_to_execution_changes_update_all_around_capella_fork() {
         assert!(validator.has_eth1_withdrawal_credential(&spec));
     }
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn light_client_bootstrap_across_fork() {
+    let validator_count = E::sync_committee_size();
+    let fork_epoch = Epoch::new(1);
+    let spec = altair_spec(fork_epoch);
+    let tester = InteractiveTester::<E>::new(Some(spec), validator_count).await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+
+    // Build a chain which extends past the Altair fork.
+    let num_blocks = 2 * E::slots_per_epoch() as usize;
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            num_blocks,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Blocks prior to the fork have no sync committee to bootstrap from.
+    let pre_fork_root = harness
+        .chain
+        .block_root_at_slot(
+            fork_epoch.start_slot(E::slots_per_epoch()) - 1,
+            WhenSlotSkipped::None,
+        )
+        .unwrap()
+        .unwrap();
+    let error = client
+        .get_light_client_bootstrap::<E>(pre_fork_root)
+        .await
+        .unwrap_err();
+    assert_eq!(error.status().map(Into::into), Some(400));
+
+    // Unknown blocks should 404.
+    assert!(client
+        .get_light_client_bootstrap::<E>(Hash256::repeat_byte(0xff))
+        .await
+        .unwrap()
+        .is_none());
+
+    // The bootstrap for the head block should match one computed from the head state.
+    let head = harness.chain.head_snapshot();
+    let head_root = head.beacon_block_root;
+    let mut head_state = head.beacon_state.clone();
+    let expected = LightClientBootstrap::from_beacon_state(&mut head_state).unwrap();
+    assert_eq!(expected.header.slot, head.beacon_block.slot());
+
+    let response = client
+        .get_light_client_bootstrap::<E>(head_root)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(response.version, Some(ForkName::Altair));
+    assert_eq!(response.data, expected);
+
+    // The SSZ response should contain the same (cached) bootstrap.
+    let ssz_bootstrap = client
+        .get_light_client_bootstrap_ssz::<E>(head_root)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(ssz_bootstrap, expected);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn light_client_updates_after_altair() {
+    let validator_count = E::sync_committee_size();
+    let spec = altair_spec(Epoch::new(0));
+    let tester = InteractiveTester::<E>::new_with_initializer_and_mutator(
+        Some(spec),
+        validator_count,
+        Some(Box::new(move |builder| {
+            builder
+                .deterministic_keypairs(validator_count)
+                .fresh_ephemeral_store()
+                .chain_config(ChainConfig {
+                    enable_light_client_server: true,
+                    ..ChainConfig::default()
+                })
+        })),
+        None,
+    )
+    .await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+
+    // No updates are available prior to any blocks being imported.
+    assert!(client
+        .get_light_client_optimistic_update::<E>()
+        .await
+        .unwrap()
+        .is_none());
+    assert!(client
+        .get_light_client_finality_update::<E>()
+        .await
+        .unwrap()
+        .is_none());
+
+    // Build a chain with full sync committee participation that finalizes, stopping short of an
+    // epoch boundary so that the head and its parent agree on the finalized checkpoint.
+    let num_blocks = 4 * E::slots_per_epoch() as usize - 2;
+    harness.advance_slot();
+    harness
+        .extend_chain_with_sync(
+            num_blocks,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+            SyncCommitteeStrategy::AllValidators,
+        )
+        .await;
+
+    let head = harness.chain.head_snapshot();
+    let parent_root = head.beacon_block.parent_root();
+    let finalized_root = head.beacon_state.finalized_checkpoint().root;
+    assert_ne!(finalized_root, Hash256::zero());
+
+    // The updates are computed on a background task after the head changes.
+    for _ in 0..100 {
+        if harness
+            .chain
+            .get_latest_light_client_finality_update()
+            .map_or(false, |update| {
+                update.attested_header.canonical_root() == parent_root
+            })
+        {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    let optimistic_update = client
+        .get_light_client_optimistic_update::<E>()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(optimistic_update.version, Some(ForkName::Altair));
+    assert_eq!(
+        optimistic_update.data.attested_header.canonical_root(),
+        parent_root
+    );
+    assert_eq!(
+        optimistic_update.data.signature_slot,
+        head.beacon_block.slot()
+    );
+    assert_eq!(
+        optimistic_update.data.sync_aggregate.num_set_bits(),
+        E::sync_committee_size()
+    );
+
+    let finality_update = client
+        .get_light_client_finality_update::<E>()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(finality_update.version, Some(ForkName::Altair));
+    assert_eq!(
+        finality_update.data.attested_header,
+        optimistic_update.data.attested_header
+    );
+    assert_eq!(
+        finality_update.data.finalized_header.canonical_root(),
+        finalized_root
+    );
+
+    // The whole chain lies within the first sync committee period, so exactly one update is
+    // stored, regardless of how many periods are requested.
+    let updates = client.get_light_client_updates::<E>(0, 4).await.unwrap();
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].version, Some(ForkName::Altair));
+    assert_eq!(
+        updates[0].data.sync_aggregate.num_set_bits(),
+        E::sync_committee_size()
+    );
+    assert_eq!(
+        updates[0].data.next_sync_committee,
+        head.beacon_state.next_sync_committee().unwrap().clone()
+    );
+    assert!(client
+        .get_light_client_updates::<E>(1, 4)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Requests for more than `MAX_REQUEST_LIGHT_CLIENT_UPDATES` (128) periods are rejected rather
+    // than truncated.
+    assert!(client.get_light_client_updates::<E>(0, 128).await.is_ok());
+    let error = client
+        .get_light_client_updates::<E>(0, 129)
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+}
@@ -4,7 +4,7 @@ use beacon_chain::{
     test_utils::{AttestationStrategy, BlockStrategy, SyncCommitteeStrategy},
     ChainConfig,
 };
-use eth2::types::{DepositContractData, StateId};
+use eth2::types::{DepositContractData, EventKind, StateId};
 use execution_layer::{ForkchoiceState, PayloadAttributes};
 use http_api::test_utils::InteractiveTester;
 use parking_lot::Mutex;
@@ -17,8 +17,8 @@ use std::sync::Arc;
 use std::time::Duration;
 use tree_hash::TreeHash;
 use types::{
-    Address, Epoch, EthSpec, ExecPayload, ExecutionBlockHash, ForkName, FullPayload,
-    MainnetEthSpec, MinimalEthSpec, ProposerPreparationData, Slot,
+    Address, AttestationShufflingId, Epoch, EthSpec, ExecPayload, ExecutionBlockHash, ForkName,
+    FullPayload, MainnetEthSpec, MinimalEthSpec, ProposerPreparationData, Slot,
 };
 
 type E = MainnetEthSpec;
@@ -814,3 +814,476 @@ pub async fn fork_choice_before_proposal() {
     // D's parent is B.
     assert_eq!(block_d.parent_root(), block_root_b.into());
 }
+
+// Test that next-epoch proposer duties are cached against their decision root, and that a re-org
+// across the epoch boundary which changes the decision root is reflected in the duties.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn proposer_duties_next_epoch_re_org() {
+    let validator_count = 64;
+    let all_validators = (0..validator_count).collect::<Vec<_>>();
+    let slots_per_epoch = E::slots_per_epoch();
+    let num_initial = slots_per_epoch - 3;
+
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let harness = &tester.harness;
+    let spec = &harness.chain.spec;
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            num_initial as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Build the same block graph as `fork_choice_before_proposal`, but with B and C in the last
+    // two slots of epoch 0, so that they are competing decision blocks for epoch 1.
+    //
+    // A | B | - |
+    // ^ | - | C |
+    let slot_a = Slot::new(num_initial);
+    let slot_b = slot_a + 1;
+    let slot_c = slot_a + 2;
+    let next_epoch = Epoch::new(1);
+    assert_eq!(slot_c + 1, next_epoch.start_slot(slots_per_epoch));
+
+    let state_a = harness.get_current_state();
+    let (block_b, state_b) = harness.make_block(state_a.clone(), slot_b).await;
+    let block_root_b = harness
+        .process_block(slot_b, block_b.canonical_root(), block_b)
+        .await
+        .unwrap();
+    let attestations_b = harness.make_attestations(
+        &all_validators,
+        &state_b,
+        state_b.tree_hash_root(),
+        block_root_b,
+        slot_b,
+    );
+
+    let (block_c, state_c) = harness.make_block(state_a, slot_c).await;
+    let block_root_c = harness
+        .process_block(slot_c, block_c.canonical_root(), block_c)
+        .await
+        .unwrap();
+    let attestations_c = harness.make_attestations(
+        &all_validators[..validator_count / 2],
+        &state_c,
+        state_c.tree_hash_root(),
+        block_root_c,
+        slot_c,
+    );
+    harness.process_attestations(attestations_c);
+    harness.process_attestations(attestations_b);
+
+    let expected_proposers = |state: &types::BeaconState<E>| {
+        let mut state = state.clone();
+        complete_state_advance(
+            &mut state,
+            None,
+            next_epoch.start_slot(slots_per_epoch),
+            spec,
+        )
+        .unwrap();
+        state
+            .get_beacon_proposer_indices(spec)
+            .unwrap()
+            .into_iter()
+            .map(|index| index as u64)
+            .collect::<Vec<_>>()
+    };
+    let duty_indices = |duties: &eth2::types::DutiesResponse<Vec<eth2::types::ProposerData>>| {
+        duties
+            .data
+            .iter()
+            .map(|duty| duty.validator_index)
+            .collect::<Vec<_>>()
+    };
+
+    // During slot C the head is C, so the duties for the next epoch are decided by C.
+    assert_eq!(
+        harness.chain.canonical_head.cached_head().head_block_root(),
+        block_root_c.into()
+    );
+    let duties_c = tester
+        .client
+        .get_validator_duties_proposer(next_epoch)
+        .await
+        .unwrap();
+    assert_eq!(duties_c.dependent_root, block_root_c.into());
+    assert_eq!(duty_indices(&duties_c), expected_proposers(&state_c));
+    assert!(harness
+        .chain
+        .beacon_proposer_cache
+        .lock()
+        .get_epoch::<E>(block_root_c.into(), next_epoch)
+        .is_some());
+
+    // A repeated request is served from the cache.
+    let duties_c_cached = tester
+        .client
+        .get_validator_duties_proposer(next_epoch)
+        .await
+        .unwrap();
+    assert_eq!(duties_c_cached.dependent_root, duties_c.dependent_root);
+    assert_eq!(duty_indices(&duties_c_cached), duty_indices(&duties_c));
+
+    // Once proposer boost expires at the epoch boundary the head re-orgs to B, which changes the
+    // decision root for epoch 1.
+    harness.advance_slot();
+    harness.chain.per_slot_task().await;
+    assert_eq!(
+        harness.chain.canonical_head.cached_head().head_block_root(),
+        block_root_b.into()
+    );
+
+    let duties_b = tester
+        .client
+        .get_validator_duties_proposer(next_epoch)
+        .await
+        .unwrap();
+    assert_eq!(duties_b.dependent_root, block_root_b.into());
+    assert_eq!(duty_indices(&duties_b), expected_proposers(&state_b));
+
+    // The duties decided by the re-orged block C have been dropped from the cache.
+    let mut beacon_proposer_cache = harness.chain.beacon_proposer_cache.lock();
+    assert!(beacon_proposer_cache
+        .get_epoch::<E>(block_root_c.into(), next_epoch)
+        .is_none());
+    assert!(beacon_proposer_cache
+        .get_epoch::<E>(block_root_b.into(), next_epoch)
+        .is_some());
+}
+
+// Test that concurrent attester duty requests for a shuffling which is not yet cached are served
+// consistently from the shuffling cache.
+//
+// That such requests share a single computation is tested by
+// `concurrent_committee_cache_requests_build_once` in the beacon chain tests.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+pub async fn attester_duties_concurrent_requests_share_committee_cache() {
+    let validator_count = 64;
+    let num_requests = 16;
+    let slots_per_epoch = E::slots_per_epoch();
+
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let harness = &tester.harness;
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            2,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Skip into the next epoch without producing blocks. The shuffling for the epoch after is
+    // decided by the (old) head block and has not been primed by block import.
+    let current_epoch = Epoch::new(1);
+    harness.set_current_slot(current_epoch.start_slot(slots_per_epoch));
+    let request_epoch = current_epoch + 1;
+
+    let head_block_root = harness.chain.canonical_head.cached_head().head_block_root();
+    let shuffling_id = AttestationShufflingId::from_components(request_epoch, head_block_root);
+    assert!(!harness
+        .chain
+        .shuffling_cache
+        .try_read_for(Duration::from_secs(1))
+        .unwrap()
+        .contains(&shuffling_id));
+
+    let indices = (0..validator_count as u64).collect::<Vec<_>>();
+    let responses = futures::future::join_all((0..num_requests).map(|_| {
+        tester
+            .client
+            .post_validator_duties_attester(request_epoch, &indices)
+    }))
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()
+    .unwrap();
+
+    assert!(harness
+        .chain
+        .shuffling_cache
+        .try_read_for(Duration::from_secs(1))
+        .unwrap()
+        .contains(&shuffling_id));
+
+    let first = &responses[0];
+    assert_eq!(first.dependent_root, head_block_root);
+    assert_eq!(first.data.len(), validator_count);
+    for response in &responses[1..] {
+        assert_eq!(response.dependent_root, first.dependent_root);
+        assert_eq!(response.data, first.data);
+    }
+}
+
+// Test that blocks published via the HTTP API appear in the fork choice dump, with each block
+// carrying at least the weight of its descendants.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn debug_fork_choice_includes_published_blocks() {
+    let validator_count = 64;
+    let all_validators = (0..validator_count).collect::<Vec<_>>();
+    let num_initial: u64 = 3;
+    let num_published: u64 = 3;
+
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+
+    // Create some chain depth.
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            num_initial as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Publish a chain of blocks via the HTTP API, attesting to each one.
+    let mut state = harness.get_current_state();
+    let mut published_roots = vec![];
+    for i in 1..=num_published {
+        let slot = Slot::new(num_initial + i);
+        harness.advance_slot();
+        assert_eq!(harness.get_current_slot(), slot);
+
+        let (block, post_state) = harness.make_block(state, slot).await;
+        let block_root = block.canonical_root();
+        client.post_beacon_blocks(&block).await.unwrap();
+
+        let attestations = harness.make_attestations(
+            &all_validators,
+            &post_state,
+            post_state.tree_hash_root(),
+            block_root.into(),
+            slot,
+        );
+        harness.process_attestations(attestations);
+
+        published_roots.push(block_root);
+        state = post_state;
+    }
+
+    // Advance past the last block so that its attestations are applied, then re-run fork choice.
+    harness.advance_slot();
+    harness.chain.recompute_head_at_current_slot().await;
+
+    let fork_choice = client.get_debug_fork_choice().await.unwrap();
+
+    let weights = published_roots
+        .iter()
+        .map(|root| {
+            fork_choice
+                .fork_choice_nodes
+                .iter()
+                .find(|node| node.block_root == *root)
+                .unwrap_or_else(|| panic!("block {root:?} missing from fork choice"))
+                .weight
+        })
+        .collect::<Vec<_>>();
+
+    // Each published block is the parent of the next, so weights decrease towards the head.
+    for (parent_weight, child_weight) in weights.iter().zip(weights.iter().skip(1)) {
+        assert!(
+            parent_weight > child_weight,
+            "parent weight {parent_weight} should exceed child weight {child_weight}"
+        );
+    }
+    assert!(*weights.last().unwrap() > 0);
+}
+
+// Test that `payload_attributes` events are emitted at most once per (proposal slot, head block
+// root), and are emitted again when the head or proposal slot changes.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn payload_attributes_events_once_per_slot_and_head() {
+    let validator_count = 32;
+    let mut spec = ForkName::Capella.make_genesis_spec(E::default_spec());
+    spec.terminal_total_difficulty = 1.into();
+
+    let tester = InteractiveTester::<E>::new(Some(spec), validator_count).await;
+    let harness = &tester.harness;
+    let mock_el = harness.mock_execution_layer.as_ref().unwrap();
+    mock_el.server.all_payloads_valid();
+    mock_el
+        .server
+        .ctx
+        .execution_block_generator
+        .write()
+        .move_to_terminal_block()
+        .unwrap();
+
+    // Register all validators as local proposers.
+    let proposer_preparation_data = (0..validator_count as u64)
+        .map(|validator_index| ProposerPreparationData {
+            validator_index,
+            fee_recipient: Address::from_low_u64_be(validator_index),
+        })
+        .collect::<Vec<_>>();
+    harness
+        .chain
+        .execution_layer
+        .as_ref()
+        .unwrap()
+        .update_proposer_preparation(Epoch::new(0), &proposer_preparation_data)
+        .await;
+
+    let mut events = harness
+        .chain
+        .event_handler
+        .as_ref()
+        .unwrap()
+        .subscribe_payload_attributes();
+
+    let mut expected = vec![];
+    for _ in 0..2 {
+        harness.advance_slot();
+        harness
+            .extend_chain(
+                1,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+
+        // Preparing repeatedly for the same slot and head must not emit duplicate events.
+        let current_slot = harness.get_current_slot();
+        for _ in 0..3 {
+            harness
+                .chain
+                .prepare_beacon_proposer(current_slot)
+                .await
+                .unwrap();
+        }
+        expected.push((current_slot + 1, harness.head_block_root()));
+    }
+
+    // Allow any preparation triggered in the background by head changes to complete.
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let mut received = vec![];
+    while let Ok(event) = events.try_recv() {
+        match event {
+            EventKind::PayloadAttributes(event) => {
+                assert_eq!(event.version, Some(ForkName::Capella));
+                received.push((event.data.proposal_slot, event.data.parent_block_root));
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    // Events arrive in proposal slot order, and each (slot, head) pair appears exactly once.
+    assert!(received.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    for key in &received {
+        assert_eq!(received.iter().filter(|other| *other == key).count(), 1);
+    }
+    for key in &expected {
+        assert!(received.contains(key), "missing event for {:?}", key);
+    }
+}
+
+// Test that the streamed attestation performance of the entire validator set matches the
+// per-validator results.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn attestation_performance_all_matches_global() {
+    type E = MinimalEthSpec;
+    let validator_count = 32;
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            5 * E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    harness.advance_slot();
+
+    let (start_epoch, end_epoch) = (Epoch::new(0), Epoch::new(1));
+    let global = client
+        .get_lighthouse_analysis_attestation_performance(
+            start_epoch,
+            end_epoch,
+            "global".to_string(),
+        )
+        .await
+        .unwrap();
+    let all = client
+        .get_lighthouse_analysis_attestation_performance_all(start_epoch, end_epoch)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        all.iter().map(|perf| perf.epoch).collect::<Vec<_>>(),
+        vec![start_epoch.as_u64(), end_epoch.as_u64()]
+    );
+    for epoch_perf in &all {
+        assert_eq!(epoch_perf.validators.len(), validator_count);
+        for perf in &global {
+            assert_eq!(
+                epoch_perf.validators[perf.index as usize],
+                perf.epochs[&epoch_perf.epoch]
+            );
+        }
+    }
+
+    // Invalid ranges are rejected before the response starts streaming.
+    let error = client
+        .get_lighthouse_analysis_attestation_performance_all(end_epoch, start_epoch)
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(eth2::StatusCode::BAD_REQUEST));
+}
+
+// Test that requests spanning more than the maximum number of epochs are rejected, by both the
+// per-validator and the streamed endpoints.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn attestation_performance_max_epoch_span() {
+    type E = MinimalEthSpec;
+    let validator_count = 32;
+    let tester = InteractiveTester::<E>::new(None, validator_count).await;
+    let client = &tester.client;
+    let max_epochs = http_api::Config::default().attestation_performance_max_epochs as u64;
+
+    let start_epoch = Epoch::new(0);
+    let span_error = format!(
+        "BAD_REQUEST: end_epoch must not exceed start_epoch by more than {} epochs",
+        max_epochs
+    );
+    let is_span_error = |error: &eth2::Error| matches!(error, eth2::Error::ServerMessage(err) if err.message.starts_with(&span_error));
+
+    // One epoch beyond the maximum span is rejected for exceeding it.
+    let over_span = start_epoch + max_epochs + 1;
+    let error = client
+        .get_lighthouse_analysis_attestation_performance(
+            start_epoch,
+            over_span,
+            "global".to_string(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(eth2::StatusCode::BAD_REQUEST));
+    assert!(is_span_error(&error), "{:?}", error);
+    let error = client
+        .get_lighthouse_analysis_attestation_performance_all(start_epoch, over_span)
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(eth2::StatusCode::BAD_REQUEST));
+    assert!(is_span_error(&error), "{:?}", error);
+
+    // The maximum span itself is permitted, so the request is only rejected for ending after the
+    // current epoch.
+    let error = client
+        .get_lighthouse_analysis_attestation_performance_all(start_epoch, start_epoch + max_epochs)
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(eth2::StatusCode::BAD_REQUEST));
+    assert!(!is_span_error(&error), "{:?}", error);
+}
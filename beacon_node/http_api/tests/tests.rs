@@ -5,6 +5,7 @@ use beacon_chain::{
 };
 use environment::null_logger;
 use eth2::{
+    lighthouse::{IndividualTrackingStatus, PublishedBlockReward},
     mixin::{RequestAccept, ResponseForkName, ResponseOptional},
     reqwest::RequestBuilder,
     types::{BlockId as CoreBlockId, ForkChoiceNode, StateId as CoreStateId, *},
@@ -12,8 +13,9 @@ use eth2::{
 };
 use execution_layer::test_utils::{
     MockBuilder, Operation, DEFAULT_BUILDER_PAYLOAD_VALUE_WEI, DEFAULT_BUILDER_THRESHOLD_WEI,
-    DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI,
+    DEFAULT_ENGINE_CAPABILITIES, DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI,
 };
+use execution_layer::EngineCapabilities;
 use futures::stream::{Stream, StreamExt};
 use futures::FutureExt;
 use http_api::{
@@ -25,6 +27,7 @@ use network::NetworkReceivers;
 use proto_array::ExecutionStatus;
 use sensitive_url::SensitiveUrl;
 use slot_clock::SlotClock;
+use ssz::Encode;
 use state_processing::per_block_processing::get_expected_withdrawals;
 use state_processing::per_slot_processing;
 use state_processing::state_advance::partial_state_advance;
@@ -79,6 +82,7 @@ struct ApiTesterConfig {
     spec: ChainSpec,
     retain_historic_states: bool,
     builder_threshold: Option<u128>,
+    builder_boost_factor: Option<u64>,
 }
 
 impl Default for ApiTesterConfig {
@@ -89,6 +93,7 @@ impl Default for ApiTesterConfig {
             spec,
             retain_historic_states: false,
             builder_threshold: None,
+            builder_boost_factor: None,
         }
     }
 }
@@ -130,7 +135,7 @@ impl ApiTester {
             .logger(logging::test_logger())
             .deterministic_keypairs(VALIDATOR_COUNT)
             .fresh_ephemeral_store()
-            .mock_execution_layer_with_config(config.builder_threshold)
+            .mock_execution_layer_with_config(config.builder_threshold, config.builder_boost_factor)
             .build();
 
         harness
@@ -400,8 +405,15 @@ impl ApiTester {
     }
 
     pub async fn new_mev_tester_no_builder_threshold() -> Self {
+        Self::new_mev_tester_with_builder_boost_factor(None).await
+    }
+
+    pub async fn new_mev_tester_with_builder_boost_factor(
+        builder_boost_factor: Option<u64>,
+    ) -> Self {
         let mut config = ApiTesterConfig {
             builder_threshold: Some(0),
+            builder_boost_factor,
             retain_historic_states: false,
             spec: E::default_spec(),
         };
@@ -1091,6 +1103,25 @@ impl ApiTester {
                 .unwrap();
 
             assert_eq!(result.unwrap().randao, *randao_mix);
+
+            // Omitting the epoch should default to the state's current epoch.
+            let default_result = self
+                .client
+                .get_beacon_states_randao(state_id.0, None)
+                .await
+                .unwrap()
+                .unwrap()
+                .data;
+            assert_eq!(default_result.randao, *randao_mix);
+
+            // Epochs beyond the state's current epoch are outside the retained range.
+            let future_epoch = state.current_epoch() + 1;
+            let err = self
+                .client
+                .get_beacon_states_randao(state_id.0, Some(future_epoch))
+                .await
+                .unwrap_err();
+            assert_eq!(err.status().map(Into::into), Some(400));
         }
 
         self
@@ -1255,6 +1286,50 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_beacon_rewards_blocks(self) -> Self {
+        let head_block = self.chain.head_beacon_block();
+        let response = self
+            .client
+            .get_beacon_rewards_blocks(CoreBlockId::Head)
+            .await
+            .unwrap();
+
+        let rewards = response.data;
+        assert_eq!(
+            rewards.proposer_index,
+            head_block.message().proposer_index()
+        );
+        assert_eq!(
+            rewards.total,
+            rewards.attestations
+                + rewards.sync_aggregate
+                + rewards.proposer_slashings
+                + rewards.attester_slashings
+        );
+        assert_eq!(response.finalized, Some(false));
+
+        let unknown_block = self
+            .client
+            .get_beacon_rewards_blocks(CoreBlockId::Root(Hash256::zero()))
+            .await
+            .unwrap_err();
+        assert_eq!(unknown_block.status(), Some(StatusCode::NOT_FOUND));
+
+        self
+    }
+
+    pub async fn test_beacon_rewards_sync_committee_pre_altair(self) -> Self {
+        // The chain built by this tester has not yet reached the Altair fork.
+        let error = self
+            .client
+            .post_beacon_rewards_sync_committee(CoreBlockId::Head, &[])
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+
+        self
+    }
+
     pub async fn test_post_beacon_blocks_valid(mut self) -> Self {
         let next_block = &self.next_block;
 
@@ -1330,6 +1405,115 @@ impl ApiTester {
         self
     }
 
+    /// POST raw SSZ `bytes` to `url`, optionally setting the `Eth-Consensus-Version` header.
+    async fn post_raw_ssz_block(
+        &self,
+        url: eth2::Url,
+        bytes: Vec<u8>,
+        consensus_version: Option<ForkName>,
+    ) -> Result<(), Error> {
+        let mut builder = eth2::reqwest::Client::new()
+            .post(url)
+            .header("Content-Type", "application/octet-stream");
+        if let Some(fork_name) = consensus_version {
+            builder = builder.header(eth2::CONSENSUS_VERSION_HEADER, fork_name.to_string());
+        }
+        let response = builder.body(bytes).send().await?;
+        eth2::ok_or_error(response).await.map(|_| ())
+    }
+
+    fn assert_bad_request_starting_with(result: Result<(), Error>, prefix: &str) {
+        match result {
+            Err(Error::ServerMessage(err)) => {
+                assert_eq!(err.code, 400);
+                assert!(
+                    err.message.starts_with(prefix),
+                    "expected {prefix:?}, got {:?}",
+                    err.message
+                );
+            }
+            Err(e) => panic!("expected a server message, got {e:?}"),
+            Ok(()) => panic!("expected a 400 response"),
+        }
+    }
+
+    pub async fn test_post_beacon_blocks_ssz_malformed(self) -> Self {
+        let fork_name = self.next_block.fork_name(&self.chain.spec).unwrap();
+        let full_bytes = self.next_block.as_ssz_bytes();
+        let blinded_bytes = self.next_block.clone_as_blinded().as_ssz_bytes();
+
+        for (url, bytes) in [
+            (
+                self.client.post_beacon_blocks_v2_path(None).unwrap(),
+                full_bytes,
+            ),
+            (
+                self.client
+                    .post_beacon_blinded_blocks_v2_path(None)
+                    .unwrap(),
+                blinded_bytes,
+            ),
+        ] {
+            // Truncate the block so that it cannot be decoded.
+            let truncated = bytes[..bytes.len() / 2].to_vec();
+
+            // Without a consensus version header the fork is determined from the slot.
+            Self::assert_bad_request_starting_with(
+                self.post_raw_ssz_block(url.clone(), truncated.clone(), None)
+                    .await,
+                "BAD_REQUEST: invalid SSZ",
+            );
+            Self::assert_bad_request_starting_with(
+                self.post_raw_ssz_block(url, truncated, Some(fork_name))
+                    .await,
+                &format!("BAD_REQUEST: invalid SSZ for {fork_name} block"),
+            );
+        }
+
+        self
+    }
+
+    pub async fn test_post_beacon_blocks_ssz_fork_mismatch(self) -> Self {
+        let spec = &self.chain.spec;
+        let fork_at_slot = self.next_block.fork_name(spec).unwrap();
+        assert_ne!(fork_at_slot, ForkName::Base);
+
+        // A well-formed phase0 block at a slot which belongs to a later fork.
+        let mut message = BeaconBlockBase::<E>::empty(spec);
+        message.slot = self.next_block.slot();
+        let block = SignedBeaconBlock::from_block(
+            BeaconBlock::Base(message),
+            self.next_block.signature().clone(),
+        );
+        let expected = format!(
+            "BAD_REQUEST: {} (phase0) does not match the fork at the block slot ({fork_at_slot})",
+            eth2::CONSENSUS_VERSION_HEADER
+        );
+
+        Self::assert_bad_request_starting_with(
+            self.post_raw_ssz_block(
+                self.client.post_beacon_blocks_v2_path(None).unwrap(),
+                block.as_ssz_bytes(),
+                Some(ForkName::Base),
+            )
+            .await,
+            &expected,
+        );
+        Self::assert_bad_request_starting_with(
+            self.post_raw_ssz_block(
+                self.client
+                    .post_beacon_blinded_blocks_v2_path(None)
+                    .unwrap(),
+                block.clone_as_blinded().as_ssz_bytes(),
+                Some(ForkName::Base),
+            )
+            .await,
+            &expected,
+        );
+
+        self
+    }
+
     pub async fn test_post_beacon_blocks_duplicate(self) -> Self {
         let block = self
             .harness
@@ -1782,6 +1966,15 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_beacon_deposit_snapshot(self) -> Self {
+        // The eth1 service has not imported any deposits, so it has nothing to finalize.
+        let result = self.client.get_deposit_snapshot().await.unwrap();
+
+        assert_eq!(result, None);
+
+        self
+    }
+
     pub async fn test_get_config_fork_schedule(self) -> Self {
         let result = self.client.get_config_fork_schedule().await.unwrap().data;
 
@@ -1851,6 +2044,8 @@ impl ApiTester {
             el_offline: Some(true),
             head_slot,
             sync_distance,
+            is_backfilling: Some(false),
+            backfill_rate_limited: None,
         };
 
         assert_eq!(result, expected);
@@ -1905,6 +2100,7 @@ impl ApiTester {
             last_seen_p2p_address: EXTERNAL_ADDR.to_string(),
             state: PeerState::Connected,
             direction: PeerDirection::Inbound,
+            client: None,
         };
 
         assert_eq!(result, expected);
@@ -1937,6 +2133,7 @@ impl ApiTester {
                     last_seen_p2p_address: EXTERNAL_ADDR.to_string(),
                     state: PeerState::Connected,
                     direction: PeerDirection::Inbound,
+                    client: None,
                 };
 
                 let state_match =
@@ -2599,6 +2796,51 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_block_production_timings_headers(self) -> Self {
+        let slot = self.chain.slot().unwrap();
+
+        let path = self
+            .client
+            .get_validator_blocks_path::<E, FullPayload<E>>(
+                slot,
+                &Signature::infinity().unwrap().into(),
+                None,
+                SkipRandaoVerification::Yes,
+            )
+            .await
+            .unwrap();
+        let response = self.client.get_response(path, |b| b).await.unwrap();
+        let header_millis = |name: &str| -> u64 {
+            let value = response.headers().get(name);
+            assert!(value.is_some(), "missing header {name}");
+            let millis = value.unwrap().to_str().ok().and_then(|s| s.parse().ok());
+            assert!(
+                millis.is_some(),
+                "header {name} is not a number of milliseconds"
+            );
+            millis.unwrap()
+        };
+
+        let total = header_millis(eth2::BLOCK_PRODUCTION_TIME_HEADER);
+        let stages = [
+            "fork_choice",
+            "state_load",
+            "state_advance",
+            "op_pool_packing",
+            "payload_fetch",
+            "block_processing",
+            "state_root",
+        ];
+        let stage_total: u64 = stages
+            .iter()
+            .map(|stage| header_millis(&eth2::block_production_stage_header(stage)))
+            .sum();
+        // The total is the wall-clock time, which also covers the time between stages.
+        assert!(stage_total <= total);
+
+        self
+    }
+
     pub async fn test_block_production_verify_randao_invalid(self) -> Self {
         let fork = self.chain.canonical_head.cached_head().head_fork();
         let genesis_validators_root = self.chain.genesis_validators_root;
@@ -3226,6 +3468,51 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_post_validator_register_validator_builder_failure(self) -> Self {
+        let genesis_epoch = self.chain.spec.genesis_slot.epoch(E::slots_per_epoch());
+        let fork = Fork {
+            current_version: self.chain.spec.genesis_fork_version,
+            previous_version: self.chain.spec.genesis_fork_version,
+            epoch: genesis_epoch,
+        };
+        let domain = self.chain.spec.get_domain(
+            genesis_epoch,
+            Domain::ApplicationMask(ApplicationDomain::Builder),
+            &fork,
+            Hash256::zero(),
+        );
+
+        // Sign each registration with the wrong key, so that the builder rejects them.
+        let keypairs = self.validator_keypairs();
+        let registrations = keypairs
+            .iter()
+            .enumerate()
+            .map(|(val_index, keypair)| {
+                let data = ValidatorRegistrationData {
+                    fee_recipient: Address::from_low_u64_be(val_index as u64),
+                    gas_limit: 11_111_111,
+                    timestamp: 1,
+                    pubkey: keypair.pk.compress(),
+                };
+                let wrong_keypair = &keypairs[(val_index + 1) % keypairs.len()];
+                SignedValidatorRegistrationData {
+                    signature: wrong_keypair.sk.sign(data.signing_root(domain)),
+                    message: data,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let error = self
+            .client
+            .post_validator_register_validator(&registrations)
+            .await
+            .unwrap_err();
+        // The builder's rejection of the invalid signatures is forwarded as a client error.
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+
+        self
+    }
+
     pub async fn test_post_validator_liveness_epoch(self) -> Self {
         let epoch = self.chain.epoch().unwrap();
         let head_state = self.chain.head_beacon_state_cloned();
@@ -3286,6 +3573,30 @@ impl ApiTester {
 
         assert_eq!(result, expected);
 
+        // Indices which are unknown to the chain are reported as not live.
+        let unknown_index = head_state.validators().len() as u64 + 1;
+        let result = self
+            .client
+            .post_validator_liveness_epoch(epoch, vec![unknown_index])
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(
+            result,
+            vec![StandardLivenessResponseData {
+                index: unknown_index,
+                is_live: false,
+            }]
+        );
+
+        // Requests for a future epoch are rejected.
+        let err = self
+            .client
+            .post_validator_liveness_epoch(epoch + 1, indices)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().map(Into::into), Some(400));
+
         self
     }
 
@@ -3652,6 +3963,192 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_builder_reveal_failure_fallback_then_recover(self) -> Self {
+        let fork = self.chain.canonical_head.cached_head().head_fork();
+        let genesis_validators_root = self.chain.genesis_validators_root;
+        let execution_layer = self.chain.execution_layer.as_ref().unwrap();
+
+        // Mutate the next bid so that the builder is unable to reveal a payload matching it.
+        self.mock_builder
+            .as_ref()
+            .unwrap()
+            .add_operation(Operation::GasLimit(30_000_000));
+
+        let mut slot = self.chain.slot().unwrap();
+        for (expect_fallback, expect_reveal_failure) in
+            [(false, true), (true, false), (false, false)]
+        {
+            let epoch = self.chain.epoch().unwrap();
+            let (proposer_index, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+            let block = self
+                .client
+                .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
+                .await
+                .unwrap()
+                .data;
+            let payload: BlindedPayload<E> = block.body().execution_payload().unwrap().into();
+
+            // If this cache is populated, it indicates fallback to the local EE was used.
+            assert_eq!(
+                execution_layer
+                    .get_payload_by_root(&payload.tree_hash_root())
+                    .is_some(),
+                expect_fallback,
+                "slot {slot}"
+            );
+
+            let sk = &self.validator_keypairs()[proposer_index as usize].sk;
+            let signed_block = block.sign(sk, &fork, genesis_validators_root, &self.chain.spec);
+            let result = self.client.post_beacon_blinded_blocks(&signed_block).await;
+
+            assert_eq!(result.is_err(), expect_reveal_failure, "slot {slot}");
+            assert_eq!(
+                execution_layer.last_builder_reveal_failed(),
+                expect_reveal_failure,
+                "slot {slot}"
+            );
+
+            slot += 1;
+            self.chain.slot_clock.set_slot(slot.as_u64());
+        }
+
+        self
+    }
+
+    pub async fn test_builder_revealed_payload_mismatch(
+        mut self,
+        modifier: fn(&mut ExecutionPayload<E>),
+        field: &str,
+    ) -> Self {
+        let fork = self.chain.canonical_head.cached_head().head_fork();
+        let genesis_validators_root = self.chain.genesis_validators_root;
+
+        self.mock_builder
+            .as_ref()
+            .unwrap()
+            .modify_revealed_payloads(modifier);
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+        let (proposer_index, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let block = self
+            .client
+            .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data;
+        let payload: BlindedPayload<E> = block.body().execution_payload().unwrap().into();
+
+        // The bid must have come from the builder for the reveal to be exercised.
+        assert!(self
+            .chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .get_payload_by_root(&payload.tree_hash_root())
+            .is_none());
+
+        let sk = &self.validator_keypairs()[proposer_index as usize].sk;
+        let signed_block = block.sign(sk, &fork, genesis_validators_root, &self.chain.spec);
+
+        match self.client.post_beacon_blinded_blocks(&signed_block).await {
+            Err(Error::ServerMessage(err)) => {
+                assert_eq!(err.code, 500);
+                assert!(
+                    err.message
+                        .contains(&format!("RevealedPayloadMismatch {{ field: {field:?}")),
+                    "unexpected error: {}",
+                    err.message
+                );
+            }
+            other => panic!("expected a server error, got {other:?}"),
+        }
+
+        assert!(
+            self.network_rx.network_recv.recv().now_or_never().is_none(),
+            "blocks with a mismatched payload should not be sent to network"
+        );
+        assert!(self
+            .chain
+            .execution_layer
+            .as_ref()
+            .unwrap()
+            .last_builder_reveal_failed());
+
+        self
+    }
+
+    pub async fn test_fee_recipient_mismatch_registered_by_severity(self) -> Self {
+        let fork = self.chain.canonical_head.cached_head().head_fork();
+        let genesis_validators_root = self.chain.genesis_validators_root;
+        let mismatches = |src: &str| {
+            lighthouse_metrics::get_int_counter(
+                &beacon_chain::metrics::VALIDATOR_MONITOR_FEE_RECIPIENT_MISMATCH_TOTAL,
+                &[src, "total"],
+            )
+            .map_or(0, |counter| counter.get())
+        };
+        let (local_before, builder_before) = (mismatches("local"), mismatches("builder"));
+
+        for blinded in [false, true] {
+            let slot = self.chain.slot().unwrap();
+            let epoch = self.chain.epoch().unwrap();
+            let (proposer_index, randao_reveal) = self.get_test_randao(slot, epoch).await;
+            let sk = &self.validator_keypairs()[proposer_index as usize].sk;
+            // Change the proposer's fee recipient after the payload was built.
+            let preparation_data = [ProposerPreparationData {
+                validator_index: proposer_index as u64,
+                fee_recipient: Address::repeat_byte(0x42),
+            }];
+
+            if blinded {
+                let block = self
+                    .client
+                    .get_validator_blinded_blocks::<E, BlindedPayload<E>>(
+                        slot,
+                        &randao_reveal,
+                        None,
+                    )
+                    .await
+                    .unwrap()
+                    .data;
+                self.client
+                    .post_validator_prepare_beacon_proposer(&preparation_data)
+                    .await
+                    .unwrap();
+                let signed_block = block.sign(sk, &fork, genesis_validators_root, &self.chain.spec);
+                self.client
+                    .post_beacon_blinded_blocks(&signed_block)
+                    .await
+                    .unwrap();
+            } else {
+                let block = self
+                    .client
+                    .get_validator_blocks::<E, FullPayload<E>>(slot, &randao_reveal, None)
+                    .await
+                    .unwrap()
+                    .data;
+                self.client
+                    .post_validator_prepare_beacon_proposer(&preparation_data)
+                    .await
+                    .unwrap();
+                let signed_block = block.sign(sk, &fork, genesis_validators_root, &self.chain.spec);
+                self.client.post_beacon_blocks(&signed_block).await.unwrap();
+            }
+
+            self.chain.slot_clock.set_slot(slot.as_u64() + 1);
+        }
+
+        // Local payloads must always honour the fee recipient.
+        assert_eq!(mismatches("local"), local_before + 1);
+        // Builder mismatches are not significant unless the user opts in.
+        assert_eq!(mismatches("builder"), builder_before);
+
+        self
+    }
+
     pub async fn test_builder_chain_health_skips(self) -> Self {
         let slot = self.chain.slot().unwrap();
 
@@ -4040,6 +4537,46 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_payload_source_for_builder_value(
+        self,
+        builder_value: u128,
+        expect_builder: bool,
+    ) -> Self {
+        self.mock_builder
+            .as_ref()
+            .unwrap()
+            .add_operation(Operation::Value(Uint256::from(builder_value)));
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let payload: BlindedPayload<E> = self
+            .client
+            .get_validator_blinded_blocks::<E, BlindedPayload<E>>(slot, &randao_reveal, None)
+            .await
+            .unwrap()
+            .data
+            .body()
+            .execution_payload()
+            .unwrap()
+            .into();
+
+        // The local payload cache is only populated if the local payload was chosen.
+        assert_eq!(
+            self.chain
+                .execution_layer
+                .as_ref()
+                .unwrap()
+                .get_payload_by_root(&payload.tree_hash_root())
+                .is_none(),
+            expect_builder,
+            "builder value {builder_value}"
+        );
+        self
+    }
+
     pub async fn test_builder_works_post_capella(self) -> Self {
         // Ensure builder payload is chosen
         self.mock_builder
@@ -4143,7 +4680,10 @@ impl ApiTester {
     }
 
     pub async fn test_get_lighthouse_syncing(self) -> Self {
-        self.client.get_lighthouse_syncing().await.unwrap();
+        let result = self.client.get_lighthouse_syncing().await.unwrap();
+
+        assert!(!result.is_backfilling);
+        assert_eq!(result.backfill_rate_limited, None);
 
         self
     }
@@ -4200,6 +4740,64 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_execution_capabilities(self) -> Self {
+        let execution_layer = self.chain.execution_layer.as_ref().unwrap();
+        let mock_el = self.harness.mock_execution_layer.as_ref().unwrap();
+
+        // All capabilities are supported by default.
+        execution_layer
+            .get_engine_capabilities(Some(Duration::ZERO))
+            .await
+            .unwrap();
+        let capabilities = self
+            .client
+            .get_lighthouse_execution_capabilities()
+            .await
+            .unwrap()
+            .data;
+        assert!(capabilities.missing.is_empty());
+        assert!(capabilities
+            .supported
+            .contains(&"engine_getPayloadBodiesByRangeV1".to_string()));
+
+        // Remove support for the payload bodies methods.
+        mock_el.server.set_engine_capabilities(EngineCapabilities {
+            get_payload_bodies_by_hash_v1: false,
+            get_payload_bodies_by_range_v1: false,
+            ..DEFAULT_ENGINE_CAPABILITIES
+        });
+        execution_layer
+            .get_engine_capabilities(Some(Duration::ZERO))
+            .await
+            .unwrap();
+        let capabilities = self
+            .client
+            .get_lighthouse_execution_capabilities()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(
+            capabilities.missing,
+            vec![
+                "engine_getPayloadBodiesByHashV1".to_string(),
+                "engine_getPayloadBodiesByRangeV1".to_string(),
+            ]
+        );
+        assert!(!capabilities
+            .supported
+            .contains(&"engine_getPayloadBodiesByRangeV1".to_string()));
+
+        mock_el
+            .server
+            .set_engine_capabilities(DEFAULT_ENGINE_CAPABILITIES);
+        execution_layer
+            .get_engine_capabilities(Some(Duration::ZERO))
+            .await
+            .unwrap();
+
+        self
+    }
+
     pub async fn test_get_lighthouse_beacon_states_ssz(self) -> Self {
         for state_id in self.interesting_state_ids() {
             let result = self
@@ -4237,6 +4835,17 @@ impl ApiTester {
             info.schema_version,
             store::metadata::CURRENT_SCHEMA_VERSION.as_u64()
         );
+        assert_eq!(
+            info.slots_per_restore_point,
+            self.chain.store.slots_per_restore_point()
+        );
+        assert_eq!(
+            info.stats,
+            self.chain
+                .store
+                .cached_database_stats()
+                .map(|stats| stats.as_ref().clone())
+        );
 
         self
     }
@@ -4251,6 +4860,64 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_post_lighthouse_validator_monitor_individual_tracking_threshold(
+        self,
+    ) -> Self {
+        let monitored_validators = {
+            let mut validator_monitor = self.chain.validator_monitor.write();
+            for validator_index in 0..4 {
+                validator_monitor.auto_register_local_validator(validator_index);
+            }
+            validator_monitor.num_validators()
+        };
+        assert!(monitored_validators >= 4);
+
+        let status = self
+            .client
+            .post_lighthouse_validator_monitor_individual_tracking_threshold(
+                monitored_validators as u64 - 1,
+            )
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(
+            status,
+            IndividualTrackingStatus {
+                threshold: monitored_validators as u64 - 1,
+                individual_tracking: false,
+                monitored_validators,
+            }
+        );
+        assert_eq!(
+            self.chain
+                .validator_monitor
+                .read()
+                .individual_tracking_threshold(),
+            monitored_validators - 1
+        );
+
+        // Raising the threshold again restores per-validator tracking without losing validators.
+        let status = self
+            .client
+            .post_lighthouse_validator_monitor_individual_tracking_threshold(
+                monitored_validators as u64,
+            )
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(
+            status,
+            IndividualTrackingStatus {
+                threshold: monitored_validators as u64,
+                individual_tracking: true,
+                monitored_validators,
+            }
+        );
+        assert!(self.chain.validator_monitor.read().individual_tracking());
+
+        self
+    }
+
     pub async fn test_post_lighthouse_liveness(self) -> Self {
         let epoch = self.chain.epoch().unwrap();
         let head_state = self.chain.head_beacon_state_cloned();
@@ -4458,6 +5125,12 @@ impl ApiTester {
         .await;
         assert_eq!(reorg_event.as_slice(), &[expected_reorg]);
 
+        // Recomputing the head again must not emit a duplicate re-org event.
+        self.chain.recompute_head_at_current_slot().await;
+        let duplicate_events =
+            poll_events(&mut chain_reorg_event_future, 1, Duration::from_millis(500)).await;
+        assert!(duplicate_events.is_empty());
+
         self
     }
 
@@ -4527,6 +5200,42 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_events_published_block_reward(self) -> Self {
+        let topics = vec![EventTopic::PublishedBlockReward];
+        let mut events_future = self
+            .client
+            .get_events::<E>(topics.as_slice())
+            .await
+            .unwrap();
+
+        let block_root = self.next_block.canonical_root();
+
+        self.client
+            .post_beacon_blocks(&self.next_block)
+            .await
+            .unwrap();
+
+        // The reward reported after import should match the standard rewards endpoint.
+        let expected_reward = self
+            .client
+            .get_beacon_rewards_blocks(CoreBlockId::Root(block_root))
+            .await
+            .unwrap()
+            .data;
+
+        let reward_events = poll_events(&mut events_future, 1, Duration::from_millis(10000)).await;
+        assert_eq!(
+            reward_events.as_slice(),
+            &[EventKind::PublishedBlockReward(PublishedBlockReward {
+                block_root,
+                slot: self.next_block.slot(),
+                reward: expected_reward,
+            })]
+        );
+
+        self
+    }
+
     pub async fn test_get_events_altair(self) -> Self {
         let topics = vec![EventTopic::ContributionAndProof];
         let mut events_future = self
@@ -4663,6 +5372,16 @@ async fn get_events() {
     ApiTester::new().await.test_get_events().await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_events_published_block_reward() {
+    let mut config = ApiTesterConfig::default();
+    config.spec.altair_fork_epoch = Some(Epoch::new(0));
+    ApiTester::new_from_config(config)
+        .await
+        .test_get_events_published_block_reward()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_events_altair() {
     let mut config = ApiTesterConfig::default();
@@ -4731,6 +5450,10 @@ async fn beacon_get() {
         .await
         .test_beacon_blocks_root()
         .await
+        .test_beacon_rewards_blocks()
+        .await
+        .test_beacon_rewards_sync_committee_pre_altair()
+        .await
         .test_get_beacon_pool_attestations()
         .await
         .test_get_beacon_pool_attester_slashings()
@@ -4738,6 +5461,8 @@ async fn beacon_get() {
         .test_get_beacon_pool_proposer_slashings()
         .await
         .test_get_beacon_pool_voluntary_exits()
+        .await
+        .test_get_beacon_deposit_snapshot()
         .await;
 }
 
@@ -4762,6 +5487,22 @@ async fn test_post_beacon_blocks_ssz_invalid() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_blocks_ssz_malformed() {
+    ApiTester::new()
+        .await
+        .test_post_beacon_blocks_ssz_malformed()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_beacon_blocks_ssz_fork_mismatch() {
+    ApiTester::new_with_hard_forks(true, false)
+        .await
+        .test_post_beacon_blocks_ssz_fork_mismatch()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn post_beacon_blocks_invalid() {
     ApiTester::new()
@@ -4933,6 +5674,14 @@ async fn block_production() {
     ApiTester::new().await.test_block_production().await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn block_production_timings_headers() {
+    ApiTester::new()
+        .await
+        .test_block_production_timings_headers()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn block_production_with_skip_slots() {
     ApiTester::new()
@@ -5139,6 +5888,14 @@ async fn post_validator_register_validator() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_validator_register_validator_builder_failure() {
+    ApiTester::new()
+        .await
+        .test_post_validator_register_validator_builder_failure()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn post_validator_register_validator_slashed() {
     ApiTester::new()
@@ -5163,6 +5920,14 @@ async fn post_validator_register_gas_limit_mutation() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fee_recipient_mismatch_registered_by_severity() {
+    ApiTester::new_mev_tester()
+        .await
+        .test_fee_recipient_mismatch_registered_by_severity()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn post_validator_register_fee_recipient_mutation() {
     ApiTester::new_mev_tester()
@@ -5211,6 +5976,52 @@ async fn get_blinded_block_invalid_signature() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_reveal_failure_fallback_then_recover() {
+    ApiTester::new_mev_tester()
+        .await
+        .test_builder_reveal_failure_fallback_then_recover()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_revealed_payload_block_hash_mismatch() {
+    ApiTester::new_mev_tester()
+        .await
+        .test_builder_revealed_payload_mismatch(
+            |payload| *payload.block_hash_mut() = ExecutionBlockHash::repeat_byte(0x42),
+            "block_hash",
+        )
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_revealed_payload_fee_recipient_mismatch() {
+    ApiTester::new_mev_tester()
+        .await
+        .test_builder_revealed_payload_mismatch(
+            |payload| *payload.fee_recipient_mut() = Address::repeat_byte(0x42),
+            "fee_recipient",
+        )
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_revealed_payload_transactions_mismatch() {
+    ApiTester::new_mev_tester()
+        .await
+        .test_builder_revealed_payload_mismatch(
+            |payload| {
+                payload
+                    .transactions_mut()
+                    .push(VariableList::new(vec![0x42]).unwrap())
+                    .unwrap()
+            },
+            "transactions_root",
+        )
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn builder_chain_health_skips() {
     ApiTester::new_mev_tester()
@@ -5263,10 +6074,40 @@ async fn builder_payload_chosen_by_profit() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn builder_payload_chosen_by_boosted_profit() {
+    // Builder values are doubled, so the builder wins with anything above half the local value.
+    let threshold = DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI / 2;
+    ApiTester::new_mev_tester_with_builder_boost_factor(Some(200))
+        .await
+        .test_payload_source_for_builder_value(threshold - 1, false)
+        .await
+        .test_payload_source_for_builder_value(threshold, false)
+        .await
+        .test_payload_source_for_builder_value(threshold + 1, true)
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn local_payload_chosen_by_discounted_builder_profit() {
+    // Builder values are discounted by 20%, so the builder must bid more than 125% of the local
+    // value to win.
+    let threshold = DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI * 100 / 80;
+    ApiTester::new_mev_tester_with_builder_boost_factor(Some(80))
+        .await
+        .test_payload_source_for_builder_value(DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI + 1, false)
+        .await
+        .test_payload_source_for_builder_value(threshold, false)
+        .await
+        .test_payload_source_for_builder_value(threshold + 2, true)
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn builder_works_post_capella() {
     let mut config = ApiTesterConfig {
         builder_threshold: Some(0),
+        builder_boost_factor: None,
         retain_historic_states: false,
         spec: E::default_spec(),
     };
@@ -5312,6 +6153,8 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_eth1_deposit_cache()
         .await
+        .test_get_lighthouse_execution_capabilities()
+        .await
         .test_get_lighthouse_beacon_states_ssz()
         .await
         .test_get_lighthouse_staking()
@@ -5321,6 +6164,8 @@ async fn lighthouse_endpoints() {
         .test_post_lighthouse_database_reconstruct()
         .await
         .test_post_lighthouse_liveness()
+        .await
+        .test_post_lighthouse_validator_monitor_individual_tracking_threshold()
         .await;
 }
 
@@ -1,5 +1,6 @@
 use crate::listen_addr::{ListenAddr, ListenAddress};
 use crate::rpc::config::{InboundRateLimiterConfig, OutboundRateLimiterConfig};
+use crate::service::gossipsub_scoring_parameters::GossipsubScoreOverrides;
 use crate::types::GossipKind;
 use crate::{Enr, PeerIdSerialized};
 use directory::{
@@ -30,6 +31,9 @@ use types::{ForkContext, ForkName};
 /// another 500ms for "fudge factor".
 pub const DUPLICATE_CACHE_TIME: Duration = Duration::from_secs(33 * 12 + 1);
 
+/// The default age after which persisted peers are no longer dialed on startup.
+pub const DEFAULT_PERSISTED_PEERS_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
 /// The maximum size of gossip messages.
 pub fn gossip_max_size(is_merge_enabled: bool, gossip_max_size: usize) -> usize {
     if is_merge_enabled {
@@ -83,6 +87,9 @@ pub struct Config {
     #[serde(skip)]
     pub gs_config: gossipsub::Config,
 
+    /// Overrides of the gossipsub peer score thresholds and topic weights.
+    pub gossipsub_score_overrides: GossipsubScoreOverrides,
+
     /// Discv5 configuration parameters.
     #[serde(skip)]
     pub discv5_config: Discv5Config,
@@ -99,6 +106,10 @@ pub struct Config {
     /// List of trusted libp2p nodes which are not scored.
     pub trusted_peers: Vec<PeerIdSerialized>,
 
+    /// Addresses of trusted peers, each ending with `/p2p/<peer id>`. These peers are dialed on
+    /// startup and redialed whenever they disconnect.
+    pub trusted_peer_addresses: Vec<Multiaddr>,
+
     /// Disables peer scoring altogether.
     pub disable_peer_scoring: bool,
 
@@ -111,8 +122,14 @@ pub struct Config {
     /// Disables quic support.
     pub disable_quic_support: bool,
 
-    /// Attempt to construct external port mappings with UPnP.
-    pub upnp_enabled: bool,
+    /// Disables persisting known peers to the database and dialing them on startup.
+    pub disable_peer_persistence: bool,
+
+    /// Persisted peers which were last seen longer ago than this are ignored on startup.
+    pub persisted_peers_max_age: Duration,
+
+    /// Attempt to construct external port mappings with UPnP or NAT-PMP.
+    pub nat_enabled: bool,
 
     /// Subscribe to all subnets for the duration of the runtime.
     pub subscribe_all_subnets: bool,
@@ -347,16 +364,20 @@ impl Default for Config {
             enr_tcp6_port: None,
             target_peers: 50,
             gs_config,
+            gossipsub_score_overrides: GossipsubScoreOverrides::default(),
             discv5_config,
             boot_nodes_enr: vec![],
             boot_nodes_multiaddr: vec![],
             libp2p_nodes: vec![],
             trusted_peers: vec![],
+            trusted_peer_addresses: vec![],
             disable_peer_scoring: false,
             client_version: lighthouse_version::version_with_platform(),
             disable_discovery: false,
             disable_quic_support: false,
-            upnp_enabled: true,
+            disable_peer_persistence: false,
+            persisted_peers_max_age: DEFAULT_PERSISTED_PEERS_MAX_AGE,
+            nat_enabled: true,
             network_load: 3,
             private: false,
             subscribe_all_subnets: false,
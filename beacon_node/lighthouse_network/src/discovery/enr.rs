@@ -15,7 +15,7 @@ use std::fs::File;
 use std::io::prelude::*;
 use std::path::Path;
 use std::str::FromStr;
-use types::{EnrForkId, EthSpec};
+use types::{ChainSpec, EnrForkId, Epoch, EthSpec, SubnetId};
 
 use super::enr_ext::{EnrExt, QUIC6_ENR_KEY, QUIC_ENR_KEY};
 
@@ -39,6 +39,14 @@ pub trait Eth2Enr {
     ) -> Result<EnrSyncCommitteeBitfield<TSpec>, &'static str>;
 
     fn eth2(&self) -> Result<EnrForkId, &'static str>;
+
+    /// The long-lived attestation subnets the node is expected to be subscribed to during
+    /// `epoch`, as computed deterministically from its node id.
+    fn long_lived_attestation_subnets<TSpec: EthSpec>(
+        &self,
+        epoch: Epoch,
+        spec: &ChainSpec,
+    ) -> Result<Vec<SubnetId>, &'static str>;
 }
 
 impl Eth2Enr for Enr {
@@ -69,6 +77,16 @@ impl Eth2Enr for Enr {
 
         EnrForkId::from_ssz_bytes(eth2_bytes).map_err(|_| "Could not decode EnrForkId")
     }
+
+    fn long_lived_attestation_subnets<TSpec: EthSpec>(
+        &self,
+        epoch: Epoch,
+        spec: &ChainSpec,
+    ) -> Result<Vec<SubnetId>, &'static str> {
+        let (subnets, _) =
+            SubnetId::compute_subnets_for_epoch::<TSpec>(self.node_id().raw().into(), epoch, spec)?;
+        Ok(subnets.collect())
+    }
 }
 
 /// Either use the given ENR or load an ENR from file if it exists and matches the current NodeId
@@ -231,7 +231,11 @@ impl<TSpec: EthSpec> Discovery<TSpec> {
                 "ip" => ?bootnode_enr.ip4(),
                 "udp" => ?bootnode_enr.udp4(),
                 "tcp" => ?bootnode_enr.tcp4(),
-                "quic" => ?bootnode_enr.quic4()
+                "quic" => ?bootnode_enr.quic4(),
+                "ip6" => ?bootnode_enr.ip6(),
+                "udp6" => ?bootnode_enr.udp6(),
+                "tcp6" => ?bootnode_enr.tcp6(),
+                "quic6" => ?bootnode_enr.quic6()
             );
             let repr = bootnode_enr.to_string();
             let _ = discv5.add_enr(bootnode_enr).map_err(|e| {
@@ -1080,6 +1084,7 @@ mod tests {
     use enr::EnrBuilder;
     use libp2p::identity::secp256k1;
     use slog::{o, Drain};
+    use std::net::{Ipv4Addr, Ipv6Addr};
     use types::{BitVector, MinimalEthSpec, SubnetId};
 
     type E = MinimalEthSpec;
@@ -1159,6 +1164,44 @@ mod tests {
         assert_eq!(discovery.queued_queries.len(), 0);
     }
 
+    #[test]
+    fn test_dual_stack_enr() {
+        let mut config = NetworkConfig::default();
+        config.set_ipv4_ipv6_listening_addresses(
+            Ipv4Addr::UNSPECIFIED,
+            9000,
+            9000,
+            9001,
+            Ipv6Addr::UNSPECIFIED,
+            9090,
+            9090,
+            9091,
+        );
+        let ip4 = Ipv4Addr::new(192, 0, 2, 1);
+        let ip6: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        config.enr_address = (Some(ip4), Some(ip6));
+        config.enr_udp4_port = Some(9000);
+        config.enr_udp6_port = Some(9090);
+
+        let keypair = secp256k1::Keypair::generate();
+        let enr_key: CombinedKey = CombinedKey::from_secp256k1(&keypair);
+        let enr: Enr = build_enr::<E>(&enr_key, &config, &EnrForkId::default()).unwrap();
+
+        assert_eq!(enr.ip4(), Some(ip4));
+        assert_eq!(enr.udp4(), Some(9000));
+        assert_eq!(enr.tcp4(), Some(9000));
+        assert_eq!(enr.quic4(), Some(9001));
+        assert_eq!(enr.ip6(), Some(ip6));
+        assert_eq!(enr.udp6(), Some(9090));
+        assert_eq!(enr.tcp6(), Some(9090));
+        assert_eq!(enr.quic6(), Some(9091));
+
+        // Peers can reach us over either address family.
+        assert_eq!(enr.multiaddr_p2p_tcp().len(), 2);
+        assert_eq!(enr.multiaddr_p2p_udp().len(), 2);
+        assert_eq!(enr.multiaddr_quic().len(), 2);
+    }
+
     fn make_enr(subnet_ids: Vec<usize>) -> Enr {
         let mut builder = EnrBuilder::new("v4");
         let keypair = secp256k1::Keypair::generate();
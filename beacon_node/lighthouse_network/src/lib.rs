@@ -33,6 +33,12 @@ impl From<PeerIdSerialized> for PeerId {
     }
 }
 
+impl From<PeerId> for PeerIdSerialized {
+    fn from(peer_id: PeerId) -> Self {
+        Self(peer_id)
+    }
+}
+
 impl FromStr for PeerIdSerialized {
     type Err = String;
 
@@ -122,11 +128,13 @@ pub use libp2p::{multiaddr, Multiaddr};
 pub use metrics::scrape_discovery_metrics;
 pub use peer_manager::{
     peerdb::client::Client,
+    peerdb::persisted_peer::PersistedPeer,
     peerdb::score::{PeerAction, ReportSource},
     peerdb::PeerDB,
     ConnectionDirection, PeerConnectionStatus, PeerInfo, PeerManager, SyncInfo, SyncStatus,
 };
 // pub use service::{load_private_key, Context, Libp2pEvent, Service, NETWORK_KEY_FILENAME};
 pub use service::api_types::{PeerRequestId, Request, Response};
+pub use service::gossipsub_scoring_parameters::GossipsubScoreOverrides;
 pub use service::utils::*;
-pub use service::{Gossipsub, NetworkEvent};
+pub use service::{Gossipsub, NetworkEvent, TARGET_SUBNET_PEERS};
@@ -32,6 +32,18 @@ lazy_static! {
         "libp2p_peer_disconnect_event_total",
         "Count of libp2p peer disconnect events"
     );
+    pub static ref PEER_CONNECT_EVENTS_PER_TRANSPORT: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "libp2p_peer_connect_events_per_transport_total",
+            "Count of libp2p peer connect events, per transport",
+            &["transport"]
+        );
+    pub static ref PEER_DISCONNECT_EVENTS_PER_TRANSPORT: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "libp2p_peer_disconnect_events_per_transport_total",
+            "Count of libp2p peer disconnect events, per transport",
+            &["transport"]
+        );
     pub static ref DISCOVERY_SENT_BYTES: Result<IntGauge> = try_create_int_gauge(
         "discovery_sent_bytes",
         "The number of bytes sent in discovery"
@@ -53,6 +65,15 @@ lazy_static! {
         "The number of active discovery sessions with peers"
     );
 
+    pub static ref SUBSCRIBED_SUBNET_PEERS: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "libp2p_subscribed_subnet_peers",
+        "The number of good peers on each subnet we are subscribed to",
+        &["kind", "subnet"]
+    );
+    pub static ref SUBSCRIBED_SUBNETS_BELOW_TARGET: Result<IntGauge> = try_create_int_gauge(
+        "libp2p_subscribed_subnets_below_target",
+        "The number of subscribed subnets with fewer good peers than the target subnet peer count"
+    );
     pub static ref PEERS_PER_CLIENT: Result<IntGaugeVec> = try_create_int_gauge_vec(
         "libp2p_peers_per_client",
         "The connected peers via client implementation",
@@ -79,6 +100,23 @@ lazy_static! {
         "RPC requests total",
         &["type"]
     );
+    pub static ref SELF_RATE_LIMITER_QUEUED_REQUESTS: Result<IntGaugeVec> =
+        try_create_int_gauge_vec(
+            "libp2p_rpc_self_limiter_queued_requests",
+            "Outbound RPC requests currently queued by the self rate limiter, per protocol",
+            &["protocol"]
+        );
+    pub static ref SELF_RATE_LIMITER_QUEUE_DELAY: Result<HistogramVec> = try_create_histogram_vec(
+        "libp2p_rpc_self_limiter_queue_delay_seconds",
+        "Time outbound RPC requests spent queued by the self rate limiter, per protocol",
+        &["protocol"]
+    );
+    pub static ref SELF_RATE_LIMITER_QUEUE_FULL_TOTAL: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "libp2p_rpc_self_limiter_queue_full_total",
+            "Outbound RPC requests rejected because the self rate limiter queue was full",
+            &["protocol"]
+        );
     pub static ref PEER_ACTION_EVENTS_PER_CLIENT: Result<IntCounterVec> =
         try_create_int_counter_vec(
             "libp2p_peer_actions_per_client",
@@ -109,6 +147,18 @@ lazy_static! {
             "Messages that failed to be published on retry to gossipsub per topic kind.",
             &["topic_kind"]
         );
+    pub static ref GOSSIPSUB_OWN_SCORE_MESH_PEERS: Result<IntGaugeVec> =
+        try_create_int_gauge_vec(
+            "gossipsub_own_score_mesh_peers",
+            "The number of peers which have us in their mesh per topic kind, which falls when peers score us poorly",
+            &["topic_kind"]
+        );
+    pub static ref GOSSIPSUB_OWN_SCORE_MESH_DEPARTURES: Result<IntCounterVec> =
+        try_create_int_counter_vec(
+            "gossipsub_own_score_mesh_departures_total",
+            "The number of times a connected peer left our mesh per topic kind, including peers pruning us for a poor score",
+            &["topic_kind"]
+        );
     pub static ref PEER_SCORE_DISTRIBUTION: Result<IntGaugeVec> =
         try_create_int_gauge_vec(
             "peer_score_distribution",
@@ -1,3 +1,5 @@
+use crate::Multiaddr;
+
 /// The time in seconds between re-status's peers.
 pub const DEFAULT_STATUS_INTERVAL: u64 = 300;
 
@@ -30,6 +32,11 @@ pub struct Config {
     pub ping_interval_inbound: u64,
     /// Interval between PING events for peers dialed by us.
     pub ping_interval_outbound: u64,
+
+    /* Trusted peer related configurations */
+    /// Addresses of trusted peers, each ending with the peer's `PeerId`. Trusted peers with a known
+    /// address are redialed whenever they are disconnected.
+    pub trusted_peer_addresses: Vec<Multiaddr>,
 }
 
 impl Default for Config {
@@ -41,6 +48,7 @@ impl Default for Config {
             status_interval: DEFAULT_STATUS_INTERVAL,
             ping_interval_inbound: DEFAULT_PING_INTERVAL_INBOUND,
             ping_interval_outbound: DEFAULT_PING_INTERVAL_OUTBOUND,
+            trusted_peer_addresses: vec![],
         }
     }
 }
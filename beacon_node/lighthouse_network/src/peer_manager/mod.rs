@@ -4,19 +4,21 @@ use crate::discovery::enr_ext::EnrExt;
 use crate::rpc::{GoodbyeReason, MetaData, Protocol, RPCError, RPCResponseErrorCode};
 use crate::service::TARGET_SUBNET_PEERS;
 use crate::{error, metrics, Gossipsub};
-use crate::{NetworkGlobals, PeerId};
+use crate::{NetworkGlobals, PeerId, TopicHash};
 use crate::{Subnet, SubnetDiscovery};
 use delay_map::HashSetDelay;
 use discv5::Enr;
 use libp2p::identify::Info as IdentifyInfo;
 use lru_cache::LRUTimeCache;
-use peerdb::{client::ClientKind, BanOperation, BanResult, ScoreUpdateResult};
+use peerdb::{
+    client::ClientKind, persisted_peer::PersistedPeer, BanOperation, BanResult, ScoreUpdateResult,
+};
 use rand::seq::SliceRandom;
 use slog::{debug, error, trace, warn};
 use smallvec::SmallVec;
 use std::{
     sync::Arc,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use strum::IntoEnumIterator;
 use types::{EthSpec, SyncSubnetId};
@@ -28,7 +30,8 @@ pub use libp2p::identity::Keypair;
 pub mod peerdb;
 
 pub use peerdb::peer_info::{
-    ConnectionDirection, PeerConnectionStatus, PeerConnectionStatus::*, PeerInfo,
+    ConnectionDirection, GossipTopicEvents, GossipTopicScore, PeerConnectionStatus,
+    PeerConnectionStatus::*, PeerInfo,
 };
 use peerdb::score::{PeerAction, ReportSource};
 pub use peerdb::sync_status::{SyncInfo, SyncStatus};
@@ -48,6 +51,9 @@ pub const PEER_RECONNECTION_TIMEOUT: Duration = Duration::from_secs(600);
 /// lower our peer count below this number. Instead we favour a non-uniform distribution of subnet
 /// peers.
 pub const MIN_SYNC_COMMITTEE_PEERS: u64 = 2;
+/// Peers on a subscribed attestation subnet are protected from pruning while we have this many or
+/// fewer peers on the subnet.
+pub const MIN_SUBSCRIBED_SUBNET_PEERS: usize = 1;
 /// A fraction of `PeerManager::target_peers` that we allow to connect to us in excess of
 /// `PeerManager::target_peers`. For clarity, if `PeerManager::target_peers` is 50 and
 /// PEER_EXCESS_FACTOR = 0.1 we allow 10% more nodes, i.e 55.
@@ -62,6 +68,9 @@ pub const MIN_OUTBOUND_ONLY_FACTOR: f32 = 0.2;
 /// limit is 55, and we are at 55 peers, the following parameter provisions a few more slots of
 /// dialing priority peers we need for validator duties.
 pub const PRIORITY_PEER_EXCESS: f32 = 0.2;
+/// The minimum score a peer must have to be persisted across restarts. Peers which have been
+/// penalised are not redialed after a restart.
+pub const MIN_PERSISTED_PEER_SCORE: f64 = 0.0;
 
 /// The main struct that handles peer's reputation and connection status.
 pub struct PeerManager<TSpec: EthSpec> {
@@ -79,6 +88,10 @@ pub struct PeerManager<TSpec: EthSpec> {
     target_peers: usize,
     /// Peers queued to be dialed.
     peers_to_dial: Vec<Enr>,
+    /// The known addresses of trusted peers, used to redial them when they disconnect.
+    trusted_peer_addresses: HashMap<PeerId, Vec<Multiaddr>>,
+    /// Trusted peers queued to be dialed.
+    trusted_peers_to_dial: Vec<PeerId>,
     /// The number of temporarily banned peers. This is used to prevent instantaneous
     /// reconnection.
     // NOTE: This just prevents re-connections. The state of the peer is otherwise unaffected. A
@@ -149,8 +162,24 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             status_interval,
             ping_interval_inbound,
             ping_interval_outbound,
+            trusted_peer_addresses,
         } = cfg;
 
+        let mut trusted_peer_addresses_by_id: HashMap<PeerId, Vec<Multiaddr>> = HashMap::new();
+        for address in trusted_peer_addresses {
+            match address.iter().last() {
+                Some(libp2p::multiaddr::Protocol::P2p(peer_id)) => trusted_peer_addresses_by_id
+                    .entry(peer_id)
+                    .or_default()
+                    .push(address),
+                _ => {
+                    warn!(log, "Ignoring trusted peer address without a peer id"; "address" => %address)
+                }
+            }
+        }
+        // Dial all trusted peers with a known address on startup.
+        let trusted_peers_to_dial = trusted_peer_addresses_by_id.keys().copied().collect();
+
         // Set up the peer manager heartbeat interval
         let heartbeat = tokio::time::interval(tokio::time::Duration::from_secs(HEARTBEAT_INTERVAL));
 
@@ -158,6 +187,8 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             network_globals,
             events: SmallVec::new(),
             peers_to_dial: Default::default(),
+            trusted_peer_addresses: trusted_peer_addresses_by_id,
+            trusted_peers_to_dial,
             inbound_ping_peers: HashSetDelay::new(Duration::from_secs(ping_interval_inbound)),
             outbound_ping_peers: HashSetDelay::new(Duration::from_secs(ping_interval_outbound)),
             status_peers: HashSetDelay::new(Duration::from_secs(status_interval)),
@@ -181,7 +212,7 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
     pub fn goodbye_peer(&mut self, peer_id: &PeerId, reason: GoodbyeReason, source: ReportSource) {
         // Update the sync status if required
         if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
-            debug!(self.log, "Sending goodbye to peer"; "peer_id" => %peer_id, "reason" => %reason, "score" => %info.score());
+            debug!(self.log, "Sending goodbye to peer"; "peer_id" => %peer_id, "reason" => %reason, "score" => %info.score(), "client" => %info.client());
             if matches!(reason, GoodbyeReason::IrrelevantNetwork) {
                 info.update_sync_status(SyncStatus::IrrelevantPeer);
             }
@@ -412,6 +443,64 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         }
     }
 
+    /// Returns the known peers that should be persisted across a restart.
+    pub fn persisted_peers(&self) -> Vec<PersistedPeer> {
+        self.network_globals
+            .peers
+            .read()
+            .persisted_peers(MIN_PERSISTED_PEER_SCORE)
+    }
+
+    /// Restores peers that were persisted before a restart, ignoring those last seen longer than
+    /// `max_age` ago.
+    ///
+    /// Banned peers are banned again. The most recently seen of the remaining peers with a known
+    /// ENR are queued to be dialed, up to the target number of peers, and their ENRs are returned
+    /// so that they can also be added to discovery.
+    pub fn restore_persisted_peers(
+        &mut self,
+        mut peers: Vec<PersistedPeer>,
+        max_age: Duration,
+    ) -> Vec<Enr> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        // Dial the most recently seen peers first.
+        peers.sort_by_key(|peer| std::cmp::Reverse(peer.last_seen));
+
+        let mut enrs = vec![];
+        let mut banned = 0;
+        for peer in peers {
+            if now.saturating_sub(peer.last_seen) > max_age.as_secs() {
+                continue;
+            }
+            let peer_id = match peer.peer_id() {
+                Some(peer_id) => peer_id,
+                None => continue,
+            };
+            let ban_operation = self
+                .network_globals
+                .peers
+                .write()
+                .restore_peer(&peer_id, &peer);
+            if let Some(ban_operation) = ban_operation {
+                self.handle_ban_operation(&peer_id, ban_operation, None);
+                banned += 1;
+            } else if !peer.banned {
+                if let Some(enr) = peer.enr() {
+                    if enrs.len() < self.target_peers {
+                        self.dial_peer(enr.clone());
+                    }
+                    enrs.push(enr);
+                }
+            }
+        }
+
+        debug!(self.log, "Restored persisted peers"; "peers" => enrs.len(), "banned" => banned);
+        enrs
+    }
+
     /// Reports if a peer is banned or not.
     ///
     /// This is used to determine if we should accept incoming connections.
@@ -585,6 +674,7 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             },
             RPCError::NegotiationTimeout => PeerAction::LowToleranceError,
             RPCError::Disconnected => return, // No penalty for a graceful disconnection
+            RPCError::SelfRateLimitQueueFull => return, // The request was never sent
         };
 
         self.report_peer(
@@ -682,13 +772,19 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         }
     }
 
-    /// Updates the gossipsub scores for all known peers in gossipsub.
-    pub(crate) fn update_gossipsub_scores(&mut self, gossipsub: &Gossipsub) {
-        let actions = self
-            .network_globals
-            .peers
-            .write()
-            .update_gossipsub_scores(self.target_peers, gossipsub);
+    /// Updates the gossipsub scores for all known peers in gossipsub, along with their per-topic
+    /// scoring components given the messages observed since the last update.
+    pub(crate) fn update_gossipsub_scores(
+        &mut self,
+        gossipsub: &Gossipsub,
+        topic_events: HashMap<PeerId, HashMap<TopicHash, GossipTopicEvents>>,
+        topic_score_cap: f64,
+    ) {
+        let actions = {
+            let mut peers = self.network_globals.peers.write();
+            peers.update_gossip_topic_scores(gossipsub, topic_events, topic_score_cap);
+            peers.update_gossipsub_scores(self.target_peers, gossipsub)
+        };
 
         for (peer_id, score_action) in actions {
             self.handle_score_action(&peer_id, score_action, None);
@@ -888,6 +984,65 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         }
     }
 
+    /// Run discovery queries for the attestation subnets we are subscribed to which have fewer than
+    /// `TARGET_SUBNET_PEERS`, and protect the peers on our scarcest subnets from pruning until the
+    /// next heartbeat.
+    ///
+    /// Sync committee subnets are maintained by `Self::maintain_sync_committee_peers`.
+    fn maintain_subscribed_subnet_peers(&mut self) {
+        let subnet_peer_counts = self.network_globals.subscribed_subnet_peer_counts();
+
+        if self.metrics_enabled {
+            let _ = metrics::SUBSCRIBED_SUBNET_PEERS
+                .as_ref()
+                .map(|gauge| gauge.reset());
+            for (subnet, count) in &subnet_peer_counts {
+                metrics::set_gauge_vec(
+                    &metrics::SUBSCRIBED_SUBNET_PEERS,
+                    &[subnet.kind(), &subnet.id().to_string()],
+                    *count as i64,
+                );
+            }
+            metrics::set_gauge(
+                &metrics::SUBSCRIBED_SUBNETS_BELOW_TARGET,
+                subnet_peer_counts
+                    .values()
+                    .filter(|count| **count < TARGET_SUBNET_PEERS)
+                    .count() as i64,
+            );
+        }
+
+        let protect_until = Instant::now() + Duration::from_secs(HEARTBEAT_INTERVAL);
+        let mut subnets_to_discover = vec![];
+        for (subnet, count) in subnet_peer_counts {
+            if !matches!(subnet, Subnet::Attestation(_)) {
+                continue;
+            }
+            if count <= MIN_SUBSCRIBED_SUBNET_PEERS {
+                self.network_globals
+                    .peers
+                    .write()
+                    .extend_peers_on_subnet(&subnet, protect_until);
+            }
+            if count < TARGET_SUBNET_PEERS {
+                subnets_to_discover.push(SubnetDiscovery {
+                    subnet,
+                    min_ttl: None,
+                });
+            }
+        }
+
+        if !subnets_to_discover.is_empty() {
+            debug!(
+                self.log,
+                "Making subnet queries for maintaining subscribed subnet peers";
+                "subnets" => ?subnets_to_discover.iter().map(|s| s.subnet).collect::<Vec<_>>()
+            );
+            self.events
+                .push(PeerManagerEvent::DiscoverSubnetPeers(subnets_to_discover));
+        }
+    }
+
     /// This function checks the status of our current peers and optionally requests a discovery
     /// query if we need to find more peers to maintain the current number of peers
     fn maintain_peer_count(&mut self, dialing_peers: usize) {
@@ -1197,6 +1352,12 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         // Maintain minimum count for sync committee peers.
         self.maintain_sync_committee_peers();
 
+        // Maintain peers on the attestation subnets we are subscribed to.
+        self.maintain_subscribed_subnet_peers();
+
+        // Redial any trusted peers that we are not connected to.
+        self.maintain_trusted_peers();
+
         // Prune any excess peers back to our target in such a way that incentivises good scores and
         // a uniform distribution of subnets.
         self.prune_excess_peers();
@@ -1208,6 +1369,20 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         self.shrink_mappings();
     }
 
+    /// Queues a dial to each trusted peer with a known address that is neither connected nor
+    /// being dialed.
+    fn maintain_trusted_peers(&mut self) {
+        let peers = self.network_globals.peers.read();
+        for peer_id in self.trusted_peer_addresses.keys() {
+            if !peers.is_connected_or_dialing(peer_id)
+                && !self.trusted_peers_to_dial.contains(peer_id)
+            {
+                debug!(self.log, "Redialing trusted peer"; "peer_id" => %peer_id);
+                self.trusted_peers_to_dial.push(*peer_id);
+            }
+        }
+    }
+
     // Reduce memory footprint by routinely shrinking associating mappings.
     fn shrink_mappings(&mut self) {
         self.inbound_ping_peers.shrink_to(5);
@@ -1792,6 +1967,178 @@ mod tests {
         }
     }
 
+    /// Test that subscribed attestation subnets with too few peers trigger discovery queries, and
+    /// that the only peer on a subscribed subnet is protected from pruning.
+    #[tokio::test]
+    async fn test_peer_manager_discovers_peers_on_deficient_subnets() {
+        let mut peer_manager = build_peer_manager(10).await;
+
+        // Subscribe to subnets 1, 2 and 3.
+        for subnet in 1..=3u64 {
+            peer_manager
+                .network_globals
+                .gossipsub_subscriptions
+                .write()
+                .insert(crate::types::GossipTopic::new(
+                    crate::types::GossipKind::Attestation(subnet.into()),
+                    Default::default(),
+                    [0; 4],
+                ));
+        }
+
+        // Subnet 1 has the target number of peers, subnet 2 has a single peer and subnet 3 has
+        // none.
+        let mut peers = vec![];
+        for i in 0..=TARGET_SUBNET_PEERS {
+            let subnet: u64 = if i < TARGET_SUBNET_PEERS { 1 } else { 2 };
+            let peer = PeerId::random();
+            peer_manager.inject_connect_ingoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+
+            let mut attnets = crate::types::EnrAttestationBitfield::<E>::new();
+            attnets.set(subnet as usize, true).unwrap();
+            let metadata = crate::rpc::MetaDataV2 {
+                seq_number: 0,
+                attnets,
+                syncnets: Default::default(),
+            };
+            let mut peer_db = peer_manager.network_globals.peers.write();
+            peer_db
+                .peer_info_mut(&peer)
+                .unwrap()
+                .set_meta_data(MetaData::V2(metadata));
+            peer_db.add_subscription(&peer, Subnet::Attestation(subnet.into()));
+            peers.push(peer);
+        }
+        peer_manager.events.clear();
+
+        peer_manager.heartbeat();
+
+        let mut discovered_subnets = peer_manager
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                PeerManagerEvent::DiscoverSubnetPeers(subnets) => Some(subnets),
+                _ => None,
+            })
+            .flatten()
+            .map(|discovery| discovery.subnet.id())
+            .collect::<Vec<_>>();
+        discovered_subnets.sort_unstable();
+        assert_eq!(discovered_subnets, vec![2, 3]);
+
+        // The only peer on subnet 2 is protected, the peers on subnet 1 are not.
+        let peer_db = peer_manager.network_globals.peers.read();
+        let scarce_peer = peers.last().unwrap();
+        assert!(peer_db.peer_info(scarce_peer).unwrap().has_future_duty());
+        assert!(peers[..TARGET_SUBNET_PEERS]
+            .iter()
+            .all(|peer| !peer_db.peer_info(peer).unwrap().has_future_duty()));
+    }
+
+    #[tokio::test]
+    async fn test_peer_manager_redials_trusted_peers() {
+        let trusted_peer = PeerId::random();
+        let address: Multiaddr = format!("/ip4/127.0.0.1/tcp/9000/p2p/{}", trusted_peer)
+            .parse()
+            .unwrap();
+        let config = config::Config {
+            target_peer_count: 3,
+            discovery_enabled: false,
+            trusted_peer_addresses: vec![
+                address.clone(),
+                "/ip4/127.0.0.1/tcp/9001".parse().unwrap(),
+            ],
+            ..Default::default()
+        };
+        let log = build_log(slog::Level::Debug, false);
+        let globals = NetworkGlobals::new_test_globals(vec![trusted_peer], &log);
+        let mut peer_manager = PeerManager::new(config, Arc::new(globals), &log).unwrap();
+
+        // Trusted peers with a known address are dialed on startup, and addresses without a peer
+        // id are ignored.
+        assert_eq!(peer_manager.trusted_peers_to_dial, vec![trusted_peer]);
+        assert_eq!(
+            peer_manager.trusted_peer_addresses.get(&trusted_peer),
+            Some(&vec![address.clone()])
+        );
+
+        // A connected trusted peer is not redialed.
+        peer_manager.trusted_peers_to_dial.clear();
+        peer_manager.inject_connect_outgoing(&trusted_peer, address, None);
+        peer_manager.heartbeat();
+        assert!(peer_manager.trusted_peers_to_dial.is_empty());
+
+        // Once disconnected, the heartbeat queues the trusted peer to be redialed.
+        peer_manager.inject_disconnect(&trusted_peer);
+        peer_manager.heartbeat();
+        assert_eq!(peer_manager.trusted_peers_to_dial, vec![trusted_peer]);
+    }
+
+    #[tokio::test]
+    async fn test_peer_manager_restores_persisted_peers() {
+        use crate::CombinedKeyExt;
+        let mut peer_manager = build_peer_manager(3).await;
+
+        let build_enr = || {
+            let keypair = libp2p::identity::secp256k1::Keypair::generate();
+            let enr_key = discv5::enr::CombinedKey::from_secp256k1(&keypair);
+            discv5::enr::EnrBuilder::new("v4").build(&enr_key).unwrap()
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let address: Multiaddr = "/ip4/1.2.3.4/tcp/9000".parse().unwrap();
+        let recent_enr = build_enr();
+        let stale_enr = build_enr();
+        let banned_peer = PeerId::random();
+        let persisted_peers = vec![
+            PersistedPeer::new(
+                &recent_enr.peer_id(),
+                Some(&recent_enr),
+                std::iter::once(&address),
+                None,
+                now - 60,
+                false,
+            ),
+            PersistedPeer::new(
+                &stale_enr.peer_id(),
+                Some(&stale_enr),
+                std::iter::once(&address),
+                None,
+                now - 7200,
+                false,
+            ),
+            PersistedPeer::new(
+                &banned_peer,
+                None,
+                std::iter::once(&address),
+                None,
+                now - 60,
+                true,
+            ),
+        ];
+
+        let enrs = peer_manager.restore_persisted_peers(persisted_peers, Duration::from_secs(3600));
+
+        // Only the recently seen peer is dialed, the stale peer is ignored.
+        assert_eq!(enrs, vec![recent_enr.clone()]);
+        assert_eq!(peer_manager.peers_to_dial, vec![recent_enr]);
+        assert!(peer_manager
+            .network_globals
+            .peers
+            .read()
+            .peer_info(&stale_enr.peer_id())
+            .is_none());
+
+        // The banned peer remains banned.
+        assert!(peer_manager.ban_status(&banned_peer).is_banned());
+        assert!(peer_manager.events.iter().any(|event| matches!(
+            event,
+            PeerManagerEvent::Banned(peer_id, _) if *peer_id == banned_peer
+        )));
+    }
+
     /// Test the pruning logic to prioritise peers with the most subnets
     ///
     /// Create 6 peers.
@@ -117,6 +117,19 @@ impl<TSpec: EthSpec> NetworkBehaviour for PeerManager<TSpec> {
             });
         }
 
+        if let Some(peer_id) = self.trusted_peers_to_dial.pop() {
+            if let Some(addresses) = self.trusted_peer_addresses.get(&peer_id).cloned() {
+                debug!(self.log, "Dialing trusted peer"; "peer_id" => %peer_id, "addresses" => ?addresses);
+                self.inject_peer_connection(&peer_id, ConnectingType::Dialing, None);
+                return Poll::Ready(ToSwarm::Dial {
+                    opts: DialOpts::peer_id(peer_id)
+                        .condition(PeerCondition::Disconnected)
+                        .addresses(addresses)
+                        .build(),
+                });
+            }
+        }
+
         Poll::Pending
     }
 
@@ -227,9 +240,14 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             }) {
                 Some(multiaddr::Protocol::QuicV1) => {
                     metrics::inc_gauge(&metrics::QUIC_PEERS_CONNECTED);
+                    metrics::inc_counter_vec(
+                        &metrics::PEER_CONNECT_EVENTS_PER_TRANSPORT,
+                        &["quic"],
+                    );
                 }
                 Some(multiaddr::Protocol::Tcp(_)) => {
                     metrics::inc_gauge(&metrics::TCP_PEERS_CONNECTED);
+                    metrics::inc_counter_vec(&metrics::PEER_CONNECT_EVENTS_PER_TRANSPORT, &["tcp"]);
                 }
                 Some(_) => unreachable!(),
                 None => {
@@ -326,6 +344,13 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
         // reference so that peer manager can track this peer.
         self.inject_disconnect(&peer_id);
 
+        // Redial trusted peers immediately rather than waiting for the next heartbeat.
+        if self.trusted_peer_addresses.contains_key(&peer_id)
+            && !self.trusted_peers_to_dial.contains(&peer_id)
+        {
+            self.trusted_peers_to_dial.push(peer_id);
+        }
+
         let remote_addr = match endpoint {
             ConnectedPoint::Listener { send_back_addr, .. } => send_back_addr,
             ConnectedPoint::Dialer { address, .. } => address,
@@ -341,9 +366,17 @@ impl<TSpec: EthSpec> PeerManager<TSpec> {
             }) {
                 Some(multiaddr::Protocol::QuicV1) => {
                     metrics::dec_gauge(&metrics::QUIC_PEERS_CONNECTED);
+                    metrics::inc_counter_vec(
+                        &metrics::PEER_DISCONNECT_EVENTS_PER_TRANSPORT,
+                        &["quic"],
+                    );
                 }
                 Some(multiaddr::Protocol::Tcp(_)) => {
                     metrics::dec_gauge(&metrics::TCP_PEERS_CONNECTED);
+                    metrics::inc_counter_vec(
+                        &metrics::PEER_DISCONNECT_EVENTS_PER_TRANSPORT,
+                        &["tcp"],
+                    );
                 }
                 // If it's an unknown protocol we already logged when connection was established.
                 _ => {}
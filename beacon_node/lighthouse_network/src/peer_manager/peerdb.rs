@@ -1,17 +1,23 @@
-use crate::{metrics, multiaddr::Multiaddr, types::Subnet, Enr, Gossipsub, PeerId};
-use peer_info::{ConnectionDirection, PeerConnectionStatus, PeerInfo};
+use crate::{
+    metrics, multiaddr::Multiaddr, types::Subnet, Enr, GossipTopic, Gossipsub, PeerId, TopicHash,
+};
+use client::Client;
+use libp2p::gossipsub::IdentTopic as Topic;
+use peer_info::{ConnectionDirection, GossipTopicEvents, PeerConnectionStatus, PeerInfo};
+use persisted_peer::PersistedPeer;
 use rand::seq::SliceRandom;
 use score::{PeerAction, ReportSource, Score, ScoreState};
 use slog::{crit, debug, error, trace, warn};
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
 use std::net::IpAddr;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use sync_status::SyncStatus;
 use types::EthSpec;
 
 pub mod client;
 pub mod peer_info;
+pub mod persisted_peer;
 pub mod score;
 pub mod sync_status;
 
@@ -321,6 +327,49 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
             .map(|info| info.connection_status().clone())
     }
 
+    /// Returns the peers worth remembering across a restart.
+    ///
+    /// These are banned peers, so that their bans are retained, and peers we have connected to
+    /// whose score is at least `min_score`. Trusted peers are excluded as they are always re-added
+    /// from the configuration.
+    pub fn persisted_peers(&self, min_score: f64) -> Vec<PersistedPeer> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        self.peers
+            .iter()
+            .filter(|(_, info)| !info.is_trusted())
+            .filter_map(|(peer_id, info)| {
+                let banned = info.is_banned();
+                if !banned
+                    && (info.score().score() < min_score || info.seen_multiaddrs().next().is_none())
+                {
+                    return None;
+                }
+                let last_seen = match info.connection_status() {
+                    PeerConnectionStatus::Connected { .. }
+                    | PeerConnectionStatus::Disconnecting { .. } => now,
+                    PeerConnectionStatus::Disconnected { since }
+                    | PeerConnectionStatus::Banned { since } => {
+                        now.saturating_sub(since.elapsed().as_secs())
+                    }
+                    PeerConnectionStatus::Dialing { .. } | PeerConnectionStatus::Unknown => {
+                        return None
+                    }
+                };
+                Some(PersistedPeer::new(
+                    peer_id,
+                    info.enr(),
+                    info.seen_multiaddrs(),
+                    info.client().agent_string.as_deref(),
+                    last_seen,
+                    banned,
+                ))
+            })
+            .collect()
+    }
+
     /* Mutability */
 
     /// Cleans up the connection state of dialing peers.
@@ -415,6 +464,80 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         result
     }
 
+    /// Updates the gossipsub scoring components of each connected peer on the topics we are
+    /// subscribed to, applying the messages observed since the last update, and estimates each
+    /// peer's behavioural penalty from the remainder of its gossipsub score.
+    pub(super) fn update_gossip_topic_scores(
+        &mut self,
+        gossipsub: &Gossipsub,
+        mut topic_events: HashMap<PeerId, HashMap<TopicHash, GossipTopicEvents>>,
+        topic_score_cap: f64,
+    ) {
+        let now = Instant::now();
+        let topics: Vec<_> = gossipsub
+            .topics()
+            .filter_map(|topic_hash| {
+                let topic = GossipTopic::decode(topic_hash.as_str()).ok()?;
+                let params = gossipsub.get_topic_params(&Topic::new(topic_hash.as_str()))?;
+                let mesh_peers: HashSet<&PeerId> = gossipsub.mesh_peers(topic_hash).collect();
+                Some((topic_hash, topic.kind().clone(), params, mesh_peers))
+            })
+            .collect();
+
+        // Peers keep only well-scored peers in their mesh (and pruning is mutual), so the size of
+        // our mesh is the best indication we have of our own score.
+        let mut mesh_peers_per_kind: HashMap<&str, usize> = HashMap::new();
+        for (_, kind, _, mesh_peers) in &topics {
+            *mesh_peers_per_kind.entry(kind.as_ref()).or_default() += mesh_peers.len();
+        }
+        for (kind, mesh_peers) in mesh_peers_per_kind {
+            metrics::set_gauge_vec(
+                &metrics::GOSSIPSUB_OWN_SCORE_MESH_PEERS,
+                &[kind],
+                mesh_peers as i64,
+            );
+        }
+
+        for (peer_id, info) in self.peers.iter_mut() {
+            if !info.is_connected() {
+                continue;
+            }
+            let mut events = topic_events.remove(peer_id).unwrap_or_default();
+
+            let mut topic_score = 0.0;
+            for (topic_hash, kind, params, mesh_peers) in &topics {
+                let kind_name = kind.to_string();
+                let in_mesh = mesh_peers.contains(&peer_id);
+                let topic_events = events.remove(*topic_hash);
+                let previous = info.gossip_topic_scores().get(&kind_name);
+                let was_in_mesh = previous.map_or(false, |score| score.in_mesh());
+                if !in_mesh && topic_events.is_none() && previous.is_none() {
+                    continue;
+                }
+                if was_in_mesh && !in_mesh {
+                    metrics::inc_counter_vec(
+                        &metrics::GOSSIPSUB_OWN_SCORE_MESH_DEPARTURES,
+                        &[kind.as_ref()],
+                    );
+                }
+                topic_score += info.update_gossip_topic_score(
+                    kind_name,
+                    params,
+                    in_mesh,
+                    topic_events.unwrap_or_default(),
+                    now,
+                );
+            }
+
+            if topic_score_cap > 0.0 {
+                topic_score = topic_score.min(topic_score_cap);
+            }
+            if let Some(score) = gossipsub.peer_score(peer_id) {
+                info.set_gossip_behaviour_penalty((score - topic_score).min(0.0));
+            }
+        }
+    }
+
     /// Updates gossipsub scores for all peers.
     #[must_use = "Score updates need to be reported to libp2p"]
     pub(super) fn update_gossipsub_scores(
@@ -537,7 +660,8 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
                         "Peer score adjusted";
                         "msg" => %msg,
                         "peer_id" => %peer_id,
-                        "score" => %info.score()
+                        "score" => %info.score(),
+                        "client" => %info.client()
                     );
                 }
                 match result {
@@ -640,6 +764,60 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
         self.update_connection_state(peer_id, NewConnectionState::Dialing { enr });
     }
 
+    /// Adds a peer that was persisted before a restart as a disconnected peer. Peers which are
+    /// already known are left untouched.
+    ///
+    /// A peer that was banned is banned again, in which case the resulting `BanOperation` needs
+    /// to be handled by the peer manager.
+    #[must_use = "Banned peers need to be handled in libp2p"]
+    pub(super) fn restore_peer(
+        &mut self,
+        peer_id: &PeerId,
+        peer: &PersistedPeer,
+    ) -> Option<BanOperation> {
+        if self.peers.contains_key(peer_id) {
+            return None;
+        }
+
+        let mut info = if self.disable_peer_scoring {
+            PeerInfo::trusted_peer_info()
+        } else {
+            PeerInfo::default()
+        };
+        if let Some(enr) = peer.enr() {
+            info.set_enr(enr);
+        }
+        if let Some(agent_string) = peer.agent_string() {
+            info.set_client(Client::from_agent_string(agent_string));
+        }
+        for multiaddr in peer.multiaddrs() {
+            info.insert_seen_multiaddr(multiaddr);
+        }
+        self.peers.insert(*peer_id, info);
+        self.update_connection_state(peer_id, NewConnectionState::Disconnected);
+
+        // Keep the time the peer was last seen so that stale peers eventually age out.
+        let age = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default()
+            .saturating_sub(peer.last_seen);
+        if let (Some(info), Some(since)) = (
+            self.peers.get_mut(peer_id),
+            Instant::now().checked_sub(Duration::from_secs(age)),
+        ) {
+            info.set_connection_status(PeerConnectionStatus::Disconnected { since });
+        }
+
+        if peer.banned && !self.disable_peer_scoring {
+            if let Some(info) = self.peers.get_mut(peer_id) {
+                info.apply_peer_action_to_score(PeerAction::Fatal);
+            }
+            return self.update_connection_state(peer_id, NewConnectionState::Banned);
+        }
+        None
+    }
+
     /// Sets a peer as connected with an ingoing connection.
     // VISIBILITY: Only the peer manager can adjust the connection state.
     pub(super) fn connect_ingoing(
@@ -1068,11 +1246,11 @@ impl<TSpec: EthSpec> PeerDB<TSpec> {
     ) -> ScoreTransitionResult {
         match (info.score_state(), previous_state) {
             (ScoreState::Banned, ScoreState::Healthy | ScoreState::Disconnected) => {
-                debug!(log, "Peer has been banned"; "peer_id" => %peer_id, "score" => %info.score());
+                debug!(log, "Peer has been banned"; "peer_id" => %peer_id, "score" => %info.score(), "client" => %info.client());
                 ScoreTransitionResult::Banned
             }
             (ScoreState::Disconnected, ScoreState::Banned | ScoreState::Healthy) => {
-                debug!(log, "Peer transitioned to disconnect state"; "peer_id" => %peer_id, "score" => %info.score(), "past_state" => %previous_state);
+                debug!(log, "Peer transitioned to disconnect state"; "peer_id" => %peer_id, "score" => %info.score(), "past_state" => %previous_state, "client" => %info.client());
                 // disconnect the peer if it's currently connected or dialing
                 if info.is_connected_or_dialing() {
                     ScoreTransitionResult::Disconnected
@@ -2027,4 +2205,59 @@ mod tests {
             Score::max_score().score()
         );
     }
+
+    #[test]
+    fn test_persisted_peers_restored() {
+        let mut pdb = get_db();
+
+        let good_peer = connect_peer_with_ips(&mut pdb, vec![Ipv4Addr::new(1, 2, 3, 4).into()]);
+        let bad_peer = connect_peer_with_ips(&mut pdb, vec![Ipv4Addr::new(5, 6, 7, 8).into()]);
+        let banned_peer = connect_peer_with_ips(&mut pdb, vec![Ipv4Addr::new(9, 9, 9, 9).into()]);
+        // A peer that was only dialed is not worth persisting.
+        let dialed_peer = PeerId::random();
+        pdb.dialing_peer(&dialed_peer, None);
+
+        add_score(&mut pdb, &bad_peer, -10.0);
+        let _ = pdb.report_peer(
+            &banned_peer,
+            PeerAction::Fatal,
+            ReportSource::PeerManager,
+            "",
+        );
+        pdb.inject_disconnect(&banned_peer);
+
+        let persisted = pdb.persisted_peers(0.0);
+        assert_eq!(persisted.len(), 2);
+
+        let mut restored_pdb = get_db();
+        let mut ban_operations = vec![];
+        for peer in &persisted {
+            let peer_id = peer.peer_id().unwrap();
+            assert!(peer_id == good_peer || peer_id == banned_peer);
+            if let Some(ban_operation) = restored_pdb.restore_peer(&peer_id, peer) {
+                ban_operations.push((peer_id, ban_operation));
+            }
+        }
+
+        // The good peer is known but disconnected, so it may be dialed.
+        assert!(restored_pdb.should_dial(&good_peer));
+        assert_eq!(
+            restored_pdb
+                .peer_info(&good_peer)
+                .unwrap()
+                .seen_ip_addresses()
+                .collect::<Vec<_>>(),
+            vec![IpAddr::from(Ipv4Addr::new(1, 2, 3, 4))]
+        );
+
+        // The banned peer remains banned.
+        assert!(matches!(
+            restored_pdb.ban_status(&banned_peer),
+            BanResult::BadScore
+        ));
+        assert!(matches!(
+            ban_operations.as_slice(),
+            [(peer_id, BanOperation::ReadyToBan(_))] if *peer_id == banned_peer
+        ));
+    }
 }
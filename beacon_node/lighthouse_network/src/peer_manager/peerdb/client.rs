@@ -62,6 +62,19 @@ impl Client {
             agent_string: Some(info.agent_version.clone()),
         }
     }
+
+    /// Builds a `Client` from an identify agent string seen previously, e.g. before a restart.
+    pub fn from_agent_string(agent_string: String) -> Self {
+        let (kind, version, os_version) = client_from_agent_version(&agent_string);
+
+        Client {
+            kind,
+            version,
+            os_version,
+            agent_string: Some(agent_string),
+            ..Default::default()
+        }
+    }
 }
 
 impl std::fmt::Display for Client {
@@ -142,18 +155,14 @@ fn client_from_agent_version(agent_version: &str) -> (ClientKind, String, String
             (kind, unknown.clone(), unknown)
         }
         Some("Prysm") => {
+            // Prysm agent strings are of the form `Prysm/<version>/<commit>` and do not include
+            // the OS.
             let kind = ClientKind::Prysm;
-            let mut version = String::from("unknown");
-            let mut os_version = version.clone();
-            if agent_split.next().is_some() {
-                if let Some(agent_version) = agent_split.next() {
-                    version = agent_version.into();
-                    if let Some(agent_os_version) = agent_split.next() {
-                        os_version = agent_os_version.into();
-                    }
-                }
-            }
-            (kind, version, os_version)
+            let version = agent_split
+                .next()
+                .map(String::from)
+                .unwrap_or_else(|| String::from("unknown"));
+            (kind, version, String::from("unknown"))
         }
         Some("nimbus") => {
             let kind = ClientKind::Nimbus;
@@ -199,3 +208,66 @@ fn client_from_agent_version(agent_version: &str) -> (ClientKind, String, String
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_client(agent_string: &str, kind: ClientKind, version: &str, os_version: &str) {
+        let client = Client::from_agent_string(agent_string.to_string());
+        assert_eq!(client.kind, kind, "{}", agent_string);
+        assert_eq!(client.version, version, "{}", agent_string);
+        assert_eq!(client.os_version, os_version, "{}", agent_string);
+        assert_eq!(client.agent_string.as_deref(), Some(agent_string));
+    }
+
+    #[test]
+    fn parses_real_agent_strings() {
+        assert_client(
+            "Lighthouse/v4.5.0-441fc16/x86_64-linux",
+            ClientKind::Lighthouse,
+            "v4.5.0-441fc16",
+            "x86_64-linux",
+        );
+        assert_client(
+            "teku/teku/v23.10.0/linux-x86_64/-eclipseadoptium-openjdk64bitservervm-java-17",
+            ClientKind::Teku,
+            "v23.10.0",
+            "linux-x86_64",
+        );
+        assert_client(
+            "Prysm/v4.1.1/5d1ac2b14046eb3bb8ee41b1a9e2a2f4a08f1b0a",
+            ClientKind::Prysm,
+            "v4.1.1",
+            "unknown",
+        );
+        assert_client(
+            "github.com/prysmaticlabs/prysm",
+            ClientKind::Prysm,
+            "unknown",
+            "unknown",
+        );
+        assert_client("nimbus", ClientKind::Nimbus, "unknown", "unknown");
+        assert_client("nim-libp2p/0.0.1", ClientKind::Nimbus, "0.0.1", "unknown");
+        assert_client(
+            "lodestar/v1.12.0/c7a8b9d",
+            ClientKind::Lodestar,
+            "v1.12.0",
+            "c7a8b9d",
+        );
+        assert_client(
+            "js-libp2p/0.45.9",
+            ClientKind::Lodestar,
+            "0.45.9",
+            "unknown",
+        );
+    }
+
+    #[test]
+    fn unknown_agent_string_is_preserved() {
+        let client = Client::from_agent_string("erigon/caplin".to_string());
+        assert_eq!(client.kind, ClientKind::Unknown);
+        assert_eq!(client.agent_string.as_deref(), Some("erigon/caplin"));
+        assert_eq!(client.to_string(), "Unknown: erigon/caplin");
+    }
+}
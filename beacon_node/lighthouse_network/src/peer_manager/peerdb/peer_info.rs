@@ -5,17 +5,22 @@ use crate::discovery::Eth2Enr;
 use crate::{rpc::MetaData, types::Subnet};
 use discv5::Enr;
 use libp2p::core::multiaddr::{Multiaddr, Protocol};
+use libp2p::gossipsub::TopicScoreParams;
 use serde::{
     ser::{SerializeStruct, Serializer},
     Serialize,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::net::IpAddr;
 use std::time::Instant;
 use strum::AsRefStr;
 use types::EthSpec;
 use PeerConnectionStatus::*;
 
+/// Decaying gossipsub score counters are reset to zero once they fall below this value, matching
+/// the `decay_to_zero` score parameter.
+const GOSSIP_SCORE_DECAY_TO_ZERO: f64 = 0.01;
+
 /// Information about a given connected peer.
 #[derive(Clone, Debug, Serialize)]
 #[serde(bound = "T: EthSpec")]
@@ -51,6 +56,12 @@ pub struct PeerInfo<T: EthSpec> {
     connection_direction: Option<ConnectionDirection>,
     /// The enr of the peer, if known.
     enr: Option<Enr>,
+    /// Gossipsub scoring components of the peer, per topic kind.
+    gossip_topic_scores: HashMap<String, GossipTopicScore>,
+    /// The estimated behavioural penalty (P7) in the peer's gossipsub score. This is the negative
+    /// part of the score which is not accounted for by `gossip_topic_scores`, so it also includes
+    /// any IP colocation penalty.
+    gossip_behaviour_penalty: f64,
 }
 
 impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
@@ -68,6 +79,8 @@ impl<TSpec: EthSpec> Default for PeerInfo<TSpec> {
             is_trusted: false,
             connection_direction: None,
             enr: None,
+            gossip_topic_scores: HashMap::new(),
+            gossip_behaviour_penalty: 0.0,
         }
     }
 }
@@ -99,6 +112,16 @@ impl<T: EthSpec> PeerInfo<T> {
         false
     }
 
+    /// Returns the gossipsub scoring components of the peer we have observed, per topic kind.
+    pub fn gossip_topic_scores(&self) -> &HashMap<String, GossipTopicScore> {
+        &self.gossip_topic_scores
+    }
+
+    /// Returns the estimated behavioural penalty in the peer's gossipsub score.
+    pub fn gossip_behaviour_penalty(&self) -> f64 {
+        self.gossip_behaviour_penalty
+    }
+
     /// Obtains the client of the peer.
     pub fn client(&self) -> &Client {
         &self.client
@@ -353,6 +376,11 @@ impl<T: EthSpec> PeerInfo<T> {
         self.connection_status = connection_status
     }
 
+    /// Records an address we have connected to the peer on.
+    pub(super) fn insert_seen_multiaddr(&mut self, multiaddr: Multiaddr) {
+        self.seen_multiaddrs.insert(multiaddr);
+    }
+
     /// Sets the ENR of the peer if one is known.
     pub(super) fn set_enr(&mut self, enr: Enr) {
         self.enr = Some(enr)
@@ -398,6 +426,30 @@ impl<T: EthSpec> PeerInfo<T> {
         self.score.update_gossipsub_score(new_score, ignore);
     }
 
+    /// Updates the gossipsub scoring components of the peer on `topic`, applying the messages
+    /// observed since the last update and returning the weighted score of the topic.
+    pub(super) fn update_gossip_topic_score(
+        &mut self,
+        topic: String,
+        params: &TopicScoreParams,
+        in_mesh: bool,
+        events: GossipTopicEvents,
+        now: Instant,
+    ) -> f64 {
+        let topic_score = self.gossip_topic_scores.entry(topic.clone()).or_default();
+        topic_score.update(params, in_mesh, events, now);
+        let score = topic_score.score;
+        if topic_score.is_empty() {
+            self.gossip_topic_scores.remove(&topic);
+        }
+        score
+    }
+
+    /// Sets the estimated behavioural penalty of the peer's gossipsub score.
+    pub(super) fn set_gossip_behaviour_penalty(&mut self, penalty: f64) {
+        self.gossip_behaviour_penalty = penalty;
+    }
+
     #[cfg(test)]
     /// Resets the peers score.
     pub fn reset_score(&mut self) {
@@ -472,6 +524,132 @@ impl<T: EthSpec> PeerInfo<T> {
     }
 }
 
+/// The gossipsub messages on a single topic from a peer which were observed since the last score
+/// update.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GossipTopicEvents {
+    /// The number of messages which the peer was the first to deliver to us.
+    pub first_message_deliveries: u64,
+    /// The number of messages from the peer which we rejected.
+    pub invalid_messages: u64,
+}
+
+/// The gossipsub scoring components of a peer on a single topic, estimated from the messages we
+/// observe locally using the same parameters and decay as gossipsub.
+///
+/// Gossipsub only notifies us of the first delivery of each message, so near-first deliveries
+/// don't count towards `mesh_message_deliveries` and the deficit may be overestimated.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct GossipTopicScore {
+    /// When we first observed the peer in our mesh for the topic.
+    #[serde(skip)]
+    mesh_joined: Option<Instant>,
+    /// Seconds the peer has been in our mesh for the topic (P1).
+    pub time_in_mesh: u64,
+    /// The decaying count of messages the peer was the first to deliver to us (P2).
+    pub first_message_deliveries: f64,
+    /// The decaying count of messages the peer delivered to us while in our mesh.
+    pub mesh_message_deliveries: f64,
+    /// How far `mesh_message_deliveries` is below the topic's threshold, once the peer has been
+    /// in our mesh for longer than the activation window (P3).
+    pub mesh_message_delivery_deficit: f64,
+    /// The decaying penalty for leaving our mesh with a delivery deficit (P3b).
+    pub mesh_failure_penalty: f64,
+    /// The decaying count of messages from the peer which we rejected (P4).
+    pub invalid_message_deliveries: f64,
+    /// The contribution of the topic to the peer's gossipsub score, including the topic weight.
+    pub score: f64,
+}
+
+impl GossipTopicScore {
+    /// Decays the counters, applies `events` and recomputes the score, mirroring the gossipsub
+    /// scoring function.
+    fn update(
+        &mut self,
+        params: &TopicScoreParams,
+        in_mesh: bool,
+        events: GossipTopicEvents,
+        now: Instant,
+    ) {
+        let decay = |value: f64, factor: f64| {
+            let value = value * factor;
+            if value < GOSSIP_SCORE_DECAY_TO_ZERO {
+                0.0
+            } else {
+                value
+            }
+        };
+        self.first_message_deliveries = decay(
+            self.first_message_deliveries,
+            params.first_message_deliveries_decay,
+        );
+        self.mesh_message_deliveries = decay(
+            self.mesh_message_deliveries,
+            params.mesh_message_deliveries_decay,
+        );
+        self.mesh_failure_penalty =
+            decay(self.mesh_failure_penalty, params.mesh_failure_penalty_decay);
+        self.invalid_message_deliveries = decay(
+            self.invalid_message_deliveries,
+            params.invalid_message_deliveries_decay,
+        );
+
+        let deliveries = events.first_message_deliveries as f64;
+        self.first_message_deliveries =
+            (self.first_message_deliveries + deliveries).min(params.first_message_deliveries_cap);
+        self.invalid_message_deliveries += events.invalid_messages as f64;
+
+        if in_mesh {
+            let joined = *self.mesh_joined.get_or_insert(now);
+            let mesh_time = now.saturating_duration_since(joined);
+            self.time_in_mesh = mesh_time.as_secs();
+            self.mesh_message_deliveries =
+                (self.mesh_message_deliveries + deliveries).min(params.mesh_message_deliveries_cap);
+            self.mesh_message_delivery_deficit = if mesh_time
+                > params.mesh_message_deliveries_activation
+            {
+                (params.mesh_message_deliveries_threshold - self.mesh_message_deliveries).max(0.0)
+            } else {
+                0.0
+            };
+        } else if self.mesh_joined.take().is_some() {
+            // The peer left our mesh, it is penalised for any delivery deficit at the time.
+            self.mesh_failure_penalty += self.mesh_message_delivery_deficit.powi(2);
+            self.time_in_mesh = 0;
+            self.mesh_message_deliveries = 0.0;
+            self.mesh_message_delivery_deficit = 0.0;
+        }
+
+        let time_in_mesh = if in_mesh {
+            (self.time_in_mesh as f64 / params.time_in_mesh_quantum.as_secs_f64())
+                .min(params.time_in_mesh_cap)
+        } else {
+            0.0
+        };
+        self.score = params.topic_weight
+            * (time_in_mesh * params.time_in_mesh_weight
+                + self.first_message_deliveries * params.first_message_deliveries_weight
+                + self.mesh_message_delivery_deficit.powi(2)
+                    * params.mesh_message_deliveries_weight
+                + self.mesh_failure_penalty * params.mesh_failure_penalty_weight
+                + self.invalid_message_deliveries.powi(2)
+                    * params.invalid_message_deliveries_weight);
+    }
+
+    /// Returns true if the peer was in our mesh for the topic at the last update.
+    pub fn in_mesh(&self) -> bool {
+        self.mesh_joined.is_some()
+    }
+
+    /// Returns true if none of the components contribute to the peer's score.
+    fn is_empty(&self) -> bool {
+        self.mesh_joined.is_none()
+            && self.first_message_deliveries == 0.0
+            && self.mesh_failure_penalty == 0.0
+            && self.invalid_message_deliveries == 0.0
+    }
+}
+
 /// Connection Direction of connection.
 #[derive(Debug, Clone, Serialize, AsRefStr)]
 #[strum(serialize_all = "snake_case")]
@@ -568,3 +746,46 @@ impl Serialize for PeerConnectionStatus {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn gossip_topic_score_deficit_and_failure_penalty() {
+        let params = TopicScoreParams {
+            mesh_message_deliveries_threshold: 10.0,
+            mesh_message_deliveries_activation: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let deliveries = |first_message_deliveries| GossipTopicEvents {
+            first_message_deliveries,
+            invalid_messages: 0,
+        };
+
+        // No deficit until the activation window has passed.
+        let mut score = GossipTopicScore::default();
+        score.update(&params, true, deliveries(4), start);
+        assert_eq!(score.mesh_message_delivery_deficit, 0.0);
+
+        score.update(&params, true, deliveries(0), start + Duration::from_secs(6));
+        assert_eq!(score.time_in_mesh, 6);
+        // The 4 deliveries have decayed by half.
+        assert_eq!(score.mesh_message_deliveries, 2.0);
+        assert_eq!(score.mesh_message_delivery_deficit, 8.0);
+
+        // Leaving the mesh with a deficit incurs a mesh failure penalty.
+        score.update(
+            &params,
+            false,
+            deliveries(0),
+            start + Duration::from_secs(7),
+        );
+        assert!(!score.in_mesh());
+        assert_eq!(score.time_in_mesh, 0);
+        assert_eq!(score.mesh_message_delivery_deficit, 0.0);
+        assert_eq!(score.mesh_failure_penalty, 64.0);
+    }
+}
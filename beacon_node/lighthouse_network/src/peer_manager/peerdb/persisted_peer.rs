@@ -0,0 +1,112 @@
+//! A representation of a known peer that can be written to disk and restored after a restart.
+
+use crate::{Enr, Multiaddr, PeerId};
+use ssz_derive::{Decode, Encode};
+use std::str::FromStr;
+
+/// A peer from the `PeerDB` that is remembered across restarts.
+///
+/// Fields are stored as raw bytes so that the encoding does not depend on libp2p types. Fields
+/// which fail to decode are treated as unknown when the peer is restored.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct PersistedPeer {
+    /// The `PeerId` of the peer.
+    peer_id: Vec<u8>,
+    /// The text encoding of the peer's ENR, empty if unknown.
+    enr: Vec<u8>,
+    /// The addresses we have connected to the peer on.
+    multiaddrs: Vec<Vec<u8>>,
+    /// The identify agent string of the peer, empty if unknown.
+    agent_string: Vec<u8>,
+    /// The number of seconds since the UNIX epoch at which the peer was last seen.
+    pub last_seen: u64,
+    /// Whether the peer was banned.
+    pub banned: bool,
+}
+
+impl PersistedPeer {
+    pub fn new<'a>(
+        peer_id: &PeerId,
+        enr: Option<&Enr>,
+        multiaddrs: impl Iterator<Item = &'a Multiaddr>,
+        agent_string: Option<&str>,
+        last_seen: u64,
+        banned: bool,
+    ) -> Self {
+        PersistedPeer {
+            peer_id: peer_id.to_bytes(),
+            enr: enr
+                .map(|enr| enr.to_base64().into_bytes())
+                .unwrap_or_default(),
+            multiaddrs: multiaddrs.map(|multiaddr| multiaddr.to_vec()).collect(),
+            agent_string: agent_string
+                .map(|agent_string| agent_string.as_bytes().to_vec())
+                .unwrap_or_default(),
+            last_seen,
+            banned,
+        }
+    }
+
+    /// Returns the `PeerId` of the peer, if it can be decoded.
+    pub fn peer_id(&self) -> Option<PeerId> {
+        PeerId::from_bytes(&self.peer_id).ok()
+    }
+
+    /// Returns the ENR of the peer, if one was known and it can be decoded.
+    pub fn enr(&self) -> Option<Enr> {
+        std::str::from_utf8(&self.enr)
+            .ok()
+            .filter(|enr| !enr.is_empty())
+            .and_then(|enr| Enr::from_str(enr).ok())
+    }
+
+    /// Returns the addresses we have connected to the peer on.
+    pub fn multiaddrs(&self) -> impl Iterator<Item = Multiaddr> + '_ {
+        self.multiaddrs
+            .iter()
+            .filter_map(|bytes| Multiaddr::try_from(bytes.clone()).ok())
+    }
+
+    /// Returns the identify agent string of the peer, if one was known.
+    pub fn agent_string(&self) -> Option<String> {
+        String::from_utf8(self.agent_string.clone())
+            .ok()
+            .filter(|agent_string| !agent_string.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ssz::{Decode, Encode};
+
+    #[test]
+    fn persisted_peer_round_trip() {
+        let peer_id = PeerId::random();
+        let multiaddr: Multiaddr = "/ip4/127.0.0.1/tcp/9000".parse().unwrap();
+        let enr = Enr::from_str("enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8").unwrap();
+        let peer = PersistedPeer::new(
+            &peer_id,
+            Some(&enr),
+            std::iter::once(&multiaddr),
+            Some("Lighthouse/v4.5.0-441fc16/x86_64-linux"),
+            1_700_000_000,
+            true,
+        );
+
+        let decoded = PersistedPeer::from_ssz_bytes(&peer.as_ssz_bytes()).unwrap();
+        assert_eq!(decoded, peer);
+        assert_eq!(decoded.peer_id(), Some(peer_id));
+        assert_eq!(decoded.enr(), Some(enr));
+        assert_eq!(decoded.multiaddrs().collect::<Vec<_>>(), vec![multiaddr]);
+        assert_eq!(
+            decoded.agent_string().as_deref(),
+            Some("Lighthouse/v4.5.0-441fc16/x86_64-linux")
+        );
+
+        // Unknown fields are restored as such.
+        let peer = PersistedPeer::new(&peer_id, None, std::iter::empty(), None, 0, false);
+        assert_eq!(peer.enr(), None);
+        assert_eq!(peer.agent_string(), None);
+    }
+}
@@ -8,6 +8,10 @@ use super::{methods, rate_limiter::Quota, Protocol};
 
 use serde_derive::{Deserialize, Serialize};
 
+/// Name used in configuration strings for the quota on the number of `BlocksByRange` requests,
+/// as opposed to the number of blocks requested.
+const BLOCKS_BY_RANGE_REQUESTS: &str = "blocks_by_range_requests";
+
 /// Auxiliary struct to aid on configuration parsing.
 ///
 /// A protocol's quota is specified as `protocol_name:tokens/time_in_seconds`.
@@ -39,31 +43,49 @@ impl FromStr for ProtocolQuota {
         let protocol = protocol_str
             .parse()
             .map_err(|_parse_err| "Wrong protocol representation in quota")?;
-        let (tokens_str, time_str) = quota_str
-            .split_once('/')
-            .ok_or("Quota should be defined as \"n/t\" (t in seconds). Missing '/' from quota.")?;
-        let tokens = tokens_str
-            .parse()
-            .map_err(|_| "Failed to parse tokens from quota.")?;
-        let seconds = time_str
-            .parse::<u64>()
-            .map_err(|_| "Failed to parse time in seconds from quota.")?;
         Ok(ProtocolQuota {
             protocol,
-            quota: Quota {
-                replenish_all_every: Duration::from_secs(seconds),
-                max_tokens: tokens,
-            },
+            quota: parse_quota(quota_str)?,
         })
     }
 }
 
+/// Parse a quota defined as `tokens/time_in_seconds`.
+fn parse_quota(quota_str: &str) -> Result<Quota, &'static str> {
+    let (tokens_str, time_str) = quota_str
+        .split_once('/')
+        .ok_or("Quota should be defined as \"n/t\" (t in seconds). Missing '/' from quota.")?;
+    let tokens = tokens_str
+        .parse()
+        .map_err(|_| "Failed to parse tokens from quota.")?;
+    let seconds = time_str
+        .parse::<u64>()
+        .map_err(|_| "Failed to parse time in seconds from quota.")?;
+    Ok(Quota {
+        replenish_all_every: Duration::from_secs(seconds),
+        max_tokens: tokens,
+    })
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, Default)]
 pub struct OutboundRateLimiterConfig(pub RateLimiterConfig);
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
 pub struct InboundRateLimiterConfig(pub RateLimiterConfig);
 
+/// The number of `BlocksByRange` requests is only limited for inbound requests by default, so that
+/// the self rate limiter doesn't slow down sync.
+impl Default for InboundRateLimiterConfig {
+    fn default() -> Self {
+        InboundRateLimiterConfig(RateLimiterConfig {
+            blocks_by_range_requests_quota: Some(
+                RateLimiterConfig::DEFAULT_BLOCKS_BY_RANGE_REQUESTS_QUOTA,
+            ),
+            ..RateLimiterConfig::default()
+        })
+    }
+}
+
 impl FromStr for OutboundRateLimiterConfig {
     type Err = &'static str;
 
@@ -76,7 +98,11 @@ impl FromStr for InboundRateLimiterConfig {
     type Err = &'static str;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        RateLimiterConfig::from_str(s).map(Self)
+        let mut config = RateLimiterConfig::from_str(s)?;
+        config
+            .blocks_by_range_requests_quota
+            .get_or_insert(RateLimiterConfig::DEFAULT_BLOCKS_BY_RANGE_REQUESTS_QUOTA);
+        Ok(Self(config))
     }
 }
 
@@ -88,6 +114,8 @@ pub struct RateLimiterConfig {
     pub(super) status_quota: Quota,
     pub(super) goodbye_quota: Quota,
     pub(super) blocks_by_range_quota: Quota,
+    /// The number of `BlocksByRange` requests is not limited when `None`.
+    pub(super) blocks_by_range_requests_quota: Option<Quota>,
     pub(super) blocks_by_root_quota: Quota,
     pub(super) light_client_bootstrap_quota: Quota,
 }
@@ -99,6 +127,7 @@ impl RateLimiterConfig {
     pub const DEFAULT_GOODBYE_QUOTA: Quota = Quota::one_every(10);
     pub const DEFAULT_BLOCKS_BY_RANGE_QUOTA: Quota =
         Quota::n_every(methods::MAX_REQUEST_BLOCKS, 10);
+    pub const DEFAULT_BLOCKS_BY_RANGE_REQUESTS_QUOTA: Quota = Quota::n_every(32, 10);
     pub const DEFAULT_BLOCKS_BY_ROOT_QUOTA: Quota = Quota::n_every(128, 10);
     pub const DEFAULT_LIGHT_CLIENT_BOOTSTRAP_QUOTA: Quota = Quota::one_every(10);
 }
@@ -111,6 +140,7 @@ impl Default for RateLimiterConfig {
             status_quota: Self::DEFAULT_STATUS_QUOTA,
             goodbye_quota: Self::DEFAULT_GOODBYE_QUOTA,
             blocks_by_range_quota: Self::DEFAULT_BLOCKS_BY_RANGE_QUOTA,
+            blocks_by_range_requests_quota: None,
             blocks_by_root_quota: Self::DEFAULT_BLOCKS_BY_ROOT_QUOTA,
             light_client_bootstrap_quota: Self::DEFAULT_LIGHT_CLIENT_BOOTSTRAP_QUOTA,
         }
@@ -129,12 +159,25 @@ impl Debug for RateLimiterConfig {
             };
         }
 
+        let blocks_by_range_requests = match &self.blocks_by_range_requests_quota {
+            Some(quota) => format!(
+                "{}/{}s",
+                quota.max_tokens,
+                quota.replenish_all_every.as_secs()
+            ),
+            None => "unlimited".to_string(),
+        };
+
         f.debug_struct("RateLimiterConfig")
             .field("ping", fmt_q!(&self.ping_quota))
             .field("metadata", fmt_q!(&self.meta_data_quota))
             .field("status", fmt_q!(&self.status_quota))
             .field("goodbye", fmt_q!(&self.goodbye_quota))
             .field("blocks_by_range", fmt_q!(&self.blocks_by_range_quota))
+            .field(
+                "blocks_by_range_requests",
+                &format_args!("{}", blocks_by_range_requests),
+            )
             .field("blocks_by_root", fmt_q!(&self.blocks_by_root_quota))
             .finish()
     }
@@ -143,7 +186,9 @@ impl Debug for RateLimiterConfig {
 /// Parse configurations for the outbound rate limiter. Protocols that are not specified use
 /// the default values. Protocol specified more than once use only the first given Quota.
 ///
-/// The expected format is a ';' separated list of [`ProtocolQuota`].
+/// The expected format is a ';' separated list of [`ProtocolQuota`]. The number of
+/// `BlocksByRange` requests (rather than blocks) can be limited using the
+/// `blocks_by_range_requests` name in place of a protocol, and is otherwise unlimited.
 impl FromStr for RateLimiterConfig {
     type Err = &'static str;
 
@@ -153,10 +198,16 @@ impl FromStr for RateLimiterConfig {
         let mut status_quota = None;
         let mut goodbye_quota = None;
         let mut blocks_by_range_quota = None;
+        let mut blocks_by_range_requests_quota = None;
         let mut blocks_by_root_quota = None;
         let mut light_client_bootstrap_quota = None;
 
         for proto_def in s.split(';') {
+            if let Some((BLOCKS_BY_RANGE_REQUESTS, quota_str)) = proto_def.split_once(':') {
+                blocks_by_range_requests_quota =
+                    blocks_by_range_requests_quota.or(Some(parse_quota(quota_str)?));
+                continue;
+            }
             let ProtocolQuota { protocol, quota } = proto_def.parse()?;
             let quota = Some(quota);
             match protocol {
@@ -178,6 +229,7 @@ impl FromStr for RateLimiterConfig {
             goodbye_quota: goodbye_quota.unwrap_or(Self::DEFAULT_GOODBYE_QUOTA),
             blocks_by_range_quota: blocks_by_range_quota
                 .unwrap_or(Self::DEFAULT_BLOCKS_BY_RANGE_QUOTA),
+            blocks_by_range_requests_quota,
             blocks_by_root_quota: blocks_by_root_quota
                 .unwrap_or(Self::DEFAULT_BLOCKS_BY_ROOT_QUOTA),
             light_client_bootstrap_quota: light_client_bootstrap_quota
@@ -201,4 +253,41 @@ mod tests {
         };
         assert_eq!(quota.to_string().parse(), Ok(quota))
     }
+
+    #[test]
+    fn test_blocks_by_range_requests_quota() {
+        let config: RateLimiterConfig = "blocks_by_range:512/10;blocks_by_range_requests:4/5"
+            .parse()
+            .unwrap();
+        assert_eq!(config.blocks_by_range_quota, Quota::n_every(512, 10));
+        assert_eq!(
+            config.blocks_by_range_requests_quota,
+            Some(Quota::n_every(4, 5))
+        );
+        assert_eq!(config.ping_quota, RateLimiterConfig::DEFAULT_PING_QUOTA);
+    }
+
+    #[test]
+    fn test_blocks_by_range_requests_quota_only_limits_inbound_by_default() {
+        let inbound: InboundRateLimiterConfig = "ping:1/1".parse().unwrap();
+        assert_eq!(
+            inbound.0.blocks_by_range_requests_quota,
+            Some(RateLimiterConfig::DEFAULT_BLOCKS_BY_RANGE_REQUESTS_QUOTA)
+        );
+        assert_eq!(
+            InboundRateLimiterConfig::default()
+                .0
+                .blocks_by_range_requests_quota,
+            Some(RateLimiterConfig::DEFAULT_BLOCKS_BY_RANGE_REQUESTS_QUOTA)
+        );
+
+        let outbound: OutboundRateLimiterConfig = "ping:1/1".parse().unwrap();
+        assert_eq!(outbound.0.blocks_by_range_requests_quota, None);
+        assert_eq!(
+            OutboundRateLimiterConfig::default()
+                .0
+                .blocks_by_range_requests_quota,
+            None
+        );
+    }
 }
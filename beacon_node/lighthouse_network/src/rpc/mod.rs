@@ -6,6 +6,7 @@
 
 use futures::future::FutureExt;
 use handler::{HandlerEvent, RPCHandler};
+use libp2p::swarm::behaviour::{ConnectionClosed, ConnectionEstablished};
 use libp2p::swarm::{
     handler::ConnectionHandler, ConnectionId, NetworkBehaviour, NotifyHandler, PollParameters,
     ToSwarm,
@@ -14,6 +15,7 @@ use libp2p::swarm::{FromSwarm, SubstreamProtocol, THandlerInEvent};
 use libp2p::PeerId;
 use rate_limiter::{RPCRateLimiter as RateLimiter, RateLimitedErr};
 use slog::{crit, debug, o};
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -34,7 +36,7 @@ pub use protocol::{max_rpc_size, Protocol, RPCError};
 
 use self::config::{InboundRateLimiterConfig, OutboundRateLimiterConfig};
 use self::protocol::RPCProtocol;
-use self::self_limiter::SelfRateLimiter;
+use self::self_limiter::{Error as SelfRateLimiterError, SelfRateLimiter};
 
 pub(crate) mod codec;
 pub mod config;
@@ -121,6 +123,9 @@ pub struct RPC<Id: ReqId, TSpec: EthSpec> {
     limiter: Option<RateLimiter>,
     /// Rate limiter for our own requests.
     self_limiter: Option<SelfRateLimiter<Id, TSpec>>,
+    /// Established connections for each peer, used to attribute errors for requests which never
+    /// reached a handler.
+    connections: HashMap<PeerId, Vec<ConnectionId>>,
     /// Queue of events to be processed.
     events: Vec<BehaviourAction<Id, TSpec>>,
     fork_context: Arc<ForkContext>,
@@ -155,6 +160,7 @@ impl<Id: ReqId, TSpec: EthSpec> RPC<Id, TSpec> {
         RPC {
             limiter: inbound_limiter,
             self_limiter,
+            connections: HashMap::new(),
             events: Vec::new(),
             fork_context,
             enable_light_client_server,
@@ -186,7 +192,35 @@ impl<Id: ReqId, TSpec: EthSpec> RPC<Id, TSpec> {
         let event = if let Some(self_limiter) = self.self_limiter.as_mut() {
             match self_limiter.allows(peer_id, request_id, req) {
                 Ok(event) => event,
-                Err(_e) => {
+                Err(SelfRateLimiterError::QueueFull {
+                    request_id,
+                    protocol,
+                }) => {
+                    // Too many requests are already queued for this peer, inform the requester
+                    // instead of queueing indefinitely.
+                    let conn_id = match self
+                        .connections
+                        .get(&peer_id)
+                        .and_then(|conns| conns.first())
+                    {
+                        Some(conn_id) => *conn_id,
+                        None => {
+                            debug!(self.log, "Dropping request for disconnected peer";
+                                "protocol" => %protocol, "peer_id" => %peer_id);
+                            return;
+                        }
+                    };
+                    ToSwarm::GenerateEvent(RPCMessage {
+                        peer_id,
+                        conn_id,
+                        event: Err(HandlerErr::Outbound {
+                            id: request_id,
+                            proto: protocol,
+                            error: RPCError::SelfRateLimitQueueFull,
+                        }),
+                    })
+                }
+                Err(SelfRateLimiterError::PendingRequests | SelfRateLimiterError::RateLimited) => {
                     // Request is logged and queued internally in the self rate limiter.
                     return;
                 }
@@ -284,9 +318,28 @@ where
 
     fn on_swarm_event(&mut self, event: FromSwarm<Self::ConnectionHandler>) {
         match event {
-            FromSwarm::ConnectionClosed(_)
-            | FromSwarm::ConnectionEstablished(_)
-            | FromSwarm::AddressChange(_)
+            FromSwarm::ConnectionEstablished(ConnectionEstablished {
+                peer_id,
+                connection_id,
+                ..
+            }) => self
+                .connections
+                .entry(peer_id)
+                .or_default()
+                .push(connection_id),
+            FromSwarm::ConnectionClosed(ConnectionClosed {
+                peer_id,
+                connection_id,
+                ..
+            }) => {
+                if let Some(conns) = self.connections.get_mut(&peer_id) {
+                    conns.retain(|conn_id| *conn_id != connection_id);
+                    if conns.is_empty() {
+                        self.connections.remove(&peer_id);
+                    }
+                }
+            }
+            FromSwarm::AddressChange(_)
             | FromSwarm::DialFailure(_)
             | FromSwarm::ListenFailure(_)
             | FromSwarm::NewListener(_)
@@ -430,3 +483,76 @@ where
         slog::Result::Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::self_limiter::MAX_QUEUED_REQUESTS_PER_PEER;
+    use super::*;
+    use libp2p::core::{ConnectedPoint, Endpoint};
+    use libp2p::Multiaddr;
+    use types::{ChainSpec, Hash256, MainnetEthSpec, Slot};
+
+    #[tokio::test]
+    async fn queue_full_error_reaches_requester_on_its_connection() {
+        let log = slog::Logger::root(slog::Discard, slog::o!());
+        let spec = ChainSpec::mainnet();
+        let fork_context = Arc::new(ForkContext::new::<MainnetEthSpec>(
+            Slot::new(0),
+            Hash256::zero(),
+            &spec,
+        ));
+        let mut rpc: RPC<usize, MainnetEthSpec> = RPC::new(
+            fork_context,
+            false,
+            None,
+            Some("ping:1/10".parse().unwrap()),
+            log,
+            NetworkParams {
+                max_chunk_size: spec.max_chunk_size as usize,
+                ttfb_timeout: Duration::from_secs(5),
+                resp_timeout: Duration::from_secs(10),
+            },
+        );
+
+        let peer_id = PeerId::random();
+        let conn_id = ConnectionId::new_unchecked(7);
+        rpc.on_swarm_event(FromSwarm::ConnectionEstablished(ConnectionEstablished {
+            peer_id,
+            connection_id: conn_id,
+            endpoint: &ConnectedPoint::Dialer {
+                address: Multiaddr::empty(),
+                role_override: Endpoint::Dialer,
+            },
+            failed_addresses: &[],
+            other_established: 0,
+        }));
+
+        // The first request is sent, the next one is rate limited and the rest are queued until
+        // the queue is full.
+        let ping = || OutboundRequest::Ping(Ping { data: 1 });
+        for request_id in 0..=MAX_QUEUED_REQUESTS_PER_PEER {
+            rpc.send_request(peer_id, request_id, ping());
+        }
+        assert_eq!(rpc.events.len(), 1);
+
+        let request_id = MAX_QUEUED_REQUESTS_PER_PEER + 1;
+        rpc.send_request(peer_id, request_id, ping());
+        match rpc.events.pop() {
+            Some(ToSwarm::GenerateEvent(RPCMessage {
+                peer_id: event_peer_id,
+                conn_id: event_conn_id,
+                event:
+                    Err(HandlerErr::Outbound {
+                        id,
+                        proto: Protocol::Ping,
+                        error: RPCError::SelfRateLimitQueueFull,
+                    }),
+            })) => {
+                assert_eq!(event_peer_id, peer_id);
+                assert_eq!(event_conn_id, conn_id);
+                assert_eq!(id, request_id);
+            }
+            _ => panic!("expected a queue full error for the requester"),
+        }
+    }
+}
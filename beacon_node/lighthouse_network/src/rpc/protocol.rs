@@ -567,6 +567,8 @@ pub enum RPCError {
     HandlerRejected,
     /// We have intentionally disconnected.
     Disconnected,
+    /// Too many of our own requests are already queued for this peer and protocol.
+    SelfRateLimitQueueFull,
 }
 
 impl From<ssz::DecodeError> for RPCError {
@@ -606,6 +608,7 @@ impl std::fmt::Display for RPCError {
             RPCError::NegotiationTimeout => write!(f, "Negotiation timeout"),
             RPCError::HandlerRejected => write!(f, "Handler rejected the request"),
             RPCError::Disconnected => write!(f, "Gracefully Disconnected"),
+            RPCError::SelfRateLimitQueueFull => write!(f, "Self rate limiting queue is full"),
         }
     }
 }
@@ -625,6 +628,7 @@ impl std::error::Error for RPCError {
             RPCError::NegotiationTimeout => None,
             RPCError::HandlerRejected => None,
             RPCError::Disconnected => None,
+            RPCError::SelfRateLimitQueueFull => None,
         }
     }
 }
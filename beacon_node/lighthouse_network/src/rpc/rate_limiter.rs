@@ -8,8 +8,8 @@ use std::future::Future;
 use std::hash::Hash;
 use std::pin::Pin;
 use std::task::{Context, Poll};
-use std::time::{Duration, Instant};
-use tokio::time::Interval;
+use std::time::Duration;
+use tokio::time::{Instant, Interval};
 use types::EthSpec;
 
 /// Nanoseconds since a given time.
@@ -80,7 +80,8 @@ impl Quota {
 pub struct RPCRateLimiter {
     /// Interval to prune peers for which their timer ran out.
     prune_interval: Interval,
-    /// Creation time of the rate limiter.
+    /// Creation time of the rate limiter, according to tokio's clock so that it follows the
+    /// runtime's time when it is paused.
     init_time: Instant,
     /// Goodbye rate limiter.
     goodbye_rl: Limiter<PeerId>,
@@ -90,8 +91,10 @@ pub struct RPCRateLimiter {
     metadata_rl: Limiter<PeerId>,
     /// Status rate limiter.
     status_rl: Limiter<PeerId>,
-    /// BlocksByRange rate limiter.
+    /// BlocksByRange rate limiter, counting blocks.
     bbrange_rl: Limiter<PeerId>,
+    /// BlocksByRange rate limiter, counting requests. Requests are not limited when `None`.
+    bbrange_requests_rl: Option<Limiter<PeerId>>,
     /// BlocksByRoot rate limiter.
     bbroots_rl: Limiter<PeerId>,
     /// LightClientBootstrap rate limiter.
@@ -120,6 +123,8 @@ pub struct RPCRateLimiterBuilder {
     status_quota: Option<Quota>,
     /// Quota for the BlocksByRange protocol.
     bbrange_quota: Option<Quota>,
+    /// Quota for the number of BlocksByRange requests.
+    bbrange_requests_quota: Option<Quota>,
     /// Quota for the BlocksByRoot protocol.
     bbroots_quota: Option<Quota>,
     /// Quota for the LightClientBootstrap protocol.
@@ -142,6 +147,13 @@ impl RPCRateLimiterBuilder {
         self
     }
 
+    /// Set a quota for the number of BlocksByRange requests, regardless of how many blocks each
+    /// request asks for. The number of requests is not limited when `None`.
+    pub fn set_blocks_by_range_requests_quota(mut self, quota: Option<Quota>) -> Self {
+        self.bbrange_requests_quota = quota;
+        self
+    }
+
     pub fn build(self) -> Result<RPCRateLimiter, &'static str> {
         // get our quotas
         let ping_quota = self.ping_quota.ok_or("Ping quota not specified")?;
@@ -165,6 +177,10 @@ impl RPCRateLimiterBuilder {
         let goodbye_rl = Limiter::from_quota(goodbye_quota)?;
         let bbroots_rl = Limiter::from_quota(bbroots_quota)?;
         let bbrange_rl = Limiter::from_quota(bbrange_quota)?;
+        let bbrange_requests_rl = self
+            .bbrange_requests_quota
+            .map(Limiter::from_quota)
+            .transpose()?;
         let lcbootstrap_rl = Limiter::from_quota(lcbootstrap_quote)?;
 
         // check for peers to prune every 30 seconds, starting in 30 seconds
@@ -179,6 +195,7 @@ impl RPCRateLimiterBuilder {
             goodbye_rl,
             bbroots_rl,
             bbrange_rl,
+            bbrange_requests_rl,
             lcbootstrap_rl,
             init_time: Instant::now(),
         })
@@ -218,6 +235,7 @@ impl RPCRateLimiter {
             status_quota,
             goodbye_quota,
             blocks_by_range_quota,
+            blocks_by_range_requests_quota,
             blocks_by_root_quota,
             light_client_bootstrap_quota,
         } = config;
@@ -230,6 +248,7 @@ impl RPCRateLimiter {
             .set_quota(Protocol::BlocksByRange, blocks_by_range_quota)
             .set_quota(Protocol::BlocksByRoot, blocks_by_root_quota)
             .set_quota(Protocol::LightClientBootstrap, light_client_bootstrap_quota)
+            .set_blocks_by_range_requests_quota(blocks_by_range_requests_quota)
             .build()
     }
 
@@ -253,7 +272,22 @@ impl RPCRateLimiter {
             Protocol::Status => &mut self.status_rl,
             Protocol::MetaData => &mut self.metadata_rl,
             Protocol::Goodbye => &mut self.goodbye_rl,
-            Protocol::BlocksByRange => &mut self.bbrange_rl,
+            Protocol::BlocksByRange => {
+                // BlocksByRange requests are limited both by the number of requests and by the
+                // number of blocks requested. Check both quotas before consuming from either, so
+                // that a rejected request doesn't count against the other.
+                let requests_tat = self
+                    .bbrange_requests_rl
+                    .as_ref()
+                    .map(|limiter| limiter.check(time_since_start, peer_id, 1))
+                    .transpose()?;
+                let blocks_tat = self.bbrange_rl.check(time_since_start, peer_id, tokens)?;
+                if let (Some(limiter), Some(tat)) = (&mut self.bbrange_requests_rl, requests_tat) {
+                    limiter.consume(peer_id, tat);
+                }
+                self.bbrange_rl.consume(peer_id, blocks_tat);
+                return Ok(());
+            }
             Protocol::BlocksByRoot => &mut self.bbroots_rl,
             Protocol::LightClientBootstrap => &mut self.lcbootstrap_rl,
         };
@@ -267,6 +301,9 @@ impl RPCRateLimiter {
         self.metadata_rl.prune(time_since_start);
         self.goodbye_rl.prune(time_since_start);
         self.bbrange_rl.prune(time_since_start);
+        if let Some(limiter) = &mut self.bbrange_requests_rl {
+            limiter.prune(time_since_start);
+        }
         self.bbroots_rl.prune(time_since_start);
     }
 }
@@ -322,6 +359,19 @@ impl<Key: Hash + Eq + Clone> Limiter<Key> {
         key: &Key,
         tokens: u64,
     ) -> Result<(), RateLimitedErr> {
+        let tat = self.check(time_since_start, key, tokens)?;
+        self.consume(key, tat);
+        Ok(())
+    }
+
+    /// Checks whether `tokens` can be consumed by `key` without consuming them, returning the
+    /// TAT of `key` once they are.
+    fn check(
+        &self,
+        time_since_start: Duration,
+        key: &Key,
+        tokens: u64,
+    ) -> Result<Nanosecs, RateLimitedErr> {
         let time_since_start = time_since_start.as_nanos() as u64;
         let tau = self.tau;
         let t = self.t;
@@ -336,10 +386,11 @@ impl<Key: Hash + Eq + Clone> Limiter<Key> {
         // allowed)
         let tat = self
             .tat_per_key
-            .entry(key.clone())
-            .or_insert(time_since_start);
+            .get(key)
+            .copied()
+            .unwrap_or(time_since_start);
         // check how soon could the request be made
-        let earliest_time = (*tat + additional_time).saturating_sub(tau);
+        let earliest_time = (tat + additional_time).saturating_sub(tau);
         // earliest_time is in the future
         if time_since_start < earliest_time {
             Err(RateLimitedErr::TooSoon(Duration::from_nanos(
@@ -348,11 +399,15 @@ impl<Key: Hash + Eq + Clone> Limiter<Key> {
             )))
         } else {
             // calculate the new TAT
-            *tat = time_since_start.max(*tat) + additional_time;
-            Ok(())
+            Ok(time_since_start.max(tat) + additional_time)
         }
     }
 
+    /// Consumes tokens previously approved by `check`.
+    fn consume(&mut self, key: &Key, tat: Nanosecs) {
+        self.tat_per_key.insert(key.clone(), tat);
+    }
+
     /// Removes keys for which their bucket is full by `time_limit`
     pub fn prune(&mut self, time_limit: Duration) {
         let lim = &mut (time_limit.as_nanos() as u64);
@@ -363,9 +418,25 @@ impl<Key: Hash + Eq + Clone> Limiter<Key> {
 
 #[cfg(test)]
 mod tests {
-    use crate::rpc::rate_limiter::{Limiter, Quota};
+    use crate::rpc::rate_limiter::{
+        Limiter, Quota, RPCRateLimiter, RateLimitedErr, RateLimiterItem,
+    };
+    use crate::rpc::Protocol;
+    use libp2p::PeerId;
     use std::time::Duration;
 
+    struct BlocksByRange(u64);
+
+    impl RateLimiterItem for BlocksByRange {
+        fn protocol(&self) -> Protocol {
+            Protocol::BlocksByRange
+        }
+
+        fn expected_responses(&self) -> u64 {
+            self.0
+        }
+    }
+
     #[test]
     fn it_works_a() {
         let mut limiter = Limiter::from_quota(Quota {
@@ -431,4 +502,89 @@ mod tests {
             .allows(Duration::from_secs_f32(0.4), &key, 1)
             .is_err());
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn blocks_by_range_limits_requests_and_blocks() {
+        let limiter = |max_requests| {
+            RPCRateLimiter::builder()
+                .set_quota(Protocol::BlocksByRange, Quota::n_every(64, 10))
+                .set_blocks_by_range_requests_quota(Some(Quota::n_every(max_requests, 10)))
+                .set_quota(Protocol::Ping, Quota::one_every(1))
+                .set_quota(Protocol::MetaData, Quota::one_every(1))
+                .set_quota(Protocol::Status, Quota::one_every(1))
+                .set_quota(Protocol::Goodbye, Quota::one_every(1))
+                .set_quota(Protocol::BlocksByRoot, Quota::one_every(1))
+                .set_quota(Protocol::LightClientBootstrap, Quota::one_every(1))
+                .build()
+                .unwrap()
+        };
+        let peer = PeerId::random();
+
+        // Small requests are limited by the number of requests.
+        let mut rl = limiter(2);
+        assert!(rl.allows(&peer, &BlocksByRange(1)).is_ok());
+        assert!(rl.allows(&peer, &BlocksByRange(1)).is_ok());
+        assert!(matches!(
+            rl.allows(&peer, &BlocksByRange(1)),
+            Err(RateLimitedErr::TooSoon(_))
+        ));
+        // Other peers are unaffected.
+        assert!(rl.allows(&PeerId::random(), &BlocksByRange(1)).is_ok());
+
+        // Large requests are limited by the number of blocks.
+        let mut rl = limiter(10);
+        assert!(rl.allows(&peer, &BlocksByRange(64)).is_ok());
+        assert!(matches!(
+            rl.allows(&peer, &BlocksByRange(1)),
+            Err(RateLimitedErr::TooSoon(_))
+        ));
+        assert!(matches!(
+            rl.allows(&peer, &BlocksByRange(65)),
+            Err(RateLimitedErr::TooLarge)
+        ));
+
+        // A request rejected for asking for too many blocks doesn't count towards the number of
+        // requests. Blocks are replenished every ~156ms, requests every 5s.
+        let mut rl = limiter(2);
+        assert!(rl.allows(&peer, &BlocksByRange(64)).is_ok());
+        assert!(matches!(
+            rl.allows(&peer, &BlocksByRange(1)),
+            Err(RateLimitedErr::TooSoon(_))
+        ));
+        tokio::time::advance(Duration::from_millis(200)).await;
+        assert!(rl.allows(&peer, &BlocksByRange(1)).is_ok());
+        // That was the second request, so the next one must wait for a request to be replenished
+        // even though a block is.
+        tokio::time::advance(Duration::from_millis(200)).await;
+        assert!(matches!(
+            rl.allows(&peer, &BlocksByRange(1)),
+            Err(RateLimitedErr::TooSoon(_))
+        ));
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert!(rl.allows(&peer, &BlocksByRange(1)).is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn blocks_by_range_requests_unlimited_without_quota() {
+        let mut rl = RPCRateLimiter::builder()
+            .set_quota(Protocol::BlocksByRange, Quota::n_every(64, 10))
+            .set_blocks_by_range_requests_quota(None)
+            .set_quota(Protocol::Ping, Quota::one_every(1))
+            .set_quota(Protocol::MetaData, Quota::one_every(1))
+            .set_quota(Protocol::Status, Quota::one_every(1))
+            .set_quota(Protocol::Goodbye, Quota::one_every(1))
+            .set_quota(Protocol::BlocksByRoot, Quota::one_every(1))
+            .set_quota(Protocol::LightClientBootstrap, Quota::one_every(1))
+            .build()
+            .unwrap();
+        let peer = PeerId::random();
+
+        for _ in 0..64 {
+            assert!(rl.allows(&peer, &BlocksByRange(1)).is_ok());
+        }
+        assert!(matches!(
+            rl.allows(&peer, &BlocksByRange(1)),
+            Err(RateLimitedErr::TooSoon(_))
+        ));
+    }
 }
@@ -1,7 +1,7 @@
 use std::{
     collections::{hash_map::Entry, HashMap, VecDeque},
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use futures::FutureExt;
@@ -11,6 +11,8 @@ use smallvec::SmallVec;
 use tokio_util::time::DelayQueue;
 use types::EthSpec;
 
+use crate::metrics;
+
 use super::{
     config::OutboundRateLimiterConfig,
     rate_limiter::{RPCRateLimiter as RateLimiter, RateLimitedErr},
@@ -22,8 +24,13 @@ use super::{
 struct QueuedRequest<Id: ReqId, TSpec: EthSpec> {
     req: OutboundRequest<TSpec>,
     request_id: Id,
+    queued_at: Instant,
 }
 
+/// The maximum number of requests queued for a single peer and protocol. Further requests are
+/// rejected instead of being queued.
+pub(crate) const MAX_QUEUED_REQUESTS_PER_PEER: usize = 32;
+
 pub(crate) struct SelfRateLimiter<Id: ReqId, TSpec: EthSpec> {
     /// Requests queued for sending per peer. This requests are stored when the self rate
     /// limiter rejects them. Rate limiting is based on a Peer and Protocol basis, therefore
@@ -40,12 +47,13 @@ pub(crate) struct SelfRateLimiter<Id: ReqId, TSpec: EthSpec> {
 }
 
 /// Error returned when the rate limiter does not accept a request.
-// NOTE: this is currently not used, but might be useful for debugging.
-pub enum Error {
+pub enum Error<Id> {
     /// There are queued requests for this same peer and protocol.
     PendingRequests,
     /// Request was tried but rate limited.
     RateLimited,
+    /// The queue for this peer and protocol is full, so the request was dropped.
+    QueueFull { request_id: Id, protocol: Protocol },
 }
 
 impl<Id: ReqId, TSpec: EthSpec> SelfRateLimiter<Id, TSpec> {
@@ -71,15 +79,40 @@ impl<Id: ReqId, TSpec: EthSpec> SelfRateLimiter<Id, TSpec> {
         peer_id: PeerId,
         request_id: Id,
         req: OutboundRequest<TSpec>,
-    ) -> Result<BehaviourAction<Id, TSpec>, Error> {
+    ) -> Result<BehaviourAction<Id, TSpec>, Error<Id>> {
         let protocol = req.versioned_protocol().protocol();
         // First check that there are not already other requests waiting to be sent.
         if let Some(queued_requests) = self.delayed_requests.get_mut(&(peer_id, protocol)) {
-            queued_requests.push_back(QueuedRequest { req, request_id });
+            if queued_requests.len() >= MAX_QUEUED_REQUESTS_PER_PEER {
+                debug!(self.log, "Self rate limiting queue full, dropping request";
+                    "protocol" => %protocol, "peer_id" => %peer_id);
+                metrics::inc_counter_vec(
+                    &metrics::SELF_RATE_LIMITER_QUEUE_FULL_TOTAL,
+                    &[protocol.as_ref()],
+                );
+                return Err(Error::QueueFull {
+                    request_id,
+                    protocol,
+                });
+            }
+            queued_requests.push_back(QueuedRequest {
+                req,
+                request_id,
+                queued_at: Instant::now(),
+            });
+            metrics::inc_gauge_vec(
+                &metrics::SELF_RATE_LIMITER_QUEUED_REQUESTS,
+                &[protocol.as_ref()],
+            );
 
             return Err(Error::PendingRequests);
         }
-        match Self::try_send_request(&mut self.limiter, peer_id, request_id, req, &self.log) {
+        let request = QueuedRequest {
+            req,
+            request_id,
+            queued_at: Instant::now(),
+        };
+        match Self::try_send_request(&mut self.limiter, peer_id, request, &self.log) {
             Err((rate_limited_req, wait_time)) => {
                 let key = (peer_id, protocol);
                 self.next_peer_request.insert(key, wait_time);
@@ -87,6 +120,10 @@ impl<Id: ReqId, TSpec: EthSpec> SelfRateLimiter<Id, TSpec> {
                     .entry(key)
                     .or_default()
                     .push_back(rate_limited_req);
+                metrics::inc_gauge_vec(
+                    &metrics::SELF_RATE_LIMITER_QUEUED_REQUESTS,
+                    &[protocol.as_ref()],
+                );
 
                 Err(Error::RateLimited)
             }
@@ -100,10 +137,14 @@ impl<Id: ReqId, TSpec: EthSpec> SelfRateLimiter<Id, TSpec> {
     fn try_send_request(
         limiter: &mut RateLimiter,
         peer_id: PeerId,
-        request_id: Id,
-        req: OutboundRequest<TSpec>,
+        request: QueuedRequest<Id, TSpec>,
         log: &Logger,
     ) -> Result<BehaviourAction<Id, TSpec>, (QueuedRequest<Id, TSpec>, Duration)> {
+        let QueuedRequest {
+            req,
+            request_id,
+            queued_at,
+        } = request;
         match limiter.allows(&peer_id, &req) {
             Ok(()) => Ok(BehaviourAction::NotifyHandler {
                 peer_id,
@@ -129,7 +170,14 @@ impl<Id: ReqId, TSpec: EthSpec> SelfRateLimiter<Id, TSpec> {
                     }
                     RateLimitedErr::TooSoon(wait_time) => {
                         debug!(log, "Self rate limiting"; "protocol" => %protocol.protocol(), "wait_time_ms" => wait_time.as_millis(), "peer_id" => %peer_id);
-                        Err((QueuedRequest { req, request_id }, wait_time))
+                        Err((
+                            QueuedRequest {
+                                req,
+                                request_id,
+                                queued_at,
+                            },
+                            wait_time,
+                        ))
                     }
                 }
             }
@@ -141,17 +189,29 @@ impl<Id: ReqId, TSpec: EthSpec> SelfRateLimiter<Id, TSpec> {
     fn next_peer_request_ready(&mut self, peer_id: PeerId, protocol: Protocol) {
         if let Entry::Occupied(mut entry) = self.delayed_requests.entry((peer_id, protocol)) {
             let queued_requests = entry.get_mut();
-            while let Some(QueuedRequest { req, request_id }) = queued_requests.pop_front() {
-                match Self::try_send_request(&mut self.limiter, peer_id, request_id, req, &self.log)
-                {
+            while let Some(request) = queued_requests.pop_front() {
+                let queued_at = request.queued_at;
+                match Self::try_send_request(&mut self.limiter, peer_id, request, &self.log) {
                     Err((rate_limited_req, wait_time)) => {
                         let key = (peer_id, protocol);
                         self.next_peer_request.insert(key, wait_time);
-                        queued_requests.push_back(rate_limited_req);
+                        // Keep the request at the front so that requests are sent in order.
+                        queued_requests.push_front(rate_limited_req);
                         // If one fails just wait for the next window that allows sending requests.
                         return;
                     }
-                    Ok(event) => self.ready_requests.push(event),
+                    Ok(event) => {
+                        metrics::dec_gauge_vec(
+                            &metrics::SELF_RATE_LIMITER_QUEUED_REQUESTS,
+                            &[protocol.as_ref()],
+                        );
+                        metrics::observe_timer_vec(
+                            &metrics::SELF_RATE_LIMITER_QUEUE_DELAY,
+                            &[protocol.as_ref()],
+                            queued_at.elapsed(),
+                        );
+                        self.ready_requests.push(event)
+                    }
                 }
             }
             if queued_requests.is_empty() {
@@ -179,3 +239,42 @@ impl<Id: ReqId, TSpec: EthSpec> SelfRateLimiter<Id, TSpec> {
         Poll::Pending
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::methods::Ping;
+    use types::MainnetEthSpec;
+
+    #[tokio::test]
+    async fn queued_requests_are_capped_per_peer() {
+        let log = slog::Logger::root(slog::Discard, slog::o!());
+        let config = "ping:1/10".parse().unwrap();
+        let mut limiter: SelfRateLimiter<usize, MainnetEthSpec> =
+            SelfRateLimiter::new(config, log).unwrap();
+        let peer_id = PeerId::random();
+        let ping = || OutboundRequest::Ping(Ping { data: 1 });
+
+        // The first request is sent immediately and the next is rate limited.
+        assert!(limiter.allows(peer_id, 0, ping()).is_ok());
+        assert!(matches!(
+            limiter.allows(peer_id, 1, ping()),
+            Err(Error::RateLimited)
+        ));
+        // Requests are queued behind the rate limited request until the queue is full.
+        for request_id in 2..=MAX_QUEUED_REQUESTS_PER_PEER {
+            assert!(matches!(
+                limiter.allows(peer_id, request_id, ping()),
+                Err(Error::PendingRequests)
+            ));
+        }
+        let request_id = MAX_QUEUED_REQUESTS_PER_PEER + 1;
+        assert!(matches!(
+            limiter.allows(peer_id, request_id, ping()),
+            Err(Error::QueueFull { request_id: id, protocol: Protocol::Ping }) if id == request_id
+        ));
+
+        // Other peers are unaffected.
+        assert!(limiter.allows(PeerId::random(), 0, ping()).is_ok());
+    }
+}
@@ -9,6 +9,9 @@ use crate::GossipTopic;
 
 use tokio_util::time::delay_queue::{DelayQueue, Key};
 
+/// The maximum number of messages held for retry. Messages inserted beyond this limit are dropped.
+pub const MAX_CACHED_MESSAGES: usize = 1_024;
+
 /// Store of gossip messages that we failed to publish and will try again later. By default, all
 /// messages are ignored. This behaviour can be changed using `GossipCacheBuilder::default_timeout`
 /// to apply the same delay to every kind. Individual timeouts for specific kinds can be set and
@@ -183,8 +186,16 @@ impl GossipCache {
         GossipCacheBuilder::default()
     }
 
-    // Insert a message to be sent later.
-    pub fn insert(&mut self, topic: GossipTopic, data: Vec<u8>) {
+    /// Insert a message to be sent later.
+    ///
+    /// The message is retained for the timeout of its topic kind, or for `max_timeout` if that is
+    /// shorter. Returns `false` if the message was not cached.
+    pub fn insert(
+        &mut self,
+        topic: GossipTopic,
+        data: Vec<u8>,
+        max_timeout: Option<Duration>,
+    ) -> bool {
         let expire_timeout = match topic.kind() {
             GossipKind::BeaconBlock => self.beacon_block,
             GossipKind::BeaconAggregateAndProof => self.aggregates,
@@ -198,10 +209,21 @@ impl GossipCache {
             GossipKind::LightClientFinalityUpdate => self.light_client_finality_update,
             GossipKind::LightClientOptimisticUpdate => self.light_client_optimistic_update,
         };
-        let expire_timeout = match expire_timeout {
-            Some(expire_timeout) => expire_timeout,
-            None => return,
+        let expire_timeout = match (expire_timeout, max_timeout) {
+            (Some(expire_timeout), Some(max_timeout)) => std::cmp::min(expire_timeout, max_timeout),
+            (Some(expire_timeout), None) => expire_timeout,
+            (None, _) => return false,
         };
+        if expire_timeout.is_zero() {
+            return false;
+        }
+        let is_cached = self
+            .topic_msgs
+            .get(&topic)
+            .map_or(false, |msgs| msgs.contains_key(&data));
+        if !is_cached && self.expirations.len() >= MAX_CACHED_MESSAGES {
+            return false;
+        }
         match self
             .topic_msgs
             .entry(topic.clone())
@@ -214,6 +236,12 @@ impl GossipCache {
                 entry.insert(key);
             }
         }
+        true
+    }
+
+    /// Returns the topics which have messages waiting to be sent.
+    pub fn topics(&self) -> impl Iterator<Item = &GossipTopic> {
+        self.topic_msgs.keys()
     }
 
     // Get the registered messages for this topic.
@@ -277,10 +305,49 @@ mod tests {
             crate::types::GossipEncoding::SSZSnappy,
             [0u8; 4],
         );
-        cache.insert(test_topic, vec![]);
+        cache.insert(test_topic, vec![], None);
         tokio::time::sleep(Duration::from_millis(300)).await;
         while cache.next().await.is_some() {}
         assert!(cache.expirations.is_empty());
         assert!(cache.topic_msgs.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_max_timeout_and_capacity() {
+        let mut cache = GossipCache::builder()
+            .beacon_block_timeout(Duration::from_secs(12))
+            .build();
+        let block_topic = GossipTopic::new(
+            GossipKind::BeaconBlock,
+            crate::types::GossipEncoding::SSZSnappy,
+            [0u8; 4],
+        );
+        let attestation_topic = GossipTopic::new(
+            GossipKind::Attestation(1u64.into()),
+            crate::types::GossipEncoding::SSZSnappy,
+            [0u8; 4],
+        );
+
+        // Kinds without a timeout and messages whose deadline has passed are not cached.
+        assert!(!cache.insert(attestation_topic, vec![], None));
+        assert!(!cache.insert(block_topic.clone(), vec![], Some(Duration::ZERO)));
+
+        // A block is only retried until its deadline, even though its kind's timeout is longer.
+        assert!(cache.insert(
+            block_topic.clone(),
+            vec![],
+            Some(Duration::from_millis(100))
+        ));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(cache.next().await, Some(Ok(block_topic.clone())));
+        assert!(cache.topic_msgs.is_empty());
+
+        // The cache is bounded.
+        for i in 0..MAX_CACHED_MESSAGES {
+            assert!(cache.insert(block_topic.clone(), i.to_le_bytes().to_vec(), None));
+        }
+        assert!(!cache.insert(block_topic.clone(), vec![], None));
+        // Existing messages can still be refreshed.
+        assert!(cache.insert(block_topic, 0usize.to_le_bytes().to_vec(), None));
+    }
 }
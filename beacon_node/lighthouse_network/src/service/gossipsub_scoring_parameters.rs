@@ -4,6 +4,7 @@ use libp2p::gossipsub::{
     Config as GossipsubConfig, IdentTopic as Topic, PeerScoreParams, PeerScoreThresholds,
     TopicScoreParams,
 };
+use serde_derive::{Deserialize, Serialize};
 use std::cmp::max;
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -35,11 +36,104 @@ pub fn lighthouse_gossip_thresholds() -> PeerScoreThresholds {
     }
 }
 
+/// The topic kinds whose weight may be overridden by `GossipsubScoreOverrides`.
+const OVERRIDABLE_TOPIC_WEIGHTS: [&str; 6] = [
+    "beacon_block",
+    "beacon_aggregate_and_proof",
+    "beacon_attestation",
+    "voluntary_exit",
+    "proposer_slashing",
+    "attester_slashing",
+];
+
+/// Overrides of the gossipsub peer score thresholds and topic weights, for network
+/// experimentation.
+///
+/// The `beacon_attestation` topic weight is the total weight of all attestation subnets.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GossipsubScoreOverrides {
+    pub gossip_threshold: Option<f64>,
+    pub publish_threshold: Option<f64>,
+    pub graylist_threshold: Option<f64>,
+    pub accept_px_threshold: Option<f64>,
+    pub opportunistic_graft_threshold: Option<f64>,
+    #[serde(default)]
+    pub topic_weights: HashMap<String, f64>,
+}
+
+impl GossipsubScoreOverrides {
+    /// Returns the peer score thresholds with the overrides applied.
+    pub fn thresholds(&self) -> PeerScoreThresholds {
+        let defaults = lighthouse_gossip_thresholds();
+        PeerScoreThresholds {
+            gossip_threshold: self.gossip_threshold.unwrap_or(defaults.gossip_threshold),
+            publish_threshold: self.publish_threshold.unwrap_or(defaults.publish_threshold),
+            graylist_threshold: self
+                .graylist_threshold
+                .unwrap_or(defaults.graylist_threshold),
+            accept_px_threshold: self
+                .accept_px_threshold
+                .unwrap_or(defaults.accept_px_threshold),
+            opportunistic_graft_threshold: self
+                .opportunistic_graft_threshold
+                .unwrap_or(defaults.opportunistic_graft_threshold),
+        }
+    }
+
+    /// Returns an error if the overrides would produce an unusable set of score parameters.
+    pub fn validate(&self) -> Result<(), String> {
+        let thresholds = self.thresholds();
+        let all_thresholds = [
+            thresholds.gossip_threshold,
+            thresholds.publish_threshold,
+            thresholds.graylist_threshold,
+            thresholds.accept_px_threshold,
+            thresholds.opportunistic_graft_threshold,
+        ];
+        if all_thresholds
+            .iter()
+            .any(|threshold| !threshold.is_finite())
+        {
+            return Err("Gossipsub score thresholds must be finite".to_string());
+        }
+        thresholds
+            .validate()
+            .map_err(|e| format!("Invalid gossipsub score thresholds: {}", e))?;
+
+        for (topic, weight) in &self.topic_weights {
+            if !OVERRIDABLE_TOPIC_WEIGHTS.contains(&topic.as_str()) {
+                return Err(format!(
+                    "Unknown gossipsub topic weight {:?}, expected one of {:?}",
+                    topic, OVERRIDABLE_TOPIC_WEIGHTS
+                ));
+            }
+            // Invalid message penalties are scaled by the inverse of the topic weight.
+            if !weight.is_finite() || *weight <= 0.0 {
+                return Err(format!(
+                    "Gossipsub topic weight for {} must be positive, got {}",
+                    topic, weight
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn topic_weight(&self, topic: &str, default: f64) -> f64 {
+        self.topic_weights.get(topic).copied().unwrap_or(default)
+    }
+}
+
 pub struct PeerScoreSettings<TSpec: EthSpec> {
     slot: Duration,
     epoch: Duration,
 
+    beacon_block_weight: f64,
+    beacon_aggregate_proof_weight: f64,
     beacon_attestation_subnet_weight: f64,
+    voluntary_exit_weight: f64,
+    proposer_slashing_weight: f64,
+    attester_slashing_weight: f64,
     max_positive_score: f64,
 
     decay_interval: Duration,
@@ -54,21 +148,39 @@ pub struct PeerScoreSettings<TSpec: EthSpec> {
 }
 
 impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
-    pub fn new(chain_spec: &ChainSpec, gs_config: &GossipsubConfig) -> PeerScoreSettings<TSpec> {
+    pub fn new(
+        chain_spec: &ChainSpec,
+        gs_config: &GossipsubConfig,
+        overrides: &GossipsubScoreOverrides,
+    ) -> PeerScoreSettings<TSpec> {
         let slot = Duration::from_secs(chain_spec.seconds_per_slot);
-        let beacon_attestation_subnet_weight = 1.0 / chain_spec.attestation_subnet_count as f64;
+        let beacon_block_weight = overrides.topic_weight("beacon_block", BEACON_BLOCK_WEIGHT);
+        let beacon_aggregate_proof_weight =
+            overrides.topic_weight("beacon_aggregate_and_proof", BEACON_AGGREGATE_PROOF_WEIGHT);
+        let beacon_attestation_subnet_weight = overrides.topic_weight("beacon_attestation", 1.0)
+            / chain_spec.attestation_subnet_count as f64;
+        let voluntary_exit_weight = overrides.topic_weight("voluntary_exit", VOLUNTARY_EXIT_WEIGHT);
+        let proposer_slashing_weight =
+            overrides.topic_weight("proposer_slashing", PROPOSER_SLASHING_WEIGHT);
+        let attester_slashing_weight =
+            overrides.topic_weight("attester_slashing", ATTESTER_SLASHING_WEIGHT);
         let max_positive_score = (MAX_IN_MESH_SCORE + MAX_FIRST_MESSAGE_DELIVERIES_SCORE)
-            * (BEACON_BLOCK_WEIGHT
-                + BEACON_AGGREGATE_PROOF_WEIGHT
+            * (beacon_block_weight
+                + beacon_aggregate_proof_weight
                 + beacon_attestation_subnet_weight * chain_spec.attestation_subnet_count as f64
-                + VOLUNTARY_EXIT_WEIGHT
-                + PROPOSER_SLASHING_WEIGHT
-                + ATTESTER_SLASHING_WEIGHT);
+                + voluntary_exit_weight
+                + proposer_slashing_weight
+                + attester_slashing_weight);
 
         PeerScoreSettings {
             slot,
             epoch: slot * TSpec::slots_per_epoch() as u32,
+            beacon_block_weight,
+            beacon_aggregate_proof_weight,
             beacon_attestation_subnet_weight,
+            voluntary_exit_weight,
+            proposer_slashing_weight,
+            attester_slashing_weight,
             max_positive_score,
             decay_interval: max(Duration::from_secs(1), slot),
             decay_to_zero: 0.01,
@@ -81,6 +193,11 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
         }
     }
 
+    /// Returns the cap on the positive contribution of topics to a peer's score.
+    pub fn topic_score_cap(&self) -> f64 {
+        self.max_positive_score * 0.5
+    }
+
     pub fn get_peer_score_params(
         &self,
         active_validators: usize,
@@ -105,7 +222,7 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
         ) - params.behaviour_penalty_threshold;
         params.behaviour_penalty_weight = thresholds.gossip_threshold / target_value.powi(2);
 
-        params.topic_score_cap = self.max_positive_score * 0.5;
+        params.topic_score_cap = self.topic_score_cap();
         params.ip_colocation_factor_weight = -params.topic_score_cap;
 
         params.topics = HashMap::new();
@@ -121,7 +238,7 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
             get_hash(GossipKind::VoluntaryExit),
             Self::get_topic_params(
                 self,
-                VOLUNTARY_EXIT_WEIGHT,
+                self.voluntary_exit_weight,
                 4.0 / TSpec::slots_per_epoch() as f64,
                 self.epoch * 100,
                 None,
@@ -131,7 +248,7 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
             get_hash(GossipKind::AttesterSlashing),
             Self::get_topic_params(
                 self,
-                ATTESTER_SLASHING_WEIGHT,
+                self.attester_slashing_weight,
                 1.0 / 5.0 / TSpec::slots_per_epoch() as f64,
                 self.epoch * 100,
                 None,
@@ -141,7 +258,7 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
             get_hash(GossipKind::ProposerSlashing),
             Self::get_topic_params(
                 self,
-                PROPOSER_SLASHING_WEIGHT,
+                self.proposer_slashing_weight,
                 1.0 / 5.0 / TSpec::slots_per_epoch() as f64,
                 self.epoch * 100,
                 None,
@@ -183,7 +300,7 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
 
         let beacon_block_params = Self::get_topic_params(
             self,
-            BEACON_BLOCK_WEIGHT,
+            self.beacon_block_weight,
             1.0,
             self.epoch * 20,
             Some((TSpec::slots_per_epoch() * 5, 3.0, self.epoch, current_slot)),
@@ -191,7 +308,7 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
 
         let beacon_aggregate_proof_params = Self::get_topic_params(
             self,
-            BEACON_AGGREGATE_PROOF_WEIGHT,
+            self.beacon_aggregate_proof_weight,
             aggregators_per_slot,
             self.epoch,
             Some((TSpec::slots_per_epoch() * 2, 4.0, self.epoch, current_slot)),
@@ -358,3 +475,52 @@ impl<TSpec: EthSpec> PeerScoreSettings<TSpec> {
         t_params
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_score_overrides() {
+        assert!(GossipsubScoreOverrides::default().validate().is_ok());
+        assert_eq!(
+            GossipsubScoreOverrides::default()
+                .thresholds()
+                .graylist_threshold,
+            GREYLIST_THRESHOLD
+        );
+
+        let valid = GossipsubScoreOverrides {
+            graylist_threshold: Some(-20000.0),
+            topic_weights: [("beacon_attestation".to_string(), 2.0)].into(),
+            ..Default::default()
+        };
+        assert!(valid.validate().is_ok());
+
+        let invalid = [
+            GossipsubScoreOverrides {
+                gossip_threshold: Some(1.0),
+                ..Default::default()
+            },
+            GossipsubScoreOverrides {
+                graylist_threshold: Some(-1.0),
+                ..Default::default()
+            },
+            GossipsubScoreOverrides {
+                accept_px_threshold: Some(f64::NAN),
+                ..Default::default()
+            },
+            GossipsubScoreOverrides {
+                topic_weights: [("beacon_block".to_string(), 0.0)].into(),
+                ..Default::default()
+            },
+            GossipsubScoreOverrides {
+                topic_weights: [("unknown_topic".to_string(), 1.0)].into(),
+                ..Default::default()
+            },
+        ];
+        for overrides in invalid {
+            assert!(overrides.validate().is_err(), "{:?}", overrides);
+        }
+    }
+}
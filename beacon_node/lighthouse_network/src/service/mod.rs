@@ -6,7 +6,7 @@ use crate::discovery::{
 };
 use crate::peer_manager::{
     config::Config as PeerManagerCfg, peerdb::score::PeerAction, peerdb::score::ReportSource,
-    ConnectionDirection, PeerManager, PeerManagerEvent,
+    ConnectionDirection, GossipTopicEvents, PeerManager, PeerManagerEvent,
 };
 use crate::peer_manager::{MIN_OUTBOUND_ONLY_FACTOR, PEER_EXCESS_FACTOR, PRIORITY_PEER_EXCESS};
 use crate::rpc::methods::MetadataRequest;
@@ -22,7 +22,7 @@ use crate::Eth2Enr;
 use crate::{error, metrics, Enr, NetworkGlobals, PubsubMessage, TopicHash};
 use api_types::{PeerRequestId, Request, RequestId, Response};
 use futures::stream::StreamExt;
-use gossipsub_scoring_parameters::{lighthouse_gossip_thresholds, PeerScoreSettings};
+use gossipsub_scoring_parameters::PeerScoreSettings;
 use libp2p::bandwidth::BandwidthSinks;
 use libp2p::gossipsub::{
     self, IdentTopic as Topic, MessageAcceptance, MessageAuthenticity, MessageId, PublishError,
@@ -31,12 +31,15 @@ use libp2p::identify;
 use libp2p::multiaddr::{Multiaddr, Protocol as MProtocol};
 use libp2p::swarm::{Swarm, SwarmBuilder, SwarmEvent};
 use libp2p::PeerId;
+use lru::LruCache;
 use slog::{crit, debug, info, o, trace, warn};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::pin::Pin;
 use std::{
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 use types::ForkName;
 use types::{
@@ -53,6 +56,12 @@ pub mod utils;
 pub const TARGET_SUBNET_PEERS: usize = 6;
 
 const MAX_IDENTIFY_ADDRESSES: usize = 10;
+/// The interval at which messages which failed to publish due to insufficient peers are retried,
+/// in addition to retrying them whenever a peer subscribes to their topic.
+const GOSSIP_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+/// The number of received gossipsub messages for which we remember the topic until they are
+/// validated, so that rejected messages can be attributed to a topic.
+const GOSSIP_MESSAGE_TOPICS_CACHE_SIZE: usize = 16_384;
 
 /// The types of events than can be obtained from polling the behaviour.
 #[derive(Debug)]
@@ -121,7 +130,16 @@ pub struct Network<AppReqId: ReqId, TSpec: EthSpec> {
     score_settings: PeerScoreSettings<TSpec>,
     /// The interval for updating gossipsub scores
     update_gossipsub_scores: tokio::time::Interval,
+    /// Messages which failed to publish due to insufficient peers, to be retried.
     gossip_cache: GossipCache,
+    /// The interval for retrying messages in the `gossip_cache`.
+    gossip_retry_interval: tokio::time::Interval,
+    /// The topics of received gossipsub messages which are awaiting validation.
+    gossip_message_topics: LruCache<MessageId, TopicHash>,
+    /// The gossipsub messages observed from each peer since the last score update, which are
+    /// applied to the peer manager on the next update rather than locking the peer db for every
+    /// message.
+    gossip_topic_events: HashMap<PeerId, HashMap<TopicHash, GossipTopicEvents>>,
     /// The bandwidth logger for the underlying libp2p transport.
     pub bandwidth: Arc<BandwidthSinks>,
     /// This node's PeerId.
@@ -174,7 +192,11 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
             .eth2()
             .expect("Local ENR must have a fork id");
 
-        let score_settings = PeerScoreSettings::new(ctx.chain_spec, &config.gs_config);
+        let score_settings = PeerScoreSettings::new(
+            ctx.chain_spec,
+            &config.gs_config,
+            &config.gossipsub_score_overrides,
+        );
 
         let gossip_cache = {
             let slot_duration = std::time::Duration::from_secs(ctx.chain_spec.seconds_per_slot);
@@ -185,7 +207,7 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
             GossipCache::builder()
                 .beacon_block_timeout(slot_duration)
                 .aggregates_timeout(half_epoch)
-                .attestation_timeout(half_epoch)
+                .attestation_timeout(slot_duration / 2)
                 .voluntary_exit_timeout(half_epoch * 2)
                 .proposer_slashing_timeout(half_epoch * 2)
                 .attester_slashing_timeout(half_epoch * 2)
@@ -198,7 +220,7 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
         let local_peer_id = network_globals.local_peer_id();
 
         let (gossipsub, update_gossipsub_scores) = {
-            let thresholds = lighthouse_gossip_thresholds();
+            let thresholds = config.gossipsub_score_overrides.thresholds();
 
             // Prepare scoring parameters
             let params = {
@@ -259,6 +281,11 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                 .with_peer_score(params, thresholds)
                 .expect("Valid score params and thresholds");
 
+            // Always forward messages to trusted peers, regardless of mesh limits.
+            for trusted_peer in &config.trusted_peers {
+                gossipsub.add_explicit_peer(&PeerId::from(trusted_peer.clone()));
+            }
+
             (gossipsub, update_gossipsub_scores)
         };
 
@@ -311,6 +338,7 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                 discovery_enabled: !config.disable_discovery,
                 metrics_enabled: config.metrics_enabled,
                 target_peer_count: config.target_peers,
+                trusted_peer_addresses: config.trusted_peer_addresses.clone(),
                 ..Default::default()
             };
             PeerManager::new(peer_manager_cfg, network_globals.clone(), &log)?
@@ -390,6 +418,9 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
             score_settings,
             update_gossipsub_scores,
             gossip_cache,
+            gossip_retry_interval: tokio::time::interval(GOSSIP_RETRY_INTERVAL),
+            gossip_message_topics: LruCache::new(GOSSIP_MESSAGE_TOPICS_CACHE_SIZE),
+            gossip_topic_events: HashMap::new(),
             bandwidth,
             local_peer_id,
             log,
@@ -671,6 +702,18 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
 
     /// Publishes a list of messages on the pubsub (gossipsub) behaviour, choosing the encoding.
     pub fn publish(&mut self, messages: Vec<PubsubMessage<TSpec>>) {
+        self.publish_with_retry_deadline(messages, None)
+    }
+
+    /// Publishes a list of messages on the pubsub (gossipsub) behaviour, choosing the encoding.
+    ///
+    /// Messages which fail to publish due to insufficient peers are retried until the timeout for
+    /// their topic kind, or until `retry_deadline` has elapsed if that is sooner.
+    pub fn publish_with_retry_deadline(
+        &mut self,
+        messages: Vec<PubsubMessage<TSpec>>,
+        retry_deadline: Option<Duration>,
+    ) {
         for message in messages {
             for topic in message.topics(GossipEncoding::default(), self.enr_fork_id.fork_digest) {
                 let message_data = message.encode(GossipEncoding::default());
@@ -701,13 +744,82 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                     }
 
                     if let PublishError::InsufficientPeers = e {
-                        self.gossip_cache.insert(topic, message_data);
+                        self.gossip_cache
+                            .insert(topic, message_data, retry_deadline);
                     }
                 }
             }
         }
     }
 
+    /// Re-publish the messages which previously failed to publish on `topic`.
+    fn publish_cached_messages(&mut self, topic: &GossipTopic) {
+        if let Some(msgs) = self.gossip_cache.retrieve(topic) {
+            for data in msgs {
+                let topic_str: &str = topic.kind().as_ref();
+                match self
+                    .swarm
+                    .behaviour_mut()
+                    .gossipsub
+                    .publish(Topic::from(topic.clone()), data)
+                {
+                    Ok(_) => {
+                        warn!(self.log, "Gossip message published on retry"; "topic" => topic_str);
+                        if let Some(v) = metrics::get_int_counter(
+                            &metrics::GOSSIP_LATE_PUBLISH_PER_TOPIC_KIND,
+                            &[topic_str],
+                        ) {
+                            v.inc()
+                        };
+                    }
+                    Err(e) => {
+                        warn!(self.log, "Gossip message publish failed on retry"; "topic" => topic_str, "error" => %e);
+                        if let Some(v) = metrics::get_int_counter(
+                            &metrics::GOSSIP_FAILED_LATE_PUBLISH_PER_TOPIC_KIND,
+                            &[topic_str],
+                        ) {
+                            v.inc()
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    /// Re-publish cached messages on any topics which now have subscribed peers.
+    fn retry_cached_messages(&mut self) {
+        let topics_with_peers = {
+            let gossipsub = &self.swarm.behaviour().gossipsub;
+            self.gossip_cache
+                .topics()
+                .filter(|topic| {
+                    let topic_hash = Topic::from((*topic).clone()).hash();
+                    gossipsub
+                        .all_peers()
+                        .any(|(_, topics)| topics.contains(&&topic_hash))
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        for topic in topics_with_peers {
+            self.publish_cached_messages(&topic);
+        }
+    }
+
+    /// Returns the gossipsub messages observed from `peer_id` on `topic` since the last score
+    /// update.
+    fn gossip_topic_events(
+        &mut self,
+        peer_id: &PeerId,
+        topic: TopicHash,
+    ) -> &mut GossipTopicEvents {
+        self.gossip_topic_events
+            .entry(*peer_id)
+            .or_default()
+            .entry(topic)
+            .or_default()
+    }
+
     /// Informs the gossipsub about the result of a message validation.
     /// If the message is valid it will get propagated by gossipsub.
     pub fn report_message_validation_result(
@@ -716,6 +828,13 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
         message_id: MessageId,
         validation_result: MessageAcceptance,
     ) {
+        if let Some(topic) = self.gossip_message_topics.pop(&message_id) {
+            if matches!(validation_result, MessageAcceptance::Reject) {
+                self.gossip_topic_events(propagation_source, topic)
+                    .invalid_messages += 1;
+            }
+        }
+
         if let Some(result) = match validation_result {
             MessageAcceptance::Accept => None,
             MessageAcceptance::Ignore => Some("ignore"),
@@ -1098,6 +1217,8 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                 match PubsubMessage::decode(&gs_msg.topic, &gs_msg.data, &self.fork_context) {
                     Err(e) => {
                         debug!(self.log, "Could not decode gossipsub message"; "topic" => ?gs_msg.topic,"error" => e);
+                        self.gossip_topic_events(&propagation_source, gs_msg.topic.clone())
+                            .invalid_messages += 1;
                         //reject the message
                         if let Err(e) = self.gossipsub_mut().report_message_validation_result(
                             &id,
@@ -1108,6 +1229,11 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                         }
                     }
                     Ok(msg) => {
+                        // Gossipsub only notifies us of the first delivery of each message.
+                        self.gossip_topic_events(&propagation_source, gs_msg.topic.clone())
+                            .first_message_deliveries += 1;
+                        self.gossip_message_topics
+                            .put(id.clone(), gs_msg.topic.clone());
                         // Notify the network
                         return Some(NetworkEvent::PubsubMessage {
                             id,
@@ -1127,36 +1253,7 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
                             .add_subscription(&peer_id, subnet_id);
                     }
                     // Try to send the cached messages for this topic
-                    if let Some(msgs) = self.gossip_cache.retrieve(&topic) {
-                        for data in msgs {
-                            let topic_str: &str = topic.kind().as_ref();
-                            match self
-                                .swarm
-                                .behaviour_mut()
-                                .gossipsub
-                                .publish(Topic::from(topic.clone()), data)
-                            {
-                                Ok(_) => {
-                                    warn!(self.log, "Gossip message published on retry"; "topic" => topic_str);
-                                    if let Some(v) = metrics::get_int_counter(
-                                        &metrics::GOSSIP_LATE_PUBLISH_PER_TOPIC_KIND,
-                                        &[topic_str],
-                                    ) {
-                                        v.inc()
-                                    };
-                                }
-                                Err(e) => {
-                                    warn!(self.log, "Gossip message publish failed on retry"; "topic" => topic_str, "error" => %e);
-                                    if let Some(v) = metrics::get_int_counter(
-                                        &metrics::GOSSIP_FAILED_LATE_PUBLISH_PER_TOPIC_KIND,
-                                        &[topic_str],
-                                    ) {
-                                        v.inc()
-                                    };
-                                }
-                            }
-                        }
-                    }
+                    self.publish_cached_messages(&topic);
                 }
             }
             gossipsub::Event::Unsubscribed { peer_id, topic } => {
@@ -1561,8 +1658,19 @@ impl<AppReqId: ReqId, TSpec: EthSpec> Network<AppReqId, TSpec> {
 
         // perform gossipsub score updates when necessary
         while self.update_gossipsub_scores.poll_tick(cx).is_ready() {
+            let topic_events = std::mem::take(&mut self.gossip_topic_events);
+            let topic_score_cap = self.score_settings.topic_score_cap();
             let this = self.swarm.behaviour_mut();
-            this.peer_manager.update_gossipsub_scores(&this.gossipsub);
+            this.peer_manager.update_gossipsub_scores(
+                &this.gossipsub,
+                topic_events,
+                topic_score_cap,
+            );
+        }
+
+        // retry messages which failed to publish, in case peers have since become available
+        while self.gossip_retry_interval.poll_tick(cx).is_ready() {
+            self.retry_cached_messages();
         }
 
         // poll the gossipsub cache to clear expired messages
@@ -1,12 +1,13 @@
 //! A collection of variables that are accessible outside of the network thread itself.
 use crate::peer_manager::peerdb::PeerDB;
 use crate::rpc::{MetaData, MetaDataV2};
-use crate::types::{BackFillState, SyncState};
+use crate::types::{BackFillState, NatMappings, SyncState};
 use crate::Client;
 use crate::EnrExt;
-use crate::{Enr, GossipTopic, Multiaddr, PeerId};
+use crate::{Enr, GossipTopic, Multiaddr, PeerId, Subnet};
+use libp2p::multiaddr::Protocol;
 use parking_lot::RwLock;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use types::EthSpec;
 
 pub struct NetworkGlobals<TSpec: EthSpec> {
@@ -26,6 +27,8 @@ pub struct NetworkGlobals<TSpec: EthSpec> {
     pub sync_state: RwLock<SyncState>,
     /// The current state of the backfill sync.
     pub backfill_state: RwLock<BackFillState>,
+    /// The external port mappings established via NAT traversal, if any.
+    pub nat_mappings: RwLock<Option<NatMappings>>,
 }
 
 impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
@@ -45,9 +48,26 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
             backfill_state: RwLock::new(BackFillState::NotRequired),
+            nat_mappings: RwLock::new(None),
         }
     }
 
+    /// Returns the number of good peers on each attestation and sync committee subnet that we are
+    /// subscribed to via gossipsub.
+    pub fn subscribed_subnet_peer_counts(&self) -> HashMap<Subnet, usize> {
+        let subnets = self
+            .gossipsub_subscriptions
+            .read()
+            .iter()
+            .filter_map(GossipTopic::subnet_id)
+            .collect::<HashSet<_>>();
+        let peers = self.peers.read();
+        subnets
+            .into_iter()
+            .map(|subnet| (subnet, peers.good_peers_on_subnet(subnet).count()))
+            .collect()
+    }
+
     /// Returns the local ENR from the underlying Discv5 behaviour that external peers may connect
     /// to.
     pub fn local_enr(&self) -> Enr {
@@ -64,6 +84,27 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
         self.listen_multiaddrs.read().clone()
     }
 
+    /// Returns the `Multiaddr`s that peers may use to reach us, with our `PeerId` appended.
+    ///
+    /// This includes the TCP addresses advertised in our ENR for both IPv4 and IPv6, followed by
+    /// any listening addresses which are not advertised.
+    pub fn p2p_multiaddrs(&self) -> Vec<Multiaddr> {
+        let peer_id = self.local_peer_id();
+        let mut multiaddrs = self.local_enr().multiaddr_p2p_tcp();
+        for mut multiaddr in self.listen_multiaddrs() {
+            if !multiaddr
+                .iter()
+                .any(|proto| matches!(proto, Protocol::P2p(_)))
+            {
+                multiaddr.push(Protocol::P2p(peer_id));
+            }
+            if !multiaddrs.contains(&multiaddr) {
+                multiaddrs.push(multiaddr);
+            }
+        }
+        multiaddrs
+    }
+
     /// Returns the number of libp2p connected peers.
     pub fn connected_peers(&self) -> usize {
         self.peers.read().connected_peer_ids().count()
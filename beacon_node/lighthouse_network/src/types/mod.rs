@@ -1,5 +1,6 @@
 pub mod error;
 mod globals;
+mod nat;
 mod pubsub;
 mod subnet;
 mod sync_state;
@@ -13,6 +14,7 @@ pub type EnrSyncCommitteeBitfield<T> = BitVector<<T as EthSpec>::SyncCommitteeSu
 pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 
 pub use globals::NetworkGlobals;
+pub use nat::{NatMappings, NatProtocol, NatState, PortMapping};
 pub use pubsub::{PubsubMessage, SnappyTransform};
 pub use subnet::{Subnet, SubnetDiscovery};
 pub use sync_state::{BackFillState, SyncState};
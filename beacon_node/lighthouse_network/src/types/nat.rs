@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+/// The protocol with which external port mappings were established.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NatProtocol {
+    Upnp,
+    NatPmp,
+}
+
+/// A port mapping established on the gateway.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortMapping {
+    /// The local port which is mapped.
+    pub internal_port: u16,
+    /// The port on the external address of the gateway.
+    pub external_port: u16,
+    /// The unix timestamp (in seconds) at which the gateway drops the mapping unless it is
+    /// renewed, or `None` if the mapping does not expire.
+    pub lease_expiry: Option<u64>,
+}
+
+/// The external port mappings established via NAT traversal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NatMappings {
+    /// The protocol which succeeded in establishing the mappings.
+    pub protocol: NatProtocol,
+    /// The external IP address reported by the gateway, if any.
+    pub external_ip: Option<Ipv4Addr>,
+    /// A TCP port mapping for libp2p.
+    pub tcp: Option<PortMapping>,
+    /// A UDP port mapping for the QUIC libp2p transport.
+    pub udp_quic: Option<PortMapping>,
+    /// A UDP port mapping for discv5.
+    pub udp_disc: Option<PortMapping>,
+}
+
+impl NatMappings {
+    /// Returns true if at least one port is mapped.
+    pub fn is_some(&self) -> bool {
+        self.tcp.is_some() || self.udp_quic.is_some() || self.udp_disc.is_some()
+    }
+
+    /// Iterator over the UDP port mappings.
+    pub fn udp_mappings(&self) -> impl Iterator<Item = &PortMapping> {
+        self.udp_quic.iter().chain(self.udp_disc.iter())
+    }
+
+    /// Returns the earliest lease expiry of all mappings, if any of them expire.
+    pub fn earliest_lease_expiry(&self) -> Option<u64> {
+        self.tcp
+            .iter()
+            .chain(self.udp_mappings())
+            .filter_map(|mapping| mapping.lease_expiry)
+            .min()
+    }
+}
+
+/// The state of NAT traversal, as reported by the `/lighthouse/nat` endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NatState {
+    /// Whether we have received incoming connections, implying our ports are reachable.
+    pub open: bool,
+    /// The port mappings established on the gateway, if any.
+    pub mappings: Option<NatMappings>,
+}
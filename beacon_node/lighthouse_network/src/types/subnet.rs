@@ -14,6 +14,24 @@ pub enum Subnet {
     SyncCommittee(SyncSubnetId),
 }
 
+impl Subnet {
+    /// Returns a short name for the kind of subnet, for use in logs, metrics and the HTTP API.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Subnet::Attestation(_) => "attestation",
+            Subnet::SyncCommittee(_) => "sync_committee",
+        }
+    }
+
+    /// Returns the index of the subnet amongst subnets of the same kind.
+    pub fn id(&self) -> u64 {
+        match self {
+            Subnet::Attestation(subnet_id) => **subnet_id,
+            Subnet::SyncCommittee(subnet_id) => **subnet_id,
+        }
+    }
+}
+
 /// A subnet to discover peers on along with the instant after which it's no longer useful.
 #[derive(Debug, Clone)]
 pub struct SubnetDiscovery {
@@ -4,7 +4,7 @@ use lighthouse_network::service::Network as LibP2PService;
 use lighthouse_network::Enr;
 use lighthouse_network::EnrExt;
 use lighthouse_network::Multiaddr;
-use lighthouse_network::{NetworkConfig, NetworkEvent};
+use lighthouse_network::{NetworkConfig, NetworkEvent, NetworkGlobals};
 use slog::{debug, error, o, Drain};
 use std::sync::Arc;
 use std::sync::Weak;
@@ -39,7 +39,19 @@ pub fn fork_context(fork_name: ForkName) -> ForkContext {
     ForkContext::new::<E>(current_slot, Hash256::zero(), &chain_spec)
 }
 
-pub struct Libp2pInstance(LibP2PService<ReqId, E>, exit_future::Signal);
+pub struct Libp2pInstance(
+    LibP2PService<ReqId, E>,
+    exit_future::Signal,
+    Arc<NetworkGlobals<E>>,
+);
+
+impl Libp2pInstance {
+    /// The globals of the instance, which hold its view of its peers.
+    #[allow(dead_code)]
+    pub fn network_globals(&self) -> &NetworkGlobals<E> {
+        &self.2
+    }
+}
 
 impl std::ops::Deref for Libp2pInstance {
     type Target = LibP2PService<ReqId, E>;
@@ -92,6 +104,26 @@ pub fn build_config(mut boot_nodes: Vec<Enr>) -> NetworkConfig {
     config
 }
 
+/// Returns a config for a node which only listens on, and advertises, the IPv6 loopback address.
+#[allow(dead_code)]
+pub fn build_ipv6_config(boot_nodes: Vec<Enr>) -> NetworkConfig {
+    let mut config = build_config(boot_nodes);
+
+    // The ENR advertises the listening ports, so they must be known in advance.
+    let tcp_port = unused_port::unused_tcp6_port().unwrap();
+    let disc_port = unused_port::unused_udp6_port().unwrap();
+    let quic_port = unused_port::unused_udp6_port().unwrap();
+    config.set_ipv6_listening_address(
+        std::net::Ipv6Addr::LOCALHOST,
+        tcp_port,
+        disc_port,
+        quic_port,
+    );
+    config.enr_address = (None, Some(std::net::Ipv6Addr::LOCALHOST));
+    config.enr_udp6_port = Some(disc_port);
+    config
+}
+
 pub async fn build_libp2p_instance(
     rt: Weak<Runtime>,
     boot_nodes: Vec<Enr>,
@@ -100,6 +132,16 @@ pub async fn build_libp2p_instance(
     spec: &ChainSpec,
 ) -> Libp2pInstance {
     let config = build_config(boot_nodes);
+    build_libp2p_instance_with_config(rt, config, log, fork_name, spec).await
+}
+
+pub async fn build_libp2p_instance_with_config(
+    rt: Weak<Runtime>,
+    config: NetworkConfig,
+    log: slog::Logger,
+    fork_name: ForkName,
+    spec: &ChainSpec,
+) -> Libp2pInstance {
     // launch libp2p service
 
     let (signal, exit) = exit_future::signal();
@@ -112,13 +154,10 @@ pub async fn build_libp2p_instance(
         chain_spec: spec,
         gossipsub_registry: None,
     };
-    Libp2pInstance(
-        LibP2PService::new(executor, libp2p_context, &log)
-            .await
-            .expect("should build libp2p instance")
-            .0,
-        signal,
-    )
+    let (service, network_globals) = LibP2PService::new(executor, libp2p_context, &log)
+        .await
+        .expect("should build libp2p instance");
+    Libp2pInstance(service, signal, network_globals)
 }
 
 #[allow(dead_code)]
@@ -141,12 +180,27 @@ pub async fn build_node_pair(
     fork_name: ForkName,
     spec: &ChainSpec,
     protocol: Protocol,
+) -> (Libp2pInstance, Libp2pInstance) {
+    build_node_pair_with_receiver_config(rt, log, fork_name, spec, protocol, build_config(vec![]))
+        .await
+}
+
+// Constructs a pair of nodes as `build_node_pair` does, with the given config for the receiver.
+#[allow(dead_code)]
+pub async fn build_node_pair_with_receiver_config(
+    rt: Weak<Runtime>,
+    log: &slog::Logger,
+    fork_name: ForkName,
+    spec: &ChainSpec,
+    protocol: Protocol,
+    receiver_config: NetworkConfig,
 ) -> (Libp2pInstance, Libp2pInstance) {
     let sender_log = log.new(o!("who" => "sender"));
     let receiver_log = log.new(o!("who" => "receiver"));
 
     let mut sender = build_libp2p_instance(rt.clone(), vec![], sender_log, fork_name, spec).await;
-    let mut receiver = build_libp2p_instance(rt, vec![], receiver_log, fork_name, spec).await;
+    let mut receiver =
+        build_libp2p_instance_with_config(rt, receiver_config, receiver_log, fork_name, spec).await;
 
     // let the two nodes set up listeners
     let sender_fut = async {
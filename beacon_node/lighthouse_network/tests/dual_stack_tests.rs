@@ -0,0 +1,79 @@
+#![cfg(test)]
+
+mod common;
+
+use lighthouse_network::{EnrExt, NetworkEvent};
+use slog::{o, Level};
+use std::net::Ipv6Addr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio::time::sleep;
+use types::{EthSpec, ForkName, MinimalEthSpec};
+
+type E = MinimalEthSpec;
+
+// Tests that a node connects to a bootnode which is only reachable over IPv6.
+#[test]
+fn test_dial_ipv6_only_bootnode() {
+    // set up the logging. The level and enabled logging or not
+    let log_level = Level::Debug;
+    let enable_logging = false;
+
+    let rt = Arc::new(Runtime::new().unwrap());
+
+    let log = common::build_log(log_level, enable_logging);
+
+    let spec = E::default_spec();
+
+    rt.block_on(async {
+        let mut bootnode = common::build_libp2p_instance_with_config(
+            Arc::downgrade(&rt),
+            common::build_ipv6_config(vec![]),
+            log.new(o!("who" => "bootnode")),
+            ForkName::Base,
+            &spec,
+        )
+        .await;
+
+        // The bootnode only advertises an IPv6 address.
+        let bootnode_enr = common::get_enr(&bootnode);
+        assert_eq!(bootnode_enr.ip4(), None);
+        assert_eq!(bootnode_enr.ip6(), Some(Ipv6Addr::LOCALHOST));
+        assert!(bootnode_enr.tcp6().is_some());
+        let bootnode_peer_id = bootnode_enr.peer_id();
+
+        let mut node = common::build_libp2p_instance_with_config(
+            Arc::downgrade(&rt),
+            common::build_ipv6_config(vec![bootnode_enr]),
+            log.new(o!("who" => "node")),
+            ForkName::Base,
+            &spec,
+        )
+        .await;
+
+        // The node dials the bootnode on startup.
+        let node_future = async {
+            loop {
+                if let NetworkEvent::PeerConnectedOutgoing(peer_id) = node.next_event().await {
+                    assert_eq!(peer_id, bootnode_peer_id);
+                    return;
+                }
+            }
+        };
+
+        let bootnode_future = async {
+            loop {
+                bootnode.next_event().await;
+            }
+        };
+
+        tokio::select! {
+            _ = node_future => {}
+            _ = bootnode_future => {}
+            _ = sleep(Duration::from_secs(30)) => {
+                panic!("Future timed out");
+            }
+        }
+    })
+}
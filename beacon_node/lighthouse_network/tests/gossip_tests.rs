@@ -0,0 +1,93 @@
+#![cfg(test)]
+
+mod common;
+
+use common::Protocol;
+use lighthouse_network::types::GossipKind;
+use lighthouse_network::{NetworkEvent, PubsubMessage};
+use slog::{debug, Level};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio::time::sleep;
+use types::{
+    Epoch, EthSpec, ForkName, MinimalEthSpec, Signature, SignedVoluntaryExit, VoluntaryExit,
+};
+
+type E = MinimalEthSpec;
+
+// Tests publishing a gossip message between nodes connected over TCP
+#[test]
+fn test_tcp_gossip_publish() {
+    // set up the logging. The level and enabled logging or not
+    let log_level = Level::Debug;
+    let enable_logging = false;
+    gossip_publish_test(log_level, enable_logging, Protocol::Tcp);
+}
+
+// Tests publishing a gossip message between nodes connected only over QUIC
+#[test]
+fn test_quic_gossip_publish() {
+    // set up the logging. The level and enabled logging or not
+    let log_level = Level::Debug;
+    let enable_logging = false;
+    gossip_publish_test(log_level, enable_logging, Protocol::Quic);
+}
+
+/// Establishes a pair of nodes subscribed to the voluntary exit topic and checks that a message
+/// published by one node is received by the other.
+fn gossip_publish_test(log_level: Level, enable_logging: bool, protocol: Protocol) {
+    let log = common::build_log(log_level, enable_logging);
+
+    let rt = Arc::new(Runtime::new().unwrap());
+
+    let spec = E::default_spec();
+
+    rt.block_on(async {
+        // get sender/receiver
+        let (mut sender, mut receiver) =
+            common::build_node_pair(Arc::downgrade(&rt), &log, ForkName::Base, &spec, protocol)
+                .await;
+
+        assert!(sender.subscribe_kind(GossipKind::VoluntaryExit));
+        assert!(receiver.subscribe_kind(GossipKind::VoluntaryExit));
+
+        let exit = PubsubMessage::<E>::VoluntaryExit(Box::new(SignedVoluntaryExit {
+            message: VoluntaryExit {
+                epoch: Epoch::new(1),
+                validator_index: 0,
+            },
+            signature: Signature::empty(),
+        }));
+
+        // build the sender future
+        let sender_future = async {
+            loop {
+                if let NetworkEvent::PeerConnectedOutgoing(_) = sender.next_event().await {
+                    // The message is retried until the receiver's subscription is known.
+                    debug!(log, "Publishing message");
+                    sender.publish(vec![exit.clone()]);
+                }
+            }
+        };
+
+        // build the receiver future
+        let receiver_future = async {
+            loop {
+                if let NetworkEvent::PubsubMessage { message, .. } = receiver.next_event().await {
+                    debug!(log, "Receiver received message");
+                    assert_eq!(message, exit);
+                    return;
+                }
+            }
+        };
+
+        tokio::select! {
+            _ = sender_future => {}
+            _ = receiver_future => {}
+            _ = sleep(Duration::from_secs(30)) => {
+                panic!("Future timed out");
+            }
+        }
+    })
+}
@@ -0,0 +1,123 @@
+#![cfg(test)]
+
+mod common;
+
+use lighthouse_network::{Enr, EnrExt, NetworkConfig, NetworkEvent, PeerId};
+use slog::{info, o, Level};
+use std::net::Ipv4Addr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+use tokio::time::sleep;
+use types::{EthSpec, ForkName, MinimalEthSpec};
+
+type E = MinimalEthSpec;
+
+/// Returns a config for a node which can be found via discovery, which requires the ENR to
+/// advertise the listening ports.
+fn build_discoverable_config(boot_nodes: Vec<Enr>) -> NetworkConfig {
+    let mut config = common::build_config(boot_nodes);
+    let tcp_port = unused_port::unused_tcp4_port().unwrap();
+    let disc_port = unused_port::unused_udp4_port().unwrap();
+    let quic_port = unused_port::unused_udp4_port().unwrap();
+    config.set_ipv4_listening_address(Ipv4Addr::UNSPECIFIED, tcp_port, disc_port, quic_port);
+    config.enr_udp4_port = Some(disc_port);
+    config
+}
+
+/// Drives `node` until it has an outbound connection to `peer_id`, while driving `peer` in the
+/// background. Returns the time taken to connect.
+async fn time_to_connect(
+    node: &mut common::Libp2pInstance,
+    peer: &mut common::Libp2pInstance,
+    peer_id: PeerId,
+) -> Duration {
+    let start = Instant::now();
+    let node_future = async {
+        loop {
+            if let NetworkEvent::PeerConnectedOutgoing(connected) = node.next_event().await {
+                if connected == peer_id {
+                    return start.elapsed();
+                }
+            }
+        }
+    };
+    let peer_future = async {
+        loop {
+            peer.next_event().await;
+        }
+    };
+
+    tokio::select! {
+        elapsed = node_future => elapsed,
+        _ = peer_future => unreachable!(),
+        _ = sleep(Duration::from_secs(30)) => panic!("Future timed out"),
+    }
+}
+
+// Tests that a restarted node reconnects to the peers it persisted, and reports the time taken to
+// reach a peer with and without persisted peers.
+#[test]
+fn test_restart_with_persisted_peers() {
+    // set up the logging. The level and enabled logging or not
+    let log_level = Level::Info;
+    let enable_logging = false;
+
+    let rt = Arc::new(Runtime::new().unwrap());
+
+    let log = common::build_log(log_level, enable_logging);
+
+    let spec = E::default_spec();
+
+    rt.block_on(async {
+        let mut peer = common::build_libp2p_instance_with_config(
+            Arc::downgrade(&rt),
+            build_discoverable_config(vec![]),
+            log.new(o!("who" => "peer")),
+            ForkName::Base,
+            &spec,
+        )
+        .await;
+        let peer_enr = common::get_enr(&peer);
+        let peer_id = peer_enr.peer_id();
+
+        // Without persisted peers, the node has to find the peer via discovery from its boot
+        // nodes.
+        let mut node = common::build_libp2p_instance_with_config(
+            Arc::downgrade(&rt),
+            build_discoverable_config(vec![peer_enr]),
+            log.new(o!("who" => "node")),
+            ForkName::Base,
+            &spec,
+        )
+        .await;
+        let without_persisted_peers = time_to_connect(&mut node, &mut peer, peer_id).await;
+
+        // Persist the node's peers and restart it without any boot nodes.
+        let persisted_peers = node.peer_manager().persisted_peers();
+        assert_eq!(persisted_peers.len(), 1);
+        assert_eq!(persisted_peers[0].peer_id(), Some(peer_id));
+        drop(node);
+
+        let mut node = common::build_libp2p_instance(
+            Arc::downgrade(&rt),
+            vec![],
+            log.new(o!("who" => "restarted node")),
+            ForkName::Base,
+            &spec,
+        )
+        .await;
+        let enrs = node
+            .peer_manager_mut()
+            .restore_persisted_peers(persisted_peers, Duration::from_secs(3600));
+        assert_eq!(enrs.len(), 1);
+        let with_persisted_peers = time_to_connect(&mut node, &mut peer, peer_id).await;
+
+        info!(
+            log,
+            "Time to first peer";
+            "without_persisted_peers" => ?without_persisted_peers,
+            "with_persisted_peers" => ?with_persisted_peers,
+        );
+    })
+}
@@ -4,7 +4,9 @@ mod common;
 
 use common::Protocol;
 use lighthouse_network::rpc::methods::*;
-use lighthouse_network::{rpc::max_rpc_size, NetworkEvent, ReportSource, Request, Response};
+use lighthouse_network::{
+    rpc::max_rpc_size, EnrExt, NetworkEvent, ReportSource, Request, Response,
+};
 use slog::{debug, warn, Level};
 use ssz::Encode;
 use ssz_types::VariableList;
@@ -148,12 +150,24 @@ fn test_tcp_status_rpc() {
 
 // Tests a streamed BlocksByRange RPC Message
 #[test]
-#[allow(clippy::single_match)]
 fn test_tcp_blocks_by_range_chunked_rpc() {
     // set up the logging. The level and enabled logging or not
     let log_level = Level::Debug;
     let enable_logging = false;
+    blocks_by_range_chunked_rpc_test(log_level, enable_logging, Protocol::Tcp);
+}
 
+// Tests a streamed BlocksByRange RPC Message between nodes connected only over QUIC
+#[test]
+fn test_quic_blocks_by_range_chunked_rpc() {
+    // set up the logging. The level and enabled logging or not
+    let log_level = Level::Debug;
+    let enable_logging = false;
+    blocks_by_range_chunked_rpc_test(log_level, enable_logging, Protocol::Quic);
+}
+
+#[allow(clippy::single_match)]
+fn blocks_by_range_chunked_rpc_test(log_level: Level, enable_logging: bool, protocol: Protocol) {
     let messages_to_send = 6;
 
     let log = common::build_log(log_level, enable_logging);
@@ -164,14 +178,9 @@ fn test_tcp_blocks_by_range_chunked_rpc() {
 
     rt.block_on(async {
         // get sender/receiver
-        let (mut sender, mut receiver) = common::build_node_pair(
-            Arc::downgrade(&rt),
-            &log,
-            ForkName::Merge,
-            &spec,
-            Protocol::Tcp,
-        )
-        .await;
+        let (mut sender, mut receiver) =
+            common::build_node_pair(Arc::downgrade(&rt), &log, ForkName::Merge, &spec, protocol)
+                .await;
 
         // BlocksByRange Request
         let rpc_request = Request::BlocksByRange(BlocksByRangeRequest::new(0, messages_to_send));
@@ -368,6 +377,122 @@ fn test_tcp_blocks_by_range_over_limit() {
     })
 }
 
+// Tests that inbound BlocksByRange requests in excess of the receiver's quota are answered with a
+// `RateLimited` error, and that the receiver penalises the sender for them.
+#[test]
+#[allow(clippy::single_match)]
+fn test_tcp_blocks_by_range_inbound_rate_limited() {
+    // set up the logging. The level and enabled logging or not
+    let log_level = Level::Debug;
+    let enable_logging = false;
+
+    let log = common::build_log(log_level, enable_logging);
+    let rt = Arc::new(Runtime::new().unwrap());
+
+    let spec = E::default_spec();
+
+    rt.block_on(async {
+        // The receiver only accepts one BlocksByRange request per minute.
+        let mut receiver_config = common::build_config(vec![]);
+        receiver_config.inbound_rate_limiter_config =
+            Some("blocks_by_range_requests:1/60".parse().unwrap());
+        let (mut sender, mut receiver) = common::build_node_pair_with_receiver_config(
+            Arc::downgrade(&rt),
+            &log,
+            ForkName::Base,
+            &spec,
+            Protocol::Tcp,
+            receiver_config,
+        )
+        .await;
+        let sender_peer_id = sender.local_enr().peer_id();
+
+        // BlocksByRange Request
+        let rpc_request = Request::BlocksByRange(BlocksByRangeRequest::new(0, 1));
+        let (allowed_id, limited_id) = (1, 2);
+
+        // keep count of the number of requests which reach the receiver
+        let mut requests_received = 0;
+        // build the sender future
+        let sender_future = async {
+            loop {
+                match sender.next_event().await {
+                    NetworkEvent::PeerConnectedOutgoing(peer_id) => {
+                        debug!(log, "Sending RPC");
+                        sender.send_request(peer_id, allowed_id, rpc_request.clone());
+                    }
+                    // The first request is served, after which the next one exceeds the quota.
+                    NetworkEvent::ResponseReceived {
+                        peer_id,
+                        id,
+                        response: Response::BlocksByRange(None),
+                    } => {
+                        assert_eq!(id, allowed_id);
+                        sender.send_request(peer_id, limited_id, rpc_request.clone());
+                    }
+                    NetworkEvent::RPCFailed { id, .. } => {
+                        assert_eq!(id, limited_id);
+                        return;
+                    }
+                    _ => {} // Ignore other behaviour events
+                }
+            }
+        };
+
+        // build the receiver future
+        let receiver_future = async {
+            loop {
+                match receiver.next_event().await {
+                    NetworkEvent::RequestReceived {
+                        peer_id,
+                        id,
+                        request,
+                    } => {
+                        if request == rpc_request {
+                            requests_received += 1;
+                            // send the stream termination
+                            receiver.send_response(peer_id, id, Response::BlocksByRange(None));
+                        }
+                    }
+                    _ => {} // Ignore other events
+                }
+            }
+        };
+
+        tokio::select! {
+            _ = sender_future => {}
+            _ = receiver_future => {}
+            _ = sleep(Duration::from_secs(30)) => {
+                panic!("Future timed out");
+            }
+        }
+        // The rate limited request is answered by the RPC behaviour, never reaching the
+        // application.
+        assert_eq!(requests_received, 1);
+
+        // Drive the receiver until it has processed the error it sent.
+        let score = |receiver: &common::Libp2pInstance| {
+            receiver
+                .network_globals()
+                .peers
+                .read()
+                .score(&sender_peer_id)
+        };
+        let penalised = async {
+            while score(&receiver) >= 0.0 {
+                let _ =
+                    tokio::time::timeout(Duration::from_millis(100), receiver.next_event()).await;
+            }
+        };
+        tokio::select! {
+            _ = penalised => {}
+            _ = sleep(Duration::from_secs(30)) => {
+                panic!("The sender was not penalised");
+            }
+        }
+    })
+}
+
 // Tests that a streamed BlocksByRange RPC Message terminates when all expected chunks were received
 #[test]
 fn test_tcp_blocks_by_range_chunked_rpc_terminates_correctly() {
@@ -139,6 +139,16 @@ lazy_static! {
         "Total number of unaggregated attestations that referenced an unknown block and were re-queued."
     );
     // Aggregated attestations.
+    pub static ref BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_RECEIVED_PER_SUBNET: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_processor_unaggregated_attestation_received_per_subnet_total",
+        "Total number of unaggregated attestations received from gossip, per subnet.",
+        &["subnet"]
+    );
+    pub static ref BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_IMPORTED_PER_SUBNET: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_processor_unaggregated_attestation_imported_per_subnet_total",
+        "Total number of unaggregated attestations imported to fork choice, etc., per subnet.",
+        &["subnet"]
+    );
     pub static ref BEACON_PROCESSOR_AGGREGATED_ATTESTATION_VERIFIED_TOTAL: Result<IntCounter> = try_create_int_counter(
         "beacon_processor_aggregated_attestation_verified_total",
         "Total number of aggregated attestations verified for gossip."
@@ -2,16 +2,37 @@
 //!
 //! Currently supported strategies:
 //! - UPnP
+//! - NAT-PMP
 
 use crate::{NetworkConfig, NetworkMessage};
 use if_addrs::get_if_addrs;
-use slog::{debug, info};
-use std::net::{IpAddr, SocketAddr, SocketAddrV4};
+use lighthouse_network::types::{NatMappings, NatProtocol, PortMapping};
+use slog::{debug, info, warn};
+use std::net::{IpAddr, Ipv4Addr, SocketAddrV4, UdpSocket};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use types::EthSpec;
 
-/// Configuration required to construct the UPnP port mappings.
-pub struct UPnPConfig {
+/// The port on which gateways listen for NAT-PMP requests.
+const NAT_PMP_PORT: u16 = 5351;
+/// The lifetime requested for NAT-PMP port mappings, as recommended by RFC 6886.
+const NAT_PMP_LEASE_DURATION: Duration = Duration::from_secs(7200);
+/// The number of times a NAT-PMP request is sent before giving up. The timeout doubles with each
+/// attempt.
+const NAT_PMP_REQUEST_ATTEMPTS: u32 = 4;
+/// The time to wait for a response to the first attempt of a NAT-PMP request.
+const NAT_PMP_INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+/// The time to wait before retrying if the renewal of NAT-PMP mappings fails.
+const NAT_PMP_RENEWAL_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// NAT-PMP opcodes.
+const NAT_PMP_OPCODE_EXTERNAL_ADDRESS: u8 = 0;
+const NAT_PMP_OPCODE_MAP_UDP: u8 = 1;
+const NAT_PMP_OPCODE_MAP_TCP: u8 = 2;
+
+/// Configuration required to construct the port mappings.
+#[derive(Clone)]
+pub struct NatConfig {
     /// The local TCP port.
     tcp_port: u16,
     /// The local UDP discovery port.
@@ -24,32 +45,9 @@ pub struct UPnPConfig {
     disable_quic_support: bool,
 }
 
-/// Contains mappings that managed to be established.
-#[derive(Default, Debug)]
-pub struct EstablishedUPnPMappings {
-    /// A TCP port mapping for libp2p.
-    pub tcp_port: Option<u16>,
-    /// A UDP port for the QUIC libp2p transport.
-    pub udp_quic_port: Option<u16>,
-    /// A UDP port for discv5.
-    pub udp_disc_port: Option<u16>,
-}
-
-impl EstablishedUPnPMappings {
-    /// Returns true if at least one value is set.
-    pub fn is_some(&self) -> bool {
-        self.tcp_port.is_some() || self.udp_quic_port.is_some() || self.udp_disc_port.is_some()
-    }
-
-    // Iterator over the UDP ports
-    pub fn udp_ports(&self) -> impl Iterator<Item = &u16> {
-        self.udp_quic_port.iter().chain(self.udp_disc_port.iter())
-    }
-}
-
-impl UPnPConfig {
+impl NatConfig {
     pub fn from_config(config: &NetworkConfig) -> Option<Self> {
-        config.listen_addrs().v4().map(|v4_addr| UPnPConfig {
+        config.listen_addrs().v4().map(|v4_addr| NatConfig {
             tcp_port: v4_addr.tcp_port,
             disc_port: v4_addr.disc_port,
             quic_port: v4_addr.quic_port,
@@ -59,99 +57,154 @@ impl UPnPConfig {
     }
 }
 
-/// Attempts to construct external port mappings with UPnP.
-pub fn construct_upnp_mappings<T: EthSpec>(
-    config: UPnPConfig,
+/// Attempts to construct external port mappings, first with UPnP and then with NAT-PMP.
+///
+/// UPnP mappings do not expire. NAT-PMP mappings are renewed before their lease expires for as long
+/// as the network service is running, reporting each renewal to it.
+pub async fn construct_nat_mappings<T: EthSpec>(
+    config: NatConfig,
     network_send: mpsc::UnboundedSender<NetworkMessage<T>>,
     log: slog::Logger,
 ) {
-    info!(log, "UPnP Attempting to initialise routes");
-    match igd::search_gateway(Default::default()) {
-        Err(e) => info!(log, "UPnP not available"; "error" => %e),
-        Ok(gateway) => {
-            // Need to find the local listening address matched with the router subnet
-            let interfaces = match get_if_addrs() {
-                Ok(v) => v,
-                Err(e) => {
-                    info!(log, "UPnP failed to get local interfaces"; "error" => %e);
+    let mut renewing = false;
+    loop {
+        let task_config = config.clone();
+        let task_log = log.clone();
+        let mappings = tokio::task::spawn_blocking(move || {
+            if renewing {
+                construct_nat_pmp_mappings(&task_config, &task_log)
+            } else {
+                construct_upnp_mappings(&task_config, &task_log)
+                    .or_else(|| construct_nat_pmp_mappings(&task_config, &task_log))
+            }
+        })
+        .await
+        .unwrap_or_else(|e| {
+            debug!(log, "NAT mapping task failed"; "error" => %e);
+            None
+        });
+
+        let next_renewal = match mappings {
+            Some(mappings) => {
+                let next_renewal = (mappings.protocol == NatProtocol::NatPmp)
+                    .then(|| mappings.earliest_lease_expiry())
+                    .flatten()
+                    .map(|expiry| {
+                        // Renew once half of the lease has elapsed.
+                        Duration::from_secs(expiry.saturating_sub(unix_time_now()) / 2)
+                    });
+                if network_send
+                    .send(NetworkMessage::NatMappingEstablished { mappings })
+                    .is_err()
+                {
+                    debug!(
+                        log,
+                        "Network service shut down, no longer renewing NAT mappings"
+                    );
                     return;
                 }
-            };
-            let local_ip = interfaces.iter().find_map(|interface| {
-                // Just use the first IP of the first interface that is not a loopback and not an
-                // ipv6 address.
-                if !interface.is_loopback() {
-                    interface.ip().is_ipv4().then(|| interface.ip())
-                } else {
-                    None
+                match next_renewal {
+                    Some(next_renewal) => next_renewal,
+                    None => return,
                 }
-            });
+            }
+            None if renewing => {
+                warn!(log, "Failed to renew NAT-PMP port mappings"; "retry_in_secs" => NAT_PMP_RENEWAL_RETRY_INTERVAL.as_secs());
+                NAT_PMP_RENEWAL_RETRY_INTERVAL
+            }
+            None => return,
+        };
 
-            let local_ip = match local_ip {
-                None => {
-                    info!(log, "UPnP failed to find local IP address");
-                    return;
-                }
-                Some(v) => v,
-            };
-
-            debug!(log, "UPnP Local IP Discovered"; "ip" => ?local_ip);
-
-            let mut mappings = EstablishedUPnPMappings::default();
-
-            match local_ip {
-                IpAddr::V4(address) => {
-                    let libp2p_socket = SocketAddrV4::new(address, config.tcp_port);
-                    let external_ip = gateway.get_external_ip();
-                    // We add specific port mappings rather than getting the router to arbitrary assign
-                    // one.
-                    // I've found this to be more reliable. If multiple users are behind a single
-                    // router, they should ideally try to set different port numbers.
-                    mappings.tcp_port = add_port_mapping(
-                        &gateway,
-                        igd::PortMappingProtocol::TCP,
-                        libp2p_socket,
-                        "tcp",
-                        &log,
-                    ).map(|_| {
-                        let external_socket = external_ip.as_ref().map(|ip| SocketAddr::new((*ip).into(), config.tcp_port)).map_err(|_| ());
-                        info!(log, "UPnP TCP route established"; "external_socket" => format!("{}:{}", external_socket.as_ref().map(|ip| ip.to_string()).unwrap_or_else(|_| "".into()), config.tcp_port));
-                        config.tcp_port
-                    }).ok();
-
-                    let set_udp_mapping = |udp_port| {
-                        let udp_socket = SocketAddrV4::new(address, udp_port);
-                        add_port_mapping(
-                            &gateway,
-                            igd::PortMappingProtocol::UDP,
-                            udp_socket,
-                            "udp",
-                            &log,
-                        ).map(|_| {
-                            info!(log, "UPnP UDP route established"; "external_socket" => format!("{}:{}", external_ip.as_ref().map(|ip| ip.to_string()).unwrap_or_else(|_| "".into()), udp_port));
-                        })
-                    };
-
-                    // Set the discovery UDP port mapping
-                    if !config.disable_discovery && set_udp_mapping(config.disc_port).is_ok() {
-                        mappings.udp_disc_port = Some(config.disc_port);
-                    }
+        renewing = true;
+        tokio::time::sleep(next_renewal).await;
+    }
+}
 
-                    // Set the quic UDP port mapping
-                    if !config.disable_quic_support && set_udp_mapping(config.quic_port).is_ok() {
-                        mappings.udp_quic_port = Some(config.quic_port);
-                    }
+/// Attempts to construct external port mappings with UPnP.
+fn construct_upnp_mappings(config: &NatConfig, log: &slog::Logger) -> Option<NatMappings> {
+    info!(log, "UPnP Attempting to initialise routes");
+    let gateway = match igd::search_gateway(Default::default()) {
+        Err(e) => {
+            info!(log, "UPnP not available"; "error" => %e);
+            return None;
+        }
+        Ok(gateway) => gateway,
+    };
 
-                    // report any updates to the network service.
-                    if mappings.is_some() {
-                        network_send.send(NetworkMessage::UPnPMappingEstablished{ mappings })
-                .unwrap_or_else(|e| debug!(log, "Could not send message to the network service"; "error" => %e));
-                    }
-                }
-                _ => debug!(log, "UPnP no routes constructed. IPv6 not supported"),
-            }
+    // Need to find the local listening address matched with the router subnet
+    let address = match local_ipv4() {
+        Err(e) => {
+            info!(log, "UPnP failed to find local IP address"; "error" => e);
+            return None;
         }
+        Ok(address) => address,
     };
+
+    debug!(log, "UPnP Local IP Discovered"; "ip" => ?address);
+
+    let external_ip = gateway.get_external_ip().ok();
+    let external_ip_string = external_ip
+        .as_ref()
+        .map(|ip| ip.to_string())
+        .unwrap_or_default();
+    let mut mappings = NatMappings {
+        protocol: NatProtocol::Upnp,
+        external_ip,
+        tcp: None,
+        udp_quic: None,
+        udp_disc: None,
+    };
+    let permanent_mapping = |port| PortMapping {
+        internal_port: port,
+        external_port: port,
+        lease_expiry: None,
+    };
+
+    let libp2p_socket = SocketAddrV4::new(address, config.tcp_port);
+    // We add specific port mappings rather than getting the router to arbitrary assign
+    // one.
+    // I've found this to be more reliable. If multiple users are behind a single
+    // router, they should ideally try to set different port numbers.
+    if add_port_mapping(
+        &gateway,
+        igd::PortMappingProtocol::TCP,
+        libp2p_socket,
+        "tcp",
+        log,
+    )
+    .is_ok()
+    {
+        info!(log, "UPnP TCP route established"; "external_socket" => format!("{}:{}", external_ip_string, config.tcp_port));
+        mappings.tcp = Some(permanent_mapping(config.tcp_port));
+    }
+
+    let set_udp_mapping = |udp_port| {
+        let udp_socket = SocketAddrV4::new(address, udp_port);
+        add_port_mapping(
+            &gateway,
+            igd::PortMappingProtocol::UDP,
+            udp_socket,
+            "udp",
+            log,
+        )
+        .map(|_| {
+            info!(log, "UPnP UDP route established"; "external_socket" => format!("{}:{}", external_ip_string, udp_port));
+            permanent_mapping(udp_port)
+        })
+        .ok()
+    };
+
+    // Set the discovery UDP port mapping
+    if !config.disable_discovery {
+        mappings.udp_disc = set_udp_mapping(config.disc_port);
+    }
+
+    // Set the quic UDP port mapping
+    if !config.disable_quic_support {
+        mappings.udp_quic = set_udp_mapping(config.quic_port);
+    }
+
+    mappings.is_some().then_some(mappings)
 }
 
 /// Sets up a port mapping for a protocol returning the mapped port if successful.
@@ -198,30 +251,315 @@ fn add_port_mapping(
     Err(())
 }
 
-/// Removes the specified TCP and UDP port mappings.
-pub fn remove_mappings(mappings: &EstablishedUPnPMappings, log: &slog::Logger) {
+/// Attempts to construct (or renew) external port mappings with NAT-PMP.
+fn construct_nat_pmp_mappings(config: &NatConfig, log: &slog::Logger) -> Option<NatMappings> {
+    let client = match NatPmpClient::new() {
+        Err(e) => {
+            info!(log, "NAT-PMP not available"; "error" => e);
+            return None;
+        }
+        Ok(client) => client,
+    };
+
+    let external_ip = match client.external_ip() {
+        Err(e) => {
+            info!(log, "NAT-PMP not available"; "gateway" => %client.gateway, "error" => e);
+            return None;
+        }
+        Ok(external_ip) => external_ip,
+    };
+
+    let map_port = |opcode, port, protocol_string| match client.map_port(
+        opcode,
+        port,
+        NAT_PMP_LEASE_DURATION,
+    ) {
+        Ok(mapping) => {
+            debug!(log, "NAT-PMP route established"; "protocol" => protocol_string, "external_socket" => format!("{}:{}", external_ip, mapping.external_port), "lease_expiry" => mapping.lease_expiry);
+            Some(mapping)
+        }
+        Err(e) => {
+            info!(log, "NAT-PMP route not set"; "protocol" => protocol_string, "port" => port, "error" => e);
+            None
+        }
+    };
+
+    let mappings = NatMappings {
+        protocol: NatProtocol::NatPmp,
+        external_ip: Some(external_ip),
+        tcp: map_port(NAT_PMP_OPCODE_MAP_TCP, config.tcp_port, "tcp"),
+        udp_disc: (!config.disable_discovery)
+            .then(|| map_port(NAT_PMP_OPCODE_MAP_UDP, config.disc_port, "udp"))
+            .flatten(),
+        udp_quic: (!config.disable_quic_support)
+            .then(|| map_port(NAT_PMP_OPCODE_MAP_UDP, config.quic_port, "udp"))
+            .flatten(),
+    };
+
     if mappings.is_some() {
-        debug!(log, "Removing UPnP port mappings");
-        match igd::search_gateway(Default::default()) {
-            Ok(gateway) => {
-                if let Some(tcp_port) = mappings.tcp_port {
-                    match gateway.remove_port(igd::PortMappingProtocol::TCP, tcp_port) {
-                        Ok(()) => debug!(log, "UPnP Removed TCP port mapping"; "port" => tcp_port),
-                        Err(e) => {
-                            debug!(log, "UPnP Failed to remove TCP port mapping"; "port" => tcp_port, "error" => %e)
-                        }
+        info!(log, "NAT-PMP routes established"; "external_ip" => %external_ip, "lease_expiry" => mappings.earliest_lease_expiry());
+    }
+    mappings.is_some().then_some(mappings)
+}
+
+/// A minimal NAT-PMP client, as specified in RFC 6886.
+struct NatPmpClient {
+    socket: UdpSocket,
+    gateway: Ipv4Addr,
+}
+
+impl NatPmpClient {
+    fn new() -> Result<Self, String> {
+        let local_ip = local_ipv4()?;
+        let gateway = default_gateway(local_ip);
+        let socket = UdpSocket::bind((local_ip, 0))
+            .map_err(|e| format!("Failed to bind NAT-PMP socket: {}", e))?;
+        socket
+            .connect((gateway, NAT_PMP_PORT))
+            .map_err(|e| format!("Failed to connect to gateway: {}", e))?;
+        Ok(NatPmpClient { socket, gateway })
+    }
+
+    /// Sends `request` to the gateway, returning the successful response to it.
+    fn request(&self, request: &[u8], response_len: usize) -> Result<Vec<u8>, String> {
+        let mut timeout = NAT_PMP_INITIAL_TIMEOUT;
+        let mut buf = [0; 16];
+        for _ in 0..NAT_PMP_REQUEST_ATTEMPTS {
+            self.socket
+                .set_read_timeout(Some(timeout))
+                .map_err(|e| e.to_string())?;
+            self.socket.send(request).map_err(|e| e.to_string())?;
+            if let Ok(len) = self.socket.recv(&mut buf) {
+                return parse_nat_pmp_response(request[1], &buf[..len], response_len)
+                    .map(|response| response.to_vec());
+            }
+            timeout *= 2;
+        }
+        Err("No response from gateway".to_string())
+    }
+
+    /// Requests the external IP address of the gateway.
+    fn external_ip(&self) -> Result<Ipv4Addr, String> {
+        let response = self.request(&[0, NAT_PMP_OPCODE_EXTERNAL_ADDRESS], 12)?;
+        Ok(Ipv4Addr::new(
+            response[8],
+            response[9],
+            response[10],
+            response[11],
+        ))
+    }
+
+    /// Requests a mapping of `port` on the gateway to the same local port. A `lifetime` of zero
+    /// removes the mapping.
+    fn map_port(&self, opcode: u8, port: u16, lifetime: Duration) -> Result<PortMapping, String> {
+        let mut request = [0; 12];
+        request[1] = opcode;
+        request[4..6].copy_from_slice(&port.to_be_bytes());
+        // Suggest the same external port as the internal one.
+        request[6..8].copy_from_slice(&port.to_be_bytes());
+        request[8..12].copy_from_slice(&(lifetime.as_secs() as u32).to_be_bytes());
+
+        let response = self.request(&request, 16)?;
+        let lifetime = u32::from_be_bytes([response[12], response[13], response[14], response[15]]);
+        Ok(PortMapping {
+            internal_port: u16::from_be_bytes([response[8], response[9]]),
+            external_port: u16::from_be_bytes([response[10], response[11]]),
+            lease_expiry: Some(unix_time_now() + lifetime as u64),
+        })
+    }
+}
+
+/// Checks that `response` is a successful response to a request with `opcode` and has at least
+/// `response_len` bytes.
+fn parse_nat_pmp_response(
+    opcode: u8,
+    response: &[u8],
+    response_len: usize,
+) -> Result<&[u8], String> {
+    if response.len() < response_len {
+        return Err(format!("Response too short: {} bytes", response.len()));
+    }
+    if response[0] != 0 || response[1] != 128 + opcode {
+        return Err(format!(
+            "Unexpected response version {} or opcode {}",
+            response[0], response[1]
+        ));
+    }
+    match u16::from_be_bytes([response[2], response[3]]) {
+        0 => Ok(response),
+        result_code => Err(format!("Gateway returned result code {}", result_code)),
+    }
+}
+
+/// Returns the first IPv4 address of a local interface which is not a loopback.
+fn local_ipv4() -> Result<Ipv4Addr, String> {
+    let interfaces =
+        get_if_addrs().map_err(|e| format!("Failed to get local interfaces: {}", e))?;
+    interfaces
+        .iter()
+        .filter(|interface| !interface.is_loopback())
+        .find_map(|interface| match interface.ip() {
+            IpAddr::V4(ip) => Some(ip),
+            IpAddr::V6(_) => None,
+        })
+        .ok_or_else(|| "No local IPv4 address".to_string())
+}
+
+/// Returns the default IPv4 gateway.
+///
+/// On Linux this is read from the routing table. Otherwise, or if that fails, the first address of
+/// the local /24 subnet is assumed, which is the convention for home routers.
+fn default_gateway(local_ip: Ipv4Addr) -> Ipv4Addr {
+    std::fs::read_to_string("/proc/net/route")
+        .ok()
+        .and_then(|routes| parse_default_gateway(&routes))
+        .unwrap_or_else(|| {
+            let [a, b, c, _] = local_ip.octets();
+            Ipv4Addr::new(a, b, c, 1)
+        })
+}
+
+/// Parses the gateway of the default route from the contents of `/proc/net/route`.
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        let (destination, gateway) = (fields.get(1)?, fields.get(2)?);
+        if *destination != "00000000" {
+            return None;
+        }
+        // Addresses are hex encoded in host byte order.
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        Some(Ipv4Addr::from(gateway.to_ne_bytes()))
+    })
+}
+
+fn unix_time_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Removes the specified TCP and UDP port mappings.
+pub fn remove_mappings(mappings: &NatMappings, log: &slog::Logger) {
+    if !mappings.is_some() {
+        return;
+    }
+    match mappings.protocol {
+        NatProtocol::Upnp => remove_upnp_mappings(mappings, log),
+        NatProtocol::NatPmp => remove_nat_pmp_mappings(mappings, log),
+    }
+}
+
+fn remove_upnp_mappings(mappings: &NatMappings, log: &slog::Logger) {
+    debug!(log, "Removing UPnP port mappings");
+    match igd::search_gateway(Default::default()) {
+        Ok(gateway) => {
+            if let Some(tcp) = &mappings.tcp {
+                match gateway.remove_port(igd::PortMappingProtocol::TCP, tcp.external_port) {
+                    Ok(()) => {
+                        debug!(log, "UPnP Removed TCP port mapping"; "port" => tcp.external_port)
+                    }
+                    Err(e) => {
+                        debug!(log, "UPnP Failed to remove TCP port mapping"; "port" => tcp.external_port, "error" => %e)
                     }
                 }
-                for udp_port in mappings.udp_ports() {
-                    match gateway.remove_port(igd::PortMappingProtocol::UDP, *udp_port) {
-                        Ok(()) => debug!(log, "UPnP Removed UDP port mapping"; "port" => udp_port),
-                        Err(e) => {
-                            debug!(log, "UPnP Failed to remove UDP port mapping"; "port" => udp_port, "error" => %e)
-                        }
+            }
+            for udp in mappings.udp_mappings() {
+                match gateway.remove_port(igd::PortMappingProtocol::UDP, udp.external_port) {
+                    Ok(()) => {
+                        debug!(log, "UPnP Removed UDP port mapping"; "port" => udp.external_port)
+                    }
+                    Err(e) => {
+                        debug!(log, "UPnP Failed to remove UDP port mapping"; "port" => udp.external_port, "error" => %e)
                     }
                 }
             }
-            Err(e) => debug!(log, "UPnP failed to remove mappings"; "error" => %e),
         }
+        Err(e) => debug!(log, "UPnP failed to remove mappings"; "error" => %e),
+    }
+}
+
+fn remove_nat_pmp_mappings(mappings: &NatMappings, log: &slog::Logger) {
+    debug!(log, "Removing NAT-PMP port mappings");
+    let client = match NatPmpClient::new() {
+        Ok(client) => client,
+        Err(e) => {
+            debug!(log, "NAT-PMP failed to remove mappings"; "error" => e);
+            return;
+        }
+    };
+    let tcp = mappings
+        .tcp
+        .iter()
+        .map(|mapping| (NAT_PMP_OPCODE_MAP_TCP, mapping));
+    let udp = mappings
+        .udp_mappings()
+        .map(|mapping| (NAT_PMP_OPCODE_MAP_UDP, mapping));
+    for (opcode, mapping) in tcp.chain(udp) {
+        match client.map_port(opcode, mapping.internal_port, Duration::ZERO) {
+            Ok(_) => debug!(log, "NAT-PMP Removed port mapping"; "port" => mapping.internal_port),
+            Err(e) => {
+                debug!(log, "NAT-PMP Failed to remove port mapping"; "port" => mapping.internal_port, "error" => e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_default_gateway() {
+        let routes =
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n\
+                      eth0\t0000A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0\n\
+                      eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0\n";
+        let expected = if cfg!(target_endian = "little") {
+            Ipv4Addr::new(192, 168, 1, 1)
+        } else {
+            Ipv4Addr::new(1, 1, 168, 192)
+        };
+        assert_eq!(parse_default_gateway(routes), Some(expected));
+        assert_eq!(parse_default_gateway("Iface\tDestination\tGateway\n"), None);
+    }
+
+    #[test]
+    fn parses_nat_pmp_responses() {
+        let mut response = [0; 16];
+        response[1] = 128 + NAT_PMP_OPCODE_MAP_TCP;
+        response[8..10].copy_from_slice(&9000u16.to_be_bytes());
+        response[10..12].copy_from_slice(&9001u16.to_be_bytes());
+        assert!(parse_nat_pmp_response(NAT_PMP_OPCODE_MAP_TCP, &response, 16).is_ok());
+
+        // Wrong opcode.
+        assert!(parse_nat_pmp_response(NAT_PMP_OPCODE_MAP_UDP, &response, 16).is_err());
+        // Too short.
+        assert!(parse_nat_pmp_response(NAT_PMP_OPCODE_MAP_TCP, &response[..12], 16).is_err());
+        // Failure result code.
+        response[3] = 3;
+        assert!(parse_nat_pmp_response(NAT_PMP_OPCODE_MAP_TCP, &response, 16).is_err());
+    }
+
+    #[test]
+    fn earliest_lease_expiry() {
+        let mapping = |lease_expiry| {
+            Some(PortMapping {
+                internal_port: 9000,
+                external_port: 9000,
+                lease_expiry,
+            })
+        };
+        let mut mappings = NatMappings {
+            protocol: NatProtocol::NatPmp,
+            external_ip: None,
+            tcp: mapping(Some(20)),
+            udp_quic: mapping(Some(10)),
+            udp_disc: mapping(None),
+        };
+        assert_eq!(mappings.earliest_lease_expiry(), Some(10));
+        mappings.udp_quic = None;
+        assert_eq!(mappings.earliest_lease_expiry(), Some(20));
     }
 }
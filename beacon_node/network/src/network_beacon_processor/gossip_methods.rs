@@ -375,6 +375,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 metrics::inc_counter(
                     &metrics::BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_IMPORTED_TOTAL,
                 );
+                metrics::inc_counter_vec(
+                    &metrics::BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_IMPORTED_PER_SUBNET,
+                    &[subnet_id.as_ref()],
+                );
             }
             Err(RejectedUnaggregate { attestation, error }) => {
                 self.handle_attestation_verification_failure(
@@ -749,9 +753,18 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 debug!(
                     self.log,
                     "Unknown parent for gossip block";
-                    "root" => ?block_root
+                    "root" => ?block_root,
+                    "lookup" => self.chain.config.unknown_parent_lookup,
                 );
-                self.send_sync_message(SyncMessage::UnknownBlock(peer_id, block, block_root));
+                if self.chain.config.unknown_parent_lookup {
+                    self.send_sync_message(SyncMessage::UnknownBlock(peer_id, block, block_root));
+                } else {
+                    self.propagate_validation_result(
+                        message_id,
+                        peer_id,
+                        MessageAcceptance::Ignore,
+                    );
+                }
                 return None;
             }
             Err(e @ BlockError::BeaconChainError(_)) => {
@@ -797,7 +810,6 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             | Err(e @ BlockError::WeakSubjectivityConflict)
             | Err(e @ BlockError::InconsistentFork(_))
             | Err(e @ BlockError::ExecutionPayloadError(_))
-            | Err(e @ BlockError::ParentExecutionPayloadInvalid { .. })
             | Err(e @ BlockError::GenesisBlock) => {
                 warn!(self.log, "Could not verify block for gossip. Rejecting the block";
                             "error" => %e);
@@ -809,6 +821,16 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 );
                 return None;
             }
+            Err(e @ BlockError::ParentExecutionPayloadInvalid { .. }) => {
+                warn!(self.log, "Could not verify block for gossip. Rejecting the block";
+                            "error" => %e);
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Reject);
+                // Peers which repeatedly send us blocks built on invalid payloads are wasting our
+                // execution engine's time. Penalise them more harshly with each repetition.
+                let penalty = self.invalid_payload_offences.register(peer_id);
+                self.gossip_penalize_peer(peer_id, penalty, "gossip_block_invalid_parent_payload");
+                return None;
+            }
         };
 
         metrics::inc_counter(&metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_VERIFIED_TOTAL);
@@ -2067,7 +2089,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 debug!(self.log, "Attestation for finalized state"; "peer_id" => % peer_id);
                 self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
             }
-            e @ AttnError::BeaconChainError(BeaconChainError::MaxCommitteePromises(_)) => {
+            e @ AttnError::BeaconChainError(
+                BeaconChainError::MaxCommitteePromises(_)
+                | BeaconChainError::CommitteePromiseTimeout(_),
+            ) => {
                 debug!(
                     self.log,
                     "Dropping attestation";
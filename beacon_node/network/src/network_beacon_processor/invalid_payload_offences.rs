@@ -0,0 +1,85 @@
+use lighthouse_network::{PeerAction, PeerId};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The number of blocks descending from invalid execution payloads a peer may send before it is
+/// banned.
+const MAX_INVALID_PAYLOAD_OFFENCES: usize = 3;
+
+/// How long an offence counts towards a peer's total.
+const INVALID_PAYLOAD_OFFENCE_EXPIRY: Duration = Duration::from_secs(60 * 60);
+
+/// Tracks peers which send us blocks descending from execution payloads known to be invalid, so
+/// that repeat offenders are penalised more harshly.
+#[derive(Default)]
+pub struct InvalidPayloadOffences {
+    offences: Mutex<HashMap<PeerId, Vec<Instant>>>,
+}
+
+impl InvalidPayloadOffences {
+    /// Registers an offence by `peer_id`, returning the penalty to apply to the peer.
+    pub fn register(&self, peer_id: PeerId) -> PeerAction {
+        self.register_at(peer_id, Instant::now())
+    }
+
+    fn register_at(&self, peer_id: PeerId, now: Instant) -> PeerAction {
+        let mut offences = self.offences.lock();
+
+        // Forget expired offences, including those of other peers to keep the map bounded.
+        offences.retain(|_, times| {
+            times.retain(|time| {
+                now.saturating_duration_since(*time) < INVALID_PAYLOAD_OFFENCE_EXPIRY
+            });
+            !times.is_empty()
+        });
+
+        let times = offences.entry(peer_id).or_default();
+        times.push(now);
+
+        if times.len() < MAX_INVALID_PAYLOAD_OFFENCES {
+            PeerAction::LowToleranceError
+        } else {
+            PeerAction::Fatal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn penalty_escalates_with_repetition() {
+        let offences = InvalidPayloadOffences::default();
+        let peer_id = PeerId::random();
+        let other_peer_id = PeerId::random();
+        let now = Instant::now();
+
+        assert!(matches!(
+            offences.register_at(peer_id, now),
+            PeerAction::LowToleranceError
+        ));
+        assert!(matches!(
+            offences.register_at(peer_id, now),
+            PeerAction::LowToleranceError
+        ));
+        // Offences are counted per peer.
+        assert!(matches!(
+            offences.register_at(other_peer_id, now),
+            PeerAction::LowToleranceError
+        ));
+        assert!(matches!(
+            offences.register_at(peer_id, now),
+            PeerAction::Fatal
+        ));
+
+        // Expired offences are forgotten.
+        let later = now + INVALID_PAYLOAD_OFFENCE_EXPIRY;
+        assert!(matches!(
+            offences.register_at(peer_id, later),
+            PeerAction::LowToleranceError
+        ));
+        assert_eq!(offences.offences.lock().len(), 1);
+    }
+}
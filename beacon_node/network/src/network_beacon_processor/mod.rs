@@ -27,11 +27,13 @@ use task_executor::TaskExecutor;
 use tokio::sync::mpsc::{self, error::TrySendError};
 use types::*;
 
+pub use invalid_payload_offences::InvalidPayloadOffences;
 pub use sync_methods::ChainSegmentProcessId;
 
 pub type Error<T> = TrySendError<BeaconWorkEvent<T>>;
 
 mod gossip_methods;
+mod invalid_payload_offences;
 mod rpc_methods;
 mod sync_methods;
 mod tests;
@@ -57,6 +59,7 @@ pub struct NetworkBeaconProcessor<T: BeaconChainTypes> {
     pub reprocess_tx: mpsc::Sender<ReprocessQueueMessage>,
     pub network_globals: Arc<NetworkGlobals<T::EthSpec>>,
     pub invalid_block_storage: InvalidBlockStorage,
+    pub invalid_payload_offences: InvalidPayloadOffences,
     pub executor: TaskExecutor,
     pub log: Logger,
 }
@@ -574,6 +577,7 @@ impl<E: EthSpec> NetworkBeaconProcessor<TestBeaconChainType<E>> {
             reprocess_tx: work_reprocessing_tx,
             network_globals,
             invalid_block_storage: InvalidBlockStorage::Disabled,
+            invalid_payload_offences: <_>::default(),
             executor: runtime.task_executor.clone(),
             log,
         };
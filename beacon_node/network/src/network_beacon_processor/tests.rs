@@ -11,13 +11,13 @@ use crate::{
 use beacon_chain::test_utils::{
     AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
 };
-use beacon_chain::BeaconChain;
+use beacon_chain::{BeaconChain, ChainConfig};
 use beacon_processor::{work_reprocessing_queue::*, *};
 use lighthouse_network::{
     discv5::enr::{CombinedKey, EnrBuilder},
     rpc::methods::{MetaData, MetaDataV2},
     types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield},
-    Client, MessageId, NetworkGlobals, PeerId,
+    Client, MessageAcceptance, MessageId, NetworkGlobals, PeerId,
 };
 use slot_clock::SlotClock;
 use std::iter::Iterator;
@@ -54,8 +54,8 @@ struct TestRig {
     voluntary_exit: SignedVoluntaryExit,
     beacon_processor_tx: BeaconProcessorSend<E>,
     work_journal_rx: mpsc::Receiver<&'static str>,
-    _network_rx: mpsc::UnboundedReceiver<NetworkMessage<E>>,
-    _sync_rx: mpsc::UnboundedReceiver<SyncMessage<E>>,
+    network_rx: mpsc::UnboundedReceiver<NetworkMessage<E>>,
+    sync_rx: mpsc::UnboundedReceiver<SyncMessage<E>>,
     duplicate_cache: DuplicateCache,
     network_beacon_processor: Arc<NetworkBeaconProcessor<T>>,
     _harness: BeaconChainHarness<T>,
@@ -76,11 +76,16 @@ impl TestRig {
         Self::new_parametric(
             chain_length,
             BeaconProcessorConfig::default().enable_backfill_rate_limiting,
+            ChainConfig::default(),
         )
         .await
     }
 
-    pub async fn new_parametric(chain_length: u64, enable_backfill_rate_limiting: bool) -> Self {
+    pub async fn new_parametric(
+        chain_length: u64,
+        enable_backfill_rate_limiting: bool,
+        chain_config: ChainConfig,
+    ) -> Self {
         // This allows for testing voluntary exits without building out a massive chain.
         let mut spec = E::default_spec();
         spec.shard_committee_period = 2;
@@ -89,7 +94,7 @@ impl TestRig {
             .spec(spec)
             .deterministic_keypairs(VALIDATOR_COUNT)
             .fresh_ephemeral_store()
-            .chain_config(<_>::default())
+            .chain_config(chain_config)
             .build();
 
         harness.advance_slot();
@@ -171,7 +176,7 @@ impl TestRig {
 
         let chain = harness.chain.clone();
 
-        let (network_tx, _network_rx) = mpsc::unbounded_channel();
+        let (network_tx, network_rx) = mpsc::unbounded_channel();
 
         let log = harness.logger().clone();
 
@@ -184,7 +189,7 @@ impl TestRig {
             work_reprocessing_rx,
         } = BeaconProcessorChannels::new(&beacon_processor_config);
 
-        let (sync_tx, _sync_rx) = mpsc::unbounded_channel();
+        let (sync_tx, sync_rx) = mpsc::unbounded_channel();
 
         // Default metadata
         let meta_data = MetaData::V2(MetaDataV2 {
@@ -210,6 +215,7 @@ impl TestRig {
             reprocess_tx: work_reprocessing_tx.clone(),
             network_globals: network_globals.clone(),
             invalid_block_storage: InvalidBlockStorage::Disabled,
+            invalid_payload_offences: <_>::default(),
             executor: executor.clone(),
             log: log.clone(),
         };
@@ -244,8 +250,8 @@ impl TestRig {
             voluntary_exit,
             beacon_processor_tx,
             work_journal_rx,
-            _network_rx,
-            _sync_rx,
+            network_rx,
+            sync_rx,
             duplicate_cache,
             network_beacon_processor,
             _harness: harness,
@@ -272,6 +278,28 @@ impl TestRig {
             .unwrap();
     }
 
+    /// Enqueue a copy of `next_block` whose parent root points at a block we have never seen.
+    ///
+    /// Returns the root of the modified block.
+    pub fn enqueue_gossip_block_with_unknown_parent(&self) -> Hash256 {
+        let (mut block, signature) = self.next_block.as_ref().clone().deconstruct();
+        *block.parent_root_mut() = Hash256::repeat_byte(0xff);
+        let block = Arc::new(SignedBeaconBlock::from_block(block, signature));
+        let block_root = block.canonical_root();
+
+        self.network_beacon_processor
+            .send_gossip_beacon_block(
+                junk_message_id(),
+                junk_peer_id(),
+                Client::default(),
+                block,
+                Duration::from_secs(0),
+            )
+            .unwrap();
+
+        block_root
+    }
+
     pub fn enqueue_rpc_block(&self) {
         self.network_beacon_processor
             .send_rpc_beacon_block(
@@ -939,11 +967,69 @@ async fn test_backfill_sync_processing() {
     }
 }
 
+/// Ensure that a gossip block with an unknown parent is handed to sync for a parent lookup.
+#[tokio::test]
+async fn gossip_block_unknown_parent_lookup() {
+    let mut rig = TestRig::new(SMALL_CHAIN).await;
+
+    let block_root = rig.enqueue_gossip_block_with_unknown_parent();
+
+    rig.assert_event_journal(&[GOSSIP_BLOCK, WORKER_FREED, NOTHING_TO_DO])
+        .await;
+
+    match rig.sync_rx.try_recv() {
+        Ok(SyncMessage::UnknownBlock(_, _, root)) => assert_eq!(root, block_root),
+        other => panic!("expected an unknown block lookup, got {:?}", other),
+    }
+}
+
+/// Ensure that a gossip block with an unknown parent is ignored without a lookup when
+/// `unknown_parent_lookup` is disabled.
+#[tokio::test]
+async fn gossip_block_unknown_parent_lookup_disabled() {
+    let mut rig = TestRig::new_parametric(
+        SMALL_CHAIN,
+        BeaconProcessorConfig::default().enable_backfill_rate_limiting,
+        ChainConfig {
+            unknown_parent_lookup: false,
+            ..ChainConfig::default()
+        },
+    )
+    .await;
+
+    rig.enqueue_gossip_block_with_unknown_parent();
+
+    rig.assert_event_journal(&[GOSSIP_BLOCK, WORKER_FREED, NOTHING_TO_DO])
+        .await;
+
+    assert!(
+        rig.sync_rx.try_recv().is_err(),
+        "no lookup should be requested"
+    );
+
+    let mut ignored = false;
+    while let Ok(message) = rig.network_rx.try_recv() {
+        if let NetworkMessage::ValidationResult {
+            validation_result: MessageAcceptance::Ignore,
+            ..
+        } = message
+        {
+            ignored = true;
+        }
+    }
+    assert!(ignored, "the block should be ignored on gossip");
+}
+
 /// Ensure that backfill batches get processed as fast as they can when rate-limiting is disabled.
 #[tokio::test]
 async fn test_backfill_sync_processing_rate_limiting_disabled() {
     let enable_backfill_rate_limiting = false;
-    let mut rig = TestRig::new_parametric(SMALL_CHAIN, enable_backfill_rate_limiting).await;
+    let mut rig = TestRig::new_parametric(
+        SMALL_CHAIN,
+        enable_backfill_rate_limiting,
+        ChainConfig::default(),
+    )
+    .await;
 
     for _ in 0..3 {
         rig.enqueue_backfill_batch();
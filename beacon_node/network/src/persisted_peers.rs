@@ -0,0 +1,87 @@
+use lighthouse_network::PersistedPeer;
+use ssz::{Decode, Encode};
+use std::sync::Arc;
+use store::{DBColumn, Error as StoreError, HotColdDB, ItemStore, StoreItem};
+use types::{EthSpec, Hash256};
+
+/// 32-byte key for accessing the `PersistedPeers`. All zero because `PersistedPeers` has its own
+/// column.
+pub const PEERS_DB_KEY: Hash256 = Hash256::zero();
+
+/// Load the peers persisted to `store`, returning an empty list if there are none.
+pub fn load_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+) -> Vec<PersistedPeer> {
+    match store.get_item(&PEERS_DB_KEY) {
+        Ok(Some(p)) => {
+            let p: PersistedPeers = p;
+            p.peers
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Attempt to persist `peers` to `store`, replacing any previously persisted peers.
+pub fn persist_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    peers: Vec<PersistedPeer>,
+) -> Result<(), store::Error> {
+    store.put_item(&PEERS_DB_KEY, &PersistedPeers { peers })
+}
+
+/// Wrapper around the known peers for persistence to disk.
+pub struct PersistedPeers {
+    pub peers: Vec<PersistedPeer>,
+}
+
+impl StoreItem for PersistedPeers {
+    fn db_column() -> DBColumn {
+        DBColumn::PeerDb
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.peers.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        let peers = Vec::<PersistedPeer>::from_ssz_bytes(bytes)?;
+        Ok(PersistedPeers { peers })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lighthouse_network::PeerId;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use store::config::StoreConfig;
+    use store::{HotColdDB, MemoryStore};
+    use types::{ChainSpec, MinimalEthSpec};
+
+    #[test]
+    fn test_persisted_peers() {
+        let log = NullLoggerBuilder.build().unwrap();
+        let store: HotColdDB<
+            MinimalEthSpec,
+            MemoryStore<MinimalEthSpec>,
+            MemoryStore<MinimalEthSpec>,
+        > = HotColdDB::open_ephemeral(StoreConfig::default(), ChainSpec::minimal(), log).unwrap();
+        let store = Arc::new(store);
+        assert!(load_peers(store.clone()).is_empty());
+
+        let multiaddr = "/ip4/127.0.0.1/tcp/9000".parse().unwrap();
+        let peers = vec![
+            PersistedPeer::new(
+                &PeerId::random(),
+                None,
+                std::iter::once(&multiaddr),
+                None,
+                1,
+                false,
+            ),
+            PersistedPeer::new(&PeerId::random(), None, std::iter::empty(), None, 2, true),
+        ];
+        persist_peers(store.clone(), peers.clone()).unwrap();
+        assert_eq!(load_peers(store), peers);
+    }
+}
@@ -6,6 +6,7 @@
 #![allow(clippy::unit_arg)]
 
 use crate::error;
+use crate::metrics;
 use crate::network_beacon_processor::{InvalidBlockStorage, NetworkBeaconProcessor};
 use crate::service::{NetworkMessage, RequestId};
 use crate::status::status_message;
@@ -108,6 +109,7 @@ impl<T: BeaconChainTypes> Router<T> {
             reprocess_tx: beacon_processor_reprocess_tx,
             network_globals: network_globals.clone(),
             invalid_block_storage,
+            invalid_payload_offences: <_>::default(),
             executor: executor.clone(),
             log: log.clone(),
         };
@@ -259,8 +261,12 @@ impl<T: BeaconChainTypes> Router<T> {
                         timestamp_now(),
                     ),
                 ),
-            PubsubMessage::Attestation(subnet_attestation) => self
-                .handle_beacon_processor_send_result(
+            PubsubMessage::Attestation(subnet_attestation) => {
+                metrics::inc_counter_vec(
+                    &metrics::BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_RECEIVED_PER_SUBNET,
+                    &[subnet_attestation.0.as_ref()],
+                );
+                self.handle_beacon_processor_send_result(
                     self.network_beacon_processor.send_unaggregated_attestation(
                         message_id,
                         peer_id,
@@ -269,7 +275,8 @@ impl<T: BeaconChainTypes> Router<T> {
                         should_process,
                         timestamp_now(),
                     ),
-                ),
+                )
+            }
             PubsubMessage::BeaconBlock(block) => self.handle_beacon_processor_send_result(
                 self.network_beacon_processor.send_gossip_beacon_block(
                     message_id,
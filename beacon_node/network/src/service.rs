@@ -1,7 +1,7 @@
 use super::sync::manager::RequestId as SyncId;
-use crate::nat::EstablishedUPnPMappings;
 use crate::network_beacon_processor::InvalidBlockStorage;
 use crate::persisted_dht::{clear_dht, load_dht, persist_dht};
+use crate::persisted_peers::{load_peers, persist_peers};
 use crate::router::{Router, RouterMessage};
 use crate::subnet_service::SyncCommitteeService;
 use crate::{error, metrics};
@@ -16,7 +16,7 @@ use futures::future::OptionFuture;
 use futures::prelude::*;
 use futures::StreamExt;
 use lighthouse_network::service::Network;
-use lighthouse_network::types::GossipKind;
+use lighthouse_network::types::{GossipKind, NatMappings};
 use lighthouse_network::{prometheus_client::registry::Registry, MessageAcceptance};
 use lighthouse_network::{
     rpc::{GoodbyeReason, RPCResponseErrorCode},
@@ -24,10 +24,11 @@ use lighthouse_network::{
 };
 use lighthouse_network::{
     types::{core_topics_to_subscribe, GossipEncoding, GossipTopic},
-    MessageId, NetworkEvent, NetworkGlobals, PeerId,
+    MessageId, NetworkEvent, NetworkGlobals, PeerId, PersistedPeer,
 };
 use slog::{crit, debug, error, info, o, trace, warn};
-use std::{collections::HashSet, pin::Pin, sync::Arc, time::Duration};
+use slot_clock::SlotClock;
+use std::{collections::HashSet, net::SocketAddr, pin::Pin, sync::Arc, time::Duration};
 use store::HotColdDB;
 use strum::IntoStaticStr;
 use task_executor::ShutdownReason;
@@ -42,6 +43,8 @@ mod tests;
 
 /// The interval (in seconds) that various network metrics will update.
 const METRIC_UPDATE_INTERVAL: u64 = 5;
+/// The interval (in seconds) at which the known peers are persisted to the store.
+const PERSIST_PEERS_INTERVAL: u64 = 300;
 /// Number of slots before the fork when we should subscribe to the new fork topics.
 const SUBSCRIBE_DELAY_SLOTS: u64 = 2;
 /// Delay after a fork where we unsubscribe from pre-fork topics.
@@ -94,10 +97,10 @@ pub enum NetworkMessage<T: EthSpec> {
         /// The result of the validation
         validation_result: MessageAcceptance,
     },
-    /// Called if  UPnP managed to establish an external port mapping.
-    UPnPMappingEstablished {
+    /// Called if UPnP or NAT-PMP managed to establish (or renew) external port mappings.
+    NatMappingEstablished {
         /// The mappings that were established.
-        mappings: EstablishedUPnPMappings,
+        mappings: NatMappings,
     },
     /// Reports a peer to the peer manager for performing an action.
     ReportPeer {
@@ -188,15 +191,17 @@ pub struct NetworkService<T: BeaconChainTypes> {
     store: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
     /// A collection of global variables, accessible outside of the network service.
     network_globals: Arc<NetworkGlobals<T::EthSpec>>,
-    /// Stores potentially created UPnP mappings to be removed on shutdown. (TCP port and UDP
-    /// ports).
-    upnp_mappings: EstablishedUPnPMappings,
+    /// Stores potentially created UPnP or NAT-PMP mappings to be removed on shutdown. (TCP port
+    /// and UDP ports).
+    nat_mappings: Option<NatMappings>,
     /// A delay that expires when a new fork takes place.
     next_fork_update: Pin<Box<OptionFuture<Sleep>>>,
     /// A delay that expires when we need to subscribe to a new fork's topics.
     next_fork_subscriptions: Pin<Box<OptionFuture<Sleep>>>,
     /// A delay that expires when we need to unsubscribe from old fork topics.
     next_unsubscribe: Pin<Box<OptionFuture<Sleep>>>,
+    /// A delay that expires when our light client updates for the current slot may be published.
+    next_light_client_publish: Pin<Box<OptionFuture<Sleep>>>,
     /// Subscribe to all the subnets once synced.
     subscribe_all_subnets: bool,
     /// Shutdown beacon node after sync is complete.
@@ -207,6 +212,10 @@ pub struct NetworkService<T: BeaconChainTypes> {
     metrics_update: tokio::time::Interval,
     /// gossipsub_parameter_update timer
     gossipsub_parameter_update: tokio::time::Interval,
+    /// Whether the known peers are persisted to the store.
+    persist_peers_enabled: bool,
+    /// A timer for persisting the known peers to the store.
+    persist_peers_update: tokio::time::Interval,
     /// enable_light_client_server indicator
     enable_light_client_server: bool,
     /// The logger for the network service.
@@ -234,20 +243,14 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             "Backfill is disabled. DO NOT RUN IN PRODUCTION"
         );
 
-        // try and construct UPnP port mappings if required.
-        if let Some(upnp_config) = crate::nat::UPnPConfig::from_config(config) {
-            let upnp_log = network_log.new(o!("service" => "UPnP"));
-            let upnp_network_send = network_senders.network_send();
-            if config.upnp_enabled {
-                executor.spawn_blocking(
-                    move || {
-                        crate::nat::construct_upnp_mappings(
-                            upnp_config,
-                            upnp_network_send,
-                            upnp_log,
-                        )
-                    },
-                    "UPnP",
+        // try and construct UPnP or NAT-PMP port mappings if required.
+        if let Some(nat_config) = crate::nat::NatConfig::from_config(config) {
+            let nat_log = network_log.new(o!("service" => "NAT"));
+            let nat_network_send = network_senders.network_send();
+            if config.nat_enabled {
+                executor.spawn(
+                    crate::nat::construct_nat_mappings(nat_config, nat_network_send, nat_log),
+                    "NAT",
                 );
             }
         }
@@ -262,6 +265,15 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         let next_fork_update = Box::pin(next_fork_delay(&beacon_chain).into());
         let next_fork_subscriptions = Box::pin(next_fork_subscriptions_delay(&beacon_chain).into());
         let next_unsubscribe = Box::pin(None.into());
+        let next_light_client_publish = if config.enable_light_client_server {
+            Box::pin(
+                next_light_client_publish_delay(&beacon_chain)
+                    .map(tokio::time::sleep)
+                    .into(),
+            )
+        } else {
+            Box::pin(None.into())
+        };
 
         let current_slot = beacon_chain
             .slot()
@@ -301,6 +313,25 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             }
         }
 
+        // Restore the peers known before the last shutdown, so that they are dialed before
+        // falling back to discovery and the boot nodes.
+        if !config.disable_peer_persistence {
+            let persisted_peers =
+                load_peers::<T::EthSpec, T::HotStore, T::ColdStore>(store.clone());
+            debug!(
+                network_log,
+                "Restoring persisted peers"; "peers" => persisted_peers.len()
+            );
+            let enrs = libp2p
+                .peer_manager_mut()
+                .restore_persisted_peers(persisted_peers, config.persisted_peers_max_age);
+            if !config.disable_discovery {
+                for enr in enrs {
+                    libp2p.add_enr(enr);
+                }
+            }
+        }
+
         let invalid_block_storage = config
             .invalid_block_storage
             .clone()
@@ -338,6 +369,10 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         // create a timer for updating gossipsub parameters
         let gossipsub_parameter_update = tokio::time::interval(Duration::from_secs(60));
 
+        // create a timer for persisting the known peers
+        let persist_peers_update =
+            tokio::time::interval(Duration::from_secs(PERSIST_PEERS_INTERVAL));
+
         let NetworkReceivers {
             network_recv,
             validator_subscription_recv,
@@ -355,15 +390,18 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             router_send,
             store,
             network_globals: network_globals.clone(),
-            upnp_mappings: EstablishedUPnPMappings::default(),
+            nat_mappings: None,
             next_fork_update,
             next_fork_subscriptions,
             next_unsubscribe,
+            next_light_client_publish,
             subscribe_all_subnets: config.subscribe_all_subnets,
             shutdown_after_sync: config.shutdown_after_sync,
             metrics_enabled: config.metrics_enabled,
             metrics_update,
             gossipsub_parameter_update,
+            persist_peers_enabled: !config.disable_peer_persistence,
+            persist_peers_update,
             fork_context,
             log: network_log,
             enable_light_client_server: config.enable_light_client_server,
@@ -419,6 +457,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
 
     fn spawn_service(mut self, executor: task_executor::TaskExecutor) {
         let mut shutdown_sender = executor.shutdown_sender();
+        let service_executor = executor.clone();
 
         // spawn on the current executor
         let service_fut = async move {
@@ -436,6 +475,8 @@ impl<T: BeaconChainTypes> NetworkService<T> {
 
                     _ = self.gossipsub_parameter_update.tick() => self.update_gossipsub_parameters(),
 
+                    _ = self.persist_peers_update.tick(), if self.persist_peers_enabled => self.spawn_persist_peers(&service_executor),
+
                     // handle a message sent to the network
                     Some(msg) = self.network_recv.recv() => self.on_network_msg(msg, &mut shutdown_sender).await,
 
@@ -452,6 +493,8 @@ impl<T: BeaconChainTypes> NetworkService<T> {
 
                     Some(_) = &mut self.next_fork_update => self.update_next_fork(),
 
+                    Some(_) = &mut self.next_light_client_publish => self.publish_light_client_updates(),
+
                     Some(_) = &mut self.next_unsubscribe => {
                         let new_enr_fork_id = self.beacon_chain.enr_fork_id();
                         self.libp2p.unsubscribe_from_fork_topics_except(new_enr_fork_id.fork_digest);
@@ -611,20 +654,56 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             } => {
                 self.libp2p.send_error_reponse(peer_id, id, error, reason);
             }
-            NetworkMessage::UPnPMappingEstablished { mappings } => {
-                self.upnp_mappings = mappings;
+            NetworkMessage::NatMappingEstablished { mappings } => {
+                *self.network_globals.nat_mappings.write() = Some(mappings.clone());
                 // If there is an external TCP port update, modify our local ENR.
-                if let Some(tcp_port) = self.upnp_mappings.tcp_port {
-                    if let Err(e) = self.libp2p.discovery_mut().update_enr_tcp_port(tcp_port) {
+                if let Some(tcp) = &mappings.tcp {
+                    if let Err(e) = self
+                        .libp2p
+                        .discovery_mut()
+                        .update_enr_tcp_port(tcp.external_port)
+                    {
                         warn!(self.log, "Failed to update ENR"; "error" => e);
                     }
                 }
                 // If there is an external QUIC port update, modify our local ENR.
-                if let Some(quic_port) = self.upnp_mappings.udp_quic_port {
-                    if let Err(e) = self.libp2p.discovery_mut().update_enr_quic_port(quic_port) {
+                if let Some(quic) = &mappings.udp_quic {
+                    if let Err(e) = self
+                        .libp2p
+                        .discovery_mut()
+                        .update_enr_quic_port(quic.external_port)
+                    {
                         warn!(self.log, "Failed to update ENR"; "error" => e);
                     }
                 }
+                // If the gateway reported a public external IP we don't advertise yet, advertise
+                // it along with the external discovery port.
+                if let Some(external_ip) = mappings
+                    .external_ip
+                    .filter(|ip| !(ip.is_private() || ip.is_loopback() || ip.is_unspecified()))
+                {
+                    let local_enr = self.libp2p.discovery().local_enr();
+                    let udp_port = mappings
+                        .udp_disc
+                        .as_ref()
+                        .map(|disc| disc.external_port)
+                        .or_else(|| local_enr.udp4());
+                    if let Some(udp_port) = udp_port {
+                        if local_enr.ip4() != Some(external_ip) {
+                            if let Err(e) =
+                                self.libp2p
+                                    .discovery_mut()
+                                    .update_enr_udp_socket(SocketAddr::new(
+                                        external_ip.into(),
+                                        udp_port,
+                                    ))
+                            {
+                                warn!(self.log, "Failed to update ENR"; "error" => e);
+                            }
+                        }
+                    }
+                }
+                self.nat_mappings = Some(mappings);
             }
             NetworkMessage::ValidationResult {
                 propagation_source,
@@ -655,6 +734,19 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                     "count" => messages.len(),
                     "topics" => ?topic_kinds
                 );
+
+                // Blocks which fail to publish must not be re-published after their slot has
+                // passed, so limit their retries to the remainder of their slot.
+                let (blocks, messages): (Vec<_>, Vec<_>) = messages
+                    .into_iter()
+                    .partition(|message| matches!(message, PubsubMessage::BeaconBlock(_)));
+                for message in blocks {
+                    if let PubsubMessage::BeaconBlock(block) = &message {
+                        let retry_deadline = self.time_until_end_of_slot(block.slot());
+                        self.libp2p
+                            .publish_with_retry_deadline(vec![message], Some(retry_deadline));
+                    }
+                }
                 self.libp2p.publish(messages);
             }
             NetworkMessage::ReportPeer {
@@ -824,6 +916,99 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         }
     }
 
+    /// Publish the light client updates computed from our canonical head if they improve on
+    /// those already seen on the network, then schedule the next publication.
+    fn publish_light_client_updates(&mut self) {
+        let subscribed = |kind: GossipKind| {
+            self.network_globals
+                .gossipsub_subscriptions
+                .read()
+                .iter()
+                .any(|topic| *topic.kind() == kind)
+        };
+        let mut messages = vec![];
+
+        if subscribed(GossipKind::LightClientOptimisticUpdate) {
+            if let Some(update) = self
+                .beacon_chain
+                .get_latest_light_client_optimistic_update()
+            {
+                let mut latest_seen = self.beacon_chain.latest_seen_optimistic_update.lock();
+                let improves = latest_seen.as_ref().map_or(true, |seen| {
+                    update.attested_header.slot > seen.attested_header.slot
+                });
+                if improves {
+                    *latest_seen = Some(update.clone());
+                    messages.push(PubsubMessage::LightClientOptimisticUpdate(Box::new(update)));
+                }
+            }
+        }
+
+        if subscribed(GossipKind::LightClientFinalityUpdate) {
+            if let Some(update) = self.beacon_chain.get_latest_light_client_finality_update() {
+                let mut latest_seen = self.beacon_chain.latest_seen_finality_update.lock();
+                let improves = latest_seen.as_ref().map_or(true, |seen| {
+                    update.finalized_header.slot > seen.finalized_header.slot
+                });
+                if improves {
+                    *latest_seen = Some(update.clone());
+                    messages.push(PubsubMessage::LightClientFinalityUpdate(Box::new(update)));
+                }
+            }
+        }
+
+        if !messages.is_empty() {
+            debug!(self.log, "Publishing light client updates"; "count" => messages.len());
+            self.libp2p.publish(messages);
+        }
+
+        self.next_light_client_publish = Box::pin(
+            next_light_client_publish_delay(&self.beacon_chain)
+                .map(tokio::time::sleep)
+                .into(),
+        );
+    }
+
+    /// Persist the known peers to the store so that they can be redialed after a restart.
+    ///
+    /// This writes to the database on the current thread, so is only used during shutdown.
+    fn persist_peers(&self) {
+        let peers = self.libp2p.peer_manager().persisted_peers();
+        Self::write_persisted_peers(self.store.clone(), peers, &self.log);
+    }
+
+    /// Persist the known peers on a blocking task, keeping the database write off the network
+    /// event loop.
+    fn spawn_persist_peers(&self, executor: &task_executor::TaskExecutor) {
+        let store = self.store.clone();
+        let peers = self.libp2p.peer_manager().persisted_peers();
+        let log = self.log.clone();
+        executor.spawn_blocking(
+            move || Self::write_persisted_peers(store, peers, &log),
+            "persist_peers",
+        );
+    }
+
+    fn write_persisted_peers(
+        store: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+        peers: Vec<PersistedPeer>,
+        log: &slog::Logger,
+    ) {
+        let num_peers = peers.len();
+        match persist_peers::<T::EthSpec, T::HotStore, T::ColdStore>(store, peers) {
+            Err(e) => error!(
+                log,
+                "Failed to persist peers";
+                "error" => ?e
+            ),
+            Ok(_) => debug!(
+                log,
+                "Persisted peers to store";
+                "peers" => num_peers
+            ),
+        }
+    }
+
     fn on_attestation_service_msg(&mut self, msg: SubnetServiceMessage) {
         match msg {
             SubnetServiceMessage::Subscribe(subnet) => {
@@ -912,6 +1097,15 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         }
     }
 
+    /// Returns the time remaining until the end of `slot`, or zero if it has already ended.
+    fn time_until_end_of_slot(&self, slot: Slot) -> Duration {
+        let slot_clock = &self.beacon_chain.slot_clock;
+        match (slot_clock.start_of(slot + 1), slot_clock.now_duration()) {
+            (Some(end_of_slot), Some(now)) => end_of_slot.saturating_sub(now),
+            _ => Duration::ZERO,
+        }
+    }
+
     fn subscribed_core_topics(&self) -> bool {
         let core_topics = core_topics_to_subscribe(self.fork_context.current_fork());
         let core_topics: HashSet<&GossipKind> = HashSet::from_iter(&core_topics);
@@ -933,6 +1127,17 @@ fn next_fork_delay<T: BeaconChainTypes>(
         .map(|(_, until_fork)| tokio::time::sleep(until_fork))
 }
 
+/// Returns the time until one third of the way into the next slot, which is the earliest time at
+/// which peers accept light client updates signed in that slot.
+///
+/// Returns `None` if the slot clock is unavailable.
+fn next_light_client_publish_delay<T: BeaconChainTypes>(
+    beacon_chain: &BeaconChain<T>,
+) -> Option<Duration> {
+    let duration_to_next_slot = beacon_chain.slot_clock.duration_to_next_slot()?;
+    Some(duration_to_next_slot + beacon_chain.slot_clock.slot_duration() / 3)
+}
+
 /// Returns a `Sleep` that triggers `SUBSCRIBE_DELAY_SLOTS` before the next fork.
 /// Returns `None` if there are no scheduled forks or we are already past `current_slot + SUBSCRIBE_DELAY_SLOTS > fork_slot`.
 fn next_fork_subscriptions_delay<T: BeaconChainTypes>(
@@ -974,8 +1179,14 @@ impl<T: BeaconChainTypes> Drop for NetworkService<T> {
             ),
         }
 
+        if self.persist_peers_enabled {
+            self.persist_peers();
+        }
+
         // attempt to remove port mappings
-        crate::nat::remove_mappings(&self.upnp_mappings, &self.log);
+        if let Some(mappings) = &self.nat_mappings {
+            crate::nat::remove_mappings(mappings, &self.log);
+        }
 
         info!(self.log, "Network service shutdown");
     }
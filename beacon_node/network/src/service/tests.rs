@@ -2,6 +2,7 @@
 #[cfg(test)]
 mod tests {
     use crate::persisted_dht::load_dht;
+    use crate::service::next_light_client_publish_delay;
     use crate::{NetworkConfig, NetworkService};
     use beacon_chain::test_utils::BeaconChainHarness;
     use beacon_processor::BeaconProcessorChannels;
@@ -10,8 +11,9 @@ mod tests {
     use sloggers::{null::NullLoggerBuilder, Build};
     use std::str::FromStr;
     use std::sync::Arc;
+    use std::time::Duration;
     use tokio::runtime::Runtime;
-    use types::MinimalEthSpec;
+    use types::{EthSpec, MinimalEthSpec};
 
     fn get_logger(actual_log: bool) -> Logger {
         if actual_log {
@@ -31,6 +33,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn light_client_publish_delay_with_uneven_slot_duration() {
+        let mut spec = MinimalEthSpec::default_spec();
+        spec.seconds_per_slot = 5;
+        let harness = BeaconChainHarness::builder(MinimalEthSpec)
+            .spec(spec)
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .build();
+        let slot_clock = &harness.chain.slot_clock;
+        slot_clock.set_current_time(*slot_clock.genesis_duration() + Duration::from_millis(1_500));
+
+        // A third of a 5 second slot is not a whole number of seconds.
+        assert_eq!(
+            next_light_client_publish_delay(&harness.chain),
+            Some(Duration::from_millis(3_500) + Duration::from_millis(5_000) / 3)
+        );
+    }
+
     #[test]
     fn test_dht_persistence() {
         let log = get_logger(false);
@@ -62,7 +83,7 @@ mod tests {
         let mut config = NetworkConfig::default();
         config.set_ipv4_listening_address(std::net::Ipv4Addr::UNSPECIFIED, 21212, 21212, 21213);
         config.discv5_config.table_filter = |_| true; // Do not ignore local IPs
-        config.upnp_enabled = false;
+        config.nat_enabled = false;
         config.boot_nodes_enr = enrs.clone();
         runtime.block_on(async move {
             // Create a new network service which implicitly gets dropped at the
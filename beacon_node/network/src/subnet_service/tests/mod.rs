@@ -695,6 +695,51 @@ mod attestation_service {
             ]
         );
     }
+
+    #[tokio::test]
+    async fn long_lived_subnets_computable_from_enr() {
+        use lighthouse_network::discv5::enr::{CombinedKey, EnrBuilder};
+        use lighthouse_network::{Enr, Eth2Enr};
+        use std::collections::HashSet;
+
+        let log = get_logger(None);
+        let config = NetworkConfig::default();
+        let spec = &CHAIN.chain.spec;
+        let current_epoch = CHAIN.chain.epoch().unwrap();
+
+        let new_node = || {
+            let enr: Enr = EnrBuilder::new("v4")
+                .build(&CombinedKey::generate_secp256k1())
+                .unwrap();
+            let service =
+                AttestationService::new(CHAIN.chain.clone(), enr.node_id(), &config, &log);
+            (enr, service)
+        };
+
+        // Two nodes which are subscribed to different long-lived subnets.
+        let node_a = new_node();
+        let node_b = loop {
+            let node = new_node();
+            if node.1.long_lived_subscriptions() != node_a.1.long_lived_subscriptions() {
+                break node;
+            }
+        };
+
+        // Each node computes the long-lived subnets of the other from the ENR it received.
+        for ((_, local_service), (remote_enr, remote_service)) in
+            [(&node_a, &node_b), (&node_b, &node_a)]
+        {
+            let received_enr: Enr = remote_enr.to_base64().parse().unwrap();
+            let expected = received_enr
+                .long_lived_attestation_subnets::<MainnetEthSpec>(current_epoch, spec)
+                .unwrap()
+                .into_iter()
+                .collect::<HashSet<_>>();
+            assert_eq!(expected.len(), spec.subnets_per_node as usize);
+            assert_eq!(remote_service.long_lived_subscriptions(), &expected);
+            assert_ne!(local_service.long_lived_subscriptions(), &expected);
+        }
+    }
 }
 
 mod sync_committee_service {
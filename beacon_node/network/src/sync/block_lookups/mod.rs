@@ -53,6 +53,12 @@ pub(crate) struct BlockLookups<T: BeaconChainTypes> {
     /// The flag allows us to determine if the peer returned data or sent us nothing.
     single_block_lookups: FnvHashMap<Id, SingleBlockRequest<SINGLE_BLOCK_LOOKUP_MAX_ATTEMPTS>>,
 
+    /// Block lookups waiting on a range sync batch which contains the block.
+    deferred_single_block_lookups: Vec<(Hash256, PeerId)>,
+
+    /// Parent chain lookups waiting on a range sync batch which contains the next parent.
+    deferred_parent_lookups: Vec<ParentLookup<T>>,
+
     /// The logger for the import manager.
     log: Logger,
 }
@@ -66,6 +72,8 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
                 FAILED_CHAINS_CACHE_EXPIRY_SECONDS,
             )),
             single_block_lookups: Default::default(),
+            deferred_single_block_lookups: Default::default(),
+            deferred_parent_lookups: Default::default(),
             log,
         }
     }
@@ -101,6 +109,31 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
             return;
         }
 
+        if self
+            .deferred_parent_lookups
+            .iter()
+            .any(|parent_req| parent_req.contains_block(&hash) || parent_req.parent_root() == hash)
+            || self
+                .deferred_single_block_lookups
+                .iter()
+                .any(|(deferred_hash, _peer_id)| *deferred_hash == hash)
+        {
+            // this block is already waiting on range sync, ignore it.
+            return;
+        }
+
+        if cx.is_range_batch_block(&hash) {
+            // Range sync is processing this block. Only look it up if the batch fails.
+            debug!(
+                self.log,
+                "Deferring block lookup to range sync";
+                "peer_id" => %peer_id,
+                "block" => %hash
+            );
+            self.deferred_single_block_lookups.push((hash, peer_id));
+            return;
+        }
+
         debug!(
             self.log,
             "Searching for block";
@@ -159,6 +192,13 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
             return;
         }
 
+        if self.deferred_parent_lookups.iter().any(|parent_req| {
+            parent_req.contains_block(&block_root) || parent_req.parent_root() == parent_root
+        }) {
+            // this chain is already waiting on range sync, ignore it.
+            return;
+        }
+
         let parent_lookup = ParentLookup::new(block_root, block, peer_id);
         self.request_parent(parent_lookup, cx);
     }
@@ -458,6 +498,15 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
                     BlockError::ParentUnknown(block) => {
                         self.search_parent(root, block, peer_id, cx);
                     }
+                    BlockError::ParentExecutionPayloadInvalid { .. } => {
+                        // The block can never be imported, so there is no point retrying it.
+                        warn!(self.log, "Peer sent block built on an invalid payload in single block lookup"; "root" => %root, "peer_id" => %peer_id);
+                        let penalty = cx
+                            .beacon_processor()
+                            .invalid_payload_offences
+                            .register(peer_id);
+                        cx.report_peer(peer_id, penalty, "single_block_invalid_parent_payload");
+                    }
                     ref e @ BlockError::ExecutionPayloadError(ref epe) if !epe.penalize_peer() => {
                         // These errors indicate that the execution layer is offline
                         // and failed to validate the execution payload. Do not downscore peer.
@@ -541,33 +590,7 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
             }
             BlockProcessResult::Ok
             | BlockProcessResult::Err(BlockError::BlockIsAlreadyKnown { .. }) => {
-                // Check if the beacon processor is available
-                let beacon_processor = match cx.beacon_processor_if_enabled() {
-                    Some(beacon_processor) => beacon_processor,
-                    None => {
-                        return trace!(
-                            self.log,
-                            "Dropping parent chain segment that was ready for processing.";
-                            parent_lookup
-                        );
-                    }
-                };
-                let (chain_hash, blocks, hashes, request) = parent_lookup.parts_for_processing();
-                let process_id = ChainSegmentProcessId::ParentLookup(chain_hash);
-
-                match beacon_processor.send_chain_segment(process_id, blocks) {
-                    Ok(_) => {
-                        self.processing_parent_lookups
-                            .insert(chain_hash, (hashes, request));
-                    }
-                    Err(e) => {
-                        error!(
-                            self.log,
-                            "Failed to send chain segment to processor";
-                            "error" => ?e
-                        );
-                    }
-                }
+                self.send_parent_chain_for_processing(parent_lookup, cx);
             }
             ref e @ BlockProcessResult::Err(BlockError::ExecutionPayloadError(ref epe))
                 if !epe.penalize_peer() =>
@@ -654,8 +677,89 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
         );
     }
 
+    /// A range sync batch has been processed. Lookups deferred to the batch are resumed: if the
+    /// batch was imported, the blocks waiting on it are processed, otherwise they are looked up
+    /// as usual.
+    pub fn range_batch_processed(
+        &mut self,
+        block_roots: &[Hash256],
+        imported: bool,
+        cx: &mut SyncNetworkContext<T>,
+    ) {
+        if block_roots.is_empty() {
+            return;
+        }
+
+        let (ready, deferred): (Vec<_>, Vec<_>) = self
+            .deferred_parent_lookups
+            .drain(..)
+            .partition(|parent_lookup| block_roots.contains(&parent_lookup.parent_root()));
+        self.deferred_parent_lookups = deferred;
+        for parent_lookup in ready {
+            debug!(self.log, "Resuming parent lookup deferred to range sync";
+                "imported" => imported, &parent_lookup);
+            if imported {
+                self.send_parent_chain_for_processing(parent_lookup, cx);
+            } else {
+                self.request_parent(parent_lookup, cx);
+            }
+        }
+
+        let (ready, deferred): (Vec<_>, Vec<_>) = self
+            .deferred_single_block_lookups
+            .drain(..)
+            .partition(|(hash, _peer_id)| block_roots.contains(hash));
+        self.deferred_single_block_lookups = deferred;
+        if !imported {
+            for (hash, peer_id) in ready {
+                self.search_block(hash, peer_id, cx);
+            }
+        }
+
+        metrics::set_gauge(
+            &metrics::SYNC_PARENT_BLOCK_LOOKUPS,
+            self.parent_lookups.len() as i64,
+        );
+    }
+
     /* Helper functions */
 
+    /// Sends the blocks of a parent lookup whose parent is known for processing as a chain
+    /// segment.
+    fn send_parent_chain_for_processing(
+        &mut self,
+        parent_lookup: ParentLookup<T>,
+        cx: &mut SyncNetworkContext<T>,
+    ) {
+        // Check if the beacon processor is available
+        let beacon_processor = match cx.beacon_processor_if_enabled() {
+            Some(beacon_processor) => beacon_processor,
+            None => {
+                return trace!(
+                    self.log,
+                    "Dropping parent chain segment that was ready for processing.";
+                    parent_lookup
+                );
+            }
+        };
+        let (chain_hash, blocks, hashes, request) = parent_lookup.parts_for_processing();
+        let process_id = ChainSegmentProcessId::ParentLookup(chain_hash);
+
+        match beacon_processor.send_chain_segment(process_id, blocks) {
+            Ok(_) => {
+                self.processing_parent_lookups
+                    .insert(chain_hash, (hashes, request));
+            }
+            Err(e) => {
+                error!(
+                    self.log,
+                    "Failed to send chain segment to processor";
+                    "error" => ?e
+                );
+            }
+        }
+    }
+
     fn send_block_for_processing(
         &mut self,
         block_root: Hash256,
@@ -695,6 +799,13 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
         mut parent_lookup: ParentLookup<T>,
         cx: &mut SyncNetworkContext<T>,
     ) {
+        if cx.is_range_batch_block(&parent_lookup.parent_root()) {
+            // Range sync is processing the parent. Wait for the batch instead of requesting it.
+            debug!(self.log, "Deferring parent lookup to range sync"; &parent_lookup);
+            self.deferred_parent_lookups.push(parent_lookup);
+            return;
+        }
+
         match parent_lookup.request_parent(cx) {
             Err(e) => {
                 debug!(self.log, "Failed to request parent"; &parent_lookup, "error" => e.as_static());
@@ -743,11 +854,11 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
 
     /// Drops all the single block requests and returns how many requests were dropped.
     pub fn drop_single_block_requests(&mut self) -> usize {
-        self.single_block_lookups.drain().len()
+        self.single_block_lookups.drain().len() + self.deferred_single_block_lookups.drain(..).len()
     }
 
     /// Drops all the parent chain requests and returns how many requests were dropped.
     pub fn drop_parent_chain_requests(&mut self) -> usize {
-        self.parent_lookups.drain(..).len()
+        self.parent_lookups.drain(..).len() + self.deferred_parent_lookups.drain(..).len()
     }
 }
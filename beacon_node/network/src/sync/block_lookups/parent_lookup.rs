@@ -142,6 +142,11 @@ impl<T: BeaconChainTypes> ParentLookup<T> {
         self.chain_hash
     }
 
+    /// Get the root of the parent currently being looked up.
+    pub fn parent_root(&self) -> Hash256 {
+        self.current_parent_request.hash
+    }
+
     pub fn download_failed(&mut self) {
         self.current_parent_request.register_failure_downloading();
         self.current_parent_request_id = None;
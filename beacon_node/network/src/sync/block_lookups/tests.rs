@@ -16,7 +16,7 @@ use slot_clock::ManualSlotClock;
 use store::MemoryStore;
 use tokio::sync::mpsc;
 use types::test_utils::{SeedableRng, TestRandom, XorShiftRng};
-use types::MinimalEthSpec as E;
+use types::{Epoch, MinimalEthSpec as E};
 
 type T = Witness<ManualSlotClock, CachingEth1Backend<E>, E, MemoryStore<E>, MemoryStore<E>>;
 
@@ -675,3 +675,116 @@ fn test_same_chain_race_condition() {
     bl.parent_chain_processed(chain_hash, process_result, &mut cx);
     assert_eq!(bl.parent_lookups.len(), 0);
 }
+
+#[test]
+fn test_parent_lookup_deferred_to_range_sync() {
+    let (mut bl, mut cx, mut rig) = TestRig::test_setup(None);
+
+    // The parent of a gossip block is being processed by range sync.
+    let parent = rig.rand_block();
+    let parent_root = parent.canonical_root();
+    let block = rig.block_with_parent(parent_root);
+    let chain_hash = block.canonical_root();
+    let peer_id = PeerId::random();
+    cx.register_range_batch_blocks(1, Epoch::new(0), [parent_root]);
+
+    // The parent should not be requested while range sync is processing it.
+    bl.search_parent(chain_hash, Arc::new(block.clone()), peer_id, &mut cx);
+    rig.expect_empty_network();
+    assert_eq!(bl.parent_lookups.len(), 0);
+    assert_eq!(bl.deferred_parent_lookups.len(), 1);
+
+    // Looking up the parent itself is covered by the deferred lookup.
+    bl.search_block(parent_root, peer_id, &mut cx);
+    rig.expect_empty_network();
+    assert_eq!(bl.single_block_lookups.len(), 0);
+    assert_eq!(bl.deferred_single_block_lookups.len(), 0);
+
+    // Another peer sends the same block, it should not be looked up twice.
+    bl.search_parent(chain_hash, Arc::new(block), PeerId::random(), &mut cx);
+    assert_eq!(bl.deferred_parent_lookups.len(), 1);
+
+    // The range batch is imported. The block should be processed without requesting the parent.
+    let block_roots = cx.remove_range_batch_blocks(1, Epoch::new(0));
+    assert_eq!(block_roots, vec![parent_root]);
+    bl.range_batch_processed(&block_roots, true, &mut cx);
+    rig.expect_parent_chain_process();
+    rig.expect_empty_network();
+    assert_eq!(bl.deferred_parent_lookups.len(), 0);
+    assert_eq!(bl.deferred_single_block_lookups.len(), 0);
+    assert!(bl.processing_parent_lookups.contains_key(&chain_hash));
+}
+
+#[test]
+fn test_parent_lookup_deferred_to_failed_range_sync() {
+    let (mut bl, mut cx, mut rig) = TestRig::test_setup(None);
+
+    let parent = rig.rand_block();
+    let parent_root = parent.canonical_root();
+    let block = rig.block_with_parent(parent_root);
+    let chain_hash = block.canonical_root();
+    let other_root = Hash256::random();
+    let peer_id = PeerId::random();
+    cx.register_range_batch_blocks(1, Epoch::new(0), [parent_root, other_root]);
+
+    bl.search_parent(chain_hash, Arc::new(block), peer_id, &mut cx);
+    bl.search_block(other_root, peer_id, &mut cx);
+    rig.expect_empty_network();
+    assert_eq!(bl.deferred_parent_lookups.len(), 1);
+    assert_eq!(bl.deferred_single_block_lookups.len(), 1);
+
+    // The range batch fails to import. The lookups should fall back to requesting the blocks.
+    let block_roots = cx.remove_range_batch_blocks(1, Epoch::new(0));
+    bl.range_batch_processed(&block_roots, false, &mut cx);
+    let id = rig.expect_parent_request();
+    rig.expect_block_request();
+    assert_eq!(bl.deferred_parent_lookups.len(), 0);
+    assert_eq!(bl.deferred_single_block_lookups.len(), 0);
+
+    // The parent lookup continues as normal.
+    bl.parent_lookup_response(id, peer_id, Some(Arc::new(parent)), D, &mut cx);
+    rig.expect_block_process();
+}
+
+#[test]
+fn test_parent_lookup_ancestor_deferred_to_range_sync() {
+    let (mut bl, mut cx, mut rig) = TestRig::test_setup(None);
+
+    // The grandparent of a gossip block arrives via range sync while the parent is looked up.
+    let grandparent = rig.rand_block();
+    let grandparent_root = grandparent.canonical_root();
+    let parent = Arc::new(rig.block_with_parent(grandparent_root));
+    let block = rig.block_with_parent(parent.canonical_root());
+    let chain_hash = block.canonical_root();
+    let peer_id = PeerId::random();
+
+    bl.search_parent(chain_hash, Arc::new(block), peer_id, &mut cx);
+    let id = rig.expect_parent_request();
+    bl.parent_lookup_response(id, peer_id, Some(parent.clone()), D, &mut cx);
+    rig.expect_block_process();
+
+    // Range sync starts processing the grandparent before the parent is processed.
+    cx.register_range_batch_blocks(1, Epoch::new(2), [grandparent_root]);
+    bl.parent_block_processed(
+        chain_hash,
+        BlockError::ParentUnknown(parent).into(),
+        &mut cx,
+    );
+    rig.expect_empty_network();
+    assert_eq!(bl.parent_lookups.len(), 0);
+    assert_eq!(bl.deferred_parent_lookups.len(), 1);
+
+    // Batches which don't contain the grandparent don't affect the lookup.
+    cx.register_range_batch_blocks(1, Epoch::new(4), [Hash256::random()]);
+    let block_roots = cx.remove_range_batch_blocks(1, Epoch::new(4));
+    bl.range_batch_processed(&block_roots, true, &mut cx);
+    rig.expect_empty_network();
+    assert_eq!(bl.deferred_parent_lookups.len(), 1);
+
+    // Once the grandparent is imported, the parent and block are processed as a chain segment.
+    let block_roots = cx.remove_range_batch_blocks(1, Epoch::new(2));
+    bl.range_batch_processed(&block_roots, true, &mut cx);
+    rig.expect_parent_chain_process();
+    rig.expect_empty_network();
+    assert_eq!(bl.deferred_parent_lookups.len(), 0);
+}
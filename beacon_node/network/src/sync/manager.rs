@@ -553,12 +553,20 @@ impl<T: BeaconChainTypes> SyncManager<T> {
             },
             SyncMessage::BatchProcessed { sync_type, result } => match sync_type {
                 ChainSegmentProcessId::RangeBatchId(chain_id, epoch) => {
+                    let imported = matches!(result, BatchProcessResult::Success { .. });
                     self.range_sync.handle_block_process_result(
                         &mut self.network,
                         chain_id,
                         epoch,
                         result,
                     );
+                    // Resume any lookups that were waiting on the blocks of this batch.
+                    let block_roots = self.network.remove_range_batch_blocks(chain_id, epoch);
+                    self.block_lookups.range_batch_processed(
+                        &block_roots,
+                        imported,
+                        &mut self.network,
+                    );
                     self.update_sync_state();
                 }
                 ChainSegmentProcessId::BackSyncBatchId(epoch) => {
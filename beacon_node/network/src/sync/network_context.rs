@@ -13,6 +13,7 @@ use lighthouse_network::{Client, NetworkGlobals, PeerAction, PeerId, ReportSourc
 use slog::{debug, trace, warn};
 use std::sync::Arc;
 use tokio::sync::mpsc;
+use types::Hash256;
 
 /// Wraps a Network channel to employ various RPC related network functionality for the Sync manager. This includes management of a global RPC request Id.
 
@@ -29,6 +30,10 @@ pub struct SyncNetworkContext<T: BeaconChainTypes> {
     /// BlocksByRange requests made by backfill syncing.
     backfill_requests: FnvHashMap<Id, BatchId>,
 
+    /// Roots of the blocks of range sync batches which are being processed. Block lookups consult
+    /// this to avoid fetching and processing the same blocks concurrently.
+    range_batch_blocks: FnvHashMap<Hash256, (ChainId, BatchId)>,
+
     /// Whether the ee is online. If it's not, we don't allow access to the
     /// `beacon_processor_send`.
     execution_engine_state: EngineState,
@@ -52,6 +57,7 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
             request_id: 1,
             range_requests: FnvHashMap::default(),
             backfill_requests: FnvHashMap::default(),
+            range_batch_blocks: FnvHashMap::default(),
             network_beacon_processor,
             log,
         }
@@ -165,6 +171,42 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
         }
     }
 
+    /// Registers the roots of the blocks of a range sync batch which has been sent for processing.
+    pub fn register_range_batch_blocks(
+        &mut self,
+        chain_id: ChainId,
+        batch_id: BatchId,
+        block_roots: impl IntoIterator<Item = Hash256>,
+    ) {
+        for block_root in block_roots {
+            self.range_batch_blocks
+                .insert(block_root, (chain_id, batch_id));
+        }
+    }
+
+    /// Returns whether the block is part of a range sync batch being processed.
+    pub fn is_range_batch_block(&self, block_root: &Hash256) -> bool {
+        self.range_batch_blocks.contains_key(block_root)
+    }
+
+    /// Removes and returns the roots of the blocks of a range sync batch that finished processing.
+    pub fn remove_range_batch_blocks(
+        &mut self,
+        chain_id: ChainId,
+        batch_id: BatchId,
+    ) -> Vec<Hash256> {
+        let mut block_roots = Vec::new();
+        self.range_batch_blocks.retain(|block_root, id| {
+            if *id == (chain_id, batch_id) {
+                block_roots.push(*block_root);
+                false
+            } else {
+                true
+            }
+        });
+        block_roots
+    }
+
     /// Received a blocks by range response.
     pub fn backfill_sync_response(&mut self, request_id: Id, remove: bool) -> Option<BatchId> {
         if remove {
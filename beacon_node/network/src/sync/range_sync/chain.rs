@@ -314,6 +314,14 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         // for removing chains and checking completion is in the callback.
 
         let blocks = batch.start_processing()?;
+        // Each block but the last is named by its child's parent root, which chain segment
+        // verification checks before importing anything, so only the last block is hashed here.
+        let block_roots = blocks
+            .iter()
+            .skip(1)
+            .map(|block| block.parent_root())
+            .chain(blocks.last().map(|block| block.canonical_root()))
+            .collect::<Vec<_>>();
         let process_id = ChainSegmentProcessId::RangeBatchId(self.id, batch_id);
         self.current_processing_batch = Some(batch_id);
 
@@ -326,6 +334,8 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             // re-downloaded.
             self.on_batch_process_result(network, batch_id, &BatchProcessResult::NonFaultyFailure)
         } else {
+            // Let block lookups know these blocks are on their way to being imported.
+            network.register_range_batch_blocks(self.id, batch_id, block_roots);
             Ok(KeepChain)
         }
     }
@@ -0,0 +1,105 @@
+//! Attestation packing throughput of the operation pool.
+//!
+//! Each committee contributes overlapping aggregates, so that max-cover has to re-score the
+//! remaining candidates after every selection.
+//!
+//! Run using:
+//!
+//! ```text
+//! cargo bench -p operation_pool
+//! ```
+use beacon_chain::test_utils::{test_spec, BeaconChainHarness, EphemeralHarnessType};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use operation_pool::OperationPool;
+use state_processing::common::get_attesting_indices_from_state;
+use types::{
+    test_utils::generate_deterministic_keypairs, Attestation, BeaconState, ChainSpec, EthSpec,
+    Hash256, MainnetEthSpec, SignedBeaconBlockHash,
+};
+
+type E = MainnetEthSpec;
+
+/// Number of unaggregated attestations combined into each aggregate.
+const AGGREGATE_SIZE: usize = 16;
+
+fn harness(num_committees: usize, spec: &ChainSpec) -> BeaconChainHarness<EphemeralHarnessType<E>> {
+    let num_validators =
+        num_committees * E::slots_per_epoch() as usize * spec.target_committee_size;
+    let harness = BeaconChainHarness::builder(E::default())
+        .spec(spec.clone())
+        .keypairs(generate_deterministic_keypairs(num_validators))
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+    harness.advance_slot();
+    harness
+}
+
+/// Fill a pool with aggregates over sliding windows of each committee, each overlapping the
+/// previous one by half.
+///
+/// Return the pool along with a state at which the attestations can be included.
+fn packed_pool(num_committees: usize) -> (OperationPool<E>, BeaconState<E>, ChainSpec) {
+    let spec = test_spec::<E>();
+    let harness = harness(num_committees, &spec);
+    let mut state = harness.get_current_state();
+    let slot = state.slot();
+    let num_validators = state.validators().len();
+
+    let op_pool = OperationPool::new();
+    let attestations = harness.make_attestations(
+        (0..num_validators).collect::<Vec<_>>().as_slice(),
+        &state,
+        Hash256::zero(),
+        SignedBeaconBlockHash::from(Hash256::zero()),
+        slot,
+    );
+    for (committee_attestations, _) in attestations {
+        let committee_attestations = committee_attestations
+            .into_iter()
+            .map(|(att, _)| att)
+            .collect::<Vec<_>>();
+        for start in (0..committee_attestations.len()).step_by(AGGREGATE_SIZE / 2) {
+            let end = std::cmp::min(start + AGGREGATE_SIZE, committee_attestations.len());
+            let aggregate = committee_attestations[start + 1..end].iter().fold(
+                committee_attestations[start].clone(),
+                |mut aggregate: Attestation<E>, att| {
+                    aggregate.aggregate(att);
+                    aggregate
+                },
+            );
+            let attesting_indices = get_attesting_indices_from_state(&state, &aggregate).unwrap();
+            op_pool
+                .insert_attestation(aggregate, attesting_indices)
+                .unwrap();
+        }
+    }
+
+    *state.slot_mut() += spec.min_attestation_inclusion_delay;
+    (op_pool, state, spec)
+}
+
+fn attestation_packing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("op_pool_attestation_packing");
+    group.sample_size(10);
+
+    for num_committees in [1, 4] {
+        let (op_pool, state, spec) = packed_pool(num_committees);
+        group.bench_with_input(
+            BenchmarkId::new("get_attestations", op_pool.num_attestations()),
+            &(op_pool, state, spec),
+            |b, (op_pool, state, spec)| {
+                b.iter(|| {
+                    op_pool
+                        .get_attestations(state, |_| true, |_| true, spec)
+                        .unwrap()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, attestation_packing);
+criterion_main!(benches);
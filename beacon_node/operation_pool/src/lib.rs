@@ -32,6 +32,7 @@ use state_processing::per_block_processing::{
     get_slashable_indices_modular, verify_exit, VerifySignatures,
 };
 use state_processing::{SigVerifiedOp, VerifyOperation};
+use std::cmp::Reverse;
 use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::marker::PhantomData;
 use std::ptr;
@@ -362,7 +363,7 @@ impl<T: EthSpec> OperationPool<T> {
     ///
     /// This function computes both types of slashings together, because
     /// attester slashings may be invalidated by proposer slashings included
-    /// earlier in the block.
+    /// earlier in the block. Exits for validators that are slashed in the block are omitted.
     pub fn get_slashings_and_exits(
         &self,
         state: &BeaconState<T>,
@@ -372,18 +373,28 @@ impl<T: EthSpec> OperationPool<T> {
         Vec<AttesterSlashing<T>>,
         Vec<SignedVoluntaryExit>,
     ) {
-        let proposer_slashings = filter_limit_operations(
-            self.proposer_slashings.read().values(),
-            |slashing| {
-                slashing.signature_is_still_valid(&state.fork())
-                    && state
-                        .validators()
-                        .get(slashing.as_inner().signed_header_1.message.proposer_index as usize)
-                        .map_or(false, |validator| !validator.slashed)
-            },
-            |slashing| slashing.as_inner().clone(),
-            T::MaxProposerSlashings::to_usize(),
-        );
+        // Prefer proposer slashings for validators with higher effective balances, as they yield
+        // the greatest whistleblower reward.
+        let mut proposer_slashings = self
+            .proposer_slashings
+            .read()
+            .values()
+            .filter_map(|slashing| {
+                let validator = state
+                    .validators()
+                    .get(slashing.as_inner().signed_header_1.message.proposer_index as usize)?;
+                (slashing.signature_is_still_valid(&state.fork())
+                    && validator.is_slashable_at(state.current_epoch()))
+                .then(|| (validator.effective_balance, slashing.as_inner().clone()))
+            })
+            .collect::<Vec<_>>();
+        proposer_slashings
+            .sort_unstable_by_key(|(effective_balance, _)| Reverse(*effective_balance));
+        let proposer_slashings = proposer_slashings
+            .into_iter()
+            .take(T::MaxProposerSlashings::to_usize())
+            .map(|(_, slashing)| slashing)
+            .collect::<Vec<_>>();
 
         // Set of validators to be slashed, so we don't attempt to construct invalid attester
         // slashings.
@@ -769,7 +780,8 @@ mod release_tests {
     use super::attestation::earliest_attestation_validators;
     use super::*;
     use beacon_chain::test_utils::{
-        test_spec, BeaconChainHarness, EphemeralHarnessType, RelativeSyncCommittee,
+        test_spec, AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
+        RelativeSyncCommittee,
     };
     use lazy_static::lazy_static;
     use maplit::hashset;
@@ -1350,6 +1362,43 @@ mod release_tests {
         }
     }
 
+    /// Attestations whose validators have already had their participation recorded on chain
+    /// should not be packed into a new block.
+    #[tokio::test]
+    async fn attestation_packing_excludes_on_chain_participation() {
+        let mut spec = MinimalEthSpec::default_spec();
+        spec.altair_fork_epoch = Some(Epoch::new(0));
+        let harness = get_harness::<MinimalEthSpec>(64, Some(spec.clone()));
+
+        // The block at slot 2 includes the attestations from slot 1, while the attestations from
+        // slot 2 remain in the pool for the next block.
+        harness
+            .extend_chain(
+                2,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+
+        let mut state = harness.get_current_state();
+        *state.slot_mut() += 1;
+
+        let block_attestations = harness
+            .chain
+            .op_pool
+            .get_attestations(&state, |_| true, |_| true, &spec)
+            .expect("should have block attestations");
+
+        assert!(!block_attestations.is_empty());
+        for att in &block_attestations {
+            assert_eq!(
+                att.data.slot,
+                Slot::new(2),
+                "attestations already included on chain should not be re-packed"
+            );
+        }
+    }
+
     /// Insert two slashings for the same proposer and ensure only one is returned.
     #[test]
     fn duplicate_proposer_slashing() {
@@ -1378,6 +1427,36 @@ mod release_tests {
         );
     }
 
+    /// Insert more proposer slashings than fit in a block and check that the most valuable
+    /// slashable proposers are chosen.
+    #[test]
+    fn proposer_slashings_prefer_high_balance() {
+        let harness = get_harness(32, None);
+        let mut state = harness.get_current_state();
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+
+        let max_slashings = <MainnetEthSpec as EthSpec>::MaxProposerSlashings::to_u64();
+        let num_slashings = max_slashings + 2;
+        for proposer_index in 0..num_slashings {
+            let slashing = harness.make_proposer_slashing(proposer_index);
+            op_pool.insert_proposer_slashing(slashing.validate(&state, &harness.spec).unwrap());
+        }
+
+        // Give each validator a distinct balance, and slash the highest-balance validator so that
+        // its slashing is no longer includable.
+        for i in 0..num_slashings as usize {
+            state.validators_mut()[i].effective_balance = 1_000_000_000 * (i as u64 + 1);
+        }
+        state.validators_mut()[num_slashings as usize - 1].slashed = true;
+
+        let (proposer_slashings, _, _) = op_pool.get_slashings_and_exits(&state, &harness.spec);
+        let chosen = proposer_slashings
+            .iter()
+            .map(|slashing| slashing.proposer_index())
+            .collect::<BTreeSet<_>>();
+        assert_eq!(chosen, (1..=max_slashings).collect::<BTreeSet<_>>());
+    }
+
     // Sanity check on the pruning of proposer slashings
     #[test]
     fn prune_proposer_slashing_noop() {
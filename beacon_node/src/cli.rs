@@ -159,9 +159,18 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true),
         )
         .arg(
-            Arg::with_name("disable-upnp")
-                .long("disable-upnp")
-                .help("Disables UPnP support. Setting this will prevent Lighthouse from attempting to automatically establish external port mappings.")
+            Arg::with_name("gossipsub-score-params-file")
+                .long("gossipsub-score-params-file")
+                .value_name("PATH")
+                .help("Path to a TOML file overriding the gossipsub peer score thresholds and \
+                       topic weights. Intended for network experimentation only.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("disable-nat")
+                .long("disable-nat")
+                .alias("disable-upnp")
+                .help("Disables UPnP and NAT-PMP support. Setting this will prevent Lighthouse from attempting to automatically establish external port mappings.")
                 .takes_value(false),
         )
         .arg(
@@ -270,6 +279,23 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("disable-quic")
                 .help("Disables the quic transport. The node will rely solely on the TCP transport for libp2p connections.")
         )
+        .arg(
+            Arg::with_name("disable-peer-persistence")
+                .long("disable-peer-persistence")
+                .help("Disables persisting known peers to the database. By default, peers are \
+                       persisted periodically and on shutdown, and are dialed on startup before \
+                       relying on discovery.")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("persisted-peers-max-age")
+                .long("persisted-peers-max-age")
+                .value_name("SECONDS")
+                .help("Persisted peers which were last seen longer than this many seconds ago \
+                       are ignored on startup.")
+                .default_value("86400")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("disable-peer-scoring")
                 .long("disable-peer-scoring")
@@ -282,7 +308,10 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("trusted-peers")
                 .long("trusted-peers")
                 .value_name("TRUSTED_PEERS")
-                .help("One or more comma-delimited trusted peer ids which always have the highest score according to the peer scoring system.")
+                .help("One or more comma-delimited trusted peers which always have the highest score according to the peer scoring system. \
+                       Trusted peers are never disconnected due to their score or pruned when above the target peer count. \
+                       Each peer may be given as a peer id, an ENR or a multiaddr ending with /p2p/<peer id>. \
+                       Peers given as an ENR or multiaddr are redialed whenever they disconnect.")
                 .takes_value(true),
         )
         .arg(
@@ -307,7 +336,8 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 <protocol_name>:<tokens>/<time_in_seconds>. To set quotas for multiple protocols, \
                 separate them by ';'. If the self rate limiter is enabled and a protocol is not \
                 present in the configuration, the quotas used for the inbound rate limiter will be \
-                used."
+                used, except that the number of BlocksByRange requests is not limited unless \
+                `blocks_by_range_requests` is present."
             )
             .min_values(0)
             .hidden(true)
@@ -329,7 +359,9 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 Rate limit quotas per protocol can be set in the form of \
                 <protocol_name>:<tokens>/<time_in_seconds>. To set quotas for multiple protocols, \
                 separate them by ';'. If the inbound rate limiter is enabled and a protocol is not \
-                present in the configuration, the default quotas will be used. \
+                present in the configuration, the default quotas will be used. The number of \
+                BlocksByRange requests, regardless of their size, can be limited using \
+                `blocks_by_range_requests` in place of a protocol name. \
                 \
                 This is enabled by default, using default quotas. To disable rate limiting pass \
                 `disabled` to this option instead."
@@ -337,6 +369,14 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             .takes_value(true)
             .hidden(true)
         )
+        .arg(
+            Arg::with_name("disable-inbound-rate-limiter")
+                .long("disable-inbound-rate-limiter")
+                .help("Disables rate limiting of requests received from peers. Peers exceeding \
+                    the inbound quotas are otherwise sent a rate limited error and penalised.")
+                .conflicts_with("inbound-rate-limiter")
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("disable-backfill-rate-limiting")
                 .long("disable-backfill-rate-limiting")
@@ -345,6 +385,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     should generally choose to avoid this flag since backfill sync is not required for staking.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("backfill-rate-limit")
+                .long("backfill-rate-limit")
+                .value_name("SLOTS")
+                .help("Process at most one backfill sync batch every SLOTS slots. By default up to \
+                    three batches are processed per slot, at times which avoid the busiest parts of \
+                    the slot. Larger values reduce the resource usage of backfill sync after a \
+                    checkpoint sync at the cost of it taking longer to complete.")
+                .conflicts_with("disable-backfill-rate-limiting")
+                .takes_value(true),
+        )
         /* REST API related arguments */
         .arg(
             Arg::with_name("http")
@@ -449,6 +500,18 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Status code to send when a block that is already known is POSTed to the \
                        HTTP API.")
         )
+        .arg(
+            Arg::with_name("http-attestation-performance-max-epochs")
+                .long("http-attestation-performance-max-epochs")
+                .requires("enable_http")
+                .takes_value(true)
+                .default_value_if("enable_http", None, "100")
+                .value_name("EPOCHS")
+                .help("The maximum number of epochs which may be analysed in a single request to \
+                       the attestation performance endpoint. Requests for larger ranges are \
+                       rejected. Each epoch is replayed, so large values may use significant \
+                       CPU and memory, particularly when querying all validators.")
+        )
         .arg(
             Arg::with_name("http-enable-beacon-processor")
                 .long("http-enable-beacon-processor")
@@ -505,6 +568,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Shufflings are dependent on validator count and setting this value to a large number can consume a large amount of memory.")
             .takes_value(true)
         )
+        .arg(
+            Arg::with_name("snapshot-cache-size")
+            .long("snapshot-cache-size")
+            .value_name("SIZE")
+            .help("Specifies how many recent block snapshots (blocks and their post-states) should \
+                be cached in memory to speed up block import and production. Each snapshot holds \
+                a full beacon state, so large values can consume a large amount of memory. \
+                [default: 4]")
+            .takes_value(true)
+        )
 
         /*
          * Monitoring metrics
@@ -611,10 +684,21 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long("slots-per-restore-point")
                 .value_name("SLOT_COUNT")
                 .help("Specifies how often a freezer DB restore point should be stored. \
-                       Cannot be changed after initialization. \
+                       Cannot be changed after initialization without \
+                       --slots-per-restore-point-migrate. \
                        [default: 8192 (mainnet) or 64 (minimal)]")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("slots-per-restore-point-migrate")
+                .long("slots-per-restore-point-migrate")
+                .help("Migrate an existing freezer DB to the value of --slots-per-restore-point \
+                       if it differs from the value the database was created with. The migration \
+                       runs in the background and resumes after a restart. Historic states remain \
+                       available throughout.")
+                .requires("slots-per-restore-point")
+                .takes_value(false)
+        )
         .arg(
             Arg::with_name("epochs-per-migration")
                 .long("epochs-per-migration")
@@ -629,14 +713,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("block-cache-size")
                 .long("block-cache-size")
                 .value_name("SIZE")
-                .help("Specifies how many blocks the database should cache in memory [default: 5]")
+                .help("Specifies how many blocks the database should cache in memory. \
+                    Set to 0 to disable the cache [default: 5]")
                 .takes_value(true)
         )
         .arg(
             Arg::with_name("historic-state-cache-size")
                 .long("historic-state-cache-size")
                 .value_name("SIZE")
-                .help("Specifies how many states from the freezer database should cache in memory [default: 1]")
+                .help("Specifies how many states from the freezer database should cache in memory. \
+                    Set to 0 to disable the cache [default: 1]")
                 .takes_value(true)
         )
         /*
@@ -730,6 +816,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("1")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("execution-offline-threshold")
+                .long("execution-offline-threshold")
+                .value_name("NUM")
+                .help("The number of consecutive failed newPayload or forkchoiceUpdated calls \
+                       after which the execution layer is reported as offline by the \
+                       /eth/v1/node/syncing and /eth/v1/node/health endpoints. The status is \
+                       reset by the first successful call. Must be greater than 0. [default: 1]")
+                .takes_value(true)
+        )
         /*
          * Database purging and compaction.
          */
@@ -747,7 +843,8 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .arg(
             Arg::with_name("auto-compact-db")
                 .long("auto-compact-db")
-                .help("Enable or disable automatic compaction of the database on finalization.")
+                .help("Enable or disable automatic compaction of the database on finalization \
+                       and after garbage collecting a large number of states on startup.")
                 .takes_value(true)
                 .default_value("true")
         )
@@ -859,6 +956,18 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .requires("slasher")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("slasher-max-prune-txn-size")
+                .long("slasher-max-prune-txn-size")
+                .help(
+                    "Maximum number of expired entries deleted from the slasher database per \
+                     transaction. Pruning is spread over several transactions so that it doesn't \
+                     block batch processing."
+                )
+                .value_name("COUNT")
+                .requires("slasher")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("slasher-chunk-size")
                 .long("slasher-chunk-size")
@@ -952,9 +1061,9 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             Arg::with_name("validator-monitor-auto")
                 .long("validator-monitor-auto")
                 .help("Enables the automatic detection and monitoring of validators connected to the \
-                    HTTP API and using the subnet subscription endpoint. This generally has the \
-                    effect of providing additional logging and metrics for locally controlled \
-                    validators.")
+                    HTTP API and using the subnet subscription, proposer preparation or validator \
+                    registration endpoints. This generally has the effect of providing additional \
+                    logging and metrics for locally controlled validators.")
         )
         .arg(
             Arg::with_name("validator-monitor-pubkeys")
@@ -1046,6 +1155,16 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                        for ensuring the EL is given ample notice. Default: 1/3 of a slot.")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("state-advance-lookahead")
+                .long("state-advance-lookahead")
+                .value_name("MILLISECONDS")
+                .help("The time before the start of the next slot at which the head state should \
+                       be advanced into that slot. This removes slot processing from the critical \
+                       path of block production and import. Must be less than the slot duration. \
+                       Default: 1/4 of a slot.")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("always-prepare-payload")
                 .long("always-prepare-payload")
@@ -1074,6 +1193,21 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .hidden(true)
                 .takes_value(false)
         )
+        .arg(
+            Arg::with_name("paranoid-block-import")
+                .long("paranoid-block-import")
+                .help("Re-verify the proposer signature of blocks published via the HTTP API \
+                       before importing them. By default the signature checked during gossip \
+                       verification is trusted.")
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("disable-unknown-parent-lookup")
+                .long("disable-unknown-parent-lookup")
+                .help("Ignore gossip blocks whose parent is unknown instead of asking sync to \
+                       look up the missing ancestors. Lookups are enabled by default.")
+                .takes_value(false)
+        )
         .arg(
             Arg::with_name("builder-fallback-skips")
                 .long("builder-fallback-skips")
@@ -1138,11 +1272,54 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .requires("builder")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("builder-header-timeout")
+                .long("builder-header-timeout")
+                .value_name("MILLISECONDS")
+                .help("Timeout for requesting a payload header from the builder. If the builder \
+                       does not respond in time the local execution engine's payload is used. \
+                       Must be at most 60000. [default: 1000]")
+                .requires("builder")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("builder-blinded-blocks-timeout")
+                .long("builder-blinded-blocks-timeout")
+                .value_name("MILLISECONDS")
+                .help("Timeout for submitting a signed blinded block to the builder and \
+                       receiving the full payload in response. Must be at most 60000. \
+                       [default: 15000]")
+                .requires("builder")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("builder-register-validator-timeout")
+                .long("builder-register-validator-timeout")
+                .value_name("MILLISECONDS")
+                .help("Timeout for forwarding validator registrations to the builder. \
+                       Must be at most 60000. [default: 15000]")
+                .requires("builder")
+                .takes_value(true)
+        )
+        .arg(
+            Arg::with_name("builder-registration-batch-size")
+                .long("builder-registration-batch-size")
+                .value_name("INTEGER")
+                .help("The maximum number of validator registrations to send to the builder in \
+                       a single request. Registrations which have not changed since they were \
+                       last accepted by the builder are not re-sent, and the remaining batches \
+                       are spread across the slot. [default: 500]")
+                .requires("builder")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("count-unrealized")
                 .long("count-unrealized")
-                .hidden(true)
-                .help("This flag is deprecated and has no effect.")
+                .value_name("BOOLEAN")
+                .help("Whether fork choice should count the unrealized justification and \
+                       finalization of imported blocks, as required by the consensus \
+                       specification. Setting this to `false` is only intended for debugging \
+                       and testing.")
                 .takes_value(true)
                 .default_value("true")
         )
@@ -1200,6 +1377,29 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
             // always using the builder.
             .conflicts_with("builder-profit-threshold")
         )
+        .arg(
+            Arg::with_name("warn-builder-fee-recipient-mismatch")
+            .long("warn-builder-fee-recipient-mismatch")
+            .help("Log a warning when a builder payload pays a fee recipient other than the one \
+                    requested. Builders usually pay the proposer in the last transaction of the \
+                    payload instead, so by default these mismatches are only logged at debug level \
+                    and are not counted against the proposer by the validator monitor.")
+            .requires("builder")
+            .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("builder-boost-factor")
+            .long("builder-boost-factor")
+            .value_name("PERCENTAGE")
+            .help("The percentage to multiply the value of builder payloads by when comparing \
+                    them to the value of the local payload. The builder payload is only used if \
+                    its boosted value exceeds the value of the local payload. For example, a \
+                    value of 90 requires the builder payload to be worth at least ~11% more than \
+                    the local payload, while 100 compares the two values directly. \
+                    [default: 100]")
+            .takes_value(true)
+            .conflicts_with("always-prefer-builder-payload")
+        )
         .arg(
             Arg::with_name("invalid-gossip-verified-blocks-path")
             .long("invalid-gossip-verified-blocks-path")
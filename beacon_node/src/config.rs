@@ -1,6 +1,7 @@
 use beacon_chain::chain_config::{
     DisallowedReOrgOffsets, ReOrgThreshold, DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR,
     DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION, DEFAULT_RE_ORG_THRESHOLD,
+    DEFAULT_STATE_ADVANCE_LOOKAHEAD_FACTOR,
 };
 use clap::ArgMatches;
 use clap_utils::flags::DISABLE_MALLOC_TUNING_FLAG;
@@ -12,7 +13,10 @@ use execution_layer::DEFAULT_JWT_FILE;
 use genesis::Eth1Endpoint;
 use http_api::TlsConfig;
 use lighthouse_network::ListenAddress;
-use lighthouse_network::{multiaddr::Protocol, Enr, Multiaddr, NetworkConfig, PeerIdSerialized};
+use lighthouse_network::{
+    multiaddr::Protocol, Enr, EnrExt, GossipsubScoreOverrides, Multiaddr, NetworkConfig,
+    PeerIdSerialized,
+};
 use sensitive_url::SensitiveUrl;
 use slog::{info, warn, Logger};
 use std::cmp;
@@ -26,6 +30,16 @@ use std::str::FromStr;
 use std::time::Duration;
 use types::{Checkpoint, Epoch, EthSpec, Hash256, PublicKeyBytes, GRAFFITI_BYTES_LEN};
 
+/// Upper bounds for the configurable cache sizes, to catch typos that would exhaust memory.
+const MAX_SHUFFLING_CACHE_SIZE: usize = 4096;
+const MAX_SNAPSHOT_CACHE_SIZE: usize = 64;
+const MAX_BLOCK_CACHE_SIZE: usize = 8192;
+const MAX_HISTORIC_STATE_CACHE_SIZE: usize = 1024;
+
+/// Upper bound for the builder API timeouts. Anything longer than this is certainly a typo, since
+/// even a validator registration would span several slots.
+const MAX_BUILDER_TIMEOUT_MILLIS: u64 = 60_000;
+
 /// Gets the fully-initialized global client.
 ///
 /// The top-level `clap` arguments should be provided as `cli_args`.
@@ -156,10 +170,19 @@ pub fn get_config<E: EthSpec>(
 
         client_config.http_api.duplicate_block_status_code =
             parse_required(cli_args, "http-duplicate-block-status")?;
+
+        client_config.http_api.attestation_performance_max_epochs =
+            parse_required(cli_args, "http-attestation-performance-max-epochs")?;
     }
 
     if let Some(cache_size) = clap_utils::parse_optional(cli_args, "shuffling-cache-size")? {
-        client_config.chain.shuffling_cache_size = cache_size;
+        client_config.chain.shuffling_cache_size =
+            bounded_cache_size("shuffling", cache_size, 1, MAX_SHUFFLING_CACHE_SIZE)?;
+    }
+
+    if let Some(cache_size) = clap_utils::parse_optional(cli_args, "snapshot-cache-size")? {
+        client_config.chain.snapshot_cache_size =
+            bounded_cache_size("snapshot", cache_size, 1, MAX_SNAPSHOT_CACHE_SIZE)?;
     }
 
     /*
@@ -342,6 +365,15 @@ pub fn get_config<E: EthSpec>(
 
             el_config.builder_user_agent =
                 clap_utils::parse_optional(cli_args, "builder-user-agent")?;
+
+            el_config.builder_header_timeout =
+                parse_builder_timeout(cli_args, "builder-header-timeout")?;
+            el_config.builder_blinded_blocks_timeout =
+                parse_builder_timeout(cli_args, "builder-blinded-blocks-timeout")?;
+            el_config.builder_register_validator_timeout =
+                parse_builder_timeout(cli_args, "builder-register-validator-timeout")?;
+            el_config.builder_registration_batch_size =
+                clap_utils::parse_optional(cli_args, "builder-registration-batch-size")?;
         }
 
         // Set config values from parse values.
@@ -356,10 +388,19 @@ pub fn get_config<E: EthSpec>(
             clap_utils::parse_required(cli_args, "builder-profit-threshold")?;
         el_config.always_prefer_builder_payload =
             cli_args.is_present("always-prefer-builder-payload");
+        el_config.warn_builder_fee_recipient_mismatch =
+            cli_args.is_present("warn-builder-fee-recipient-mismatch");
+        el_config.builder_boost_factor =
+            clap_utils::parse_optional(cli_args, "builder-boost-factor")?;
 
         let execution_timeout_multiplier =
             clap_utils::parse_required(cli_args, "execution-timeout-multiplier")?;
         el_config.execution_timeout_multiplier = Some(execution_timeout_multiplier);
+        el_config.execution_offline_threshold =
+            clap_utils::parse_optional(cli_args, "execution-offline-threshold")?;
+        if el_config.execution_offline_threshold == Some(0) {
+            return Err("--execution-offline-threshold must be greater than 0".to_string());
+        }
 
         // If `--execution-endpoint` is provided, we should ignore any `--eth1-endpoints` values and
         // use `--execution-endpoint` instead. Also, log a deprecation warning.
@@ -389,17 +430,27 @@ pub fn get_config<E: EthSpec>(
     let (sprp, sprp_explicit) = get_slots_per_restore_point::<E>(cli_args)?;
     client_config.store.slots_per_restore_point = sprp;
     client_config.store.slots_per_restore_point_set_explicitly = sprp_explicit;
+    client_config.store.slots_per_restore_point_migrate =
+        cli_args.is_present("slots-per-restore-point-migrate");
 
     if let Some(block_cache_size) = cli_args.value_of("block-cache-size") {
-        client_config.store.block_cache_size = block_cache_size
+        let block_cache_size = block_cache_size
             .parse()
             .map_err(|_| "block-cache-size is not a valid integer".to_string())?;
+        client_config.store.block_cache_size =
+            bounded_cache_size("block", block_cache_size, 0, MAX_BLOCK_CACHE_SIZE)?;
     }
 
     if let Some(historic_state_cache_size) = cli_args.value_of("historic-state-cache-size") {
-        client_config.store.historic_state_cache_size = historic_state_cache_size
+        let historic_state_cache_size = historic_state_cache_size
             .parse()
             .map_err(|_| "historic-state-cache-size is not a valid integer".to_string())?;
+        client_config.store.historic_state_cache_size = bounded_cache_size(
+            "historic-state",
+            historic_state_cache_size,
+            0,
+            MAX_HISTORIC_STATE_CACHE_SIZE,
+        )?;
     }
 
     client_config.store.compact_on_init = cli_args.is_present("compact-db");
@@ -470,6 +521,9 @@ pub fn get_config<E: EthSpec>(
     }
     client_config.chain.checkpoint_sync_url_timeout =
         clap_utils::parse_required::<u64>(cli_args, "checkpoint-sync-url-timeout")?;
+    if client_config.chain.checkpoint_sync_url_timeout == 0 {
+        return Err("--checkpoint-sync-url-timeout must be greater than 0".to_string());
+    }
 
     client_config.genesis_state_url_timeout =
         clap_utils::parse_required(cli_args, "genesis-state-url-timeout")
@@ -656,6 +710,12 @@ pub fn get_config<E: EthSpec>(
             slasher_config.attestation_root_cache_size = attestation_cache_size;
         }
 
+        if let Some(max_prune_txn_size) =
+            clap_utils::parse_optional(cli_args, "slasher-max-prune-txn-size")?
+        {
+            slasher_config.max_prune_txn_size = max_prune_txn_size;
+        }
+
         if let Some(chunk_size) = clap_utils::parse_optional(cli_args, "slasher-chunk-size")? {
             slasher_config.chunk_size = chunk_size;
         }
@@ -763,7 +823,39 @@ pub fn get_config<E: EthSpec>(
                     / DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR
             });
 
+    let slot_duration = Duration::from_secs(spec.seconds_per_slot);
+    client_config.chain.state_advance_lookahead =
+        match clap_utils::parse_optional(cli_args, "state-advance-lookahead")? {
+            Some(millis) => {
+                let lookahead = Duration::from_millis(millis);
+                if lookahead.is_zero() || lookahead >= slot_duration {
+                    return Err(format!(
+                        "--state-advance-lookahead must be greater than 0 and less than the slot \
+                         duration ({}ms)",
+                        slot_duration.as_millis()
+                    ));
+                }
+                lookahead
+            }
+            None => slot_duration / DEFAULT_STATE_ADVANCE_LOOKAHEAD_FACTOR,
+        };
+
     client_config.chain.always_prepare_payload = cli_args.is_present("always-prepare-payload");
+    if client_config.chain.always_prepare_payload
+        && client_config
+            .execution_layer
+            .as_ref()
+            .map_or(true, |el_config| {
+                el_config.suggested_fee_recipient.is_none()
+            })
+    {
+        warn!(
+            log,
+            "No fee recipient for --always-prepare-payload";
+            "info" => "payloads for proposers without a registered fee recipient will use a junk \
+                       address, set --suggested-fee-recipient to avoid this"
+        );
+    }
 
     if let Some(timeout) =
         clap_utils::parse_optional(cli_args, "fork-choice-before-proposal-timeout")?
@@ -771,11 +863,13 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.fork_choice_before_proposal_timeout_ms = timeout;
     }
 
-    if !clap_utils::parse_required::<bool>(cli_args, "count-unrealized")? {
+    client_config.chain.count_unrealized =
+        clap_utils::parse_required(cli_args, "count-unrealized")?;
+    if !client_config.chain.count_unrealized {
         warn!(
             log,
-            "The flag --count-unrealized is deprecated and will be removed";
-            "info" => "any use of the flag will have no effect"
+            "Unrealized justification and finalization will not be counted";
+            "info" => "this deviates from the fork choice specification"
         );
     }
 
@@ -792,6 +886,11 @@ pub fn get_config<E: EthSpec>(
 
     client_config.chain.paranoid_block_proposal = cli_args.is_present("paranoid-block-proposal");
 
+    client_config.chain.paranoid_block_import = cli_args.is_present("paranoid-block-import");
+
+    client_config.chain.unknown_parent_lookup =
+        !cli_args.is_present("disable-unknown-parent-lookup");
+
     /*
      * Builder fallback configs.
      */
@@ -820,9 +919,21 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.genesis_backfill = true;
     }
 
+    // Light client server config.
+    client_config.chain.enable_light_client_server = cli_args.is_present("light-client-server");
+
     // Backfill sync rate-limiting
     client_config.beacon_processor.enable_backfill_rate_limiting =
         !cli_args.is_present("disable-backfill-rate-limiting");
+    client_config.http_api.backfill_rate_limited =
+        client_config.beacon_processor.enable_backfill_rate_limiting;
+
+    if let Some(slots) = clap_utils::parse_optional::<u64>(cli_args, "backfill-rate-limit")? {
+        if slots == 0 {
+            return Err("--backfill-rate-limit must be greater than 0".to_string());
+        }
+        client_config.beacon_processor.backfill_rate_limit_slots = Some(slots);
+    }
 
     if let Some(path) = clap_utils::parse_optional(cli_args, "invalid-gossip-verified-blocks-path")?
     {
@@ -1121,6 +1232,15 @@ pub fn set_network_config(
         config.network_load = network_load;
     }
 
+    if let Some(path) = cli_args.value_of("gossipsub-score-params-file") {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read gossipsub score params file: {:?}", e))?;
+        let overrides: GossipsubScoreOverrides = toml::from_str(&contents)
+            .map_err(|e| format!("Unable to parse gossipsub score params file: {}", e))?;
+        overrides.validate()?;
+        config.gossipsub_score_overrides = overrides;
+    }
+
     if let Some(boot_enr_str) = cli_args.value_of("boot-nodes") {
         let mut enrs: Vec<Enr> = vec![];
         let mut multiaddrs: Vec<Multiaddr> = vec![];
@@ -1162,14 +1282,39 @@ pub fn set_network_config(
     }
 
     if let Some(trusted_peers_str) = cli_args.value_of("trusted-peers") {
-        config.trusted_peers = trusted_peers_str
-            .split(',')
-            .map(|peer_id| {
-                peer_id
-                    .parse()
-                    .map_err(|_| format!("Invalid trusted peer id: {}", peer_id))
-            })
-            .collect::<Result<Vec<PeerIdSerialized>, _>>()?;
+        // Trusted peers may be given as a peer id, or as an ENR or multiaddr which also tells us
+        // where to redial the peer.
+        for trusted_peer in trusted_peers_str.split(',') {
+            if trusted_peer.starts_with("enr:") {
+                let enr = trusted_peer
+                    .parse::<Enr>()
+                    .map_err(|e| format!("Invalid trusted peer ENR: {}: {}", trusted_peer, e))?;
+                config.trusted_peers.push(enr.peer_id().into());
+                config
+                    .trusted_peer_addresses
+                    .extend(enr.multiaddr_p2p_tcp());
+            } else if trusted_peer.starts_with('/') {
+                let multiaddr = trusted_peer.parse::<Multiaddr>().map_err(|e| {
+                    format!("Invalid trusted peer multiaddr: {}: {}", trusted_peer, e)
+                })?;
+                match multiaddr.iter().last() {
+                    Some(Protocol::P2p(peer_id)) => config.trusted_peers.push(peer_id.into()),
+                    _ => {
+                        return Err(format!(
+                            "Trusted peer multiaddr must end with /p2p/<peer id>: {}",
+                            trusted_peer
+                        ))
+                    }
+                }
+                config.trusted_peer_addresses.push(multiaddr);
+            } else {
+                config.trusted_peers.push(
+                    trusted_peer
+                        .parse::<PeerIdSerialized>()
+                        .map_err(|_| format!("Invalid trusted peer id: {}", trusted_peer))?,
+                );
+            }
+        }
         if config.trusted_peers.len() >= config.target_peers {
             slog::warn!(log, "More trusted peers than the target peer limit. This will prevent efficient peer selection criteria."; "target_peers" => config.target_peers, "trusted_peers" => config.trusted_peers.len());
         }
@@ -1344,8 +1489,16 @@ pub fn set_network_config(
         config.disable_quic_support = true;
     }
 
-    if cli_args.is_present("disable-upnp") {
-        config.upnp_enabled = false;
+    if cli_args.is_present("disable-peer-persistence") {
+        config.disable_peer_persistence = true;
+    }
+
+    if let Some(max_age) = clap_utils::parse_optional(cli_args, "persisted-peers-max-age")? {
+        config.persisted_peers_max_age = Duration::from_secs(max_age);
+    }
+
+    if cli_args.is_present("disable-nat") {
+        config.nat_enabled = false;
     }
 
     if cli_args.is_present("private") {
@@ -1384,9 +1537,11 @@ pub fn set_network_config(
         config.proposer_only = true;
         warn!(log, "Proposer-only mode enabled"; "info"=> "Do not connect a validator client to this node unless via the --proposer-nodes flag");
     }
-    // The inbound rate limiter is enabled by default unless `disabled` is passed to the
-    // `inbound-rate-limiter` flag. Any other value should be parsed as a configuration string.
+    // The inbound rate limiter is enabled by default unless `--disable-inbound-rate-limiter` is
+    // set or `disabled` is passed to the `inbound-rate-limiter` flag. Any other value should be
+    // parsed as a configuration string.
     config.inbound_rate_limiter_config = match cli_args.value_of("inbound-rate-limiter") {
+        _ if cli_args.is_present("disable-inbound-rate-limiter") => None,
         None => {
             // Enabled by default, with default values
             Some(Default::default())
@@ -1442,6 +1597,42 @@ pub fn get_slots_per_restore_point<E: EthSpec>(
     }
 }
 
+/// Reject a cache size of zero, which would either disable a cache the node relies upon or cause a
+/// panic when the cache is constructed.
+/// Returns `size` if it lies within `min..=max`, otherwise an error naming the offending flag.
+fn bounded_cache_size(
+    cache_name: &str,
+    size: usize,
+    min: usize,
+    max: usize,
+) -> Result<usize, String> {
+    if size < min || size > max {
+        Err(format!(
+            "--{}-cache-size must be between {} and {}",
+            cache_name, min, max
+        ))
+    } else {
+        Ok(size)
+    }
+}
+
+/// Parses the optional builder timeout `flag` in milliseconds, rejecting zero and values above
+/// `MAX_BUILDER_TIMEOUT_MILLIS`.
+fn parse_builder_timeout(cli_args: &ArgMatches, flag: &str) -> Result<Option<Duration>, String> {
+    clap_utils::parse_optional::<u64>(cli_args, flag)?
+        .map(|millis| {
+            if millis == 0 || millis > MAX_BUILDER_TIMEOUT_MILLIS {
+                Err(format!(
+                    "--{} must be between 1 and {} milliseconds",
+                    flag, MAX_BUILDER_TIMEOUT_MILLIS
+                ))
+            } else {
+                Ok(Duration::from_millis(millis))
+            }
+        })
+        .transpose()
+}
+
 /// Parses the `cli_value` as a comma-separated string of values to be parsed with `parser`.
 ///
 /// If there is more than one value, log a warning. If there are no values, return an error.
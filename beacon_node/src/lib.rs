@@ -99,12 +99,12 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
                     );
                 }
                 DatabaseBackendOverride::Failure(path) => {
-                    warn!(
-                        log,
-                        "Slasher backend override failed";
-                        "advice" => "delete old MDBX database or enable MDBX backend",
-                        "path" => path.display()
-                    );
+                    return Err(format!(
+                        "Slasher database {} was created with a backend which is not enabled in \
+                         this binary. Slasher re-sync required: delete the old database, or use \
+                         a binary with that backend enabled.",
+                        path.display()
+                    ));
                 }
                 _ => {}
             }
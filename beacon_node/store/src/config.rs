@@ -16,6 +16,9 @@ pub struct StoreConfig {
     pub slots_per_restore_point: u64,
     /// Flag indicating whether the `slots_per_restore_point` was set explicitly by the user.
     pub slots_per_restore_point_set_explicitly: bool,
+    /// Whether to migrate the freezer database if `slots_per_restore_point` differs from the
+    /// value on disk.
+    pub slots_per_restore_point_migrate: bool,
     /// Maximum number of blocks to store in the in-memory block cache.
     pub block_cache_size: usize,
     /// Maximum number of states from freezer database to store in the in-memory state cache.
@@ -45,6 +48,7 @@ impl Default for StoreConfig {
             // Safe default for tests, shouldn't ever be read by a CLI node.
             slots_per_restore_point: MinimalEthSpec::slots_per_historical_root() as u64,
             slots_per_restore_point_set_explicitly: false,
+            slots_per_restore_point_migrate: false,
             block_cache_size: DEFAULT_BLOCK_CACHE_SIZE,
             historic_state_cache_size: DEFAULT_HISTORIC_STATE_CACHE_SIZE,
             compact_on_init: false,
@@ -0,0 +1,141 @@
+//! Approximate per-column key counts and sizes for the hot and freezer databases.
+use crate::hot_cold_store::HotColdDB;
+use crate::{metrics, DBColumn, Error, ItemStore, KeyValueStore};
+use serde_derive::{Deserialize, Serialize};
+use std::sync::Arc;
+use strum::IntoEnumIterator;
+use types::{Epoch, EthSpec};
+
+/// Read the value of every `SAMPLE_INTERVAL`th key of each database to estimate column sizes.
+/// Keys are always counted exactly.
+pub const SAMPLE_INTERVAL: usize = 64;
+
+/// Gather fresh statistics at most once in this many epochs (~3.4 hours on mainnet).
+///
+/// Counting keys iterates over the entire database, so this is deliberately infrequent.
+pub const DATABASE_STATS_REFRESH_EPOCHS: u64 = 32;
+
+const HOT: &str = "hot";
+const FREEZER: &str = "freezer";
+
+/// The number of keys in a database column and their approximate size.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ColumnStats {
+    pub column: String,
+    pub key_count: u64,
+    /// The uncompressed size of the column's keys and values, in bytes.
+    pub approximate_size: u64,
+}
+
+/// Statistics for every non-empty column of the hot and freezer databases.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseStats {
+    /// The epoch in which the statistics were gathered.
+    pub epoch: Epoch,
+    pub hot: Vec<ColumnStats>,
+    pub freezer: Vec<ColumnStats>,
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Return the most recently gathered database statistics, without gathering them.
+    pub fn cached_database_stats(&self) -> Option<Arc<DatabaseStats>> {
+        self.database_stats.read().clone()
+    }
+
+    /// Return the database statistics, gathering them afresh if they were last gathered
+    /// `DATABASE_STATS_REFRESH_EPOCHS` or more epochs prior to `epoch`.
+    ///
+    /// Gathering iterates over both databases and should only be done on a blocking task. It
+    /// doesn't hold any locks that are needed for block import or `cached_database_stats`.
+    pub fn database_stats(&self, epoch: Epoch) -> Result<Arc<DatabaseStats>, Error> {
+        let is_fresh = |stats: &Arc<DatabaseStats>| {
+            epoch < stats.epoch + DATABASE_STATS_REFRESH_EPOCHS
+        };
+
+        // Hold the lock while gathering to prevent concurrent callers from duplicating the work.
+        let _gathering = self.database_stats_lock.lock();
+        if let Some(stats) = self.cached_database_stats().filter(is_fresh) {
+            return Ok(stats);
+        }
+
+        let stats = Arc::new(DatabaseStats {
+            epoch,
+            hot: all_column_stats(&self.hot_db, HOT, SAMPLE_INTERVAL)?,
+            freezer: all_column_stats(&self.cold_db, FREEZER, SAMPLE_INTERVAL)?,
+        });
+        *self.database_stats.write() = Some(stats.clone());
+
+        Ok(stats)
+    }
+}
+
+/// Gather the statistics for every non-empty column of `db`, updating the metrics for all of
+/// them.
+fn all_column_stats<E: EthSpec, S: KeyValueStore<E>>(
+    db: &S,
+    db_name: &str,
+    sample_interval: usize,
+) -> Result<Vec<ColumnStats>, Error> {
+    let mut all_stats = vec![];
+    for column in DBColumn::iter() {
+        let stats = column_stats(db, column, sample_interval)?;
+
+        metrics::set_gauge_vec(
+            &metrics::DB_COLUMN_KEY_COUNT,
+            &[db_name, &stats.column],
+            stats.key_count as i64,
+        );
+        metrics::set_gauge_vec(
+            &metrics::DB_COLUMN_SIZE,
+            &[db_name, &stats.column],
+            stats.approximate_size as i64,
+        );
+
+        if stats.key_count > 0 {
+            all_stats.push(stats);
+        }
+    }
+    Ok(all_stats)
+}
+
+/// Count the keys in `column`, estimating its size from the values of every `sample_interval`th
+/// key.
+fn column_stats<E: EthSpec, S: KeyValueStore<E>>(
+    db: &S,
+    column: DBColumn,
+    sample_interval: usize,
+) -> Result<ColumnStats, Error> {
+    let mut key_count = 0;
+    let mut sample_count = 0u64;
+    let mut sample_size = 0u64;
+
+    for (i, key) in db.iter_raw_keys(column).enumerate() {
+        let key = key?;
+        key_count += 1;
+
+        if i % sample_interval == 0 {
+            let value_size = db
+                .get_bytes(column.as_str(), &key)?
+                .map_or(0, |value| value.len());
+            sample_count += 1;
+            sample_size += (column.as_bytes().len() + key.len() + value_size) as u64;
+        }
+    }
+
+    let approximate_size = if sample_count == 0 {
+        0
+    } else {
+        (sample_size as f64 * key_count as f64 / sample_count as f64) as u64
+    };
+
+    Ok(ColumnStats {
+        column: format!("{:?}", column),
+        key_count,
+        approximate_size,
+    })
+}
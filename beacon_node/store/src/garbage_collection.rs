@@ -1,8 +1,13 @@
 //! Garbage collection process that runs at start-up to clean up the database.
-use crate::hot_cold_store::HotColdDB;
-use crate::{Error, LevelDB, StoreOp};
-use slog::debug;
-use types::EthSpec;
+use crate::hot_cold_store::{HotColdDB, HotStateSummary};
+use crate::{DBColumn, Error, ItemStore, LevelDB, StoreItem, StoreOp};
+use slog::{debug, info};
+use std::collections::HashSet;
+use types::{EthSpec, Hash256, Slot};
+
+/// Compact the database after garbage collecting at least this many states, if automatic
+/// compaction is enabled.
+pub const GC_COMPACTION_THRESHOLD: usize = 64;
 
 impl<E> HotColdDB<E, LevelDB<E>, LevelDB<E>>
 where
@@ -15,7 +20,9 @@ where
     }
 
     /// Delete the temporary states that were leftover by failed block imports.
-    pub fn delete_temp_states(&self) -> Result<(), Error> {
+    ///
+    /// Return the number of states deleted.
+    pub fn delete_temp_states(&self) -> Result<usize, Error> {
         let delete_ops =
             self.iter_temporary_state_roots()
                 .try_fold(vec![], |mut ops, state_root| {
@@ -25,15 +32,92 @@ where
                     Result::<_, Error>::Ok(ops)
                 })?;
 
+        let num_deleted = delete_ops.len() / 2;
         if !delete_ops.is_empty() {
             debug!(
                 self.log,
-                "Garbage collecting {} temporary states",
-                delete_ops.len() / 2
+                "Garbage collecting {} temporary states", num_deleted
+            );
+            self.do_atomically(delete_ops)?;
+        }
+
+        Ok(num_deleted)
+    }
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Delete hot states which can never be referenced again.
+    ///
+    /// These are states which have already been migrated to the freezer, and states whose latest
+    /// block is not viable according to `is_viable(latest_block_root, slot)` (e.g. states on
+    /// forks that were pruned while the node was offline). The split state, and the epoch boundary
+    /// states of retained states, are always retained so that the next migration can find them.
+    ///
+    /// Return the number of states deleted.
+    pub fn delete_abandoned_hot_states<F>(&self, is_viable: F) -> Result<usize, Error>
+    where
+        F: Fn(&Hash256, Slot) -> bool,
+    {
+        let split = self.get_split_info();
+
+        let mut retained = HashSet::new();
+        let mut abandoned = vec![];
+        for res in self.hot_db.iter_column(DBColumn::BeaconStateSummary) {
+            let (state_root, bytes) = res?;
+            let summary = HotStateSummary::from_store_bytes(&bytes)?;
+
+            if state_root == split.state_root
+                || (summary.slot >= split.slot
+                    && is_viable(&summary.latest_block_root, summary.slot))
+            {
+                retained.insert(state_root);
+                retained.insert(summary.epoch_boundary_state_root);
+            } else {
+                abandoned.push((state_root, summary.slot));
+            }
+        }
+
+        let delete_ops = abandoned
+            .into_iter()
+            .filter(|(state_root, _)| !retained.contains(state_root))
+            .map(|(state_root, slot)| StoreOp::DeleteState(state_root, Some(slot)))
+            .collect::<Vec<_>>();
+
+        let num_deleted = delete_ops.len();
+        if num_deleted > 0 {
+            info!(
+                self.log,
+                "Garbage collecting abandoned hot states";
+                "count" => num_deleted,
             );
             self.do_atomically(delete_ops)?;
         }
 
+        Ok(num_deleted)
+    }
+
+    /// Compact the database if at least `GC_COMPACTION_THRESHOLD` states were garbage collected,
+    /// and automatic compaction is enabled.
+    ///
+    /// This is a full compaction, so it should be run by the background migrator.
+    pub fn compact_after_garbage_collection(&self, num_deleted: usize) -> Result<(), Error> {
+        if num_deleted < GC_COMPACTION_THRESHOLD || !self.compact_on_prune() {
+            return Ok(());
+        }
+
+        info!(
+            self.log,
+            "Compacting database after garbage collection";
+            "states_deleted" => num_deleted,
+        );
+        self.compact()?;
+        info!(self.log, "Database compaction complete");
+
         Ok(())
     }
 }
@@ -5,6 +5,7 @@ use crate::config::{
     OnDiskStoreConfig, StoreConfig, DEFAULT_SLOTS_PER_RESTORE_POINT,
     PREV_DEFAULT_SLOTS_PER_RESTORE_POINT,
 };
+use crate::database_stats::DatabaseStats;
 use crate::forwards_iter::{HybridForwardsBlockRootsIterator, HybridForwardsStateRootsIterator};
 use crate::impls::beacon_state::{get_full_state, store_full_state};
 use crate::iter::{BlockRootsIterator, ParentRootBlockIterator, RootsIterator};
@@ -54,6 +55,10 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     /// The starting slots for the range of blocks & states stored in the database.
     anchor_info: RwLock<Option<AnchorInfo>>,
     pub(crate) config: StoreConfig,
+    /// The spacing of the restore points in the freezer database.
+    ///
+    /// This differs from the configured value while a restore point migration is in progress.
+    pub(crate) slots_per_restore_point: RwLock<u64>,
     /// Cold database containing compact historical data.
     pub cold_db: Cold,
     /// Hot database containing duplicated but quick-to-access recent data.
@@ -64,6 +69,11 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     block_cache: Mutex<LruCache<Hash256, SignedBeaconBlock<E>>>,
     /// LRU cache of replayed states.
     state_cache: Mutex<LruCache<Slot, BeaconState<E>>>,
+    /// The most recently gathered database statistics.
+    pub(crate) database_stats: RwLock<Option<Arc<DatabaseStats>>>,
+    /// Held while gathering database statistics so that concurrent callers don't duplicate the
+    /// work, without blocking readers of the cached statistics.
+    pub(crate) database_stats_lock: Mutex<()>,
     /// Chain spec.
     pub(crate) spec: ChainSpec,
     /// Logger.
@@ -128,10 +138,13 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
         let db = HotColdDB {
             split: RwLock::new(Split::default()),
             anchor_info: RwLock::new(None),
+            slots_per_restore_point: RwLock::new(config.slots_per_restore_point),
             cold_db: MemoryStore::open(),
             hot_db: MemoryStore::open(),
             block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
             state_cache: Mutex::new(LruCache::new(config.historic_state_cache_size)),
+            database_stats: RwLock::new(None),
+            database_stats_lock: Mutex::new(()),
             config,
             spec,
             log,
@@ -162,10 +175,13 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         let mut db = HotColdDB {
             split: RwLock::new(Split::default()),
             anchor_info: RwLock::new(None),
+            slots_per_restore_point: RwLock::new(config.slots_per_restore_point),
             cold_db: LevelDB::open(cold_path)?,
             hot_db: LevelDB::open(hot_path)?,
             block_cache: Mutex::new(LruCache::new(config.block_cache_size)),
             state_cache: Mutex::new(LruCache::new(config.historic_state_cache_size)),
+            database_stats: RwLock::new(None),
+            database_stats_lock: Mutex::new(()),
             config,
             spec,
             log,
@@ -189,6 +205,7 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
 
                 // Mutate the in-memory config so that it's compatible.
                 db.config.slots_per_restore_point = PREV_DEFAULT_SLOTS_PER_RESTORE_POINT;
+                *db.slots_per_restore_point.get_mut() = PREV_DEFAULT_SLOTS_PER_RESTORE_POINT;
             }
         }
 
@@ -222,9 +239,10 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
             db.store_schema_version(CURRENT_SCHEMA_VERSION)?;
         }
 
-        // Ensure that any on-disk config is compatible with the supplied config.
+        // Ensure that any on-disk config is compatible with the supplied config, or begin
+        // migrating the freezer database to the supplied config.
         if let Some(disk_config) = db.load_config()? {
-            db.config.check_compatibility(&disk_config)?;
+            db.init_restore_point_migration(&disk_config)?;
         }
         db.store_config()?;
 
@@ -753,6 +771,28 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.hot_db.exists::<I>(key)
     }
 
+    /// Store the best `LightClientUpdate` for the sync committee `period`.
+    pub fn put_light_client_update(
+        &self,
+        period: u64,
+        update: &LightClientUpdate<E>,
+    ) -> Result<(), Error> {
+        self.put_item(&Self::light_client_update_key(period), update)
+    }
+
+    /// Load the best `LightClientUpdate` for the sync committee `period`, if one is known.
+    pub fn get_light_client_update(
+        &self,
+        period: u64,
+    ) -> Result<Option<LightClientUpdate<E>>, Error> {
+        self.get_item(&Self::light_client_update_key(period))
+    }
+
+    /// Convert a sync committee `period` into a database key.
+    fn light_client_update_key(period: u64) -> Hash256 {
+        Hash256::from_low_u64_be(period)
+    }
+
     /// Convert a batch of `StoreOp` to a batch of `KeyValueStoreOp`.
     pub fn convert_to_kv_batch(
         &self,
@@ -946,7 +986,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ) -> Result<(), Error> {
         ops.push(ColdStateSummary { slot: state.slot() }.as_kv_store_op(*state_root));
 
-        if state.slot() % self.config.slots_per_restore_point != 0 {
+        let slots_per_restore_point = self.slots_per_restore_point();
+        if state.slot() % slots_per_restore_point != 0 {
             return Ok(());
         }
 
@@ -957,6 +998,22 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             "state_root" => format!("{:?}", state_root)
         );
 
+        self.store_restore_point_state(state_root, state, ops)?;
+
+        // Store restore point.
+        let restore_point_index = state.slot().as_u64() / slots_per_restore_point;
+        self.store_restore_point_hash(restore_point_index, *state_root, ops);
+
+        Ok(())
+    }
+
+    /// Store the partial state and vector fields of a restore point state.
+    pub(crate) fn store_restore_point_state(
+        &self,
+        state_root: &Hash256,
+        state: &BeaconState<E>,
+        ops: &mut Vec<KeyValueStoreOp>,
+    ) -> Result<(), Error> {
         // 1. Convert to PartialBeaconState and store that in the DB.
         let partial_state = PartialBeaconState::from_state_forgetful(state);
         let op = partial_state.as_kv_store_op(*state_root);
@@ -973,10 +1030,6 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         store_updated_vector(RandaoMixes, db, state, &self.spec, ops)?;
         store_updated_vector(HistoricalSummaries, db, state, &self.spec, ops)?;
 
-        // 3. Store restore point.
-        let restore_point_index = state.slot().as_u64() / self.config.slots_per_restore_point;
-        self.store_restore_point_hash(restore_point_index, *state_root, ops);
-
         Ok(())
     }
 
@@ -994,14 +1047,18 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ///
     /// Will reconstruct the state if it lies between restore points.
     pub fn load_cold_state_by_slot(&self, slot: Slot) -> Result<Option<BeaconState<E>>, Error> {
+        // Hold the restore point spacing for the duration of the load, so that a concurrent
+        // restore point migration can't replace the restore points underneath us.
+        let slots_per_restore_point = self.slots_per_restore_point.read_recursive();
+
         // Guard against fetching states that do not exist due to gaps in the historic state
         // database, which can occur due to checkpoint sync or re-indexing.
         // See the comments in `get_historic_state_limits` for more information.
         let (lower_limit, upper_limit) = self.get_historic_state_limits();
 
         if slot <= lower_limit || slot >= upper_limit {
-            if slot % self.config.slots_per_restore_point == 0 {
-                let restore_point_idx = slot.as_u64() / self.config.slots_per_restore_point;
+            if slot % *slots_per_restore_point == 0 {
+                let restore_point_idx = slot.as_u64() / *slots_per_restore_point;
                 self.load_restore_point_by_index(restore_point_idx)
             } else {
                 self.load_cold_intermediate_state(slot)
@@ -1047,17 +1104,17 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
 
         // 1. Load the restore points either side of the intermediate state.
-        let low_restore_point_idx = slot.as_u64() / self.config.slots_per_restore_point;
+        let slots_per_restore_point = self.slots_per_restore_point();
+        let low_restore_point_idx = slot.as_u64() / slots_per_restore_point;
         let high_restore_point_idx = low_restore_point_idx + 1;
 
         // Use low restore point as the base state.
-        let mut low_slot: Slot =
-            Slot::new(low_restore_point_idx * self.config.slots_per_restore_point);
+        let mut low_slot: Slot = Slot::new(low_restore_point_idx * slots_per_restore_point);
         let mut low_state: Option<BeaconState<E>> = None;
 
         // Try to get a more recent state from the cache to avoid massive blocks replay.
         for (s, state) in self.state_cache.lock().iter() {
-            if s.as_u64() / self.config.slots_per_restore_point == low_restore_point_idx
+            if s.as_u64() / slots_per_restore_point == low_restore_point_idx
                 && *s < slot
                 && low_slot < *s
             {
@@ -1114,7 +1171,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         restore_point_idx: u64,
         split: &Split,
     ) -> Result<BeaconState<E>, Error> {
-        if restore_point_idx * self.config.slots_per_restore_point >= split.slot.as_u64() {
+        if restore_point_idx * self.slots_per_restore_point() >= split.slot.as_u64() {
             self.get_state(&split.state_root, Some(split.slot))?
                 .ok_or(HotColdDBError::MissingSplitState(
                     split.state_root,
@@ -1256,10 +1313,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         if anchor.map_or(false, |a| a.state_upper_limit >= split_slot) {
             None
         } else {
-            Some(
-                (split_slot - 1) / self.config.slots_per_restore_point
-                    * self.config.slots_per_restore_point,
-            )
+            let slots_per_restore_point = self.slots_per_restore_point();
+            Some((split_slot - 1) / slots_per_restore_point * slots_per_restore_point)
         }
     }
 
@@ -1295,7 +1350,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         retain_historic_states: bool,
     ) -> Result<KeyValueStoreOp, Error> {
         let anchor_slot = block.slot();
-        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let slots_per_restore_point = self.slots_per_restore_point();
 
         let state_upper_limit = if !retain_historic_states {
             STATE_UPPER_LIMIT_NO_RETAIN
@@ -1435,14 +1490,34 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         &self.config
     }
 
+    /// Return the spacing of the restore points in the freezer database.
+    ///
+    /// This is the configured value, unless a restore point migration is in progress.
+    pub fn slots_per_restore_point(&self) -> u64 {
+        *self.slots_per_restore_point.read_recursive()
+    }
+
+    /// Return the number of blocks currently held in the in-memory block cache.
+    pub fn block_cache_len(&self) -> usize {
+        self.block_cache.lock().len()
+    }
+
+    /// Return the number of freezer states currently held in the in-memory state cache.
+    pub fn historic_state_cache_len(&self) -> usize {
+        self.state_cache.lock().len()
+    }
+
     /// Load previously-stored config from disk.
-    fn load_config(&self) -> Result<Option<OnDiskStoreConfig>, Error> {
+    pub(crate) fn load_config(&self) -> Result<Option<OnDiskStoreConfig>, Error> {
         self.hot_db.get(&CONFIG_KEY)
     }
 
-    /// Write the config to disk.
-    fn store_config(&self) -> Result<(), Error> {
-        self.hot_db.put(&CONFIG_KEY, &self.config.as_disk_config())
+    /// Write the config to disk, with the restore point spacing currently used by the freezer.
+    pub(crate) fn store_config(&self) -> Result<(), Error> {
+        let disk_config = OnDiskStoreConfig {
+            slots_per_restore_point: self.slots_per_restore_point(),
+        };
+        self.hot_db.put(&CONFIG_KEY, &disk_config)
     }
 
     /// Load the split point from disk, sans block root.
@@ -1492,7 +1567,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Convert a `restore_point_index` into a database key.
-    fn restore_point_key(restore_point_index: u64) -> Hash256 {
+    pub(crate) fn restore_point_key(restore_point_index: u64) -> Hash256 {
         Hash256::from_low_u64_be(restore_point_index)
     }
 
@@ -1724,6 +1799,120 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         );
         Ok(())
     }
+
+    /// Delete all historic states from the freezer database, keeping only the genesis state.
+    ///
+    /// Blocks and frozen block roots are retained, so that states can later be regenerated by
+    /// historic state reconstruction. The anchor is updated to reflect that states are not
+    /// retained, so the database subsequently behaves like that of a checkpoint-synced node.
+    pub fn prune_historic_states(
+        &self,
+        genesis_state_root: Hash256,
+        genesis_state: &BeaconState<E>,
+    ) -> Result<(), Error> {
+        let old_anchor = self.get_anchor_info();
+
+        if old_anchor.as_ref().map_or(false, |anchor| {
+            anchor.state_upper_limit == STATE_UPPER_LIMIT_NO_RETAIN
+                && anchor.state_lower_limit == self.spec.genesis_slot
+        }) {
+            info!(self.log, "Historic states already pruned");
+            return Ok(());
+        }
+
+        // Mark states as unavailable before deleting them, so that an interrupted prune can't
+        // leave the database claiming to hold states that have been deleted.
+        let new_anchor = if let Some(old_anchor) = old_anchor.clone() {
+            AnchorInfo {
+                state_upper_limit: STATE_UPPER_LIMIT_NO_RETAIN,
+                state_lower_limit: self.spec.genesis_slot,
+                ..old_anchor
+            }
+        } else {
+            // Genesis archive node: all blocks are known.
+            AnchorInfo {
+                anchor_slot: self.spec.genesis_slot,
+                oldest_block_slot: self.spec.genesis_slot,
+                oldest_block_parent: Hash256::zero(),
+                state_upper_limit: STATE_UPPER_LIMIT_NO_RETAIN,
+                state_lower_limit: self.spec.genesis_slot,
+            }
+        };
+        self.compare_and_set_anchor_info_with_write(old_anchor, Some(new_anchor))?;
+        self.state_cache.lock().clear();
+
+        // Any restore points created by an unfinished restore point migration are pruned too.
+        self.abort_restore_point_migration()?;
+
+        let columns = [
+            DBColumn::BeaconState,
+            DBColumn::BeaconStateSummary,
+            DBColumn::BeaconRestorePoint,
+            DBColumn::BeaconStateRoots,
+            DBColumn::BeaconHistoricalRoots,
+            DBColumn::BeaconRandaoMixes,
+            DBColumn::BeaconHistoricalSummaries,
+        ];
+
+        let mut num_deleted = 0;
+        for column in columns {
+            let ops = self
+                .cold_db
+                .iter_raw_keys(column)
+                .map(|key| {
+                    key.map(|key| KeyValueStoreOp::DeleteKey(get_key_for_col(column.into(), &key)))
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            num_deleted += ops.len();
+            self.cold_db.do_atomically(ops)?;
+        }
+
+        // Re-store the genesis state, which is always retained.
+        let mut ops = vec![];
+        self.store_cold_state(&genesis_state_root, genesis_state, &mut ops)?;
+        self.cold_db.do_atomically(ops)?;
+
+        info!(
+            self.log,
+            "Historic states pruned";
+            "keys_deleted" => num_deleted,
+        );
+
+        // Return the space used by the deleted states to the filesystem.
+        self.cold_db.compact()?;
+
+        Ok(())
+    }
+
+    /// Resume storing historic states after they were pruned, so that they may be reconstructed.
+    ///
+    /// The `state_upper_limit` is set to the first restore point after the split, which will be
+    /// stored by the migration once finalized. Until then, reconstruction uses the split state.
+    pub fn resume_historic_state_retention(&self) -> Result<(), Error> {
+        let old_anchor = self.get_anchor_info();
+        let anchor = match old_anchor.clone() {
+            Some(anchor) if anchor.state_upper_limit == STATE_UPPER_LIMIT_NO_RETAIN => anchor,
+            _ => return Ok(()),
+        };
+
+        let slots_per_restore_point = self.slots_per_restore_point();
+        let split_slot = self.get_split_slot();
+        let state_upper_limit = Slot::new(
+            (split_slot.as_u64() / slots_per_restore_point + 1) * slots_per_restore_point,
+        );
+
+        info!(
+            self.log,
+            "Resuming retention of historic states";
+            "state_upper_limit" => state_upper_limit,
+        );
+
+        let new_anchor = AnchorInfo {
+            state_upper_limit,
+            ..anchor
+        };
+        self.compare_and_set_anchor_info_with_write(old_anchor, Some(new_anchor))
+    }
 }
 
 /// Advance the split point of the store, moving new finalized states to the freezer.
@@ -1812,7 +2001,7 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
         let op = cold_state_summary.as_kv_store_op(state_root);
         cold_db_ops.push(op);
 
-        if slot % store.config.slots_per_restore_point == 0 {
+        if slot % store.slots_per_restore_point() == 0 {
             let state: BeaconState<E> = get_full_state(&store.hot_db, &state_root, &store.spec)?
                 .ok_or(HotColdDBError::MissingStateToFreeze(state_root))?;
 
@@ -1932,7 +2121,7 @@ fn no_state_root_iter() -> Option<std::iter::Empty<Result<(Hash256, Slot), Error
 pub struct HotStateSummary {
     pub slot: Slot,
     pub latest_block_root: Hash256,
-    epoch_boundary_state_root: Hash256,
+    pub(crate) epoch_boundary_state_root: Hash256,
 }
 
 impl StoreItem for HotStateSummary {
@@ -1994,8 +2183,8 @@ impl StoreItem for ColdStateSummary {
 
 /// Struct for storing the state root of a restore point in the database.
 #[derive(Debug, Clone, Copy, Default, Encode, Decode)]
-struct RestorePointHash {
-    state_root: Hash256,
+pub(crate) struct RestorePointHash {
+    pub(crate) state_root: Hash256,
 }
 
 impl StoreItem for RestorePointHash {
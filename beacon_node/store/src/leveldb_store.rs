@@ -219,6 +219,19 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
                 }),
         )
     }
+
+    /// Iterate through all keys in a particular column, without assuming that they are hashes.
+    fn iter_raw_keys(&self, column: DBColumn) -> RawKeyIter {
+        let start_key = BytesKey::from_vec(column.as_bytes().to_vec());
+
+        let iter = self.db.keys_iter(self.read_options());
+        iter.seek(&start_key);
+
+        Box::new(
+            iter.take_while(move |key| key.matches_column(column))
+                .map(move |bytes_key| Ok(bytes_key.key[column.as_bytes().len()..].to_vec())),
+        )
+    }
 }
 
 impl<E: EthSpec> ItemStore<E> for LevelDB<E> {}
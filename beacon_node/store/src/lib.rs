@@ -14,6 +14,7 @@ mod chunk_writer;
 pub mod chunked_iter;
 pub mod chunked_vector;
 pub mod config;
+pub mod database_stats;
 pub mod errors;
 mod forwards_iter;
 mod garbage_collection;
@@ -25,6 +26,7 @@ pub mod metadata;
 pub mod metrics;
 mod partial_beacon_state;
 pub mod reconstruct;
+pub mod restore_point_migration;
 
 pub mod iter;
 
@@ -40,11 +42,12 @@ pub use metadata::AnchorInfo;
 pub use metrics::scrape_for_metrics;
 use parking_lot::MutexGuard;
 use std::sync::Arc;
-use strum::{EnumString, IntoStaticStr};
+use strum::{EnumIter, EnumString, IntoStaticStr};
 pub use types::*;
 
 pub type ColumnIter<'a> = Box<dyn Iterator<Item = Result<(Hash256, Vec<u8>), Error>> + 'a>;
 pub type ColumnKeyIter<'a> = Box<dyn Iterator<Item = Result<Hash256, Error>> + 'a>;
+pub type RawKeyIter<'a> = Box<dyn Iterator<Item = Result<Vec<u8>, Error>> + 'a>;
 
 pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
     /// Retrieve some bytes in `column` with `key`.
@@ -90,6 +93,14 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
         // Default impl for non LevelDB databases
         Box::new(std::iter::empty())
     }
+
+    /// Iterate through all keys in a particular column, without assuming that they are hashes.
+    ///
+    /// The column prefix is removed from the returned keys.
+    fn iter_raw_keys(&self, _column: DBColumn) -> RawKeyIter {
+        // Default impl for non LevelDB databases
+        Box::new(std::iter::empty())
+    }
 }
 
 pub fn get_key_for_col(column: &str, key: &[u8]) -> Vec<u8> {
@@ -165,7 +176,7 @@ pub enum StoreOp<'a, E: EthSpec> {
 }
 
 /// A unique column identifier.
-#[derive(Debug, Clone, Copy, PartialEq, IntoStaticStr, EnumString)]
+#[derive(Debug, Clone, Copy, PartialEq, IntoStaticStr, EnumString, EnumIter)]
 pub enum DBColumn {
     /// For data related to the database itself.
     #[strum(serialize = "bma")]
@@ -199,6 +210,9 @@ pub enum DBColumn {
     /// For the table mapping restore point numbers to state roots.
     #[strum(serialize = "brp")]
     BeaconRestorePoint,
+    /// For the restore points being created by a restore point migration.
+    #[strum(serialize = "bpm")]
+    BeaconRestorePointMigration,
     #[strum(serialize = "bbr")]
     BeaconBlockRoots,
     #[strum(serialize = "bsr")]
@@ -214,6 +228,15 @@ pub enum DBColumn {
     OptimisticTransitionBlock,
     #[strum(serialize = "bhs")]
     BeaconHistoricalSummaries,
+    /// For the best `LightClientUpdate` of each sync committee period.
+    #[strum(serialize = "lcu")]
+    LightClientUpdate,
+    /// For proposer preparation data and builder registrations received from validator clients.
+    #[strum(serialize = "ppd")]
+    ProposerPreparation,
+    /// For the known peers persisted by the network service across restarts.
+    #[strum(serialize = "pdb")]
+    PeerDb,
 }
 
 /// A block from the database, which might have an execution payload or not.
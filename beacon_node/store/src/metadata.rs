@@ -15,6 +15,7 @@ pub const SPLIT_KEY: Hash256 = Hash256::repeat_byte(2);
 pub const PRUNING_CHECKPOINT_KEY: Hash256 = Hash256::repeat_byte(3);
 pub const COMPACTION_TIMESTAMP_KEY: Hash256 = Hash256::repeat_byte(4);
 pub const ANCHOR_INFO_KEY: Hash256 = Hash256::repeat_byte(5);
+pub const RESTORE_POINT_MIGRATION_KEY: Hash256 = Hash256::repeat_byte(6);
 
 /// State upper limit value used to indicate that a node is not storing historic states.
 pub const STATE_UPPER_LIMIT_NO_RETAIN: Slot = Slot::new(u64::MAX);
@@ -107,6 +108,11 @@ impl AnchorInfo {
     pub fn block_backfill_complete(&self, target_slot: Slot) -> bool {
         self.oldest_block_slot <= target_slot
     }
+
+    /// Returns true if all historic states between genesis and the anchor have been stored.
+    pub fn all_historic_states_stored(&self) -> bool {
+        self.state_lower_limit >= self.state_upper_limit
+    }
 }
 
 impl StoreItem for AnchorInfo {
@@ -122,3 +128,33 @@ impl StoreItem for AnchorInfo {
         Ok(Self::from_ssz_bytes(bytes)?)
     }
 }
+
+/// Progress of a migration of the freezer database to a new restore point spacing.
+///
+/// Stored in the freezer database, so that it is updated atomically with the restore points.
+#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode, Serialize, Deserialize)]
+pub struct RestorePointMigration {
+    /// The spacing of the restore points being replaced.
+    pub from: u64,
+    /// The spacing of the restore points being created.
+    pub to: u64,
+    /// The slot of the next restore point to create.
+    pub next_slot: Slot,
+    /// Whether the new restore points have replaced the old ones, with only the anchor and config
+    /// left to update.
+    pub swapped: bool,
+}
+
+impl StoreItem for RestorePointMigration {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
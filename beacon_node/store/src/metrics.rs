@@ -11,6 +11,16 @@ lazy_static! {
         try_create_int_gauge("store_disk_db_size", "Size of the hot on-disk database (bytes)");
     pub static ref FREEZER_DB_SIZE: Result<IntGauge> =
         try_create_int_gauge("store_freezer_db_size", "Size of the on-disk freezer database (bytes)");
+    pub static ref DB_COLUMN_KEY_COUNT: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "store_db_column_key_count",
+        "Number of keys in each column of the hot and freezer databases",
+        &["db", "column"]
+    );
+    pub static ref DB_COLUMN_SIZE: Result<IntGaugeVec> = try_create_int_gauge_vec(
+        "store_db_column_size_bytes",
+        "Approximate uncompressed size of each column of the hot and freezer databases (bytes)",
+        &["db", "column"]
+    );
     pub static ref DISK_DB_WRITE_BYTES: Result<IntCounter> = try_create_int_counter(
         "store_disk_db_write_bytes_total",
         "Number of bytes attempted to be written to the hot on-disk DB"
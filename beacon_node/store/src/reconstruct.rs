@@ -16,7 +16,15 @@ where
     Hot: ItemStore<E>,
     Cold: ItemStore<E>,
 {
-    pub fn reconstruct_historic_states(self: &Arc<Self>) -> Result<(), Error> {
+    /// Replay blocks forwards from the state lower limit, storing historic states in the freezer.
+    ///
+    /// If `num_slots` is `Some`, return once at least that many slots have been replayed and the
+    /// progress up to the most recent restore point has been persisted, so that the caller may
+    /// yield to other work before resuming. If `None`, run until reconstruction is complete.
+    pub fn reconstruct_historic_states(
+        self: &Arc<Self>,
+        num_slots: Option<usize>,
+    ) -> Result<(), Error> {
         let mut anchor = if let Some(anchor) = self.get_anchor_info() {
             anchor
         } else {
@@ -37,7 +45,7 @@ where
             "start_slot" => anchor.state_lower_limit,
         );
 
-        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let slots_per_restore_point = self.slots_per_restore_point();
 
         // Iterate blocks from the state lower limit to the upper limit.
         let lower_limit_slot = anchor.state_lower_limit;
@@ -69,6 +77,7 @@ where
             let mut io_batch = vec![];
 
             let mut prev_state_root = None;
+            let mut num_slots_replayed = 0;
 
             for ((prev_block_root, _), (block_root, slot)) in iter.tuple_windows() {
                 let is_skipped_slot = prev_block_root == block_root;
@@ -112,6 +121,7 @@ where
 
                 // Stage state for storage in freezer DB.
                 self.store_cold_state(&state_root, &state, &mut io_batch)?;
+                num_slots_replayed += 1;
 
                 // If the slot lies on an epoch boundary, commit the batch and update the anchor.
                 if slot % slots_per_restore_point == 0 || slot + 1 == upper_limit_slot {
@@ -150,6 +160,18 @@ where
                             old_anchor,
                             Some(anchor.clone()),
                         )?;
+
+                        // Yield if this batch is complete. Progress has been persisted, so the
+                        // next call will resume from the new lower limit.
+                        if num_slots.map_or(false, |n| num_slots_replayed >= n) {
+                            info!(
+                                self.log,
+                                "State reconstruction paused";
+                                "slot" => slot,
+                                "remaining" => upper_limit_slot - 1 - slot
+                            );
+                            return Ok(());
+                        }
                     }
                 }
             }
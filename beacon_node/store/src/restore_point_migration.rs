@@ -0,0 +1,261 @@
+//! Migration of the freezer database to a new restore point spacing.
+//!
+//! The new restore points are created alongside the old ones in a separate column, one at a time,
+//! with the progress persisted atomically. Once they extend to the split they replace the old
+//! restore points in a single write, after which the anchor and on-disk config are updated.
+use crate::config::OnDiskStoreConfig;
+use crate::hot_cold_store::{HotColdDB, RestorePointHash};
+use crate::metadata::{
+    AnchorInfo, RestorePointMigration, RESTORE_POINT_MIGRATION_KEY, STATE_UPPER_LIMIT_NO_RETAIN,
+};
+use crate::{get_key_for_col, DBColumn, Error, ItemStore, KeyValueStoreOp, StoreItem};
+use slog::info;
+use types::{EthSpec, Slot};
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Return the progress of the restore point migration, if one is in progress.
+    pub fn get_restore_point_migration(&self) -> Result<Option<RestorePointMigration>, Error> {
+        self.cold_db.get(&RESTORE_POINT_MIGRATION_KEY)
+    }
+
+    /// Return `true` if the freezer database is being migrated to a new restore point spacing.
+    pub fn restore_point_migration_in_progress(&self) -> bool {
+        matches!(self.get_restore_point_migration(), Ok(Some(_)))
+    }
+
+    /// Reconcile the configured restore point spacing with the one on disk.
+    ///
+    /// A mismatch is an error unless `slots_per_restore_point_migrate` is set, in which case a
+    /// migration is started (or resumed) and the freezer continues to use the on-disk spacing until
+    /// it completes.
+    pub(crate) fn init_restore_point_migration(
+        &self,
+        disk_config: &OnDiskStoreConfig,
+    ) -> Result<(), Error> {
+        let mut on_disk = disk_config.slots_per_restore_point;
+        let configured = self.config.slots_per_restore_point;
+
+        if let Some(migration) = self.get_restore_point_migration()? {
+            if migration.swapped {
+                // The restore points were replaced, but we were interrupted before finishing.
+                *self.slots_per_restore_point.write() = migration.to;
+                self.finish_restore_point_migration(&migration)?;
+                on_disk = migration.to;
+            } else if migration.to == configured {
+                info!(
+                    self.log,
+                    "Resuming restore point migration";
+                    "from" => migration.from,
+                    "to" => migration.to,
+                    "next_slot" => migration.next_slot,
+                );
+                *self.slots_per_restore_point.write() = migration.from;
+                return Ok(());
+            } else {
+                info!(
+                    self.log,
+                    "Abandoning restore point migration";
+                    "from" => migration.from,
+                    "to" => migration.to,
+                );
+                self.abort_restore_point_migration()?;
+            }
+        }
+
+        if configured == on_disk {
+            *self.slots_per_restore_point.write() = configured;
+            return Ok(());
+        }
+
+        if !self.config.slots_per_restore_point_migrate {
+            self.config.check_compatibility(&OnDiskStoreConfig {
+                slots_per_restore_point: on_disk,
+            })?;
+        }
+
+        info!(
+            self.log,
+            "Starting restore point migration";
+            "from" => on_disk,
+            "to" => configured,
+        );
+        let migration = RestorePointMigration {
+            from: on_disk,
+            to: configured,
+            next_slot: self.spec.genesis_slot,
+            swapped: false,
+        };
+        self.cold_db
+            .do_atomically(vec![migration.as_kv_store_op(RESTORE_POINT_MIGRATION_KEY)])?;
+        *self.slots_per_restore_point.write() = on_disk;
+
+        Ok(())
+    }
+
+    /// Create restore points at the new spacing, from the last one created up to the split.
+    ///
+    /// If `num_restore_points` is `Some`, return once that many restore points have been created,
+    /// so that the caller may yield to other work before resuming. If `None`, run until the
+    /// migration is complete.
+    pub fn migrate_restore_points(&self, num_restore_points: Option<usize>) -> Result<(), Error> {
+        let mut migration = match self.get_restore_point_migration()? {
+            Some(migration) if !migration.swapped => migration,
+            Some(migration) => return self.finish_restore_point_migration(&migration),
+            None => return Ok(()),
+        };
+
+        // The split can't advance while we're running on the migrator thread.
+        let split_slot = self.get_split_slot();
+        let mut num_created = 0;
+
+        while migration.next_slot < split_slot {
+            let slot = migration.next_slot;
+            let mut ops = vec![];
+
+            // States which are unavailable (e.g. due to checkpoint sync) are skipped, and remain
+            // unavailable under the new spacing.
+            if let Some(mut state) = self.load_cold_state_by_slot(slot)? {
+                let state_root = state.update_tree_hash_cache()?;
+                self.store_restore_point_state(&state_root, &state, &mut ops)?;
+
+                let key = Self::restore_point_key(slot.as_u64() / migration.to);
+                ops.push(KeyValueStoreOp::PutKeyValue(
+                    get_key_for_col(DBColumn::BeaconRestorePointMigration.into(), key.as_bytes()),
+                    RestorePointHash { state_root }.as_store_bytes(),
+                ));
+                num_created += 1;
+            }
+
+            migration.next_slot = slot + migration.to;
+            ops.push(migration.as_kv_store_op(RESTORE_POINT_MIGRATION_KEY));
+            self.cold_db.do_atomically(ops)?;
+
+            if num_restore_points.map_or(false, |n| num_created >= n) {
+                info!(
+                    self.log,
+                    "Restore point migration in progress";
+                    "slot" => slot,
+                    "remaining_restore_points" => (split_slot - slot).as_u64() / migration.to,
+                );
+                return Ok(());
+            }
+        }
+
+        self.complete_restore_point_migration(migration)
+    }
+
+    /// Replace the old restore points with the new ones in a single write.
+    fn complete_restore_point_migration(
+        &self,
+        mut migration: RestorePointMigration,
+    ) -> Result<(), Error> {
+        // Block loads of frozen states until the in-memory spacing matches the restore points.
+        let mut slots_per_restore_point = self.slots_per_restore_point.write();
+        let mut ops = vec![];
+
+        for key in self.cold_db.iter_column_keys(DBColumn::BeaconRestorePoint) {
+            let key = key?;
+            let index = key.to_low_u64_be();
+
+            // Delete states which are no longer restore points.
+            if (index * migration.from) % migration.to != 0 {
+                if let Some(RestorePointHash { state_root }) = self.cold_db.get(&key)? {
+                    ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                        DBColumn::BeaconState.into(),
+                        state_root.as_bytes(),
+                    )));
+                }
+            }
+            ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                DBColumn::BeaconRestorePoint.into(),
+                key.as_bytes(),
+            )));
+        }
+
+        let column = DBColumn::BeaconRestorePointMigration;
+        for res in self.cold_db.iter_column(column) {
+            let (key, value) = res?;
+            ops.push(KeyValueStoreOp::PutKeyValue(
+                get_key_for_col(DBColumn::BeaconRestorePoint.into(), key.as_bytes()),
+                value,
+            ));
+            ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                column.into(),
+                key.as_bytes(),
+            )));
+        }
+
+        migration.swapped = true;
+        ops.push(migration.as_kv_store_op(RESTORE_POINT_MIGRATION_KEY));
+        self.cold_db.do_atomically(ops)?;
+
+        *slots_per_restore_point = migration.to;
+        drop(slots_per_restore_point);
+
+        self.finish_restore_point_migration(&migration)
+    }
+
+    /// Align the anchor with the new restore points and record the new spacing on disk.
+    fn finish_restore_point_migration(
+        &self,
+        migration: &RestorePointMigration,
+    ) -> Result<(), Error> {
+        let to = migration.to;
+        let old_anchor = self.get_anchor_info();
+
+        if let Some(anchor) = old_anchor.clone() {
+            // States between the old and new limits are treated as unavailable, and may be
+            // regenerated by historic state reconstruction.
+            let state_upper_limit = if anchor.state_upper_limit == STATE_UPPER_LIMIT_NO_RETAIN {
+                STATE_UPPER_LIMIT_NO_RETAIN
+            } else {
+                Slot::new((anchor.state_upper_limit.as_u64() + to - 1) / to * to)
+            };
+            let state_lower_limit = Slot::new(anchor.state_lower_limit.as_u64() / to * to);
+
+            let new_anchor = AnchorInfo {
+                state_upper_limit,
+                state_lower_limit,
+                ..anchor
+            };
+            if old_anchor.as_ref() != Some(&new_anchor) {
+                self.compare_and_set_anchor_info_with_write(old_anchor, Some(new_anchor))?;
+            }
+        }
+
+        self.store_config()?;
+        self.cold_db
+            .delete::<RestorePointMigration>(&RESTORE_POINT_MIGRATION_KEY)?;
+
+        info!(
+            self.log,
+            "Restore point migration complete";
+            "slots_per_restore_point" => to,
+        );
+        Ok(())
+    }
+
+    /// Discard the progress of a restore point migration, keeping the old restore points.
+    pub(crate) fn abort_restore_point_migration(&self) -> Result<(), Error> {
+        let column = DBColumn::BeaconRestorePointMigration;
+        let mut ops = self
+            .cold_db
+            .iter_column_keys(column)
+            .map(|key| {
+                key.map(|key| {
+                    KeyValueStoreOp::DeleteKey(get_key_for_col(column.into(), key.as_bytes()))
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+            RestorePointMigration::db_column().into(),
+            RESTORE_POINT_MIGRATION_KEY.as_bytes(),
+        )));
+        self.cold_db.do_atomically(ops)
+    }
+}
@@ -28,7 +28,7 @@ use reqwest::{
 pub use reqwest::{StatusCode, Url};
 pub use sensitive_url::{SensitiveError, SensitiveUrl};
 use serde::{de::DeserializeOwned, Serialize};
-use ssz::Encode;
+use ssz::{Decode, Encode};
 use std::convert::TryFrom;
 use std::fmt;
 use std::iter::Iterator;
@@ -40,6 +40,13 @@ pub const V1: EndpointVersion = EndpointVersion(1);
 pub const V2: EndpointVersion = EndpointVersion(2);
 
 pub const CONSENSUS_VERSION_HEADER: &str = "Eth-Consensus-Version";
+/// The total time taken by Lighthouse to produce a block, in milliseconds.
+pub const BLOCK_PRODUCTION_TIME_HEADER: &str = "Lighthouse-Block-Production-Ms";
+
+/// The header holding the time taken by `stage` of Lighthouse block production, in milliseconds.
+pub fn block_production_stage_header(stage: &str) -> String {
+    format!("Lighthouse-Block-Production-{}-Ms", stage.replace('_', "-"))
+}
 
 #[derive(Debug)]
 pub enum Error {
@@ -980,6 +987,106 @@ impl BeaconNodeHttpClient {
             .transpose()
     }
 
+    /// Path for `v1/beacon/light_client/bootstrap/{block_root}`
+    pub fn get_light_client_bootstrap_path(&self, block_root: Hash256) -> Result<Url, Error> {
+        let mut path = self.eth_path(V1)?;
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("light_client")
+            .push("bootstrap")
+            .push(&format!("{:?}", block_root));
+        Ok(path)
+    }
+
+    /// `GET v1/beacon/light_client/bootstrap/{block_root}`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_light_client_bootstrap<T: EthSpec>(
+        &self,
+        block_root: Hash256,
+    ) -> Result<Option<ForkVersionedResponse<LightClientBootstrap<T>>>, Error> {
+        let path = self.get_light_client_bootstrap_path(block_root)?;
+        self.get_opt(path).await
+    }
+
+    /// `GET v1/beacon/light_client/bootstrap/{block_root}` as SSZ
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_light_client_bootstrap_ssz<T: EthSpec>(
+        &self,
+        block_root: Hash256,
+    ) -> Result<Option<LightClientBootstrap<T>>, Error> {
+        let path = self.get_light_client_bootstrap_path(block_root)?;
+        let response = match self
+            .get_response(path, |b| b.accept(Accept::Ssz))
+            .await
+            .optional()?
+        {
+            Some(res) => res,
+            None => return Ok(None),
+        };
+
+        LightClientBootstrap::from_ssz_bytes(&response.bytes().await?)
+            .map(Some)
+            .map_err(Error::InvalidSsz)
+    }
+
+    /// `GET v1/beacon/light_client/finality_update`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_light_client_finality_update<T: EthSpec>(
+        &self,
+    ) -> Result<Option<ForkVersionedResponse<LightClientFinalityUpdate<T>>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("light_client")
+            .push("finality_update");
+
+        self.get_opt(path).await
+    }
+
+    /// `GET v1/beacon/light_client/optimistic_update`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_light_client_optimistic_update<T: EthSpec>(
+        &self,
+    ) -> Result<Option<ForkVersionedResponse<LightClientOptimisticUpdate<T>>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("light_client")
+            .push("optimistic_update");
+
+        self.get_opt(path).await
+    }
+
+    /// `GET v1/beacon/light_client/updates?start_period,count`
+    pub async fn get_light_client_updates<T: EthSpec>(
+        &self,
+        start_period: u64,
+        count: u64,
+    ) -> Result<Vec<ForkVersionedResponse<LightClientUpdate<T>>>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("light_client")
+            .push("updates");
+
+        path.query_pairs_mut()
+            .append_pair("start_period", &start_period.to_string())
+            .append_pair("count", &count.to_string());
+
+        self.get(path).await
+    }
+
     /// `GET beacon/blocks/{block_id}/root`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -1211,56 +1318,60 @@ impl BeaconNodeHttpClient {
             .map(|opt| opt.map(|r| r.data))
     }
 
-    /// `POST beacon/rewards/sync_committee`
+    /// `POST beacon/rewards/sync_committee/{block_id}`
     pub async fn post_beacon_rewards_sync_committee(
         &self,
-        rewards: &[Option<Vec<lighthouse::SyncCommitteeReward>>],
-    ) -> Result<(), Error> {
+        block_id: BlockId,
+        validators: &[ValidatorId],
+    ) -> Result<
+        ExecutionOptimisticFinalizedResponse<Option<Vec<lighthouse::SyncCommitteeReward>>>,
+        Error,
+    > {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
             .map_err(|()| Error::InvalidUrl(self.server.clone()))?
             .push("beacon")
             .push("rewards")
-            .push("sync_committee");
-
-        self.post(path, &rewards).await?;
+            .push("sync_committee")
+            .push(&block_id.to_string());
 
-        Ok(())
+        self.post_with_response(path, &validators).await
     }
 
-    /// `GET beacon/rewards/blocks`
-    pub async fn get_beacon_rewards_blocks(&self, epoch: Epoch) -> Result<(), Error> {
+    /// `GET beacon/rewards/blocks/{block_id}`
+    pub async fn get_beacon_rewards_blocks(
+        &self,
+        block_id: BlockId,
+    ) -> Result<ExecutionOptimisticFinalizedResponse<lighthouse::StandardBlockReward>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
             .map_err(|()| Error::InvalidUrl(self.server.clone()))?
             .push("beacon")
             .push("rewards")
-            .push("blocks");
-
-        path.query_pairs_mut()
-            .append_pair("epoch", &epoch.to_string());
+            .push("blocks")
+            .push(&block_id.to_string());
 
         self.get(path).await
     }
 
-    /// `POST beacon/rewards/attestations`
+    /// `POST beacon/rewards/attestations/{epoch}`
     pub async fn post_beacon_rewards_attestations(
         &self,
-        attestations: &[ValidatorId],
-    ) -> Result<(), Error> {
+        epoch: Epoch,
+        validators: &[ValidatorId],
+    ) -> Result<ExecutionOptimisticResponse<lighthouse::StandardAttestationRewards>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
             .map_err(|()| Error::InvalidUrl(self.server.clone()))?
             .push("beacon")
             .push("rewards")
-            .push("attestations");
-
-        self.post(path, &attestations).await?;
+            .push("attestations")
+            .push(&epoch.to_string());
 
-        Ok(())
+        self.post_with_response(path, &validators).await
     }
 
     // GET builder/states/{state_id}/expected_withdrawals
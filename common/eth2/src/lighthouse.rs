@@ -20,10 +20,13 @@ use reqwest::IntoUrl;
 use serde::{Deserialize, Serialize};
 use ssz::four_byte_option_impl;
 use ssz_derive::{Decode, Encode};
-use store::{AnchorInfo, Split, StoreConfig};
+use store::{
+    database_stats::DatabaseStats, metadata::RestorePointMigration, AnchorInfo, Split, StoreConfig,
+};
 
 pub use attestation_performance::{
     AttestationPerformance, AttestationPerformanceQuery, AttestationPerformanceStatistics,
+    EpochAttestationPerformance,
 };
 pub use attestation_rewards::StandardAttestationRewards;
 pub use block_packing_efficiency::{
@@ -31,7 +34,7 @@ pub use block_packing_efficiency::{
 };
 pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
 pub use lighthouse_network::{types::SyncState, PeerInfo};
-pub use standard_block_rewards::StandardBlockReward;
+pub use standard_block_rewards::{PublishedBlockReward, StandardBlockReward};
 pub use sync_committee_rewards::SyncCommitteeReward;
 
 // Define "legacy" implementations of `Option<T>` which use four bytes for encoding the union
@@ -50,6 +53,63 @@ pub struct Peer<T: EthSpec> {
     pub peer_info: PeerInfo<T>,
 }
 
+/// Information returned by `peers`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(bound = "T: EthSpec")]
+pub struct PeersResponse<T: EthSpec> {
+    /// All peers known to the node.
+    pub peers: Vec<Peer<T>>,
+    /// The number of peers on each subnet the node is subscribed to.
+    pub subnets: Vec<SubnetPeers>,
+}
+
+/// The number of peers on a subnet, as returned by `peers`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubnetPeers {
+    /// The kind of subnet, either `attestation` or `sync_committee`.
+    pub kind: String,
+    /// The index of the subnet amongst subnets of the same kind.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub subnet_id: u64,
+    /// The number of good peers subscribed to the subnet.
+    pub peers: usize,
+    /// The number of peers the node aims to maintain on the subnet.
+    pub target_peers: usize,
+}
+
+/// The response of `GET lighthouse/syncing`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncingResponse {
+    /// The sync state of the node.
+    pub data: SyncState,
+    /// Whether the node is synced to the head and downloading historical blocks.
+    pub is_backfilling: bool,
+    /// Whether the historical block download is rate-limited. Only present while backfilling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backfill_rate_limited: Option<bool>,
+}
+
+/// The request body of `POST lighthouse/validator_monitor/individual_tracking_threshold`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndividualTrackingThreshold {
+    /// The number of monitored validators above which per-validator metrics are disabled.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub threshold: u64,
+}
+
+/// The state of the validator monitor returned by
+/// `POST lighthouse/validator_monitor/individual_tracking_threshold`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndividualTrackingStatus {
+    /// The number of monitored validators above which per-validator metrics are disabled.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub threshold: u64,
+    /// Whether per-validator metrics and logs are currently emitted.
+    pub individual_tracking: bool,
+    /// The number of monitored validators.
+    pub monitored_validators: usize,
+}
+
 /// The results of validators voting during an epoch.
 ///
 /// Provides information about the current and previous epochs.
@@ -364,6 +424,43 @@ pub struct DatabaseInfo {
     pub config: StoreConfig,
     pub split: Split,
     pub anchor: Option<AnchorInfo>,
+    #[serde(default)]
+    pub caches: CachesInfo,
+    /// Progress of the migration to the configured `slots_per_restore_point`, if in progress.
+    #[serde(default)]
+    pub restore_point_migration: Option<RestorePointMigration>,
+    /// The spacing of the restore points in the freezer database, which differs from the
+    /// configured value while a restore point migration is in progress.
+    #[serde(default)]
+    pub slots_per_restore_point: u64,
+    /// Key counts and approximate sizes of the database columns, gathered at most once per epoch.
+    #[serde(default)]
+    pub stats: Option<DatabaseStats>,
+}
+
+/// The configured capacity and current number of entries of an in-memory cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CacheInfo {
+    pub capacity: usize,
+    pub len: usize,
+}
+
+/// Information about the beacon node's in-memory block, state and shuffling caches.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CachesInfo {
+    pub snapshot: CacheInfo,
+    pub shuffling: CacheInfo,
+    pub block: CacheInfo,
+    pub historic_state: CacheInfo,
+}
+
+/// The engine API methods supported by the connected execution engine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionEngineCapabilities {
+    /// Methods supported by both Lighthouse and the execution engine.
+    pub supported: Vec<String>,
+    /// Methods supported by Lighthouse but not by the execution engine.
+    pub missing: Vec<String>,
 }
 
 impl BeaconNodeHttpClient {
@@ -401,7 +498,7 @@ impl BeaconNodeHttpClient {
     }
 
     /// `GET lighthouse/syncing`
-    pub async fn get_lighthouse_syncing(&self) -> Result<GenericResponse<SyncState>, Error> {
+    pub async fn get_lighthouse_syncing(&self) -> Result<SyncingResponse, Error> {
         let mut path = self.server.full.clone();
 
         path.path_segments_mut()
@@ -512,6 +609,21 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/execution/capabilities`
+    pub async fn get_lighthouse_execution_capabilities(
+        &self,
+    ) -> Result<GenericResponse<ExecutionEngineCapabilities>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("execution")
+            .push("capabilities");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/beacon/states/{state_id}/ssz`
     pub async fn get_lighthouse_beacon_states_ssz<E: EthSpec>(
         &self,
@@ -572,6 +684,23 @@ impl BeaconNodeHttpClient {
         self.post_with_response(path, &()).await
     }
 
+    /// `POST lighthouse/validator_monitor/individual_tracking_threshold`
+    pub async fn post_lighthouse_validator_monitor_individual_tracking_threshold(
+        &self,
+        threshold: u64,
+    ) -> Result<GenericResponse<IndividualTrackingStatus>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator_monitor")
+            .push("individual_tracking_threshold");
+
+        self.post_with_response(path, &IndividualTrackingThreshold { threshold })
+            .await
+    }
+
     ///
     /// Analysis endpoints.
     ///
@@ -640,4 +769,26 @@ impl BeaconNodeHttpClient {
 
         self.get(path).await
     }
+
+    /// `GET` lighthouse/analysis/attestation_performance/all?start_epoch,end_epoch
+    pub async fn get_lighthouse_analysis_attestation_performance_all(
+        &self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<EpochAttestationPerformance>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("attestation_performance")
+            .push("all");
+
+        path.query_pairs_mut()
+            .append_pair("start_epoch", &start_epoch.to_string())
+            .append_pair("end_epoch", &end_epoch.to_string());
+
+        self.get(path).await
+    }
 }
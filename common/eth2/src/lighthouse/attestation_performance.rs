@@ -31,6 +31,15 @@ impl AttestationPerformance {
     }
 }
 
+/// The attestation performance of every validator in a single epoch.
+///
+/// The statistics of the validator with index `i` are at position `i` of `validators`.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct EpochAttestationPerformance {
+    pub epoch: u64,
+    pub validators: Vec<AttestationPerformanceStatistics>,
+}
+
 /// Query parameters for the `/lighthouse/analysis/attestation_performance` endpoint.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct AttestationPerformanceQuery {
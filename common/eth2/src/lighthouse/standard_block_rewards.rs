@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use types::{Hash256, Slot};
 
 // Details about the rewards for a single block
 // All rewards in GWei
@@ -24,3 +25,13 @@ pub struct StandardBlockReward {
     #[serde(with = "serde_utils::quoted_u64")]
     pub attester_slashings: u64,
 }
+
+// Proposer reward for a block published via the HTTP API, as emitted on the
+// `published_block_reward` event stream
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PublishedBlockReward {
+    pub block_root: Hash256,
+    pub slot: Slot,
+    #[serde(flatten)]
+    pub reward: StandardBlockReward,
+}
@@ -382,6 +382,20 @@ impl ValidatorClientHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/doppelganger`
+    pub async fn get_lighthouse_doppelganger(
+        &self,
+    ) -> Result<GenericResponse<Vec<DoppelgangerData>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("doppelganger");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/validators/{validator_pubkey}`
     pub async fn get_lighthouse_validators_pubkey(
         &self,
@@ -666,6 +680,7 @@ impl ValidatorClientHttpClient {
         &self,
         pubkey: &PublicKeyBytes,
         epoch: Option<Epoch>,
+        genesis_validators_root: Option<Hash256>,
     ) -> Result<GenericResponse<SignedVoluntaryExit>, Error> {
         let mut path = self.server.full.clone();
 
@@ -682,6 +697,13 @@ impl ValidatorClientHttpClient {
                 .append_pair("epoch", &epoch.to_string());
         }
 
+        if let Some(genesis_validators_root) = genesis_validators_root {
+            path.query_pairs_mut().append_pair(
+                "genesis_validators_root",
+                &format!("{:?}", genesis_validators_root),
+            );
+        }
+
         self.post(path, &()).await
     }
 }
@@ -5,10 +5,23 @@ use types::{Address, PublicKeyBytes};
 
 pub use slashing_protection::interchange::Interchange;
 
+/// Indicates whether a per-validator proposal setting was explicitly set for that validator, was
+/// loaded from the validator client's fee recipient file, or is inherited from the validator
+/// client's defaults.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalSettingSource {
+    Override,
+    File,
+    Default,
+}
+
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
 pub struct GetFeeRecipientResponse {
     pub pubkey: PublicKeyBytes,
     pub ethaddress: Address,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<ProposalSettingSource>,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
@@ -16,6 +29,8 @@ pub struct GetGasLimitResponse {
     pub pubkey: PublicKeyBytes,
     #[serde(with = "serde_utils::quoted_u64")]
     pub gas_limit: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<ProposalSettingSource>,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
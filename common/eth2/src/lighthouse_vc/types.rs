@@ -16,6 +16,25 @@ pub struct ValidatorData {
     pub voting_pubkey: PublicKeyBytes,
 }
 
+/// The status of a validator with regards to doppelganger protection.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoppelgangerState {
+    /// The validator has passed doppelganger protection and may sign messages.
+    SigningEnabled,
+    /// The validator is waiting for doppelganger protection to complete and will not sign
+    /// slashable messages.
+    SigningDisabled,
+    /// The validator is unknown to the doppelganger service and will not sign any messages.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoppelgangerData {
+    pub voting_pubkey: PublicKeyBytes,
+    pub status: DoppelgangerState,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ValidatorRequest {
     pub enable: bool,
@@ -151,6 +170,9 @@ pub struct UpdateGasLimitRequest {
 #[derive(Deserialize)]
 pub struct VoluntaryExitQuery {
     pub epoch: Option<Epoch>,
+    /// Overrides the genesis validators root that the validator client has cached, e.g. to
+    /// pre-sign an exit for a network the beacon node has not yet reported.
+    pub genesis_validators_root: Option<Hash256>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -2,7 +2,7 @@
 //! required for the HTTP API.
 
 use crate::Error as ServerError;
-use lighthouse_network::{ConnectionDirection, Enr, Multiaddr, PeerConnectionStatus};
+use lighthouse_network::{Client, ConnectionDirection, Enr, Multiaddr, PeerConnectionStatus};
 use mediatype::{names, MediaType, MediaTypeList};
 use serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
@@ -12,7 +12,7 @@ use std::time::Duration;
 pub use types::*;
 
 #[cfg(feature = "lighthouse")]
-use crate::lighthouse::BlockReward;
+use crate::lighthouse::{BlockReward, PublishedBlockReward};
 
 /// An API error serializable to JSON.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -473,6 +473,12 @@ pub struct RandaoQuery {
     pub epoch: Option<Epoch>,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct LightClientUpdatesQuery {
+    pub start_period: u64,
+    pub count: u64,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct AttestationPoolQuery {
     pub slot: Option<Slot>,
@@ -579,6 +585,12 @@ pub struct SyncingData {
     pub el_offline: Option<bool>,
     pub head_slot: Slot,
     pub sync_distance: Slot,
+    /// Whether the node is synced to the head and downloading historical blocks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_backfilling: Option<bool>,
+    /// Whether the historical block download is rate-limited. Only present while backfilling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backfill_rate_limited: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -757,6 +769,31 @@ pub struct PeerData {
     pub last_seen_p2p_address: String,
     pub state: PeerState,
     pub direction: PeerDirection,
+    /// The client the peer is running, if it has identified itself. This is a Lighthouse
+    /// extension to the standard response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client: Option<PeerClient>,
+}
+
+/// The client a peer is running, as parsed from its identify agent string.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PeerClient {
+    pub kind: String,
+    pub version: String,
+    pub os_version: String,
+    pub agent_string: String,
+}
+
+impl PeerClient {
+    /// Returns the client of a peer, or `None` if the peer has not sent an agent string.
+    pub fn from_client(client: &Client) -> Option<Self> {
+        client.agent_string.as_ref().map(|agent_string| PeerClient {
+            kind: client.kind.to_string(),
+            version: client.version.clone(),
+            os_version: client.os_version.clone(),
+            agent_string: agent_string.clone(),
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -1008,6 +1045,8 @@ pub enum EventKind<T: EthSpec> {
     LateHead(SseLateHead),
     #[cfg(feature = "lighthouse")]
     BlockReward(BlockReward),
+    #[cfg(feature = "lighthouse")]
+    PublishedBlockReward(PublishedBlockReward),
     PayloadAttributes(VersionedSsePayloadAttributes),
 }
 
@@ -1025,6 +1064,8 @@ impl<T: EthSpec> EventKind<T> {
             EventKind::LateHead(_) => "late_head",
             #[cfg(feature = "lighthouse")]
             EventKind::BlockReward(_) => "block_reward",
+            #[cfg(feature = "lighthouse")]
+            EventKind::PublishedBlockReward(_) => "published_block_reward",
         }
     }
 
@@ -1086,6 +1127,12 @@ impl<T: EthSpec> EventKind<T> {
             "block_reward" => Ok(EventKind::BlockReward(serde_json::from_str(data).map_err(
                 |e| ServerError::InvalidServerSentEvent(format!("Block Reward: {:?}", e)),
             )?)),
+            #[cfg(feature = "lighthouse")]
+            "published_block_reward" => Ok(EventKind::PublishedBlockReward(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Published Block Reward: {:?}", e))
+                })?,
+            )),
             _ => Err(ServerError::InvalidServerSentEvent(
                 "Could not parse event tag".to_string(),
             )),
@@ -1114,6 +1161,8 @@ pub enum EventTopic {
     PayloadAttributes,
     #[cfg(feature = "lighthouse")]
     BlockReward,
+    #[cfg(feature = "lighthouse")]
+    PublishedBlockReward,
 }
 
 impl FromStr for EventTopic {
@@ -1132,6 +1181,8 @@ impl FromStr for EventTopic {
             "late_head" => Ok(EventTopic::LateHead),
             #[cfg(feature = "lighthouse")]
             "block_reward" => Ok(EventTopic::BlockReward),
+            #[cfg(feature = "lighthouse")]
+            "published_block_reward" => Ok(EventTopic::PublishedBlockReward),
             _ => Err("event topic cannot be parsed.".to_string()),
         }
     }
@@ -1151,6 +1202,8 @@ impl fmt::Display for EventTopic {
             EventTopic::LateHead => write!(f, "late_head"),
             #[cfg(feature = "lighthouse")]
             EventTopic::BlockReward => write!(f, "block_reward"),
+            #[cfg(feature = "lighthouse")]
+            EventTopic::PublishedBlockReward => write!(f, "published_block_reward"),
         }
     }
 }
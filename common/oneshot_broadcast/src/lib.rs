@@ -4,6 +4,7 @@
 //! This implementation may not be blazingly fast but it should be simple enough to be reliable.
 use parking_lot::{Condvar, Mutex};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Error {
@@ -81,6 +82,33 @@ impl<T: Clone> Receiver<T> {
             }
         }
     }
+
+    /// Returns `true` if `self` and `other` receive from the same `Sender`.
+    pub fn is_same_channel(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// As per `Self::recv`, but returns `Ok(None)` if no message has been sent and the `Sender`
+    /// has not been dropped after `timeout` has elapsed.
+    pub fn recv_timeout(self, timeout: Duration) -> Result<Option<T>, Error> {
+        let deadline = Instant::now() + timeout;
+        let mut lock = self.0.mutex.lock();
+        loop {
+            match &*lock {
+                Future::Ready(item) => return Ok(Some(item.clone())),
+                Future::NotReady => {
+                    if self.0.condvar.wait_until(&mut lock, deadline).timed_out() {
+                        return match &*lock {
+                            Future::Ready(item) => Ok(Some(item.clone())),
+                            Future::NotReady => Ok(None),
+                            Future::SenderDropped => Err(Error::SenderDropped),
+                        };
+                    }
+                }
+                Future::SenderDropped => return Err(Error::SenderDropped),
+            }
+        }
+    }
 }
 
 /// A single-sender, multiple-receiver broadcast channel.
@@ -134,6 +162,41 @@ mod tests {
         assert_eq!(receiver.recv(), Err(Error::SenderDropped));
     }
 
+    #[test]
+    fn same_channel() {
+        let (_sender_a, receiver_a) = oneshot::<u8>();
+        let (_sender_b, receiver_b) = oneshot::<u8>();
+        assert!(receiver_a.is_same_channel(&receiver_a.clone()));
+        assert!(!receiver_a.is_same_channel(&receiver_b));
+    }
+
+    #[test]
+    fn recv_timeout_no_message() {
+        let (sender, receiver) = oneshot::<u8>();
+        assert_eq!(receiver.recv_timeout(Duration::from_millis(10)), Ok(None));
+        drop(sender);
+    }
+
+    #[test]
+    fn two_threads_recv_timeout_message_sent() {
+        let (sender, receiver) = oneshot();
+
+        let handle = thread::spawn(|| receiver.recv_timeout(Duration::from_secs(60)));
+
+        sender.send(42);
+        assert_eq!(handle.join().unwrap(), Ok(Some(42)));
+    }
+
+    #[test]
+    fn two_threads_recv_timeout_sender_dropped() {
+        let (sender, receiver) = oneshot::<u8>();
+
+        let handle = thread::spawn(|| receiver.recv_timeout(Duration::from_secs(60)));
+
+        drop(sender);
+        assert_eq!(handle.join().unwrap(), Err(Error::SenderDropped));
+    }
+
     #[test]
     fn two_threads_message_sent() {
         let (sender, receiver) = oneshot();
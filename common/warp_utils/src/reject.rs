@@ -73,6 +73,15 @@ pub fn custom_not_found(msg: String) -> warp::reject::Rejection {
     warp::reject::custom(CustomNotFound(msg))
 }
 
+#[derive(Debug)]
+pub struct CustomForbidden(pub String);
+
+impl Reject for CustomForbidden {}
+
+pub fn custom_forbidden(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(CustomForbidden(msg))
+}
+
 #[derive(Debug)]
 pub struct CustomBadRequest(pub String);
 
@@ -182,6 +191,9 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
     } else if let Some(e) = err.find::<crate::reject::CustomNotFound>() {
         code = StatusCode::NOT_FOUND;
         message = format!("NOT_FOUND: {}", e.0);
+    } else if let Some(e) = err.find::<crate::reject::CustomForbidden>() {
+        code = StatusCode::FORBIDDEN;
+        message = format!("FORBIDDEN: {}", e.0);
     } else if let Some(e) = err.find::<crate::reject::CustomBadRequest>() {
         code = StatusCode::BAD_REQUEST;
         message = format!("BAD_REQUEST: {}", e.0);
@@ -133,6 +133,30 @@ impl ResetPayloadStatuses {
     }
 }
 
+/// Controls whether fork choice computes and tracks the unrealized justification and finalization
+/// of imported blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountUnrealized {
+    True,
+    False,
+}
+
+impl CountUnrealized {
+    pub fn is_true(&self) -> bool {
+        matches!(self, CountUnrealized::True)
+    }
+}
+
+impl From<bool> for CountUnrealized {
+    fn from(count_unrealized: bool) -> Self {
+        if count_unrealized {
+            CountUnrealized::True
+        } else {
+            CountUnrealized::False
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum InvalidBlock {
     UnknownParent(Hash256),
@@ -658,6 +682,7 @@ where
         state: &BeaconState<E>,
         payload_verification_status: PayloadVerificationStatus,
         progressive_balances_mode: ProgressiveBalancesMode,
+        count_unrealized: CountUnrealized,
         spec: &ChainSpec,
         log: &Logger,
     ) -> Result<(), Error<T::Error>> {
@@ -732,127 +757,139 @@ where
             state.finalized_checkpoint(),
         )?;
 
-        // Update unrealized justified/finalized checkpoints.
-        let block_epoch = block.slot().epoch(E::slots_per_epoch());
-
-        // If the parent checkpoints are already at the same epoch as the block being imported,
-        // it's impossible for the unrealized checkpoints to differ from the parent's. This
-        // holds true because:
-        //
-        // 1. A child block cannot have lower FFG checkpoints than its parent.
-        // 2. A block in epoch `N` cannot contain attestations which would justify an epoch higher than `N`.
-        // 3. A block in epoch `N` cannot contain attestations which would finalize an epoch higher than `N - 1`.
-        //
-        // This is an optimization. It should reduce the amount of times we run
-        // `process_justification_and_finalization` by approximately 1/3rd when the chain is
-        // performing optimally.
-        let parent_checkpoints = parent_block
-            .unrealized_justified_checkpoint
-            .zip(parent_block.unrealized_finalized_checkpoint)
-            .filter(|(parent_justified, parent_finalized)| {
-                parent_justified.epoch == block_epoch && parent_finalized.epoch + 1 == block_epoch
-            });
-
-        let (unrealized_justified_checkpoint, unrealized_finalized_checkpoint) = if let Some((
-            parent_justified,
-            parent_finalized,
-        )) =
-            parent_checkpoints
+        // Update unrealized justified/finalized checkpoints, unless disabled in which case fork
+        // choice falls back to the realized checkpoints of the block.
+        let (unrealized_justified_checkpoint, unrealized_finalized_checkpoint) = if count_unrealized
+            .is_true()
         {
-            (parent_justified, parent_finalized)
-        } else {
-            let justification_and_finalization_state = match block {
-                BeaconBlockRef::Capella(_)
-                | BeaconBlockRef::Merge(_)
-                | BeaconBlockRef::Altair(_) => match progressive_balances_mode {
-                    ProgressiveBalancesMode::Disabled => {
-                        let participation_cache = ParticipationCache::new(state, spec)
-                            .map_err(Error::ParticipationCacheBuild)?;
-                        per_epoch_processing::altair::process_justification_and_finalization(
+            let block_epoch = block.slot().epoch(E::slots_per_epoch());
+
+            // If the parent checkpoints are already at the same epoch as the block being imported,
+            // it's impossible for the unrealized checkpoints to differ from the parent's. This
+            // holds true because:
+            //
+            // 1. A child block cannot have lower FFG checkpoints than its parent.
+            // 2. A block in epoch `N` cannot contain attestations which would justify an epoch higher than `N`.
+            // 3. A block in epoch `N` cannot contain attestations which would finalize an epoch higher than `N - 1`.
+            //
+            // This is an optimization. It should reduce the amount of times we run
+            // `process_justification_and_finalization` by approximately 1/3rd when the chain is
+            // performing optimally.
+            let parent_checkpoints = parent_block
+                .unrealized_justified_checkpoint
+                .zip(parent_block.unrealized_finalized_checkpoint)
+                .filter(|(parent_justified, parent_finalized)| {
+                    parent_justified.epoch == block_epoch
+                        && parent_finalized.epoch + 1 == block_epoch
+                });
+
+            let (unrealized_justified_checkpoint, unrealized_finalized_checkpoint) = if let Some(
+                (parent_justified, parent_finalized),
+            ) =
+                parent_checkpoints
+            {
+                (parent_justified, parent_finalized)
+            } else {
+                let justification_and_finalization_state = match block {
+                    BeaconBlockRef::Capella(_)
+                    | BeaconBlockRef::Merge(_)
+                    | BeaconBlockRef::Altair(_) => match progressive_balances_mode {
+                        ProgressiveBalancesMode::Disabled => {
+                            let participation_cache = ParticipationCache::new(state, spec)
+                                .map_err(Error::ParticipationCacheBuild)?;
+                            per_epoch_processing::altair::process_justification_and_finalization(
+                                state,
+                                &participation_cache,
+                            )?
+                        }
+                        ProgressiveBalancesMode::Fast
+                        | ProgressiveBalancesMode::Checked
+                        | ProgressiveBalancesMode::Strict => {
+                            let maybe_participation_cache = progressive_balances_mode
+                                .perform_comparative_checks()
+                                .then(|| {
+                                    ParticipationCache::new(state, spec)
+                                        .map_err(Error::ParticipationCacheBuild)
+                                })
+                                .transpose()?;
+
+                            process_justification_and_finalization_from_progressive_cache::<E, T>(
+                                    state,
+                                    maybe_participation_cache.as_ref(),
+                                )
+                                .or_else(|e| {
+                                    if progressive_balances_mode != ProgressiveBalancesMode::Strict {
+                                        error!(
+                                            log,
+                                            "Processing with progressive balances cache failed";
+                                            "info" => "falling back to the non-optimized processing method",
+                                            "error" => ?e,
+                                        );
+                                        let participation_cache = maybe_participation_cache
+                                            .map(Ok)
+                                            .unwrap_or_else(|| ParticipationCache::new(state, spec))
+                                            .map_err(Error::ParticipationCacheBuild)?;
+                                        per_epoch_processing::altair::process_justification_and_finalization(
+                                            state,
+                                            &participation_cache,
+                                        ).map_err(Error::from)
+                                    } else {
+                                        Err(e)
+                                    }
+                                })?
+                        }
+                    },
+                    BeaconBlockRef::Base(_) => {
+                        let mut validator_statuses =
+                            per_epoch_processing::base::ValidatorStatuses::new(state, spec)
+                                .map_err(Error::ValidatorStatuses)?;
+                        validator_statuses
+                            .process_attestations(state)
+                            .map_err(Error::ValidatorStatuses)?;
+                        per_epoch_processing::base::process_justification_and_finalization(
                             state,
-                            &participation_cache,
+                            &validator_statuses.total_balances,
+                            spec,
                         )?
                     }
-                    ProgressiveBalancesMode::Fast
-                    | ProgressiveBalancesMode::Checked
-                    | ProgressiveBalancesMode::Strict => {
-                        let maybe_participation_cache = progressive_balances_mode
-                            .perform_comparative_checks()
-                            .then(|| {
-                                ParticipationCache::new(state, spec)
-                                    .map_err(Error::ParticipationCacheBuild)
-                            })
-                            .transpose()?;
-
-                        process_justification_and_finalization_from_progressive_cache::<E, T>(
-                                state,
-                                maybe_participation_cache.as_ref(),
-                            )
-                            .or_else(|e| {
-                                if progressive_balances_mode != ProgressiveBalancesMode::Strict {
-                                    error!(
-                                        log,
-                                        "Processing with progressive balances cache failed";
-                                        "info" => "falling back to the non-optimized processing method",
-                                        "error" => ?e,
-                                    );
-                                    let participation_cache = maybe_participation_cache
-                                        .map(Ok)
-                                        .unwrap_or_else(|| ParticipationCache::new(state, spec))
-                                        .map_err(Error::ParticipationCacheBuild)?;
-                                    per_epoch_processing::altair::process_justification_and_finalization(
-                                        state,
-                                        &participation_cache,
-                                    ).map_err(Error::from)
-                                } else {
-                                    Err(e)
-                                }
-                            })?
-                    }
-                },
-                BeaconBlockRef::Base(_) => {
-                    let mut validator_statuses =
-                        per_epoch_processing::base::ValidatorStatuses::new(state, spec)
-                            .map_err(Error::ValidatorStatuses)?;
-                    validator_statuses
-                        .process_attestations(state)
-                        .map_err(Error::ValidatorStatuses)?;
-                    per_epoch_processing::base::process_justification_and_finalization(
-                        state,
-                        &validator_statuses.total_balances,
-                        spec,
-                    )?
-                }
+                };
+
+                (
+                    justification_and_finalization_state.current_justified_checkpoint(),
+                    justification_and_finalization_state.finalized_checkpoint(),
+                )
             };
 
+            // Update best known unrealized justified & finalized checkpoints
+            if unrealized_justified_checkpoint.epoch
+                > self.fc_store.unrealized_justified_checkpoint().epoch
+            {
+                self.fc_store
+                    .set_unrealized_justified_checkpoint(unrealized_justified_checkpoint);
+            }
+            if unrealized_finalized_checkpoint.epoch
+                > self.fc_store.unrealized_finalized_checkpoint().epoch
+            {
+                self.fc_store
+                    .set_unrealized_finalized_checkpoint(unrealized_finalized_checkpoint);
+            }
+
+            // If block is from past epochs, try to update store's justified & finalized checkpoints right away
+            if block.slot().epoch(E::slots_per_epoch()) < current_slot.epoch(E::slots_per_epoch()) {
+                self.pull_up_store_checkpoints(
+                    unrealized_justified_checkpoint,
+                    unrealized_finalized_checkpoint,
+                )?;
+            }
+
             (
-                justification_and_finalization_state.current_justified_checkpoint(),
-                justification_and_finalization_state.finalized_checkpoint(),
+                Some(unrealized_justified_checkpoint),
+                Some(unrealized_finalized_checkpoint),
             )
+        } else {
+            (None, None)
         };
 
-        // Update best known unrealized justified & finalized checkpoints
-        if unrealized_justified_checkpoint.epoch
-            > self.fc_store.unrealized_justified_checkpoint().epoch
-        {
-            self.fc_store
-                .set_unrealized_justified_checkpoint(unrealized_justified_checkpoint);
-        }
-        if unrealized_finalized_checkpoint.epoch
-            > self.fc_store.unrealized_finalized_checkpoint().epoch
-        {
-            self.fc_store
-                .set_unrealized_finalized_checkpoint(unrealized_finalized_checkpoint);
-        }
-
-        // If block is from past epochs, try to update store's justified & finalized checkpoints right away
-        if block.slot().epoch(E::slots_per_epoch()) < current_slot.epoch(E::slots_per_epoch()) {
-            self.pull_up_store_checkpoints(
-                unrealized_justified_checkpoint,
-                unrealized_finalized_checkpoint,
-            )?;
-        }
-
         let target_slot = block
             .slot()
             .epoch(E::slots_per_epoch())
@@ -922,8 +959,8 @@ where
                 justified_checkpoint: state.current_justified_checkpoint(),
                 finalized_checkpoint: state.finalized_checkpoint(),
                 execution_status,
-                unrealized_justified_checkpoint: Some(unrealized_justified_checkpoint),
-                unrealized_finalized_checkpoint: Some(unrealized_finalized_checkpoint),
+                unrealized_justified_checkpoint,
+                unrealized_finalized_checkpoint,
             },
             current_slot,
         )?;
@@ -339,6 +339,7 @@ impl ForkChoiceTest {
                 &state,
                 PayloadVerificationStatus::Verified,
                 self.harness.chain.config.progressive_balances_mode,
+                self.harness.chain.config.count_unrealized.into(),
                 &self.harness.chain.spec,
                 self.harness.logger(),
             )
@@ -383,6 +384,7 @@ impl ForkChoiceTest {
                 &state,
                 PayloadVerificationStatus::Verified,
                 self.harness.chain.config.progressive_balances_mode,
+                self.harness.chain.config.count_unrealized.into(),
                 &self.harness.chain.spec,
                 self.harness.logger(),
             )
@@ -1389,3 +1391,49 @@ async fn progressive_balances_cache_proposer_slashing() {
         .apply_blocks(MainnetEthSpec::slots_per_epoch() as usize)
         .await;
 }
+
+/// Checks that fork choice tracks the unrealized justification of imported blocks by default.
+#[tokio::test]
+async fn count_unrealized_enabled() {
+    let test = ForkChoiceTest::new()
+        .apply_blocks_while(|_, state| state.finalized_checkpoint().epoch == 0)
+        .await
+        .unwrap();
+
+    let head_block_root = test.harness.head_block_root();
+    let fork_choice = test.harness.chain.canonical_head.fork_choice_read_lock();
+    let head_block = fork_choice.get_block(&head_block_root).unwrap();
+
+    assert!(fork_choice.unrealized_justified_checkpoint().epoch > 0);
+    assert!(head_block.unrealized_justified_checkpoint.is_some());
+    assert!(head_block.unrealized_finalized_checkpoint.is_some());
+}
+
+/// Checks that fork choice ignores the unrealized justification of imported blocks when
+/// `count_unrealized` is disabled, whilst still following the realized checkpoints.
+#[tokio::test]
+async fn count_unrealized_disabled() {
+    let chain_config = ChainConfig {
+        count_unrealized: false,
+        ..ChainConfig::default()
+    };
+
+    let test = ForkChoiceTest::new_with_chain_config(chain_config)
+        .apply_blocks_while(|_, state| state.finalized_checkpoint().epoch == 0)
+        .await
+        .unwrap();
+
+    {
+        let head_block_root = test.harness.head_block_root();
+        let fork_choice = test.harness.chain.canonical_head.fork_choice_read_lock();
+        let head_block = fork_choice.get_block(&head_block_root).unwrap();
+
+        assert_eq!(fork_choice.unrealized_justified_checkpoint().epoch, 0);
+        assert_eq!(fork_choice.unrealized_finalized_checkpoint().epoch, 0);
+        assert!(head_block.unrealized_justified_checkpoint.is_none());
+        assert!(head_block.unrealized_finalized_checkpoint.is_none());
+        assert!(head_block.justified_checkpoint.epoch > 0);
+    }
+
+    test.apply_blocks(1).await.assert_finalized_epoch(2);
+}
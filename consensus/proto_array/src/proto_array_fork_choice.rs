@@ -249,6 +249,7 @@ pub enum DoNotReOrg {
     MissingHeadFinalizedCheckpoint,
     ParentDistance,
     HeadDistance,
+    NotFullyVerified,
     ShufflingUnstable,
     DisallowedOffset {
         offset: u64,
@@ -273,6 +274,9 @@ impl std::fmt::Display for DoNotReOrg {
             Self::MissingHeadFinalizedCheckpoint => write!(f, "finalized checkpoint missing"),
             Self::ParentDistance => write!(f, "parent too far from head"),
             Self::HeadDistance => write!(f, "head too far from current slot"),
+            Self::NotFullyVerified => {
+                write!(f, "head or parent execution payload not fully verified")
+            }
             Self::ShufflingUnstable => write!(f, "shuffling unstable at epoch boundary"),
             Self::DisallowedOffset { offset } => {
                 write!(f, "re-orgs disabled at offset {offset}")
@@ -574,6 +578,14 @@ impl ProtoArrayForkChoice {
             return Err(DoNotReOrg::ParentDistance.into());
         }
 
+        // Check that neither the head nor the parent are optimistic. Building upon an optimistic
+        // parent risks producing an invalid block.
+        if head_node.execution_status.is_optimistic_or_invalid()
+            || parent_node.execution_status.is_optimistic_or_invalid()
+        {
+            return Err(DoNotReOrg::NotFullyVerified.into());
+        }
+
         // Check shuffling stability.
         let shuffling_stable = re_org_block_slot % E::slots_per_epoch() != 0;
         if !shuffling_stable {
@@ -1065,6 +1077,95 @@ mod test_compute_deltas {
         assert!(!fc.is_descendant(not_finalized_desc, unknown));
     }
 
+    /// Builds `genesis <- parent <- head` at slots 0, 1 and 2 and asks whether the proposer at
+    /// slot 3 may re-org `head`.
+    fn proposer_head_info_with_statuses(
+        parent_status: ExecutionStatus,
+        head_status: ExecutionStatus,
+    ) -> Result<ProposerHeadInfo, ProposerHeadError<Error>> {
+        let genesis_slot = Slot::new(0);
+        let state_root = Hash256::zero();
+        let genesis_root = hash_from_index(0);
+        let parent_root = hash_from_index(1);
+        let head_root = hash_from_index(2);
+        let junk_shuffling_id =
+            AttestationShufflingId::from_components(Epoch::new(0), Hash256::zero());
+
+        let genesis_checkpoint = Checkpoint {
+            epoch: Epoch::new(0),
+            root: genesis_root,
+        };
+
+        let mut fc = ProtoArrayForkChoice::new::<MainnetEthSpec>(
+            genesis_slot,
+            genesis_slot,
+            state_root,
+            genesis_checkpoint,
+            genesis_checkpoint,
+            junk_shuffling_id.clone(),
+            junk_shuffling_id.clone(),
+            ExecutionStatus::Valid(ExecutionBlockHash::zero()),
+        )
+        .unwrap();
+
+        for (slot, root, parent, execution_status) in [
+            (1, parent_root, genesis_root, parent_status),
+            (2, head_root, parent_root, head_status),
+        ] {
+            fc.process_block::<MainnetEthSpec>(
+                Block {
+                    slot: Slot::new(slot),
+                    root,
+                    parent_root: Some(parent),
+                    state_root,
+                    target_root: genesis_root,
+                    current_epoch_shuffling_id: junk_shuffling_id.clone(),
+                    next_epoch_shuffling_id: junk_shuffling_id.clone(),
+                    justified_checkpoint: genesis_checkpoint,
+                    finalized_checkpoint: genesis_checkpoint,
+                    execution_status,
+                    unrealized_justified_checkpoint: Some(genesis_checkpoint),
+                    unrealized_finalized_checkpoint: Some(genesis_checkpoint),
+                },
+                Slot::new(slot),
+            )
+            .unwrap();
+        }
+
+        fc.get_proposer_head_info::<MainnetEthSpec>(
+            Slot::new(3),
+            head_root,
+            &JustifiedBalances::from_effective_balances(vec![32_000_000_000; 64]).unwrap(),
+            ReOrgThreshold(20),
+            &DisallowedReOrgOffsets::default(),
+            Epoch::new(2),
+        )
+    }
+
+    #[test]
+    fn proposer_head_not_fully_verified() {
+        let valid = ExecutionStatus::Valid(ExecutionBlockHash::repeat_byte(1));
+        let optimistic = ExecutionStatus::Optimistic(ExecutionBlockHash::repeat_byte(2));
+
+        // An optimistic head must not be re-orged.
+        assert!(matches!(
+            proposer_head_info_with_statuses(valid, optimistic),
+            Err(ProposerHeadError::DoNotReOrg(DoNotReOrg::NotFullyVerified))
+        ));
+
+        // Nor may we build upon an optimistic parent.
+        assert!(matches!(
+            proposer_head_info_with_statuses(optimistic, valid),
+            Err(ProposerHeadError::DoNotReOrg(DoNotReOrg::NotFullyVerified))
+        ));
+
+        // With both blocks fully verified the re-org is permitted.
+        let info = proposer_head_info_with_statuses(valid, valid)
+            .unwrap_or_else(|_| panic!("fully verified head should be re-orgable"));
+        assert_eq!(info.head_node.root, hash_from_index(2));
+        assert_eq!(info.parent_node.root, hash_from_index(1));
+    }
+
     /// This test covers an interesting case where a block can be a descendant
     /// of the finalized *block*, but not a descenant of the finalized
     /// *checkpoint*.
@@ -118,6 +118,17 @@ impl<T: EthSpec, Payload: AbstractExecPayload<T>> BeaconBlock<T, Payload> {
         ))
     }
 
+    /// SSZ decode with the fork variant given by `fork_name` rather than the slot.
+    ///
+    /// The caller is responsible for checking that the decoded block's slot is consistent with
+    /// `fork_name`, e.g. via `Self::fork_name`.
+    pub fn from_ssz_bytes_for_fork(
+        bytes: &[u8],
+        fork_name: ForkName,
+    ) -> Result<Self, ssz::DecodeError> {
+        Ok(map_fork_name!(fork_name, Self, <_>::from_ssz_bytes(bytes)?))
+    }
+
     /// Try decoding each beacon block variant in sequence.
     ///
     /// This is *not* recommended unless you really have no idea what variant the block should be.
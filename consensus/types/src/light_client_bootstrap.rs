@@ -1,5 +1,5 @@
 use super::{BeaconBlockHeader, BeaconState, EthSpec, FixedVector, Hash256, SyncCommittee};
-use crate::{light_client_update::*, test_utils::TestRandom};
+use crate::{light_client_update::*, test_utils::TestRandom, ForkName, ForkVersionDeserialize};
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use std::sync::Arc;
@@ -44,6 +44,27 @@ impl<T: EthSpec> LightClientBootstrap<T> {
     }
 }
 
+impl<T: EthSpec> ForkVersionDeserialize for LightClientBootstrap<T> {
+    fn deserialize_by_fork<'de, D: serde::Deserializer<'de>>(
+        value: serde_json::value::Value,
+        fork_name: ForkName,
+    ) -> Result<Self, D::Error> {
+        match fork_name {
+            ForkName::Altair | ForkName::Merge | ForkName::Capella => serde_json::from_value(value)
+                .map_err(|e| {
+                    serde::de::Error::custom(format!(
+                        "LightClientBootstrap failed to deserialize: {:?}",
+                        e
+                    ))
+                }),
+            ForkName::Base => Err(serde::de::Error::custom(format!(
+                "LightClientBootstrap failed to deserialize: unsupported fork '{}'",
+                fork_name
+            ))),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
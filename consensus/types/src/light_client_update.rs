@@ -1,5 +1,8 @@
 use super::{BeaconBlockHeader, EthSpec, FixedVector, Hash256, Slot, SyncAggregate, SyncCommittee};
-use crate::{beacon_state, test_utils::TestRandom, BeaconBlock, BeaconState, ChainSpec};
+use crate::{
+    beacon_state, test_utils::TestRandom, BeaconState, ChainSpec, ForkName, ForkVersionDeserialize,
+    SignedBeaconBlock, SignedBlindedBeaconBlock,
+};
 use safe_arith::ArithError;
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
@@ -84,11 +87,10 @@ pub struct LightClientUpdate<T: EthSpec> {
 
 impl<T: EthSpec> LightClientUpdate<T> {
     pub fn new(
-        chain_spec: ChainSpec,
-        beacon_state: BeaconState<T>,
-        block: BeaconBlock<T>,
+        chain_spec: &ChainSpec,
+        block: &SignedBeaconBlock<T>,
         attested_state: &mut BeaconState<T>,
-        finalized_block: BeaconBlock<T>,
+        finalized_block: Option<&SignedBlindedBeaconBlock<T>>,
     ) -> Result<Self, Error> {
         let altair_fork_epoch = chain_spec
             .altair_fork_epoch
@@ -97,56 +99,217 @@ impl<T: EthSpec> LightClientUpdate<T> {
             return Err(Error::AltairForkNotActive);
         }
 
-        let sync_aggregate = block.body().sync_aggregate()?;
+        let sync_aggregate = block.message().body().sync_aggregate()?;
         if sync_aggregate.num_set_bits() < chain_spec.min_sync_committee_participants as usize {
             return Err(Error::NotEnoughSyncCommitteeParticipants);
         }
 
-        let signature_period = block.epoch().sync_committee_period(&chain_spec)?;
+        let signature_period = block
+            .slot()
+            .epoch(T::slots_per_epoch())
+            .sync_committee_period(chain_spec)?;
         // Compute and validate attested header.
         let mut attested_header = attested_state.latest_block_header().clone();
         attested_header.state_root = attested_state.tree_hash_root();
         let attested_period = attested_header
             .slot
             .epoch(T::slots_per_epoch())
-            .sync_committee_period(&chain_spec)?;
+            .sync_committee_period(chain_spec)?;
         if attested_period != signature_period {
             return Err(Error::MismatchingPeriods);
         }
-        // Build finalized header from finalized block
-        let finalized_header = BeaconBlockHeader {
-            slot: finalized_block.slot(),
-            proposer_index: finalized_block.proposer_index(),
-            parent_root: finalized_block.parent_root(),
-            state_root: finalized_block.state_root(),
-            body_root: finalized_block.body_root(),
+        // Build finalized header from finalized block. Without a finalized block the update carries
+        // no finality, which is indicated by an empty header and branch.
+        let (finalized_header, finality_branch) = match finalized_block {
+            Some(finalized_block) => {
+                let finalized_header = finalized_block.message().block_header();
+                if finalized_header.tree_hash_root() != attested_state.finalized_checkpoint().root {
+                    return Err(Error::InvalidFinalizedBlock);
+                }
+                let finality_branch = attested_state.compute_merkle_proof(FINALIZED_ROOT_INDEX)?;
+                (finalized_header, FixedVector::new(finality_branch)?)
+            }
+            None => (
+                BeaconBlockHeader {
+                    slot: Slot::new(0),
+                    proposer_index: 0,
+                    parent_root: Hash256::zero(),
+                    state_root: Hash256::zero(),
+                    body_root: Hash256::zero(),
+                },
+                FixedVector::default(),
+            ),
         };
-        if finalized_header.tree_hash_root() != beacon_state.finalized_checkpoint().root {
-            return Err(Error::InvalidFinalizedBlock);
-        }
         let next_sync_committee_branch =
             attested_state.compute_merkle_proof(NEXT_SYNC_COMMITTEE_INDEX)?;
-        let finality_branch = attested_state.compute_merkle_proof(FINALIZED_ROOT_INDEX)?;
         Ok(Self {
             attested_header,
             next_sync_committee: attested_state.next_sync_committee()?.clone(),
             next_sync_committee_branch: FixedVector::new(next_sync_committee_branch)?,
             finalized_header,
-            finality_branch: FixedVector::new(finality_branch)?,
+            finality_branch,
             sync_aggregate: sync_aggregate.clone(),
             signature_slot: block.slot(),
         })
     }
+
+    /// Returns `true` if `self` is a better update than `old`, as per the spec's
+    /// `is_better_update`.
+    ///
+    /// https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/light-client/sync-protocol.md#is_better_update
+    pub fn is_better_update(&self, old: &Self, chain_spec: &ChainSpec) -> Result<bool, Error> {
+        let period_at_slot = |slot: Slot| {
+            slot.epoch(T::slots_per_epoch())
+                .sync_committee_period(chain_spec)
+        };
+
+        // Compare supermajority (> 2/3) sync committee participation.
+        let max_active_participants = T::sync_committee_size();
+        let new_active_participants = self.sync_aggregate.num_set_bits();
+        let old_active_participants = old.sync_aggregate.num_set_bits();
+        let new_has_supermajority = new_active_participants * 3 >= max_active_participants * 2;
+        let old_has_supermajority = old_active_participants * 3 >= max_active_participants * 2;
+        if new_has_supermajority != old_has_supermajority {
+            return Ok(new_has_supermajority);
+        }
+        if !new_has_supermajority && new_active_participants != old_active_participants {
+            return Ok(new_active_participants > old_active_participants);
+        }
+
+        // Compare presence of relevant sync committee.
+        let new_has_relevant_sync_committee = self.is_sync_committee_update()
+            && period_at_slot(self.attested_header.slot)? == period_at_slot(self.signature_slot)?;
+        let old_has_relevant_sync_committee = old.is_sync_committee_update()
+            && period_at_slot(old.attested_header.slot)? == period_at_slot(old.signature_slot)?;
+        if new_has_relevant_sync_committee != old_has_relevant_sync_committee {
+            return Ok(new_has_relevant_sync_committee);
+        }
+
+        // Compare indication of any finality.
+        let new_has_finality = self.is_finality_update();
+        let old_has_finality = old.is_finality_update();
+        if new_has_finality != old_has_finality {
+            return Ok(new_has_finality);
+        }
+
+        // Compare sync committee finality.
+        if new_has_finality {
+            let new_has_sync_committee_finality = period_at_slot(self.finalized_header.slot)?
+                == period_at_slot(self.attested_header.slot)?;
+            let old_has_sync_committee_finality = period_at_slot(old.finalized_header.slot)?
+                == period_at_slot(old.attested_header.slot)?;
+            if new_has_sync_committee_finality != old_has_sync_committee_finality {
+                return Ok(new_has_sync_committee_finality);
+            }
+        }
+
+        // Tiebreaker 1: sync committee participation beyond supermajority.
+        if new_active_participants != old_active_participants {
+            return Ok(new_active_participants > old_active_participants);
+        }
+
+        // Tiebreaker 2: prefer older data (fewer changes to best).
+        if self.attested_header.slot != old.attested_header.slot {
+            return Ok(self.attested_header.slot < old.attested_header.slot);
+        }
+        Ok(self.signature_slot < old.signature_slot)
+    }
+
+    /// Returns `true` if the update contains a proof of the next sync committee.
+    fn is_sync_committee_update(&self) -> bool {
+        self.next_sync_committee_branch
+            .iter()
+            .any(|node| *node != Hash256::zero())
+    }
+
+    /// Returns `true` if the update contains a proof of the finalized header.
+    fn is_finality_update(&self) -> bool {
+        self.finality_branch
+            .iter()
+            .any(|node| *node != Hash256::zero())
+    }
+}
+
+impl<T: EthSpec> ForkVersionDeserialize for LightClientUpdate<T> {
+    fn deserialize_by_fork<'de, D: serde::Deserializer<'de>>(
+        value: serde_json::value::Value,
+        fork_name: ForkName,
+    ) -> Result<Self, D::Error> {
+        match fork_name {
+            ForkName::Altair | ForkName::Merge | ForkName::Capella => serde_json::from_value(value)
+                .map_err(|e| {
+                    serde::de::Error::custom(format!(
+                        "LightClientUpdate failed to deserialize: {:?}",
+                        e
+                    ))
+                }),
+            ForkName::Base => Err(serde::de::Error::custom(format!(
+                "LightClientUpdate failed to deserialize: unsupported fork '{}'",
+                fork_name
+            ))),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::{SeedableRng, XorShiftRng};
     use crate::MainnetEthSpec;
     use ssz_types::typenum::Unsigned;
 
     ssz_tests!(LightClientUpdate<MainnetEthSpec>);
 
+    /// Returns a random update with all header slots set to `slot` and `num_participants` sync
+    /// committee members participating.
+    fn update_at_slot(
+        rng: &mut XorShiftRng,
+        slot: Slot,
+        num_participants: usize,
+    ) -> LightClientUpdate<MainnetEthSpec> {
+        let mut update = LightClientUpdate::random_for_test(rng);
+        update.attested_header.slot = slot;
+        update.finalized_header.slot = slot;
+        update.signature_slot = slot;
+        for i in 0..MainnetEthSpec::sync_committee_size() {
+            update
+                .sync_aggregate
+                .sync_committee_bits
+                .set(i, i < num_participants)
+                .unwrap();
+        }
+        update
+    }
+
+    #[test]
+    fn is_better_update() {
+        let spec = MainnetEthSpec::default_spec();
+        let rng = &mut XorShiftRng::from_seed([42; 16]);
+        let size = MainnetEthSpec::sync_committee_size();
+        let slot = Slot::new(100);
+
+        // Supermajority participation is preferred, regardless of age.
+        let minority = update_at_slot(rng, slot, size / 2);
+        let supermajority = update_at_slot(rng, slot + 1, size);
+        assert!(supermajority.is_better_update(&minority, &spec).unwrap());
+        assert!(!minority.is_better_update(&supermajority, &spec).unwrap());
+
+        // Without a supermajority, greater participation is preferred.
+        let more = update_at_slot(rng, slot + 1, size / 2 + 1);
+        assert!(more.is_better_update(&minority, &spec).unwrap());
+
+        // Updates with finality are preferred over those without.
+        let mut no_finality = update_at_slot(rng, slot, size);
+        no_finality.finality_branch = FixedVector::default();
+        assert!(supermajority.is_better_update(&no_finality, &spec).unwrap());
+        assert!(!no_finality.is_better_update(&supermajority, &spec).unwrap());
+
+        // All else being equal, older data is preferred.
+        let older = update_at_slot(rng, slot, size);
+        assert!(older.is_better_update(&supermajority, &spec).unwrap());
+        assert!(!supermajority.is_better_update(&older, &spec).unwrap());
+    }
+
     #[test]
     fn finalized_root_params() {
         assert!(2usize.pow(FINALIZED_ROOT_PROOF_LEN as u32) <= FINALIZED_ROOT_INDEX);
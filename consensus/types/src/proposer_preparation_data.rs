@@ -1,9 +1,10 @@
 use crate::*;
 use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
 
 /// A proposer preparation, created when a validator prepares the beacon node for potential proposers
 /// by supplying information required when proposing blocks for the given validators.
-#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone, Encode, Decode)]
 pub struct ProposerPreparationData {
     /// The validators index.
     #[serde(with = "serde_utils::quoted_u64")]
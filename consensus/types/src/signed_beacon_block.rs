@@ -95,6 +95,16 @@ impl<E: EthSpec, Payload: AbstractExecPayload<E>> SignedBeaconBlock<E, Payload>
         Self::from_ssz_bytes_with(bytes, |bytes| BeaconBlock::from_ssz_bytes(bytes, spec))
     }
 
+    /// SSZ decode with the fork variant given by `fork_name` rather than the slot.
+    pub fn from_ssz_bytes_for_fork(
+        bytes: &[u8],
+        fork_name: ForkName,
+    ) -> Result<Self, ssz::DecodeError> {
+        Self::from_ssz_bytes_with(bytes, |bytes| {
+            BeaconBlock::from_ssz_bytes_for_fork(bytes, fork_name)
+        })
+    }
+
     /// SSZ decode which attempts to decode all variants (slow).
     pub fn any_from_ssz_bytes(bytes: &[u8]) -> Result<Self, ssz::DecodeError> {
         Self::from_ssz_bytes_with(bytes, BeaconBlock::any_from_ssz_bytes)
@@ -459,7 +469,14 @@ mod test {
                 BeaconBlock::Altair(BeaconBlockAltair::empty(spec)),
                 sig.clone(),
             ),
-            SignedBeaconBlock::from_block(BeaconBlock::Merge(BeaconBlockMerge::empty(spec)), sig),
+            SignedBeaconBlock::from_block(
+                BeaconBlock::Merge(BeaconBlockMerge::empty(spec)),
+                sig.clone(),
+            ),
+            SignedBeaconBlock::from_block(
+                BeaconBlock::Capella(BeaconBlockCapella::empty(spec)),
+                sig,
+            ),
         ];
 
         for block in blocks {
@@ -481,4 +498,29 @@ mod test {
             assert_eq!(reconstructed, block);
         }
     }
+
+    #[test]
+    fn try_into_full_block_rejects_wrong_payload() {
+        type E = MainnetEthSpec;
+
+        let spec = &E::default_spec();
+        let sig = Signature::empty();
+
+        let merge_block: SignedBlindedBeaconBlock<E> = SignedBeaconBlock::from_block(
+            BeaconBlock::Merge(BeaconBlockMerge::empty(spec)),
+            sig.clone(),
+        );
+        assert!(merge_block.clone().try_into_full_block(None).is_none());
+        assert!(merge_block
+            .try_into_full_block(Some(ExecutionPayloadCapella::default().into()))
+            .is_none());
+
+        let capella_block: SignedBlindedBeaconBlock<E> = SignedBeaconBlock::from_block(
+            BeaconBlock::Capella(BeaconBlockCapella::empty(spec)),
+            sig,
+        );
+        assert!(capella_block
+            .try_into_full_block(Some(ExecutionPayloadMerge::default().into()))
+            .is_none());
+    }
 }
@@ -71,10 +71,29 @@ pub fn inspect_cli_app<'a, 'b>() -> App<'a, 'b> {
 
 pub fn prune_payloads_app<'a, 'b>() -> App<'a, 'b> {
     App::new("prune_payloads")
+        .visible_aliases(&["prune-payloads"])
         .setting(clap::AppSettings::ColoredHelp)
         .about("Prune finalized execution payloads")
 }
 
+pub fn prune_states_app<'a, 'b>() -> App<'a, 'b> {
+    App::new("prune-states")
+        .setting(clap::AppSettings::ColoredHelp)
+        .arg(
+            Arg::with_name("confirm")
+                .long("confirm")
+                .help(
+                    "Commit to pruning states irreversibly. Without this flag the command will \
+                     just check that the database is capable of being pruned.",
+                )
+                .takes_value(false),
+        )
+        .about(
+            "Prune all beacon states from the freezer database, keeping blocks. States may be \
+             regenerated later using --reconstruct-historic-states",
+        )
+}
+
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new(CMD)
         .visible_aliases(&["db"])
@@ -102,6 +121,7 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .subcommand(version_cli_app())
         .subcommand(inspect_cli_app())
         .subcommand(prune_payloads_app())
+        .subcommand(prune_states_app())
 }
 
 fn parse_client_config<E: EthSpec>(
@@ -205,9 +225,10 @@ pub fn inspect_db<E: EthSpec>(
         spec,
         log,
     )
-    .map_err(|e| format!("{:?}", e))?;
+    .map_err(format_db_error)?;
 
     let mut total = 0;
+    let mut num_keys = 0;
     let base_path = &inspect_config.output_dir;
 
     if let InspectTarget::Values = inspect_config.target {
@@ -217,6 +238,7 @@ pub fn inspect_db<E: EthSpec>(
 
     for res in db.hot_db.iter_column(inspect_config.column) {
         let (key, value) = res.map_err(|e| format!("{:?}", e))?;
+        num_keys += 1;
 
         match inspect_config.target {
             InspectTarget::ValueSizes => {
@@ -252,7 +274,7 @@ pub fn inspect_db<E: EthSpec>(
 
     match inspect_config.target {
         InspectTarget::ValueSizes | InspectTarget::ValueTotal | InspectTarget::Values => {
-            println!("Total: {} bytes", total);
+            println!("Total: {} bytes across {} keys", total, num_keys);
         }
     }
 
@@ -325,13 +347,132 @@ pub fn prune_payloads<E: EthSpec>(
         |_, _, _| Ok(()),
         client_config.store,
         spec.clone(),
-        log,
+        log.clone(),
     )?;
 
+    let payloads_size_before = execution_payloads_size(&db)?;
+
     // If we're trigging a prune manually then ignore the check on the split's parent that bails
     // out early.
     let force = true;
-    db.try_prune_execution_payloads(force)
+    db.try_prune_execution_payloads(force)?;
+
+    // Compact the database so that the space freed by the deleted payloads is returned to the
+    // filesystem.
+    db.compact()?;
+
+    let payloads_size_after = execution_payloads_size(&db)?;
+    info!(
+        log,
+        "Execution payloads pruned";
+        "reclaimed_bytes" => payloads_size_before.saturating_sub(payloads_size_after),
+        "remaining_bytes" => payloads_size_after,
+    );
+
+    Ok(())
+}
+
+pub struct PruneStatesConfig {
+    confirm: bool,
+}
+
+fn parse_prune_states_config(cli_args: &ArgMatches) -> Result<PruneStatesConfig, String> {
+    let confirm = cli_args.is_present("confirm");
+    Ok(PruneStatesConfig { confirm })
+}
+
+pub fn prune_states<E: EthSpec>(
+    prune_config: PruneStatesConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(format_db_error)?;
+
+    // The genesis state is always retained, and is used to sanity check that the database
+    // belongs to the network which was specified.
+    let mut genesis_state = db
+        .load_cold_state_by_slot(spec.genesis_slot)
+        .map_err(format_db_error)?
+        .ok_or("Genesis state missing from database, unable to prune states")?;
+    let genesis_state_root = genesis_state
+        .update_tree_hash_cache()
+        .map_err(|e| format!("Unable to hash genesis state: {:?}", e))?;
+
+    if let Some(network_config) = &runtime_context.eth2_network_config {
+        if network_config.genesis_state_is_known() {
+            let expected = network_config.genesis_validators_root::<E>()?;
+            if expected.map_or(false, |root| {
+                root != genesis_state.genesis_validators_root()
+            }) {
+                return Err(format!(
+                    "Database genesis_validators_root {:?} does not match the network \
+                     ({:?}), check the --network and --datadir flags",
+                    genesis_state.genesis_validators_root(),
+                    expected,
+                ));
+            }
+        }
+    }
+
+    if !prune_config.confirm {
+        info!(
+            log,
+            "Ready to prune states";
+            "genesis_state_root" => ?genesis_state_root,
+        );
+        return Err(
+            "Confirmation flag required, re-run with --confirm to delete all historic states"
+                .into(),
+        );
+    }
+
+    db.prune_historic_states(genesis_state_root, &genesis_state)
+        .map_err(format_db_error)?;
+
+    info!(
+        log,
+        "Historic states pruned, use --reconstruct-historic-states to regenerate them"
+    );
+
+    Ok(())
+}
+
+/// Return the total size in bytes of the execution payloads stored in the hot database.
+fn execution_payloads_size<E: EthSpec>(
+    db: &HotColdDB<E, LevelDB<E>, LevelDB<E>>,
+) -> Result<usize, Error> {
+    db.hot_db
+        .iter_column(DBColumn::ExecPayload)
+        .map(|res| res.map(|(_, value)| value.len()))
+        .sum()
+}
+
+/// Format a database error for display, with advice if the database is locked by another process.
+///
+/// LevelDB holds an exclusive lock on its `LOCK` file for as long as the database is open, so a
+/// running beacon node prevents the database from being opened here.
+fn format_db_error(e: Error) -> String {
+    match e {
+        Error::DBError { message } if message.contains("LOCK") => format!(
+            "Fatal error: database is locked by another process, stop the beacon node before \
+             running this command: {}",
+            message
+        ),
+        e => format!("Fatal error: {:?}", e),
+    }
 }
 
 /// Run the database manager, returning an error string if the operation did not succeed.
@@ -339,22 +480,25 @@ pub fn run<T: EthSpec>(cli_args: &ArgMatches<'_>, env: Environment<T>) -> Result
     let client_config = parse_client_config(cli_args, &env)?;
     let context = env.core_context();
     let log = context.log().clone();
-    let format_err = |e| format!("Fatal error: {:?}", e);
 
     match cli_args.subcommand() {
         ("version", Some(_)) => {
-            display_db_version(client_config, &context, log).map_err(format_err)
+            display_db_version(client_config, &context, log).map_err(format_db_error)
         }
         ("migrate", Some(cli_args)) => {
             let migrate_config = parse_migrate_config(cli_args)?;
-            migrate_db(migrate_config, client_config, &context, log).map_err(format_err)
+            migrate_db(migrate_config, client_config, &context, log).map_err(format_db_error)
         }
         ("inspect", Some(cli_args)) => {
             let inspect_config = parse_inspect_config(cli_args)?;
             inspect_db(inspect_config, client_config, &context, log)
         }
         ("prune_payloads", Some(_)) => {
-            prune_payloads(client_config, &context, log).map_err(format_err)
+            prune_payloads(client_config, &context, log).map_err(format_db_error)
+        }
+        ("prune-states", Some(cli_args)) => {
+            let prune_config = parse_prune_states_config(cli_args)?;
+            prune_states(prune_config, client_config, &context, log)
         }
         _ => Err("Unknown subcommand, for help `lighthouse database_manager --help`".into()),
     }
@@ -144,6 +144,53 @@ fn shuffling_cache_set() {
         .with_config(|config| assert_eq!(config.chain.shuffling_cache_size, 500));
 }
 
+#[test]
+#[should_panic]
+fn shuffling_cache_zero() {
+    CommandLineTest::new()
+        .flag("shuffling-cache-size", Some("0"))
+        .run_with_zero_port();
+}
+
+#[test]
+fn snapshot_cache_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.snapshot_cache_size, 4));
+}
+
+#[test]
+fn snapshot_cache_set() {
+    CommandLineTest::new()
+        .flag("snapshot-cache-size", Some("8"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.snapshot_cache_size, 8));
+}
+
+#[test]
+#[should_panic]
+fn snapshot_cache_zero() {
+    CommandLineTest::new()
+        .flag("snapshot-cache-size", Some("0"))
+        .run_with_zero_port();
+}
+
+#[test]
+#[should_panic]
+fn snapshot_cache_too_large() {
+    CommandLineTest::new()
+        .flag("snapshot-cache-size", Some("65"))
+        .run_with_zero_port();
+}
+
+#[test]
+#[should_panic]
+fn shuffling_cache_too_large() {
+    CommandLineTest::new()
+        .flag("shuffling-cache-size", Some("4097"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn fork_choice_before_proposal_timeout_default() {
     CommandLineTest::new()
@@ -174,6 +221,14 @@ fn checkpoint_sync_url_timeout_flag() {
         });
 }
 
+#[test]
+#[should_panic]
+fn checkpoint_sync_url_timeout_zero() {
+    CommandLineTest::new()
+        .flag("checkpoint-sync-url-timeout", Some("0"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn checkpoint_sync_url_timeout_default() {
     CommandLineTest::new()
@@ -208,6 +263,44 @@ fn prepare_payload_lookahead_shorter() {
         });
 }
 
+#[test]
+fn state_advance_lookahead_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.state_advance_lookahead, Duration::from_secs(3),)
+        });
+}
+
+#[test]
+fn state_advance_lookahead_longer() {
+    CommandLineTest::new()
+        .flag("state-advance-lookahead", Some("4500"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.state_advance_lookahead,
+                Duration::from_millis(4500)
+            )
+        });
+}
+
+#[test]
+#[should_panic]
+fn state_advance_lookahead_zero() {
+    CommandLineTest::new()
+        .flag("state-advance-lookahead", Some("0"))
+        .run_with_zero_port();
+}
+
+#[test]
+#[should_panic]
+fn state_advance_lookahead_full_slot() {
+    CommandLineTest::new()
+        .flag("state-advance-lookahead", Some("12000"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn always_prepare_payload_default() {
     CommandLineTest::new()
@@ -238,31 +331,65 @@ fn paranoid_block_proposal_on() {
         .with_config(|config| assert!(config.chain.paranoid_block_proposal));
 }
 
+#[test]
+fn paranoid_block_import_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.chain.paranoid_block_import));
+}
+
+#[test]
+fn paranoid_block_import_on() {
+    CommandLineTest::new()
+        .flag("paranoid-block-import", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.paranoid_block_import));
+}
+
+#[test]
+fn unknown_parent_lookup_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.unknown_parent_lookup));
+}
+
+#[test]
+fn disable_unknown_parent_lookup() {
+    CommandLineTest::new()
+        .flag("disable-unknown-parent-lookup", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.chain.unknown_parent_lookup));
+}
+
+#[test]
+fn count_unrealized_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.count_unrealized));
+}
+
 #[test]
 fn count_unrealized_no_arg() {
     CommandLineTest::new()
         .flag("count-unrealized", None)
-        // This flag should be ignored, so there's nothing to test but that the
-        // client starts with the flag present.
-        .run_with_zero_port();
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.count_unrealized));
 }
 
 #[test]
 fn count_unrealized_false() {
     CommandLineTest::new()
         .flag("count-unrealized", Some("false"))
-        // This flag should be ignored, so there's nothing to test but that the
-        // client starts with the flag present.
-        .run_with_zero_port();
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.chain.count_unrealized));
 }
 
 #[test]
 fn count_unrealized_true() {
     CommandLineTest::new()
         .flag("count-unrealized", Some("true"))
-        // This flag should be ignored, so there's nothing to test but that the
-        // client starts with the flag present.
-        .run_with_zero_port();
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.count_unrealized));
 }
 
 #[test]
@@ -350,6 +477,34 @@ fn trusted_peers_flag() {
         });
 }
 
+#[test]
+fn trusted_peers_multiaddr_flag() {
+    let peer = PeerId::random();
+    let multiaddr = format!("/ip4/127.0.0.1/tcp/9000/p2p/{}", peer);
+    CommandLineTest::new()
+        .flag("trusted-peers", Some(multiaddr.as_str()))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.trusted_peers.len(), 1);
+            assert_eq!(
+                PeerId::from(config.network.trusted_peers[0].clone()).to_bytes(),
+                peer.to_bytes()
+            );
+            assert_eq!(
+                config.network.trusted_peer_addresses,
+                vec![multiaddr.parse().unwrap()]
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn trusted_peers_multiaddr_without_peer_id_flag() {
+    CommandLineTest::new()
+        .flag("trusted-peers", Some("/ip4/127.0.0.1/tcp/9000"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn genesis_backfill_flag() {
     CommandLineTest::new()
@@ -515,6 +670,35 @@ fn execution_timeout_multiplier_flag() {
         });
 }
 #[test]
+fn execution_offline_threshold_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("execution-endpoint", Some("http://meow.cats"))
+        .flag(
+            "execution-jwt",
+            dir.path().join("jwt-file").as_os_str().to_str(),
+        )
+        .flag("execution-offline-threshold", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(config.execution_offline_threshold, Some(4));
+        });
+}
+#[test]
+#[should_panic]
+fn execution_offline_threshold_zero() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("execution-endpoint", Some("http://meow.cats"))
+        .flag(
+            "execution-jwt",
+            dir.path().join("jwt-file").as_os_str().to_str(),
+        )
+        .flag("execution-offline-threshold", Some("0"))
+        .run_with_zero_port();
+}
+#[test]
 fn merge_execution_endpoints_flag() {
     run_merge_execution_endpoints_flag_test("execution-endpoints")
 }
@@ -755,6 +939,212 @@ fn builder_fallback_flags() {
     );
 }
 
+#[test]
+fn warn_builder_fee_recipient_mismatch_flag() {
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("warn-builder-fee-recipient-mismatch"),
+        None,
+        |config| {
+            assert!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .warn_builder_fee_recipient_mismatch
+            );
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        None,
+        None,
+        |config| {
+            assert!(
+                !config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .warn_builder_fee_recipient_mismatch
+            );
+        },
+    );
+}
+
+#[test]
+fn builder_boost_factor_flag() {
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-boost-factor"),
+        Some("90"),
+        |config| {
+            assert_eq!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_boost_factor,
+                Some(90)
+            );
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        None,
+        None,
+        |config| {
+            assert_eq!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_boost_factor,
+                None
+            );
+        },
+    );
+}
+
+#[test]
+fn builder_timeout_flags() {
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-header-timeout"),
+        Some("500"),
+        |config| {
+            assert_eq!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_header_timeout,
+                Some(Duration::from_millis(500))
+            );
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-blinded-blocks-timeout"),
+        Some("3000"),
+        |config| {
+            assert_eq!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_blinded_blocks_timeout,
+                Some(Duration::from_millis(3000))
+            );
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-register-validator-timeout"),
+        Some("30000"),
+        |config| {
+            assert_eq!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_register_validator_timeout,
+                Some(Duration::from_millis(30000))
+            );
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        None,
+        None,
+        |config| {
+            let el_config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(el_config.builder_header_timeout, None);
+            assert_eq!(el_config.builder_blinded_blocks_timeout, None);
+            assert_eq!(el_config.builder_register_validator_timeout, None);
+        },
+    );
+}
+
+#[test]
+#[should_panic]
+fn builder_header_timeout_zero() {
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-header-timeout"),
+        Some("0"),
+        |_| {},
+    );
+}
+
+#[test]
+#[should_panic]
+fn builder_blinded_blocks_timeout_too_large() {
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-blinded-blocks-timeout"),
+        Some("60001"),
+        |_| {},
+    );
+}
+
+#[test]
+#[should_panic]
+fn builder_register_validator_timeout_zero() {
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-register-validator-timeout"),
+        Some("0"),
+        |_| {},
+    );
+}
+
+#[test]
+fn builder_registration_batch_size_flag() {
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        Some("builder-registration-batch-size"),
+        Some("100"),
+        |config| {
+            assert_eq!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_registration_batch_size,
+                Some(100)
+            );
+        },
+    );
+    run_payload_builder_flag_test_with_config(
+        "builder",
+        "http://meow.cats",
+        None,
+        None,
+        |config| {
+            assert_eq!(
+                config
+                    .execution_layer
+                    .as_ref()
+                    .unwrap()
+                    .builder_registration_batch_size,
+                None
+            );
+        },
+    );
+}
+
 #[test]
 fn builder_user_agent() {
     run_payload_builder_flag_test_with_config(
@@ -916,6 +1306,36 @@ fn network_import_all_attestations_flag() {
         .with_config(|config| assert!(config.network.import_all_attestations));
 }
 #[test]
+fn network_gossipsub_score_params_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("score-params.toml");
+    let mut file = File::create(&path).expect("Unable to create file");
+    file.write_all(b"gossip_threshold = -2000.0\n\n[topic_weights]\nbeacon_block = 0.8\n")
+        .expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag("gossipsub-score-params-file", path.as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            let overrides = &config.network.gossipsub_score_overrides;
+            assert_eq!(overrides.gossip_threshold, Some(-2000.0));
+            assert_eq!(overrides.publish_threshold, None);
+            assert_eq!(overrides.topic_weights.get("beacon_block"), Some(&0.8));
+        });
+}
+#[test]
+#[should_panic]
+fn network_gossipsub_score_params_file_invalid() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("score-params.toml");
+    let mut file = File::create(&path).expect("Unable to create file");
+    // The publish threshold must not be greater than the gossip threshold.
+    file.write_all(b"gossip_threshold = -8000.0\npublish_threshold = -4000.0\n")
+        .expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag("gossipsub-score-params-file", path.as_os_str().to_str())
+        .run_with_zero_port();
+}
+#[test]
 fn network_shutdown_after_sync_flag() {
     CommandLineTest::new()
         .flag("shutdown-after-sync", None)
@@ -1166,6 +1586,37 @@ fn disable_quic_flag() {
         .with_config(|config| assert!(config.network.disable_quic_support));
 }
 #[test]
+fn peer_persistence_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.network.disable_peer_persistence);
+            assert_eq!(
+                config.network.persisted_peers_max_age,
+                Duration::from_secs(86400)
+            );
+        });
+}
+#[test]
+fn disable_peer_persistence_flag() {
+    CommandLineTest::new()
+        .flag("disable-peer-persistence", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.network.disable_peer_persistence));
+}
+#[test]
+fn persisted_peers_max_age_flag() {
+    CommandLineTest::new()
+        .flag("persisted-peers-max-age", Some("3600"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.persisted_peers_max_age,
+                Duration::from_secs(3600)
+            )
+        });
+}
+#[test]
 fn disable_peer_scoring_flag() {
     CommandLineTest::new()
         .flag("disable-peer-scoring", None)
@@ -1173,11 +1624,24 @@ fn disable_peer_scoring_flag() {
         .with_config(|config| assert!(config.network.disable_peer_scoring));
 }
 #[test]
+fn disable_nat_flag() {
+    CommandLineTest::new()
+        .flag("disable-nat", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.network.nat_enabled));
+}
+#[test]
 fn disable_upnp_flag() {
     CommandLineTest::new()
         .flag("disable-upnp", None)
         .run_with_zero_port()
-        .with_config(|config| assert!(!config.network.upnp_enabled));
+        .with_config(|config| assert!(!config.network.nat_enabled));
+}
+#[test]
+fn nat_enabled_by_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.network.nat_enabled));
 }
 #[test]
 fn disable_backfill_rate_limiting_flag() {
@@ -1193,6 +1657,33 @@ fn default_backfill_rate_limiting_flag() {
         .with_config(|config| assert!(config.beacon_processor.enable_backfill_rate_limiting));
 }
 #[test]
+fn backfill_rate_limit_flag() {
+    CommandLineTest::new()
+        .flag("backfill-rate-limit", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.beacon_processor.enable_backfill_rate_limiting);
+            assert_eq!(config.beacon_processor.backfill_rate_limit_slots, Some(4));
+            assert!(config.http_api.backfill_rate_limited);
+        });
+}
+#[test]
+fn default_backfill_rate_limit_flag() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.beacon_processor.backfill_rate_limit_slots, None);
+            assert!(config.http_api.backfill_rate_limited);
+        });
+}
+#[test]
+#[should_panic]
+fn backfill_rate_limit_zero() {
+    CommandLineTest::new()
+        .flag("backfill-rate-limit", Some("0"))
+        .run_with_zero_port();
+}
+#[test]
 fn default_boot_nodes() {
     let number_of_boot_nodes = 17;
 
@@ -1523,6 +2014,13 @@ fn disable_inbound_rate_limiter_flag() {
         .run_with_zero_port()
         .with_config(|config| assert_eq!(config.network.inbound_rate_limiter_config, None));
 }
+#[test]
+fn disable_inbound_rate_limiter_boolean_flag() {
+    CommandLineTest::new()
+        .flag("disable-inbound-rate-limiter", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.inbound_rate_limiter_config, None));
+}
 
 #[test]
 fn http_allow_origin_flag() {
@@ -1739,6 +2237,20 @@ fn slots_per_restore_point_flag() {
         .with_config(|config| assert_eq!(config.store.slots_per_restore_point, 64));
 }
 #[test]
+fn slots_per_restore_point_migrate_flag() {
+    CommandLineTest::new()
+        .flag("slots-per-restore-point", Some("64"))
+        .flag("slots-per-restore-point-migrate", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.store.slots_per_restore_point_migrate));
+}
+#[test]
+fn slots_per_restore_point_migrate_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.store.slots_per_restore_point_migrate));
+}
+#[test]
 fn slots_per_restore_point_update_prev_default() {
     use beacon_node::beacon_chain::store::config::{
         DEFAULT_SLOTS_PER_RESTORE_POINT, PREV_DEFAULT_SLOTS_PER_RESTORE_POINT,
@@ -1787,6 +2299,34 @@ fn historic_state_cache_size_flag() {
         .with_config(|config| assert_eq!(config.store.historic_state_cache_size, 4_usize));
 }
 #[test]
+fn block_cache_size_zero() {
+    CommandLineTest::new()
+        .flag("block-cache-size", Some("0"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.block_cache_size, 0));
+}
+#[test]
+#[should_panic]
+fn block_cache_size_too_large() {
+    CommandLineTest::new()
+        .flag("block-cache-size", Some("8193"))
+        .run_with_zero_port();
+}
+#[test]
+fn historic_state_cache_size_zero() {
+    CommandLineTest::new()
+        .flag("historic-state-cache-size", Some("0"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.historic_state_cache_size, 0));
+}
+#[test]
+#[should_panic]
+fn historic_state_cache_size_too_large() {
+    CommandLineTest::new()
+        .flag("historic-state-cache-size", Some("1025"))
+        .run_with_zero_port();
+}
+#[test]
 fn historic_state_cache_size_default() {
     use beacon_node::beacon_chain::store::config::DEFAULT_HISTORIC_STATE_CACHE_SIZE;
     CommandLineTest::new()
@@ -1974,6 +2514,21 @@ fn slasher_attestation_cache_size_flag() {
         });
 }
 #[test]
+fn slasher_max_prune_txn_size_flag() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .flag("slasher-max-prune-txn-size", Some("1000"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config
+                .slasher
+                .as_ref()
+                .expect("Unable to parse Slasher config");
+            assert_eq!(slasher_config.max_prune_txn_size, 1000);
+        });
+}
+#[test]
 fn slasher_chunk_size_flag() {
     CommandLineTest::new()
         .flag("slasher", None)
@@ -2315,7 +2870,10 @@ fn sync_eth1_chain_disable_deposit_contract_sync_flag() {
 fn light_client_server_default() {
     CommandLineTest::new()
         .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.network.enable_light_client_server, false));
+        .with_config(|config| {
+            assert_eq!(config.network.enable_light_client_server, false);
+            assert_eq!(config.chain.enable_light_client_server, false);
+        });
 }
 
 #[test]
@@ -2323,7 +2881,10 @@ fn light_client_server_enabled() {
     CommandLineTest::new()
         .flag("light-client-server", None)
         .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.network.enable_light_client_server, true));
+        .with_config(|config| {
+            assert_eq!(config.network.enable_light_client_server, true);
+            assert_eq!(config.chain.enable_light_client_server, true);
+        });
 }
 
 #[test]
@@ -2437,7 +2998,8 @@ fn beacon_processor() {
                     max_scheduled_work_queue_len: 3,
                     max_gossip_attestation_batch_size: 4,
                     max_gossip_aggregate_batch_size: 5,
-                    enable_backfill_rate_limiting: false
+                    enable_backfill_rate_limiting: false,
+                    backfill_rate_limit_slots: None,
                 }
             )
         });
@@ -2476,6 +3038,23 @@ fn http_duplicate_block_status_default() {
         });
 }
 
+#[test]
+fn http_attestation_performance_max_epochs_default() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.attestation_performance_max_epochs, 100));
+}
+
+#[test]
+fn http_attestation_performance_max_epochs_override() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-attestation-performance-max-epochs", Some("10"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.attestation_performance_max_epochs, 10));
+}
+
 #[test]
 fn http_duplicate_block_status_override() {
     CommandLineTest::new()
@@ -1,4 +1,4 @@
-use validator_client::Config;
+use validator_client::{ApiTopic, Config};
 
 use crate::exec::CommandLineTestExec;
 use bls::{Keypair, PublicKeyBytes};
@@ -158,6 +158,51 @@ fn beacon_nodes_tls_certs_flag() {
         });
 }
 
+#[test]
+fn web3_signer_client_identity_flags() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag(
+            "web3-signer-client-identity-path",
+            dir.path().join("identity.p12").to_str(),
+        )
+        .flag("web3-signer-client-identity-password", Some("password"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.web3_signer_defaults.client_identity_path,
+                Some(dir.path().join("identity.p12"))
+            );
+            assert_eq!(
+                config.web3_signer_defaults.client_identity_password,
+                Some("password".to_string())
+            );
+        });
+}
+
+#[test]
+fn web3_signer_client_identity_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.web3_signer_defaults.client_identity_path, None);
+        assert_eq!(config.web3_signer_defaults.client_identity_password, None);
+    });
+}
+
+#[test]
+fn slashing_protection_history_epochs_default() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert_eq!(config.slashing_protection_history_epochs, 512));
+}
+
+#[test]
+fn slashing_protection_history_epochs_flag() {
+    CommandLineTest::new()
+        .flag("slashing-protection-history-epochs", Some("1024"))
+        .run()
+        .with_config(|config| assert_eq!(config.slashing_protection_history_epochs, 1024));
+}
+
 // Tests for Graffiti flags.
 #[test]
 fn graffiti_flag() {
@@ -202,6 +247,39 @@ fn graffiti_file_flag() {
         });
 }
 #[test]
+fn graffiti_file_and_graffiti_flags() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let mut file = File::create(dir.path().join("graffiti.txt")).expect("Unable to create file");
+    let new_key = Keypair::random();
+    let pubkeybytes = PublicKeyBytes::from(new_key.pk);
+    // No default entry, so validators not in the file fall back to `--graffiti`.
+    let contents = format!("{}:nice-graffiti", Keypair::random().pk.compress());
+    file.write_all(contents.as_bytes())
+        .expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag(
+            "graffiti-file",
+            dir.path().join("graffiti.txt").as_os_str().to_str(),
+        )
+        .flag("graffiti", Some("fallback"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config
+                    .graffiti_file
+                    .clone()
+                    .unwrap()
+                    .load_graffiti(&pubkeybytes)
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                config.graffiti.unwrap().as_utf8_lossy(),
+                "fallback".to_string()
+            );
+        });
+}
+#[test]
 fn graffiti_file_with_pk_flag() {
     let dir = TempDir::new().expect("Unable to create temporary directory");
     let mut file = File::create(dir.path().join("graffiti.txt")).expect("Unable to create file");
@@ -247,6 +325,35 @@ fn fee_recipient_flag() {
             )
         });
 }
+#[test]
+fn fee_recipient_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let mut file =
+        File::create(dir.path().join("fee_recipients.txt")).expect("Unable to create file");
+    let new_key = Keypair::random();
+    let pubkeybytes = PublicKeyBytes::from(new_key.pk);
+    let contents = "default:0x00000000219ab540356cbb839cbe05303d7705fa";
+    file.write_all(contents.as_bytes())
+        .expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag(
+            "suggested-fee-recipient-file",
+            dir.path().join("fee_recipients.txt").as_os_str().to_str(),
+        )
+        .run()
+        .with_config(|config| {
+            // Public key not present so load default.
+            assert_eq!(
+                config
+                    .fee_recipient_file
+                    .clone()
+                    .unwrap()
+                    .load_fee_recipient(&pubkeybytes)
+                    .unwrap(),
+                Some(Address::from_str("0x00000000219ab540356cbb839cbe05303d7705fa").unwrap())
+            )
+        });
+}
 
 // Tests for HTTP flags.
 #[test]
@@ -500,9 +607,9 @@ fn monitoring_endpoint() {
         });
 }
 #[test]
-fn disable_run_on_all_default() {
+fn broadcast_default() {
     CommandLineTest::new().run().with_config(|config| {
-        assert!(!config.disable_run_on_all);
+        assert_eq!(config.broadcast_topics, vec![ApiTopic::Subscriptions]);
     });
 }
 
@@ -512,10 +619,72 @@ fn disable_run_on_all() {
         .flag("disable-run-on-all", None)
         .run()
         .with_config(|config| {
-            assert!(config.disable_run_on_all);
+            assert!(config.broadcast_topics.is_empty());
+        });
+}
+
+#[test]
+fn broadcast_none() {
+    CommandLineTest::new()
+        .flag("broadcast", Some("none"))
+        .run()
+        .with_config(|config| {
+            assert!(config.broadcast_topics.is_empty());
+        });
+}
+
+#[test]
+fn broadcast_topics() {
+    CommandLineTest::new()
+        .flag("broadcast", Some("attestations,blocks,sync-committee"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.broadcast_topics,
+                vec![
+                    ApiTopic::Attestations,
+                    ApiTopic::Blocks,
+                    ApiTopic::SyncCommittee
+                ]
+            );
         });
 }
 
+#[test]
+#[should_panic]
+fn broadcast_invalid_topic() {
+    CommandLineTest::new()
+        .flag("broadcast", Some("attestations,blobs"))
+        .run()
+        .with_config(|_| {});
+}
+
+#[test]
+fn signing_concurrency_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.signing_concurrency, 64);
+    });
+}
+
+#[test]
+fn signing_concurrency() {
+    CommandLineTest::new()
+        .flag("signing-concurrency", Some("256"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.signing_concurrency, 256);
+        });
+}
+
+#[test]
+#[should_panic]
+fn signing_concurrency_zero() {
+    CommandLineTest::new()
+        .flag("signing-concurrency", Some("0"))
+        .run()
+        .with_config(|_| {});
+}
+
 #[test]
 fn latency_measurement_service() {
     CommandLineTest::new().run().with_config(|config| {
@@ -0,0 +1,90 @@
+//! Batch processing throughput of the slasher on each of the enabled database backends.
+//!
+//! Run with all backends enabled using:
+//!
+//! ```text
+//! cargo bench -p slasher --features mdbx
+//! ```
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use slasher::{
+    test_utils::{indexed_att, E},
+    Config, DatabaseBackend, Slasher,
+};
+use sloggers::{null::NullLoggerBuilder, Build};
+use strum::VariantNames;
+use tempfile::{tempdir, TempDir};
+use types::{Epoch, IndexedAttestation};
+
+const VALIDATORS_PER_COMMITTEE: u64 = 128;
+const NUM_EPOCHS: u64 = 8;
+
+/// The backends enabled in this build.
+fn backends() -> Vec<DatabaseBackend> {
+    DatabaseBackend::VARIANTS
+        .iter()
+        .filter_map(|name| name.parse().ok())
+        .filter(|backend| *backend != DatabaseBackend::Disabled)
+        .collect()
+}
+
+/// One attestation per committee for each of `NUM_EPOCHS` epochs, as if every validator attested
+/// to the canonical chain.
+fn attestations(num_validators: u64) -> Vec<IndexedAttestation<E>> {
+    (1..=NUM_EPOCHS)
+        .flat_map(|target_epoch| {
+            (0..num_validators)
+                .step_by(VALIDATORS_PER_COMMITTEE as usize)
+                .map(move |first| {
+                    let last = std::cmp::min(first + VALIDATORS_PER_COMMITTEE, num_validators);
+                    let indices = (first..last).collect::<Vec<_>>();
+                    indexed_att(indices, target_epoch - 1, target_epoch, target_epoch)
+                })
+        })
+        .collect()
+}
+
+/// Open a slasher with a fresh database and queue `attestations` for processing.
+fn queued_slasher(
+    backend: DatabaseBackend,
+    attestations: &[IndexedAttestation<E>],
+) -> (Slasher<E>, TempDir) {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.backend = backend;
+    let log = NullLoggerBuilder.build().unwrap();
+    let slasher = Slasher::open(config, log).unwrap();
+    for attestation in attestations {
+        slasher.accept_attestation(attestation.clone());
+    }
+    (slasher, tempdir)
+}
+
+fn batch_processing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("slasher_batch_processing");
+    group.sample_size(10);
+
+    for num_validators in [1_024, 16_384] {
+        let attestations = attestations(num_validators);
+        for backend in backends() {
+            group.bench_with_input(
+                BenchmarkId::new(backend.to_string(), num_validators),
+                &attestations,
+                |b, attestations| {
+                    b.iter_batched(
+                        || queued_slasher(backend, attestations),
+                        |(slasher, tempdir)| {
+                            slasher.process_queued(Epoch::new(NUM_EPOCHS)).unwrap();
+                            (slasher, tempdir)
+                        },
+                        BatchSize::PerIteration,
+                    )
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, batch_processing);
+criterion_main!(benches);
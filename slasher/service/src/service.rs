@@ -1,21 +1,13 @@
-use beacon_chain::{
-    observed_operations::ObservationOutcome, BeaconChain, BeaconChainError, BeaconChainTypes,
-};
+use beacon_chain::{observed_operations::ObservationOutcome, BeaconChain, BeaconChainTypes};
 use directory::size_of_dir;
 use lighthouse_network::PubsubMessage;
 use network::NetworkMessage;
 use slasher::{
-    metrics::{self, SLASHER_DATABASE_SIZE, SLASHER_RUN_TIME},
+    metrics::{self, SLASHER_DATABASE_SIZE, SLASHER_RUN_TIME, SLASHER_SLASHINGS_BROADCAST},
     Slasher,
 };
-use slog::{debug, error, info, trace, warn, Logger};
+use slog::{debug, error, info, trace, Logger};
 use slot_clock::SlotClock;
-use state_processing::{
-    per_block_processing::errors::{
-        AttesterSlashingInvalid, BlockOperationError, ProposerSlashingInvalid,
-    },
-    VerifyOperation,
-};
 use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
 use std::sync::Arc;
 use task_executor::TaskExecutor;
@@ -137,14 +129,17 @@ impl<T: BeaconChainTypes> SlasherService<T> {
 
             // Prune the database, even in the case where batch processing failed.
             // If the database is full then pruning could help to free it up.
-            if let Err(e) = slasher.prune_database(current_epoch) {
-                error!(
-                    log,
-                    "Error during slasher database pruning";
-                    "epoch" => current_epoch,
-                    "error" => ?e,
-                );
-                continue;
+            let prune_stats = match slasher.prune_database(current_epoch) {
+                Ok(prune_stats) => prune_stats,
+                Err(e) => {
+                    error!(
+                        log,
+                        "Error during slasher database pruning";
+                        "epoch" => current_epoch,
+                        "error" => ?e,
+                    );
+                    continue;
+                }
             };
 
             // Provide slashings to the beacon chain, and optionally publish them.
@@ -161,6 +156,9 @@ impl<T: BeaconChainTypes> SlasherService<T> {
                     "time_taken" => format!("{}ms", t.elapsed().as_millis()),
                     "num_attestations" => stats.attestation_stats.num_processed,
                     "num_blocks" => stats.block_stats.num_processed,
+                    "num_attestations_pruned" => prune_stats.num_attestations_pruned,
+                    "num_blocks_pruned" => prune_stats.num_proposers_pruned,
+                    "pruning_lag" => prune_stats.lag,
                 );
             }
         }
@@ -171,117 +169,44 @@ impl<T: BeaconChainTypes> SlasherService<T> {
         beacon_chain: &BeaconChain<T>,
         slasher: &Slasher<T::EthSpec>,
         network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
-    ) {
-        Self::process_attester_slashings(beacon_chain, slasher, network_sender);
-        Self::process_proposer_slashings(beacon_chain, slasher, network_sender);
-    }
-
-    fn process_attester_slashings(
-        beacon_chain: &BeaconChain<T>,
-        slasher: &Slasher<T::EthSpec>,
-        network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
     ) {
         let log = slasher.log();
-        let attester_slashings = slasher.get_attester_slashings();
-
-        for slashing in attester_slashings {
-            // Verify slashing signature.
-            let verified_slashing = match beacon_chain.with_head(|head| {
-                Ok::<_, BeaconChainError>(
-                    slashing
-                        .clone()
-                        .validate(&head.beacon_state, &beacon_chain.spec)?,
-                )
-            }) {
-                Ok(verified) => verified,
-                Err(BeaconChainError::AttesterSlashingValidationError(
-                    BlockOperationError::Invalid(AttesterSlashingInvalid::NoSlashableIndices),
-                )) => {
-                    debug!(
-                        log,
-                        "Skipping attester slashing for slashed validators";
-                        "slashing" => ?slashing,
-                    );
-                    continue;
-                }
-                Err(e) => {
-                    warn!(
-                        log,
-                        "Attester slashing produced is invalid";
-                        "error" => ?e,
-                        "slashing" => ?slashing,
-                    );
-                    continue;
-                }
-            };
+        let new_slashings = match beacon_chain.import_slasher_slashings() {
+            Ok(new_slashings) => new_slashings,
+            Err(e) => {
+                error!(
+                    log,
+                    "Error importing slasher slashings";
+                    "error" => ?e,
+                );
+                return;
+            }
+        };
 
-            // Add to local op pool.
-            beacon_chain.import_attester_slashing(verified_slashing);
+        // Publish to the network if broadcast is enabled.
+        if !slasher.config().broadcast {
+            return;
+        }
 
-            // Publish to the network if broadcast is enabled.
-            if slasher.config().broadcast {
-                if let Err(e) =
-                    Self::publish_attester_slashing(beacon_chain, network_sender, slashing)
-                {
-                    debug!(
-                        log,
-                        "Unable to publish attester slashing";
-                        "error" => e,
-                    );
-                }
+        for slashing in new_slashings.attester_slashings {
+            if let Err(e) = Self::publish_attester_slashing(beacon_chain, network_sender, slashing)
+            {
+                debug!(
+                    log,
+                    "Unable to publish attester slashing";
+                    "error" => e,
+                );
             }
         }
-    }
 
-    fn process_proposer_slashings(
-        beacon_chain: &BeaconChain<T>,
-        slasher: &Slasher<T::EthSpec>,
-        network_sender: &UnboundedSender<NetworkMessage<T::EthSpec>>,
-    ) {
-        let log = slasher.log();
-        let proposer_slashings = slasher.get_proposer_slashings();
-
-        for slashing in proposer_slashings {
-            let verified_slashing = match beacon_chain.with_head(|head| {
-                Ok(slashing
-                    .clone()
-                    .validate(&head.beacon_state, &beacon_chain.spec)?)
-            }) {
-                Ok(verified) => verified,
-                Err(BeaconChainError::ProposerSlashingValidationError(
-                    BlockOperationError::Invalid(ProposerSlashingInvalid::ProposerNotSlashable(
-                        index,
-                    )),
-                )) => {
-                    debug!(
-                        log,
-                        "Skipping proposer slashing for slashed validator";
-                        "validator_index" => index,
-                    );
-                    continue;
-                }
-                Err(e) => {
-                    error!(
-                        log,
-                        "Proposer slashing produced is invalid";
-                        "error" => ?e,
-                        "slashing" => ?slashing,
-                    );
-                    continue;
-                }
-            };
-            beacon_chain.import_proposer_slashing(verified_slashing);
-
-            if slasher.config().broadcast {
-                if let Err(e) =
-                    Self::publish_proposer_slashing(beacon_chain, network_sender, slashing)
-                {
-                    debug!(
-                        log,
-                        "Unable to publish proposer slashing";
-                        "error" => e,
-                    );
-                }
+        for slashing in new_slashings.proposer_slashings {
+            if let Err(e) = Self::publish_proposer_slashing(beacon_chain, network_sender, slashing)
+            {
+                debug!(
+                    log,
+                    "Unable to publish proposer slashing";
+                    "error" => e,
+                );
             }
         }
     }
@@ -303,6 +228,7 @@ impl<T: BeaconChainTypes> SlasherService<T> {
                     ))],
                 })
                 .map_err(|e| format!("network error: {:?}", e))?;
+            metrics::inc_counter_vec(&SLASHER_SLASHINGS_BROADCAST, &["attester"]);
         }
         Ok(())
     }
@@ -324,6 +250,7 @@ impl<T: BeaconChainTypes> SlasherService<T> {
                     ))],
                 })
                 .map_err(|e| format!("network error: {:?}", e))?;
+            metrics::inc_counter_vec(&SLASHER_SLASHINGS_BROADCAST, &["proposer"]);
         }
         Ok(())
     }
@@ -11,7 +11,8 @@ pub const DEFAULT_UPDATE_PERIOD: u64 = 12;
 pub const DEFAULT_SLOT_OFFSET: f64 = 10.5;
 pub const DEFAULT_MAX_DB_SIZE: usize = 256 * 1024; // 256 GiB
 pub const DEFAULT_ATTESTATION_ROOT_CACHE_SIZE: usize = 100_000;
-pub const DEFAULT_BROADCAST: bool = false;
+pub const DEFAULT_BROADCAST: bool = true;
+pub const DEFAULT_MAX_PRUNE_TXN_SIZE: usize = 16_384;
 
 #[cfg(all(feature = "mdbx", not(feature = "lmdb")))]
 pub const DEFAULT_BACKEND: DatabaseBackend = DatabaseBackend::Mdbx;
@@ -23,6 +24,7 @@ pub const DEFAULT_BACKEND: DatabaseBackend = DatabaseBackend::Disabled;
 pub const MAX_HISTORY_LENGTH: usize = 1 << 16;
 pub const MEGABYTE: usize = 1 << 20;
 pub const MDBX_DATA_FILENAME: &str = "mdbx.dat";
+pub const LMDB_DATA_FILENAME: &str = "data.mdb";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -39,6 +41,8 @@ pub struct Config {
     pub max_db_size_mbs: usize,
     /// Maximum size of the in-memory cache for attestation roots.
     pub attestation_root_cache_size: usize,
+    /// Maximum number of entries deleted by a single pruning transaction.
+    pub max_prune_txn_size: usize,
     /// Whether to broadcast slashings found to the network.
     pub broadcast: bool,
     /// Database backend to use.
@@ -83,6 +87,7 @@ impl Config {
             slot_offset: DEFAULT_SLOT_OFFSET,
             max_db_size_mbs: DEFAULT_MAX_DB_SIZE,
             attestation_root_cache_size: DEFAULT_ATTESTATION_ROOT_CACHE_SIZE,
+            max_prune_txn_size: DEFAULT_MAX_PRUNE_TXN_SIZE,
             broadcast: DEFAULT_BROADCAST,
             backend: DEFAULT_BACKEND,
         }
@@ -93,6 +98,7 @@ impl Config {
             || self.validator_chunk_size == 0
             || self.history_length == 0
             || self.max_db_size_mbs == 0
+            || self.max_prune_txn_size == 0
         {
             Err(Error::ConfigInvalidZeroParameter {
                 config: self.clone(),
@@ -170,14 +176,32 @@ impl Config {
             .copied()
     }
 
+    /// Switch to the backend of an existing database, if one exists for a backend other than
+    /// the configured one.
+    ///
+    /// Databases cannot be converted between backends, so an existing database must be opened
+    /// with the backend that created it. If that backend isn't enabled in this binary then
+    /// `Failure` is returned with the path of the existing database, and the slasher must re-sync
+    /// from scratch.
     pub fn override_backend(&mut self) -> DatabaseBackendOverride {
         let mdbx_path = self.database_path.join(MDBX_DATA_FILENAME);
+        let lmdb_path = self.database_path.join(LMDB_DATA_FILENAME);
 
         #[cfg(feature = "mdbx")]
         let already_mdbx = self.backend == DatabaseBackend::Mdbx;
         #[cfg(not(feature = "mdbx"))]
         let already_mdbx = false;
 
+        #[cfg(feature = "lmdb")]
+        let already_lmdb = self.backend == DatabaseBackend::Lmdb;
+        #[cfg(not(feature = "lmdb"))]
+        let already_lmdb = false;
+
+        // A database for the configured backend takes precedence.
+        if (already_mdbx && mdbx_path.exists()) || (already_lmdb && lmdb_path.exists()) {
+            return DatabaseBackendOverride::Noop;
+        }
+
         if !already_mdbx && mdbx_path.exists() {
             #[cfg(feature = "mdbx")]
             {
@@ -189,6 +213,17 @@ impl Config {
             {
                 DatabaseBackendOverride::Failure(mdbx_path)
             }
+        } else if !already_lmdb && lmdb_path.exists() {
+            #[cfg(feature = "lmdb")]
+            {
+                let old_backend = self.backend;
+                self.backend = DatabaseBackend::Lmdb;
+                DatabaseBackendOverride::Success(old_backend)
+            }
+            #[cfg(not(feature = "lmdb"))]
+            {
+                DatabaseBackendOverride::Failure(lmdb_path)
+            }
         } else {
             DatabaseBackendOverride::Noop
         }
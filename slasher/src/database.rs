@@ -55,6 +55,11 @@ const MAX_NUM_DBS: usize = 9;
 const METADATA_VERSION_KEY: &[u8] = &[0];
 /// Constant key under which the slasher configuration is stored in the `metadata_db`.
 const METADATA_CONFIG_KEY: &[u8] = &[1];
+/// Constant key under which the pruning cursor is stored in the `metadata_db`.
+const METADATA_PRUNING_CURSOR_KEY: &[u8] = &[2];
+
+/// Maximum number of pruning transactions committed by a single call to `SlasherDB::prune`.
+pub const MAX_PRUNE_TXNS_PER_CYCLE: usize = 16;
 
 const ATTESTER_KEY_SIZE: usize = 7;
 const PROPOSER_KEY_SIZE: usize = 16;
@@ -62,6 +67,15 @@ const CURRENT_EPOCH_KEY_SIZE: usize = 8;
 const INDEXED_ATTESTATION_ID_SIZE: usize = 6;
 const INDEXED_ATTESTATION_ID_KEY_SIZE: usize = 40;
 
+/// Statistics about a single pruning cycle.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruneStats {
+    pub num_proposers_pruned: usize,
+    pub num_attestations_pruned: usize,
+    /// The number of epochs of expired entries which remain to be pruned.
+    pub lag: u64,
+}
+
 #[derive(Debug)]
 pub struct SlasherDB<E: EthSpec> {
     pub(crate) env: &'static Environment,
@@ -663,78 +677,153 @@ impl<E: EthSpec> SlasherDB<E> {
         }
     }
 
-    /// Attempt to prune the database, deleting old blocks and attestations.
-    pub fn prune(&self, current_epoch: Epoch) -> Result<(), Error> {
-        let mut txn = self.begin_rw_txn()?;
-        self.try_prune(current_epoch, &mut txn)?;
-        txn.commit()?;
-        Ok(())
+    /// Load the epoch below which all blocks and attestations have been pruned.
+    pub fn load_pruning_cursor(&self, txn: &mut RwTransaction<'_>) -> Result<Option<Epoch>, Error> {
+        txn.get(&self.databases.metadata_db, METADATA_PRUNING_CURSOR_KEY)?
+            .map(ssz_decode)
+            .transpose()
     }
 
-    /// Try to prune the database.
-    pub fn try_prune(
-        &self,
-        current_epoch: Epoch,
-        txn: &mut RwTransaction<'_>,
-    ) -> Result<(), Error> {
-        self.prune_proposers(current_epoch, txn)?;
-        self.prune_indexed_attestations(current_epoch, txn)?;
+    fn store_pruning_cursor(&self, epoch: Epoch, txn: &mut RwTransaction<'_>) -> Result<(), Error> {
+        txn.put(
+            &self.databases.metadata_db,
+            METADATA_PRUNING_CURSOR_KEY,
+            &epoch.as_ssz_bytes(),
+        )?;
         Ok(())
     }
 
+    /// Attempt to prune the database, deleting old blocks and attestations.
+    ///
+    /// Pruning is incremental: each transaction deletes at most `max_prune_txn_size` entries, and
+    /// at most `MAX_PRUNE_TXNS_PER_CYCLE` transactions are committed per call, so that pruning a
+    /// large backlog doesn't block batch processing. The progress is persisted as a cursor so
+    /// that pruning resumes where it left off, including across restarts.
+    ///
+    /// The attester records and min-max target arrays are indexed modulo the history length and
+    /// are overwritten rather than pruned.
+    pub fn prune(&self, current_epoch: Epoch) -> Result<PruneStats, Error> {
+        let min_epoch = current_epoch
+            .saturating_add(1u64)
+            .saturating_sub(self.config.history_length as u64);
+
+        let mut stats = PruneStats::default();
+
+        let mut txn = self.begin_rw_txn()?;
+        let mut cursor = self.load_pruning_cursor(&mut txn)?.unwrap_or(Epoch::new(0));
+        drop(txn);
+
+        for _ in 0..MAX_PRUNE_TXNS_PER_CYCLE {
+            if cursor >= min_epoch {
+                break;
+            }
+
+            let mut txn = self.begin_rw_txn()?;
+            let mut budget = self.config.max_prune_txn_size;
+
+            let proposers_stop =
+                self.prune_proposers(cursor, min_epoch, &mut budget, &mut stats, &mut txn)?;
+            let attestations_stop = if budget > 0 {
+                self.prune_indexed_attestations(
+                    cursor,
+                    min_epoch,
+                    &mut budget,
+                    &mut stats,
+                    &mut txn,
+                )?
+            } else {
+                // The proposers used the whole budget, there may be attestations left to prune.
+                Some(cursor)
+            };
+
+            // Everything below the earliest remaining expired entry has been deleted.
+            cursor = std::cmp::min(
+                proposers_stop.unwrap_or(min_epoch),
+                attestations_stop.unwrap_or(min_epoch),
+            );
+            self.store_pruning_cursor(cursor, &mut txn)?;
+            txn.commit()?;
+        }
+
+        stats.lag = min_epoch.saturating_sub(cursor).as_u64();
+        metrics::set_gauge(&metrics::SLASHER_PRUNING_LAG, stats.lag as i64);
+        metrics::set_gauge(
+            &metrics::SLASHER_NUM_PROPOSERS_PRUNED,
+            stats.num_proposers_pruned as i64,
+        );
+        metrics::set_gauge(
+            &metrics::SLASHER_NUM_ATTESTATIONS_PRUNED,
+            stats.num_attestations_pruned as i64,
+        );
+
+        Ok(stats)
+    }
+
+    /// Delete blocks from `from_epoch` up to `min_epoch`, up to a maximum of `budget`.
+    ///
+    /// Return the epoch of the first expired block which remains, if any.
     fn prune_proposers(
         &self,
-        current_epoch: Epoch,
+        from_epoch: Epoch,
+        min_epoch: Epoch,
+        budget: &mut usize,
+        stats: &mut PruneStats,
         txn: &mut RwTransaction<'_>,
-    ) -> Result<(), Error> {
-        let min_slot = current_epoch
-            .saturating_add(1u64)
-            .saturating_sub(self.config.history_length)
-            .start_slot(E::slots_per_epoch());
+    ) -> Result<Option<Epoch>, Error> {
+        let min_slot = min_epoch.start_slot(E::slots_per_epoch());
 
         let mut cursor = txn.cursor(&self.databases.proposers_db)?;
 
-        // Position cursor at first key, bailing out if the database is empty.
-        if cursor.first_key()?.is_none() {
-            return Ok(());
+        // Position cursor at the first block not yet pruned, bailing out if there are none.
+        let from_key = ProposerKey::new(0, from_epoch.start_slot(E::slots_per_epoch()));
+        if cursor.seek_key(from_key.as_ref())?.is_none() {
+            return Ok(None);
         }
 
         loop {
             let (key_bytes, _) = cursor.get_current()?.ok_or(Error::MissingProposerKey)?;
 
             let (slot, _) = ProposerKey::parse(key_bytes)?;
-            if slot < min_slot {
-                cursor.delete_current()?;
+            if slot >= min_slot {
+                return Ok(None);
+            }
+            if *budget == 0 {
+                return Ok(Some(slot.epoch(E::slots_per_epoch())));
+            }
 
-                // End the loop if there is no next entry.
-                if cursor.next_key()?.is_none() {
-                    break;
-                }
-            } else {
-                break;
+            cursor.delete_current()?;
+            *budget -= 1;
+            stats.num_proposers_pruned += 1;
+
+            // End the loop if there is no next entry.
+            if cursor.next_key()?.is_none() {
+                return Ok(None);
             }
         }
-
-        Ok(())
     }
 
+    /// Delete attestations targeting epochs from `from_epoch` up to `min_epoch`, up to a maximum
+    /// of `budget`.
+    ///
+    /// Return the target epoch of the first expired attestation which remains, if any.
     fn prune_indexed_attestations(
         &self,
-        current_epoch: Epoch,
+        from_epoch: Epoch,
+        min_epoch: Epoch,
+        budget: &mut usize,
+        stats: &mut PruneStats,
         txn: &mut RwTransaction<'_>,
-    ) -> Result<(), Error> {
-        let min_epoch = current_epoch
-            .saturating_add(1u64)
-            .saturating_sub(self.config.history_length as u64);
-
+    ) -> Result<Option<Epoch>, Error> {
         // Collect indexed attestation IDs to delete.
         let mut indexed_attestation_ids = vec![];
+        let mut remaining = None;
 
         let mut cursor = txn.cursor(&self.databases.indexed_attestation_id_db)?;
 
-        // Position cursor at first key, bailing out if the database is empty.
-        if cursor.first_key()?.is_none() {
-            return Ok(());
+        // Position cursor at the first attestation not yet pruned, bailing out if there are none.
+        let from_key = IndexedAttestationIdKey::new(from_epoch, Hash256::zero());
+        if cursor.seek_key(from_key.as_ref())?.is_none() {
+            return Ok(None);
         }
 
         loop {
@@ -744,17 +833,22 @@ impl<E: EthSpec> SlasherDB<E> {
 
             let (target_epoch, _) = IndexedAttestationIdKey::parse(key_bytes)?;
 
-            if target_epoch < min_epoch {
-                indexed_attestation_ids.push(IndexedAttestationId::new(
-                    IndexedAttestationId::parse(value)?,
-                ));
+            if target_epoch >= min_epoch {
+                break;
+            }
+            if *budget == 0 {
+                remaining = Some(target_epoch);
+                break;
+            }
+
+            indexed_attestation_ids.push(IndexedAttestationId::new(IndexedAttestationId::parse(
+                value,
+            )?));
 
-                cursor.delete_current()?;
+            cursor.delete_current()?;
+            *budget -= 1;
 
-                if cursor.next_key()?.is_none() {
-                    break;
-                }
-            } else {
+            if cursor.next_key()?.is_none() {
                 break;
             }
         }
@@ -766,8 +860,9 @@ impl<E: EthSpec> SlasherDB<E> {
         for indexed_attestation_id in &indexed_attestation_ids {
             txn.del(indexed_attestation_db, indexed_attestation_id)?;
         }
+        stats.num_attestations_pruned += indexed_attestation_ids.len();
         self.delete_attestation_data_roots(indexed_attestation_ids);
 
-        Ok(())
+        Ok(remaining)
     }
 }
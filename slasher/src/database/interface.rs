@@ -187,6 +187,17 @@ impl<'env> Cursor<'env> {
         }
     }
 
+    /// Position the cursor at the first key greater than or equal to `key`, and return it.
+    pub fn seek_key(&mut self, key: &[u8]) -> Result<Option<Key>, Error> {
+        match self {
+            #[cfg(feature = "mdbx")]
+            Cursor::Mdbx(cursor) => cursor.seek_key(key),
+            #[cfg(feature = "lmdb")]
+            Cursor::Lmdb(cursor) => cursor.seek_key(key),
+            _ => Err(Error::MismatchedDatabaseVariant),
+        }
+    }
+
     pub fn next_key(&mut self) -> Result<Option<Key>, Error> {
         match self {
             #[cfg(feature = "mdbx")]
@@ -1,7 +1,7 @@
 #![cfg(feature = "lmdb")]
 
 use crate::{
-    config::MEGABYTE,
+    config::{LMDB_DATA_FILENAME, MEGABYTE},
     database::{
         interface::{Key, OpenDatabases, Value},
         *,
@@ -9,7 +9,7 @@ use crate::{
     Config, Error,
 };
 use lmdb::{Cursor as _, DatabaseFlags, Transaction, WriteFlags};
-use lmdb_sys::{MDB_FIRST, MDB_GET_CURRENT, MDB_LAST, MDB_NEXT};
+use lmdb_sys::{MDB_FIRST, MDB_GET_CURRENT, MDB_LAST, MDB_NEXT, MDB_SET_RANGE};
 use std::borrow::Cow;
 use std::marker::PhantomData;
 use std::path::PathBuf;
@@ -90,7 +90,7 @@ impl Environment {
 
     pub fn filenames(&self, config: &Config) -> Vec<PathBuf> {
         vec![
-            config.database_path.join("data.mdb"),
+            config.database_path.join(LMDB_DATA_FILENAME),
             config.database_path.join("lock.mdb"),
         ]
     }
@@ -158,6 +158,15 @@ impl<'env> Cursor<'env> {
         Ok(opt_key)
     }
 
+    pub fn seek_key(&mut self, key: &[u8]) -> Result<Option<Key<'env>>, Error> {
+        let opt_key = self
+            .cursor
+            .get(Some(key), None, MDB_SET_RANGE)
+            .optional()?
+            .and_then(|(key, _)| Some(Cow::Borrowed(key?)));
+        Ok(opt_key)
+    }
+
     pub fn next_key(&mut self) -> Result<Option<Key<'env>>, Error> {
         let opt_key = self
             .cursor
@@ -164,6 +164,11 @@ impl<'env> Cursor<'env> {
         Ok(opt_key)
     }
 
+    pub fn seek_key(&mut self, key: &[u8]) -> Result<Option<Cow<'env, [u8]>>, Error> {
+        let opt_key = self.cursor.set_range(key)?.map(|(key_bytes, ())| key_bytes);
+        Ok(opt_key)
+    }
+
     pub fn next_key(&mut self) -> Result<Option<Cow<'env, [u8]>>, Error> {
         let opt_key = self.cursor.next()?.map(|(key_bytes, ())| key_bytes);
         Ok(opt_key)
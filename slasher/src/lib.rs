@@ -24,7 +24,7 @@ pub use block_queue::BlockQueue;
 pub use config::{Config, DatabaseBackend, DatabaseBackendOverride};
 pub use database::{
     interface::{Database, Environment, RwTransaction},
-    IndexedAttestationId, SlasherDB,
+    IndexedAttestationId, PruneStats, SlasherDB, MAX_PRUNE_TXNS_PER_CYCLE,
 };
 pub use error::Error;
 
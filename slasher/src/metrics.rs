@@ -53,4 +53,37 @@ lazy_static! {
         "slasher_attestation_root_cache_size",
         "Number of attestation data roots cached in memory"
     );
+    pub static ref SLASHER_PRUNING_LAG: Result<IntGauge> = try_create_int_gauge(
+        "slasher_pruning_lag",
+        "Number of epochs of expired blocks and attestations remaining to be pruned"
+    );
+    pub static ref SLASHER_NUM_PROPOSERS_PRUNED: Result<IntGauge> = try_create_int_gauge(
+        "slasher_num_proposers_pruned",
+        "Number of blocks deleted in the last pruning cycle"
+    );
+    pub static ref SLASHER_NUM_ATTESTATIONS_PRUNED: Result<IntGauge> = try_create_int_gauge(
+        "slasher_num_attestations_pruned",
+        "Number of indexed attestations deleted in the last pruning cycle"
+    );
+    pub static ref SLASHER_SLASHINGS_DETECTED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "slasher_slashings_detected_total",
+        "Number of slashings found by the slasher",
+        &["type"]
+    );
+    pub static ref SLASHER_SLASHINGS_BROADCAST: Result<IntCounterVec> = try_create_int_counter_vec(
+        "slasher_slashings_broadcast_total",
+        "Number of slashings found by the slasher which were published on gossip",
+        &["type"]
+    );
+    pub static ref SLASHER_SLASHINGS_INCLUDED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "slasher_slashings_included_total",
+        "Number of slashings found by the slasher whose validators were slashed on chain",
+        &["type"]
+    );
+    pub static ref SLASHER_SLASHINGS_DROPPED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "slasher_slashings_dropped_total",
+        "Number of slashings found by the slasher which repeatedly failed verification or were \
+         evicted from the pending list",
+        &["type"]
+    );
 }
@@ -6,7 +6,8 @@ use crate::metrics::{
 };
 use crate::{
     array, AttestationBatch, AttestationQueue, AttesterRecord, BlockQueue, Config, Error,
-    IndexedAttestationId, ProposerSlashingStatus, RwTransaction, SimpleBatch, SlasherDB,
+    IndexedAttestationId, ProposerSlashingStatus, PruneStats, RwTransaction, SimpleBatch,
+    SlasherDB,
 };
 use parking_lot::Mutex;
 use slog::{debug, error, info, Logger};
@@ -349,7 +350,9 @@ impl<E: EthSpec> Slasher<E> {
     }
 
     /// Prune unnecessary attestations and blocks from the on-disk database.
-    pub fn prune_database(&self, current_epoch: Epoch) -> Result<(), Error> {
+    ///
+    /// Large backlogs are pruned incrementally over several calls, see `SlasherDB::prune`.
+    pub fn prune_database(&self, current_epoch: Epoch) -> Result<PruneStats, Error> {
         self.db.prune(current_epoch)
     }
 }
@@ -1,6 +1,9 @@
 #![cfg(feature = "lmdb")]
 
-use slasher::{config::MDBX_DATA_FILENAME, Config, DatabaseBackend, DatabaseBackendOverride};
+use slasher::{
+    config::{LMDB_DATA_FILENAME, MDBX_DATA_FILENAME},
+    Config, DatabaseBackend, DatabaseBackendOverride,
+};
 use std::fs::File;
 use tempfile::tempdir;
 
@@ -55,3 +58,44 @@ fn failed_override_with_existing_mdbx_db() {
     );
     assert_eq!(config.backend, DatabaseBackend::Lmdb);
 }
+
+#[test]
+#[cfg(all(feature = "mdbx", feature = "lmdb"))]
+fn override_with_existing_lmdb_db() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.backend = DatabaseBackend::Mdbx;
+
+    File::create(config.database_path.join(LMDB_DATA_FILENAME)).unwrap();
+
+    assert_eq!(
+        config.override_backend(),
+        DatabaseBackendOverride::Success(DatabaseBackend::Mdbx)
+    );
+    assert_eq!(config.backend, DatabaseBackend::Lmdb);
+}
+
+#[test]
+#[cfg(all(feature = "mdbx", feature = "lmdb"))]
+fn no_override_with_databases_for_both_backends() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+
+    File::create(config.database_path.join(MDBX_DATA_FILENAME)).unwrap();
+    File::create(config.database_path.join(LMDB_DATA_FILENAME)).unwrap();
+
+    assert_eq!(config.override_backend(), DatabaseBackendOverride::Noop);
+    assert_eq!(config.backend, DatabaseBackend::Lmdb);
+}
+
+#[test]
+fn no_override_with_existing_lmdb_db() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.backend = DatabaseBackend::Lmdb;
+
+    File::create(config.database_path.join(LMDB_DATA_FILENAME)).unwrap();
+
+    assert_eq!(config.override_backend(), DatabaseBackendOverride::Noop);
+    assert_eq!(config.backend, DatabaseBackend::Lmdb);
+}
@@ -0,0 +1,106 @@
+#![cfg(any(feature = "mdbx", feature = "lmdb"))]
+
+use logging::test_logger;
+use slasher::{
+    test_utils::{block, indexed_att, E},
+    Config, Slasher, MAX_PRUNE_TXNS_PER_CYCLE,
+};
+use tempfile::tempdir;
+use types::{Epoch, EthSpec};
+
+const NUM_VALIDATORS: u64 = 4;
+
+#[test]
+fn detection_near_history_boundary_during_incremental_pruning() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.chunk_size = 4;
+    config.history_length = 16;
+    config.max_prune_txn_size = 1;
+
+    let slasher = Slasher::open(config.clone(), test_logger()).unwrap();
+    let history_length = config.history_length as u64;
+
+    // Every validator attests separately in each epoch of the first history length.
+    for epoch in 1..history_length {
+        for validator in 0..NUM_VALIDATORS {
+            slasher.accept_attestation(indexed_att(vec![validator], epoch - 1, epoch, 0));
+        }
+        slasher.process_queued(Epoch::new(epoch)).unwrap();
+        slasher.prune_database(Epoch::new(epoch)).unwrap();
+    }
+    assert!(slasher.get_attester_slashings().is_empty());
+
+    // Jump forward so that the attestations targeting epochs 1..8 expire.
+    let current_epoch = Epoch::new(history_length + 7);
+    let min_epoch = 8;
+
+    // A single cycle deletes one attestation per transaction, leaving some expired epochs.
+    let stats = slasher.prune_database(current_epoch).unwrap();
+    let max_pruned_per_cycle = MAX_PRUNE_TXNS_PER_CYCLE;
+    assert_eq!(stats.num_attestations_pruned, max_pruned_per_cycle);
+    let remaining_epoch = 1 + (max_pruned_per_cycle as u64 / NUM_VALIDATORS);
+    assert_eq!(stats.lag, min_epoch - remaining_epoch);
+
+    // Double votes just inside the history boundary are still detected while pruning is in
+    // progress.
+    slasher.accept_attestation(indexed_att(vec![1], min_epoch, min_epoch + 1, 1));
+    slasher.process_queued(current_epoch).unwrap();
+    let slashings = slasher.get_attester_slashings();
+    assert_eq!(slashings.len(), 1);
+    let slashing = slashings.into_iter().next().unwrap();
+    assert_eq!(
+        slashing.attestation_1.data.target.epoch,
+        Epoch::new(min_epoch + 1)
+    );
+
+    // Pruning resumes from the persisted cursor and catches up.
+    let stats = slasher.prune_database(current_epoch).unwrap();
+    assert_eq!(
+        stats.num_attestations_pruned as u64,
+        (min_epoch - 1) * NUM_VALIDATORS - max_pruned_per_cycle as u64
+    );
+    assert_eq!(stats.lag, 0);
+
+    // Once caught up there is nothing left to do until the next epoch.
+    let stats = slasher.prune_database(current_epoch).unwrap();
+    assert_eq!(stats.num_attestations_pruned, 0);
+    assert_eq!(stats.lag, 0);
+
+    // windows won't delete the temporary directory if you don't do this..
+    drop(slasher);
+}
+
+#[test]
+fn resumed_pruning_starts_from_cursor() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.chunk_size = 4;
+    config.history_length = 16;
+
+    let slasher = Slasher::open(config.clone(), test_logger()).unwrap();
+    let history_length = config.history_length as u64;
+    let slot_in_epoch = |epoch: u64| epoch * E::slots_per_epoch();
+
+    // One block in each of epochs 1..=5.
+    for epoch in 1..=5 {
+        slasher.accept_block_header(block(slot_in_epoch(epoch), 0, epoch));
+    }
+    let current_epoch = Epoch::new(history_length + 3);
+    slasher.process_queued(current_epoch).unwrap();
+
+    // Prune the blocks from epochs 1..4, leaving the cursor at epoch 4.
+    let stats = slasher.prune_database(current_epoch).unwrap();
+    assert_eq!(stats.num_proposers_pruned, 3);
+    assert_eq!(stats.lag, 0);
+
+    // A block from below the cursor is not revisited when pruning resumes, only the blocks from
+    // epochs 4 and 5 are scanned and deleted.
+    slasher.accept_block_header(block(slot_in_epoch(2), 1, 2));
+    slasher.process_queued(current_epoch).unwrap();
+    let stats = slasher.prune_database(current_epoch + 2).unwrap();
+    assert_eq!(stats.num_proposers_pruned, 2);
+    assert_eq!(stats.lag, 0);
+
+    drop(slasher);
+}
@@ -1,6 +1,6 @@
 use super::*;
 use crate::decode::{ssz_decode_file, ssz_decode_file_with, ssz_decode_state, yaml_decode_file};
-use ::fork_choice::PayloadVerificationStatus;
+use ::fork_choice::{CountUnrealized, PayloadVerificationStatus};
 use beacon_chain::slot_clock::SlotClock;
 use beacon_chain::{
     attestation_verification::{
@@ -446,6 +446,7 @@ impl<E: EthSpec> Tester<E> {
                         &state,
                         PayloadVerificationStatus::Irrelevant,
                         ProgressiveBalancesMode::Strict,
+                        CountUnrealized::True,
                         &self.harness.chain.spec,
                         self.harness.logger(),
                     );
@@ -99,7 +99,7 @@ pub fn testing_client_config() -> ClientConfig {
     client_config
         .network
         .set_ipv4_listening_address(std::net::Ipv4Addr::UNSPECIFIED, 0, 0, 0);
-    client_config.network.upnp_enabled = false;
+    client_config.network.nat_enabled = false;
     client_config.http_api.enabled = true;
     client_config.http_api.listen_port = 0;
 
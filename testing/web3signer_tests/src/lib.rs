@@ -305,6 +305,7 @@ mod tests {
             let initialized_validators = InitializedValidators::from_definitions(
                 validator_definitions,
                 validator_dir.path().into(),
+                Default::default(),
                 log.clone(),
             )
             .await
@@ -73,3 +73,30 @@ fn export_same_key_twice() {
         export_double.minify().unwrap()
     );
 }
+
+#[test]
+fn select_pubkeys_from_interchange() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    let key1 = pubkey(1);
+    let key2 = pubkey(2);
+    let key3 = pubkey(3);
+
+    slashing_db.register_validator(key1).unwrap();
+    slashing_db.register_validator(key2).unwrap();
+
+    let interchange = slashing_db
+        .export_all_interchange_info(Hash256::zero())
+        .unwrap();
+
+    let selected = interchange.clone().select_pubkeys(&[key2]).unwrap();
+    assert_eq!(selected.data.len(), 1);
+    assert_eq!(selected.data[0].pubkey, key2);
+    assert_eq!(selected.metadata, interchange.metadata);
+
+    // Selecting a key which isn't in the interchange should fail.
+    let err = interchange.select_pubkeys(&[key1, key3]).unwrap_err();
+    assert!(matches!(err, InterchangeError::MissingPubkey(k) if k == key3));
+}
@@ -86,6 +86,30 @@ impl Interchange {
         self.len() == 0
     }
 
+    /// Retain only the records for the validators in `pubkeys`.
+    ///
+    /// Returns an error if any of `pubkeys` does not have a record in `self`.
+    pub fn select_pubkeys(self, pubkeys: &[PublicKeyBytes]) -> Result<Self, InterchangeError> {
+        let present = self
+            .data
+            .iter()
+            .map(|record| record.pubkey)
+            .collect::<HashSet<_>>();
+        if let Some(missing) = pubkeys.iter().find(|pubkey| !present.contains(pubkey)) {
+            return Err(InterchangeError::MissingPubkey(*missing));
+        }
+
+        let selected = pubkeys.iter().collect::<HashSet<_>>();
+        Ok(Self {
+            metadata: self.metadata,
+            data: self
+                .data
+                .into_iter()
+                .filter(|record| selected.contains(&record.pubkey))
+                .collect(),
+        })
+    }
+
     /// Minify an interchange by constructing a synthetic block & attestation for each validator.
     pub fn minify(&self) -> Result<Self, InterchangeError> {
         // Map from pubkey to optional max block and max attestation.
@@ -24,6 +24,11 @@ use types::{Hash256, PublicKeyBytes};
 /// The filename within the `validators` directory that contains the slashing protection DB.
 pub const SLASHING_PROTECTION_FILENAME: &str = "slashing_protection.sqlite";
 
+/// Default number of epochs of slashing protection history to keep when pruning.
+///
+/// This acts as a maximum safe-guard against clock drift.
+pub const SLASHING_PROTECTION_HISTORY_EPOCHS: u64 = 512;
+
 /// The attestation or block is not safe to sign.
 ///
 /// This could be because it's slashable, or because an error occurred.
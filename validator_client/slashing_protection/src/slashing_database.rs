@@ -1012,6 +1012,41 @@ impl SlashingDatabase {
         Ok(())
     }
 
+    /// Prune the signed blocks and attestations of all validators, retaining only those within
+    /// `history_epochs` of each validator's most recent block or attestation respectively.
+    ///
+    /// Unlike `prune_all_signed_blocks` and `prune_all_signed_attestations` this does not require
+    /// knowledge of the current epoch, so it is suitable for use while the validator client is
+    /// offline. Like them, it never removes a validator's maximum-slot block or maximum-target
+    /// attestation.
+    pub fn prune_all_relative_to_latest(
+        &self,
+        history_epochs: u64,
+        slots_per_epoch: u64,
+    ) -> Result<(), NotSafe> {
+        let history_slots = Slot::new(history_epochs.saturating_mul(slots_per_epoch));
+        let history_epochs = Epoch::new(history_epochs);
+
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction()?;
+        txn.execute(
+            "DELETE FROM signed_blocks
+             WHERE slot + ?1 < (SELECT MAX(b.slot)
+                                FROM signed_blocks b
+                                WHERE b.validator_id = signed_blocks.validator_id)",
+            params![history_slots],
+        )?;
+        txn.execute(
+            "DELETE FROM signed_attestations
+             WHERE target_epoch + ?1 < (SELECT MAX(a.target_epoch)
+                                        FROM signed_attestations a
+                                        WHERE a.validator_id = signed_attestations.validator_id)",
+            params![history_epochs],
+        )?;
+        txn.commit()?;
+        Ok(())
+    }
+
     pub fn num_validator_rows(&self) -> Result<u32, NotSafe> {
         let mut conn = self.conn_pool.get()?;
         let txn = conn.transaction()?;
@@ -1150,6 +1185,8 @@ pub enum InterchangeError {
     SQLPoolError(r2d2::Error),
     SerdeJsonError(serde_json::Error),
     InvalidPubkey(String),
+    /// A public key selected for import is not present in the interchange file.
+    MissingPubkey(PublicKeyBytes),
     NotSafe(NotSafe),
     AtomicBatchAborted(Vec<InterchangeImportOutcome>),
 }
@@ -1238,6 +1275,121 @@ mod tests {
         check(&db2);
     }
 
+    // Pruning must not change whether any message in the retained range is safe to sign, and must
+    // never make a message outside of that range safe to sign.
+    #[test]
+    fn prune_relative_to_latest_preserves_constraints() {
+        use crate::attestation_tests::attestation_data_builder;
+        use crate::test_utils::{pubkey, DEFAULT_DOMAIN};
+
+        let dir = tempdir().unwrap();
+        let pk = pubkey(0);
+        let slots_per_epoch = 8;
+        let history_epochs = 10;
+
+        let populate = |name: &str| {
+            let db = SlashingDatabase::create(&dir.path().join(name)).unwrap();
+            db.register_validator(pk).unwrap();
+            for slot in (3..300).step_by(7) {
+                db.check_and_insert_block_signing_root(
+                    &pk,
+                    Slot::new(slot),
+                    Hash256::from_low_u64_be(slot + 1).into(),
+                )
+                .unwrap();
+            }
+            for target in (1..40u64).filter(|target| target % 5 != 0) {
+                let source = target.saturating_sub(1 + (target % 3 == 0) as u64);
+                db.check_and_insert_attestation(
+                    &pk,
+                    &attestation_data_builder(source, target),
+                    DEFAULT_DOMAIN,
+                )
+                .unwrap();
+            }
+            db
+        };
+        let unpruned = populate("unpruned.sqlite");
+        let pruned = populate("pruned.sqlite");
+        pruned
+            .prune_all_relative_to_latest(history_epochs, slots_per_epoch)
+            .unwrap();
+
+        let (min_slot, min_source, min_target): (Slot, Epoch, Epoch) = {
+            let conn = pruned.conn_pool.get().unwrap();
+            let min_slot = conn
+                .query_row("SELECT MIN(slot) FROM signed_blocks", params![], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            let (min_source, min_target) = conn
+                .query_row(
+                    "SELECT MIN(source_epoch), MIN(target_epoch) FROM signed_attestations",
+                    params![],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .unwrap();
+            (min_slot, min_source, min_target)
+        };
+        assert!(min_slot > Slot::new(3), "blocks should have been pruned");
+        assert!(
+            min_target > Epoch::new(1),
+            "attestations should have been pruned"
+        );
+
+        for slot in (0..310).map(Slot::new) {
+            let signing_root = SigningRoot::from(Hash256::random());
+            let check = |db: &SlashingDatabase| {
+                db.with_transaction(|txn| db.check_block_proposal(txn, &pk, slot, signing_root))
+            };
+            if slot > min_slot {
+                assert_eq!(check(&pruned), check(&unpruned), "slot {}", slot);
+            } else {
+                assert!(check(&pruned).is_err(), "slot {}", slot);
+            }
+        }
+
+        for target in (0..45).map(Epoch::new) {
+            for source in (0..=target.as_u64()).map(Epoch::new) {
+                let signing_root = SigningRoot::from(Hash256::random());
+                let check = |db: &SlashingDatabase| {
+                    db.with_transaction(|txn| {
+                        db.check_attestation(txn, &pk, source, target, signing_root)
+                    })
+                };
+                if source >= min_source && target > min_target {
+                    assert_eq!(
+                        check(&pruned),
+                        check(&unpruned),
+                        "source {}, target {}",
+                        source,
+                        target
+                    );
+                } else {
+                    assert!(
+                        check(&pruned).is_err(),
+                        "source {}, target {}",
+                        source,
+                        target
+                    );
+                }
+            }
+        }
+
+        // The latest block and attestation are always retained.
+        let conn = pruned.conn_pool.get().unwrap();
+        let (max_slot, max_target): (Slot, Epoch) = conn
+            .query_row(
+                "SELECT (SELECT MAX(slot) FROM signed_blocks),
+                        (SELECT MAX(target_epoch) FROM signed_attestations)",
+                params![],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(max_slot, Slot::new(297));
+        assert_eq!(max_target, Epoch::new(39));
+    }
+
     #[test]
     fn test_transaction_failure() {
         let dir = tempdir().unwrap();
@@ -1,12 +1,13 @@
-use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
+use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced};
+use crate::config::DEFAULT_SIGNING_CONCURRENCY;
 use crate::{
     duties_service::{DutiesService, DutyAndProof},
     http_metrics::metrics,
+    signing_tasks::spawn_bounded,
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
     OfflineOnFailure,
 };
 use environment::RuntimeContext;
-use futures::future::join_all;
 use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
@@ -26,6 +27,7 @@ pub struct AttestationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
     slot_clock: Option<T>,
     beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
     context: Option<RuntimeContext<E>>,
+    signing_concurrency: usize,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
@@ -36,6 +38,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
             slot_clock: None,
             beacon_nodes: None,
             context: None,
+            signing_concurrency: DEFAULT_SIGNING_CONCURRENCY,
         }
     }
 
@@ -64,6 +67,11 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
         self
     }
 
+    pub fn signing_concurrency(mut self, signing_concurrency: usize) -> Self {
+        self.signing_concurrency = signing_concurrency;
+        self
+    }
+
     pub fn build(self) -> Result<AttestationService<T, E>, String> {
         Ok(AttestationService {
             inner: Arc::new(Inner {
@@ -82,6 +90,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
                 context: self
                     .context
                     .ok_or("Cannot build AttestationService without runtime_context")?,
+                signing_concurrency: self.signing_concurrency,
             }),
         })
     }
@@ -94,6 +103,8 @@ pub struct Inner<T, E: EthSpec> {
     slot_clock: T,
     beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     context: RuntimeContext<E>,
+    /// The maximum number of messages which may be signed concurrently for each committee.
+    signing_concurrency: usize,
 }
 
 /// Attempts to produce attestations for all known validators 1/3rd of the way through each slot.
@@ -357,85 +368,101 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .map_err(|e| e.to_string())?;
 
         // Create futures to produce signed `Attestation` objects.
-        let attestation_data_ref = &attestation_data;
-        let signing_futures = validator_duties.iter().map(|duty_and_proof| async move {
-            let duty = &duty_and_proof.duty;
-            let attestation_data = attestation_data_ref;
-
-            // Ensure that the attestation matches the duties.
-            #[allow(clippy::suspicious_operation_groupings)]
-            if duty.slot != attestation_data.slot || duty.committee_index != attestation_data.index
-            {
-                crit!(
-                    log,
-                    "Inconsistent validator duties during signing";
-                    "validator" => ?duty.pubkey,
-                    "duty_slot" => duty.slot,
-                    "attestation_slot" => attestation_data.slot,
-                    "duty_index" => duty.committee_index,
-                    "attestation_index" => attestation_data.index,
-                );
-                return None;
-            }
-
-            let mut attestation = Attestation {
-                aggregation_bits: BitList::with_capacity(duty.committee_length as usize).unwrap(),
-                data: attestation_data.clone(),
-                signature: AggregateSignature::infinity(),
-            };
+        let signing_futures = validator_duties.iter().cloned().map(|duty_and_proof| {
+            let service = self.clone();
+            let attestation_data = attestation_data.clone();
+            let log = log.clone();
+            async move {
+                let duty = &duty_and_proof.duty;
 
-            match self
-                .validator_store
-                .sign_attestation(
-                    duty.pubkey,
-                    duty.validator_committee_index as usize,
-                    &mut attestation,
-                    current_epoch,
-                )
-                .await
-            {
-                Ok(()) => Some((attestation, duty.validator_index)),
-                Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
-                    // A pubkey can be missing when a validator was recently
-                    // removed via the API.
-                    warn!(
-                        log,
-                        "Missing pubkey for attestation";
-                        "info" => "a validator may have recently been removed from this VC",
-                        "pubkey" => ?pubkey,
-                        "validator" => ?duty.pubkey,
-                        "committee_index" => committee_index,
-                        "slot" => slot.as_u64(),
-                    );
-                    None
-                }
-                Err(e) => {
+                // Ensure that the attestation matches the duties.
+                #[allow(clippy::suspicious_operation_groupings)]
+                if duty.slot != attestation_data.slot
+                    || duty.committee_index != attestation_data.index
+                {
                     crit!(
                         log,
-                        "Failed to sign attestation";
-                        "error" => ?e,
+                        "Inconsistent validator duties during signing";
                         "validator" => ?duty.pubkey,
-                        "committee_index" => committee_index,
-                        "slot" => slot.as_u64(),
+                        "duty_slot" => duty.slot,
+                        "attestation_slot" => attestation_data.slot,
+                        "duty_index" => duty.committee_index,
+                        "attestation_index" => attestation_data.index,
                     );
-                    None
+                    return None;
+                }
+
+                let mut attestation = Attestation {
+                    aggregation_bits: BitList::with_capacity(duty.committee_length as usize)
+                        .unwrap(),
+                    data: attestation_data.clone(),
+                    signature: AggregateSignature::infinity(),
+                };
+
+                match service
+                    .validator_store
+                    .sign_attestation(
+                        duty.pubkey,
+                        duty.validator_committee_index as usize,
+                        &mut attestation,
+                        current_epoch,
+                    )
+                    .await
+                {
+                    Ok(()) => Some((attestation, duty.validator_index)),
+                    Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
+                        // A pubkey can be missing when a validator was recently
+                        // removed via the API.
+                        warn!(
+                            log,
+                            "Missing pubkey for attestation";
+                            "info" => "a validator may have recently been removed from this VC",
+                            "pubkey" => ?pubkey,
+                            "validator" => ?duty.pubkey,
+                            "committee_index" => committee_index,
+                            "slot" => slot.as_u64(),
+                        );
+                        None
+                    }
+                    Err(e) => {
+                        crit!(
+                            log,
+                            "Failed to sign attestation";
+                            "error" => ?e,
+                            "validator" => ?duty.pubkey,
+                            "committee_index" => committee_index,
+                            "slot" => slot.as_u64(),
+                        );
+                        None
+                    }
                 }
             }
         });
 
         // Execute all the futures in parallel, collecting any successful results.
-        let (ref attestations, ref validator_indices): (Vec<_>, Vec<_>) = join_all(signing_futures)
-            .await
-            .into_iter()
-            .flatten()
-            .unzip();
+        let signing_timer = metrics::start_timer_vec(
+            &metrics::ATTESTATION_SERVICE_TIMES,
+            &[metrics::ATTESTATIONS_SIGN],
+        );
+        let (ref attestations, ref validator_indices): (Vec<_>, Vec<_>) = spawn_bounded(
+            &self.context.executor,
+            signing_futures,
+            self.signing_concurrency,
+            "attestation_signing",
+        )
+        .await
+        .into_iter()
+        .flatten()
+        .unzip();
+        drop(signing_timer);
 
         // Post the attestations to the BN.
         match self
             .beacon_nodes
-            .first_success(
+            .request(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::Attestations,
                 |beacon_node| async move {
                     let _timer = metrics::start_timer_vec(
                         &metrics::ATTESTATION_SERVICE_TIMES,
@@ -518,65 +545,82 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .map_err(|e| e.to_string())?;
 
         // Create futures to produce the signed aggregated attestations.
-        let signing_futures = validator_duties.iter().map(|duty_and_proof| async move {
-            let duty = &duty_and_proof.duty;
-            let selection_proof = duty_and_proof.selection_proof.as_ref()?;
-
-            let slot = attestation_data.slot;
-            let committee_index = attestation_data.index;
+        let signing_futures = validator_duties.iter().cloned().map(|duty_and_proof| {
+            let service = self.clone();
+            let attestation_data = attestation_data.clone();
+            let aggregated_attestation = aggregated_attestation.clone();
+            let log = log.clone();
+            async move {
+                let duty = &duty_and_proof.duty;
+                let selection_proof = duty_and_proof.selection_proof.as_ref()?;
 
-            if duty.slot != slot || duty.committee_index != committee_index {
-                crit!(log, "Inconsistent validator duties during signing");
-                return None;
-            }
+                let slot = attestation_data.slot;
+                let committee_index = attestation_data.index;
 
-            match self
-                .validator_store
-                .produce_signed_aggregate_and_proof(
-                    duty.pubkey,
-                    duty.validator_index,
-                    aggregated_attestation.clone(),
-                    selection_proof.clone(),
-                )
-                .await
-            {
-                Ok(aggregate) => Some(aggregate),
-                Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
-                    // A pubkey can be missing when a validator was recently
-                    // removed via the API.
-                    debug!(
-                        log,
-                        "Missing pubkey for aggregate";
-                        "pubkey" => ?pubkey,
-                    );
-                    None
+                if duty.slot != slot || duty.committee_index != committee_index {
+                    crit!(log, "Inconsistent validator duties during signing");
+                    return None;
                 }
-                Err(e) => {
-                    crit!(
-                        log,
-                        "Failed to sign aggregate";
-                        "error" => ?e,
-                        "pubkey" => ?duty.pubkey,
-                    );
-                    None
+
+                match service
+                    .validator_store
+                    .produce_signed_aggregate_and_proof(
+                        duty.pubkey,
+                        duty.validator_index,
+                        aggregated_attestation,
+                        selection_proof.clone(),
+                    )
+                    .await
+                {
+                    Ok(aggregate) => Some(aggregate),
+                    Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
+                        // A pubkey can be missing when a validator was recently
+                        // removed via the API.
+                        debug!(
+                            log,
+                            "Missing pubkey for aggregate";
+                            "pubkey" => ?pubkey,
+                        );
+                        None
+                    }
+                    Err(e) => {
+                        crit!(
+                            log,
+                            "Failed to sign aggregate";
+                            "error" => ?e,
+                            "pubkey" => ?duty.pubkey,
+                        );
+                        None
+                    }
                 }
             }
         });
 
         // Execute all the futures in parallel, collecting any successful results.
-        let signed_aggregate_and_proofs = join_all(signing_futures)
-            .await
-            .into_iter()
-            .flatten()
-            .collect::<Vec<_>>();
+        let signing_timer = metrics::start_timer_vec(
+            &metrics::ATTESTATION_SERVICE_TIMES,
+            &[metrics::AGGREGATES_SIGN],
+        );
+        let signed_aggregate_and_proofs = spawn_bounded(
+            &self.context.executor,
+            signing_futures,
+            self.signing_concurrency,
+            "aggregate_signing",
+        )
+        .await
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        drop(signing_timer);
 
         if !signed_aggregate_and_proofs.is_empty() {
             let signed_aggregate_and_proofs_slice = signed_aggregate_and_proofs.as_slice();
             match self
                 .beacon_nodes
-                .first_success(
+                .request(
                     RequireSynced::No,
                     OfflineOnFailure::Yes,
+                    ApiTopic::Attestations,
                     |beacon_node| async move {
                         let _timer = metrics::start_timer_vec(
                             &metrics::ATTESTATION_SERVICE_TIMES,
@@ -1,18 +1,23 @@
 //! Allows for a list of `BeaconNodeHttpClient` to appear as a single entity which will exhibits
 //! "fallback" behaviour; it will try a request on all of the nodes until one or none of them
-//! succeed.
+//! succeed. Nodes are tried in order of their health, see `beacon_node_health`.
 
-use crate::check_synced::check_synced;
-use crate::http_metrics::metrics::{inc_counter_vec, ENDPOINT_ERRORS, ENDPOINT_REQUESTS};
+use crate::beacon_node_health::{smooth_latency, BeaconNodeHealth};
+use crate::check_synced::{check_synced, get_syncing_data};
+use crate::http_metrics::metrics::{
+    inc_counter_vec, BEACON_NODE_BROADCAST_OUTCOMES, ENDPOINT_ERRORS, ENDPOINT_REQUESTS,
+};
 use environment::RuntimeContext;
-use eth2::BeaconNodeHttpClient;
+use eth2::{types::SyncingData, BeaconNodeHttpClient};
 use futures::future;
+use serde_derive::{Deserialize, Serialize};
 use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::fmt;
 use std::fmt::Debug;
 use std::future::Future;
 use std::marker::PhantomData;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::{sync::RwLock, time::sleep};
@@ -30,14 +35,59 @@ const UPDATE_REQUIRED_LOG_HINT: &str = "this VC or the remote BN may need updati
 /// having the correct nodes up and running prior to the start of the slot.
 const SLOT_LOOKAHEAD: Duration = Duration::from_secs(2);
 
+/// The types of requests which may be sent to all beacon nodes at once, rather than only to the
+/// first beacon node that responds successfully.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiTopic {
+    Attestations,
+    Blocks,
+    Subscriptions,
+    SyncCommittee,
+}
+
+impl ApiTopic {
+    /// The topics which are broadcast if the user does not specify otherwise.
+    pub fn default_broadcast() -> Vec<Self> {
+        vec![ApiTopic::Subscriptions]
+    }
+}
+
+impl FromStr for ApiTopic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "attestations" => Ok(ApiTopic::Attestations),
+            "blocks" => Ok(ApiTopic::Blocks),
+            "subscriptions" => Ok(ApiTopic::Subscriptions),
+            "sync-committee" => Ok(ApiTopic::SyncCommittee),
+            other => Err(format!("unknown broadcast topic: {}", other)),
+        }
+    }
+}
+
 /// Indicates a measurement of latency between the VC and a BN.
+#[derive(Debug, Clone, Serialize)]
 pub struct LatencyMeasurement {
     /// An identifier for the beacon node (e.g. the URL).
     pub beacon_node_id: String,
     /// The round-trip latency, if the BN responded successfully.
+    #[serde(rename = "latency_millis", serialize_with = "serialize_latency_millis")]
     pub latency: Option<Duration>,
 }
 
+/// Serialize a latency as a whole number of milliseconds.
+fn serialize_latency_millis<S: serde::Serializer>(
+    latency: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match latency {
+        Some(latency) => serializer.serialize_u64(latency.as_millis() as u64),
+        None => serializer.serialize_none(),
+    }
+}
+
 /// Starts a service that will routinely try and update the status of the provided `beacon_nodes`.
 ///
 /// See `SLOT_LOOKAHEAD` for information about when this should run.
@@ -146,6 +196,8 @@ pub enum CandidateError {
 pub struct CandidateBeaconNode<E> {
     beacon_node: BeaconNodeHttpClient,
     status: RwLock<Result<(), CandidateError>>,
+    syncing_data: RwLock<Option<SyncingData>>,
+    latency: RwLock<Option<Duration>>,
     _phantom: PhantomData<E>,
 }
 
@@ -155,6 +207,8 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         Self {
             beacon_node,
             status: RwLock::new(Err(CandidateError::Uninitialized)),
+            syncing_data: RwLock::new(None),
+            latency: RwLock::new(None),
             _phantom: PhantomData,
         }
     }
@@ -174,6 +228,17 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         *self.status.write().await = Err(CandidateError::Offline)
     }
 
+    /// Returns the health of `self`, as of the last time its status was refreshed.
+    pub async fn health(&self, index: usize) -> BeaconNodeHealth {
+        BeaconNodeHealth::new(
+            index,
+            self.beacon_node.to_string(),
+            *self.status.read().await,
+            self.syncing_data.read().await.as_ref(),
+            *self.latency.read().await,
+        )
+    }
+
     /// Perform some queries against the node to determine if it is a good candidate, updating
     /// `self.status` and returning that result.
     pub async fn refresh_status<T: SlotClock>(
@@ -301,14 +366,30 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         Ok(())
     }
 
-    /// Checks if the beacon node is synced.
+    /// Checks if the beacon node is synced, recording its sync status and the latency of the
+    /// request for use in ranking the health of the node.
     async fn is_synced<T: SlotClock>(
         &self,
         slot_clock: Option<&T>,
         log: &Logger,
     ) -> Result<(), CandidateError> {
         if let Some(slot_clock) = slot_clock {
-            check_synced(&self.beacon_node, slot_clock, Some(log)).await
+            let request_instant = Instant::now();
+            let syncing_data = match get_syncing_data(&self.beacon_node, Some(log)).await {
+                Ok(syncing_data) => syncing_data,
+                Err(e) => {
+                    *self.syncing_data.write().await = None;
+                    return Err(e);
+                }
+            };
+
+            let mut latency = self.latency.write().await;
+            *latency = Some(smooth_latency(*latency, request_instant.elapsed()));
+            drop(latency);
+
+            let result = check_synced(&self.beacon_node, &syncing_data, slot_clock, Some(log));
+            *self.syncing_data.write().await = Some(syncing_data);
+            result
         } else {
             // Skip this check if we don't supply a slot clock.
             Ok(())
@@ -322,7 +403,8 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
 pub struct BeaconNodeFallback<T, E> {
     candidates: Vec<CandidateBeaconNode<E>>,
     slot_clock: Option<T>,
-    disable_run_on_all: bool,
+    broadcast_topics: Vec<ApiTopic>,
+    latency_measurements: RwLock<Vec<LatencyMeasurement>>,
     spec: ChainSpec,
     log: Logger,
 }
@@ -330,19 +412,25 @@ pub struct BeaconNodeFallback<T, E> {
 impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
     pub fn new(
         candidates: Vec<CandidateBeaconNode<E>>,
-        disable_run_on_all: bool,
+        broadcast_topics: Vec<ApiTopic>,
         spec: ChainSpec,
         log: Logger,
     ) -> Self {
         Self {
             candidates,
             slot_clock: None,
-            disable_run_on_all,
+            broadcast_topics,
+            latency_measurements: RwLock::new(vec![]),
             spec,
             log,
         }
     }
 
+    /// Returns `true` if requests for `topic` are sent to all beacon nodes.
+    pub fn broadcasts(&self, topic: ApiTopic) -> bool {
+        self.broadcast_topics.contains(&topic)
+    }
+
     /// Used to update the slot clock post-instantiation.
     ///
     /// This is the result of a chicken-and-egg issue where `Self` needs a slot clock for some
@@ -352,6 +440,25 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         self.slot_clock = Some(slot_clock);
     }
 
+    /// Returns the health of all candidates, ranked from healthiest to least healthy.
+    pub async fn health(&self) -> Vec<BeaconNodeHealth> {
+        let mut health = Vec::with_capacity(self.candidates.len());
+        for (index, candidate) in self.candidates.iter().enumerate() {
+            health.push(candidate.health(index).await);
+        }
+        health.sort_by_key(BeaconNodeHealth::rank_key);
+        health
+    }
+
+    /// Returns all candidates, ordered from healthiest to least healthy.
+    async fn ranked_candidates(&self) -> Vec<&CandidateBeaconNode<E>> {
+        self.health()
+            .await
+            .into_iter()
+            .map(|health| &self.candidates[health.index])
+            .collect()
+    }
+
     /// The count of candidates, regardless of their state.
     pub fn num_total(&self) -> usize {
         self.candidates.len()
@@ -408,9 +515,16 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         let _ = future::join_all(futures).await;
     }
 
+    /// Returns the results of the most recent call to `measure_latency`.
+    pub async fn latest_latency_measurements(&self) -> Vec<LatencyMeasurement> {
+        self.latency_measurements.read().await.clone()
+    }
+
     /// Concurrently send a request to all candidates (regardless of
     /// offline/online) status and attempt to collect a rough reading on the
     /// latency between the VC and candidate.
+    ///
+    /// The results are retained, see `latest_latency_measurements`.
     pub async fn measure_latency(&self) -> Vec<LatencyMeasurement> {
         let futures: Vec<_> = self
             .candidates
@@ -438,7 +552,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         // Send the request to all BNs at the same time. This might involve some
         // queueing on the sending host, however I hope it will avoid bias
         // caused by sending requests at different times.
-        future::join_all(futures)
+        let measurements: Vec<_> = future::join_all(futures)
             .await
             .into_iter()
             .map(|(beacon_node_id, response_instant)| LatencyMeasurement {
@@ -446,13 +560,17 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
                 latency: response_instant
                     .and_then(|response| response.checked_duration_since(request_instant)),
             })
-            .collect()
+            .collect();
+
+        *self.latency_measurements.write().await = measurements.clone();
+        measurements
     }
 
     /// Run `func` against each candidate in `self`, returning immediately if a result is found.
     /// Otherwise, return all the errors encountered along the way.
     ///
-    /// First this function will try all nodes with a suitable status. If no candidates are suitable
+    /// Candidates are tried in order of their health, rather than the order in which they were
+    /// provided. First this function will try all nodes with a suitable status. If no candidates are suitable
     /// or all the requests fail, it will try updating the status of all unsuitable nodes and
     /// re-running `func` again.
     pub async fn first_success<'a, F, O, Err, R>(
@@ -505,10 +623,10 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
             }};
         }
 
-        // First pass: try `func` on all synced and ready candidates.
+        // First pass: try `func` on all synced and ready candidates, healthiest first.
         //
         // This ensures that we always choose a synced node if it is available.
-        for candidate in &self.candidates {
+        for candidate in self.ranked_candidates().await {
             match candidate.status(RequireSynced::Yes).await {
                 Err(e @ CandidateError::NotSynced) if require_synced == false => {
                     // This client is unsynced we will try it after trying all synced clients
@@ -561,17 +679,16 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         Err(Errors(errors))
     }
 
-    /// Run `func` against all candidates in `self`, collecting the result of `func` against each
-    /// candidate.
+    /// Concurrently run `func` against all candidates in `self`, returning `Ok(())` if at least
+    /// one candidate returned successfully.
     ///
-    /// First this function will try all nodes with a suitable status. If no candidates are suitable
-    /// it will try updating the status of all unsuitable nodes and re-running `func` again.
+    /// Candidates with an unsuitable status will have their status refreshed before `func` is
+    /// run against them. Failures on individual candidates are logged, but they are only returned
+    /// as an error if no candidate succeeded.
     ///
-    /// Note: This function returns `Ok(())` if `func` returned successfully on all beacon nodes.
-    /// It returns a list of errors along with the beacon node id that failed for `func`.
     /// Since this ignores the actual result of `func`, this function should only be used for beacon
     /// node calls whose results we do not care about, only that they completed successfully.
-    pub async fn run_on_all<'a, F, O, Err, R>(
+    pub async fn broadcast<'a, F, O, Err, R>(
         &'a self,
         require_synced: RequireSynced,
         offline_on_failure: OfflineOnFailure,
@@ -580,110 +697,79 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
     where
         F: Fn(&'a BeaconNodeHttpClient) -> R,
         R: Future<Output = Result<O, Err>>,
+        Err: Debug,
     {
-        let mut results = vec![];
-        let mut to_retry = vec![];
-        let mut retry_unsynced = vec![];
-
-        // Run `func` using a `candidate`, returning the value or capturing errors.
-        //
-        // We use a macro instead of a closure here since it is not trivial to move `func` into a
-        // closure.
-        macro_rules! try_func {
-            ($candidate: ident) => {{
-                inc_counter_vec(&ENDPOINT_REQUESTS, &[$candidate.beacon_node.as_ref()]);
-
-                // There exists a race condition where `func` may be called when the candidate is
-                // actually not ready. We deem this an acceptable inefficiency.
-                match func(&$candidate.beacon_node).await {
-                    Ok(val) => results.push(Ok(val)),
-                    Err(e) => {
-                        // If we have an error on this function, make the client as not-ready.
-                        //
-                        // There exists a race condition where the candidate may have been marked
-                        // as ready between the `func` call and now. We deem this an acceptable
-                        // inefficiency.
-                        if matches!(offline_on_failure, OfflineOnFailure::Yes) {
-                            $candidate.set_offline().await;
-                        }
-                        results.push(Err((
-                            $candidate.beacon_node.to_string(),
-                            Error::RequestFailed(e),
-                        )));
-                        inc_counter_vec(&ENDPOINT_ERRORS, &[$candidate.beacon_node.as_ref()]);
-                    }
-                }
-            }};
-        }
-
-        // First pass: try `func` on all synced and ready candidates.
-        //
-        // This ensures that we always choose a synced node if it is available.
-        for candidate in &self.candidates {
-            match candidate.status(RequireSynced::Yes).await {
-                Err(CandidateError::NotSynced) if require_synced == false => {
-                    // This client is unsynced we will try it after trying all synced clients
-                    retry_unsynced.push(candidate);
-                }
-                Err(_) => {
-                    // This client was not ready on the first pass, we might try it again later.
-                    to_retry.push(candidate);
-                }
-                Ok(_) => try_func!(candidate),
-            }
-        }
+        let log = &self.log;
+        let func = &func;
 
-        // Second pass: try `func` on ready unsynced candidates. This only runs if we permit
-        // unsynced candidates.
-        //
-        // Due to async race-conditions, it is possible that we will send a request to a candidate
-        // that has been set to an offline/unready status. This is acceptable.
-        if require_synced == false {
-            for candidate in retry_unsynced {
-                try_func!(candidate);
-            }
-        }
-
-        // Third pass: try again, attempting to make non-ready clients become ready.
-        for candidate in to_retry {
-            // If the candidate hasn't luckily transferred into the correct state in the meantime,
-            // force an update of the state.
-            let new_status = match candidate.status(require_synced).await {
+        let futures = self.candidates.iter().map(|candidate| async move {
+            // If the candidate isn't already in a suitable state, force an update of the state.
+            let status = match candidate.status(require_synced).await {
                 Ok(()) => Ok(()),
                 Err(_) => {
                     candidate
-                        .refresh_status(self.slot_clock.as_ref(), &self.spec, &self.log)
+                        .refresh_status(self.slot_clock.as_ref(), &self.spec, log)
                         .await
                 }
             };
 
-            match new_status {
-                Ok(()) => try_func!(candidate),
-                Err(CandidateError::NotSynced) if require_synced == false => try_func!(candidate),
+            match status {
+                Ok(()) => {}
+                Err(CandidateError::NotSynced) if require_synced == false => {}
                 Err(e) => {
-                    results.push(Err((
-                        candidate.beacon_node.to_string(),
-                        Error::Unavailable(e),
-                    )));
+                    return Err((candidate.beacon_node.to_string(), Error::Unavailable(e)));
                 }
             }
-        }
 
-        let errors: Vec<_> = results.into_iter().filter_map(|res| res.err()).collect();
+            inc_counter_vec(&ENDPOINT_REQUESTS, &[candidate.beacon_node.as_ref()]);
 
-        if !errors.is_empty() {
-            Err(Errors(errors))
-        } else {
+            match func(&candidate.beacon_node).await {
+                Ok(_) => {
+                    inc_counter_vec(
+                        &BEACON_NODE_BROADCAST_OUTCOMES,
+                        &[candidate.beacon_node.as_ref(), "success"],
+                    );
+                    Ok(())
+                }
+                Err(e) => {
+                    warn!(
+                        log,
+                        "Broadcast to beacon node failed";
+                        "node" => %candidate.beacon_node,
+                        "error" => ?e,
+                    );
+                    // If we have an error on this function, make the client as not-ready.
+                    if matches!(offline_on_failure, OfflineOnFailure::Yes) {
+                        candidate.set_offline().await;
+                    }
+                    inc_counter_vec(&ENDPOINT_ERRORS, &[candidate.beacon_node.as_ref()]);
+                    inc_counter_vec(
+                        &BEACON_NODE_BROADCAST_OUTCOMES,
+                        &[candidate.beacon_node.as_ref(), "failure"],
+                    );
+                    Err((candidate.beacon_node.to_string(), Error::RequestFailed(e)))
+                }
+            }
+        });
+
+        let results = future::join_all(futures).await;
+
+        if results.iter().any(Result::is_ok) {
             Ok(())
+        } else {
+            Err(Errors(
+                results.into_iter().filter_map(Result::err).collect(),
+            ))
         }
     }
 
-    /// Call `func` on first beacon node that returns success or on all beacon nodes
-    /// depending on the value of `disable_run_on_all`.
-    pub async fn run<'a, F, Err, R>(
+    /// Call `func` on all beacon nodes if `topic` is one of the `broadcast_topics`, otherwise on
+    /// the first beacon node that returns success.
+    pub async fn request<'a, F, Err, R>(
         &'a self,
         require_synced: RequireSynced,
         offline_on_failure: OfflineOnFailure,
+        topic: ApiTopic,
         func: F,
     ) -> Result<(), Errors<Err>>
     where
@@ -691,13 +777,13 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         R: Future<Output = Result<(), Err>>,
         Err: Debug,
     {
-        if self.disable_run_on_all {
+        if self.broadcast_topics.contains(&topic) {
+            self.broadcast(require_synced, offline_on_failure, func)
+                .await
+        } else {
             self.first_success(require_synced, offline_on_failure, func)
                 .await?;
             Ok(())
-        } else {
-            self.run_on_all(require_synced, offline_on_failure, func)
-                .await
         }
     }
 }
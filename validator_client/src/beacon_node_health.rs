@@ -0,0 +1,200 @@
+//! Scores the health of each beacon node so that requests can be routed to the healthiest node,
+//! rather than strictly the first one in the `--beacon-nodes` list.
+
+use crate::beacon_node_fallback::CandidateError;
+use crate::check_synced::beacon_node_is_synced;
+use eth2::types::SyncingData;
+use serde_derive::{Deserialize, Serialize};
+use std::time::Duration;
+use types::Slot;
+
+/// Latencies are compared in buckets of this many milliseconds, so that small fluctuations don't
+/// cause requests to flip between otherwise equally healthy beacon nodes.
+const LATENCY_BUCKET_MILLIS: u64 = 100;
+
+/// Each new latency sample contributes `1 / LATENCY_SMOOTHING` of the smoothed latency.
+const LATENCY_SMOOTHING: u32 = 4;
+
+/// The health tiers of a beacon node, from most to least preferred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthTier {
+    /// The node is synced, its head is fully verified and its execution layer is online.
+    Synced,
+    /// The node is synced, but its head is optimistic.
+    Optimistic,
+    /// The node is synced, but its execution layer is offline so it cannot produce blocks.
+    ElOffline,
+    /// The node is further than the sync tolerance from the head.
+    Syncing,
+    /// The node is unreachable, incompatible or has not yet been checked.
+    Unavailable,
+}
+
+/// A snapshot of the health of a single beacon node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaconNodeHealth {
+    /// The position of the node in the `--beacon-nodes` list.
+    pub index: usize,
+    pub endpoint: String,
+    pub tier: HealthTier,
+    pub head_slot: Option<Slot>,
+    pub sync_distance: Option<Slot>,
+    pub is_optimistic: Option<bool>,
+    pub el_offline: Option<bool>,
+    /// The smoothed latency of recent health checks against the node.
+    pub latency_millis: Option<u64>,
+}
+
+impl BeaconNodeHealth {
+    pub fn new(
+        index: usize,
+        endpoint: String,
+        status: Result<(), CandidateError>,
+        syncing_data: Option<&SyncingData>,
+        latency: Option<Duration>,
+    ) -> Self {
+        Self {
+            index,
+            endpoint,
+            tier: health_tier(status, syncing_data),
+            head_slot: syncing_data.map(|data| data.head_slot),
+            sync_distance: syncing_data.map(|data| data.sync_distance),
+            is_optimistic: syncing_data.and_then(|data| data.is_optimistic),
+            el_offline: syncing_data.and_then(|data| data.el_offline),
+            latency_millis: latency.map(|latency| latency.as_millis() as u64),
+        }
+    }
+
+    /// The key by which beacon nodes are ranked, where lower is healthier.
+    ///
+    /// Nodes are ranked by tier, then by latency, then by their position in the `--beacon-nodes`
+    /// list. Nodes without a latency measurement are ranked below those with one.
+    pub fn rank_key(&self) -> (HealthTier, u64, usize) {
+        let latency_bucket = self
+            .latency_millis
+            .map_or(u64::MAX, |millis| millis / LATENCY_BUCKET_MILLIS);
+        (self.tier, latency_bucket, self.index)
+    }
+}
+
+/// Determine the health tier of a node from its candidate status and its most recent response to
+/// `/eth/v1/node/syncing`.
+fn health_tier(
+    status: Result<(), CandidateError>,
+    syncing_data: Option<&SyncingData>,
+) -> HealthTier {
+    match (status, syncing_data) {
+        (Ok(()) | Err(CandidateError::NotSynced), Some(data)) => {
+            if !beacon_node_is_synced(data) {
+                HealthTier::Syncing
+            } else if data.el_offline == Some(true) {
+                HealthTier::ElOffline
+            } else if data.is_optimistic == Some(true) {
+                HealthTier::Optimistic
+            } else {
+                HealthTier::Synced
+            }
+        }
+        // The sync status is not checked prior to genesis.
+        (Ok(()), None) => HealthTier::Synced,
+        (Err(CandidateError::NotSynced), None) => HealthTier::Syncing,
+        (Err(_), _) => HealthTier::Unavailable,
+    }
+}
+
+/// Combine a new latency `sample` with the `previous` smoothed latency.
+pub fn smooth_latency(previous: Option<Duration>, sample: Duration) -> Duration {
+    match previous {
+        Some(previous) => (previous * (LATENCY_SMOOTHING - 1) + sample) / LATENCY_SMOOTHING,
+        None => sample,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syncing_data(sync_distance: u64, is_optimistic: bool, el_offline: bool) -> SyncingData {
+        SyncingData {
+            is_syncing: sync_distance > 0,
+            is_optimistic: Some(is_optimistic),
+            el_offline: Some(el_offline),
+            head_slot: Slot::new(100),
+            sync_distance: Slot::new(sync_distance),
+            is_backfilling: None,
+            backfill_rate_limited: None,
+        }
+    }
+
+    fn health(
+        index: usize,
+        status: Result<(), CandidateError>,
+        data: Option<SyncingData>,
+        latency_millis: u64,
+    ) -> BeaconNodeHealth {
+        BeaconNodeHealth::new(
+            index,
+            format!("http://node-{}", index),
+            status,
+            data.as_ref(),
+            Some(Duration::from_millis(latency_millis)),
+        )
+    }
+
+    #[test]
+    fn health_tiers() {
+        let tier = |status, data| health(0, status, data, 0).tier;
+
+        assert_eq!(
+            tier(Ok(()), Some(syncing_data(0, false, false))),
+            HealthTier::Synced
+        );
+        assert_eq!(
+            tier(Ok(()), Some(syncing_data(1, true, false))),
+            HealthTier::Optimistic
+        );
+        assert_eq!(
+            tier(
+                Err(CandidateError::NotSynced),
+                Some(syncing_data(0, false, true))
+            ),
+            HealthTier::ElOffline
+        );
+        assert_eq!(
+            tier(
+                Err(CandidateError::NotSynced),
+                Some(syncing_data(64, false, false))
+            ),
+            HealthTier::Syncing
+        );
+        assert_eq!(
+            tier(Err(CandidateError::Offline), None),
+            HealthTier::Unavailable
+        );
+    }
+
+    #[test]
+    fn ranking() {
+        let mut nodes = vec![
+            health(0, Ok(()), Some(syncing_data(0, true, false)), 10),
+            health(1, Ok(()), Some(syncing_data(0, false, false)), 450),
+            health(2, Ok(()), Some(syncing_data(0, false, false)), 30),
+            health(3, Ok(()), Some(syncing_data(0, false, false)), 60),
+            health(4, Err(CandidateError::Offline), None, 0),
+        ];
+        nodes.sort_by_key(BeaconNodeHealth::rank_key);
+
+        // Fully verified nodes come first, with similar latencies ranked in the user's order.
+        let indices = nodes.iter().map(|node| node.index).collect::<Vec<_>>();
+        assert_eq!(indices, vec![2, 3, 1, 0, 4]);
+    }
+
+    #[test]
+    fn latency_smoothing() {
+        let latency = smooth_latency(None, Duration::from_millis(100));
+        assert_eq!(latency, Duration::from_millis(100));
+        let latency = smooth_latency(Some(latency), Duration::from_millis(500));
+        assert_eq!(latency, Duration::from_millis(200));
+    }
+}
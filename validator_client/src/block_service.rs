@@ -1,4 +1,4 @@
-use crate::beacon_node_fallback::{Error as FallbackError, Errors};
+use crate::beacon_node_fallback::{ApiTopic, Error as FallbackError, Errors};
 use crate::{
     beacon_node_fallback::{BeaconNodeFallback, RequireSynced},
     determine_graffiti,
@@ -11,6 +11,7 @@ use crate::{
 };
 use environment::RuntimeContext;
 use eth2::{BeaconNodeHttpClient, StatusCode};
+use futures::future;
 use slog::Logger;
 use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
@@ -22,8 +23,8 @@ use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::time::sleep;
 use types::{
-    AbstractExecPayload, BlindedPayload, BlockType, EthSpec, FullPayload, Graffiti, PublicKeyBytes,
-    Slot,
+    AbstractExecPayload, BlindedPayload, BlockType, EthSpec, ExecPayload, FullPayload, Graffiti,
+    PublicKeyBytes, Slot,
 };
 
 #[derive(Debug)]
@@ -173,6 +174,50 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
             .await
     }
 
+    // Publish using `func` on all proposer and non-proposer nodes if blocks are broadcast,
+    // otherwise on the first node to succeed, trying `self.proposer_nodes` first.
+    pub async fn request_proposers_first<'a, F, Err, R>(
+        &'a self,
+        require_synced: RequireSynced,
+        offline_on_failure: OfflineOnFailure,
+        func: F,
+    ) -> Result<(), Errors<Err>>
+    where
+        F: Fn(&'a BeaconNodeHttpClient) -> R + Clone,
+        R: Future<Output = Result<(), Err>>,
+        Err: Debug,
+    {
+        if !self.beacon_nodes.broadcasts(ApiTopic::Blocks) {
+            return self
+                .first_success_try_proposers_first(require_synced, offline_on_failure, func)
+                .await;
+        }
+
+        let beacon_nodes_future =
+            self.beacon_nodes
+                .broadcast(require_synced, offline_on_failure, func.clone());
+        let (beacon_nodes_result, proposer_nodes_result) = match &self.proposer_nodes {
+            Some(proposer_nodes) => {
+                let proposer_nodes_future =
+                    proposer_nodes.broadcast(require_synced, offline_on_failure, func);
+                let (beacon_nodes_result, proposer_nodes_result) =
+                    future::join(beacon_nodes_future, proposer_nodes_future).await;
+                (beacon_nodes_result, Some(proposer_nodes_result))
+            }
+            None => (beacon_nodes_future.await, None),
+        };
+
+        // The block was published if any node at all accepted it.
+        match (beacon_nodes_result, proposer_nodes_result) {
+            (Ok(()), _) | (_, Some(Ok(()))) => Ok(()),
+            (Err(Errors(mut errors)), Some(Err(Errors(proposer_errors)))) => {
+                errors.extend(proposer_errors);
+                Err(Errors(errors))
+            }
+            (Err(e), None) => Err(e),
+        }
+    }
+
     // Try `func` on `self.beacon_nodes` first. If that doesn't work, try `self.proposer_nodes`.
     pub async fn first_success_try_proposers_last<'a, F, O, Err, R>(
         &'a self,
@@ -468,6 +513,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             log,
             "Requesting unsigned block";
             "slot" => slot.as_u64(),
+            "graffiti" => ?graffiti.map(|g| g.as_utf8_lossy()),
         );
 
         // Request block from first responsive beacon node.
@@ -575,13 +621,14 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             "signing_time_ms" => signing_time_ms,
         );
 
-        // Publish block with first available beacon node.
+        // Publish block with first available beacon node, or with all of them if blocks are
+        // broadcast. The block is only signed once, regardless of how many nodes receive it.
         //
         // Try the proposer nodes first, since we've likely gone to efforts to
         // protect them from DoS attacks and they're most likely to successfully
         // publish a block.
         proposer_fallback
-            .first_success_try_proposers_first(
+            .request_proposers_first(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
                 |beacon_node| async {
@@ -619,6 +666,12 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             "deposits" => signed_block.message().body().deposits().len(),
             "attestations" => signed_block.message().body().attestations().len(),
             "graffiti" => ?graffiti.map(|g| g.as_utf8_lossy()),
+            "fee_recipient" => ?signed_block
+                .message()
+                .body()
+                .execution_payload()
+                .ok()
+                .map(|payload| payload.fee_recipient()),
             "slot" => signed_block.slot().as_u64(),
         );
 
@@ -1,4 +1,5 @@
 use clap::{App, Arg};
+use slashing_protection::SLASHING_PROTECTION_HISTORY_EPOCHS;
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new("validator_client")
@@ -35,15 +36,28 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 )
                 .takes_value(true),
         )
+        // This argument is deprecated, use `--broadcast none` instead.
         .arg(
             Arg::with_name("disable-run-on-all")
                 .long("disable-run-on-all")
                 .value_name("DISABLE_RUN_ON_ALL")
-                .help("By default, Lighthouse publishes attestation, sync committee subscriptions \
-                       and proposer preparation messages to all beacon nodes provided in the \
-                       `--beacon-nodes flag`. This option changes that behaviour such that these \
-                       api calls only go out to the first available and synced beacon node")
+                .help("Deprecated. Use --broadcast none.")
                 .takes_value(false)
+                .conflicts_with("broadcast")
+        )
+        .arg(
+            Arg::with_name("broadcast")
+                .long("broadcast")
+                .value_name("API_TOPICS")
+                .help("Comma-separated list of beacon API topics to broadcast to all beacon nodes. \
+                       Possible values are: none, attestations, blocks, subscriptions, \
+                       sync-committee. Broadcast messages are signed once and published to every \
+                       available beacon node concurrently, succeeding if any node accepts them. \
+                       Topics that are not broadcast are sent to the first available beacon node, \
+                       healthiest first. Duties and block production always use this fallback \
+                       order.")
+                .default_value("subscriptions")
+                .takes_value(true)
         )
         // This argument is deprecated, use `--beacon-nodes` instead.
         .arg(
@@ -98,6 +112,19 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                      misplace your database and then run with this flag you risk being slashed."
                 )
         )
+        .arg(
+            Arg::with_name("slashing-protection-history-epochs")
+                .long("slashing-protection-history-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs of signed blocks and attestations to retain in the \
+                       slashing protection database. Older entries are pruned periodically, \
+                       although the latest block and attestation of each validator are always \
+                       kept.")
+                .default_value(Box::leak(
+                    SLASHING_PROTECTION_HISTORY_EPOCHS.to_string().into_boxed_str(),
+                ))
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("disable-auto-discover")
             .long("disable-auto-discover")
@@ -128,6 +155,23 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         in addition to the OS trust store. Commas must only be used as a \
                         delimiter, and must not be part of the certificate path.")
         )
+        .arg(
+            Arg::with_name("web3-signer-client-identity-path")
+                .long("web3-signer-client-identity-path")
+                .value_name("PKCS12-FILE")
+                .takes_value(true)
+                .help("Path to a PKCS12 client certificate used to authenticate to Web3Signer \
+                        servers. Applies to all Web3Signer validators whose definitions do not \
+                        specify a `client_identity_path` or `client_identity_password`.")
+        )
+        .arg(
+            Arg::with_name("web3-signer-client-identity-password")
+                .long("web3-signer-client-identity-password")
+                .value_name("PASSWORD")
+                .takes_value(true)
+                .requires("web3-signer-client-identity-path")
+                .help("Password for the PKCS12 file given by --web3-signer-client-identity-path.")
+        )
         // This overwrites the graffiti configured in the beacon node.
         .arg(
             Arg::with_name("graffiti")
@@ -139,10 +183,11 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .arg(
             Arg::with_name("graffiti-file")
                 .long("graffiti-file")
-                .help("Specify a graffiti file to load validator graffitis from.")
+                .help("Specify a graffiti file to load validator graffitis from. The file is \
+                       re-read before each block proposal. Validators without an entry in the \
+                       file use its `default` entry, falling back to --graffiti.")
                 .value_name("GRAFFITI-FILE")
                 .takes_value(true)
-                .conflicts_with("graffiti")
         )
         .arg(
             Arg::with_name("suggested-fee-recipient")
@@ -153,6 +198,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("FEE-RECIPIENT")
                 .takes_value(true)
         )
+        .arg(
+            Arg::with_name("suggested-fee-recipient-file")
+                .long("suggested-fee-recipient-file")
+                .help("Specify a fee recipient file to load per validator fee recipients. \
+                       The file should contain key-value pairs of validator public keys and \
+                       addresses, with an optional `default` entry. The file is checked once per \
+                       slot and re-read if it has changed. Fee recipients in the validator definitions take priority over \
+                       this file, which takes priority over --suggested-fee-recipient.")
+                .value_name("FEE-RECIPIENT-FILE")
+                .takes_value(true)
+        )
         /* REST API related arguments */
         .arg(
             Arg::with_name("http")
@@ -369,6 +425,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .default_value("500")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("signing-concurrency")
+                .long("signing-concurrency")
+                .value_name("INTEGER")
+                .help("The maximum number of attestations, aggregates or sync committee messages \
+                    which may be signed concurrently for a single committee. Signing is spread \
+                    across the async runtime, so increasing this value may reduce the time taken \
+                    to sign for many validators in the same slot.")
+                .default_value("64")
+                .takes_value(true),
+        )
         /*
          * Experimental/development options.
          */
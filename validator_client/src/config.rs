@@ -1,4 +1,7 @@
+use crate::beacon_node_fallback::ApiTopic;
+use crate::fee_recipient_file::FeeRecipientFile;
 use crate::graffiti_file::GraffitiFile;
+use crate::initialized_validators::Web3SignerDefaults;
 use crate::{http_api, http_metrics};
 use clap::ArgMatches;
 use clap_utils::{flags::DISABLE_MALLOC_TUNING_FLAG, parse_optional, parse_required};
@@ -9,6 +12,7 @@ use directory::{
 use eth2::types::Graffiti;
 use sensitive_url::SensitiveUrl;
 use serde_derive::{Deserialize, Serialize};
+use slashing_protection::SLASHING_PROTECTION_HISTORY_EPOCHS;
 use slog::{info, warn, Logger};
 use std::fs;
 use std::net::IpAddr;
@@ -17,6 +21,8 @@ use std::time::Duration;
 use types::{Address, GRAFFITI_BYTES_LEN};
 
 pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
+/// The default maximum number of messages which may be signed concurrently for a committee.
+pub const DEFAULT_SIGNING_CONCURRENCY: usize = 64;
 
 /// Stores the core configuration for this validator instance.
 #[derive(Clone, Serialize, Deserialize)]
@@ -38,6 +44,9 @@ pub struct Config {
     pub disable_auto_discover: bool,
     /// If true, re-register existing validators in definitions.yml for slashing protection.
     pub init_slashing_protection: bool,
+    /// Number of epochs of slashing protection history to keep when periodically pruning the
+    /// slashing protection database.
+    pub slashing_protection_history_epochs: u64,
     /// If true, use longer timeouts for requests made to the beacon node.
     pub use_long_timeouts: bool,
     /// Graffiti to be inserted everytime we create a block.
@@ -46,6 +55,8 @@ pub struct Config {
     pub graffiti_file: Option<GraffitiFile>,
     /// Fallback fallback address.
     pub fee_recipient: Option<Address>,
+    /// Fee recipient file to load per validator fee recipients.
+    pub fee_recipient_file: Option<FeeRecipientFile>,
     /// Configuration for the HTTP REST API.
     pub http_api: http_api::Config,
     /// Configuration for the HTTP REST API.
@@ -69,16 +80,21 @@ pub struct Config {
     /// A list of custom certificates that the validator client will additionally use when
     /// connecting to a beacon node over SSL/TLS.
     pub beacon_nodes_tls_certs: Option<Vec<PathBuf>>,
+    /// Settings used for Web3Signer validators whose definitions do not specify them.
+    pub web3_signer_defaults: Web3SignerDefaults,
     /// Delay from the start of the slot to wait before publishing a block.
     ///
     /// This is *not* recommended in prod and should only be used for testing.
     pub block_delay: Option<Duration>,
-    /// Disables publishing http api requests to all beacon nodes for select api calls.
-    pub disable_run_on_all: bool,
+    /// The beacon API topics which are published to all beacon nodes, rather than only the first
+    /// available one.
+    pub broadcast_topics: Vec<ApiTopic>,
     /// Enables a service which attempts to measure latency between the VC and BNs.
     pub enable_latency_measurement_service: bool,
     /// Defines the number of validators per `validator/register_validator` request sent to the BN.
     pub validator_registration_batch_size: usize,
+    /// The maximum number of messages which may be signed concurrently for a committee.
+    pub signing_concurrency: usize,
 }
 
 impl Default for Config {
@@ -103,23 +119,27 @@ impl Default for Config {
             allow_unsynced_beacon_node: false,
             disable_auto_discover: false,
             init_slashing_protection: false,
+            slashing_protection_history_epochs: SLASHING_PROTECTION_HISTORY_EPOCHS,
             use_long_timeouts: false,
             graffiti: None,
             graffiti_file: None,
             fee_recipient: None,
+            fee_recipient_file: None,
             http_api: <_>::default(),
             http_metrics: <_>::default(),
             monitoring_api: None,
             enable_doppelganger_protection: false,
             enable_high_validator_count_metrics: false,
             beacon_nodes_tls_certs: None,
+            web3_signer_defaults: <_>::default(),
             block_delay: None,
             builder_proposals: false,
             builder_registration_timestamp_override: None,
             gas_limit: None,
-            disable_run_on_all: false,
+            broadcast_topics: ApiTopic::default_broadcast(),
             enable_latency_measurement_service: true,
             validator_registration_batch_size: 500,
+            signing_concurrency: DEFAULT_SIGNING_CONCURRENCY,
         }
     }
 }
@@ -215,9 +235,26 @@ impl Config {
                 "msg" => "it no longer has any effect",
             );
         }
-        config.disable_run_on_all = cli_args.is_present("disable-run-on-all");
+
+        if cli_args.is_present("disable-run-on-all") {
+            warn!(
+                log,
+                "The --disable-run-on-all flag is deprecated";
+                "msg" => "please use --broadcast none instead"
+            );
+            config.broadcast_topics = vec![];
+        } else if let Some(broadcast_topics) = cli_args.value_of("broadcast") {
+            config.broadcast_topics = parse_broadcast_topics(broadcast_topics)?;
+        }
+
         config.disable_auto_discover = cli_args.is_present("disable-auto-discover");
         config.init_slashing_protection = cli_args.is_present("init-slashing-protection");
+
+        config.slashing_protection_history_epochs =
+            parse_required(cli_args, "slashing-protection-history-epochs")?;
+        if config.slashing_protection_history_epochs == 0 {
+            return Err("slashing-protection-history-epochs cannot be 0".to_string());
+        }
         config.use_long_timeouts = cli_args.is_present("use-long-timeouts");
 
         if let Some(graffiti_file_path) = cli_args.value_of("graffiti-file") {
@@ -254,10 +291,28 @@ impl Config {
             config.fee_recipient = Some(input_fee_recipient);
         }
 
+        if let Some(fee_recipient_file_path) = cli_args.value_of("suggested-fee-recipient-file") {
+            let mut fee_recipient_file = FeeRecipientFile::new(fee_recipient_file_path.into());
+            fee_recipient_file
+                .read_fee_recipient_file()
+                .map_err(|e| format!("Error reading fee recipient file: {:?}", e))?;
+            config.fee_recipient_file = Some(fee_recipient_file);
+            info!(
+                log,
+                "Successfully loaded fee recipient file";
+                "path" => fee_recipient_file_path
+            );
+        }
+
         if let Some(tls_certs) = parse_optional::<String>(cli_args, "beacon-nodes-tls-certs")? {
             config.beacon_nodes_tls_certs = Some(tls_certs.split(',').map(PathBuf::from).collect());
         }
 
+        config.web3_signer_defaults.client_identity_path =
+            parse_optional(cli_args, "web3-signer-client-identity-path")?;
+        config.web3_signer_defaults.client_identity_password =
+            parse_optional(cli_args, "web3-signer-client-identity-password")?;
+
         /*
          * Http API server
          */
@@ -397,6 +452,11 @@ impl Config {
             return Err("validator-registration-batch-size cannot be 0".to_string());
         }
 
+        config.signing_concurrency = parse_required(cli_args, "signing-concurrency")?;
+        if config.signing_concurrency == 0 {
+            return Err("signing-concurrency cannot be 0".to_string());
+        }
+
         /*
          * Experimental
          */
@@ -408,6 +468,21 @@ impl Config {
     }
 }
 
+/// Parse a comma-separated list of broadcast topics, where `none` disables broadcasting.
+fn parse_broadcast_topics(value: &str) -> Result<Vec<ApiTopic>, String> {
+    let mut topics = vec![];
+    for topic in value.split(',').map(str::trim) {
+        if topic == "none" {
+            continue;
+        }
+        let topic = topic.parse::<ApiTopic>()?;
+        if !topics.contains(&topic) {
+            topics.push(topic);
+        }
+    }
+    Ok(topics)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
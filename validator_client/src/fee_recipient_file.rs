@@ -0,0 +1,253 @@
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{prelude::*, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+use bls::PublicKeyBytes;
+use types::Address;
+
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum Error {
+    InvalidFile(std::io::Error),
+    InvalidLine(String),
+    InvalidPublicKey(String),
+    InvalidFeeRecipient(String),
+}
+
+/// Struct to load validator fee recipients from file.
+/// The fee recipient file is expected to have the following structure
+///
+/// default: 0x00000000219ab540356cbb839cbe05303d7705fa
+/// public_key1: fee_recipient1
+/// public_key2: fee_recipient2
+/// ...
+///
+/// The file can be re-read with `reload_if_modified` whenever its modification time changes, so
+/// that fee recipients can be updated without restarting the validator client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeRecipientFile {
+    fee_recipient_path: PathBuf,
+    fee_recipients: HashMap<PublicKeyBytes, Address>,
+    default: Option<Address>,
+    last_modified: Option<SystemTime>,
+}
+
+impl FeeRecipientFile {
+    pub fn new(fee_recipient_path: PathBuf) -> Self {
+        Self {
+            fee_recipient_path,
+            fee_recipients: HashMap::new(),
+            default: None,
+            last_modified: None,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.fee_recipient_path
+    }
+
+    /// Returns a freshly read copy of the fee recipient file if its modification time has changed
+    /// since `self` was read, otherwise `None`. `self` is left untouched, so the values from the
+    /// last successful read remain available if loading fails.
+    pub fn reload_if_modified(&self) -> Result<Option<Self>, Error> {
+        let modified = std::fs::metadata(&self.fee_recipient_path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(Error::InvalidFile)?;
+        if self.last_modified == Some(modified) {
+            return Ok(None);
+        }
+        let mut reloaded = Self::new(self.fee_recipient_path.clone());
+        reloaded.read_fee_recipient_file()?;
+        Ok(Some(reloaded))
+    }
+
+    /// Returns the fee recipient for the given public key from the last successful read of the
+    /// file, falling back to the default fee recipient.
+    pub fn get_fee_recipient(&self, public_key: &PublicKeyBytes) -> Option<Address> {
+        self.fee_recipients
+            .get(public_key)
+            .copied()
+            .or(self.default)
+    }
+
+    /// Reads from a fee recipient file with the specified format and populates the default value
+    /// and the hashmap.
+    ///
+    /// Returns an error if the file does not exist, or if the format is invalid. Errors for
+    /// invalid entries include the line number at which they occurred.
+    pub fn read_fee_recipient_file(&mut self) -> Result<(), Error> {
+        let modified = std::fs::metadata(&self.fee_recipient_path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(Error::InvalidFile)?;
+        let file = File::open(self.fee_recipient_path.as_path()).map_err(Error::InvalidFile)?;
+        let reader = BufReader::new(file);
+
+        let mut fee_recipients = HashMap::new();
+        let mut default = None;
+
+        for (i, line) in reader.lines().enumerate() {
+            let line_number = i + 1;
+            let line = line.map_err(|e| Error::InvalidLine(format!("line {line_number}: {e}")))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (pk_opt, fee_recipient) = read_line(&line).map_err(|e| match e {
+                Error::InvalidLine(e) => Error::InvalidLine(format!("line {line_number}: {e}")),
+                Error::InvalidPublicKey(e) => {
+                    Error::InvalidPublicKey(format!("line {line_number}: {e}"))
+                }
+                Error::InvalidFeeRecipient(e) => {
+                    Error::InvalidFeeRecipient(format!("line {line_number}: {e}"))
+                }
+                e => e,
+            })?;
+            match pk_opt {
+                Some(pk) => {
+                    fee_recipients.insert(pk, fee_recipient);
+                }
+                None => default = Some(fee_recipient),
+            }
+        }
+
+        self.fee_recipients = fee_recipients;
+        self.default = default;
+        self.last_modified = Some(modified);
+        Ok(())
+    }
+}
+
+/// Parses a line from the fee recipient file.
+///
+/// `Ok((None, fee_recipient))` represents the fee recipient for the default key.
+/// `Ok((Some(pk), fee_recipient))` represents the fee recipient for the public key `pk`.
+/// Returns an error if the line is in the wrong format or does not contain a valid public key or
+/// address.
+fn read_line(line: &str) -> Result<(Option<PublicKeyBytes>, Address), Error> {
+    if let Some(i) = line.find(':') {
+        let (key, value) = line.split_at(i);
+        // Note: `value.len() >=1` so `value[1..]` is safe
+        let value = value[1..].trim();
+        let fee_recipient = Address::from_str(value)
+            .map_err(|e| Error::InvalidFeeRecipient(format!("{value}: {e:?}")))?;
+        let key = key.trim();
+        if key == "default" {
+            Ok((None, fee_recipient))
+        } else {
+            let pk = PublicKeyBytes::from_str(key).map_err(Error::InvalidPublicKey)?;
+            Ok((Some(pk), fee_recipient))
+        }
+    } else {
+        Err(Error::InvalidLine(format!("Missing delimiter: {}", line)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls::Keypair;
+    use std::io::LineWriter;
+    use tempfile::TempDir;
+
+    const DEFAULT_FEE_RECIPIENT: &str = "0x00000000219ab540356cbb839cbe05303d7705fa";
+    const CUSTOM_FEE_RECIPIENT1: &str = "0x4242424242424242424242424242424242424242";
+    const CUSTOM_FEE_RECIPIENT2: &str = "0x0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f";
+    const PK1: &str = "0x800012708dc03f611751aad7a43a082142832b5c1aceed07ff9b543cf836381861352aa923c70eeb02018b638aa306aa";
+    const PK2: &str = "0x80001866ce324de7d80ec73be15e2d064dcf121adf1b34a0d679f2b9ecbab40ce021e03bb877e1a2fe72eaaf475e6e21";
+
+    // Create a fee recipient file with the given lines and return a path to the file.
+    fn create_fee_recipient_file(lines: &[String]) -> PathBuf {
+        let temp = TempDir::new().unwrap();
+        let file_name = temp.into_path().join("fee_recipients.txt");
+
+        let file = File::create(&file_name).unwrap();
+        let mut fee_recipient_file = LineWriter::new(file);
+        for line in lines {
+            fee_recipient_file
+                .write_all(format!("{}\n", line).as_bytes())
+                .unwrap();
+        }
+        fee_recipient_file.flush().unwrap();
+        file_name
+    }
+
+    #[test]
+    fn test_load_fee_recipient() {
+        let path = create_fee_recipient_file(&[
+            format!("default: {}", DEFAULT_FEE_RECIPIENT),
+            format!("{}: {}", PK1, CUSTOM_FEE_RECIPIENT1),
+            format!("{}:{}", PK2, CUSTOM_FEE_RECIPIENT2),
+        ]);
+        let mut file = FeeRecipientFile::new(path);
+        file.read_fee_recipient_file().unwrap();
+
+        let pk1 = PublicKeyBytes::from_str(PK1).unwrap();
+        let pk2 = PublicKeyBytes::from_str(PK2).unwrap();
+
+        assert_eq!(
+            file.get_fee_recipient(&pk1),
+            Some(Address::from_str(CUSTOM_FEE_RECIPIENT1).unwrap())
+        );
+        assert_eq!(
+            file.get_fee_recipient(&pk2),
+            Some(Address::from_str(CUSTOM_FEE_RECIPIENT2).unwrap())
+        );
+
+        // Random pk should return the default fee recipient
+        let random_pk = Keypair::random().pk.compress();
+        assert_eq!(
+            file.get_fee_recipient(&random_pk),
+            Some(Address::from_str(DEFAULT_FEE_RECIPIENT).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_reload_if_modified() {
+        let path = create_fee_recipient_file(&[format!("default: {}", DEFAULT_FEE_RECIPIENT)]);
+        let file = FeeRecipientFile::new(path.clone());
+        let random_pk = Keypair::random().pk.compress();
+
+        // A file that has never been read is always loaded, afterwards only if it is modified.
+        let mut file = file.reload_if_modified().unwrap().unwrap();
+        assert!(file.reload_if_modified().unwrap().is_none());
+        assert_eq!(
+            file.get_fee_recipient(&random_pk),
+            Some(Address::from_str(DEFAULT_FEE_RECIPIENT).unwrap())
+        );
+
+        // Modification times can be coarse, so forget the last one to simulate an update.
+        std::fs::write(&path, format!("default: {}\n", CUSTOM_FEE_RECIPIENT1)).unwrap();
+        file.last_modified = Some(SystemTime::UNIX_EPOCH);
+        let mut file = file.reload_if_modified().unwrap().unwrap();
+        assert_eq!(
+            file.get_fee_recipient(&random_pk),
+            Some(Address::from_str(CUSTOM_FEE_RECIPIENT1).unwrap())
+        );
+
+        // An invalid update is reported and the previously loaded values are retained.
+        std::fs::write(&path, "default 0x00\n").unwrap();
+        file.last_modified = Some(SystemTime::UNIX_EPOCH);
+        assert!(file.reload_if_modified().is_err());
+        assert_eq!(
+            file.get_fee_recipient(&random_pk),
+            Some(Address::from_str(CUSTOM_FEE_RECIPIENT1).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_invalid_fee_recipient_reports_line() {
+        let path = create_fee_recipient_file(&[
+            format!("default: {}", DEFAULT_FEE_RECIPIENT),
+            format!("{}: 0xnotanaddress", PK1),
+        ]);
+        let mut file = FeeRecipientFile::new(path);
+
+        match file.read_fee_recipient_file() {
+            Err(Error::InvalidFeeRecipient(e)) => assert!(e.starts_with("line 2:"), "{}", e),
+            other => panic!("expected invalid fee recipient error, got {:?}", other),
+        }
+    }
+}
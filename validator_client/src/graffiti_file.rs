@@ -54,7 +54,7 @@ impl GraffitiFile {
     }
 
     /// Reads from a graffiti file with the specified format and populates the default value
-    /// and the hashmap.
+    /// and the hashmap, replacing any values from a previous read.
     ///
     /// Returns an error if the file does not exist, or if the format is invalid.
     pub fn read_graffiti_file(&mut self) -> Result<(), Error> {
@@ -63,16 +63,22 @@ impl GraffitiFile {
 
         let lines = reader.lines();
 
+        let mut graffitis = HashMap::new();
+        let mut default = None;
+
         for line in lines {
             let line = line.map_err(|e| Error::InvalidLine(e.to_string()))?;
             let (pk_opt, graffiti) = read_line(&line)?;
             match pk_opt {
                 Some(pk) => {
-                    self.graffitis.insert(pk, graffiti);
+                    graffitis.insert(pk, graffiti);
                 }
-                None => self.default = Some(graffiti),
+                None => default = Some(graffiti),
             }
         }
+
+        self.graffitis = graffitis;
+        self.default = default;
         Ok(())
     }
 }
@@ -87,7 +93,7 @@ fn read_line(line: &str) -> Result<(Option<PublicKeyBytes>, Graffiti), Error> {
         let (key, value) = line.split_at(i);
         // Note: `value.len() >=1` so `value[1..]` is safe
         let graffiti = GraffitiString::from_str(value[1..].trim())
-            .map_err(Error::InvalidGraffiti)?
+            .map_err(|e| Error::InvalidGraffiti(format!("{} for {}", e, key)))?
             .into();
         if key == "default" {
             Ok((None, graffiti))
@@ -175,4 +181,45 @@ mod tests {
             GraffitiString::from_str(DEFAULT_GRAFFITI).unwrap().into()
         );
     }
+
+    #[test]
+    fn test_reload_removes_graffiti() {
+        let graffiti_file_path = create_graffiti_file();
+        let mut gf = GraffitiFile::new(graffiti_file_path.clone());
+        let pk1 = PublicKeyBytes::deserialize(&hex::decode(&PK1[2..]).unwrap()).unwrap();
+
+        gf.read_graffiti_file().unwrap();
+        assert_eq!(
+            gf.load_graffiti(&pk1).unwrap().unwrap(),
+            GraffitiString::from_str(CUSTOM_GRAFFITI1).unwrap().into()
+        );
+
+        // Edits to the file take effect on the next load, without falling back to stale entries.
+        std::fs::write(
+            &graffiti_file_path,
+            format!("{}: {}\n", PK2, CUSTOM_GRAFFITI2),
+        )
+        .unwrap();
+        assert_eq!(gf.load_graffiti(&pk1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_graffiti_length_boundary() {
+        // Each "é" is 2 bytes in UTF-8, so 16 of them exactly fill the graffiti.
+        let max_len = "é".repeat(16);
+        let (_, graffiti) = read_line(&format!("{}: {}", PK1, max_len)).unwrap();
+        assert_eq!(graffiti.as_utf8_lossy(), max_len);
+
+        // A graffiti which would have to be truncated part-way through a character is rejected,
+        // naming the offending public key.
+        let over_len = format!("a{}", max_len);
+        match read_line(&format!("{}: {}", PK1, over_len)) {
+            Err(Error::InvalidGraffiti(e)) => assert!(e.contains(PK1), "{}", e),
+            other => panic!("expected invalid graffiti error, got {:?}", other),
+        }
+        match read_line(&format!("default: {}", over_len)) {
+            Err(Error::InvalidGraffiti(e)) => assert!(e.contains("default"), "{}", e),
+            other => panic!("expected invalid graffiti error, got {:?}", other),
+        }
+    }
 }
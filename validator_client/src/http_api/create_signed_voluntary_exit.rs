@@ -4,11 +4,12 @@ use eth2::types::GenericResponse;
 use slog::{info, Logger};
 use slot_clock::SlotClock;
 use std::sync::Arc;
-use types::{Epoch, EthSpec, SignedVoluntaryExit, VoluntaryExit};
+use types::{Epoch, EthSpec, Hash256, SignedVoluntaryExit, VoluntaryExit};
 
 pub async fn create_signed_voluntary_exit<T: 'static + SlotClock + Clone, E: EthSpec>(
     pubkey: PublicKey,
     maybe_epoch: Option<Epoch>,
+    genesis_validators_root: Option<Hash256>,
     validator_store: Arc<ValidatorStore<T, E>>,
     slot_clock: T,
     log: Logger,
@@ -48,11 +49,12 @@ pub async fn create_signed_voluntary_exit<T: 'static + SlotClock + Clone, E: Eth
         log,
         "Signing voluntary exit";
         "validator" => pubkey_bytes.as_hex_string(),
-        "epoch" => epoch
+        "epoch" => epoch,
+        "genesis_validators_root" => ?genesis_validators_root,
     );
 
     let signed_voluntary_exit = validator_store
-        .sign_voluntary_exit(pubkey_bytes, voluntary_exit)
+        .sign_voluntary_exit(pubkey_bytes, voluntary_exit, genesis_validators_root)
         .await
         .map_err(|e| {
             warp_utils::reject::custom_server_error(format!(
@@ -15,6 +15,7 @@ use eth2::lighthouse_vc::{
 use eth2_keystore::Keystore;
 use slog::{info, warn, Logger};
 use slot_clock::SlotClock;
+use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
 use task_executor::TaskExecutor;
@@ -185,12 +186,14 @@ fn import_single_keystore<T: SlotClock + 'static, E: EthSpec>(
         }
     }
 
-    let password_storage = if let Some(secrets_dir) = &secrets_dir {
-        let password_path = keystore_password_path(secrets_dir, &keystore);
+    let password_path_opt = secrets_dir
+        .as_ref()
+        .map(|secrets_dir| keystore_password_path(secrets_dir, &keystore));
+    let password_storage = if let Some(password_path) = &password_path_opt {
         if password_path.exists() {
             return Ok(ImportKeystoreStatus::Duplicate);
         }
-        PasswordStorage::File(password_path)
+        PasswordStorage::File(password_path.clone())
     } else {
         PasswordStorage::ValidatorDefinitions(password.clone())
     };
@@ -215,17 +218,25 @@ fn import_single_keystore<T: SlotClock + 'static, E: EthSpec>(
     let voting_keystore_path = validator_dir.voting_keystore_path();
     drop(validator_dir);
 
-    handle
-        .block_on(validator_store.add_validator_keystore(
-            voting_keystore_path,
-            password_storage,
-            true,
-            None,
-            None,
-            None,
-            None,
-        ))
-        .map_err(|e| format!("failed to initialize validator: {:?}", e))?;
+    if let Err(e) = handle.block_on(validator_store.add_validator_keystore(
+        voting_keystore_path.clone(),
+        password_storage,
+        true,
+        None,
+        None,
+        None,
+        None,
+    )) {
+        // Remove the files written above so that the import can be retried, and so that the
+        // key isn't picked up by a later restart without its validator definition.
+        if let Some(dir) = voting_keystore_path.parent() {
+            let _ = fs::remove_dir_all(dir);
+        }
+        if let Some(password_path) = password_path_opt {
+            let _ = fs::remove_file(password_path);
+        }
+        return Err(format!("failed to initialize validator: {:?}", e));
+    }
 
     Ok(ImportKeystoreStatus::Imported)
 }
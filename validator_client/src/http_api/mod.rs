@@ -7,6 +7,8 @@ mod tests;
 
 pub mod test_utils;
 
+use crate::beacon_node_fallback::BeaconNodeFallback;
+use crate::doppelganger_service::DoppelgangerStatus;
 use crate::http_api::create_signed_voluntary_exit::create_signed_voluntary_exit;
 use crate::{determine_graffiti, GraffitiFile, ValidatorStore};
 use account_utils::{
@@ -18,7 +20,9 @@ use create_validator::{
     create_validators_mnemonic, create_validators_web3signer, get_voting_password_storage,
 };
 use eth2::lighthouse_vc::{
-    std_types::{AuthResponse, GetFeeRecipientResponse, GetGasLimitResponse},
+    std_types::{
+        AuthResponse, GetFeeRecipientResponse, GetGasLimitResponse, ProposalSettingSource,
+    },
     types::{self as api_types, GenericResponse, Graffiti, PublicKey, PublicKeyBytes},
 };
 use lighthouse_version::version_with_platform;
@@ -74,6 +78,7 @@ pub struct Context<T: SlotClock, E: EthSpec> {
     pub task_executor: TaskExecutor,
     pub api_secret: ApiSecret,
     pub validator_store: Option<Arc<ValidatorStore<T, E>>>,
+    pub beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
     pub validator_dir: Option<PathBuf>,
     pub secrets_dir: Option<PathBuf>,
     pub graffiti_file: Option<GraffitiFile>,
@@ -198,6 +203,17 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    let inner_beacon_nodes = ctx.beacon_nodes.clone();
+    let beacon_nodes_filter = warp::any()
+        .map(move || inner_beacon_nodes.clone())
+        .and_then(|beacon_nodes: Option<_>| async move {
+            beacon_nodes.ok_or_else(|| {
+                warp_utils::reject::custom_not_found(
+                    "beacon nodes are not initialized.".to_string(),
+                )
+            })
+        });
+
     let inner_secrets_dir = ctx.secrets_dir.clone();
     let secrets_dir_filter = warp::any().map(move || inner_secrets_dir.clone()).and_then(
         |secrets_dir: Option<_>| async move {
@@ -323,6 +339,40 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    // GET lighthouse/doppelganger
+    let get_lighthouse_doppelganger = warp::path("lighthouse")
+        .and(warp::path("doppelganger"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(signer.clone())
+        .and_then(|validator_store: Arc<ValidatorStore<T, E>>, signer| {
+            blocking_signed_json_task(signer, move || {
+                let statuses = validator_store
+                    .doppelganger_statuses()
+                    .into_iter()
+                    .map(|status| {
+                        let (voting_pubkey, status) = match status {
+                            DoppelgangerStatus::SigningEnabled(pubkey) => {
+                                (pubkey, api_types::DoppelgangerState::SigningEnabled)
+                            }
+                            DoppelgangerStatus::SigningDisabled(pubkey) => {
+                                (pubkey, api_types::DoppelgangerState::SigningDisabled)
+                            }
+                            DoppelgangerStatus::UnknownToDoppelganger(pubkey) => {
+                                (pubkey, api_types::DoppelgangerState::Unknown)
+                            }
+                        };
+                        api_types::DoppelgangerData {
+                            voting_pubkey,
+                            status,
+                        }
+                    })
+                    .collect::<Vec<_>>();
+
+                Ok(api_types::GenericResponse::from(statuses))
+            })
+        });
+
     // GET lighthouse/validators/{validator_pubkey}
     let get_lighthouse_validators_pubkey = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -374,6 +424,53 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    // GET lighthouse/ui/fallback_health
+    let get_lighthouse_ui_fallback_health = warp::path("lighthouse")
+        .and(warp::path("ui"))
+        .and(warp::path("fallback_health"))
+        .and(warp::path::end())
+        .and(beacon_nodes_filter.clone())
+        .and(signer.clone())
+        .and(task_executor_filter.clone())
+        .and_then(
+            |beacon_nodes: Arc<BeaconNodeFallback<T, E>>, signer, task_executor: TaskExecutor| {
+                blocking_signed_json_task(signer, move || {
+                    if let Some(handle) = task_executor.handle() {
+                        let health = handle.block_on(beacon_nodes.health());
+                        Ok(api_types::GenericResponse::from(health))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
+    // GET lighthouse/ui/latency
+    let get_lighthouse_ui_latency = warp::path("lighthouse")
+        .and(warp::path("ui"))
+        .and(warp::path("latency"))
+        .and(warp::path::end())
+        .and(beacon_nodes_filter.clone())
+        .and(signer.clone())
+        .and(task_executor_filter.clone())
+        .and_then(
+            |beacon_nodes: Arc<BeaconNodeFallback<T, E>>, signer, task_executor: TaskExecutor| {
+                blocking_signed_json_task(signer, move || {
+                    if let Some(handle) = task_executor.handle() {
+                        let measurements =
+                            handle.block_on(beacon_nodes.latest_latency_measurements());
+                        Ok(api_types::GenericResponse::from(measurements))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
     let get_lighthouse_ui_graffiti = warp::path("lighthouse")
         .and(warp::path("ui"))
         .and(warp::path("graffiti"))
@@ -784,12 +881,26 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                             validator_pubkey
                         )));
                     }
+                    let pubkey = PublicKeyBytes::from(&validator_pubkey);
+                    let source = if validator_store
+                        .initialized_validators()
+                        .read()
+                        .suggested_fee_recipient(&pubkey)
+                        .is_some()
+                    {
+                        ProposalSettingSource::Override
+                    } else if validator_store.fee_recipient_from_file(&pubkey).is_some() {
+                        ProposalSettingSource::File
+                    } else {
+                        ProposalSettingSource::Default
+                    };
                     validator_store
-                        .get_fee_recipient(&PublicKeyBytes::from(&validator_pubkey))
+                        .get_fee_recipient(&pubkey)
                         .map(|fee_recipient| {
                             GenericResponse::from(GetFeeRecipientResponse {
-                                pubkey: PublicKeyBytes::from(validator_pubkey.clone()),
+                                pubkey,
                                 ethaddress: fee_recipient,
+                                source: Some(source),
                             })
                         })
                         .ok_or_else(|| {
@@ -816,17 +927,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
              validator_store: Arc<ValidatorStore<T, E>>,
              signer| {
                 blocking_signed_json_task(signer, move || {
-                    if validator_store
-                        .initialized_validators()
-                        .read()
-                        .is_enabled(&validator_pubkey)
-                        .is_none()
-                    {
-                        return Err(warp_utils::reject::custom_not_found(format!(
-                            "no validator found with pubkey {:?}",
-                            validator_pubkey
-                        )));
-                    }
+                    check_validator_writable(&validator_store, &validator_pubkey)?;
                     validator_store
                         .initialized_validators()
                         .write()
@@ -853,17 +954,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         .and_then(
             |validator_pubkey: PublicKey, validator_store: Arc<ValidatorStore<T, E>>, signer| {
                 blocking_signed_json_task(signer, move || {
-                    if validator_store
-                        .initialized_validators()
-                        .read()
-                        .is_enabled(&validator_pubkey)
-                        .is_none()
-                    {
-                        return Err(warp_utils::reject::custom_not_found(format!(
-                            "no validator found with pubkey {:?}",
-                            validator_pubkey
-                        )));
-                    }
+                    check_validator_writable(&validator_store, &validator_pubkey)?;
                     validator_store
                         .initialized_validators()
                         .write()
@@ -901,10 +992,21 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                             validator_pubkey
                         )));
                     }
+                    let pubkey = PublicKeyBytes::from(&validator_pubkey);
+                    let source = if validator_store
+                        .initialized_validators()
+                        .read()
+                        .gas_limit(&pubkey)
+                        .is_some()
+                    {
+                        ProposalSettingSource::Override
+                    } else {
+                        ProposalSettingSource::Default
+                    };
                     Ok(GenericResponse::from(GetGasLimitResponse {
-                        pubkey: PublicKeyBytes::from(validator_pubkey.clone()),
-                        gas_limit: validator_store
-                            .get_gas_limit(&PublicKeyBytes::from(&validator_pubkey)),
+                        pubkey,
+                        gas_limit: validator_store.get_gas_limit(&pubkey),
+                        source: Some(source),
                     }))
                 })
             },
@@ -925,17 +1027,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
              validator_store: Arc<ValidatorStore<T, E>>,
              signer| {
                 blocking_signed_json_task(signer, move || {
-                    if validator_store
-                        .initialized_validators()
-                        .read()
-                        .is_enabled(&validator_pubkey)
-                        .is_none()
-                    {
-                        return Err(warp_utils::reject::custom_not_found(format!(
-                            "no validator found with pubkey {:?}",
-                            validator_pubkey
-                        )));
-                    }
+                    check_validator_writable(&validator_store, &validator_pubkey)?;
                     validator_store
                         .initialized_validators()
                         .write()
@@ -962,17 +1054,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         .and_then(
             |validator_pubkey: PublicKey, validator_store: Arc<ValidatorStore<T, E>>, signer| {
                 blocking_signed_json_task(signer, move || {
-                    if validator_store
-                        .initialized_validators()
-                        .read()
-                        .is_enabled(&validator_pubkey)
-                        .is_none()
-                    {
-                        return Err(warp_utils::reject::custom_not_found(format!(
-                            "no validator found with pubkey {:?}",
-                            validator_pubkey
-                        )));
-                    }
+                    check_validator_writable(&validator_store, &validator_pubkey)?;
                     validator_store
                         .initialized_validators()
                         .write()
@@ -1014,6 +1096,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                             handle.block_on(create_signed_voluntary_exit(
                                 pubkey,
                                 query.epoch,
+                                query.genesis_validators_root,
                                 validator_store,
                                 slot_clock,
                                 log,
@@ -1171,7 +1254,10 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(get_lighthouse_spec)
                         .or(get_lighthouse_validators)
                         .or(get_lighthouse_validators_pubkey)
+                        .or(get_lighthouse_doppelganger)
                         .or(get_lighthouse_ui_health)
+                        .or(get_lighthouse_ui_fallback_health)
+                        .or(get_lighthouse_ui_latency)
                         .or(get_lighthouse_ui_graffiti)
                         .or(get_fee_recipient)
                         .or(get_gas_limit)
@@ -1223,6 +1309,29 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
     Ok((listening_socket, server))
 }
 
+/// Returns an error unless `validator_pubkey` is known to the validator store and enabled, such
+/// that its per-validator settings may be modified via the API.
+fn check_validator_writable<T: SlotClock + 'static, E: EthSpec>(
+    validator_store: &ValidatorStore<T, E>,
+    validator_pubkey: &PublicKey,
+) -> Result<(), warp::Rejection> {
+    match validator_store
+        .initialized_validators()
+        .read()
+        .is_enabled(validator_pubkey)
+    {
+        None => Err(warp_utils::reject::custom_not_found(format!(
+            "no validator found with pubkey {:?}",
+            validator_pubkey
+        ))),
+        Some(false) => Err(warp_utils::reject::custom_forbidden(format!(
+            "validator {:?} is disabled",
+            validator_pubkey
+        ))),
+        Some(true) => Ok(()),
+    }
+}
+
 /// Executes `func` in blocking tokio task (i.e., where long-running tasks are permitted).
 /// JSON-encodes the return value of `func`, using the `signer` function to produce a signature of
 /// those bytes.
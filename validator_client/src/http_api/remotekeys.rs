@@ -102,10 +102,11 @@ fn import_single_remotekey<T: SlotClock + 'static, E: EthSpec>(
         .decompress()
         .map_err(|_| format!("invalid pubkey: {}", pubkey))?;
 
-    if let Some(def) = validator_store
-        .initialized_validators()
-        .read()
-        .validator_definitions()
+    let initialized_validators_rwlock = validator_store.initialized_validators();
+    let initialized_validators = initialized_validators_rwlock.read();
+    let definitions = initialized_validators.validator_definitions();
+
+    if let Some(def) = definitions
         .iter()
         .find(|def| def.voting_public_key == pubkey)
     {
@@ -116,8 +117,26 @@ fn import_single_remotekey<T: SlotClock + 'static, E: EthSpec>(
         }
     }
 
+    // The remotekey API provides less configuration options than the web3signer API, so reuse the
+    // TLS and timeout settings of any existing web3signer validator with the same URL.
+    let web3signer_definition = definitions
+        .iter()
+        .find_map(|def| match &def.signing_definition {
+            SigningDefinition::Web3Signer(web3signer) if web3signer.url == url => {
+                Some(web3signer.clone())
+            }
+            _ => None,
+        })
+        .unwrap_or(Web3SignerDefinition {
+            url,
+            root_certificate_path: None,
+            request_timeout_ms: None,
+            client_identity_path: None,
+            client_identity_password: None,
+        });
+    drop(initialized_validators);
+
     // Remotekeys are stored as web3signers.
-    // The remotekey API provides less confgiuration option than the web3signer API.
     let web3signer_validator = ValidatorDefinition {
         enabled: true,
         voting_public_key: pubkey,
@@ -126,13 +145,7 @@ fn import_single_remotekey<T: SlotClock + 'static, E: EthSpec>(
         gas_limit: None,
         builder_proposals: None,
         description: String::from("Added by remotekey API"),
-        signing_definition: SigningDefinition::Web3Signer(Web3SignerDefinition {
-            url,
-            root_certificate_path: None,
-            request_timeout_ms: None,
-            client_identity_path: None,
-            client_identity_password: None,
-        }),
+        signing_definition: SigningDefinition::Web3Signer(web3signer_definition),
     };
     handle
         .block_on(validator_store.add_validator(web3signer_validator))
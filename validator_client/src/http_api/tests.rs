@@ -4,6 +4,7 @@
 mod keystores;
 
 use crate::doppelganger_service::DoppelgangerService;
+use crate::fee_recipient_file::FeeRecipientFile;
 use crate::{
     http_api::{ApiSecret, Config as HttpConfig, Context},
     initialized_validators::InitializedValidators,
@@ -37,6 +38,7 @@ use types::graffiti::GraffitiString;
 
 const PASSWORD_BYTES: &[u8] = &[42, 50, 37];
 pub const TEST_DEFAULT_FEE_RECIPIENT: Address = Address::repeat_byte(42);
+const TEST_GENESIS_VALIDATORS_ROOT: Hash256 = Hash256::repeat_byte(42);
 
 type E = MainnetEthSpec;
 
@@ -46,12 +48,16 @@ struct ApiTester {
     validator_store: Arc<ValidatorStore<TestingSlotClock, E>>,
     url: SensitiveUrl,
     slot_clock: TestingSlotClock,
-    _validator_dir: TempDir,
+    validator_dir: TempDir,
     _test_runtime: TestRuntime,
 }
 
 impl ApiTester {
     pub async fn new() -> Self {
+        Self::new_with_fee_recipient_file(None).await
+    }
+
+    pub async fn new_with_fee_recipient_file(fee_recipient_file: Option<FeeRecipientFile>) -> Self {
         let log = test_logger();
 
         let validator_dir = tempdir().unwrap();
@@ -62,6 +68,7 @@ impl ApiTester {
         let initialized_validators = InitializedValidators::from_definitions(
             validator_defs,
             validator_dir.path().into(),
+            Default::default(),
             log.clone(),
         )
         .await
@@ -74,6 +81,7 @@ impl ApiTester {
         config.validator_dir = validator_dir.path().into();
         config.secrets_dir = secrets_dir.path().into();
         config.fee_recipient = Some(TEST_DEFAULT_FEE_RECIPIENT);
+        config.fee_recipient_file = fee_recipient_file;
 
         let spec = E::default_spec();
 
@@ -92,7 +100,7 @@ impl ApiTester {
         let validator_store = Arc::new(ValidatorStore::<_, E>::new(
             initialized_validators,
             slashing_protection,
-            Hash256::repeat_byte(42),
+            TEST_GENESIS_VALIDATORS_ROOT,
             spec.clone(),
             Some(Arc::new(DoppelgangerService::new(log.clone()))),
             slot_clock.clone(),
@@ -113,6 +121,7 @@ impl ApiTester {
             validator_dir: Some(validator_dir.path().into()),
             secrets_dir: Some(secrets_dir.path().into()),
             validator_store: Some(validator_store.clone()),
+            beacon_nodes: None,
             graffiti_file: None,
             graffiti_flag: Some(Graffiti::default()),
             spec: E::default_spec(),
@@ -150,7 +159,7 @@ impl ApiTester {
             validator_store,
             url,
             slot_clock,
-            _validator_dir: validator_dir,
+            validator_dir,
             _test_runtime: test_runtime,
         }
     }
@@ -259,6 +268,24 @@ impl ApiTester {
         self
     }
 
+    pub async fn assert_doppelganger_statuses(
+        self,
+        count: usize,
+        status: DoppelgangerState,
+    ) -> Self {
+        let statuses = self
+            .client
+            .get_lighthouse_doppelganger()
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(statuses.len(), count);
+        assert!(statuses.iter().all(|data| data.status == status));
+
+        self
+    }
+
     pub async fn create_hd_validators(self, s: HdValidatorScenario) -> Self {
         let initial_vals = self.vals_total();
         let initial_enabled_vals = self.vals_enabled();
@@ -498,7 +525,7 @@ impl ApiTester {
 
         let resp = self
             .client
-            .post_validator_voluntary_exit(&validator.voting_pubkey, maybe_epoch)
+            .post_validator_voluntary_exit(&validator.voting_pubkey, maybe_epoch, None)
             .await;
 
         assert!(resp.is_ok());
@@ -507,6 +534,56 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_sign_voluntary_exit_with_genesis_validators_root(
+        self,
+        index: usize,
+        genesis_validators_root: Hash256,
+    ) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        self.initialized_validators
+            .write()
+            .set_index(&validator.voting_pubkey, 0);
+
+        let signed_exit = self
+            .client
+            .post_validator_voluntary_exit(
+                &validator.voting_pubkey,
+                None,
+                Some(genesis_validators_root),
+            )
+            .await
+            .unwrap()
+            .data;
+
+        // The signature must be valid for the overridden genesis validators root.
+        let spec = E::default_spec();
+        let epoch = signed_exit.message.epoch;
+        let domain = spec.get_domain(
+            epoch,
+            Domain::VoluntaryExit,
+            &spec.fork_at_epoch(epoch),
+            genesis_validators_root,
+        );
+        let pubkey = validator.voting_pubkey.decompress().unwrap();
+        assert!(signed_exit
+            .signature
+            .verify(&pubkey, signed_exit.message.signing_root(domain)));
+
+        // The signature must not be valid for the validator store's own genesis validators root.
+        assert_ne!(genesis_validators_root, TEST_GENESIS_VALIDATORS_ROOT);
+        let default_domain = spec.get_domain(
+            epoch,
+            Domain::VoluntaryExit,
+            &spec.fork_at_epoch(epoch),
+            TEST_GENESIS_VALIDATORS_ROOT,
+        );
+        assert!(!signed_exit
+            .signature
+            .verify(&pubkey, signed_exit.message.signing_root(default_domain)));
+
+        self
+    }
+
     fn get_current_epoch(&self) -> Epoch {
         self.slot_clock
             .now()
@@ -682,6 +759,8 @@ async fn routes_with_invalid_auth() {
         .await
         .test_with_invalid_auth(|client| async move { client.get_lighthouse_validators().await })
         .await
+        .test_with_invalid_auth(|client| async move { client.get_lighthouse_doppelganger().await })
+        .await
         .test_with_invalid_auth(|client| async move {
             client
                 .get_lighthouse_validators_pubkey(&PublicKeyBytes::empty())
@@ -821,6 +900,24 @@ async fn hd_validator_creation() {
         .assert_validators_count(3);
 }
 
+#[tokio::test]
+async fn doppelganger_statuses() {
+    ApiTester::new()
+        .await
+        .assert_doppelganger_statuses(0, DoppelgangerState::SigningEnabled)
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 2,
+            specify_mnemonic: true,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        // Validators added during the genesis epoch are not subject to doppelganger protection.
+        .assert_doppelganger_statuses(2, DoppelgangerState::SigningEnabled)
+        .await;
+}
+
 #[tokio::test]
 async fn validator_exit() {
     ApiTester::new()
@@ -837,6 +934,8 @@ async fn validator_exit() {
         .test_sign_voluntary_exits(0, None)
         .await
         .test_sign_voluntary_exits(0, Some(Epoch::new(256)))
+        .await
+        .test_sign_voluntary_exit_with_genesis_validators_root(0, Hash256::repeat_byte(0xaa))
         .await;
 }
 
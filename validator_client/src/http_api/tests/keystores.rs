@@ -1,6 +1,6 @@
 use super::super::super::validator_store::DEFAULT_GAS_LIMIT;
 use super::*;
-use account_utils::random_password_string;
+use account_utils::{random_password_string, validator_definitions::CONFIG_FILENAME};
 use bls::PublicKeyBytes;
 use eth2::lighthouse_vc::types::UpdateFeeRecipientRequest;
 use eth2::lighthouse_vc::{
@@ -556,6 +556,51 @@ async fn import_keystores_wrong_password() {
     .await;
 }
 
+#[tokio::test]
+async fn import_keystore_cleanup_after_failed_initialization() {
+    run_test(|tester| async move {
+        let password = random_password_string();
+        let keystore = new_keystore(password.clone());
+        let import_req = ImportKeystoresRequest {
+            keystores: vec![keystore.clone()],
+            passwords: vec![password],
+            slashing_protection: None,
+        };
+
+        let validator_dir = tester.validator_dir.path();
+        let dir_entries = || {
+            std::fs::read_dir(validator_dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .sorted()
+                .collect::<Vec<_>>()
+        };
+        let entries_before = dir_entries();
+
+        // Replace the definitions file with a directory so that it can't be saved.
+        let definitions_path = validator_dir.join(CONFIG_FILENAME);
+        std::fs::remove_file(&definitions_path).unwrap();
+        std::fs::create_dir(&definitions_path).unwrap();
+
+        let import_res = tester.client.post_keystores(&import_req).await.unwrap();
+        check_keystore_import_response(&import_res, all_import_error(1));
+
+        // The keystore written during the import should have been removed again, and the key
+        // should not be active.
+        assert_eq!(dir_entries(), entries_before);
+        let get_res = tester.client.get_keystores().await.unwrap();
+        check_keystore_get_response(&get_res, &[]);
+
+        // Once the definitions file can be written the import can be retried.
+        std::fs::remove_dir(&definitions_path).unwrap();
+        let import_res = tester.client.post_keystores(&import_req).await.unwrap();
+        check_keystore_import_response(&import_res, all_imported(1));
+        let get_res = tester.client.get_keystores().await.unwrap();
+        check_keystore_get_response(&get_res, &[keystore]);
+    })
+    .await;
+}
+
 #[tokio::test]
 async fn import_invalid_slashing_protection() {
     run_test(|tester| async move {
@@ -594,6 +639,98 @@ async fn import_invalid_slashing_protection() {
     .await;
 }
 
+#[tokio::test]
+async fn fee_recipient_source_from_file() {
+    let password = random_password_string();
+    let keystores = (0..2)
+        .map(|_| new_keystore(password.clone()))
+        .collect::<Vec<_>>();
+    let all_pubkeys = keystores.iter().map(keystore_pubkey).collect::<Vec<_>>();
+
+    // Only the first validator has an entry in the file, and the file has no default.
+    let file_fee_recipient = Address::repeat_byte(0x11);
+    let file_dir = tempdir().unwrap();
+    let file_path = file_dir.path().join("fee_recipients.txt");
+    std::fs::write(
+        &file_path,
+        format!("{:?}: {:?}\n", all_pubkeys[0], file_fee_recipient),
+    )
+    .unwrap();
+    let mut fee_recipient_file = FeeRecipientFile::new(file_path);
+    fee_recipient_file.read_fee_recipient_file().unwrap();
+
+    let tester = ApiTester::new_with_fee_recipient_file(Some(fee_recipient_file)).await;
+    let import_res = tester
+        .client
+        .post_keystores(&ImportKeystoresRequest {
+            keystores: keystores.clone(),
+            passwords: vec![password.clone(); keystores.len()],
+            slashing_protection: None,
+        })
+        .await
+        .unwrap();
+    check_keystore_import_response(&import_res, all_imported(keystores.len()));
+
+    let check_fee_recipient = |pubkey: PublicKeyBytes, ethaddress, source| {
+        let client = &tester.client;
+        async move {
+            assert_eq!(
+                client.get_fee_recipient(&pubkey).await.unwrap(),
+                GetFeeRecipientResponse {
+                    pubkey,
+                    ethaddress,
+                    source: Some(source),
+                }
+            );
+        }
+    };
+
+    check_fee_recipient(
+        all_pubkeys[0],
+        file_fee_recipient,
+        ProposalSettingSource::File,
+    )
+    .await;
+    check_fee_recipient(
+        all_pubkeys[1],
+        TEST_DEFAULT_FEE_RECIPIENT,
+        ProposalSettingSource::Default,
+    )
+    .await;
+
+    // An override set via the API takes precedence over the file.
+    let override_fee_recipient = Address::repeat_byte(0x22);
+    tester
+        .client
+        .post_fee_recipient(
+            &all_pubkeys[0],
+            &UpdateFeeRecipientRequest {
+                ethaddress: override_fee_recipient,
+            },
+        )
+        .await
+        .unwrap();
+    check_fee_recipient(
+        all_pubkeys[0],
+        override_fee_recipient,
+        ProposalSettingSource::Override,
+    )
+    .await;
+
+    // Removing the override falls back to the file again.
+    tester
+        .client
+        .delete_fee_recipient(&all_pubkeys[0])
+        .await
+        .unwrap();
+    check_fee_recipient(
+        all_pubkeys[0],
+        file_fee_recipient,
+        ProposalSettingSource::File,
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn check_get_set_fee_recipient() {
     run_test(|tester: ApiTester| async move {
@@ -633,6 +770,7 @@ async fn check_get_set_fee_recipient() {
                 GetFeeRecipientResponse {
                     pubkey: pubkey.clone(),
                     ethaddress: TEST_DEFAULT_FEE_RECIPIENT,
+                    source: Some(ProposalSettingSource::Default),
                 }
             );
         }
@@ -673,6 +811,11 @@ async fn check_get_set_fee_recipient() {
                 GetFeeRecipientResponse {
                     pubkey: pubkey.clone(),
                     ethaddress: expected,
+                    source: Some(if expected == TEST_DEFAULT_FEE_RECIPIENT {
+                        ProposalSettingSource::Default
+                    } else {
+                        ProposalSettingSource::Override
+                    }),
                 }
             );
         }
@@ -707,6 +850,11 @@ async fn check_get_set_fee_recipient() {
                 GetFeeRecipientResponse {
                     pubkey: pubkey.clone(),
                     ethaddress: expected,
+                    source: Some(if expected == TEST_DEFAULT_FEE_RECIPIENT {
+                        ProposalSettingSource::Default
+                    } else {
+                        ProposalSettingSource::Override
+                    }),
                 }
             );
         }
@@ -740,6 +888,11 @@ async fn check_get_set_fee_recipient() {
                 GetFeeRecipientResponse {
                     pubkey: pubkey.clone(),
                     ethaddress: expected,
+                    source: Some(if expected == TEST_DEFAULT_FEE_RECIPIENT {
+                        ProposalSettingSource::Default
+                    } else {
+                        ProposalSettingSource::Override
+                    }),
                 }
             );
         }
@@ -767,6 +920,11 @@ async fn check_get_set_fee_recipient() {
                 GetFeeRecipientResponse {
                     pubkey: pubkey.clone(),
                     ethaddress: expected,
+                    source: Some(if expected == TEST_DEFAULT_FEE_RECIPIENT {
+                        ProposalSettingSource::Default
+                    } else {
+                        ProposalSettingSource::Override
+                    }),
                 }
             );
         }
@@ -813,6 +971,7 @@ async fn check_get_set_gas_limit() {
                 GetGasLimitResponse {
                     pubkey: pubkey.clone(),
                     gas_limit: DEFAULT_GAS_LIMIT,
+                    source: Some(ProposalSettingSource::Default),
                 }
             );
         }
@@ -849,6 +1008,11 @@ async fn check_get_set_gas_limit() {
                 GetGasLimitResponse {
                     pubkey: pubkey.clone(),
                     gas_limit: expected,
+                    source: Some(if expected == DEFAULT_GAS_LIMIT {
+                        ProposalSettingSource::Default
+                    } else {
+                        ProposalSettingSource::Override
+                    }),
                 }
             );
         }
@@ -883,6 +1047,11 @@ async fn check_get_set_gas_limit() {
                 GetGasLimitResponse {
                     pubkey: pubkey.clone(),
                     gas_limit: expected,
+                    source: Some(if expected == DEFAULT_GAS_LIMIT {
+                        ProposalSettingSource::Default
+                    } else {
+                        ProposalSettingSource::Override
+                    }),
                 }
             );
         }
@@ -916,6 +1085,11 @@ async fn check_get_set_gas_limit() {
                 GetGasLimitResponse {
                     pubkey: pubkey.clone(),
                     gas_limit: expected,
+                    source: Some(if expected == DEFAULT_GAS_LIMIT {
+                        ProposalSettingSource::Default
+                    } else {
+                        ProposalSettingSource::Override
+                    }),
                 }
             );
         }
@@ -943,6 +1117,11 @@ async fn check_get_set_gas_limit() {
                 GetGasLimitResponse {
                     pubkey: pubkey.clone(),
                     gas_limit: expected,
+                    source: Some(if expected == DEFAULT_GAS_LIMIT {
+                        ProposalSettingSource::Default
+                    } else {
+                        ProposalSettingSource::Override
+                    }),
                 }
             );
         }
@@ -950,6 +1129,60 @@ async fn check_get_set_gas_limit() {
     .await
 }
 
+#[tokio::test]
+async fn set_proposal_settings_disabled_validator_forbidden() {
+    run_test(|tester: ApiTester| async move {
+        let _ = &tester;
+        let password = random_password_string();
+        let keystore = new_keystore(password.clone());
+        let pubkey = keystore_pubkey(&keystore);
+
+        let import_res = tester
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: vec![keystore],
+                passwords: vec![password],
+                slashing_protection: None,
+            })
+            .await
+            .unwrap();
+        check_keystore_import_response(&import_res, all_imported(1));
+
+        tester
+            .client
+            .patch_lighthouse_validators(&pubkey, Some(false), None, None, None)
+            .await
+            .unwrap();
+
+        // Writes to a disabled validator are rejected.
+        let err = tester
+            .client
+            .post_fee_recipient(
+                &pubkey,
+                &UpdateFeeRecipientRequest {
+                    ethaddress: Address::repeat_byte(42),
+                },
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 403);
+        let err = tester
+            .client
+            .post_gas_limit(&pubkey, &UpdateGasLimitRequest { gas_limit: 42 })
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 403);
+        let err = tester.client.delete_gas_limit(&pubkey).await.unwrap_err();
+        assert_eq!(err.status().unwrap(), 403);
+
+        // Reads still report the default values.
+        let get_res = tester.client.get_fee_recipient(&pubkey).await.unwrap();
+        assert_eq!(get_res.ethaddress, TEST_DEFAULT_FEE_RECIPIENT);
+        assert_eq!(get_res.source, Some(ProposalSettingSource::Default));
+    })
+    .await;
+}
+
 fn all_indices(count: usize) -> Vec<usize> {
     (0..count).collect()
 }
@@ -2068,6 +2301,50 @@ async fn import_remotekey_web3signer() {
     .await
 }
 
+#[tokio::test]
+async fn import_remotekey_reuses_web3signer_settings() {
+    run_test(|tester| async move {
+        // Import a web3signer validator with a custom request timeout.
+        let mut web3signer_req = new_web3signer_validator().1;
+        web3signer_req.request_timeout_ms = Some(12_345);
+        tester
+            .client
+            .post_lighthouse_validators_web3signer(&vec![web3signer_req.clone()])
+            .await
+            .unwrap();
+
+        // Import a remotekey with the same URL.
+        let (kp, remotekey_req) = new_remotekey_validator();
+        let import_res = tester
+            .client
+            .post_remotekeys(&ImportRemotekeysRequest {
+                remote_keys: vec![remotekey_req],
+            })
+            .await
+            .unwrap();
+        check_remotekey_import_response(
+            &import_res,
+            all_with_status(1, ImportRemotekeyStatus::Imported),
+        );
+
+        // The remotekey should use the settings of the existing web3signer validator.
+        let vals = tester.initialized_validators.read();
+        let def = vals
+            .validator_definitions()
+            .iter()
+            .find(|def| def.voting_public_key == kp.pk)
+            .unwrap();
+        match &def.signing_definition {
+            account_utils::validator_definitions::SigningDefinition::Web3Signer(web3signer) => {
+                assert_eq!(web3signer.url, web3signer_req.url);
+                assert_eq!(web3signer.request_timeout_ms, Some(12_345));
+            }
+            _ => panic!("remotekey should be a web3signer validator"),
+        }
+    })
+    .await
+}
+
 #[tokio::test]
 async fn import_remotekey_web3signer_disabled() {
     run_test(|tester| async move {
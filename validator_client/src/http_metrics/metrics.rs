@@ -20,6 +20,10 @@ pub const ATTESTATIONS_HTTP_POST: &str = "attestations_http_post";
 pub const AGGREGATES: &str = "aggregates";
 pub const AGGREGATES_HTTP_GET: &str = "aggregates_http_get";
 pub const AGGREGATES_HTTP_POST: &str = "aggregates_http_post";
+pub const ATTESTATIONS_SIGN: &str = "attestations_sign";
+pub const AGGREGATES_SIGN: &str = "aggregates_sign";
+pub const SYNC_COMMITTEE_SIGNATURES_SIGN: &str = "sync_committee_signatures_sign";
+pub const SYNC_COMMITTEE_CONTRIBUTIONS_SIGN: &str = "sync_committee_contributions_sign";
 pub const CURRENT_EPOCH: &str = "current_epoch";
 pub const NEXT_EPOCH: &str = "next_epoch";
 pub const UPDATE_INDICES: &str = "update_indices";
@@ -109,6 +113,11 @@ lazy_static::lazy_static! {
         "Duration to perform attestation service tasks",
         &["task"]
     );
+    pub static ref SYNC_COMMITTEE_SERVICE_TIMES: Result<HistogramVec> = try_create_histogram_vec(
+        "vc_sync_committee_service_task_times_seconds",
+        "Duration to perform sync committee service tasks",
+        &["task"]
+    );
     pub static ref SLASHING_PROTECTION_PRUNE_TIMES: Result<Histogram> = try_create_histogram(
         "vc_slashing_protection_prune_times_seconds",
         "Time required to prune the slashing protection DB",
@@ -145,6 +154,11 @@ lazy_static::lazy_static! {
         "The number of beacon node requests for each endpoint",
         &["endpoint"]
     );
+    pub static ref BEACON_NODE_BROADCAST_OUTCOMES: Result<IntCounterVec> = try_create_int_counter_vec(
+        "bn_broadcast_outcomes_total",
+        "The number of successful and failed broadcasts to each beacon node",
+        &["endpoint", "outcome"]
+    );
 
     /*
     * Beacon node availability metrics
@@ -179,6 +193,11 @@ lazy_static::lazy_static! {
         "Duration to obtain a signature",
         &["type"]
     );
+    pub static ref WEB3SIGNER_FAILURES_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_web3signer_failures_total",
+        "Total count of failed Web3Signer signing requests, by message type",
+        &["message_type"]
+    );
     pub static ref BLOCK_SIGNING_TIMES: Result<Histogram> = try_create_histogram(
         "vc_block_signing_times_seconds",
         "Duration to obtain a signature for a block",
@@ -16,14 +16,17 @@ use account_utils::{
     ZeroizeString,
 };
 use eth2_keystore::Keystore;
+use futures::stream::{self, StreamExt};
 use lighthouse_metrics::set_gauge;
 use lockfile::{Lockfile, LockfileError};
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
 use reqwest::{Certificate, Client, Error as ReqwestError, Identity};
+use serde_derive::{Deserialize, Serialize};
 use slog::{debug, error, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{self, Read};
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
@@ -448,6 +451,28 @@ fn unlock_keystore_via_stdin_password(
     }
 }
 
+/// Client authentication settings applied to Web3Signer validators whose definitions do not
+/// specify their own.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Web3SignerDefaults {
+    /// Path to a PKCS12 file.
+    pub client_identity_path: Option<PathBuf>,
+    /// Password for the PKCS12 file.
+    pub client_identity_password: Option<String>,
+}
+
+impl Web3SignerDefaults {
+    /// Fill in the client identity of `web3_signer` if it specifies neither a path nor a password.
+    fn apply_to(&self, web3_signer: &mut Web3SignerDefinition) {
+        if web3_signer.client_identity_path.is_none()
+            && web3_signer.client_identity_password.is_none()
+        {
+            web3_signer.client_identity_path = self.client_identity_path.clone();
+            web3_signer.client_identity_password = self.client_identity_password.clone();
+        }
+    }
+}
+
 /// A set of `InitializedValidator` objects which is initialized from a list of
 /// `ValidatorDefinition`. The `ValidatorDefinition` file is maintained as `self` is modified.
 ///
@@ -461,6 +486,8 @@ pub struct InitializedValidators {
     validators: HashMap<PublicKeyBytes, InitializedValidator>,
     /// The clients used for communications with a remote signer.
     web3_signer_client_map: Option<HashMap<Web3SignerDefinition, Client>>,
+    /// Settings used for remote signers whose definitions do not specify them.
+    web3_signer_defaults: Web3SignerDefaults,
     /// For logging via `slog`.
     log: Logger,
 }
@@ -470,6 +497,7 @@ impl InitializedValidators {
     pub async fn from_definitions(
         definitions: ValidatorDefinitions,
         validators_dir: PathBuf,
+        web3_signer_defaults: Web3SignerDefaults,
         log: Logger,
     ) -> Result<Self, Error> {
         let mut this = Self {
@@ -477,6 +505,7 @@ impl InitializedValidators {
             definitions,
             validators: HashMap::default(),
             web3_signer_client_map: None,
+            web3_signer_defaults,
             log,
         };
         this.update_validators().await?;
@@ -538,13 +567,18 @@ impl InitializedValidators {
             return Err(Error::DuplicatePublicKey);
         }
 
+        let pubkey = def.voting_public_key.compress();
         self.definitions.push(def);
 
         self.update_validators().await?;
 
-        self.definitions
-            .save(&self.validators_dir)
-            .map_err(Error::UnableToSaveDefinitions)?;
+        if let Err(e) = self.definitions.save(&self.validators_dir) {
+            // Forget the validator so that `self` stays consistent with the definitions on disk.
+            self.definitions
+                .retain(|def| def.voting_public_key.compress() != pubkey);
+            self.validators.remove(&pubkey);
+            return Err(Error::UnableToSaveDefinitions(e));
+        }
 
         Ok(())
     }
@@ -965,6 +999,91 @@ impl InitializedValidators {
         Ok(())
     }
 
+    /// Decrypts the keystores of all enabled, not-yet-initialized local validators which are
+    /// missing from `key_cache`, adding their keypairs to `key_cache`.
+    ///
+    /// Decryption is spread across a pool of blocking threads, rather than performed serially as
+    /// each validator is initialized. Keystores without a password in their definition may need
+    /// to prompt on stdin, so they are skipped here and decrypted during initialization. Failures
+    /// are also left for initialization to report.
+    async fn decrypt_uncached_keystores(
+        &self,
+        key_cache: &mut KeyCache,
+        key_stores: &mut HashMap<PathBuf, Keystore>,
+    ) {
+        let mut to_decrypt = vec![];
+        for def in self.definitions.as_slice() {
+            if !def.enabled
+                || self
+                    .validators
+                    .contains_key(&def.voting_public_key.compress())
+            {
+                continue;
+            }
+
+            if let SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                voting_keystore_password,
+            } = &def.signing_definition
+            {
+                if voting_keystore_password_path.is_none() && voting_keystore_password.is_none() {
+                    continue;
+                }
+
+                let keystore = match key_stores.get(voting_keystore_path) {
+                    Some(keystore) => keystore.clone(),
+                    None => match open_keystore(voting_keystore_path) {
+                        Ok(keystore) => {
+                            key_stores.insert(voting_keystore_path.clone(), keystore.clone());
+                            keystore
+                        }
+                        Err(_) => continue,
+                    },
+                };
+
+                if key_cache.get(keystore.uuid()).is_none() {
+                    to_decrypt.push((
+                        keystore,
+                        voting_keystore_password_path.clone(),
+                        voting_keystore_password.clone(),
+                    ));
+                }
+            }
+        }
+
+        if to_decrypt.is_empty() {
+            return;
+        }
+
+        let num_workers = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        debug!(
+            self.log,
+            "Decrypting uncached keystores";
+            "count" => to_decrypt.len(),
+            "workers" => num_workers,
+        );
+
+        let decrypted = stream::iter(to_decrypt)
+            .map(|(keystore, password_path, password)| {
+                tokio::task::spawn_blocking(move || {
+                    let password = match password {
+                        Some(password) => password.as_ref().to_vec().into(),
+                        None => read_password(password_path?).ok()?,
+                    };
+                    let keypair = keystore.decrypt_keypair(password.as_bytes()).ok()?;
+                    Some((*keystore.uuid(), password, keypair))
+                })
+            })
+            .buffer_unordered(num_workers)
+            .collect::<Vec<_>>()
+            .await;
+
+        for (uuid, password, keypair) in decrypted.into_iter().flatten().flatten() {
+            key_cache.add(keypair, &uuid, password);
+        }
+    }
+
     /// Tries to decrypt the key cache.
     ///
     /// Returns the decrypted cache if decryption was successful, or an error if a required password
@@ -1100,6 +1219,11 @@ impl InitializedValidators {
             KeyCache::new()
         };
 
+        if has_local_definitions {
+            self.decrypt_uncached_keystores(&mut key_cache, &mut key_stores)
+                .await;
+        }
+
         let mut disabled_uuids = HashSet::new();
         for def in self.definitions.as_slice() {
             if def.enabled {
@@ -1168,6 +1292,12 @@ impl InitializedValidators {
                         }
                     }
                     SigningDefinition::Web3Signer(Web3SignerDefinition { .. }) => {
+                        let mut def = def.clone();
+                        if let SigningDefinition::Web3Signer(web3_signer) =
+                            &mut def.signing_definition
+                        {
+                            self.web3_signer_defaults.apply_to(web3_signer);
+                        }
                         match InitializedValidator::from_definition(
                             def.clone(),
                             &mut key_cache,
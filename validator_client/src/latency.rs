@@ -27,7 +27,9 @@ pub fn start_latency_service<T: SlotClock + 'static, E: EthSpec>(
                     // This is 11/12ths through the next slot. On mainnet this
                     // will happen in the 11th second of each slot, one second
                     // before the next slot.
-                    next_slot + (next_slot / SLOT_DELAY_DENOMINATOR) * SLOT_DELAY_MULTIPLIER
+                    next_slot
+                        + (slot_clock.slot_duration() / SLOT_DELAY_DENOMINATOR)
+                            * SLOT_DELAY_MULTIPLIER
                 })
                 // If we can't read the slot clock, just wait one slot. Running
                 // the measurement at a non-exact time is not a big issue.
@@ -1,10 +1,12 @@
 mod attestation_service;
 mod beacon_node_fallback;
+mod beacon_node_health;
 mod block_service;
 mod check_synced;
 mod cli;
 mod config;
 mod duties_service;
+mod fee_recipient_file;
 mod graffiti_file;
 mod http_metrics;
 mod key_cache;
@@ -12,6 +14,7 @@ mod latency;
 mod notifier;
 mod preparation_service;
 mod signing_method;
+mod signing_tasks;
 mod sync_committee_service;
 
 mod doppelganger_service;
@@ -19,6 +22,7 @@ pub mod http_api;
 pub mod initialized_validators;
 pub mod validator_store;
 
+pub use beacon_node_fallback::ApiTopic;
 pub use cli::cli_app;
 pub use config::Config;
 use initialized_validators::InitializedValidators;
@@ -191,6 +195,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         let validators = InitializedValidators::from_definitions(
             validator_defs,
             config.validator_dir.clone(),
+            config.web3_signer_defaults.clone(),
             log.clone(),
         )
         .await
@@ -369,14 +374,14 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
 
         let mut beacon_nodes: BeaconNodeFallback<_, T> = BeaconNodeFallback::new(
             candidates,
-            config.disable_run_on_all,
+            config.broadcast_topics.clone(),
             context.eth2_config.spec.clone(),
             log.clone(),
         );
 
         let mut proposer_nodes: BeaconNodeFallback<_, T> = BeaconNodeFallback::new(
             proposer_candidates,
-            config.disable_run_on_all,
+            config.broadcast_topics.clone(),
             context.eth2_config.spec.clone(),
             log.clone(),
         );
@@ -487,6 +492,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .validator_store(validator_store.clone())
             .beacon_nodes(beacon_nodes.clone())
             .runtime_context(context.service_context("attestation".into()))
+            .signing_concurrency(config.signing_concurrency)
             .build()?;
 
         let preparation_service = PreparationServiceBuilder::new()
@@ -504,6 +510,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             slot_clock.clone(),
             beacon_nodes.clone(),
             context.service_context("sync_committee".into()),
+            config.signing_concurrency,
         );
 
         Ok(Self {
@@ -538,6 +545,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
                 task_executor: self.context.executor.clone(),
                 api_secret,
                 validator_store: Some(self.validator_store.clone()),
+                beacon_nodes: Some(self.duties_service.beacon_nodes.clone()),
                 validator_dir: Some(self.config.validator_dir.clone()),
                 secrets_dir: Some(self.config.secrets_dir.clone()),
                 graffiti_file: self.config.graffiti_file.clone(),
@@ -605,6 +613,8 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             info!(log, "Doppelganger protection disabled.")
         }
 
+        self.validator_store.start_fee_recipient_file_service();
+
         spawn_notifier(self).map_err(|e| format!("Failed to start notifier: {}", e))?;
 
         if self.config.enable_latency_measurement_service {
@@ -1,4 +1,4 @@
-use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
+use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced};
 use crate::validator_store::{DoppelgangerStatus, Error as ValidatorStoreError, ValidatorStore};
 use crate::OfflineOnFailure;
 use bls::PublicKeyBytes;
@@ -23,6 +23,10 @@ const PROPOSER_PREPARATION_LOOKAHEAD_EPOCHS: u64 = 2;
 /// Number of epochs to wait before re-submitting validator registration.
 const EPOCHS_PER_VALIDATOR_REGISTRATION_SUBMISSION: u64 = 1;
 
+/// Number of seconds after which a validator registration is re-signed with a new timestamp, even
+/// if its contents have not changed.
+const VALIDATOR_REGISTRATION_REFRESH_SECONDS: u64 = 24 * 60 * 60;
+
 /// Builds an `PreparationService`.
 pub struct PreparationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
     validator_store: Option<Arc<ValidatorStore<T, E>>>,
@@ -342,9 +346,10 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
         let preparation_entries = preparation_data.as_slice();
         match self
             .beacon_nodes
-            .run(
+            .request(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::Subscriptions,
                 |beacon_node| async move {
                     beacon_node
                         .post_validator_prepare_beacon_proposer(preparation_entries)
@@ -408,22 +413,27 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
         let registration_data_len = registration_keys.len();
         let mut signed = Vec::with_capacity(registration_data_len);
 
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("{e:?}"))?
+            .as_secs();
+
         for key in registration_keys {
-            let cached_registration_opt =
-                self.validator_registration_cache.read().get(&key).cloned();
+            let cached_registration_opt = self
+                .validator_registration_cache
+                .read()
+                .get(&key)
+                .filter(|signed_data| {
+                    // Registrations with an overridden timestamp never need refreshing.
+                    self.builder_registration_timestamp_override.is_some()
+                        || !registration_needs_refresh(signed_data.message.timestamp, now)
+                })
+                .cloned();
 
             let signed_data = if let Some(signed_data) = cached_registration_opt {
                 signed_data
             } else {
-                let timestamp =
-                    if let Some(timestamp) = self.builder_registration_timestamp_override {
-                        timestamp
-                    } else {
-                        SystemTime::now()
-                            .duration_since(UNIX_EPOCH)
-                            .map_err(|e| format!("{e:?}"))?
-                            .as_secs()
-                    };
+                let timestamp = self.builder_registration_timestamp_override.unwrap_or(now);
 
                 let ValidatorRegistrationKey {
                     fee_recipient,
@@ -509,3 +519,29 @@ pub struct ProposalData {
     pub(crate) gas_limit: u64,
     pub(crate) builder_proposals: bool,
 }
+
+/// Returns `true` if a registration signed at `timestamp` should be re-signed at `now`.
+fn registration_needs_refresh(timestamp: u64, now: u64) -> bool {
+    now.saturating_sub(timestamp) >= VALIDATOR_REGISTRATION_REFRESH_SECONDS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registration_refresh() {
+        let timestamp = 1_700_000_000;
+        assert!(!registration_needs_refresh(timestamp, timestamp));
+        assert!(!registration_needs_refresh(
+            timestamp,
+            timestamp + VALIDATOR_REGISTRATION_REFRESH_SECONDS - 1
+        ));
+        assert!(registration_needs_refresh(
+            timestamp,
+            timestamp + VALIDATOR_REGISTRATION_REFRESH_SECONDS
+        ));
+        // A clock which has gone backwards does not trigger a refresh.
+        assert!(!registration_needs_refresh(timestamp, timestamp - 1));
+    }
+}
@@ -26,6 +26,10 @@ pub enum Error {
         domain: Domain,
     },
     Web3SignerRequestFailed(String),
+    /// The Web3Signer server presented a certificate which is not trusted by the VC.
+    Web3SignerServerCertificateUntrusted(String),
+    /// The Web3Signer server rejected the client certificate presented by the VC.
+    Web3SignerClientCertificateRejected(String),
     Web3SignerJsonParsingFailed(String),
     ShuttingDown,
     TokioJoin(String),
@@ -227,20 +231,174 @@ impl SigningMethod {
                 };
 
                 // Request a signature from the Web3Signer instance via HTTP(S).
-                let response: SigningResponse = http_client
-                    .post(signing_url.clone())
-                    .json(&request)
-                    .send()
-                    .await
-                    .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
-                    .error_for_status()
-                    .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
-                    .json()
-                    .await
-                    .map_err(|e| Error::Web3SignerJsonParsingFailed(e.to_string()))?;
+                //
+                // Errors are tagged with the message type so that it's clear which duties a given
+                // Web3Signer version is unable to sign.
+                let result = async {
+                    http_client
+                        .post(signing_url.clone())
+                        .json(&request)
+                        .send()
+                        .await
+                        .map_err(|e| {
+                            web3signer_request_error(
+                                &e,
+                                format!("{:?}: {}", message_type, error_chain(&e)),
+                            )
+                        })?
+                        .error_for_status()
+                        .map_err(|e| {
+                            Error::Web3SignerRequestFailed(format!("{:?}: {}", message_type, e))
+                        })?
+                        .json::<SigningResponse>()
+                        .await
+                        .map_err(|e| {
+                            Error::Web3SignerJsonParsingFailed(format!("{:?}: {}", message_type, e))
+                        })
+                }
+                .await;
+
+                if result.is_err() {
+                    metrics::inc_counter_vec(
+                        &metrics::WEB3SIGNER_FAILURES_TOTAL,
+                        &[&format!("{:?}", message_type)],
+                    );
+                }
 
-                Ok(response.signature)
+                result.map(|response| response.signature)
             }
         }
     }
 }
+
+/// Returns `e` and all of its sources as a single string.
+fn error_chain(e: &dyn std::error::Error) -> String {
+    let mut description = e.to_string();
+    let mut source = e.source();
+    while let Some(e) = source {
+        description.push_str(&format!(": {}", e));
+        source = e.source();
+    }
+    description
+}
+
+/// A TLS handshake failure caused by the certificate of either side of the connection.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(any(target_os = "windows", target_vendor = "apple"), allow(dead_code))]
+enum TlsCertificateFailure {
+    ServerCertificateUntrusted,
+    ClientCertificateRejected,
+}
+
+/// Maps a failed Web3Signer request to an `Error`, distinguishing an untrusted server certificate
+/// from a rejected client certificate.
+fn web3signer_request_error(e: &reqwest::Error, description: String) -> Error {
+    // TLS handshakes happen whilst connecting, so there's no need to inspect other errors.
+    let failure = if e.is_connect() {
+        tls_certificate_failure(e)
+    } else {
+        None
+    };
+
+    match failure {
+        Some(TlsCertificateFailure::ServerCertificateUntrusted) => {
+            Error::Web3SignerServerCertificateUntrusted(description)
+        }
+        Some(TlsCertificateFailure::ClientCertificateRejected) => {
+            Error::Web3SignerClientCertificateRejected(description)
+        }
+        None => Error::Web3SignerRequestFailed(description),
+    }
+}
+
+/// Searches the sources of `e` for the OpenSSL errors raised by `native-tls` during the TLS
+/// handshake.
+#[cfg(not(any(target_os = "windows", target_vendor = "apple")))]
+fn tls_certificate_failure(e: &reqwest::Error) -> Option<TlsCertificateFailure> {
+    let mut source = std::error::Error::source(e);
+    while let Some(e) = source {
+        if let Some(error_stack) = e.downcast_ref::<openssl::error::ErrorStack>() {
+            return openssl_certificate_failure(
+                error_stack.errors().iter().map(|e| e.reason_code()),
+            );
+        }
+        source = e.source();
+    }
+    None
+}
+
+/// `native-tls` does not use OpenSSL on this platform, so certificate failures are not
+/// distinguished from other connection errors.
+#[cfg(any(target_os = "windows", target_vendor = "apple"))]
+fn tls_certificate_failure(_: &reqwest::Error) -> Option<TlsCertificateFailure> {
+    None
+}
+
+/// The OpenSSL `SSL_R_CERTIFICATE_VERIFY_FAILED` reason code.
+#[cfg(not(any(target_os = "windows", target_vendor = "apple")))]
+const SSL_R_CERTIFICATE_VERIFY_FAILED: i32 = 134;
+
+/// OpenSSL reports a fatal alert sent by the peer with a reason code of `SSL_AD_REASON_OFFSET`
+/// plus the alert description.
+#[cfg(not(any(target_os = "windows", target_vendor = "apple")))]
+const SSL_AD_REASON_OFFSET: i32 = 1000;
+
+/// TLS alert descriptions sent by a server which indicate that it rejected the client certificate.
+///
+/// See https://www.rfc-editor.org/rfc/rfc8446#section-6.2
+#[cfg(not(any(target_os = "windows", target_vendor = "apple")))]
+const CLIENT_CERTIFICATE_ALERTS: &[i32] = &[
+    42,  // bad_certificate
+    43,  // unsupported_certificate
+    44,  // certificate_revoked
+    45,  // certificate_expired
+    46,  // certificate_unknown
+    48,  // unknown_ca
+    49,  // access_denied
+    116, // certificate_required
+];
+
+/// Classifies the reason codes of the errors in an OpenSSL error stack.
+#[cfg(not(any(target_os = "windows", target_vendor = "apple")))]
+fn openssl_certificate_failure(
+    reason_codes: impl IntoIterator<Item = i32>,
+) -> Option<TlsCertificateFailure> {
+    reason_codes.into_iter().find_map(|reason_code| {
+        if reason_code == SSL_R_CERTIFICATE_VERIFY_FAILED {
+            Some(TlsCertificateFailure::ServerCertificateUntrusted)
+        } else if CLIENT_CERTIFICATE_ALERTS.contains(&(reason_code - SSL_AD_REASON_OFFSET)) {
+            Some(TlsCertificateFailure::ClientCertificateRejected)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(all(test, not(any(target_os = "windows", target_vendor = "apple"))))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_openssl_certificate_failures() {
+        assert_eq!(
+            openssl_certificate_failure([SSL_R_CERTIFICATE_VERIFY_FAILED]),
+            Some(TlsCertificateFailure::ServerCertificateUntrusted)
+        );
+
+        // tlsv13 alert certificate required.
+        assert_eq!(
+            openssl_certificate_failure([1116]),
+            Some(TlsCertificateFailure::ClientCertificateRejected)
+        );
+
+        // sslv3 alert bad certificate, preceded by an unrelated error.
+        assert_eq!(
+            openssl_certificate_failure([197, 1042]),
+            Some(TlsCertificateFailure::ClientCertificateRejected)
+        );
+
+        // sslv3 alert handshake failure.
+        assert_eq!(openssl_certificate_failure([1040]), None);
+        assert_eq!(openssl_certificate_failure([]), None);
+    }
+}
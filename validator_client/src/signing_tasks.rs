@@ -0,0 +1,30 @@
+//! Spreads the signing of many messages for the same slot across the task executor.
+
+use futures::{future, stream, StreamExt};
+use std::future::Future;
+use task_executor::TaskExecutor;
+
+/// Spawns each of `tasks` on the `executor`, with at most `concurrency` running at once, and
+/// returns their outputs in the order in which they complete.
+///
+/// Each task is spawned separately so that signing with local keystores is spread across the
+/// runtime's worker threads, whilst requests to remote signers are pipelined over their shared
+/// HTTP clients. Tasks which are cancelled by shutdown produce no output.
+pub async fn spawn_bounded<I, F, R>(
+    executor: &TaskExecutor,
+    tasks: I,
+    concurrency: usize,
+    name: &'static str,
+) -> Vec<R>
+where
+    I: IntoIterator<Item = F>,
+    F: Future<Output = R> + Send + 'static,
+    R: Send + 'static,
+{
+    stream::iter(tasks)
+        .filter_map(|task| future::ready(executor.spawn_handle(task, name)))
+        .buffer_unordered(concurrency.max(1))
+        .filter_map(|result| future::ready(result.ok().flatten()))
+        .collect()
+        .await
+}
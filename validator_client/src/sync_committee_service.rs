@@ -1,12 +1,13 @@
-use crate::beacon_node_fallback::{BeaconNodeFallback, RequireSynced};
+use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequireSynced};
 use crate::{
     duties_service::DutiesService,
+    http_metrics::metrics,
+    signing_tasks::spawn_bounded,
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
     OfflineOnFailure,
 };
 use environment::RuntimeContext;
 use eth2::types::BlockId;
-use futures::future::join_all;
 use futures::future::FutureExt;
 use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
@@ -48,6 +49,8 @@ pub struct Inner<T: SlotClock + 'static, E: EthSpec> {
     slot_clock: T,
     beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     context: RuntimeContext<E>,
+    /// The maximum number of messages which may be signed concurrently for each slot or subnet.
+    signing_concurrency: usize,
     /// Boolean to track whether the service has posted subscriptions to the BN at least once.
     ///
     /// This acts as a latch that fires once upon start-up, and then never again.
@@ -61,6 +64,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         slot_clock: T,
         beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
         context: RuntimeContext<E>,
+        signing_concurrency: usize,
     ) -> Self {
         Self {
             inner: Arc::new(Inner {
@@ -69,6 +73,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 slot_clock,
                 beacon_nodes,
                 context,
+                signing_concurrency,
                 first_subscription_done: AtomicBool::new(false),
             }),
         }
@@ -256,54 +261,69 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         let log = self.context.log();
 
         // Create futures to produce sync committee signatures.
-        let signature_futures = validator_duties.iter().map(|duty| async move {
-            match self
-                .validator_store
-                .produce_sync_committee_signature(
-                    slot,
-                    beacon_block_root,
-                    duty.validator_index,
-                    &duty.pubkey,
-                )
-                .await
-            {
-                Ok(signature) => Some(signature),
-                Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
-                    // A pubkey can be missing when a validator was recently
-                    // removed via the API.
-                    debug!(
-                        log,
-                        "Missing pubkey for sync committee signature";
-                        "pubkey" => ?pubkey,
-                        "validator_index" => duty.validator_index,
-                        "slot" => slot,
-                    );
-                    None
-                }
-                Err(e) => {
-                    crit!(
-                        log,
-                        "Failed to sign sync committee signature";
-                        "validator_index" => duty.validator_index,
-                        "slot" => slot,
-                        "error" => ?e,
-                    );
-                    None
+        let signature_futures = validator_duties.into_iter().map(|duty| {
+            let service = self.clone();
+            let log = log.clone();
+            async move {
+                match service
+                    .validator_store
+                    .produce_sync_committee_signature(
+                        slot,
+                        beacon_block_root,
+                        duty.validator_index,
+                        &duty.pubkey,
+                    )
+                    .await
+                {
+                    Ok(signature) => Some(signature),
+                    Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
+                        // A pubkey can be missing when a validator was recently
+                        // removed via the API.
+                        debug!(
+                            log,
+                            "Missing pubkey for sync committee signature";
+                            "pubkey" => ?pubkey,
+                            "validator_index" => duty.validator_index,
+                            "slot" => slot,
+                        );
+                        None
+                    }
+                    Err(e) => {
+                        crit!(
+                            log,
+                            "Failed to sign sync committee signature";
+                            "validator_index" => duty.validator_index,
+                            "slot" => slot,
+                            "error" => ?e,
+                        );
+                        None
+                    }
                 }
             }
         });
 
         // Execute all the futures in parallel, collecting any successful results.
-        let committee_signatures = &join_all(signature_futures)
-            .await
-            .into_iter()
-            .flatten()
-            .collect::<Vec<_>>();
+        let signing_timer = metrics::start_timer_vec(
+            &metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+            &[metrics::SYNC_COMMITTEE_SIGNATURES_SIGN],
+        );
+        let committee_signatures = &spawn_bounded(
+            &self.context.executor,
+            signature_futures,
+            self.signing_concurrency,
+            "sync_committee_signing",
+        )
+        .await
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        drop(signing_timer);
 
         self.beacon_nodes
-            .first_success(
+            .request(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::SyncCommittee,
                 |beacon_node| async move {
                     beacon_node
                         .post_beacon_pool_sync_committee_signatures(committee_signatures)
@@ -409,54 +429,70 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
 
         // Create futures to produce signed contributions.
         let signature_futures = subnet_aggregators.into_iter().map(
-            |(aggregator_index, aggregator_pk, selection_proof)| async move {
-                match self
-                    .validator_store
-                    .produce_signed_contribution_and_proof(
-                        aggregator_index,
-                        aggregator_pk,
-                        contribution.clone(),
-                        selection_proof,
-                    )
-                    .await
-                {
-                    Ok(signed_contribution) => Some(signed_contribution),
-                    Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
-                        // A pubkey can be missing when a validator was recently
-                        // removed via the API.
-                        debug!(
-                            log,
-                            "Missing pubkey for sync contribution";
-                            "pubkey" => ?pubkey,
-                            "slot" => slot,
-                        );
-                        None
-                    }
-                    Err(e) => {
-                        crit!(
-                            log,
-                            "Unable to sign sync committee contribution";
-                            "slot" => slot,
-                            "error" => ?e,
-                        );
-                        None
+            |(aggregator_index, aggregator_pk, selection_proof)| {
+                let service = self.clone();
+                let contribution = contribution.clone();
+                let log = log.clone();
+                async move {
+                    match service
+                        .validator_store
+                        .produce_signed_contribution_and_proof(
+                            aggregator_index,
+                            aggregator_pk,
+                            contribution,
+                            selection_proof,
+                        )
+                        .await
+                    {
+                        Ok(signed_contribution) => Some(signed_contribution),
+                        Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
+                            // A pubkey can be missing when a validator was recently
+                            // removed via the API.
+                            debug!(
+                                log,
+                                "Missing pubkey for sync contribution";
+                                "pubkey" => ?pubkey,
+                                "slot" => slot,
+                            );
+                            None
+                        }
+                        Err(e) => {
+                            crit!(
+                                log,
+                                "Unable to sign sync committee contribution";
+                                "slot" => slot,
+                                "error" => ?e,
+                            );
+                            None
+                        }
                     }
                 }
             },
         );
 
         // Execute all the futures in parallel, collecting any successful results.
-        let signed_contributions = &join_all(signature_futures)
-            .await
-            .into_iter()
-            .flatten()
-            .collect::<Vec<_>>();
+        let signing_timer = metrics::start_timer_vec(
+            &metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+            &[metrics::SYNC_COMMITTEE_CONTRIBUTIONS_SIGN],
+        );
+        let signed_contributions = &spawn_bounded(
+            &self.context.executor,
+            signature_futures,
+            self.signing_concurrency,
+            "sync_contribution_signing",
+        )
+        .await
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+        drop(signing_timer);
 
         // Publish to the beacon node.
         self.beacon_nodes
-            .first_success(
+            .request(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::SyncCommittee,
                 |beacon_node| async move {
                     beacon_node
                         .post_validator_contribution_and_proofs(signed_contributions)
@@ -596,9 +632,10 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
 
         if let Err(e) = self
             .beacon_nodes
-            .run(
+            .request(
                 RequireSynced::No,
                 OfflineOnFailure::Yes,
+                ApiTopic::Subscriptions,
                 |beacon_node| async move {
                     beacon_node
                         .post_validator_sync_committee_subscriptions(subscriptions_slice)
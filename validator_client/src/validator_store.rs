@@ -1,5 +1,6 @@
 use crate::{
     doppelganger_service::DoppelgangerService,
+    fee_recipient_file::FeeRecipientFile,
     http_metrics::metrics,
     initialized_validators::InitializedValidators,
     signing_method::{Error as SigningError, SignableMessage, SigningContext, SigningMethod},
@@ -16,7 +17,9 @@ use std::iter::FromIterator;
 use std::marker::PhantomData;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 use task_executor::TaskExecutor;
+use tokio::time::sleep;
 use types::{
     attestation::Error as AttestationError, graffiti::GraffitiString, AbstractExecPayload, Address,
     AggregateAndProof, Attestation, BeaconBlock, BlindedPayload, ChainSpec, ContributionAndProof,
@@ -50,11 +53,6 @@ impl From<SigningError> for Error {
     }
 }
 
-/// Number of epochs of slashing protection history to keep.
-///
-/// This acts as a maximum safe-guard against clock drift.
-const SLASHING_PROTECTION_HISTORY_EPOCHS: u64 = 512;
-
 /// Currently used as the default gas limit in execution clients.
 ///
 /// https://github.com/ethereum/builder-specs/issues/17
@@ -89,12 +87,15 @@ pub struct ValidatorStore<T, E: EthSpec> {
     validators: Arc<RwLock<InitializedValidators>>,
     slashing_protection: SlashingDatabase,
     slashing_protection_last_prune: Arc<Mutex<Epoch>>,
+    /// Number of epochs of slashing protection history to keep when pruning.
+    slashing_protection_history_epochs: u64,
     genesis_validators_root: Hash256,
     spec: Arc<ChainSpec>,
     log: Logger,
     doppelganger_service: Option<Arc<DoppelgangerService>>,
     slot_clock: T,
     fee_recipient_process: Option<Address>,
+    fee_recipient_file: Option<RwLock<FeeRecipientFile>>,
     gas_limit: Option<u64>,
     builder_proposals: bool,
     task_executor: TaskExecutor,
@@ -120,12 +121,14 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             validators: Arc::new(RwLock::new(validators)),
             slashing_protection,
             slashing_protection_last_prune: Arc::new(Mutex::new(Epoch::new(0))),
+            slashing_protection_history_epochs: config.slashing_protection_history_epochs,
             genesis_validators_root,
             spec: Arc::new(spec),
             log,
             doppelganger_service,
             slot_clock,
             fee_recipient_process: config.fee_recipient,
+            fee_recipient_file: config.fee_recipient_file.clone().map(RwLock::new),
             gas_limit: config.gas_limit,
             builder_proposals: config.builder_proposals,
             task_executor,
@@ -235,7 +238,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             .map(|validator| ProposalData {
                 validator_index: validator.get_index(),
                 fee_recipient: self
-                    .get_fee_recipient_defaulting(validator.get_suggested_fee_recipient()),
+                    .get_fee_recipient_defaulting(pubkey, validator.get_suggested_fee_recipient()),
                 gas_limit: self.get_gas_limit_defaulting(validator.get_gas_limit()),
                 builder_proposals: self
                     .get_builder_proposals_defaulting(validator.get_builder_proposals()),
@@ -404,16 +407,94 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     /// Returns the fee recipient for the given public key. The priority order for fetching
     /// the fee recipient is:
     /// 1. validator_definitions.yml
-    /// 2. process level fee recipient
+    /// 2. fee recipient file
+    /// 3. process level fee recipient
     pub fn get_fee_recipient(&self, validator_pubkey: &PublicKeyBytes) -> Option<Address> {
         // If there is a `suggested_fee_recipient` in the validator definitions yaml
         // file, use that value.
-        self.get_fee_recipient_defaulting(self.suggested_fee_recipient(validator_pubkey))
+        self.get_fee_recipient_defaulting(
+            validator_pubkey,
+            self.suggested_fee_recipient(validator_pubkey),
+        )
+    }
+
+    pub fn get_fee_recipient_defaulting(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        fee_recipient: Option<Address>,
+    ) -> Option<Address> {
+        fee_recipient
+            // If there's nothing in the definitions, try the fee recipient file.
+            .or_else(|| self.fee_recipient_from_file(validator_pubkey))
+            // If there's nothing in either file, try the process-level default value.
+            .or(self.fee_recipient_process)
     }
 
-    pub fn get_fee_recipient_defaulting(&self, fee_recipient: Option<Address>) -> Option<Address> {
-        // If there's nothing in the file, try the process-level default value.
-        fee_recipient.or(self.fee_recipient_process)
+    /// Returns the fee recipient for `validator_pubkey` from the last successful read of the fee
+    /// recipient file. The file is kept up to date by `start_fee_recipient_file_service`.
+    pub fn fee_recipient_from_file(&self, validator_pubkey: &PublicKeyBytes) -> Option<Address> {
+        self.fee_recipient_file
+            .as_ref()?
+            .read()
+            .get_fee_recipient(validator_pubkey)
+    }
+
+    /// Starts a service which re-reads the fee recipient file once per slot if it has been
+    /// modified. Does nothing if no fee recipient file is configured.
+    pub fn start_fee_recipient_file_service(self: &Arc<Self>) {
+        if self.fee_recipient_file.is_none() {
+            return;
+        }
+
+        let validator_store = self.clone();
+        let slot_duration = Duration::from_secs(self.spec.seconds_per_slot);
+        self.task_executor.spawn(
+            async move {
+                let mut read_failed = false;
+                loop {
+                    sleep(slot_duration).await;
+                    read_failed = validator_store.reload_fee_recipient_file(read_failed);
+                }
+            },
+            "fee_recipient_file",
+        );
+    }
+
+    /// Re-reads the fee recipient file if it has been modified, returning `true` if the read
+    /// failed. Failures are only logged when `previously_failed` is `false`, so that a broken file
+    /// is reported once rather than every slot.
+    fn reload_fee_recipient_file(&self, previously_failed: bool) -> bool {
+        let fee_recipient_file = match self.fee_recipient_file.as_ref() {
+            Some(fee_recipient_file) => fee_recipient_file,
+            None => return false,
+        };
+
+        // Only take the write lock to swap in a successfully re-read file, so that lookups are
+        // never blocked on I/O.
+        let reloaded = fee_recipient_file.read().reload_if_modified();
+        match reloaded {
+            Ok(Some(updated_file)) => {
+                info!(
+                    self.log,
+                    "Reloaded fee recipient file";
+                    "path" => ?updated_file.path(),
+                );
+                *fee_recipient_file.write() = updated_file;
+                false
+            }
+            Ok(None) => previously_failed,
+            Err(e) => {
+                if !previously_failed {
+                    warn!(
+                        self.log,
+                        "Failed to read fee recipient file";
+                        "msg" => "using previously loaded fee recipients",
+                        "error" => ?e,
+                    );
+                }
+                true
+            }
+        }
     }
 
     /// Returns the suggested_fee_recipient from `validator_definitions.yml` if any.
@@ -625,13 +706,21 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         }
     }
 
+    /// Sign `voluntary_exit` without broadcasting it.
+    ///
+    /// The fork is taken from the cached spec, so the beacon node need not be reachable. The
+    /// cached genesis validators root is used unless `genesis_validators_root` is provided.
     pub async fn sign_voluntary_exit(
         &self,
         validator_pubkey: PublicKeyBytes,
         voluntary_exit: VoluntaryExit,
+        genesis_validators_root: Option<Hash256>,
     ) -> Result<SignedVoluntaryExit, Error> {
         let signing_epoch = voluntary_exit.epoch;
-        let signing_context = self.signing_context(Domain::VoluntaryExit, signing_epoch);
+        let mut signing_context = self.signing_context(Domain::VoluntaryExit, signing_epoch);
+        if let Some(genesis_validators_root) = genesis_validators_root {
+            signing_context.genesis_validators_root = genesis_validators_root;
+        }
         let signing_method = self.doppelganger_bypassed_signing_method(validator_pubkey)?;
 
         let signature = signing_method
@@ -910,12 +999,11 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     /// cheap to call. The `first_run` flag can be used to print a more verbose message when pruning
     /// runs.
     pub fn prune_slashing_protection_db(&self, current_epoch: Epoch, first_run: bool) {
-        // Attempt to prune every SLASHING_PROTECTION_HISTORY_EPOCHs, with a tolerance for
+        // Attempt to prune every `slashing_protection_history_epochs`, with a tolerance for
         // missing the epoch that aligns exactly.
+        let history_epochs = self.slashing_protection_history_epochs;
         let mut last_prune = self.slashing_protection_last_prune.lock();
-        if current_epoch / SLASHING_PROTECTION_HISTORY_EPOCHS
-            <= *last_prune / SLASHING_PROTECTION_HISTORY_EPOCHS
-        {
+        if current_epoch / history_epochs <= *last_prune / history_epochs {
             return;
         }
 
@@ -932,7 +1020,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
 
         let _timer = metrics::start_timer(&metrics::SLASHING_PROTECTION_PRUNE_TIMES);
 
-        let new_min_target_epoch = current_epoch.saturating_sub(SLASHING_PROTECTION_HISTORY_EPOCHS);
+        let new_min_target_epoch = current_epoch.saturating_sub(history_epochs);
         let new_min_slot = new_min_target_epoch.start_slot(E::slots_per_epoch());
 
         let all_pubkeys: Vec<_> = self.voting_pubkeys(DoppelgangerStatus::ignored);